@@ -1,7 +1,9 @@
 //! Agent loop: the core processing engine
 
 use agent_diva_core::bus::{AgentEvent, InboundMessage, MessageBus, OutboundMessage};
-use agent_diva_core::config::MCPServerConfig;
+use agent_diva_core::config::{
+    AgentMemoryConfig, AgentSessionsConfig, AgentSkillsConfig, MCPServerConfig,
+};
 use agent_diva_core::cron::CronService;
 use agent_diva_core::debug::DebugEventLogger;
 use agent_diva_core::error_context::ErrorContext;
@@ -11,6 +13,7 @@ use agent_diva_core::trace::{TraceId, TraceLogger};
 use agent_diva_files::{FileConfig, FileManager};
 use agent_diva_providers::LLMProvider;
 use agent_diva_tooling::{ToolError, ToolRegistry};
+use agent_diva_tools::{ExecSandboxConfig, McpManager, ShellJobManager};
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -23,8 +26,9 @@ use crate::context::{ContextBuilder, SoulContextSettings};
 use crate::context_budget::ContextBudgetPolicy;
 use crate::runtime_control::RuntimeControlCommand;
 use crate::subagent::SubagentManager;
-use crate::subagent::SubagentSpawnRequest;
+use crate::subagent::{SubagentReport, SubagentSpawnRequest};
 use crate::subagent_policy::SubagentPolicy;
+use crate::subagent_profile::SubagentProfile;
 use crate::tool_assembly::{SubagentSpawner, ToolAssembly};
 use crate::tool_config::builtin::BuiltInToolsConfig;
 use crate::tool_config::network::NetworkToolConfig;
@@ -32,6 +36,7 @@ use crate::tool_config::network::NetworkToolConfig;
 pub(crate) mod context_retry;
 mod loop_guard;
 mod loop_runtime_control;
+mod loop_summary;
 mod loop_tools;
 mod loop_turn;
 
@@ -44,12 +49,20 @@ pub struct ToolConfig {
     pub network: NetworkToolConfig,
     /// Default tool execution timeout in seconds
     pub exec_timeout: u64,
+    /// Sandbox backend and limits for the `exec` tool
+    pub exec_sandbox: ExecSandboxConfig,
+    /// Background jobs and persistent shells started by the `exec` tool
+    pub shell_jobs: Arc<ShellJobManager>,
     /// Whether to restrict file access to workspace
     pub restrict_to_workspace: bool,
     /// Configured MCP servers
     pub mcp_servers: HashMap<String, MCPServerConfig>,
+    /// Live MCP connections; tracks servers whose tool/prompt lists changed
+    pub mcp_manager: Arc<McpManager>,
     /// Subagent delegation policy
     pub subagent_policy: SubagentPolicy,
+    /// Named subagent profiles selectable through `spawn`
+    pub subagent_profiles: Vec<SubagentProfile>,
     /// Optional cron service for scheduling tools
     pub cron_service: Option<Arc<CronService>>,
     /// Soul context settings
@@ -66,6 +79,12 @@ pub struct ToolConfig {
     pub notify_on_soul_change: bool,
     /// Governance behavior for soul evolution transparency
     pub soul_governance: SoulGovernanceSettings,
+    /// Memory backend settings, including how turns map to memory scopes
+    pub memory: AgentMemoryConfig,
+    /// Session storage backend
+    pub sessions: AgentSessionsConfig,
+    /// Per-turn skill selection
+    pub skills: AgentSkillsConfig,
 }
 
 impl Default for ToolConfig {
@@ -74,9 +93,13 @@ impl Default for ToolConfig {
             builtin: BuiltInToolsConfig::default(),
             network: NetworkToolConfig::default(),
             exec_timeout: 60,
+            exec_sandbox: ExecSandboxConfig::default(),
+            shell_jobs: Arc::new(ShellJobManager::new()),
             restrict_to_workspace: false,
             mcp_servers: HashMap::new(),
+            mcp_manager: Arc::new(McpManager::new()),
             subagent_policy: SubagentPolicy::default(),
+            subagent_profiles: Vec::new(),
             cron_service: None,
             soul_context: SoulContextSettings::default(),
             request_max_tokens: 4096,
@@ -86,6 +109,9 @@ impl Default for ToolConfig {
             debug_logger: None,
             notify_on_soul_change: true,
             soul_governance: SoulGovernanceSettings::default(),
+            memory: AgentMemoryConfig::default(),
+            sessions: AgentSessionsConfig::default(),
+            skills: AgentSkillsConfig::default(),
         }
     }
 }
@@ -158,6 +184,44 @@ impl SubagentSpawner for SubagentManagerSpawner {
             .await
             .map_err(|e| ToolError::ExecutionFailed(e.to_string()))
     }
+
+    fn profiles(&self) -> Vec<(String, String)> {
+        self.manager.profile_summaries()
+    }
+
+    async fn wait(
+        &self,
+        session_key: String,
+        task_ids: Vec<String>,
+        timeout: std::time::Duration,
+    ) -> Result<Vec<SubagentReport>, ToolError> {
+        self.manager
+            .wait(&session_key, &task_ids, timeout)
+            .await
+            .map_err(|e| ToolError::ExecutionFailed(e.to_string()))
+    }
+
+    async fn status(
+        &self,
+        session_key: String,
+        task_ids: Vec<String>,
+    ) -> Result<Vec<SubagentReport>, ToolError> {
+        self.manager
+            .status(&session_key, &task_ids)
+            .await
+            .map_err(|e| ToolError::ExecutionFailed(e.to_string()))
+    }
+
+    async fn cancel(
+        &self,
+        session_key: String,
+        task_id: String,
+    ) -> Result<SubagentReport, ToolError> {
+        self.manager
+            .cancel(&session_key, &task_id)
+            .await
+            .map_err(|e| ToolError::ExecutionFailed(e.to_string()))
+    }
 }
 
 impl AgentLoop {
@@ -231,6 +295,11 @@ impl AgentLoop {
         self.file_manager.clone()
     }
 
+    /// Get the tool registry used for agent turns
+    pub fn tools(&self) -> &ToolRegistry {
+        &self.tools
+    }
+
     /// Create a new agent loop with tool configuration
     #[allow(clippy::too_many_arguments)]
     pub async fn with_tools(
@@ -273,23 +342,31 @@ impl AgentLoop {
         memory_provider: Option<Arc<dyn MemoryProvider>>,
     ) -> Result<Self, Box<dyn std::error::Error>> {
         let model = model.unwrap_or_else(|| provider.get_default_model());
-        let mut context = ContextBuilder::with_skills(workspace.clone(), None);
+        let memory_provider = memory_provider
+            .unwrap_or_else(|| Arc::new(agent_diva_core::memory::MemoryManager::new(&workspace)));
+        let mut context = ContextBuilder::with_skills(workspace.clone(), None)
+            .with_memory_provider(memory_provider.clone());
         context.set_soul_settings(tool_config.soul_context.clone());
-        let sessions = SessionManager::new(workspace.clone());
-
-        let subagent_manager = Arc::new(SubagentManager::new(
-            provider.clone(),
-            workspace.clone(),
-            bus.clone(),
-            Some(model.clone()),
-            tool_config.builtin.clone(),
-            tool_config.network.clone(),
-            Some(tool_config.exec_timeout),
-            tool_config.restrict_to_workspace,
-            tool_config.mcp_servers.clone(),
-            tool_config.subagent_policy.clone(),
-            tool_config.context_budget.clone(),
-        ));
+        context.set_skill_settings(tool_config.skills.clone());
+        let sessions = SessionManager::open(workspace.clone(), &tool_config.sessions)?;
+
+        let subagent_manager = Arc::new(
+            SubagentManager::new(
+                provider.clone(),
+                workspace.clone(),
+                bus.clone(),
+                Some(model.clone()),
+                tool_config.builtin.clone(),
+                tool_config.network.clone(),
+                Some(tool_config.exec_timeout),
+                tool_config.restrict_to_workspace,
+                tool_config.mcp_servers.clone(),
+                tool_config.subagent_policy.clone(),
+                tool_config.context_budget.clone(),
+            )
+            .with_exec_sandbox(tool_config.exec_sandbox.clone())
+            .with_profiles(tool_config.subagent_profiles.clone()),
+        );
 
         let spawner = Arc::new(SubagentManagerSpawner {
             manager: subagent_manager.clone(),
@@ -298,16 +375,17 @@ impl AgentLoop {
             .builtin(tool_config.builtin.clone())
             .with_network_config(tool_config.network.clone())
             .with_exec_timeout(tool_config.exec_timeout)
+            .with_exec_sandbox(tool_config.exec_sandbox.clone())
+            .with_shell_jobs(tool_config.shell_jobs.clone())
+            .with_mcp_manager(tool_config.mcp_manager.clone())
             .restrict_to_workspace(tool_config.restrict_to_workspace)
             .mcp_servers(tool_config.mcp_servers.clone())
             .with_subagent_spawner(spawner)
             .with_file_manager(file_manager.clone())
+            .with_memory_provider(memory_provider.clone())
             .with_tools(Vec::new())
             .build();
 
-        let memory_provider = memory_provider
-            .unwrap_or_else(|| Arc::new(agent_diva_core::memory::MemoryManager::new(&workspace)));
-
         let mut agent = Self {
             bus,
             provider,
@@ -339,6 +417,9 @@ impl AgentLoop {
                 .builtin(agent.tool_config.builtin.clone())
                 .with_network_config(agent.tool_config.network.clone())
                 .with_exec_timeout(agent.tool_config.exec_timeout)
+                .with_exec_sandbox(agent.tool_config.exec_sandbox.clone())
+                .with_shell_jobs(agent.tool_config.shell_jobs.clone())
+                .with_mcp_manager(agent.tool_config.mcp_manager.clone())
                 .restrict_to_workspace(agent.tool_config.restrict_to_workspace)
                 .mcp_servers(agent.tool_config.mcp_servers.clone())
                 .with_subagent_spawner(Arc::new(SubagentManagerSpawner {
@@ -346,6 +427,7 @@ impl AgentLoop {
                 }))
                 .with_cron_service(cron_service)
                 .with_file_manager(agent.file_manager.clone())
+                .with_memory_provider(agent.memory_provider.clone())
                 .build();
         }
 
@@ -366,20 +448,25 @@ impl AgentLoop {
         let model = model.unwrap_or_else(|| provider.get_default_model());
         let mut context = ContextBuilder::with_skills(workspace.clone(), None);
         context.set_soul_settings(toolset.config.soul_context.clone());
-        let sessions = SessionManager::new(workspace.clone());
-        let subagent_manager = Arc::new(SubagentManager::new(
-            provider.clone(),
-            workspace.clone(),
-            bus.clone(),
-            Some(model.clone()),
-            toolset.config.builtin.clone(),
-            toolset.config.network.clone(),
-            Some(toolset.config.exec_timeout),
-            toolset.config.restrict_to_workspace,
-            toolset.config.mcp_servers.clone(),
-            toolset.config.subagent_policy.clone(),
-            toolset.config.context_budget.clone(),
-        ));
+        context.set_skill_settings(toolset.config.skills.clone());
+        let sessions = SessionManager::open(workspace.clone(), &toolset.config.sessions)?;
+        let subagent_manager = Arc::new(
+            SubagentManager::new(
+                provider.clone(),
+                workspace.clone(),
+                bus.clone(),
+                Some(model.clone()),
+                toolset.config.builtin.clone(),
+                toolset.config.network.clone(),
+                Some(toolset.config.exec_timeout),
+                toolset.config.restrict_to_workspace,
+                toolset.config.mcp_servers.clone(),
+                toolset.config.subagent_policy.clone(),
+                toolset.config.context_budget.clone(),
+            )
+            .with_exec_sandbox(toolset.config.exec_sandbox.clone())
+            .with_profiles(toolset.config.subagent_profiles.clone()),
+        );
 
         let memory_provider: Arc<dyn MemoryProvider> =
             Arc::new(agent_diva_core::memory::MemoryManager::new(&workspace));
@@ -458,6 +545,8 @@ impl AgentLoop {
         }
 
         info!("Agent loop stopped");
+        self.tool_config.shell_jobs.shutdown().await;
+        self.tool_config.mcp_manager.shutdown().await;
 
         // Trigger session-end rhythm work with idempotency.
         match self
@@ -619,6 +708,10 @@ mod tests {
         calls: AtomicUsize,
         fail_times: usize,
     }
+    struct SummarizingProvider {
+        summary_calls: AtomicUsize,
+        saw_summary: AtomicBool,
+    }
     struct RepeatingToolStreamProvider {
         args_sequence: Mutex<Vec<HashMap<String, serde_json::Value>>>,
     }
@@ -746,6 +839,58 @@ mod tests {
         }
     }
 
+    #[async_trait]
+    impl LLMProvider for SummarizingProvider {
+        async fn chat(
+            &self,
+            _messages: Vec<Message>,
+            _tools: Option<Vec<serde_json::Value>>,
+            _model: Option<String>,
+            _max_tokens: i32,
+            _temperature: f64,
+        ) -> ProviderResult<LLMResponse> {
+            self.summary_calls.fetch_add(1, Ordering::SeqCst);
+            Ok(LLMResponse {
+                content: Some("user has been asking about old topics".to_string()),
+                tool_calls: Vec::new(),
+                finish_reason: "stop".to_string(),
+                usage: HashMap::new(),
+                reasoning_content: None,
+            })
+        }
+
+        async fn chat_stream(
+            &self,
+            messages: Vec<Message>,
+            _tools: Option<Vec<serde_json::Value>>,
+            _model: Option<String>,
+            _max_tokens: i32,
+            _temperature: f64,
+        ) -> ProviderResult<ProviderEventStream> {
+            let saw_summary = messages.iter().any(|message| {
+                message.role == "system"
+                    && message
+                        .content
+                        .to_text_lossy()
+                        .contains("user has been asking about old topics")
+            });
+            self.saw_summary.store(saw_summary, Ordering::SeqCst);
+            Ok(Box::pin(stream::iter(vec![Ok(LLMStreamEvent::Completed(
+                LLMResponse {
+                    content: Some("assistant ok".to_string()),
+                    tool_calls: Vec::new(),
+                    finish_reason: "stop".to_string(),
+                    usage: HashMap::new(),
+                    reasoning_content: None,
+                },
+            ))])))
+        }
+
+        fn get_default_model(&self) -> String {
+            "test-model".to_string()
+        }
+    }
+
     #[async_trait]
     impl LLMProvider for RepeatingToolStreamProvider {
         async fn chat(
@@ -936,6 +1081,43 @@ mod tests {
         assert_eq!(agent.max_iterations, 20);
     }
 
+    struct PrefixedUserTool;
+
+    #[async_trait]
+    impl Tool for PrefixedUserTool {
+        fn name(&self) -> &str {
+            "mcp_notes"
+        }
+
+        fn description(&self) -> &str {
+            "User tool that only shares the MCP prefix"
+        }
+
+        fn parameters(&self) -> serde_json::Value {
+            json!({ "type": "object", "properties": {} })
+        }
+
+        async fn execute(&self, _args: serde_json::Value) -> agent_diva_tooling::Result<String> {
+            Ok("notes".to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mcp_reconfigure_keeps_unrelated_prefixed_tools() {
+        let bus = MessageBus::new();
+        let provider = Arc::new(LiteLLMClient::default());
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut agent = AgentLoop::new(bus, provider, temp_dir.path().to_path_buf(), None, None)
+            .await
+            .unwrap();
+        agent.tools.register(Arc::new(PrefixedUserTool));
+
+        agent.apply_mcp_config(HashMap::new()).await;
+        agent.refresh_mcp_tools().await;
+
+        assert!(agent.tools.has("mcp_notes"));
+    }
+
     #[tokio::test]
     async fn test_process_direct() {
         let bus = MessageBus::new();
@@ -1102,10 +1284,56 @@ mod tests {
         assert_eq!(provider.calls.load(Ordering::SeqCst), 2);
     }
 
+    #[tokio::test]
+    async fn test_process_inbound_summarizes_history_over_budget() {
+        let bus = MessageBus::new();
+        let provider = Arc::new(SummarizingProvider {
+            summary_calls: AtomicUsize::new(0),
+            saw_summary: AtomicBool::new(false),
+        });
+        let temp_dir = tempfile::tempdir().unwrap();
+        let workspace = temp_dir.path().to_path_buf();
+
+        let mut agent = AgentLoop::new(bus, provider.clone(), workspace, None, Some(1))
+            .await
+            .unwrap();
+        agent.context_budget = ContextBudgetPolicy {
+            context_budget_tokens: 30_000,
+            reserve_tokens: 0,
+            overflow_retry_enabled: true,
+            compaction_strategy: agent_diva_core::config::ContextCompactionStrategy::Summarize,
+        };
+        {
+            let session = agent.sessions.get_or_create("gui:chat-1").unwrap();
+            for i in 0..20 {
+                session.add_message("user", format!("old question {} {}", i, "q".repeat(4_000)));
+                session.add_message(
+                    "assistant",
+                    format!("old answer {} {}", i, "a".repeat(4_000)),
+                );
+            }
+        }
+
+        let response = agent
+            .process_inbound_message(InboundMessage::new("gui", "user", "chat-1", "Hello"), None)
+            .await
+            .unwrap()
+            .expect("response should exist");
+
+        assert_eq!(response.content, "assistant ok");
+        assert_eq!(provider.summary_calls.load(Ordering::SeqCst), 1);
+        assert!(provider.saw_summary.load(Ordering::SeqCst));
+        let session = agent.sessions.get_or_create("gui:chat-1").unwrap();
+        let summary = crate::context_summary::ContextSummary::load(session)
+            .expect("summary should be cached in session metadata");
+        assert!(summary.covered_messages > 0);
+        assert_eq!(session.messages.len(), 42);
+    }
+
     use agent_diva_core::memory::{
-        PrefetchRequest, PrefetchResponse, PrefetchStatus, SessionEndRequest, SessionEndResponse,
-        SessionEndStatus, StartupStatus, SyncTurnRequest, SyncTurnResponse, SyncTurnStatus,
-        SystemPromptBlock, SystemPromptRequest, SystemPromptResponse,
+        MemoryScope, PrefetchRequest, PrefetchResponse, PrefetchStatus, SessionEndRequest,
+        SessionEndResponse, SessionEndStatus, StartupStatus, SyncTurnRequest, SyncTurnResponse,
+        SyncTurnStatus, SystemPromptBlock, SystemPromptRequest, SystemPromptResponse,
     };
     use std::sync::atomic::AtomicBool;
 
@@ -1216,8 +1444,9 @@ mod tests {
         .await
         .unwrap();
 
-        // Verify the provider is the one we injected (Arc pointer identity).
-        assert_eq!(Arc::strong_count(&memory_provider), 2); // one in agent, one here
+        // Verify the provider is the one we injected (Arc pointer identity):
+        // held here, by the agent, its context builder and the three memory tools.
+        assert_eq!(Arc::strong_count(&memory_provider), 6);
     }
 
     #[tokio::test]
@@ -1250,6 +1479,7 @@ mod tests {
         let response = provider
             .prefetch(PrefetchRequest {
                 workspace_root: PathBuf::from("/tmp"),
+                scope: MemoryScope::Global,
                 intent: "   ".to_string(),
                 current_room: None,
                 user_message: Some("help".to_string()),
@@ -1272,6 +1502,7 @@ mod tests {
         let response = provider
             .prefetch(PrefetchRequest {
                 workspace_root: PathBuf::from("/tmp"),
+                scope: MemoryScope::Global,
                 intent: "recall provider boundary".to_string(),
                 current_room: Some("roadmap".to_string()),
                 user_message: Some("status?".to_string()),
@@ -1294,8 +1525,10 @@ mod tests {
         let response = provider
             .sync_turn(SyncTurnRequest {
                 workspace_root: PathBuf::from("/tmp"),
+                scope: MemoryScope::Global,
                 memory_update_markdown: Some("Updated memory".to_string()),
                 history_entry: Some("task complete".to_string()),
+                session_key: None,
             })
             .await
             .unwrap();
@@ -1328,6 +1561,7 @@ mod tests {
         let startup = provider
             .system_prompt_block(&SystemPromptRequest {
                 workspace_root: PathBuf::from("/tmp"),
+                scope: MemoryScope::Global,
             })
             .unwrap();
         assert!(matches!(startup.status, StartupStatus::Ready));
@@ -1337,6 +1571,7 @@ mod tests {
         let _prefetch = provider
             .prefetch(PrefetchRequest {
                 workspace_root: PathBuf::from("/tmp"),
+                scope: MemoryScope::Global,
                 intent: "review memory".to_string(),
                 current_room: None,
                 user_message: None,
@@ -1349,8 +1584,10 @@ mod tests {
         let sync = provider
             .sync_turn(SyncTurnRequest {
                 workspace_root: PathBuf::from("/tmp"),
+                scope: MemoryScope::Global,
                 memory_update_markdown: Some("evidence".to_string()),
                 history_entry: None,
+                session_key: None,
             })
             .await
             .unwrap();
@@ -2,6 +2,7 @@ use crate::context_budget::{
     compact_messages_to_budget, provider_error_indicates_context_overflow, CompactionMode,
     ContextBudgetPolicy, ContextBudgetReport,
 };
+use crate::tokenizer::Tokenizer;
 use agent_diva_providers::{Message, ProviderError};
 
 pub(crate) struct PreparedRequest {
@@ -14,8 +15,10 @@ pub(crate) fn prepare_budgeted_messages(
     tool_defs: &[serde_json::Value],
     policy: &ContextBudgetPolicy,
     mode: CompactionMode,
+    tokenizer: &dyn Tokenizer,
 ) -> PreparedRequest {
-    let (messages, report) = compact_messages_to_budget(messages, tool_defs, policy, mode);
+    let (messages, report) =
+        compact_messages_to_budget(messages, tool_defs, policy, mode, tokenizer);
     PreparedRequest { messages, report }
 }
 
@@ -1,6 +1,9 @@
 use super::AgentLoop;
+use crate::consolidation;
 use crate::runtime_control::RuntimeControlCommand;
 use agent_diva_core::bus::{AgentEvent, InboundMessage};
+use agent_diva_core::memory::MemoryScope;
+use agent_diva_tools::ToolCallContext;
 use tokio::sync::mpsc;
 use tokio::sync::mpsc::error::TryRecvError;
 use tracing::info;
@@ -18,6 +21,7 @@ impl AgentLoop {
                 self.cancelled_sessions.insert(session_key);
             }
             RuntimeControlCommand::ResetSession { session_key } => {
+                self.tool_config.shell_jobs.kill_session(&session_key).await;
                 if let Err(e) = self.sessions.archive_and_reset(&session_key) {
                     tracing::error!("Failed to archive and reset session: {}", e);
                 } else {
@@ -49,6 +53,7 @@ impl AgentLoop {
                 session_key,
                 reply_tx,
             } => {
+                self.tool_config.shell_jobs.kill_session(&session_key).await;
                 let result = self
                     .sessions
                     .delete(&session_key)
@@ -71,7 +76,127 @@ impl AgentLoop {
                 }
                 let _ = reply_tx.send(result);
             }
+            RuntimeControlCommand::RewindSession {
+                session_key,
+                message_count,
+                reply_tx,
+            } => {
+                let result = self
+                    .sessions
+                    .rewind(&session_key, message_count)
+                    .map_err(|e| e.to_string());
+                log_session_edit("rewind", &session_key, &result);
+                let _ = reply_tx.send(result);
+            }
+            RuntimeControlCommand::RewindToUserMessage {
+                session_key,
+                index,
+                reply_tx,
+            } => {
+                let result = self
+                    .sessions
+                    .rewind_to_user_message(&session_key, index)
+                    .map_err(|e| e.to_string());
+                log_session_edit("rewind to user message", &session_key, &result);
+                let _ = reply_tx.send(result);
+            }
+            RuntimeControlCommand::ForkSession {
+                session_key,
+                new_key,
+                message_count,
+                reply_tx,
+            } => {
+                let new_key = new_key.unwrap_or_else(|| fork_session_key(&session_key));
+                let result = self
+                    .sessions
+                    .fork(&session_key, &new_key, message_count)
+                    .map(|session| session.key)
+                    .map_err(|e| e.to_string());
+                log_session_edit("fork", &session_key, &result);
+                let _ = reply_tx.send(result);
+            }
+            RuntimeControlCommand::ConsolidateSession {
+                session_key,
+                reply_tx,
+            } => {
+                let result = self.consolidate_session_now(&session_key).await;
+                if let Err(err) = &result {
+                    tracing::error!(
+                        session_key = %session_key,
+                        error = %err,
+                        "Runtime session consolidation failed"
+                    );
+                }
+                let _ = reply_tx.send(result);
+            }
+            RuntimeControlCommand::InvokeTool {
+                name,
+                mut args,
+                channel,
+                chat_id,
+                reply_tx,
+            } => {
+                if name == "cron" {
+                    let _ = reply_tx.send(
+                        "Error: the cron tool cannot be invoked outside of a turn".to_string(),
+                    );
+                    return;
+                }
+                // Scheduled jobs run as the "cron" sender of their conversation,
+                // like cron-triggered turns.
+                let memory_scope = MemoryScope::resolve(
+                    self.tool_config.memory.scope_policy(&channel),
+                    &channel,
+                    &chat_id,
+                    "cron",
+                );
+                if let Err(e) = self.memory_provider.prepare_scope(&memory_scope).await {
+                    tracing::warn!("Failed to prepare memory scope {}: {}", memory_scope, e);
+                }
+                ToolCallContext::new(channel, chat_id)
+                    .with_memory_scope(memory_scope)
+                    .with_in_cron(true)
+                    .apply(&name, &mut args);
+                // Run on a snapshot of the registry so a slow tool does not
+                // hold up the loop.
+                let tools = self.tools.clone();
+                tokio::spawn(async move {
+                    let result = tools.execute_outside_turn(&name, args).await;
+                    let _ = reply_tx.send(result);
+                });
+            }
+        }
+    }
+
+    async fn consolidate_session_now(&mut self, session_key: &str) -> Result<bool, String> {
+        match self.sessions.get_or_load(session_key) {
+            Ok(Some(_)) => {}
+            Ok(None) => return Err(format!("session '{}' not found", session_key)),
+            Err(e) => return Err(e.to_string()),
+        }
+        let model = self.provider.get_default_model();
+        let session = self
+            .sessions
+            .get_or_create(session_key)
+            .map_err(|e| e.to_string())?;
+        let before = session.last_consolidated;
+        consolidation::consolidate(
+            session,
+            &self.provider,
+            &model,
+            &self.workspace,
+            &*self.memory_provider,
+            self.memory_window,
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+        let advanced = session.last_consolidated > before;
+        if advanced {
+            if let Some(session) = self.sessions.get(session_key) {
+                self.sessions.save(session).map_err(|e| e.to_string())?;
+            }
         }
+        Ok(advanced)
     }
 
     pub(super) async fn drain_runtime_control_commands(&mut self) {
@@ -110,8 +235,27 @@ impl AgentLoop {
         if let Some(tx) = event_tx {
             let _ = tx.send(event.clone());
         }
-        let _ = self
-            .bus
-            .publish_event(msg.channel.clone(), msg.chat_id.clone(), event);
+        let _ = self.bus.publish_message_event(msg, event);
     }
 }
+
+fn log_session_edit<T>(action: &str, session_key: &str, result: &Result<T, String>) {
+    match result {
+        Ok(_) => info!(session_key = %session_key, "Runtime session {} completed", action),
+        Err(err) => tracing::warn!(
+            session_key = %session_key,
+            error = %err,
+            "Runtime session {} failed",
+            action
+        ),
+    }
+}
+
+/// Default key for a fork of `session_key`: same channel, timestamped chat id.
+fn fork_session_key(session_key: &str) -> String {
+    format!(
+        "{}:fork-{}",
+        session_key,
+        chrono::Local::now().format("%Y%m%d%H%M%S")
+    )
+}
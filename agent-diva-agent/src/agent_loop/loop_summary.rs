@@ -0,0 +1,108 @@
+use super::AgentLoop;
+use crate::context::ContextBuilder;
+use crate::context_budget::estimate_request_tokens;
+use crate::context_summary::{summarize_history, ContextSummary, SummaryOutcome};
+use crate::tokenizer::Tokenizer;
+use agent_diva_providers::{Message, MessageContent};
+use tracing::warn;
+
+/// Initial request messages for a turn: `[system, history.., current]`.
+pub(super) struct TurnMessages {
+    pub messages: Vec<Message>,
+    /// Number of history slots after the system prompt, including the summary message.
+    pub history_len: usize,
+    /// Savings from a summary generated while building this turn.
+    pub summary: Option<SummaryOutcome>,
+}
+
+impl AgentLoop {
+    /// Build the turn's messages from session history.
+    ///
+    /// In summarizing mode the oldest turns are replaced by the session's cached
+    /// context summary, which is extended first when the request would exceed the
+    /// budget. Summarizer failures leave history untouched so normal trimming applies.
+    pub(super) async fn build_turn_messages(
+        &mut self,
+        session_key: &str,
+        message_content: MessageContent,
+        channel: &str,
+        chat_id: &str,
+        model: &str,
+        tokenizer: &dyn Tokenizer,
+    ) -> Result<TurnMessages, Box<dyn std::error::Error>> {
+        let probe = self.context_budget.history_probe_messages();
+        let summarize = self.context_budget.summarizes_history();
+        let session = self.sessions.get_or_create(session_key)?;
+        let mut summary = summarize.then(|| ContextSummary::load(session)).flatten();
+        let covered = summary
+            .as_ref()
+            .map_or(0, |summary| summary.covered_messages);
+        let history = session.get_history_from(covered, probe);
+        let mut history_len = history.len();
+        let mut messages = self.context.build_messages_with_content(
+            history,
+            message_content,
+            Some(channel),
+            Some(chat_id),
+        );
+
+        let mut outcome = None;
+        if summarize {
+            let available = self.context_budget.available_context_tokens();
+            let summary_tokens = summary
+                .as_ref()
+                .map_or(0, |summary| summary.estimated_tokens(tokenizer));
+            let history_tokens =
+                estimate_request_tokens(&messages[1..1 + history_len], &[], tokenizer);
+            let total =
+                estimate_request_tokens(&messages, &self.tools.get_definitions(), tokenizer)
+                    + summary_tokens;
+            if total > available {
+                let fixed_tokens = total - history_tokens - summary_tokens;
+                let session = self.sessions.get_or_create(session_key)?;
+                match summarize_history(
+                    session,
+                    &self.provider,
+                    model,
+                    fixed_tokens,
+                    available,
+                    tokenizer,
+                )
+                .await
+                {
+                    Ok(Some(new_outcome)) => {
+                        summary = ContextSummary::load(session);
+                        let covered = summary
+                            .as_ref()
+                            .map_or(0, |summary| summary.covered_messages);
+                        let history = ContextBuilder::history_messages(
+                            session.get_history_from(covered, probe),
+                        );
+                        let replaced = history.len();
+                        messages.splice(1..1 + history_len, history);
+                        history_len = replaced;
+                        outcome = Some(new_outcome);
+                    }
+                    Ok(None) => {}
+                    Err(error) => {
+                        warn!(
+                            "Context summarization failed, falling back to trimming: {}",
+                            error
+                        );
+                    }
+                }
+            }
+        }
+
+        if let Some(summary) = &summary {
+            messages.insert(1, summary.to_message());
+            history_len += 1;
+        }
+
+        Ok(TurnMessages {
+            messages,
+            history_len,
+            summary: outcome,
+        })
+    }
+}
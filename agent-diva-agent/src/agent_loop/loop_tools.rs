@@ -1,5 +1,5 @@
 use super::{AgentLoop, ToolConfig};
-use crate::subagent::SubagentSpawnRequest;
+use crate::subagent::{SubagentReport, SubagentSpawnRequest};
 use crate::tool_assembly::{SubagentSpawner, ToolAssembly};
 use crate::tool_config::network::NetworkToolConfig;
 use agent_diva_core::config::MCPServerConfig;
@@ -20,6 +20,44 @@ impl SubagentSpawner for RuntimeSubagentSpawner {
             .await
             .map_err(|e| ToolError::ExecutionFailed(e.to_string()))
     }
+
+    fn profiles(&self) -> Vec<(String, String)> {
+        self.manager.profile_summaries()
+    }
+
+    async fn wait(
+        &self,
+        session_key: String,
+        task_ids: Vec<String>,
+        timeout: std::time::Duration,
+    ) -> Result<Vec<SubagentReport>, ToolError> {
+        self.manager
+            .wait(&session_key, &task_ids, timeout)
+            .await
+            .map_err(|e| ToolError::ExecutionFailed(e.to_string()))
+    }
+
+    async fn status(
+        &self,
+        session_key: String,
+        task_ids: Vec<String>,
+    ) -> Result<Vec<SubagentReport>, ToolError> {
+        self.manager
+            .status(&session_key, &task_ids)
+            .await
+            .map_err(|e| ToolError::ExecutionFailed(e.to_string()))
+    }
+
+    async fn cancel(
+        &self,
+        session_key: String,
+        task_id: String,
+    ) -> Result<SubagentReport, ToolError> {
+        self.manager
+            .cancel(&session_key, &task_id)
+            .await
+            .map_err(|e| ToolError::ExecutionFailed(e.to_string()))
+    }
 }
 
 impl AgentLoop {
@@ -30,6 +68,9 @@ impl AgentLoop {
             .builtin(tool_config.builtin)
             .with_network_config(tool_config.network)
             .with_exec_timeout(tool_config.exec_timeout)
+            .with_exec_sandbox(tool_config.exec_sandbox)
+            .with_shell_jobs(tool_config.shell_jobs)
+            .with_mcp_manager(tool_config.mcp_manager)
             .restrict_to_workspace(tool_config.restrict_to_workspace)
             .mcp_servers(tool_config.mcp_servers)
             .with_subagent_spawner(Arc::new(RuntimeSubagentSpawner {
@@ -65,23 +106,38 @@ impl AgentLoop {
 
     pub(super) async fn apply_mcp_config(&mut self, servers: HashMap<String, MCPServerConfig>) {
         self.tool_config.mcp_servers = servers.clone();
-        for name in self
-            .tools
-            .tool_names()
-            .into_iter()
-            .filter(|name| name.starts_with("mcp_"))
-        {
+        for name in self.tool_config.mcp_manager.tool_names() {
             self.tools.unregister(&name);
         }
 
         if self.tool_config.builtin.mcp {
-            for mcp_tool in agent_diva_tools::load_mcp_tools_sync(&servers) {
+            for mcp_tool in self.tool_config.mcp_manager.connect(&servers).await {
                 self.tools.register(mcp_tool);
             }
+        } else {
+            self.tool_config.mcp_manager.shutdown().await;
         }
 
         self.subagent_manager.update_mcp_servers(servers).await;
 
         info!("Applied runtime MCP tool configuration update");
     }
+
+    /// Re-register MCP tools after a server announced a tool/prompt list change.
+    pub(super) async fn refresh_mcp_tools(&mut self) {
+        // Only tools that came from MCP servers are replaced; other tools named
+        // `mcp_*` stay registered.
+        let previous = self.tool_config.mcp_manager.tool_names();
+        let Some(mcp_tools) = self.tool_config.mcp_manager.refresh_if_changed().await else {
+            return;
+        };
+        for name in previous {
+            self.tools.unregister(&name);
+        }
+        let count = mcp_tools.len();
+        for mcp_tool in mcp_tools {
+            self.tools.register(mcp_tool);
+        }
+        info!("Re-registered {} MCP tools after list change", count);
+    }
 }
@@ -2,13 +2,15 @@ use super::context_retry::{prepare_budgeted_messages, should_retry_context_overf
 use super::loop_guard::{
     is_tool_error_result, LoopGuard, DEFAULT_AGENT_LOOP_TIMEOUT, DEFAULT_REPEATED_FAILURE_THRESHOLD,
 };
+use super::loop_summary::TurnMessages;
 use super::AgentLoop;
 use crate::consolidation;
 use crate::context_budget::CompactionMode;
+use crate::tokenizer::tokenizer_for_model;
 use agent_diva_core::attachment::FileAttachmentRef;
 use agent_diva_core::bus::{AgentEvent, InboundMessage, OutboundMessage};
 use agent_diva_core::debug::DebugEvent;
-use agent_diva_core::memory::PrefetchRequest;
+use agent_diva_core::memory::{MemoryScope, PrefetchRequest};
 use agent_diva_core::session::ChatMessage;
 use agent_diva_core::soul::SoulStateStore;
 use agent_diva_core::trace::{TraceEvent, TraceId};
@@ -17,6 +19,7 @@ use agent_diva_providers::{
     provider_error_indicates_vision_unsupported, ImageFile, ImageUrl, LLMResponse, LLMStreamEvent,
     Message, MessageContent, MessageContentPart, ProviderError,
 };
+use agent_diva_tools::ToolCallContext;
 use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
 use base64::Engine;
 use futures::StreamExt;
@@ -44,6 +47,7 @@ impl AgentLoop {
 
         // Use the default model from the current provider
         let model_to_use = self.provider.get_default_model();
+        let tokenizer = tokenizer_for_model(&model_to_use);
 
         let preview = if msg.content.chars().count() > 80 {
             format!("{}...", msg.content.chars().take(80).collect::<String>())
@@ -106,17 +110,67 @@ impl AgentLoop {
             ),
         );
         self.clear_session_cancellation(&session_key);
-        let session = self.sessions.get_or_create(&session_key)?;
 
-        // Build initial messages
-        let history = session.get_history(self.context_budget.history_probe_messages());
-        let history_len = history.len();
-        let mut messages = self.context.build_messages_with_content(
-            history,
-            message_content,
-            Some(&msg.channel),
-            Some(&msg.chat_id),
+        // Resolve which memory this turn reads and writes before the prompt is built.
+        let memory_scope = MemoryScope::resolve(
+            self.tool_config.memory.scope_policy(&msg.channel),
+            &msg.channel,
+            &msg.chat_id,
+            &msg.sender_id,
         );
+        if let Err(e) = self.memory_provider.prepare_scope(&memory_scope).await {
+            warn!("Failed to prepare memory scope {}: {}", memory_scope, e);
+        }
+        self.context.set_memory_scope(memory_scope.clone());
+        let tool_context = ToolCallContext::new(msg.channel.clone(), msg.chat_id.clone())
+            .with_session_key(session_key.clone())
+            .with_memory_scope(memory_scope.clone())
+            .with_in_cron(msg.channel == "cron" || is_cron_trigger);
+
+        // Load the skills relevant to this message before the prompt is built.
+        let selected_skills = self.context.select_skills(&msg.content);
+        if !selected_skills.is_empty() {
+            let names: Vec<&str> = selected_skills
+                .iter()
+                .map(|skill| skill.name.as_str())
+                .collect();
+            debug!("Activated skills: {}", names.join(", "));
+            self.emit_runtime_trace(
+                "info",
+                &trace_id,
+                &session_key,
+                &msg.channel,
+                "skills",
+                "skills_activated",
+                format!("Activated skills: {}", names.join(", ")),
+                serde_json::json!({
+                    "skills": selected_skills
+                        .iter()
+                        .map(|skill| serde_json::json!({
+                            "name": skill.name,
+                            "score": skill.score,
+                            "matched": skill.matched,
+                        }))
+                        .collect::<Vec<_>>(),
+                }),
+            );
+        }
+
+        // Build initial messages
+        let TurnMessages {
+            mut messages,
+            history_len,
+            summary: history_summary,
+        } = self
+            .build_turn_messages(
+                &session_key,
+                message_content,
+                &msg.channel,
+                &msg.chat_id,
+                &model_to_use,
+                tokenizer,
+            )
+            .await?;
         if is_cron_trigger {
             // Make trigger origin explicit so the model does not treat it as a fresh user request.
             let current_message = messages.pop();
@@ -132,11 +186,14 @@ impl AgentLoop {
         {
             let session = self.sessions.get_or_create(&session_key)?;
             persist_inbound_message(session, user_role, &msg.content, user_attachments.clone());
+            consolidation::set_session_memory_scope(session, &memory_scope);
         }
         self.persist_session_or_fail(&session_key, &msg, event_tx, "persist inbound user message")?;
 
         // Agent loop
         let mut iteration = 0;
+        // Skill-scoped tools are only offered while their skill is active.
+        let mut active_skills: HashSet<String> = self.context.active_skills().into_iter().collect();
         let mut loop_guard = LoopGuard::new(
             self.max_iterations,
             DEFAULT_AGENT_LOOP_TIMEOUT,
@@ -151,6 +208,7 @@ impl AgentLoop {
                 .memory_provider
                 .prefetch(PrefetchRequest {
                     workspace_root: self.workspace.clone(),
+                    scope: memory_scope.clone(),
                     intent: prefetch_intent,
                     current_room: Some(msg.channel.clone()),
                     user_message: Some(prefetch_user_message.clone()),
@@ -207,16 +265,16 @@ impl AgentLoop {
             if let Some(tx) = event_tx {
                 let _ = tx.send(event.clone());
             }
-            let _ = self
-                .bus
-                .publish_event(msg.channel.clone(), msg.chat_id.clone(), event);
+            let _ = self.bus.publish_message_event(&msg, event);
+
+            self.refresh_mcp_tools().await;
 
             // Call LLM (streaming when provider supports it)
             // For cron-triggered turns, keep normal tools available but hide cron tool
             // to prevent recursive schedule creation loops.
             let tool_defs = if msg.channel == "cron" || is_cron_trigger {
                 self.tools
-                    .get_definitions()
+                    .get_scoped_definitions(&active_skills)
                     .into_iter()
                     .filter(|def| {
                         def.get("function")
@@ -226,19 +284,25 @@ impl AgentLoop {
                     })
                     .collect()
             } else {
-                self.tools.get_definitions()
+                self.tools.get_scoped_definitions(&active_skills)
             };
             let response = {
                 let mut compaction_mode = CompactionMode::Normal;
                 let mut overflow_retry_used = false;
 
                 loop {
-                    let prepared_request = prepare_budgeted_messages(
+                    let mut prepared_request = prepare_budgeted_messages(
                         &messages,
                         &tool_defs,
                         &self.context_budget,
                         compaction_mode,
+                        tokenizer,
                     );
+                    if let Some(summary) = history_summary {
+                        prepared_request
+                            .report
+                            .record_summary(summary.summarized_messages, summary.tokens_saved);
+                    }
                     trace!(
                         trace_id = %trace_id,
                         loop_index = iteration,
@@ -248,6 +312,8 @@ impl AgentLoop {
                         available_budget = prepared_request.report.available_context_tokens,
                         removed_history_messages = prepared_request.report.removed_history_messages,
                         truncated_tool_messages = prepared_request.report.truncated_tool_messages,
+                        summarized_history_messages = prepared_request.report.summarized_history_messages,
+                        summary_tokens_saved = prepared_request.report.summary_tokens_saved,
                         step_name = "context_compacted",
                         "Prepared request under context budget"
                     );
@@ -454,11 +520,7 @@ impl AgentLoop {
                                 if let Some(tx) = event_tx {
                                     let _ = tx.send(event.clone());
                                 }
-                                let _ = self.bus.publish_event(
-                                    msg.channel.clone(),
-                                    msg.chat_id.clone(),
-                                    event,
-                                );
+                                let _ = self.bus.publish_message_event(&msg, event);
                             }
                             LLMStreamEvent::ReasoningDelta(delta) => {
                                 debug!("Stream ReasoningDelta: {:?}", delta);
@@ -467,11 +529,7 @@ impl AgentLoop {
                                 if let Some(tx) = event_tx {
                                     let _ = tx.send(event.clone());
                                 }
-                                let _ = self.bus.publish_event(
-                                    msg.channel.clone(),
-                                    msg.chat_id.clone(),
-                                    event,
-                                );
+                                let _ = self.bus.publish_message_event(&msg, event);
                             }
                             LLMStreamEvent::ToolCallDelta {
                                 name,
@@ -486,11 +544,7 @@ impl AgentLoop {
                                     if let Some(tx) = event_tx {
                                         let _ = tx.send(event.clone());
                                     }
-                                    let _ = self.bus.publish_event(
-                                        msg.channel.clone(),
-                                        msg.chat_id.clone(),
-                                        event,
-                                    );
+                                    let _ = self.bus.publish_message_event(&msg, event);
                                 }
                             }
                             LLMStreamEvent::Completed(done) => {
@@ -625,9 +679,7 @@ impl AgentLoop {
                     if let Some(tx) = event_tx {
                         let _ = tx.send(event.clone());
                     }
-                    let _ = self
-                        .bus
-                        .publish_event(msg.channel.clone(), msg.chat_id.clone(), event);
+                    let _ = self.bus.publish_message_event(&msg, event);
                     self.emit_runtime_trace(
                         "info",
                         &trace_id,
@@ -666,24 +718,7 @@ impl AgentLoop {
 
                     let result = match serde_json::to_value(&tool_call.arguments) {
                         Ok(mut params_value) => {
-                            if tool_call.name == "cron" {
-                                if let Some(params_obj) = params_value.as_object_mut() {
-                                    params_obj.insert(
-                                        "context_channel".to_string(),
-                                        serde_json::Value::String(msg.channel.clone()),
-                                    );
-                                    params_obj.insert(
-                                        "context_chat_id".to_string(),
-                                        serde_json::Value::String(msg.chat_id.clone()),
-                                    );
-                                    if msg.channel == "cron" || is_cron_trigger {
-                                        params_obj.insert(
-                                            "_in_cron_context".to_string(),
-                                            serde_json::Value::Bool(true),
-                                        );
-                                    }
-                                }
-                            }
+                            tool_context.apply(&tool_call.name, &mut params_value);
                             self.emit_debug_raw(
                                 &trace_id,
                                 &session_key,
@@ -702,6 +737,10 @@ impl AgentLoop {
                             );
                             if is_cron_trigger && tool_call.name == "cron" {
                                 "Error: cron tool is disabled during cron-triggered execution to prevent recursive scheduling".to_string()
+                            } else if let Some(error) =
+                                self.tools.scope_error(&tool_call.name, &active_skills)
+                            {
+                                error
                             } else {
                                 self.tools.execute(&tool_call.name, params_value).await
                             }
@@ -728,6 +767,24 @@ impl AgentLoop {
                             soul_files_changed.insert(changed_file.to_string());
                         }
                     }
+                    if tool_call.name == "load_skill" && !is_tool_error_result(&result) {
+                        if let Some(name) = tool_call.arguments.get("name").and_then(|v| v.as_str())
+                        {
+                            // Tools of an explicitly loaded skill stay callable for the rest of the turn.
+                            let name = name.trim().to_string();
+                            self.emit_runtime_trace(
+                                "info",
+                                &trace_id,
+                                &session_key,
+                                &msg.channel,
+                                "skills",
+                                "skill_loaded",
+                                format!("Loaded skill: {}", name),
+                                serde_json::json!({ "skill": name, "loop_index": iteration }),
+                            );
+                            active_skills.insert(name);
+                        }
+                    }
 
                     trace!(trace_id = %trace_id, loop_index = iteration, step_name = "tool_completed", tool_name = %tool_call.name, "Tool completed");
 
@@ -740,9 +797,7 @@ impl AgentLoop {
                     if let Some(tx) = event_tx {
                         let _ = tx.send(event.clone());
                     }
-                    let _ = self
-                        .bus
-                        .publish_event(msg.channel.clone(), msg.chat_id.clone(), event);
+                    let _ = self.bus.publish_message_event(&msg, event);
                     let duration_ms = tool_started_at.elapsed().as_millis() as u64;
                     let tool_failed = is_tool_error_result(&result);
                     let mut metadata = serde_json::json!({
@@ -892,9 +947,7 @@ impl AgentLoop {
         if let Some(tx) = event_tx {
             let _ = tx.send(event.clone());
         }
-        let _ = self
-            .bus
-            .publish_event(msg.channel.clone(), msg.chat_id.clone(), event);
+        let _ = self.bus.publish_message_event(&msg, event);
 
         // Save complete turn to session
         {
@@ -906,7 +959,14 @@ impl AgentLoop {
         // Run memory consolidation if threshold reached
         {
             let session = self.sessions.get_or_create(&session_key)?;
-            if consolidation::should_consolidate(session, self.memory_window) {
+            if consolidation::should_consolidate(session, self.memory_window)
+                || consolidation::history_exceeds_token_budget(
+                    session,
+                    self.memory_window,
+                    tokenizer,
+                    self.context_budget.available_context_tokens(),
+                )
+            {
                 if let Err(e) = consolidation::consolidate(
                     session,
                     &self.provider,
@@ -1,6 +1,8 @@
 //! Memory consolidation: summarizes old conversation history into long-term memory
 
-use agent_diva_core::memory::{MemoryProvider, SyncTurnRequest, SyncTurnStatus};
+use crate::context_summary::estimate_chat_message_tokens;
+use crate::tokenizer::Tokenizer;
+use agent_diva_core::memory::{MemoryProvider, MemoryScope, SyncTurnRequest, SyncTurnStatus};
 use agent_diva_core::session::Session;
 use agent_diva_providers::{LLMProvider, Message};
 use std::path::Path;
@@ -10,6 +12,9 @@ use tracing::{debug, info, warn};
 /// Default number of messages before consolidation triggers
 pub const DEFAULT_MEMORY_WINDOW: usize = 100;
 
+/// Session metadata key holding the memory scope the session's turns use.
+const MEMORY_SCOPE_METADATA_KEY: &str = "memory_scope";
+
 const CONSOLIDATION_PROMPT: &str = r#"You are a memory consolidation assistant. Analyze the conversation below and extract important information.
 
 You MUST call the `save_memory` tool with your findings. Do not respond with text.
@@ -42,6 +47,30 @@ fn save_memory_tool_schema() -> serde_json::Value {
     })
 }
 
+/// Memory scope recorded on `session`, or the global scope for sessions that
+/// predate scoping.
+pub fn session_memory_scope(session: &Session) -> MemoryScope {
+    session
+        .metadata
+        .get(MEMORY_SCOPE_METADATA_KEY)
+        .and_then(|value| serde_json::from_value(value.clone()).ok())
+        .unwrap_or_default()
+}
+
+/// Record the memory scope of the session's latest turn, so consolidation
+/// writes to the scope the conversation belongs to.
+pub fn set_session_memory_scope(session: &mut Session, scope: &MemoryScope) {
+    let Ok(value) = serde_json::to_value(scope) else {
+        return;
+    };
+    if !session.metadata.is_object() {
+        session.metadata = serde_json::Value::Object(serde_json::Map::new());
+    }
+    if let Some(metadata) = session.metadata.as_object_mut() {
+        metadata.insert(MEMORY_SCOPE_METADATA_KEY.to_string(), value);
+    }
+}
+
 /// Check if consolidation should run
 pub fn should_consolidate(session: &Session, memory_window: usize) -> bool {
     let consolidated = session.last_consolidated.min(session.messages.len());
@@ -49,7 +78,40 @@ pub fn should_consolidate(session: &Session, memory_window: usize) -> bool {
     unconsolidated >= memory_window
 }
 
+/// Check if unconsolidated history no longer fits in `token_budget`, so older
+/// turns would be trimmed from context before reaching the message-count window
+///
+/// Consolidation keeps the most recent half window, which can itself exceed the
+/// budget. To avoid an LLM consolidation call on every turn in that case, the
+/// check only fires once a quarter window of messages is ready to be folded.
+pub fn history_exceeds_token_budget(
+    session: &Session,
+    memory_window: usize,
+    tokenizer: &dyn Tokenizer,
+    token_budget: usize,
+) -> bool {
+    let consolidated = session.last_consolidated.min(session.messages.len());
+    let unconsolidated = &session.messages[consolidated..];
+    let foldable = unconsolidated.len().saturating_sub(memory_window / 2);
+    if foldable < min_foldable_messages(memory_window) {
+        return false;
+    }
+    let tokens: usize = unconsolidated
+        .iter()
+        .map(|message| estimate_chat_message_tokens(message, tokenizer))
+        .sum();
+    tokens > token_budget
+}
+
+/// Messages that must be ready to fold before the token budget triggers consolidation.
+fn min_foldable_messages(memory_window: usize) -> usize {
+    (memory_window / 4).max(1)
+}
+
 /// Consolidate old messages into long-term memory
+///
+/// Callers decide when to run it (see [`should_consolidate`] and
+/// [`history_exceeds_token_budget`]); the most recent half window is always kept.
 pub async fn consolidate(
     session: &mut Session,
     provider: &Arc<dyn LLMProvider>,
@@ -59,10 +121,8 @@ pub async fn consolidate(
     memory_window: usize,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let consolidated = session.last_consolidated.min(session.messages.len());
+    let scope = session_memory_scope(session);
     let unconsolidated_count = session.messages.len() - consolidated;
-    if unconsolidated_count < memory_window {
-        return Ok(());
-    }
 
     info!(
         "Starting memory consolidation: {} unconsolidated messages",
@@ -92,6 +152,7 @@ pub async fn consolidate(
     let existing_memory = memory_provider
         .system_prompt_block(&agent_diva_core::memory::SystemPromptRequest {
             workspace_root: workspace.to_path_buf(),
+            scope: scope.clone(),
         })?
         .prompt_block
         .map(|block| block.markdown)
@@ -149,9 +210,11 @@ pub async fn consolidate(
             memory_provider
                 .sync_turn(SyncTurnRequest {
                     workspace_root: workspace.to_path_buf(),
+                    scope: scope.clone(),
                     memory_update_markdown: (!memory_update.is_empty())
                         .then(|| memory_update.to_string()),
                     history_entry: Some(entry),
+                    session_key: Some(session.key.clone()),
                 })
                 .await
                 .and_then(|response| match response.status {
@@ -165,8 +228,10 @@ pub async fn consolidate(
             memory_provider
                 .sync_turn(SyncTurnRequest {
                     workspace_root: workspace.to_path_buf(),
+                    scope: scope.clone(),
                     memory_update_markdown: Some(memory_update.to_string()),
                     history_entry: None,
+                    session_key: Some(session.key.clone()),
                 })
                 .await
                 .and_then(|response| match response.status {
@@ -192,3 +257,38 @@ pub async fn consolidate(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokenizer::HeuristicTokenizer;
+
+    fn session_with(messages: usize, last_consolidated: usize) -> Session {
+        let mut session = Session::new("cli:test");
+        for i in 0..messages {
+            session.add_message("user", format!("message {} {}", i, "word ".repeat(50)));
+        }
+        session.last_consolidated = last_consolidated;
+        session
+    }
+
+    #[test]
+    fn token_budget_waits_for_enough_foldable_messages() {
+        let tokenizer = HeuristicTokenizer;
+        // Right after consolidation the kept half window is over budget, but a
+        // few new messages are not worth another consolidation call.
+        let session = session_with(60, 5);
+        assert!(!history_exceeds_token_budget(&session, 100, &tokenizer, 10));
+
+        let session = session_with(80, 5);
+        assert!(history_exceeds_token_budget(&session, 100, &tokenizer, 10));
+
+        let session = session_with(80, 5);
+        assert!(!history_exceeds_token_budget(
+            &session,
+            100,
+            &tokenizer,
+            usize::MAX
+        ));
+    }
+}
@@ -1,9 +1,10 @@
 //! Context builder for assembling prompts
 
-use crate::skills::SkillsLoader;
+use crate::skills::{SkillMatch, SkillsLoader};
+use agent_diva_core::config::{AgentSkillsConfig, SkillActivationMode};
 use agent_diva_core::memory::{
-    MemoryManager, MemoryProvider, StartupInjectionShape, StartupStatus, SystemPromptBlock,
-    SystemPromptRequest, SystemPromptResponse,
+    HashingEmbedder, MemoryManager, MemoryProvider, MemoryScope, StartupInjectionShape,
+    StartupStatus, SystemPromptBlock, SystemPromptRequest, SystemPromptResponse,
 };
 use agent_diva_core::soul::SoulStateStore;
 use agent_diva_providers::{Message, MessageContent};
@@ -39,7 +40,12 @@ pub struct ContextBuilder {
     workspace: PathBuf,
     skills_loader: SkillsLoader,
     memory_provider: Arc<dyn MemoryProvider>,
+    memory_scope: MemoryScope,
     soul_settings: SoulContextSettings,
+    skill_settings: AgentSkillsConfig,
+    skill_embedder: Option<HashingEmbedder>,
+    /// Skills picked for the current turn by `select_skills`
+    selected_skills: Vec<String>,
 }
 
 impl ContextBuilder {
@@ -51,7 +57,11 @@ impl ContextBuilder {
             workspace,
             skills_loader,
             memory_provider,
+            memory_scope: MemoryScope::Global,
             soul_settings: SoulContextSettings::default(),
+            skill_settings: AgentSkillsConfig::default(),
+            skill_embedder: None,
+            selected_skills: Vec::new(),
         }
     }
 
@@ -63,7 +73,11 @@ impl ContextBuilder {
             workspace,
             skills_loader,
             memory_provider,
+            memory_scope: MemoryScope::Global,
             soul_settings: SoulContextSettings::default(),
+            skill_settings: AgentSkillsConfig::default(),
+            skill_embedder: None,
+            selected_skills: Vec::new(),
         }
     }
 
@@ -73,14 +87,56 @@ impl ContextBuilder {
         self
     }
 
+    /// Set the memory scope used for the next prompts.
+    pub fn set_memory_scope(&mut self, scope: MemoryScope) {
+        self.memory_scope = scope;
+    }
+
     /// Override soul context settings.
     pub fn set_soul_settings(&mut self, settings: SoulContextSettings) {
         self.soul_settings = settings;
     }
 
+    /// Override how skills are selected for each turn.
+    pub fn set_skill_settings(&mut self, settings: AgentSkillsConfig) {
+        self.skill_embedder = settings.embeddings.then(HashingEmbedder::default);
+        self.selected_skills.clear();
+        self.skill_settings = settings;
+    }
+
+    /// Pick the skills whose instructions the next prompts load, based on the
+    /// user message. Returns the picked skills with their scores.
+    pub fn select_skills(&mut self, message: &str) -> Vec<SkillMatch> {
+        let selected: Vec<SkillMatch> = match self.skill_settings.activation {
+            SkillActivationMode::Summary => Vec::new(),
+            SkillActivationMode::Relevance => self
+                .skills_loader
+                .rank_skills(message, self.skill_embedder.as_ref())
+                .into_iter()
+                .filter(|skill| skill.score >= self.skill_settings.min_score)
+                .take(self.skill_settings.top_k)
+                .collect(),
+        };
+        self.selected_skills = selected.iter().map(|skill| skill.name.clone()).collect();
+        selected
+    }
+
+    /// Skills whose instructions are loaded into the system prompt
+    pub fn active_skills(&self) -> Vec<String> {
+        let mut skills = self.skills_loader.get_always_skills();
+        for name in &self.selected_skills {
+            if !skills.contains(name) {
+                skills.push(name.clone());
+            }
+        }
+        skills
+    }
+
     /// Build system prompt from workspace files and memory
     pub fn build_system_prompt(&self) -> String {
         let workspace_path = self.workspace.display();
+        let memory_dir = self.memory_scope.memory_dir(&self.workspace);
+        let memory_path = memory_dir.display();
         let now = chrono::Local::now().format("%Y-%m-%d %H:%M (%A)");
         let identity_header = self.load_identity_header();
 
@@ -99,8 +155,8 @@ You have access to tools that allow you to:
 
 ## Workspace
 Your workspace is at: {workspace_path}
-- Memory files: {workspace_path}/memory/MEMORY.md
-- Memory history log: {workspace_path}/memory/HISTORY.md"#
+- Memory files: {memory_path}/MEMORY.md
+- Memory history log: {memory_path}/HISTORY.md"#
         );
 
         if self.soul_settings.enabled {
@@ -108,26 +164,39 @@ Your workspace is at: {workspace_path}
         }
 
         // Skills - progressive loading
-        // 1) Always-loaded skills (full content)
-        let always_skills = self.skills_loader.get_always_skills();
-        if !always_skills.is_empty() {
-            let always_content = self.skills_loader.load_skills_for_context(&always_skills);
-            if !always_content.is_empty() {
+        // 1) Always-loaded and selected skills (full content)
+        let active_skills = self.active_skills();
+        if !active_skills.is_empty() {
+            let active_content = self.skills_loader.load_skills_for_context(&active_skills);
+            if !active_content.is_empty() {
                 prompt.push_str("\n\n## Active Skills\n");
-                prompt.push_str(&always_content);
+                prompt.push_str(&active_content);
             }
         }
 
-        // 2) Available skills summary
-        let skills_summary = self.skills_loader.build_skills_summary();
-        if !skills_summary.is_empty() {
-            prompt.push_str("\n\n## Skills\n");
-            prompt.push_str(
-                "The following skills extend your capabilities. To use a skill, read its SKILL.md file using the read_file tool.\n",
-            );
-            prompt
-                .push_str("Skills with available=\"false\" need dependencies installed first.\n\n");
-            prompt.push_str(&skills_summary);
+        // 2) Remaining skills: a one-line index in relevance mode, the full
+        //    summary otherwise
+        if self.skill_settings.activation == SkillActivationMode::Relevance {
+            let skills_index = self.skills_loader.build_skills_index(&active_skills);
+            if !skills_index.is_empty() {
+                prompt.push_str("\n\n## Skills\n");
+                prompt.push_str(
+                    "More skills are available. Call the load_skill tool with a skill name when one fits the task.\n\n",
+                );
+                prompt.push_str(&skills_index);
+            }
+        } else {
+            let skills_summary = self.skills_loader.build_skills_summary();
+            if !skills_summary.is_empty() {
+                prompt.push_str("\n\n## Skills\n");
+                prompt.push_str(
+                    "The following skills extend your capabilities. To use a skill, read its SKILL.md file using the read_file tool.\n",
+                );
+                prompt.push_str(
+                    "Skills with available=\"false\" need dependencies installed first.\n\n",
+                );
+                prompt.push_str(&skills_summary);
+            }
         }
 
         // Inject long-term memory if available
@@ -135,6 +204,7 @@ Your workspace is at: {workspace_path}
             .memory_provider
             .system_prompt_block(&SystemPromptRequest {
                 workspace_root: self.workspace.clone(),
+                scope: self.memory_scope.clone(),
             })
             .map(render_startup_injection)
             .unwrap_or_else(|err| render_provider_error_startup_injection(&err.to_string()));
@@ -155,8 +225,8 @@ Always be helpful, accurate, and concise. When using tools, explain what you're
         );
 
         prompt.push_str(&format!(
-            "\nWhen remembering something, write to {}/memory/MEMORY.md",
-            workspace_path
+            "\nWhen remembering something, write to {}/MEMORY.md",
+            memory_path
         ));
 
         prompt
@@ -263,6 +333,17 @@ Always be helpful, accurate, and concise. When using tools, explain what you're
         messages.push(Message::system(system_prompt));
 
         // History - convert from ChatMessage to Message
+        messages.extend(Self::history_messages(history));
+
+        // Current message
+        messages.push(Message::user(current_message));
+
+        messages
+    }
+
+    /// Convert session history into provider messages, skipping unknown roles.
+    pub fn history_messages(history: Vec<agent_diva_core::session::ChatMessage>) -> Vec<Message> {
+        let mut messages = Vec::with_capacity(history.len());
         for msg in history {
             let message = match msg.role.as_str() {
                 "user" => Message::user(&msg.content),
@@ -296,10 +377,6 @@ Always be helpful, accurate, and concise. When using tools, explain what you're
             };
             messages.push(message);
         }
-
-        // Current message
-        messages.push(Message::user(current_message));
-
         messages
     }
 
@@ -533,7 +610,11 @@ mod tests {
         )
         .unwrap();
 
-        let builder = ContextBuilder::with_skills(workspace.path().to_path_buf(), None);
+        let mut builder = ContextBuilder::with_skills(workspace.path().to_path_buf(), None);
+        builder.set_skill_settings(AgentSkillsConfig {
+            activation: SkillActivationMode::Summary,
+            ..AgentSkillsConfig::default()
+        });
         let prompt = builder.build_system_prompt();
 
         assert!(prompt.contains("## Active Skills"));
@@ -541,6 +622,59 @@ mod tests {
         assert!(prompt.contains("<skills>"));
     }
 
+    #[test]
+    fn test_select_skills_loads_only_matching_skills() {
+        let workspace = TempDir::new().unwrap();
+        let skills_dir = workspace.path().join("skills");
+        for (name, front) in [
+            (
+                "pdf-tools",
+                "description: Work with PDF files\ntriggers: [pdf, extract text]",
+            ),
+            (
+                "weather",
+                "description: Weather forecasts\nkeywords:\n  - forecast\n  - rain",
+            ),
+            (
+                "github",
+                "description: GitHub workflows\ntriggers: pull request",
+            ),
+        ] {
+            fs::create_dir_all(skills_dir.join(name)).unwrap();
+            fs::write(
+                skills_dir.join(name).join("SKILL.md"),
+                format!("---\nname: {name}\n{front}\n---\n\n# {name} body\n"),
+            )
+            .unwrap();
+        }
+        let mut builder = ContextBuilder::with_skills(
+            workspace.path().to_path_buf(),
+            Some(workspace.path().join("no-builtin")),
+        );
+
+        let selected = builder.select_skills("Will it rain? Also extract text from this PDF");
+        let names: Vec<&str> = selected.iter().map(|skill| skill.name.as_str()).collect();
+        assert_eq!(names, vec!["pdf-tools", "weather"]);
+        assert_eq!(selected[0].score, 2.0);
+
+        let prompt = builder.build_system_prompt();
+        assert!(prompt.contains("# pdf-tools body"));
+        assert!(prompt.contains("# weather body"));
+        assert!(!prompt.contains("# github body"));
+        assert!(prompt.contains("- github: GitHub workflows"));
+        assert!(!prompt.contains("<skills>"));
+        assert_eq!(builder.active_skills(), vec!["pdf-tools", "weather"]);
+
+        builder.set_skill_settings(AgentSkillsConfig {
+            top_k: 1,
+            ..AgentSkillsConfig::default()
+        });
+        let selected = builder.select_skills("rain and pdf");
+        assert_eq!(selected.len(), 1);
+        assert!(builder.select_skills("hello there").is_empty());
+        assert!(builder.active_skills().is_empty());
+    }
+
     #[test]
     fn test_build_system_prompt_uses_memory_provider_contract() {
         let workspace = TempDir::new().unwrap();
@@ -553,6 +687,25 @@ mod tests {
         assert!(prompt.contains(&workspace.path().display().to_string()));
     }
 
+    #[test]
+    fn test_build_system_prompt_uses_scoped_memory() {
+        let workspace = TempDir::new().unwrap();
+        let scope = MemoryScope::Chat {
+            channel: "telegram".to_string(),
+            chat_id: "42".to_string(),
+        };
+        let scoped_dir = scope.memory_dir(workspace.path());
+        fs::create_dir_all(&scoped_dir).unwrap();
+        fs::write(scoped_dir.join("MEMORY.md"), "- Prefers tea").unwrap();
+        let mut builder = ContextBuilder::new(workspace.path().to_path_buf());
+
+        assert!(!builder.build_system_prompt().contains("Prefers tea"));
+        builder.set_memory_scope(scope);
+        let prompt = builder.build_system_prompt();
+        assert!(prompt.contains("Prefers tea"));
+        assert!(prompt.contains(&format!("{}/MEMORY.md", scoped_dir.display())));
+    }
+
     #[test]
     fn test_build_system_prompt_consumes_explicit_compact_rendered_shape() {
         let rendered = render_startup_injection(SystemPromptResponse::ready(SystemPromptBlock {
@@ -1,3 +1,5 @@
+use crate::tokenizer::{HeuristicTokenizer, Tokenizer};
+use agent_diva_core::config::ContextCompactionStrategy;
 use agent_diva_providers::{
     provider_error_indicates_context_overflow as provider_context_overflow, Message,
     MessageContent, MessageContentPart, ProviderError,
@@ -8,6 +10,7 @@ pub struct ContextBudgetPolicy {
     pub context_budget_tokens: usize,
     pub reserve_tokens: usize,
     pub overflow_retry_enabled: bool,
+    pub compaction_strategy: ContextCompactionStrategy,
 }
 
 impl ContextBudgetPolicy {
@@ -21,6 +24,10 @@ impl ContextBudgetPolicy {
         200
     }
 
+    pub fn summarizes_history(&self) -> bool {
+        self.compaction_strategy == ContextCompactionStrategy::Summarize
+    }
+
     pub fn overflow_user_message(&self) -> &'static str {
         "The conversation context is too large for this model. I automatically shrank it once, but it still did not fit. Please start a fresh session or shorten the request."
     }
@@ -32,6 +39,7 @@ impl Default for ContextBudgetPolicy {
             context_budget_tokens: 24_000,
             reserve_tokens: 4_000,
             overflow_retry_enabled: true,
+            compaction_strategy: ContextCompactionStrategy::Trim,
         }
     }
 }
@@ -50,6 +58,17 @@ pub struct ContextBudgetReport {
     pub available_context_tokens: usize,
     pub removed_history_messages: usize,
     pub truncated_tool_messages: usize,
+    pub summarized_history_messages: usize,
+    pub summary_tokens_saved: usize,
+}
+
+impl ContextBudgetReport {
+    /// Fold the savings of a summarizing pass (which runs before trimming) into this report.
+    pub fn record_summary(&mut self, summarized_history_messages: usize, tokens_saved: usize) {
+        self.summarized_history_messages = summarized_history_messages;
+        self.summary_tokens_saved = tokens_saved;
+        self.estimated_tokens_before += tokens_saved;
+    }
 }
 
 pub fn compact_messages_to_budget(
@@ -57,9 +76,10 @@ pub fn compact_messages_to_budget(
     tool_defs: &[serde_json::Value],
     policy: &ContextBudgetPolicy,
     mode: CompactionMode,
+    tokenizer: &dyn Tokenizer,
 ) -> (Vec<Message>, ContextBudgetReport) {
     let available_context_tokens = policy.available_context_tokens();
-    let estimated_tokens_before = estimate_request_tokens(messages, tool_defs);
+    let estimated_tokens_before = estimate_request_tokens(messages, tool_defs, tokenizer);
     let mut compacted = messages.to_vec();
     let mut truncated_tool_messages = 0;
 
@@ -74,15 +94,21 @@ pub fn compact_messages_to_budget(
         }
     }
 
-    let mut estimated_tokens_after = estimate_request_tokens(&compacted, tool_defs);
+    // Count each message once; BPE encoding is too costly to redo per removal.
+    let tool_tokens = estimate_tool_defs_tokens(tool_defs, tokenizer);
+    let mut message_tokens: Vec<usize> = compacted
+        .iter()
+        .map(|message| estimate_message_tokens(message, tokenizer))
+        .collect();
+    let mut estimated_tokens_after = tool_tokens + message_tokens.iter().sum::<usize>();
     let mut removed_history_messages = 0;
     while estimated_tokens_after > available_context_tokens {
         let Some(index) = oldest_removable_index(&compacted, mode) else {
             break;
         };
         compacted.remove(index);
+        estimated_tokens_after -= message_tokens.remove(index);
         removed_history_messages += 1;
-        estimated_tokens_after = estimate_request_tokens(&compacted, tool_defs);
     }
 
     (
@@ -94,14 +120,29 @@ pub fn compact_messages_to_budget(
             available_context_tokens,
             removed_history_messages,
             truncated_tool_messages,
+            summarized_history_messages: 0,
+            summary_tokens_saved: 0,
         },
     )
 }
 
-pub fn estimate_request_tokens(messages: &[Message], tool_defs: &[serde_json::Value]) -> usize {
-    let message_tokens: usize = messages.iter().map(estimate_message_tokens).sum();
-    let tool_tokens: usize = tool_defs.iter().map(estimate_serialized_tokens).sum();
-    message_tokens + tool_tokens
+pub fn estimate_request_tokens(
+    messages: &[Message],
+    tool_defs: &[serde_json::Value],
+    tokenizer: &dyn Tokenizer,
+) -> usize {
+    let message_tokens: usize = messages
+        .iter()
+        .map(|message| estimate_message_tokens(message, tokenizer))
+        .sum();
+    message_tokens + estimate_tool_defs_tokens(tool_defs, tokenizer)
+}
+
+fn estimate_tool_defs_tokens(tool_defs: &[serde_json::Value], tokenizer: &dyn Tokenizer) -> usize {
+    tool_defs
+        .iter()
+        .map(|def| estimate_serialized_tokens(def, tokenizer))
+        .sum()
 }
 
 pub fn provider_error_indicates_context_overflow(error: &ProviderError) -> bool {
@@ -173,18 +214,18 @@ fn trim_text(text: &mut String, max_chars: usize) -> bool {
     true
 }
 
-fn estimate_message_tokens(message: &Message) -> usize {
+fn estimate_message_tokens(message: &Message, tokenizer: &dyn Tokenizer) -> usize {
     let base = 12;
-    let content_tokens = estimate_content_tokens(&message.content);
+    let content_tokens = estimate_content_tokens(&message.content, tokenizer);
     let name_tokens = message
         .name
         .as_deref()
-        .map(estimate_text_tokens)
+        .map(|text| estimate_text_tokens(text, tokenizer))
         .unwrap_or(0);
     let tool_call_id_tokens = message
         .tool_call_id
         .as_deref()
-        .map(estimate_text_tokens)
+        .map(|text| estimate_text_tokens(text, tokenizer))
         .unwrap_or(0);
     let tool_calls_tokens = message
         .tool_calls
@@ -193,10 +234,10 @@ fn estimate_message_tokens(message: &Message) -> usize {
             calls
                 .iter()
                 .map(|call| {
-                    let mut tokens = estimate_text_tokens(&call.id)
-                        + estimate_text_tokens(&call.call_type)
-                        + estimate_text_tokens(&call.name);
-                    tokens += estimate_serialized_tokens(&call.arguments);
+                    let mut tokens = estimate_text_tokens(&call.id, tokenizer)
+                        + estimate_text_tokens(&call.call_type, tokenizer)
+                        + estimate_text_tokens(&call.name, tokenizer);
+                    tokens += estimate_serialized_tokens(&call.arguments, tokenizer);
                     tokens
                 })
                 .sum::<usize>()
@@ -205,12 +246,12 @@ fn estimate_message_tokens(message: &Message) -> usize {
     let reasoning_tokens = message
         .reasoning_content
         .as_deref()
-        .map(estimate_text_tokens)
+        .map(|text| estimate_text_tokens(text, tokenizer))
         .unwrap_or(0);
     let thinking_tokens = message
         .thinking_blocks
         .as_ref()
-        .map(estimate_serialized_tokens)
+        .map(|value| estimate_serialized_tokens(value, tokenizer))
         .unwrap_or(0);
 
     base + content_tokens
@@ -221,34 +262,36 @@ fn estimate_message_tokens(message: &Message) -> usize {
         + thinking_tokens
 }
 
-fn estimate_content_tokens(content: &MessageContent) -> usize {
+fn estimate_content_tokens(content: &MessageContent, tokenizer: &dyn Tokenizer) -> usize {
     match content {
-        MessageContent::Text(text) => estimate_text_tokens(text),
+        MessageContent::Text(text) => estimate_text_tokens(text, tokenizer),
         MessageContent::Parts(parts) => parts
             .iter()
             .map(|part| match part {
-                MessageContentPart::Text { text } => estimate_text_tokens(text),
-                MessageContentPart::ImageUrl { image_url } => estimate_text_tokens(&image_url.url),
+                MessageContentPart::Text { text } => estimate_text_tokens(text, tokenizer),
+                MessageContentPart::ImageUrl { image_url } => {
+                    estimate_text_tokens(&image_url.url, tokenizer)
+                }
                 MessageContentPart::ImageFile { image_file } => {
-                    estimate_text_tokens(&image_file.file_id)
+                    estimate_text_tokens(&image_file.file_id, tokenizer)
                 }
+                // Inline image payloads are not text; BPE-encoding megabytes of base64 is wasted work.
                 MessageContentPart::ImageData { image_data } => {
-                    estimate_text_tokens(&image_data.data_uri)
+                    estimate_text_tokens(&image_data.data_uri, &HeuristicTokenizer)
                 }
             })
             .sum(),
     }
 }
 
-fn estimate_serialized_tokens<T: serde::Serialize>(value: &T) -> usize {
+fn estimate_serialized_tokens<T: serde::Serialize>(value: &T, tokenizer: &dyn Tokenizer) -> usize {
     serde_json::to_string(value)
-        .map(|json| estimate_text_tokens(&json))
+        .map(|json| estimate_text_tokens(&json, tokenizer))
         .unwrap_or(64)
 }
 
-fn estimate_text_tokens(text: &str) -> usize {
-    let chars = text.chars().count();
-    (chars / 4).max(1) + 2
+pub(crate) fn estimate_text_tokens(text: &str, tokenizer: &dyn Tokenizer) -> usize {
+    tokenizer.count_tokens(text).max(1) + 2
 }
 
 #[cfg(test)]
@@ -271,10 +314,16 @@ mod tests {
             context_budget_tokens: 3_000,
             reserve_tokens: 500,
             overflow_retry_enabled: true,
+            compaction_strategy: ContextCompactionStrategy::Trim,
         };
 
-        let (compacted, report) =
-            compact_messages_to_budget(&messages, &[], &policy, CompactionMode::Normal);
+        let (compacted, report) = compact_messages_to_budget(
+            &messages,
+            &[],
+            &policy,
+            CompactionMode::Normal,
+            &HeuristicTokenizer,
+        );
 
         assert!(report.truncated_tool_messages >= 1);
         assert_eq!(
@@ -297,10 +346,16 @@ mod tests {
             context_budget_tokens: 40,
             reserve_tokens: 10,
             overflow_retry_enabled: true,
+            compaction_strategy: ContextCompactionStrategy::Trim,
         };
 
-        let (compacted, report) =
-            compact_messages_to_budget(&messages, &[], &policy, CompactionMode::OverflowRecovery);
+        let (compacted, report) = compact_messages_to_budget(
+            &messages,
+            &[],
+            &policy,
+            CompactionMode::OverflowRecovery,
+            &HeuristicTokenizer,
+        );
 
         assert!(report.removed_history_messages >= 1);
         assert!(!compacted
@@ -339,7 +394,20 @@ mod tests {
             "function": {"name": "read_file", "parameters": {"type": "object"}}
         })];
 
-        assert!(estimate_request_tokens(&messages, &tool_defs) > 0);
+        assert!(estimate_request_tokens(&messages, &tool_defs, &HeuristicTokenizer) > 0);
+    }
+
+    #[test]
+    fn estimate_request_tokens_uses_model_tokenizer() {
+        let chinese = vec![Message::user("上下文预算需要准确地统计中文字符的数量")];
+        assert!(
+            estimate_request_tokens(&chinese, &[], &HeuristicTokenizer)
+                >= 12 + "上下文预算需要准确地统计中文字符的数量".chars().count()
+        );
+
+        let english = vec![Message::user("The quick brown fox jumps over the lazy dog")];
+        let bpe = crate::tokenizer::tokenizer_for_model("gpt-4o");
+        assert_eq!(estimate_request_tokens(&english, &[], bpe), 12 + 9 + 2);
     }
 
     #[test]
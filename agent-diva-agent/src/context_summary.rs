@@ -0,0 +1,420 @@
+//! Summarizing context compaction: folds old session turns into a cached summary
+
+use crate::context_budget::estimate_text_tokens;
+use crate::tokenizer::Tokenizer;
+use agent_diva_core::session::{ChatMessage, Session};
+use agent_diva_providers::{LLMProvider, Message, ProviderError};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use std::sync::Arc;
+use tracing::info;
+
+/// Session metadata key holding the cached [`ContextSummary`].
+pub const CONTEXT_SUMMARY_METADATA_KEY: &str = "context_summary";
+
+/// Per-message character cap when rendering a span for the summarizer.
+const SUMMARY_INPUT_MESSAGE_CHARS: usize = 2_000;
+
+const SUMMARY_PROMPT: &str = r#"You compress the earlier part of a conversation so it can be dropped from the context window.
+
+Write a concise summary in Markdown that preserves:
+- decisions that were made and the reasons given
+- facts, names, paths, identifiers and numbers the user or tools provided
+- the user's stated preferences and constraints
+- tasks that are still open or were promised
+
+If a previous summary is provided, merge the new conversation into it instead of repeating it.
+Respond with the summary only."#;
+
+/// Model-generated summary of the oldest session messages.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContextSummary {
+    /// Number of leading session messages folded into `summary`.
+    pub covered_messages: usize,
+    /// Timestamp of the last covered message, used to detect a reset session.
+    pub covered_until: DateTime<Utc>,
+    /// Summary text injected in place of the covered messages.
+    pub summary: String,
+}
+
+/// Savings from one summarizing pass, reported through `ContextBudgetReport`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SummaryOutcome {
+    pub summarized_messages: usize,
+    pub tokens_saved: usize,
+}
+
+impl ContextSummary {
+    /// Load the cached summary, ignoring entries that no longer match the session
+    /// (e.g. after the session was cleared).
+    pub fn load(session: &Session) -> Option<Self> {
+        let value = session.metadata.get(CONTEXT_SUMMARY_METADATA_KEY)?;
+        let summary: Self = serde_json::from_value(value.clone()).ok()?;
+        let last_covered = session
+            .messages
+            .get(summary.covered_messages.checked_sub(1)?)?;
+        (last_covered.timestamp == summary.covered_until && !summary.summary.is_empty())
+            .then_some(summary)
+    }
+
+    /// Store the summary in session metadata.
+    pub fn store(&self, session: &mut Session) {
+        if !session.metadata.is_object() {
+            session.metadata = serde_json::Value::Object(serde_json::Map::new());
+        }
+        if let (Some(map), Ok(value)) =
+            (session.metadata.as_object_mut(), serde_json::to_value(self))
+        {
+            map.insert(CONTEXT_SUMMARY_METADATA_KEY.to_string(), value);
+        }
+    }
+
+    /// Render the summary as the message that replaces the covered turns.
+    pub fn to_message(&self) -> Message {
+        Message::system(format!(
+            "## Summary of Earlier Conversation\n\nThe oldest turns of this session were compacted into the summary below.\n\n{}",
+            self.summary
+        ))
+    }
+
+    pub fn estimated_tokens(&self, tokenizer: &dyn Tokenizer) -> usize {
+        estimate_text_tokens(&self.summary, tokenizer) + 32
+    }
+}
+
+/// Rough token estimate for a stored session message.
+pub fn estimate_chat_message_tokens(message: &ChatMessage, tokenizer: &dyn Tokenizer) -> usize {
+    let tool_call_tokens = message
+        .tool_calls
+        .as_ref()
+        .and_then(|calls| serde_json::to_string(calls).ok())
+        .map(|json| estimate_text_tokens(&json, tokenizer))
+        .unwrap_or(0);
+    12 + estimate_text_tokens(&message.content, tokenizer) + tool_call_tokens
+}
+
+/// Pick the contiguous span of session messages to fold into the summary.
+///
+/// The span starts right after whatever is already summarized (or consolidated)
+/// and ends where the most recent messages that fit in half of the history
+/// budget begin, aligned to a user message so tool results never lose their call.
+pub fn plan_summary_span(
+    session: &Session,
+    previous: Option<&ContextSummary>,
+    fixed_tokens: usize,
+    available_tokens: usize,
+    tokenizer: &dyn Tokenizer,
+) -> Option<Range<usize>> {
+    let len = session.messages.len();
+    let start = previous
+        .map(|summary| summary.covered_messages)
+        .unwrap_or(0)
+        .max(session.last_consolidated)
+        .min(len);
+    let keep_budget = available_tokens.saturating_sub(fixed_tokens) / 2;
+
+    let mut kept_tokens = 0;
+    let mut split = len;
+    for index in (start..len).rev() {
+        let tokens = estimate_chat_message_tokens(&session.messages[index], tokenizer);
+        if kept_tokens + tokens > keep_budget {
+            break;
+        }
+        kept_tokens += tokens;
+        split = index;
+    }
+
+    let user_at_or_after = (split..len).find(|index| session.messages[*index].role == "user");
+    let split = match user_at_or_after {
+        Some(index) => index,
+        // Nothing recent fits; keep at least the latest user turn verbatim.
+        None => (start..len)
+            .rev()
+            .find(|index| session.messages[*index].role == "user")?,
+    };
+
+    (split > start).then_some(start..split)
+}
+
+/// Ask the model to fold `span` into `previous`, returning the merged summary text.
+pub async fn summarize_span(
+    provider: &Arc<dyn LLMProvider>,
+    model: &str,
+    previous: Option<&str>,
+    span: &[ChatMessage],
+) -> Result<String, ProviderError> {
+    let mut conversation = String::new();
+    for msg in span {
+        let content = if msg.content.chars().count() > SUMMARY_INPUT_MESSAGE_CHARS {
+            format!(
+                "{}...",
+                msg.content
+                    .chars()
+                    .take(SUMMARY_INPUT_MESSAGE_CHARS)
+                    .collect::<String>()
+            )
+        } else {
+            msg.content.clone()
+        };
+        match msg.name.as_deref() {
+            Some(name) if msg.role == "tool" => {
+                conversation.push_str(&format!("[tool {}]: {}\n", name, content))
+            }
+            _ => conversation.push_str(&format!("[{}]: {}\n", msg.role, content)),
+        }
+    }
+
+    let user_content = format!(
+        "## Previous Summary\n{}\n\n## Conversation to Summarize\n{}",
+        previous.unwrap_or("(none)"),
+        conversation
+    );
+    let response = provider
+        .chat(
+            vec![Message::system(SUMMARY_PROMPT), Message::user(user_content)],
+            None,
+            Some(model.to_string()),
+            2048,
+            0.3,
+        )
+        .await?;
+
+    response
+        .content
+        .map(|content| content.trim().to_string())
+        .filter(|content| !content.is_empty())
+        .ok_or_else(|| ProviderError::InvalidResponse("summarizer returned no text".to_string()))
+}
+
+/// Extend the session's cached summary so the request fits the budget.
+///
+/// Returns `Ok(None)` when there is nothing worth summarizing. The new summary
+/// is stored in session metadata; callers persist the session.
+pub async fn summarize_history(
+    session: &mut Session,
+    provider: &Arc<dyn LLMProvider>,
+    model: &str,
+    fixed_tokens: usize,
+    available_tokens: usize,
+    tokenizer: &dyn Tokenizer,
+) -> Result<Option<SummaryOutcome>, ProviderError> {
+    let previous = ContextSummary::load(session);
+    let Some(span) = plan_summary_span(
+        session,
+        previous.as_ref(),
+        fixed_tokens,
+        available_tokens,
+        tokenizer,
+    ) else {
+        return Ok(None);
+    };
+
+    let messages = &session.messages[span.clone()];
+    let span_tokens: usize = messages
+        .iter()
+        .map(|message| estimate_chat_message_tokens(message, tokenizer))
+        .sum();
+    let summary = summarize_span(
+        provider,
+        model,
+        previous.as_ref().map(|summary| summary.summary.as_str()),
+        messages,
+    )
+    .await?;
+
+    let summary = ContextSummary {
+        covered_messages: span.end,
+        covered_until: session.messages[span.end - 1].timestamp,
+        summary,
+    };
+    let previous_tokens = previous
+        .as_ref()
+        .map(|summary| summary.estimated_tokens(tokenizer))
+        .unwrap_or(0);
+    let outcome = SummaryOutcome {
+        summarized_messages: span.len(),
+        tokens_saved: (span_tokens + previous_tokens)
+            .saturating_sub(summary.estimated_tokens(tokenizer)),
+    };
+    info!(
+        "Summarized {} history messages for {} (~{} tokens saved)",
+        outcome.summarized_messages, session.key, outcome.tokens_saved
+    );
+    summary.store(session);
+    Ok(Some(outcome))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokenizer::HeuristicTokenizer;
+    use agent_diva_providers::{LLMResponse, ProviderResult};
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    struct StubSummarizer {
+        reply: Option<String>,
+        requests: Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl LLMProvider for StubSummarizer {
+        async fn chat(
+            &self,
+            messages: Vec<Message>,
+            _tools: Option<Vec<serde_json::Value>>,
+            _model: Option<String>,
+            _max_tokens: i32,
+            _temperature: f64,
+        ) -> ProviderResult<LLMResponse> {
+            self.requests
+                .lock()
+                .unwrap()
+                .push(messages[1].content.to_text_lossy());
+            match &self.reply {
+                Some(reply) => Ok(LLMResponse {
+                    content: Some(reply.clone()),
+                    tool_calls: Vec::new(),
+                    finish_reason: "stop".to_string(),
+                    usage: HashMap::new(),
+                    reasoning_content: None,
+                }),
+                None => Err(ProviderError::api_message("summarizer down".to_string())),
+            }
+        }
+
+        fn get_default_model(&self) -> String {
+            "stub".to_string()
+        }
+    }
+
+    fn stub(reply: Option<&str>) -> (Arc<StubSummarizer>, Arc<dyn LLMProvider>) {
+        let stub = Arc::new(StubSummarizer {
+            reply: reply.map(str::to_string),
+            requests: Mutex::new(Vec::new()),
+        });
+        let provider: Arc<dyn LLMProvider> = stub.clone();
+        (stub, provider)
+    }
+
+    fn long_session(turns: usize) -> Session {
+        let mut session = Session::new("cli:test");
+        for i in 0..turns {
+            session.add_message("user", format!("question {} {}", i, "q".repeat(400)));
+            session.add_message("assistant", format!("answer {} {}", i, "a".repeat(400)));
+        }
+        session
+    }
+
+    #[test]
+    fn plan_summary_span_keeps_recent_turns_aligned_to_user() {
+        let session = long_session(10);
+        let span = plan_summary_span(&session, None, 500, 1_500, &HeuristicTokenizer).unwrap();
+
+        assert_eq!(span.start, 0);
+        assert!(span.end < session.messages.len());
+        assert_eq!(session.messages[span.end].role, "user");
+    }
+
+    #[test]
+    fn plan_summary_span_starts_after_cached_summary() {
+        let session = long_session(10);
+        let previous = ContextSummary {
+            covered_messages: 6,
+            covered_until: session.messages[5].timestamp,
+            summary: "earlier".to_string(),
+        };
+        let span =
+            plan_summary_span(&session, Some(&previous), 500, 1_500, &HeuristicTokenizer).unwrap();
+        assert_eq!(span.start, 6);
+
+        let everything = ContextSummary {
+            covered_messages: 18,
+            covered_until: session.messages[17].timestamp,
+            summary: "earlier".to_string(),
+        };
+        assert!(
+            plan_summary_span(&session, Some(&everything), 500, 1_500, &HeuristicTokenizer)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn summary_round_trips_through_session_metadata() {
+        let mut session = long_session(2);
+        let summary = ContextSummary {
+            covered_messages: 2,
+            covered_until: session.messages[1].timestamp,
+            summary: "user asked twice".to_string(),
+        };
+        summary.store(&mut session);
+        assert_eq!(ContextSummary::load(&session), Some(summary));
+
+        session.clear();
+        assert_eq!(ContextSummary::load(&session), None);
+        session.add_message("user", "fresh start");
+        session.add_message("assistant", "hello again");
+        assert_eq!(ContextSummary::load(&session), None);
+    }
+
+    #[tokio::test]
+    async fn summarize_history_merges_into_previous_summary() {
+        let mut session = long_session(10);
+        let (stub, provider) = stub(Some("first summary"));
+        let outcome = summarize_history(
+            &mut session,
+            &provider,
+            "stub",
+            500,
+            1_500,
+            &HeuristicTokenizer,
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert!(outcome.summarized_messages > 0);
+        assert!(outcome.tokens_saved > 0);
+        let first = ContextSummary::load(&session).unwrap();
+        assert_eq!(first.summary, "first summary");
+        assert_eq!(first.covered_messages, outcome.summarized_messages);
+
+        for i in 10..16 {
+            session.add_message("user", format!("question {} {}", i, "q".repeat(400)));
+            session.add_message("assistant", format!("answer {} {}", i, "a".repeat(400)));
+        }
+        let (stub_again, provider) = self::stub(Some("second summary"));
+        summarize_history(
+            &mut session,
+            &provider,
+            "stub",
+            500,
+            1_500,
+            &HeuristicTokenizer,
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        let second = ContextSummary::load(&session).unwrap();
+        assert!(second.covered_messages > first.covered_messages);
+        assert!(stub_again.requests.lock().unwrap()[0].contains("first summary"));
+        assert!(stub.requests.lock().unwrap()[0].contains("(none)"));
+    }
+
+    #[tokio::test]
+    async fn summarize_history_failure_leaves_cache_untouched() {
+        let mut session = long_session(10);
+        let (_stub, provider) = stub(None);
+        assert!(summarize_history(
+            &mut session,
+            &provider,
+            "stub",
+            500,
+            1_500,
+            &HeuristicTokenizer
+        )
+        .await
+        .is_err());
+        assert_eq!(ContextSummary::load(&session), None);
+    }
+}
@@ -6,11 +6,15 @@ pub mod agent_loop;
 pub mod consolidation;
 pub mod context;
 pub mod context_budget;
+pub mod context_summary;
 pub(crate) mod loop_guard;
 pub mod runtime_control;
 pub mod skills;
 pub mod subagent;
 pub mod subagent_policy;
+pub mod subagent_profile;
+pub mod subagent_tools;
+pub mod tokenizer;
 pub mod tool_assembly;
 pub mod tool_config;
 
@@ -19,5 +23,6 @@ pub use agent_loop::{AgentLoop, AgentLoopToolSet, ToolConfig};
 pub use context_budget::ContextBudgetPolicy;
 pub use runtime_control::RuntimeControlCommand;
 pub use subagent_policy::SubagentPolicy;
+pub use subagent_profile::SubagentProfile;
 pub use tool_assembly::{SubagentSpawner, ToolAssembly};
 pub use tool_config::builtin::BuiltInToolsConfig;
@@ -27,4 +27,47 @@ pub enum RuntimeControlCommand {
         session_key: String,
         reply_tx: tokio::sync::oneshot::Sender<Result<bool, String>>,
     },
+    /// Drop every message from `message_count` on. Replies with the dropped messages.
+    RewindSession {
+        session_key: String,
+        message_count: usize,
+        reply_tx: tokio::sync::oneshot::Sender<
+            Result<Vec<agent_diva_core::session::ChatMessage>, String>,
+        >,
+    },
+    /// Rewind to just before the user message at `index` so it can be edited
+    /// and resent. Replies with the removed user message.
+    RewindToUserMessage {
+        session_key: String,
+        index: usize,
+        reply_tx:
+            tokio::sync::oneshot::Sender<Result<agent_diva_core::session::ChatMessage, String>>,
+    },
+    /// Copy the first `message_count` messages (all when `None`) into a new
+    /// session. Replies with the new session key.
+    ForkSession {
+        session_key: String,
+        new_key: Option<String>,
+        message_count: Option<usize>,
+        reply_tx: tokio::sync::oneshot::Sender<Result<String, String>>,
+    },
+    /// Run memory consolidation for one session now instead of waiting for the
+    /// window threshold. Replies whether the consolidation cursor advanced.
+    ConsolidateSession {
+        session_key: String,
+        reply_tx: tokio::sync::oneshot::Sender<Result<bool, String>>,
+    },
+    /// Run one registered tool outside of any turn, e.g. for a scheduled job.
+    /// Replies with the tool output; failures come back as "Error: ..." text.
+    ///
+    /// The tool runs for the `channel`/`chat_id` conversation: its session and
+    /// memory scope are injected by the runtime, replacing any `context_*`
+    /// values in `args`.
+    InvokeTool {
+        name: String,
+        args: serde_json::Value,
+        channel: String,
+        chat_id: String,
+        reply_tx: tokio::sync::oneshot::Sender<String>,
+    },
 }
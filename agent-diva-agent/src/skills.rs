@@ -4,8 +4,13 @@
 //! specific tools or perform certain tasks. They contain YAML frontmatter
 //! with metadata and markdown content with instructions.
 
+use agent_diva_core::memory::embedding::cosine_similarity;
+use agent_diva_core::memory::HashingEmbedder;
+use agent_diva_tooling::{Tool, ToolError};
+use agent_diva_tools::SkillToolSpec;
+use async_trait::async_trait;
 use regex::Regex;
-use serde_json::Value;
+use serde_json::{json, Value};
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -41,6 +46,17 @@ pub struct SkillMetadata {
     pub homepage: Option<String>,
     pub always: bool,
     pub metadata: Option<String>,
+    /// Words and phrases that activate the skill (`triggers` and `keywords`)
+    pub triggers: Vec<String>,
+}
+
+/// A skill scored against a user message
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkillMatch {
+    pub name: String,
+    pub score: f32,
+    /// Triggers (or the skill name) found in the message
+    pub matched: Vec<String>,
 }
 
 /// Parsed agent-diva metadata from JSON in frontmatter
@@ -50,6 +66,30 @@ pub struct SkillRuntimeMetadata {
     pub always: bool,
     pub requires_bins: Vec<String>,
     pub requires_env: Vec<String>,
+    /// Scripts the skill exposes as tools
+    pub tools: Vec<SkillToolSpec>,
+}
+
+impl SkillRuntimeMetadata {
+    /// Required binaries and environment variables that are not present,
+    /// as `CLI: <bin>` / `ENV: <var>` entries
+    pub fn missing(&self) -> Vec<String> {
+        let mut missing = Vec::new();
+
+        for bin in &self.requires_bins {
+            if which::which(bin).is_err() {
+                missing.push(format!("CLI: {}", bin));
+            }
+        }
+
+        for env in &self.requires_env {
+            if std::env::var(env).is_err() {
+                missing.push(format!("ENV: {}", env));
+            }
+        }
+
+        missing
+    }
 }
 
 /// Skills loader for agent capabilities
@@ -326,6 +366,99 @@ impl SkillsLoader {
         result
     }
 
+    /// Score available skills against a user message, best first
+    ///
+    /// Each trigger found in the message, and the skill name itself, adds 1.
+    /// With an embedder, the cosine similarity between the message and the
+    /// skill's name, description and triggers is added as well. `always`
+    /// skills and skills scoring zero are left out.
+    pub fn rank_skills(
+        &self,
+        message: &str,
+        embedder: Option<&HashingEmbedder>,
+    ) -> Vec<SkillMatch> {
+        let text = message.to_lowercase();
+        let message_vector = embedder.map(|embedder| embedder.embed_text(message));
+        let mut matches = Vec::new();
+
+        for skill in self.list_skills(true) {
+            let metadata = self.get_skill_metadata(&skill.name);
+            if metadata.always || Self::runtime_metadata_of(&metadata).always {
+                continue;
+            }
+
+            let mut score = 0.0;
+            let mut matched = Vec::new();
+            let spoken_name = skill.name.replace(['-', '_'], " ");
+            for phrase in metadata.triggers.iter().chain([&skill.name, &spoken_name]) {
+                if !matched.contains(phrase) && contains_phrase(&text, phrase) {
+                    score += 1.0;
+                    matched.push(phrase.clone());
+                }
+            }
+            if let (Some(embedder), Some(message_vector)) = (embedder, &message_vector) {
+                let profile = format!(
+                    "{} {} {}",
+                    spoken_name,
+                    metadata.description.as_deref().unwrap_or_default(),
+                    metadata.triggers.join(" ")
+                );
+                score += cosine_similarity(message_vector, &embedder.embed_text(&profile)).max(0.0);
+            }
+
+            if score > 0.0 {
+                matches.push(SkillMatch {
+                    name: skill.name,
+                    score,
+                    matched,
+                });
+            }
+        }
+
+        matches.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        matches
+    }
+
+    /// One line per available skill, for prompts that only load some skills
+    pub fn build_skills_index(&self, exclude: &[String]) -> String {
+        self.list_skills(true)
+            .into_iter()
+            .filter(|skill| !exclude.contains(&skill.name))
+            .map(|skill| {
+                format!(
+                    "- {}: {}",
+                    skill.name,
+                    self.get_skill_description(&skill.name)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Tools declared by available skills, with the skill directory each
+    /// script runs in
+    pub fn get_skill_tools(&self) -> Vec<(String, PathBuf, SkillToolSpec)> {
+        let mut result = Vec::new();
+
+        for skill in self.list_skills(true) {
+            let Some(skill_dir) = skill.path.parent() else {
+                continue;
+            };
+            for spec in self.get_skill_runtime_metadata(&skill.name).tools {
+                match spec.validate() {
+                    Ok(()) => result.push((skill.name.clone(), skill_dir.to_path_buf(), spec)),
+                    Err(e) => tracing::warn!("Skill '{}': {}", skill.name, e),
+                }
+            }
+        }
+
+        result
+    }
+
     /// Get metadata from a skill's frontmatter
     ///
     /// # Arguments
@@ -336,18 +469,21 @@ impl SkillsLoader {
     ///
     /// Metadata or default if not found
     pub fn get_skill_metadata(&self, name: &str) -> SkillMetadata {
-        let content = match self.load_skill(name) {
-            Some(c) => c,
-            None => return SkillMetadata::default(),
-        };
+        match self.load_skill(name) {
+            Some(content) => Self::parse_skill_metadata(&content),
+            None => SkillMetadata::default(),
+        }
+    }
 
+    /// Parse the frontmatter of SKILL.md content that is not on a search path
+    pub fn parse_skill_metadata(content: &str) -> SkillMetadata {
         if !content.starts_with("---") {
             return SkillMetadata::default();
         }
 
         // Match YAML frontmatter
         let re = Regex::new(r"(?s)^---\n(.*?)\n---").unwrap();
-        if let Some(caps) = re.captures(&content) {
+        if let Some(caps) = re.captures(content) {
             let yaml_content = caps.get(1).unwrap().as_str();
             return Self::parse_yaml_frontmatter(yaml_content);
         }
@@ -356,12 +492,16 @@ impl SkillsLoader {
     }
 
     /// Get runtime metadata from a skill frontmatter JSON blob.
-    fn get_skill_runtime_metadata(&self, name: &str) -> SkillRuntimeMetadata {
-        let metadata = self.get_skill_metadata(name);
-        if let Some(ref meta_str) = metadata.metadata {
-            return Self::parse_runtime_metadata(meta_str);
+    pub fn get_skill_runtime_metadata(&self, name: &str) -> SkillRuntimeMetadata {
+        Self::runtime_metadata_of(&self.get_skill_metadata(name))
+    }
+
+    /// Runtime metadata carried by already parsed frontmatter
+    pub fn runtime_metadata_of(metadata: &SkillMetadata) -> SkillRuntimeMetadata {
+        match metadata.metadata {
+            Some(ref meta_str) => Self::parse_runtime_metadata(meta_str),
+            None => SkillRuntimeMetadata::default(),
         }
-        SkillRuntimeMetadata::default()
     }
 
     /// Get the description of a skill
@@ -391,21 +531,7 @@ impl SkillsLoader {
 
     /// Get a description of missing requirements
     fn get_missing_requirements(&self, meta: &SkillRuntimeMetadata) -> String {
-        let mut missing = Vec::new();
-
-        for bin in &meta.requires_bins {
-            if which::which(bin).is_err() {
-                missing.push(format!("CLI: {}", bin));
-            }
-        }
-
-        for env in &meta.requires_env {
-            if std::env::var(env).is_err() {
-                missing.push(format!("ENV: {}", env));
-            }
-        }
-
-        missing.join(", ")
+        meta.missing().join(", ")
     }
 
     /// Remove YAML frontmatter from markdown content
@@ -425,8 +551,18 @@ impl SkillsLoader {
     /// Parse YAML frontmatter (simple key-value parser)
     fn parse_yaml_frontmatter(yaml: &str) -> SkillMetadata {
         let mut metadata = SkillMetadata::default();
+        let mut in_trigger_list = false;
 
         for line in yaml.lines() {
+            // Block list items under `triggers:` / `keywords:`
+            if let Some(item) = line.trim_start().strip_prefix("- ") {
+                if in_trigger_list {
+                    metadata.triggers.extend(Self::parse_list(item));
+                }
+                continue;
+            }
+            in_trigger_list = false;
+
             if let Some((key, value)) = line.split_once(':') {
                 let key = key.trim();
                 let value = value.trim().trim_matches('"').trim_matches('\'');
@@ -437,6 +573,10 @@ impl SkillsLoader {
                     "homepage" => metadata.homepage = Some(value.to_string()),
                     "always" => metadata.always = value == "true",
                     "metadata" => metadata.metadata = Some(value.to_string()),
+                    "triggers" | "keywords" => {
+                        in_trigger_list = value.is_empty();
+                        metadata.triggers.extend(Self::parse_list(value));
+                    }
                     _ => {}
                 }
             }
@@ -445,6 +585,21 @@ impl SkillsLoader {
         metadata
     }
 
+    /// Parse an inline list: `[a, "b c"]` or `a, b c`
+    fn parse_list(value: &str) -> Vec<String> {
+        let value = value.trim();
+        let value = value
+            .strip_prefix('[')
+            .and_then(|v| v.strip_suffix(']'))
+            .unwrap_or(value);
+        value
+            .split(',')
+            .map(|item| item.trim().trim_matches('"').trim_matches('\'').trim())
+            .filter(|item| !item.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
     /// Parse runtime metadata JSON from frontmatter.
     /// Supports `nanobot` and `openclaw` keys for compatibility.
     fn parse_runtime_metadata(raw: &str) -> SkillRuntimeMetadata {
@@ -484,6 +639,21 @@ impl SkillsLoader {
             }
         }
 
+        if let Some(tools) = runtime.get("tools").and_then(|v| v.as_array()) {
+            meta.tools = tools
+                .iter()
+                .filter_map(
+                    |v| match serde_json::from_value::<SkillToolSpec>(v.clone()) {
+                        Ok(spec) => Some(spec),
+                        Err(e) => {
+                            tracing::warn!("Ignoring invalid skill tool declaration: {}", e);
+                            None
+                        }
+                    },
+                )
+                .collect();
+        }
+
         meta
     }
 
@@ -495,6 +665,94 @@ impl SkillsLoader {
     }
 }
 
+/// Whether `text` (lowercase) contains `phrase` as whole words
+fn contains_phrase(text: &str, phrase: &str) -> bool {
+    let phrase = phrase.trim().to_lowercase();
+    if phrase.is_empty() {
+        return false;
+    }
+    let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() && c.is_ascii());
+    let word_start = is_word(phrase.chars().next());
+    let word_end = is_word(phrase.chars().next_back());
+    text.match_indices(&phrase).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + phrase.len()..].chars().next();
+        let joined_before = word_start && is_word(before);
+        let joined_after = word_end && is_word(after);
+        !joined_before && !joined_after
+    })
+}
+
+/// Loads a skill's instructions on request
+pub struct LoadSkillTool {
+    loader: SkillsLoader,
+}
+
+impl LoadSkillTool {
+    pub fn new(loader: SkillsLoader) -> Self {
+        Self { loader }
+    }
+}
+
+#[async_trait]
+impl Tool for LoadSkillTool {
+    fn name(&self) -> &str {
+        "load_skill"
+    }
+
+    fn description(&self) -> &str {
+        "Load the full instructions of a skill by name. Use it when a listed skill fits the task but its instructions are not in the prompt yet."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "description": "Skill name as listed in the prompt"
+                }
+            },
+            "required": ["name"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> Result<String, ToolError> {
+        let name = args
+            .get("name")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| ToolError::InvalidParams("Missing 'name' parameter".to_string()))?;
+
+        let content = self.loader.load_skills_for_context(&[name.to_string()]);
+        if content.is_empty() {
+            let available: Vec<String> = self
+                .loader
+                .list_skills(false)
+                .into_iter()
+                .map(|skill| skill.name)
+                .collect();
+            return Ok(format!(
+                "Error: skill '{}' not found. Available skills: {}",
+                name,
+                available.join(", ")
+            ));
+        }
+
+        let missing = self.loader.get_skill_runtime_metadata(name).missing();
+        if missing.is_empty() {
+            Ok(content)
+        } else {
+            Ok(format!(
+                "{}\n\nNote: this skill needs {} before it can be used.",
+                content,
+                missing.join(", ")
+            ))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -591,6 +849,69 @@ mod tests {
         assert!(meta.requires_env.is_empty());
     }
 
+    #[test]
+    fn test_get_skill_tools() {
+        let temp = TempDir::new().unwrap();
+        let workspace = temp.path();
+        let skills_dir = workspace.join("skills");
+        create_test_skill(
+            &skills_dir,
+            "pdf",
+            "---\nname: pdf\nmetadata: '{\"nanobot\":{\"tools\":[{\"name\":\"pdf_extract\",\"entry\":\"scripts/extract.py\",\"interpreter\":\"python3\"},{\"name\":\"bad\",\"entry\":\"../x\"}]}}'\n---\n\n# PDF\n",
+        );
+
+        let loader = SkillsLoader::new(workspace, Some(temp.path().join("none")));
+        let tools = loader.get_skill_tools();
+        assert_eq!(tools.len(), 1);
+        let (skill, dir, spec) = &tools[0];
+        assert_eq!(skill, "pdf");
+        assert_eq!(dir, &skills_dir.join("pdf"));
+        assert_eq!(spec.name, "pdf_extract");
+        assert_eq!(spec.interpreter.as_deref(), Some("python3"));
+    }
+
+    #[test]
+    fn test_parse_triggers() {
+        let meta = SkillsLoader::parse_yaml_frontmatter(
+            "name: pdf\ntriggers: [pdf, \"extract text\"]\nkeywords:\n  - ocr\n  - scan\ndescription: PDFs",
+        );
+
+        assert_eq!(meta.triggers, vec!["pdf", "extract text", "ocr", "scan"]);
+        assert_eq!(meta.description.unwrap(), "PDFs");
+    }
+
+    #[test]
+    fn test_contains_phrase_matches_whole_words() {
+        assert!(contains_phrase("convert this pdf please", "PDF"));
+        assert!(contains_phrase("pdf.", "pdf"));
+        assert!(!contains_phrase("pdfs and more", "pdf"));
+        assert!(!contains_phrase("grain", "rain"));
+        assert!(contains_phrase("帮我查一下天气", "天气"));
+    }
+
+    #[tokio::test]
+    async fn test_load_skill_tool() {
+        let workspace = TempDir::new().unwrap();
+        let skills_dir = workspace.path().join("skills");
+        create_test_skill(
+            &skills_dir,
+            "weather",
+            "---\nname: weather\n---\n\n# Weather\n",
+        );
+        let tool = LoadSkillTool::new(SkillsLoader::new(
+            workspace.path(),
+            Some(workspace.path().join("none")),
+        ));
+
+        let loaded = tool.execute(json!({"name": "weather"})).await.unwrap();
+        assert_eq!(loaded, "### Skill: weather\n\n# Weather");
+
+        let missing = tool.execute(json!({"name": "nope"})).await.unwrap();
+        assert!(missing.starts_with("Error: skill 'nope' not found"));
+        assert!(missing.contains("weather"));
+        assert!(tool.execute(json!({})).await.is_err());
+    }
+
     #[test]
     fn test_escape_xml() {
         assert_eq!(SkillsLoader::escape_xml("<test>"), "&lt;test&gt;");
@@ -1,15 +1,16 @@
 //! Subagent management for background tasks
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::future::Future;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use anyhow::{anyhow, Result};
-use tokio::sync::{OwnedSemaphorePermit, RwLock, Semaphore};
+use serde::Serialize;
+use tokio::sync::{watch, OwnedSemaphorePermit, RwLock, Semaphore};
 use tokio::task::JoinHandle;
-use tokio::time::timeout;
+use tokio::time::{timeout, timeout_at};
 use tracing::{debug, error, info};
 use uuid::Uuid;
 
@@ -17,16 +18,19 @@ use agent_diva_core::bus::{InboundMessage, MessageBus};
 use agent_diva_core::utils::truncate;
 use agent_diva_providers::base::{LLMProvider, Message};
 use agent_diva_tooling::ToolRegistry;
+use agent_diva_tools::{ExecSandboxConfig, SpawnMode};
 
 use crate::agent_loop::context_retry::{prepare_budgeted_messages, should_retry_context_overflow};
 use crate::context_budget::{
     provider_error_indicates_context_overflow, CompactionMode, ContextBudgetPolicy,
 };
 use crate::loop_guard::{
-    LoopGuard, DEFAULT_REPEATED_FAILURE_THRESHOLD, DEFAULT_SUBAGENT_LOOP_TIMEOUT,
-    DEFAULT_SUBAGENT_MAX_ITERATIONS,
+    is_tool_error_result, LoopGuard, DEFAULT_REPEATED_FAILURE_THRESHOLD,
+    DEFAULT_SUBAGENT_LOOP_TIMEOUT, DEFAULT_SUBAGENT_MAX_ITERATIONS,
 };
 use crate::subagent_policy::SubagentPolicy;
+use crate::subagent_profile::SubagentProfile;
+use crate::tokenizer::tokenizer_for_model;
 use crate::tool_assembly::ToolAssembly;
 use crate::tool_config::builtin::BuiltInToolsConfig;
 use crate::tool_config::network::NetworkToolConfig;
@@ -35,6 +39,8 @@ use agent_diva_core::config::MCPServerConfig;
 pub const MAX_CONCURRENT_SUBAGENTS: usize = 8;
 pub const DEFAULT_SUBAGENT_TIMEOUT_SECS: u64 = 300;
 const DEFAULT_SUBAGENT_TIMEOUT: Duration = Duration::from_secs(DEFAULT_SUBAGENT_TIMEOUT_SECS);
+/// How long a finished handle-mode report is kept when nobody collects it.
+const SUBAGENT_REPORT_TTL: Duration = Duration::from_secs(30 * 60);
 
 #[derive(Debug, Clone)]
 pub struct SubagentSpawnRequest {
@@ -44,6 +50,93 @@ pub struct SubagentSpawnRequest {
     pub origin_chat_id: String,
     pub current_depth: usize,
     pub origin: String,
+    /// Named profile to run the task with
+    pub profile: Option<String>,
+    /// Announce the result on the bus, or keep it for `wait`
+    pub mode: SpawnMode,
+}
+
+/// Lifecycle state of a spawned subagent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubagentStatus {
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+/// Structured result of a subagent task, as handed back to the parent agent
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SubagentReport {
+    pub task_id: String,
+    pub label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    pub status: SubagentStatus,
+    pub summary: String,
+    pub artifacts: Vec<String>,
+    pub files_written: Vec<String>,
+    pub elapsed_secs: u64,
+}
+
+/// Final response of a subagent run and the files it wrote along the way
+#[derive(Debug, Clone, Default)]
+struct SubagentOutput {
+    content: String,
+    files_written: Vec<String>,
+}
+
+/// Bookkeeping for one spawned task; the report is set once it finishes
+struct SubagentHandle {
+    /// `channel:chat_id` of the session that spawned the task
+    session_key: String,
+    label: String,
+    profile: Option<String>,
+    mode: SpawnMode,
+    started: Instant,
+    report: Arc<watch::Sender<Option<SubagentReport>>>,
+}
+
+impl SubagentHandle {
+    fn current_report(&self, task_id: &str) -> SubagentReport {
+        self.report
+            .borrow()
+            .clone()
+            .unwrap_or_else(|| SubagentReport {
+                task_id: task_id.to_string(),
+                label: self.label.clone(),
+                profile: self.profile.clone(),
+                status: SubagentStatus::Running,
+                summary: String::new(),
+                artifacts: Vec::new(),
+                files_written: Vec::new(),
+                elapsed_secs: self.started.elapsed().as_secs(),
+            })
+    }
+
+    /// Whether the task finished more than `ttl` ago.
+    fn expired(&self, ttl: Duration) -> bool {
+        self.report.borrow().as_ref().is_some_and(|report| {
+            self.started.elapsed() > Duration::from_secs(report.elapsed_secs) + ttl
+        })
+    }
+}
+
+/// Iteration and time limits of one subagent run
+#[derive(Debug, Clone, Copy)]
+struct SubagentLimits {
+    max_iterations: usize,
+    loop_timeout: Duration,
+}
+
+impl Default for SubagentLimits {
+    fn default() -> Self {
+        Self {
+            max_iterations: DEFAULT_SUBAGENT_MAX_ITERATIONS,
+            loop_timeout: DEFAULT_SUBAGENT_LOOP_TIMEOUT,
+        }
+    }
 }
 
 /// Subagent manager for background task execution.
@@ -59,12 +152,16 @@ pub struct SubagentManager {
     builtin_tools: BuiltInToolsConfig,
     network_config: Arc<RwLock<NetworkToolConfig>>,
     exec_timeout: u64,
+    exec_sandbox: ExecSandboxConfig,
     restrict_to_workspace: bool,
     mcp_servers: Arc<RwLock<HashMap<String, MCPServerConfig>>>,
     running_tasks: Arc<tokio::sync::Mutex<HashMap<String, JoinHandle<()>>>>,
+    handles: Arc<tokio::sync::Mutex<HashMap<String, SubagentHandle>>>,
+    report_ttl: Duration,
     subagent_policy: SubagentPolicy,
     concurrency_limit: Arc<Semaphore>,
     context_budget: ContextBudgetPolicy,
+    profiles: BTreeMap<String, SubagentProfile>,
 }
 
 impl SubagentManager {
@@ -96,15 +193,42 @@ impl SubagentManager {
             builtin_tools,
             network_config: Arc::new(RwLock::new(network_config)),
             exec_timeout,
+            exec_sandbox: ExecSandboxConfig::default(),
             restrict_to_workspace,
             mcp_servers: Arc::new(RwLock::new(mcp_servers)),
             running_tasks: Arc::new(tokio::sync::Mutex::new(HashMap::new())),
+            handles: Arc::new(tokio::sync::Mutex::new(HashMap::new())),
+            report_ttl: SUBAGENT_REPORT_TTL,
             concurrency_limit: Arc::new(Semaphore::new(effective_max_concurrent)),
             subagent_policy,
             context_budget,
+            profiles: BTreeMap::new(),
         }
     }
 
+    /// Run subagent `exec` calls through the same sandbox as the parent agent.
+    pub fn with_exec_sandbox(mut self, sandbox: ExecSandboxConfig) -> Self {
+        self.exec_sandbox = sandbox;
+        self
+    }
+
+    /// Named profiles the `spawn` tool can select.
+    pub fn with_profiles(mut self, profiles: Vec<SubagentProfile>) -> Self {
+        self.profiles = profiles
+            .into_iter()
+            .map(|profile| (profile.name.clone(), profile))
+            .collect();
+        self
+    }
+
+    /// `name: description` of every profile, in name order.
+    pub fn profile_summaries(&self) -> Vec<(String, String)> {
+        self.profiles
+            .values()
+            .map(|profile| (profile.name.clone(), profile.summary()))
+            .collect()
+    }
+
     pub async fn update_network_config(&self, network_config: NetworkToolConfig) {
         let mut guard = self.network_config.write().await;
         *guard = network_config;
@@ -122,11 +246,21 @@ impl SubagentManager {
     /// * `label` - Optional human-readable label for the task
     /// * `origin_channel` - The channel to announce results to
     /// * `origin_chat_id` - The chat ID to announce results to
+    /// * `mode` - Announce the result, or keep it for [`Self::wait`]
     ///
     /// # Returns
     /// Status message indicating the subagent was started
     pub async fn spawn(&self, request: SubagentSpawnRequest) -> Result<String> {
         self.ensure_depth_allowed(request.current_depth)?;
+        let profile = match request.profile.as_deref().map(str::trim) {
+            Some(name) if !name.is_empty() => Some(
+                self.profiles
+                    .get(name)
+                    .cloned()
+                    .ok_or_else(|| self.unknown_profile_error(name))?,
+            ),
+            _ => None,
+        };
         let permit = self
             .concurrency_limit
             .clone()
@@ -145,18 +279,29 @@ impl SubagentManager {
             }
         });
 
-        let provider = Arc::clone(&self.provider);
+        let provider = profile
+            .as_ref()
+            .and_then(|profile| profile.provider.clone())
+            .unwrap_or_else(|| Arc::clone(&self.provider));
         let workspace = self.workspace.clone();
         let bus = self.bus.clone();
-        let model = self.model.clone();
-        let builtin_tools = self.subagent_policy.builtin_tools(&self.builtin_tools);
+        let model = profile
+            .as_ref()
+            .and_then(|profile| profile.model.clone())
+            .unwrap_or_else(|| self.model.clone());
+        let subagent_policy = profile.as_ref().map_or_else(
+            || self.subagent_policy.clone(),
+            |profile| profile.policy(&self.subagent_policy),
+        );
+        let builtin_tools = subagent_policy.builtin_tools(&self.builtin_tools);
         let parent_network_config = self.network_config.read().await.clone();
-        let network_config = self.subagent_policy.network_config(&parent_network_config);
+        let network_config = subagent_policy.network_config(&parent_network_config);
         let exec_timeout = self.exec_timeout;
+        let exec_sandbox = self.exec_sandbox.clone();
         let restrict_to_workspace = self.restrict_to_workspace;
         let parent_mcp_servers = self.mcp_servers.read().await.clone();
-        let mcp_servers = self.subagent_policy.mcp_servers(&parent_mcp_servers);
-        let subagent_policy = self.subagent_policy.clone();
+        let mcp_servers = subagent_policy.mcp_servers(&parent_mcp_servers);
+        let profile_name = profile.as_ref().map(|profile| profile.name.clone());
         let context_budget = self.context_budget.clone();
         let next_depth = request.current_depth + 1;
         let origin_channel = request.origin_channel.clone();
@@ -164,9 +309,28 @@ impl SubagentManager {
         let task = request.task.clone();
         let origin = request.origin.clone();
 
+        let mode = request.mode;
+        let (report_tx, _) = watch::channel(None);
+        let report_tx = Arc::new(report_tx);
+        let mut handles = self.handles.lock().await;
+        self.evict_expired(&mut handles);
+        handles.insert(
+            task_id.clone(),
+            SubagentHandle {
+                session_key: format!("{}:{}", request.origin_channel, request.origin_chat_id),
+                label: display_label.clone(),
+                profile: profile_name.clone(),
+                mode,
+                started: Instant::now(),
+                report: Arc::clone(&report_tx),
+            },
+        );
+        drop(handles);
+
         let task_id_clone = task_id.clone();
         let display_label_clone = display_label.clone();
         let running_tasks = Arc::clone(&self.running_tasks);
+        let handles = Arc::clone(&self.handles);
 
         // Create background task
         let bg_task = tokio::spawn(async move {
@@ -183,19 +347,27 @@ impl SubagentManager {
                 builtin_tools,
                 network_config,
                 exec_timeout,
+                exec_sandbox,
                 restrict_to_workspace,
                 mcp_servers,
                 subagent_policy,
+                profile,
                 context_budget,
                 next_depth,
                 origin,
+                mode,
+                report_tx,
                 permit,
             )
             .await;
 
-            // Cleanup when done
+            // Cleanup when done; handle-mode results stay until collected
             let mut tasks = running_tasks.lock().await;
             tasks.remove(&task_id_clone);
+            drop(tasks);
+            if mode == SpawnMode::Background {
+                handles.lock().await.remove(&task_id_clone);
+            }
         });
 
         // Store the task handle
@@ -204,13 +376,156 @@ impl SubagentManager {
         drop(tasks);
 
         info!(
-            "Spawned subagent [{}]: {} (depth={}, origin={})",
-            task_id, display_label, next_depth, request.origin
+            "Spawned subagent [{}]: {} (depth={}, origin={}, profile={})",
+            task_id,
+            display_label,
+            next_depth,
+            request.origin,
+            profile_name.as_deref().unwrap_or("default")
         );
-        Ok(format!(
-            "Subagent [{}] started (id: {}). I'll notify you when it completes.",
-            display_label, task_id
-        ))
+        Ok(match mode {
+            SpawnMode::Background => format!(
+                "Subagent [{}] started (id: {}). I'll notify you when it completes.",
+                display_label, task_id
+            ),
+            SpawnMode::Handle => format!(
+                "Subagent [{}] started with task id {}. Call subagent_wait with this id to collect its result.",
+                display_label, task_id
+            ),
+        })
+    }
+
+    /// Wait for handle-mode subagents and collect their reports.
+    ///
+    /// Only tasks spawned from `session_key` are visible. An empty `task_ids`
+    /// waits for every handle-mode task of that session. Tasks still running
+    /// when `wait_timeout` elapses are reported as `running`; finished reports
+    /// are handed out once and then forgotten.
+    pub async fn wait(
+        &self,
+        session_key: &str,
+        task_ids: &[String],
+        wait_timeout: Duration,
+    ) -> Result<Vec<SubagentReport>> {
+        let mut targets = {
+            let mut handles = self.handles.lock().await;
+            self.evict_expired(&mut handles);
+            self.resolve_handles(&handles, session_key, task_ids, true)?
+                .into_iter()
+                .map(|task_id| {
+                    let receiver = handles[&task_id].report.subscribe();
+                    (task_id, receiver)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let deadline = tokio::time::Instant::now() + wait_timeout;
+        for (_, receiver) in &mut targets {
+            let _ = timeout_at(deadline, receiver.wait_for(Option::is_some)).await;
+        }
+
+        let mut handles = self.handles.lock().await;
+        let mut reports = Vec::with_capacity(targets.len());
+        for (task_id, _) in targets {
+            let Some(handle) = handles.get(&task_id) else {
+                continue;
+            };
+            let report = handle.current_report(&task_id);
+            if report.status != SubagentStatus::Running {
+                handles.remove(&task_id);
+            }
+            reports.push(report);
+        }
+        Ok(reports)
+    }
+
+    /// Current state of the given subagents of `session_key`, or of every one
+    /// it tracks when empty.
+    pub async fn status(
+        &self,
+        session_key: &str,
+        task_ids: &[String],
+    ) -> Result<Vec<SubagentReport>> {
+        let mut handles = self.handles.lock().await;
+        self.evict_expired(&mut handles);
+        Ok(self
+            .resolve_handles(&handles, session_key, task_ids, false)?
+            .into_iter()
+            .map(|task_id| handles[&task_id].current_report(&task_id))
+            .collect())
+    }
+
+    /// Abort a running subagent of `session_key`; finished tasks keep their
+    /// final report.
+    pub async fn cancel(&self, session_key: &str, task_id: &str) -> Result<SubagentReport> {
+        let mut handles = self.handles.lock().await;
+        self.evict_expired(&mut handles);
+        let handle = handles
+            .get(task_id)
+            .filter(|handle| handle.session_key == session_key)
+            .ok_or_else(|| anyhow!("Unknown subagent task '{}'.", task_id))?;
+        if handle.report.borrow().is_none() {
+            if let Some(task) = self.running_tasks.lock().await.remove(task_id) {
+                task.abort();
+            }
+            let mut report = handle.current_report(task_id);
+            report.status = SubagentStatus::Cancelled;
+            report.summary = "Cancelled by the parent agent.".to_string();
+            handle.report.send_replace(Some(report));
+            info!("Subagent [{}] cancelled", task_id);
+        }
+        let report = handle.current_report(task_id);
+        if handle.mode == SpawnMode::Background {
+            handles.remove(task_id);
+        }
+        Ok(report)
+    }
+
+    /// Drop finished reports nobody collected within the report TTL.
+    fn evict_expired(&self, handles: &mut HashMap<String, SubagentHandle>) {
+        handles.retain(|task_id, handle| {
+            let expired = handle.expired(self.report_ttl);
+            if expired {
+                debug!("Dropping uncollected report of subagent [{}]", task_id);
+            }
+            !expired
+        });
+    }
+
+    /// Task ids of `session_key`; tasks of other sessions count as unknown.
+    fn resolve_handles(
+        &self,
+        handles: &HashMap<String, SubagentHandle>,
+        session_key: &str,
+        task_ids: &[String],
+        handle_mode_only: bool,
+    ) -> Result<Vec<String>> {
+        if task_ids.is_empty() {
+            let mut all: Vec<String> = handles
+                .iter()
+                .filter(|(_, handle)| handle.session_key == session_key)
+                .filter(|(_, handle)| !handle_mode_only || handle.mode == SpawnMode::Handle)
+                .map(|(task_id, _)| task_id.clone())
+                .collect();
+            all.sort();
+            return Ok(all);
+        }
+        task_ids
+            .iter()
+            .map(|task_id| {
+                if handles
+                    .get(task_id)
+                    .is_some_and(|handle| handle.session_key == session_key)
+                {
+                    Ok(task_id.clone())
+                } else {
+                    Err(anyhow!(
+                        "Unknown subagent task '{}'. It may have been collected already.",
+                        task_id
+                    ))
+                }
+            })
+            .collect()
     }
 
     /// Execute the subagent task and announce the result
@@ -228,18 +543,24 @@ impl SubagentManager {
         builtin_tools: BuiltInToolsConfig,
         network_config: NetworkToolConfig,
         exec_timeout: u64,
+        exec_sandbox: ExecSandboxConfig,
         restrict_to_workspace: bool,
         mcp_servers: HashMap<String, MCPServerConfig>,
         subagent_policy: SubagentPolicy,
+        profile: Option<SubagentProfile>,
         context_budget: ContextBudgetPolicy,
         depth: usize,
         origin: String,
+        mode: SpawnMode,
+        report_tx: Arc<watch::Sender<Option<SubagentReport>>>,
         _permit: OwnedSemaphorePermit,
     ) {
         info!(
             "Subagent [{}] starting task: {} (depth={}, origin={})",
             task_id, label, depth, origin
         );
+        let started = Instant::now();
+        let profile_name = profile.as_ref().map(|profile| profile.name.clone());
 
         let result = Self::with_subagent_timeout(
             Self::execute_subagent_task(
@@ -251,27 +572,52 @@ impl SubagentManager {
                 &builtin_tools,
                 &network_config,
                 exec_timeout,
+                &exec_sandbox,
                 restrict_to_workspace,
                 &mcp_servers,
                 &subagent_policy,
+                profile.as_ref(),
                 &context_budget,
             ),
-            DEFAULT_SUBAGENT_TIMEOUT,
+            profile
+                .as_ref()
+                .and_then(|profile| profile.timeout)
+                .unwrap_or(DEFAULT_SUBAGENT_TIMEOUT),
         )
         .await;
 
-        let (final_result, status) = match result {
-            Ok(content) => {
+        let (final_result, status, files_written) = match result {
+            Ok(output) => {
                 info!("Subagent [{}] completed successfully", task_id);
-                (content, "ok")
+                (output.content, "ok", output.files_written)
             }
             Err(e) => {
                 let error_msg = format!("Error: {}", e);
                 error!("Subagent [{}] failed: {}", task_id, e);
-                (error_msg, "error")
+                (error_msg, "error", Vec::new())
             }
         };
 
+        let (summary, artifacts) = split_artifacts(&final_result);
+        report_tx.send_replace(Some(SubagentReport {
+            task_id: task_id.clone(),
+            label: label.clone(),
+            profile: profile_name,
+            status: if status == "ok" {
+                SubagentStatus::Completed
+            } else {
+                SubagentStatus::Failed
+            },
+            summary,
+            artifacts,
+            files_written,
+            elapsed_secs: started.elapsed().as_secs(),
+        }));
+        if mode == SpawnMode::Handle {
+            debug!("Subagent [{}] result kept for collection", task_id);
+            return;
+        }
+
         Self::announce_result(
             &task_id,
             &label,
@@ -296,19 +642,39 @@ impl SubagentManager {
         builtin_tools: &BuiltInToolsConfig,
         network_config: &NetworkToolConfig,
         exec_timeout: u64,
+        exec_sandbox: &ExecSandboxConfig,
         restrict_to_workspace: bool,
         mcp_servers: &HashMap<String, MCPServerConfig>,
         subagent_policy: &SubagentPolicy,
+        profile: Option<&SubagentProfile>,
         context_budget: &ContextBudgetPolicy,
-    ) -> Result<String> {
-        let tools: ToolRegistry = ToolAssembly::new(workspace.to_path_buf())
+    ) -> Result<SubagentOutput> {
+        let mut tools: ToolRegistry = ToolAssembly::new(workspace.to_path_buf())
             .builtin(builtin_tools.clone())
             .with_network_config(network_config.clone())
             .with_exec_timeout(exec_timeout)
+            .with_exec_sandbox(exec_sandbox.clone())
             .restrict_to_workspace(restrict_to_workspace)
             .mcp_servers(mcp_servers.clone())
             .build_subagent_registry(subagent_policy);
-        let system_prompt = Self::build_subagent_prompt(task, workspace, subagent_policy);
+        let mut system_prompt = Self::build_subagent_prompt(task, workspace, subagent_policy);
+        let mut limits = SubagentLimits::default();
+        if let Some(profile) = profile {
+            for name in tools.tool_names() {
+                if !profile.allows_tool(&name) {
+                    tools.unregister(&name);
+                }
+            }
+            if let Some(prompt) = &profile.system_prompt {
+                system_prompt.push_str(&format!("\n\n## Role: {}\n{}", profile.name, prompt));
+            }
+            if let Some(max_iterations) = profile.max_iterations {
+                limits.max_iterations = max_iterations;
+            }
+            if let Some(timeout) = profile.timeout {
+                limits.loop_timeout = timeout;
+            }
+        }
         Self::execute_subagent_task_with_registry(
             task_id,
             task,
@@ -317,10 +683,12 @@ impl SubagentManager {
             system_prompt,
             &tools,
             context_budget,
+            limits,
         )
         .await
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn execute_subagent_task_with_registry(
         task_id: &str,
         task: &str,
@@ -329,16 +697,18 @@ impl SubagentManager {
         system_prompt: String,
         tools: &ToolRegistry,
         context_budget: &ContextBudgetPolicy,
-    ) -> Result<String> {
+        limits: SubagentLimits,
+    ) -> Result<SubagentOutput> {
         let mut messages = vec![
             Message::system(system_prompt),
             Message::user(task.to_string()),
         ];
 
+        let mut files_written: Vec<String> = Vec::new();
         let mut iteration = 0;
         let mut loop_guard = LoopGuard::new(
-            DEFAULT_SUBAGENT_MAX_ITERATIONS,
-            DEFAULT_SUBAGENT_LOOP_TIMEOUT,
+            limits.max_iterations,
+            limits.loop_timeout,
             DEFAULT_REPEATED_FAILURE_THRESHOLD,
         );
         let final_result = loop {
@@ -359,6 +729,7 @@ impl SubagentManager {
                     &tool_defs,
                     context_budget,
                     compaction_mode,
+                    tokenizer_for_model(model),
                 );
                 let response = provider
                     .chat(
@@ -420,6 +791,16 @@ impl SubagentManager {
                     ) {
                         return Err(anyhow::anyhow!(reason.user_message()));
                     }
+                    if matches!(tool_call.name.as_str(), "write_file" | "edit_file")
+                        && !is_tool_error_result(&result)
+                    {
+                        if let Some(path) = tool_call.arguments.get("path").and_then(|v| v.as_str())
+                        {
+                            if !files_written.iter().any(|seen| seen == path) {
+                                files_written.push(path.to_string());
+                            }
+                        }
+                    }
                     messages.push(Message::tool(result, tool_call.id.clone()));
                 }
             } else {
@@ -427,8 +808,12 @@ impl SubagentManager {
             }
         };
 
-        Ok(final_result
-            .unwrap_or_else(|| "Task completed but no final response was generated.".to_string()))
+        Ok(SubagentOutput {
+            content: final_result.unwrap_or_else(|| {
+                "Task completed but no final response was generated.".to_string()
+            }),
+            files_written,
+        })
     }
 
     async fn with_subagent_timeout<T>(
@@ -524,6 +909,7 @@ You are a subagent spawned by the main agent to complete a specific task.
 2. Your final response will be reported back to the main agent
 3. Do not initiate conversations or take on side tasks
 4. Be concise but informative in your findings
+5. If you produced artifacts (files, URLs, commands), end with an `Artifacts:` line followed by one `- item` per artifact
 
 ## What You Can Do
 {}
@@ -595,6 +981,21 @@ When you have completed the task, provide a clear summary of your findings or ac
         )
     }
 
+    fn unknown_profile_error(&self, name: &str) -> anyhow::Error {
+        if self.profiles.is_empty() {
+            anyhow!(
+                "Unknown subagent profile '{}': no profiles are configured.",
+                name
+            )
+        } else {
+            anyhow!(
+                "Unknown subagent profile '{}'. Available profiles: {}.",
+                name,
+                self.profiles.keys().cloned().collect::<Vec<_>>().join(", ")
+            )
+        }
+    }
+
     fn depth_limit_error(&self, attempted_depth: usize) -> anyhow::Error {
         anyhow!(
             "Subagent spawn rejected: nesting depth {} exceeds the configured maximum of {}.",
@@ -608,24 +1009,53 @@ When you have completed the task, provide a clear summary of your findings or ac
     }
 }
 
+/// Split a trailing `Artifacts:` bullet list off a subagent's final response.
+fn split_artifacts(content: &str) -> (String, Vec<String>) {
+    let lines: Vec<&str> = content.lines().collect();
+    let Some(heading) = lines.iter().rposition(|line| {
+        line.trim()
+            .trim_start_matches('#')
+            .trim_matches('*')
+            .trim()
+            .eq_ignore_ascii_case("artifacts:")
+    }) else {
+        return (content.trim().to_string(), Vec::new());
+    };
+
+    let mut artifacts = Vec::new();
+    for line in &lines[heading + 1..] {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        match line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) {
+            Some(item) if !item.trim().is_empty() => artifacts.push(item.trim().to_string()),
+            _ => return (content.trim().to_string(), Vec::new()),
+        }
+    }
+    (lines[..heading].join("\n").trim().to_string(), artifacts)
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
-        SubagentManager, SubagentSpawnRequest, DEFAULT_SUBAGENT_TIMEOUT_SECS,
-        MAX_CONCURRENT_SUBAGENTS,
+        split_artifacts, SubagentLimits, SubagentManager, SubagentSpawnRequest, SubagentStatus,
+        DEFAULT_SUBAGENT_TIMEOUT_SECS, MAX_CONCURRENT_SUBAGENTS,
     };
     use crate::subagent_policy::SubagentPolicy;
+    use crate::subagent_profile::SubagentProfile;
     use crate::tool_config::builtin::BuiltInToolsConfig;
     use crate::tool_config::network::{
         NetworkToolConfig, WebFetchRuntimeConfig, WebRuntimeConfig, WebSearchRuntimeConfig,
     };
     use crate::ContextBudgetPolicy;
     use agent_diva_core::bus::MessageBus;
-    use agent_diva_core::config::MCPServerConfig;
+    use agent_diva_core::config::{MCPServerConfig, SubagentProfileConfig};
     use agent_diva_providers::{
         LLMResponse, Message, ProviderError, ProviderResult, ToolCallRequest,
     };
     use agent_diva_tooling::{Tool, ToolRegistry};
+    use agent_diva_tools::SpawnMode;
     use async_trait::async_trait;
     use serde_json::json;
     use std::collections::HashMap;
@@ -819,6 +1249,7 @@ mod tests {
             "system".to_string(),
             &registry,
             &ContextBudgetPolicy::default(),
+            SubagentLimits::default(),
         )
         .await
         .expect_err("subagent should stop on repeated tool failures");
@@ -888,6 +1319,8 @@ mod tests {
                 origin_chat_id: "direct".to_string(),
                 current_depth: 0,
                 origin: "test".to_string(),
+                profile: None,
+                mode: SpawnMode::Background,
             })
             .await
             .expect("first spawn should succeed");
@@ -901,6 +1334,8 @@ mod tests {
                 origin_chat_id: "direct".to_string(),
                 current_depth: 0,
                 origin: "test".to_string(),
+                profile: None,
+                mode: SpawnMode::Background,
             })
             .await
             .expect_err("second spawn should be rejected");
@@ -940,12 +1375,398 @@ mod tests {
                 origin_chat_id: "direct".to_string(),
                 current_depth: 1,
                 origin: "test".to_string(),
+                profile: None,
+                mode: SpawnMode::Background,
             })
             .await
             .expect_err("depth violation should be rejected");
         assert!(err.to_string().contains("nesting depth"));
     }
 
+    /// Model, system prompt and tool names of each request
+    type SeenRequests = Arc<Mutex<Vec<(String, String, Vec<String>)>>>;
+
+    struct RecordingProvider {
+        seen: SeenRequests,
+    }
+
+    #[async_trait]
+    impl agent_diva_providers::LLMProvider for RecordingProvider {
+        async fn chat(
+            &self,
+            messages: Vec<Message>,
+            tools: Option<Vec<serde_json::Value>>,
+            model: Option<String>,
+            _max_tokens: i32,
+            _temperature: f64,
+        ) -> ProviderResult<LLMResponse> {
+            let tool_names = tools
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|tool| tool["function"]["name"].as_str().map(str::to_string))
+                .collect();
+            self.seen.lock().unwrap().push((
+                model.unwrap_or_default(),
+                messages[0].content.to_text_lossy(),
+                tool_names,
+            ));
+            Ok(LLMResponse {
+                content: Some("done".to_string()),
+                tool_calls: Vec::new(),
+                finish_reason: "stop".to_string(),
+                usage: HashMap::new(),
+                reasoning_content: None,
+            })
+        }
+
+        async fn chat_stream(
+            &self,
+            _messages: Vec<Message>,
+            _tools: Option<Vec<serde_json::Value>>,
+            _model: Option<String>,
+            _max_tokens: i32,
+            _temperature: f64,
+        ) -> ProviderResult<agent_diva_providers::ProviderEventStream> {
+            Err(ProviderError::api_message(
+                "chat_stream should not be used".to_string(),
+            ))
+        }
+
+        fn get_default_model(&self) -> String {
+            "test-model".to_string()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subagent_profile_sets_model_prompt_and_tools() {
+        let parent_seen = Arc::new(Mutex::new(Vec::new()));
+        let profile_seen = Arc::new(Mutex::new(Vec::new()));
+        let profile = SubagentProfile::from_config(
+            "reader",
+            &SubagentProfileConfig {
+                description: "Reads files".to_string(),
+                system_prompt: Some("Only read, never write.".to_string()),
+                model: Some("cheap-model".to_string()),
+                tools: vec!["read_file".to_string(), "list_dir".to_string()],
+                ..SubagentProfileConfig::default()
+            },
+        )
+        .with_provider(Arc::new(RecordingProvider {
+            seen: profile_seen.clone(),
+        }));
+        let bus = MessageBus::new();
+        let manager = SubagentManager::new(
+            Arc::new(RecordingProvider {
+                seen: parent_seen.clone(),
+            }),
+            tempfile::tempdir().unwrap().path().to_path_buf(),
+            bus.clone(),
+            Some("test-model".to_string()),
+            BuiltInToolsConfig::default(),
+            NetworkToolConfig::default(),
+            Some(5),
+            false,
+            HashMap::new(),
+            SubagentPolicy::default(),
+            ContextBudgetPolicy::default(),
+        )
+        .with_profiles(vec![profile]);
+        assert_eq!(
+            manager.profile_summaries(),
+            vec![("reader".to_string(), "reader: Reads files".to_string())]
+        );
+
+        let request = |profile: &str| SubagentSpawnRequest {
+            task: "list files".to_string(),
+            label: None,
+            origin_channel: "cli".to_string(),
+            origin_chat_id: "direct".to_string(),
+            current_depth: 0,
+            origin: "test".to_string(),
+            profile: Some(profile.to_string()),
+            mode: SpawnMode::Background,
+        };
+        let err = manager
+            .spawn(request("writer"))
+            .await
+            .expect_err("unknown profile should be rejected");
+        assert!(err.to_string().contains("Available profiles: reader"));
+
+        manager.spawn(request("reader")).await.unwrap();
+        let mut inbound = bus.take_inbound_receiver().await.unwrap();
+        let announced = inbound.recv().await.unwrap();
+        assert!(announced.content.contains("done"));
+
+        assert!(parent_seen.lock().unwrap().is_empty());
+        let seen = profile_seen.lock().unwrap();
+        let (model, prompt, mut tools) = seen[0].clone();
+        tools.sort();
+        assert_eq!(model, "cheap-model");
+        assert!(prompt.contains("## Role: reader\nOnly read, never write."));
+        assert_eq!(tools, vec!["list_dir", "read_file"]);
+    }
+
+    /// Writes a file on the first call, then finishes with an artifacts list
+    struct WritingProvider {
+        calls: Mutex<usize>,
+    }
+
+    #[async_trait]
+    impl agent_diva_providers::LLMProvider for WritingProvider {
+        async fn chat(
+            &self,
+            _messages: Vec<Message>,
+            _tools: Option<Vec<serde_json::Value>>,
+            _model: Option<String>,
+            _max_tokens: i32,
+            _temperature: f64,
+        ) -> ProviderResult<LLMResponse> {
+            let mut calls = self.calls.lock().unwrap();
+            *calls += 1;
+            let (content, tool_calls) = if *calls == 1 {
+                (
+                    None,
+                    vec![ToolCallRequest {
+                        id: "call-1".to_string(),
+                        call_type: "function".to_string(),
+                        name: "write_file".to_string(),
+                        arguments: HashMap::from([
+                            ("path".to_string(), json!("notes.txt")),
+                            ("content".to_string(), json!("hello")),
+                        ]),
+                    }],
+                )
+            } else {
+                (
+                    Some("Wrote the notes.\n\nArtifacts:\n- notes.txt".to_string()),
+                    Vec::new(),
+                )
+            };
+            Ok(LLMResponse {
+                content,
+                tool_calls,
+                finish_reason: "stop".to_string(),
+                usage: HashMap::new(),
+                reasoning_content: None,
+            })
+        }
+
+        async fn chat_stream(
+            &self,
+            _messages: Vec<Message>,
+            _tools: Option<Vec<serde_json::Value>>,
+            _model: Option<String>,
+            _max_tokens: i32,
+            _temperature: f64,
+        ) -> ProviderResult<agent_diva_providers::ProviderEventStream> {
+            Err(ProviderError::api_message(
+                "chat_stream should not be used".to_string(),
+            ))
+        }
+
+        fn get_default_model(&self) -> String {
+            "test-model".to_string()
+        }
+    }
+
+    fn handle_request(task: &str) -> SubagentSpawnRequest {
+        SubagentSpawnRequest {
+            task: task.to_string(),
+            label: Some(task.to_string()),
+            origin_channel: "cli".to_string(),
+            origin_chat_id: "direct".to_string(),
+            current_depth: 0,
+            origin: "test".to_string(),
+            profile: None,
+            mode: SpawnMode::Handle,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subagent_handle_mode_returns_structured_report() {
+        let workspace = tempfile::tempdir().unwrap();
+        let bus = MessageBus::new();
+        let manager = SubagentManager::new(
+            Arc::new(WritingProvider {
+                calls: Mutex::new(0),
+            }),
+            workspace.path().to_path_buf(),
+            bus.clone(),
+            Some("test-model".to_string()),
+            BuiltInToolsConfig::default(),
+            NetworkToolConfig::default(),
+            Some(5),
+            false,
+            HashMap::new(),
+            SubagentPolicy::default(),
+            ContextBudgetPolicy::default(),
+        );
+
+        let started = manager.spawn(handle_request("notes")).await.unwrap();
+        assert!(started.contains("subagent_wait"));
+        let reports = manager
+            .wait("cli:direct", &[], Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(reports.len(), 1);
+        let report = &reports[0];
+        assert_eq!(report.status, SubagentStatus::Completed);
+        assert_eq!(report.summary, "Wrote the notes.");
+        assert_eq!(report.artifacts, vec!["notes.txt"]);
+        assert_eq!(report.files_written, vec!["notes.txt"]);
+        assert!(workspace.path().join("notes.txt").exists());
+
+        // Collected once, never announced on the bus
+        assert!(manager.status("cli:direct", &[]).await.unwrap().is_empty());
+        let mut inbound = bus.take_inbound_receiver().await.unwrap();
+        assert!(inbound.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_subagent_cancel_stops_running_handle() {
+        let notify = Arc::new(Notify::new());
+        let manager = SubagentManager::new(
+            Arc::new(BlockingProvider {
+                notify: notify.clone(),
+            }),
+            tempfile::tempdir().unwrap().path().to_path_buf(),
+            MessageBus::new(),
+            Some("test-model".to_string()),
+            BuiltInToolsConfig::default(),
+            NetworkToolConfig::default(),
+            Some(5),
+            false,
+            HashMap::new(),
+            SubagentPolicy {
+                max_concurrent: 1,
+                ..SubagentPolicy::default()
+            },
+            ContextBudgetPolicy::default(),
+        );
+
+        manager.spawn(handle_request("hold")).await.unwrap();
+        let running = manager.status("cli:direct", &[]).await.unwrap();
+        assert_eq!(running[0].status, SubagentStatus::Running);
+        let task_id = running[0].task_id.clone();
+
+        let timed_out = manager
+            .wait(
+                "cli:direct",
+                std::slice::from_ref(&task_id),
+                Duration::from_millis(10),
+            )
+            .await
+            .unwrap();
+        assert_eq!(timed_out[0].status, SubagentStatus::Running);
+
+        let cancelled = manager.cancel("cli:direct", &task_id).await.unwrap();
+        assert_eq!(cancelled.status, SubagentStatus::Cancelled);
+        assert_eq!(manager.get_running_count().await, 0);
+
+        // The concurrency slot is released once the aborted task is dropped
+        tokio::task::yield_now().await;
+        manager.spawn(handle_request("next")).await.unwrap();
+
+        let collected = manager
+            .wait(
+                "cli:direct",
+                std::slice::from_ref(&task_id),
+                Duration::from_secs(1),
+            )
+            .await
+            .unwrap();
+        assert_eq!(collected[0].status, SubagentStatus::Cancelled);
+        let err = manager.status("cli:direct", &[task_id]).await.unwrap_err();
+        assert!(err.to_string().contains("Unknown subagent task"));
+        notify.notify_waiters();
+    }
+
+    #[tokio::test]
+    async fn test_subagent_handles_are_scoped_to_their_session() {
+        let notify = Arc::new(Notify::new());
+        let manager = SubagentManager::new(
+            Arc::new(BlockingProvider {
+                notify: notify.clone(),
+            }),
+            tempfile::tempdir().unwrap().path().to_path_buf(),
+            MessageBus::new(),
+            Some("test-model".to_string()),
+            BuiltInToolsConfig::default(),
+            NetworkToolConfig::default(),
+            Some(5),
+            false,
+            HashMap::new(),
+            SubagentPolicy::default(),
+            ContextBudgetPolicy::default(),
+        );
+
+        manager.spawn(handle_request("hold")).await.unwrap();
+        let task_id = manager.status("cli:direct", &[]).await.unwrap()[0]
+            .task_id
+            .clone();
+
+        assert!(manager.status("telegram:42", &[]).await.unwrap().is_empty());
+        let ids = std::slice::from_ref(&task_id);
+        for err in [
+            manager.status("telegram:42", ids).await.unwrap_err(),
+            manager
+                .wait("telegram:42", ids, Duration::from_millis(10))
+                .await
+                .unwrap_err(),
+            manager.cancel("telegram:42", &task_id).await.unwrap_err(),
+        ] {
+            assert!(err.to_string().contains("Unknown subagent task"));
+        }
+        assert_eq!(
+            manager.status("cli:direct", ids).await.unwrap()[0].status,
+            SubagentStatus::Running
+        );
+        notify.notify_waiters();
+    }
+
+    #[tokio::test]
+    async fn test_subagent_uncollected_reports_expire() {
+        let mut manager = SubagentManager::new(
+            Arc::new(WritingProvider {
+                calls: Mutex::new(0),
+            }),
+            tempfile::tempdir().unwrap().path().to_path_buf(),
+            MessageBus::new(),
+            Some("test-model".to_string()),
+            BuiltInToolsConfig::default(),
+            NetworkToolConfig::default(),
+            Some(5),
+            false,
+            HashMap::new(),
+            SubagentPolicy::default(),
+            ContextBudgetPolicy::default(),
+        );
+        manager.report_ttl = Duration::ZERO;
+
+        manager.spawn(handle_request("notes")).await.unwrap();
+        let mut report = {
+            let handles = manager.handles.lock().await;
+            handles.values().next().unwrap().report.subscribe()
+        };
+        report.wait_for(Option::is_some).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        assert!(manager.status("cli:direct", &[]).await.unwrap().is_empty());
+        assert!(manager.handles.lock().await.is_empty());
+    }
+
+    #[test]
+    fn test_split_artifacts_extracts_trailing_list() {
+        let (summary, artifacts) =
+            split_artifacts("Done.\n\n**Artifacts:**\n- out/report.md\n* https://example.com\n");
+        assert_eq!(summary, "Done.");
+        assert_eq!(artifacts, vec!["out/report.md", "https://example.com"]);
+
+        let (summary, artifacts) = split_artifacts("Artifacts:\nnone produced");
+        assert_eq!(summary, "Artifacts:\nnone produced");
+        assert!(artifacts.is_empty());
+    }
+
     #[tokio::test]
     async fn test_subagent_timeout_helper_returns_error() {
         let err = SubagentManager::with_subagent_timeout(
@@ -0,0 +1,171 @@
+use crate::subagent_policy::SubagentPolicy;
+use agent_diva_core::config::SubagentProfileConfig;
+use agent_diva_providers::LLMProvider;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Tools behind each allowlist group name.
+const TOOL_GROUPS: &[(&str, &[&str])] = &[
+    (
+        "filesystem",
+        &["read_file", "write_file", "edit_file", "list_dir"],
+    ),
+    ("shell", &["exec", "exec_job"]),
+    ("web", &["web_search", "web_fetch"]),
+    ("mcp", &["mcp_*"]),
+];
+
+/// A named subagent setup the main agent can pick when spawning.
+#[derive(Clone)]
+pub struct SubagentProfile {
+    pub name: String,
+    pub description: String,
+    pub system_prompt: Option<String>,
+    pub model: Option<String>,
+    /// Provider serving `model`; the main agent's provider when unset
+    pub provider: Option<Arc<dyn LLMProvider>>,
+    /// Allowed tool names, `prefix*` patterns and groups; empty keeps the policy's tools
+    pub tools: Vec<String>,
+    pub max_iterations: Option<usize>,
+    pub timeout: Option<Duration>,
+}
+
+impl SubagentProfile {
+    pub fn from_config(name: impl Into<String>, config: &SubagentProfileConfig) -> Self {
+        Self {
+            name: name.into(),
+            description: config.description.trim().to_string(),
+            system_prompt: config
+                .system_prompt
+                .as_deref()
+                .map(str::trim)
+                .filter(|prompt| !prompt.is_empty())
+                .map(str::to_string),
+            model: config.model.clone(),
+            provider: None,
+            tools: config.tools.clone(),
+            max_iterations: config.max_iterations.filter(|limit| *limit > 0),
+            timeout: config
+                .timeout_secs
+                .filter(|secs| *secs > 0)
+                .map(Duration::from_secs),
+        }
+    }
+
+    pub fn with_provider(mut self, provider: Arc<dyn LLMProvider>) -> Self {
+        self.provider = Some(provider);
+        self
+    }
+
+    /// Whether the allowlist admits `tool`.
+    pub fn allows_tool(&self, tool: &str) -> bool {
+        self.tools.is_empty()
+            || self
+                .patterns()
+                .any(|pattern| match pattern.strip_suffix('*') {
+                    Some(prefix) => tool.starts_with(prefix),
+                    None => tool == pattern,
+                })
+    }
+
+    /// Policy for subagents of this profile. An allowlist replaces the
+    /// `allow_*` switches; concurrency and depth limits are kept.
+    pub fn policy(&self, base: &SubagentPolicy) -> SubagentPolicy {
+        if self.tools.is_empty() {
+            return base.clone();
+        }
+        SubagentPolicy {
+            allow_shell: self.allows_tool("exec"),
+            allow_filesystem: ["read_file", "write_file", "edit_file", "list_dir"]
+                .iter()
+                .any(|tool| self.allows_tool(tool)),
+            allow_web_fetch: self.allows_tool("web_fetch"),
+            allow_web_search: self.allows_tool("web_search"),
+            allow_mcp: self
+                .patterns()
+                .any(|pattern| pattern == "*" || pattern.starts_with("mcp_")),
+            ..base.clone()
+        }
+    }
+
+    /// `name: description` line shown to the main agent.
+    pub fn summary(&self) -> String {
+        if self.description.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{}: {}", self.name, self.description)
+        }
+    }
+
+    fn patterns(&self) -> impl Iterator<Item = &str> {
+        self.tools.iter().flat_map(|entry| {
+            let entry = entry.trim();
+            TOOL_GROUPS
+                .iter()
+                .find(|(group, _)| *group == entry)
+                .map_or_else(|| vec![entry], |(_, tools)| tools.to_vec())
+        })
+    }
+}
+
+impl std::fmt::Debug for SubagentProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SubagentProfile")
+            .field("name", &self.name)
+            .field("model", &self.model)
+            .field("tools", &self.tools)
+            .field("max_iterations", &self.max_iterations)
+            .field("timeout", &self.timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(tools: &[&str]) -> SubagentProfile {
+        SubagentProfile::from_config(
+            "researcher",
+            &SubagentProfileConfig {
+                description: "Web research".to_string(),
+                tools: tools.iter().map(|tool| tool.to_string()).collect(),
+                timeout_secs: Some(0),
+                ..SubagentProfileConfig::default()
+            },
+        )
+    }
+
+    #[test]
+    fn allowlist_groups_and_patterns() {
+        let researcher = profile(&["web", "read_file"]);
+        assert!(researcher.allows_tool("web_search"));
+        assert!(researcher.allows_tool("read_file"));
+        assert!(!researcher.allows_tool("write_file"));
+        assert!(!researcher.allows_tool("exec"));
+
+        let mcp = profile(&["mcp_github_*"]);
+        assert!(mcp.allows_tool("mcp_github_search"));
+        assert!(!mcp.allows_tool("mcp_slack_post"));
+
+        assert!(profile(&[]).allows_tool("anything"));
+        assert_eq!(researcher.timeout, None);
+        assert_eq!(researcher.summary(), "researcher: Web research");
+    }
+
+    #[test]
+    fn allowlist_replaces_policy_switches_but_keeps_limits() {
+        let base = SubagentPolicy {
+            max_concurrent: 3,
+            ..SubagentPolicy::default()
+        };
+        let policy = profile(&["web", "read_file"]).policy(&base);
+        assert!(policy.allow_web_search);
+        assert!(policy.allow_web_fetch);
+        assert!(policy.allow_filesystem);
+        assert!(!policy.allow_shell);
+        assert!(!policy.allow_mcp);
+        assert_eq!(policy.max_concurrent, 3);
+        assert_eq!(profile(&[]).policy(&base), base);
+    }
+}
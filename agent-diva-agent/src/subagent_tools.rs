@@ -0,0 +1,205 @@
+//! Tools for collecting, inspecting and cancelling handle-mode subagents
+
+use crate::tool_assembly::SubagentSpawner;
+use agent_diva_tooling::{Tool, ToolError};
+use async_trait::async_trait;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+
+const DEFAULT_WAIT_SECS: u64 = 30;
+/// Upper bound of one wait; the turn is blocked for its whole length.
+const MAX_WAIT_SECS: u64 = 60;
+
+/// Session of the calling turn, injected as `context_session_key`.
+fn session_key_arg(args: &Value) -> Result<String, ToolError> {
+    args.get("context_session_key")
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+        .ok_or_else(|| {
+            ToolError::ExecutionFailed("no current session owns subagent tasks".to_string())
+        })
+}
+
+fn task_ids_arg(args: &Value) -> Result<Vec<String>, ToolError> {
+    match args.get("task_ids") {
+        None | Some(Value::Null) => Ok(Vec::new()),
+        Some(Value::Array(items)) => items
+            .iter()
+            .map(|item| {
+                item.as_str()
+                    .map(|id| id.trim().to_string())
+                    .filter(|id| !id.is_empty())
+                    .ok_or_else(|| {
+                        ToolError::InvalidParams("'task_ids' must be non-empty strings".to_string())
+                    })
+            })
+            .collect(),
+        Some(_) => Err(ToolError::InvalidParams(
+            "'task_ids' must be an array of task ids".to_string(),
+        )),
+    }
+}
+
+fn render<T: Serialize>(result: Result<T, ToolError>) -> Result<String, ToolError> {
+    match result {
+        Ok(value) => serde_json::to_string_pretty(&value)
+            .map_err(|e| ToolError::ExecutionFailed(e.to_string())),
+        Err(e) => Ok(format!("Error: {}", e)),
+    }
+}
+
+fn task_ids_schema(description: &str) -> Value {
+    json!({
+        "type": "array",
+        "items": { "type": "string" },
+        "description": description
+    })
+}
+
+/// Waits for handle-mode subagents and returns their structured reports
+pub struct SubagentWaitTool {
+    spawner: Arc<dyn SubagentSpawner>,
+}
+
+impl SubagentWaitTool {
+    pub fn new(spawner: Arc<dyn SubagentSpawner>) -> Self {
+        Self { spawner }
+    }
+}
+
+#[async_trait]
+impl Tool for SubagentWaitTool {
+    fn name(&self) -> &str {
+        "subagent_wait"
+    }
+
+    fn description(&self) -> &str {
+        "Wait for subagents spawned with mode \"handle\" and return their results: summary, artifacts and files written. Tasks still running at the timeout are reported as running. A finished result is returned only once."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "task_ids": task_ids_schema("Task ids to wait for; omit to wait for all handle-mode subagents"),
+                "timeout_secs": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "description": format!(
+                        "How long to wait, in seconds (default {}, max {})",
+                        DEFAULT_WAIT_SECS, MAX_WAIT_SECS
+                    )
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: Value) -> Result<String, ToolError> {
+        let task_ids = task_ids_arg(&args)?;
+        let timeout_secs = args
+            .get("timeout_secs")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(DEFAULT_WAIT_SECS)
+            .min(MAX_WAIT_SECS);
+        let result = match session_key_arg(&args) {
+            Ok(session_key) => {
+                self.spawner
+                    .wait(session_key, task_ids, Duration::from_secs(timeout_secs))
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+        render(result)
+    }
+}
+
+/// Reports the current state of spawned subagents without waiting
+pub struct SubagentStatusTool {
+    spawner: Arc<dyn SubagentSpawner>,
+}
+
+impl SubagentStatusTool {
+    pub fn new(spawner: Arc<dyn SubagentSpawner>) -> Self {
+        Self { spawner }
+    }
+}
+
+#[async_trait]
+impl Tool for SubagentStatusTool {
+    fn name(&self) -> &str {
+        "subagent_status"
+    }
+
+    fn description(&self) -> &str {
+        "Check the state of spawned subagents (running, completed, failed or cancelled) without waiting."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "task_ids": task_ids_schema("Task ids to check; omit to list every tracked subagent")
+            }
+        })
+    }
+
+    async fn execute(&self, args: Value) -> Result<String, ToolError> {
+        let task_ids = task_ids_arg(&args)?;
+        let result = match session_key_arg(&args) {
+            Ok(session_key) => self.spawner.status(session_key, task_ids).await,
+            Err(e) => Err(e),
+        };
+        render(result)
+    }
+}
+
+/// Cancels a running subagent
+pub struct SubagentCancelTool {
+    spawner: Arc<dyn SubagentSpawner>,
+}
+
+impl SubagentCancelTool {
+    pub fn new(spawner: Arc<dyn SubagentSpawner>) -> Self {
+        Self { spawner }
+    }
+}
+
+#[async_trait]
+impl Tool for SubagentCancelTool {
+    fn name(&self) -> &str {
+        "subagent_cancel"
+    }
+
+    fn description(&self) -> &str {
+        "Cancel a running subagent by task id. Finished subagents keep their result."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "task_id": {
+                    "type": "string",
+                    "description": "Task id returned by spawn"
+                }
+            },
+            "required": ["task_id"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> Result<String, ToolError> {
+        let task_id = args
+            .get("task_id")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .ok_or_else(|| ToolError::InvalidParams("Missing 'task_id' parameter".to_string()))?;
+        let result = match session_key_arg(&args) {
+            Ok(session_key) => self.spawner.cancel(session_key, task_id.to_string()).await,
+            Err(e) => Err(e),
+        };
+        render(result)
+    }
+}
@@ -0,0 +1,131 @@
+//! Token counting for context budgeting
+//!
+//! OpenAI-family models are counted with their tiktoken BPE vocabularies, which
+//! are compiled into the binary so counting works offline. Every other model
+//! falls back to a character heuristic that counts CJK text per character.
+
+use std::sync::OnceLock;
+use tiktoken_rs::tokenizer::{get_tokenizer, Tokenizer as TiktokenEncoding};
+use tiktoken_rs::CoreBPE;
+
+/// Counts tokens in text for a specific model family.
+pub trait Tokenizer: Send + Sync {
+    /// Short identifier (e.g. `o200k_base`, `heuristic`) for logs and traces.
+    fn name(&self) -> &'static str;
+
+    /// Number of tokens `text` encodes to.
+    fn count_tokens(&self, text: &str) -> usize;
+}
+
+/// Character-based estimate used when no vocabulary is known for the model.
+///
+/// Roughly four Latin characters per token, one token per CJK character.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct HeuristicTokenizer;
+
+impl Tokenizer for HeuristicTokenizer {
+    fn name(&self) -> &'static str {
+        "heuristic"
+    }
+
+    fn count_tokens(&self, text: &str) -> usize {
+        let mut wide = 0;
+        let mut narrow = 0;
+        for ch in text.chars() {
+            if is_wide_char(ch) {
+                wide += 1;
+            } else {
+                narrow += 1;
+            }
+        }
+        wide + narrow / 4
+    }
+}
+
+/// Exact counts from a tiktoken-compatible BPE vocabulary.
+pub struct BpeTokenizer {
+    name: &'static str,
+    bpe: &'static CoreBPE,
+}
+
+impl Tokenizer for BpeTokenizer {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn count_tokens(&self, text: &str) -> usize {
+        self.bpe.encode_ordinary(text).len()
+    }
+}
+
+static HEURISTIC: HeuristicTokenizer = HeuristicTokenizer;
+static O200K: OnceLock<BpeTokenizer> = OnceLock::new();
+static CL100K: OnceLock<BpeTokenizer> = OnceLock::new();
+
+/// Pick the tokenizer for `model`, accepting provider-prefixed names such as
+/// `openai/gpt-4o`. Unknown models use [`HeuristicTokenizer`].
+pub fn tokenizer_for_model(model: &str) -> &'static dyn Tokenizer {
+    match bpe_encoding_for_model(model) {
+        Some(TiktokenEncoding::O200kBase) => O200K.get_or_init(|| BpeTokenizer {
+            name: "o200k_base",
+            bpe: tiktoken_rs::o200k_base_singleton(),
+        }),
+        Some(TiktokenEncoding::Cl100kBase) => CL100K.get_or_init(|| BpeTokenizer {
+            name: "cl100k_base",
+            bpe: tiktoken_rs::cl100k_base_singleton(),
+        }),
+        _ => &HEURISTIC,
+    }
+}
+
+fn bpe_encoding_for_model(model: &str) -> Option<TiktokenEncoding> {
+    let name = model
+        .rsplit('/')
+        .next()
+        .unwrap_or(model)
+        .to_ascii_lowercase();
+    if let Some(encoding) = get_tokenizer(&name) {
+        return Some(encoding);
+    }
+    // Newer OpenAI families not yet in tiktoken-rs' model table.
+    ["gpt-5", "gpt-4o", "gpt-4.1", "o1", "o3", "o4"]
+        .iter()
+        .any(|prefix| name.starts_with(prefix))
+        .then_some(TiktokenEncoding::O200kBase)
+}
+
+fn is_wide_char(ch: char) -> bool {
+    matches!(
+        ch as u32,
+        0x1100..=0x11FF     // Hangul Jamo
+        | 0x2E80..=0x9FFF   // CJK radicals, kana, CJK unified ideographs
+        | 0xAC00..=0xD7AF   // Hangul syllables
+        | 0xF900..=0xFAFF   // CJK compatibility ideographs
+        | 0xFF00..=0xFFEF   // Full-width forms
+        | 0x20000..=0x2FFFF // CJK extensions
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn openai_models_use_bundled_bpe_vocab() {
+        assert_eq!(tokenizer_for_model("gpt-4o-mini").name(), "o200k_base");
+        assert_eq!(tokenizer_for_model("openai/gpt-5").name(), "o200k_base");
+        assert_eq!(tokenizer_for_model("gpt-4-turbo").name(), "cl100k_base");
+        assert_eq!(tokenizer_for_model("deepseek-chat").name(), "heuristic");
+
+        let tokenizer = tokenizer_for_model("gpt-4o");
+        assert_eq!(tokenizer.count_tokens("hello world"), 2);
+    }
+
+    #[test]
+    fn heuristic_counts_cjk_per_character() {
+        let tokenizer = HeuristicTokenizer;
+        assert_eq!(tokenizer.count_tokens("你好世界"), 4);
+        assert_eq!(tokenizer.count_tokens("abcdefgh"), 2);
+        assert_eq!(tokenizer.count_tokens("こんにちは abcd"), 6);
+    }
+}
@@ -1,14 +1,19 @@
-use crate::subagent::SubagentSpawnRequest;
+use crate::skills::{LoadSkillTool, SkillsLoader};
+use crate::subagent::{SubagentReport, SubagentSpawnRequest};
 use crate::subagent_policy::SubagentPolicy;
+use crate::subagent_tools::{SubagentCancelTool, SubagentStatusTool, SubagentWaitTool};
 use crate::tool_config::{builtin::BuiltInToolsConfig, network::NetworkToolConfig};
 use agent_diva_core::config::MCPServerConfig;
 use agent_diva_core::cron::CronService;
+use agent_diva_core::memory::MemoryProvider;
 use agent_diva_core::security::{SecurityConfig, SecurityLevel, SecurityPolicy};
 use agent_diva_files::FileManager;
 use agent_diva_tooling::{Tool, ToolError, ToolRegistry};
 use agent_diva_tools::{
-    load_mcp_tools_sync, CronTool, EditFileTool, ExecTool, ListDirTool, ReadAttachmentTool,
-    ReadFileTool, SpawnTool, WebFetchTool, WebSearchTool, WriteFileTool,
+    job_wait_cap_secs, load_mcp_tools_sync, CronTool, EditFileTool, ExecJobTool, ExecSandboxConfig,
+    ExecTool, ListDirTool, McpManager, MemoryForgetTool, MemorySaveTool, MemorySearchTool,
+    ReadAttachmentTool, ReadFileTool, SessionSearchTool, ShellJobManager, SkillScriptTool,
+    SpawnTool, WebFetchTool, WebSearchTool, WriteFileTool,
 };
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -17,6 +22,45 @@ use std::sync::Arc;
 #[async_trait::async_trait]
 pub trait SubagentSpawner: Send + Sync {
     async fn spawn(&self, request: SubagentSpawnRequest) -> Result<String, ToolError>;
+
+    /// `(name, summary)` of the profiles `spawn` may select.
+    fn profiles(&self) -> Vec<(String, String)> {
+        Vec::new()
+    }
+
+    /// Block until the given handle-mode tasks of `session_key` finish or
+    /// `timeout` elapses.
+    async fn wait(
+        &self,
+        _session_key: String,
+        _task_ids: Vec<String>,
+        _timeout: std::time::Duration,
+    ) -> Result<Vec<SubagentReport>, ToolError> {
+        Err(unsupported_handles())
+    }
+
+    /// Current state of the given tasks of `session_key`, or of every one it
+    /// tracks when empty.
+    async fn status(
+        &self,
+        _session_key: String,
+        _task_ids: Vec<String>,
+    ) -> Result<Vec<SubagentReport>, ToolError> {
+        Err(unsupported_handles())
+    }
+
+    /// Abort a running task of `session_key`.
+    async fn cancel(
+        &self,
+        _session_key: String,
+        _task_id: String,
+    ) -> Result<SubagentReport, ToolError> {
+        Err(unsupported_handles())
+    }
+}
+
+fn unsupported_handles() -> ToolError {
+    ToolError::ExecutionFailed("This spawner does not track subagent handles".to_string())
 }
 
 pub struct ToolAssembly {
@@ -24,12 +68,16 @@ pub struct ToolAssembly {
     builtin_config: BuiltInToolsConfig,
     network_config: NetworkToolConfig,
     exec_timeout: u64,
+    exec_sandbox: ExecSandboxConfig,
+    shell_jobs: Option<Arc<ShellJobManager>>,
     restrict_to_workspace: bool,
     mcp_servers: HashMap<String, MCPServerConfig>,
+    mcp_manager: Option<Arc<McpManager>>,
     cron_service: Option<Arc<CronService>>,
     custom_tools: Vec<Arc<dyn Tool>>,
     subagent_spawner: Option<Arc<dyn SubagentSpawner>>,
     file_manager: Option<Arc<FileManager>>,
+    memory_provider: Option<Arc<dyn MemoryProvider>>,
 }
 
 impl ToolAssembly {
@@ -39,12 +87,16 @@ impl ToolAssembly {
             builtin_config: BuiltInToolsConfig::default(),
             network_config: NetworkToolConfig::default(),
             exec_timeout: 60,
+            exec_sandbox: ExecSandboxConfig::default(),
+            shell_jobs: None,
             restrict_to_workspace: false,
             mcp_servers: HashMap::new(),
+            mcp_manager: None,
             cron_service: None,
             custom_tools: Vec::new(),
             subagent_spawner: None,
             file_manager: None,
+            memory_provider: None,
         }
     }
 
@@ -63,6 +115,16 @@ impl ToolAssembly {
         self
     }
 
+    pub fn with_exec_sandbox(mut self, sandbox: ExecSandboxConfig) -> Self {
+        self.exec_sandbox = sandbox;
+        self
+    }
+
+    pub fn with_shell_jobs(mut self, jobs: Arc<ShellJobManager>) -> Self {
+        self.shell_jobs = Some(jobs);
+        self
+    }
+
     pub fn restrict_to_workspace(mut self, restrict: bool) -> Self {
         self.restrict_to_workspace = restrict;
         self
@@ -73,6 +135,13 @@ impl ToolAssembly {
         self
     }
 
+    /// Load MCP tools through a shared manager so list-changed notifications can
+    /// refresh them later.
+    pub fn with_mcp_manager(mut self, manager: Arc<McpManager>) -> Self {
+        self.mcp_manager = Some(manager);
+        self
+    }
+
     pub fn with_cron_service(mut self, service: Arc<CronService>) -> Self {
         self.cron_service = Some(service);
         self
@@ -88,6 +157,12 @@ impl ToolAssembly {
         self
     }
 
+    /// Register `memory_save`, `memory_search` and `memory_forget` against `provider`.
+    pub fn with_memory_provider(mut self, provider: Arc<dyn MemoryProvider>) -> Self {
+        self.memory_provider = Some(provider);
+        self
+    }
+
     pub fn with_tool(mut self, tool: Arc<dyn Tool>) -> Self {
         self.custom_tools.push(tool);
         self
@@ -107,6 +182,9 @@ impl ToolAssembly {
         self.subagent_spawner = None;
         self.cron_service = None;
         self.file_manager = None;
+        self.shell_jobs = None;
+        self.mcp_manager = None;
+        self.memory_provider = None;
         self.build_internal(true)
     }
 
@@ -131,6 +209,12 @@ impl ToolAssembly {
             registry.register(Arc::new(WriteFileTool::new(security.clone())));
             registry.register(Arc::new(EditFileTool::new(security.clone())));
             registry.register(Arc::new(ListDirTool::new(security)));
+            if !subagent_mode {
+                registry.register(Arc::new(LoadSkillTool::new(SkillsLoader::new(
+                    &self.workspace,
+                    None,
+                ))));
+            }
         }
 
         if self.builtin_config.attachment {
@@ -140,11 +224,43 @@ impl ToolAssembly {
         }
 
         if self.builtin_config.shell {
-            registry.register(Arc::new(ExecTool::with_config(
+            let mut exec = ExecTool::with_config(
                 self.exec_timeout,
                 Some(self.workspace.clone()),
                 self.restrict_to_workspace,
-            )));
+            )
+            .with_sandbox(self.exec_sandbox.clone());
+            if let Some(jobs) = self.shell_jobs.clone() {
+                exec = exec.with_jobs(jobs.clone());
+                // Leave headroom below the registry timeout for `wait`.
+                registry.register(Arc::new(ExecJobTool::new(
+                    jobs,
+                    job_wait_cap_secs(self.exec_timeout),
+                )));
+            }
+            registry.register(Arc::new(exec));
+
+            // Skill tools are scoped: the agent loop only offers them while
+            // their skill is active, so subagents do not get them.
+            if !subagent_mode {
+                for (skill, skill_dir, spec) in
+                    SkillsLoader::new(&self.workspace, None).get_skill_tools()
+                {
+                    if registry.has(&spec.name) {
+                        tracing::warn!(
+                            "Skill '{}' declares tool '{}', which is already registered",
+                            skill,
+                            spec.name
+                        );
+                        continue;
+                    }
+                    registry.register(Arc::new(
+                        SkillScriptTool::new(skill, skill_dir, spec, self.workspace.clone())
+                            .with_timeout(self.exec_timeout)
+                            .with_sandbox(self.exec_sandbox.clone()),
+                    ));
+                }
+            }
         }
 
         if self.builtin_config.web_search && self.network_config.web.search.enabled {
@@ -161,28 +277,42 @@ impl ToolAssembly {
 
         if self.builtin_config.spawn && !subagent_mode {
             if let Some(spawner) = self.subagent_spawner {
-                registry.register(Arc::new(SpawnTool::new(
-                    move |task, label, channel, chat_id| {
+                let profiles = spawner.profiles();
+                registry.register(Arc::new(
+                    SpawnTool::new({
                         let spawner = spawner.clone();
-                        async move {
-                            spawner
-                                .spawn(SubagentSpawnRequest {
-                                    task,
-                                    label,
-                                    origin_channel: channel,
-                                    origin_chat_id: chat_id,
-                                    current_depth: 0,
-                                    origin: "main_agent".to_string(),
-                                })
-                                .await
+                        move |args, channel, chat_id| {
+                            let spawner = spawner.clone();
+                            async move {
+                                spawner
+                                    .spawn(SubagentSpawnRequest {
+                                        task: args.task,
+                                        label: args.label,
+                                        origin_channel: channel,
+                                        origin_chat_id: chat_id,
+                                        current_depth: 0,
+                                        origin: "main_agent".to_string(),
+                                        profile: args.profile,
+                                        mode: args.mode,
+                                    })
+                                    .await
+                            }
                         }
-                    },
-                )));
+                    })
+                    .with_profiles(profiles),
+                ));
+                registry.register(Arc::new(SubagentWaitTool::new(spawner.clone())));
+                registry.register(Arc::new(SubagentStatusTool::new(spawner.clone())));
+                registry.register(Arc::new(SubagentCancelTool::new(spawner)));
             }
         }
 
         if self.builtin_config.mcp && !self.mcp_servers.is_empty() {
-            for tool in load_mcp_tools_sync(&self.mcp_servers) {
+            let mcp_tools = match &self.mcp_manager {
+                Some(manager) => manager.connect_sync(&self.mcp_servers),
+                None => load_mcp_tools_sync(&self.mcp_servers),
+            };
+            for tool in mcp_tools {
                 registry.register(tool);
             }
         }
@@ -193,6 +323,24 @@ impl ToolAssembly {
             }
         }
 
+        if self.builtin_config.memory && !subagent_mode {
+            registry.register(Arc::new(SessionSearchTool::new(self.workspace.clone())));
+            if let Some(provider) = self.memory_provider {
+                registry.register(Arc::new(MemorySaveTool::new(
+                    provider.clone(),
+                    self.workspace.clone(),
+                )));
+                registry.register(Arc::new(MemorySearchTool::new(
+                    provider.clone(),
+                    self.workspace.clone(),
+                )));
+                registry.register(Arc::new(MemoryForgetTool::new(
+                    provider,
+                    self.workspace.clone(),
+                )));
+            }
+        }
+
         for tool in self.custom_tools {
             registry.register(tool);
         }
@@ -216,6 +364,7 @@ mod tests {
         assert!(registry.has("write_file"));
         assert!(registry.has("edit_file"));
         assert!(registry.has("list_dir"));
+        assert!(registry.has("load_skill"));
         assert!(!registry.has("exec"));
         assert!(!registry.has("web_search"));
         assert!(!registry.has("web_fetch"));
@@ -258,10 +407,72 @@ mod tests {
             .build_subagent_registry(&policy);
 
         assert!(registry.has("read_file"));
+        assert!(!registry.has("load_skill"));
         assert!(!registry.has("spawn"));
         assert!(!registry.has("read_attachment"));
     }
 
+    struct NoopSpawner;
+
+    #[async_trait::async_trait]
+    impl SubagentSpawner for NoopSpawner {
+        async fn spawn(&self, _request: SubagentSpawnRequest) -> Result<String, ToolError> {
+            Ok("spawned".to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tool_assembly_registers_subagent_handle_tools_with_spawner() {
+        let registry = ToolAssembly::new(PathBuf::from("/tmp/test"))
+            .builtin(BuiltInToolsConfig {
+                spawn: true,
+                ..BuiltInToolsConfig::none()
+            })
+            .with_subagent_spawner(Arc::new(NoopSpawner))
+            .build();
+
+        for name in [
+            "spawn",
+            "subagent_wait",
+            "subagent_status",
+            "subagent_cancel",
+        ] {
+            assert!(registry.has(name), "missing {}", name);
+        }
+        let result = registry
+            .execute(
+                "subagent_status",
+                serde_json::json!({"context_session_key": "cli:direct"}),
+            )
+            .await;
+        assert!(result.starts_with("Error: "));
+        assert!(result.contains("does not track subagent handles"));
+    }
+
+    #[test]
+    fn test_tool_assembly_registers_memory_tools_for_main_agent_only() {
+        let provider: Arc<dyn MemoryProvider> = Arc::new(
+            agent_diva_core::memory::MemoryManager::new(PathBuf::from("/tmp/test")),
+        );
+        let assembly = || {
+            ToolAssembly::new(PathBuf::from("/tmp/test"))
+                .builtin(BuiltInToolsConfig {
+                    memory: true,
+                    ..BuiltInToolsConfig::none()
+                })
+                .with_memory_provider(provider.clone())
+        };
+
+        let registry = assembly().build();
+        assert!(registry.has("memory_save"));
+        assert!(registry.has("memory_search"));
+        assert!(registry.has("memory_forget"));
+        assert!(registry.has("session_search"));
+
+        let subagent = assembly().build_subagent_registry(&SubagentPolicy::default());
+        assert!(!subagent.has("memory_save"));
+    }
+
     #[test]
     fn test_tool_assembly_subagent_mode_respects_policy_for_web_tools() {
         let policy = SubagentPolicy {
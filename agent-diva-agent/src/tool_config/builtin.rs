@@ -21,6 +21,8 @@ pub struct BuiltInToolsConfig {
     pub mcp: bool,
     #[serde(default = "default_true")]
     pub attachment: bool,
+    #[serde(default = "default_true")]
+    pub memory: bool,
 }
 
 fn default_true() -> bool {
@@ -38,6 +40,7 @@ impl BuiltInToolsConfig {
             cron: false,
             mcp: false,
             attachment: false,
+            memory: false,
         }
     }
 
@@ -51,6 +54,7 @@ impl BuiltInToolsConfig {
             cron: false,
             mcp: false,
             attachment: false,
+            memory: false,
         }
     }
 
@@ -64,6 +68,7 @@ impl BuiltInToolsConfig {
             cron: true,
             mcp: true,
             attachment: true,
+            memory: true,
         }
     }
 
@@ -77,6 +82,7 @@ impl BuiltInToolsConfig {
             cron: false,
             mcp: self.mcp && policy.allow_mcp,
             attachment: false,
+            memory: false,
         }
     }
 }
@@ -92,6 +98,7 @@ impl Default for BuiltInToolsConfig {
             cron: false,
             mcp: true,
             attachment: true,
+            memory: true,
         }
     }
 }
@@ -1,5 +1,6 @@
 use crate::cli_runtime::{
-    build_provider, ensure_workspace_templates, session_channel_and_chat_id, CliRuntime,
+    build_provider, build_subagent_profiles, ensure_workspace_templates,
+    session_channel_and_chat_id, CliRuntime,
 };
 use crate::client::ApiClient;
 use agent_diva_agent::{
@@ -16,7 +17,9 @@ use agent_diva_core::bus::MessageBus;
 use agent_diva_core::config::Config;
 use agent_diva_core::cron::CronService;
 use agent_diva_core::logging::build_runtime_trace_logger;
+use agent_diva_core::memory::open_memory_provider;
 use agent_diva_files::{FileConfig, FileManager};
+use agent_diva_tools::{ExecSandboxConfig, McpManager, ShellJobManager};
 use anyhow::Result;
 use console::style;
 use dialoguer::Input;
@@ -61,10 +64,11 @@ pub fn build_builtin_tools_config(config: &Config) -> BuiltInToolsConfig {
         cron: config.tools.builtin.cron,
         mcp: config.tools.builtin.mcp,
         attachment: config.tools.builtin.attachment,
+        memory: config.tools.builtin.memory,
     }
 }
 
-async fn build_local_cli_agent(
+pub(crate) async fn build_local_cli_agent(
     runtime: &CliRuntime,
     model: Option<String>,
     with_runtime_control: bool,
@@ -85,9 +89,13 @@ async fn build_local_cli_agent(
         builtin: build_builtin_tools_config(&config),
         network: build_network_tool_config(&config),
         exec_timeout: config.tools.exec.timeout,
+        exec_sandbox: ExecSandboxConfig::from(&config.tools.exec),
+        shell_jobs: Arc::new(ShellJobManager::new()),
         restrict_to_workspace: config.tools.restrict_to_workspace,
         mcp_servers: config.tools.active_mcp_servers(),
+        mcp_manager: Arc::new(McpManager::new()),
         subagent_policy: SubagentPolicy::from(config.tools.subagent.clone()),
+        subagent_profiles: build_subagent_profiles(&config),
         cron_service: Some(Arc::new(CronService::new(runtime.cron_store_path(), None))),
         soul_context: SoulContextSettings {
             enabled: config.agents.soul.enabled,
@@ -100,6 +108,7 @@ async fn build_local_cli_agent(
             context_budget_tokens: config.agents.defaults.context_budget_tokens as usize,
             reserve_tokens: config.agents.defaults.context_budget_reserve_tokens as usize,
             overflow_retry_enabled: config.agents.defaults.context_overflow_retry_enabled,
+            compaction_strategy: config.agents.defaults.context_compaction,
         },
         trace_logger: Some(build_runtime_trace_logger(&config.logging)),
         debug_logger: None,
@@ -109,6 +118,9 @@ async fn build_local_cli_agent(
             frequent_change_threshold: config.agents.soul.frequent_change_threshold,
             boundary_confirmation_hint: config.agents.soul.boundary_confirmation_hint,
         },
+        memory: config.agents.memory.clone(),
+        sessions: config.agents.sessions.clone(),
+        skills: config.agents.skills.clone(),
     };
 
     let (runtime_control_tx, runtime_control_rx) = if with_runtime_control {
@@ -125,7 +137,8 @@ async fn build_local_cli_agent(
     let file_config = FileConfig::with_path(&storage_path);
     let file_manager = Arc::new(FileManager::new(file_config).await?);
 
-    let agent = AgentLoop::with_tools(
+    let memory_provider = open_memory_provider(&config.agents.memory, &workspace).await?;
+    let agent = AgentLoop::with_tools_and_memory_provider(
         bus,
         provider,
         workspace,
@@ -134,6 +147,7 @@ async fn build_local_cli_agent(
         tool_config,
         runtime_control_rx,
         file_manager,
+        memory_provider,
     )
     .await
     .map_err(|e| anyhow::anyhow!("Failed to create agent loop: {}", e))?;
@@ -341,7 +355,7 @@ pub async fn run_chat(
     println!("{}", style("Agent Diva Chat").bold().cyan());
     println!("  model: {}", selected_model);
     println!("  session: {}", current_session);
-    println!("  commands: /quit /clear /new /stop");
+    println!("  commands: /quit /clear /new /stop /rewind <n> /fork [n]");
 
     loop {
         let input: String = Input::new()
@@ -353,6 +367,26 @@ pub async fn run_chat(
             continue;
         }
 
+        if let Some(edit) = parse_session_edit(command) {
+            match (edit, &runtime_control_tx) {
+                (Err(usage), _) => println!("{}", style(usage).yellow()),
+                (Ok(edit), Some(tx)) => {
+                    match apply_local_session_edit(tx, &current_session, edit).await {
+                        Ok(SessionEditOutcome::Rewound(removed)) => {
+                            println!("rewound: dropped {} messages", removed)
+                        }
+                        Ok(SessionEditOutcome::Forked(new_key)) => {
+                            current_session = new_key;
+                            println!("session -> {}", current_session);
+                        }
+                        Err(err) => println!("{}", style(err).red()),
+                    }
+                }
+                (Ok(_), None) => println!("{}", style("runtime control is unavailable").red()),
+            }
+            continue;
+        }
+
         match command {
             "/quit" => break,
             "/clear" => {
@@ -395,7 +429,7 @@ pub async fn run_chat_remote(
 
     println!("{}", style("Agent Diva Chat (remote)").bold().cyan());
     println!("  session: {}", current_session);
-    println!("  commands: /quit /clear /new /stop");
+    println!("  commands: /quit /clear /new /stop /rewind <n> /fork [n]");
 
     loop {
         let input: String = Input::new()
@@ -407,6 +441,34 @@ pub async fn run_chat_remote(
             continue;
         }
 
+        if let Some(edit) = parse_session_edit(command) {
+            let outcome = match edit {
+                Err(usage) => {
+                    println!("{}", style(usage).yellow());
+                    continue;
+                }
+                Ok(SessionEdit::Rewind(message_count)) => client
+                    .rewind_session(&current_session, message_count)
+                    .await
+                    .map(SessionEditOutcome::Rewound),
+                Ok(SessionEdit::Fork(message_count)) => client
+                    .fork_session(&current_session, message_count)
+                    .await
+                    .map(SessionEditOutcome::Forked),
+            };
+            match outcome {
+                Ok(SessionEditOutcome::Rewound(removed)) => {
+                    println!("rewound: dropped {} messages", removed)
+                }
+                Ok(SessionEditOutcome::Forked(new_key)) => {
+                    current_session = new_key;
+                    println!("session -> {}", current_session);
+                }
+                Err(err) => println!("{}", style(err).red()),
+            }
+            continue;
+        }
+
         match command {
             "/quit" => break,
             "/clear" => {
@@ -442,3 +504,72 @@ pub async fn run_chat_remote(
 
     Ok(())
 }
+
+/// `/rewind <n>` or `/fork [n]` typed in chat
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SessionEdit {
+    /// Keep the first `n` messages
+    Rewind(usize),
+    /// Branch into a new session, copying the first `n` messages (all when `None`)
+    Fork(Option<usize>),
+}
+
+enum SessionEditOutcome {
+    Rewound(usize),
+    Forked(String),
+}
+
+/// Parse a session edit command. Returns `None` for other input and
+/// `Some(Err(usage))` for malformed edit commands.
+fn parse_session_edit(input: &str) -> Option<Result<SessionEdit, &'static str>> {
+    let mut parts = input.split_whitespace();
+    let command = parts.next()?;
+    let count = parts.next().map(str::parse::<usize>);
+    if parts.next().is_some() {
+        return match command {
+            "/rewind" | "/fork" => Some(Err("usage: /rewind <n> | /fork [n]")),
+            _ => None,
+        };
+    }
+    match (command, count) {
+        ("/rewind", Some(Ok(n))) => Some(Ok(SessionEdit::Rewind(n))),
+        ("/rewind", _) => Some(Err("usage: /rewind <n> (keep the first n messages)")),
+        ("/fork", None) => Some(Ok(SessionEdit::Fork(None))),
+        ("/fork", Some(Ok(n))) => Some(Ok(SessionEdit::Fork(Some(n)))),
+        ("/fork", Some(Err(_))) => Some(Err("usage: /fork [n] (copy the first n messages)")),
+        _ => None,
+    }
+}
+
+async fn apply_local_session_edit(
+    tx: &mpsc::UnboundedSender<RuntimeControlCommand>,
+    session_key: &str,
+    edit: SessionEdit,
+) -> Result<SessionEditOutcome> {
+    let session_key = session_key.to_string();
+    match edit {
+        SessionEdit::Rewind(message_count) => {
+            let (reply_tx, reply_rx) = tokio::sync::oneshot::channel();
+            tx.send(RuntimeControlCommand::RewindSession {
+                session_key,
+                message_count,
+                reply_tx,
+            })
+            .map_err(|e| anyhow::anyhow!("failed to send rewind command: {}", e))?;
+            let removed = reply_rx.await?.map_err(anyhow::Error::msg)?;
+            Ok(SessionEditOutcome::Rewound(removed.len()))
+        }
+        SessionEdit::Fork(message_count) => {
+            let (reply_tx, reply_rx) = tokio::sync::oneshot::channel();
+            tx.send(RuntimeControlCommand::ForkSession {
+                session_key,
+                new_key: None,
+                message_count,
+                reply_tx,
+            })
+            .map_err(|e| anyhow::anyhow!("failed to send fork command: {}", e))?;
+            let new_key = reply_rx.await?.map_err(anyhow::Error::msg)?;
+            Ok(SessionEditOutcome::Forked(new_key))
+        }
+    }
+}
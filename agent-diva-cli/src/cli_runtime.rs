@@ -1,3 +1,4 @@
+use agent_diva_agent::SubagentProfile;
 use agent_diva_core::config::validate::validate_config;
 use agent_diva_core::config::{Config, ConfigLoader, ProviderConfig, ProvidersConfig};
 use agent_diva_core::cron::CronService;
@@ -316,13 +317,25 @@ pub fn session_channel_and_chat_id(session_key: &str) -> (&str, &str) {
 }
 
 pub fn build_provider(config: &Config, model: &str) -> Result<LiteLLMClient> {
+    build_provider_for(config, model, None)
+}
+
+/// Build a client for `model`, preferring `provider` when given and the
+/// configured default provider when `model` is the default model.
+fn build_provider_for(
+    config: &Config,
+    model: &str,
+    provider: Option<&str>,
+) -> Result<LiteLLMClient> {
     let catalog = ProviderCatalogService::new();
     let provider_name = resolve_provider_name_for_model(
         config,
         model,
-        (model == config.agents.defaults.model)
-            .then_some(config.agents.defaults.provider.as_deref())
-            .flatten(),
+        provider.or_else(|| {
+            (model == config.agents.defaults.model)
+                .then_some(config.agents.defaults.provider.as_deref())
+                .flatten()
+        }),
     )
     .ok_or_else(|| anyhow::anyhow!("No provider found for model: {}", model))?;
     let access = catalog
@@ -347,6 +360,40 @@ pub fn build_provider(config: &Config, model: &str) -> Result<LiteLLMClient> {
     ))
 }
 
+/// Subagent profiles from config; profiles naming a model or provider get
+/// their own client.
+pub fn build_subagent_profiles(config: &Config) -> Vec<SubagentProfile> {
+    config
+        .tools
+        .subagent
+        .profiles
+        .iter()
+        .filter_map(|(name, profile_config)| {
+            let profile = SubagentProfile::from_config(name, profile_config);
+            if profile_config.model.is_none() && profile_config.provider.is_none() {
+                return Some(profile);
+            }
+            let model = profile_config
+                .model
+                .clone()
+                .unwrap_or_else(|| config.agents.defaults.model.clone());
+            match build_provider_for(config, &model, profile_config.provider.as_deref()) {
+                Ok(provider) => Some(
+                    SubagentProfile {
+                        model: Some(model),
+                        ..profile
+                    }
+                    .with_provider(Arc::new(provider)),
+                ),
+                Err(e) => {
+                    tracing::warn!("Skipping subagent profile '{}': {}", name, e);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
 pub fn set_provider_credentials(
     config: &mut Config,
     provider_name: &str,
@@ -148,4 +148,59 @@ impl ApiClient {
             .and_then(|v| v.as_bool())
             .unwrap_or(true))
     }
+
+    /// Keep the first `message_count` messages of a session. Returns how many
+    /// messages were dropped.
+    pub async fn rewind_session(&self, session_key: &str, message_count: usize) -> Result<usize> {
+        let url = format!("{}/sessions/{}/rewind", self.base_url, session_key);
+        let body = self
+            .post_session_command(
+                &url,
+                serde_json::json!({ "message_count": message_count }),
+                "Rewind",
+            )
+            .await?;
+        Ok(body
+            .get("removed")
+            .and_then(|v| v.as_array())
+            .map(Vec::len)
+            .unwrap_or(0))
+    }
+
+    /// Fork a session, optionally at `message_count`. Returns the new session key.
+    pub async fn fork_session(
+        &self,
+        session_key: &str,
+        message_count: Option<usize>,
+    ) -> Result<String> {
+        let url = format!("{}/sessions/{}/fork", self.base_url, session_key);
+        let body = self
+            .post_session_command(
+                &url,
+                serde_json::json!({ "message_count": message_count }),
+                "Fork",
+            )
+            .await?;
+        body.get("session_key")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| anyhow::anyhow!("Fork failed: missing session key in response"))
+    }
+
+    async fn post_session_command(&self, url: &str, payload: Value, label: &str) -> Result<Value> {
+        let response = self.client.post(url).json(&payload).send().await?;
+        if !response.status().is_success() {
+            anyhow::bail!("Server returned error: {}", response.status());
+        }
+
+        let body: Value = response.json().await?;
+        if body.get("status").and_then(|v| v.as_str()) != Some("ok") {
+            let msg = body
+                .get("message")
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown error");
+            anyhow::bail!("{} failed: {}", label, msg);
+        }
+        Ok(body)
+    }
 }
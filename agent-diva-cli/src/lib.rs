@@ -3,4 +3,6 @@
 pub mod chat_commands;
 pub mod cli_runtime;
 pub mod client;
+pub mod mcp_serve;
 pub mod provider_commands;
+pub mod workflow_commands;
@@ -10,20 +10,30 @@ use agent_diva_cli::chat_commands::{
     run_chat_remote,
 };
 use agent_diva_cli::cli_runtime::{
-    available_provider_names, build_provider, channel_statuses, collect_status_report,
-    current_provider_name, default_model_from_registry, doctor_report, ensure_workspace_templates,
-    fetch_provider_models, print_json, redacted_config_value, set_provider_credentials, CliRuntime,
+    available_provider_names, build_provider, build_subagent_profiles, channel_statuses,
+    collect_status_report, current_provider_name, default_model_from_registry, doctor_report,
+    ensure_workspace_templates, fetch_provider_models, print_json, redacted_config_value,
+    set_provider_credentials, CliRuntime,
+};
+use agent_diva_cli::mcp_serve::{
+    run_mcp_serve, McpServeOptions, McpServeTransport, DEFAULT_MCP_SESSION,
 };
 use agent_diva_cli::provider_commands::{
     run_provider_list, run_provider_login, run_provider_models, run_provider_set,
     run_provider_status,
 };
+use agent_diva_cli::workflow_commands::{run_workflow, run_workflow_validate, WorkflowRunOptions};
 use agent_diva_core::bus::MessageBus;
 use agent_diva_core::config::validate::validate_config;
 use agent_diva_core::config::Config;
-use agent_diva_core::cron::{CronSchedule, CronService};
+use agent_diva_core::cron::{CronRunStatus, CronSchedule, CronService};
 use agent_diva_core::debug::DebugRun;
 use agent_diva_core::logging::{build_runtime_trace_logger, init_raw_debug_logging};
+use agent_diva_core::memory::{migrate_global_memory, open_memory_provider, MemoryScope};
+use agent_diva_core::session::{
+    export_session, import_bundle, parse_date_bound, SessionBundle, SessionExportFormat,
+    SessionExportOptions, SessionManager, SessionSearchIndex, SessionSearchQuery,
+};
 use agent_diva_files::{FileConfig, FileManager};
 use anyhow::Result;
 use clap::{Args, Parser, Subcommand, ValueEnum};
@@ -51,10 +61,12 @@ mod service;
 use agent_diva_cli::client::ApiClient;
 use service::{run_service_command, ServiceCommands};
 
+use agent_diva_manager::skill_package::SkillUpdateStatus;
+use agent_diva_manager::skill_service::SkillService;
 use agent_diva_manager::{
     create_debug_bundle, run_local_gateway, GatewayRuntimeConfig, DEFAULT_GATEWAY_PORT,
 };
-use agent_diva_tools::wtf;
+use agent_diva_tools::{wtf, ExecSandboxConfig, McpManager, ShellJobManager};
 
 #[derive(Parser)]
 #[command(name = "agent-diva")]
@@ -176,14 +188,64 @@ enum Commands {
         #[command(subcommand)]
         command: CronCommands,
     },
+    /// Inspect conversation sessions
+    Sessions {
+        #[command(subcommand)]
+        command: SessionCommands,
+    },
+    /// Manage long-term memory
+    Memory {
+        #[command(subcommand)]
+        command: MemoryCommands,
+    },
+    /// Install and manage skill packages
+    Skills {
+        #[command(subcommand)]
+        command: SkillCommands,
+    },
+    /// Run and check workflow files
+    Workflow {
+        #[command(subcommand)]
+        command: WorkflowCommands,
+    },
+    /// Serve this agent's tools, memory and sessions over MCP
+    McpServe {
+        /// Transport to serve on
+        #[arg(long, value_enum, default_value_t = McpServeTransport::Stdio)]
+        transport: McpServeTransport,
+        /// Bind address for the HTTP transport (loopback only; it has no auth)
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+        /// Port for the HTTP transport
+        #[arg(long, default_value_t = 3921)]
+        port: u16,
+        /// Model to use for the `chat` tool
+        #[arg(short, long)]
+        model: Option<String>,
+        /// Session key used by the `chat` tool
+        #[arg(short, long, default_value = DEFAULT_MCP_SESSION)]
+        session: String,
+    },
 }
 
 fn command_writes_logs_to_terminal(command: &Commands) -> bool {
     !matches!(command, Commands::Tui { .. } | Commands::Agent { .. })
+        && !mcp_serve_uses_stdio(command)
 }
 
 fn command_shows_startup_branding(command: &Commands) -> bool {
-    !matches!(command, Commands::Agent { .. })
+    !matches!(command, Commands::Agent { .. }) && !mcp_serve_uses_stdio(command)
+}
+
+/// Stdout carries the MCP protocol, so nothing else may be printed there.
+fn mcp_serve_uses_stdio(command: &Commands) -> bool {
+    matches!(
+        command,
+        Commands::McpServe {
+            transport: McpServeTransport::Stdio,
+            ..
+        }
+    )
 }
 
 fn gateway_debug_run(command: &Commands) -> bool {
@@ -331,6 +393,129 @@ enum CronCommands {
         #[arg(short, long)]
         force: bool,
     },
+    /// Show the run history of a cron job
+    Runs {
+        /// Job ID
+        job_id: String,
+        /// Maximum number of runs to show
+        #[arg(short, long, default_value_t = 20)]
+        limit: usize,
+    },
+}
+
+#[derive(Subcommand)]
+#[command(rename_all = "kebab-case")]
+enum SessionCommands {
+    /// Full-text search across all session transcripts
+    Search {
+        /// Words that must all appear in the message
+        query: String,
+        /// Only sessions from this channel
+        #[arg(long)]
+        channel: Option<String>,
+        /// Only messages with this role (user, assistant, tool, system)
+        #[arg(long)]
+        role: Option<String>,
+        /// Earliest message date (YYYY-MM-DD or RFC 3339)
+        #[arg(long)]
+        from: Option<String>,
+        /// Latest message date (YYYY-MM-DD or RFC 3339)
+        #[arg(long)]
+        to: Option<String>,
+        /// Maximum number of results
+        #[arg(short, long, default_value_t = 20)]
+        limit: usize,
+        /// Output structured JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Export a session as Markdown, HTML or a portable JSON bundle
+    Export {
+        /// Session key, e.g. cli:direct
+        key: String,
+        /// Output format: markdown, html or json
+        #[arg(short, long, default_value = "markdown")]
+        format: String,
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Keep secrets such as API keys in the export
+        #[arg(long)]
+        no_redact: bool,
+    },
+    /// Import a JSON session bundle
+    Import {
+        /// Bundle file produced by `sessions export --format json`
+        path: PathBuf,
+        /// Store under this key instead of `import:<bundled key>`
+        #[arg(long)]
+        key: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+#[command(rename_all = "kebab-case")]
+enum MemoryCommands {
+    /// Move the global memory files into a scope. Stop the gateway first.
+    MigrateScope {
+        /// Target scope: channel:<channel>, chat:<channel>:<chat_id> or sender:<channel>:<sender_id>
+        scope: String,
+    },
+}
+
+#[derive(Subcommand)]
+#[command(rename_all = "kebab-case")]
+enum SkillCommands {
+    /// Install a skill package from a directory, .zip/tarball or git URL
+    Install {
+        /// Package directory, archive path or git URL
+        source: String,
+        /// Branch or tag to check out for git sources
+        #[arg(long = "ref")]
+        reference: Option<String>,
+    },
+    /// Re-fetch installed packages from their recorded source
+    Update {
+        /// Only update this skill
+        name: Option<String>,
+    },
+    /// Remove a workspace skill
+    Remove {
+        name: String,
+        /// Remove even when other packages depend on it
+        #[arg(long)]
+        force: bool,
+    },
+    /// List skills with their versions and unmet requirements
+    List {
+        /// Output structured JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+#[command(rename_all = "kebab-case")]
+enum WorkflowCommands {
+    /// Run a YAML or JSON workflow with the local agent's provider and tools
+    Run {
+        /// Workflow file
+        file: PathBuf,
+        /// Workflow input as KEY=VALUE (repeatable)
+        #[arg(short, long = "input")]
+        inputs: Vec<String>,
+        /// Default model for neuron nodes
+        #[arg(short, long)]
+        model: Option<String>,
+        /// Print workflow events as JSON lines
+        #[arg(long)]
+        json: bool,
+    },
+    /// Check a workflow file without running it
+    Validate {
+        /// Workflow file
+        file: PathBuf,
+    },
 }
 
 #[derive(Args, Clone, Default)]
@@ -596,7 +781,120 @@ async fn main() -> Result<()> {
                 }
                 run_cron_run(&runtime, job_id, force).await?;
             }
+            CronCommands::Runs { job_id, limit } => {
+                if !structured_output {
+                    info!("Listing runs of cron job: {}", job_id);
+                }
+                run_cron_runs(&runtime, job_id, limit).await?;
+            }
+        },
+        Commands::Sessions { command } => match command {
+            SessionCommands::Search {
+                query,
+                channel,
+                role,
+                from,
+                to,
+                limit,
+                json,
+            } => {
+                let query = SessionSearchQuery {
+                    text: query,
+                    channel,
+                    session_key: None,
+                    role,
+                    from: from
+                        .as_deref()
+                        .map(|value| parse_date_bound(value, false))
+                        .transpose()?,
+                    to: to
+                        .as_deref()
+                        .map(|value| parse_date_bound(value, true))
+                        .transpose()?,
+                    limit: Some(limit),
+                };
+                run_sessions_search(&runtime, &query, json).await?;
+            }
+            SessionCommands::Export {
+                key,
+                format,
+                output,
+                no_redact,
+            } => {
+                let options = SessionExportOptions { redact: !no_redact };
+                run_sessions_export(&runtime, &key, format.parse()?, options, output).await?;
+            }
+            SessionCommands::Import { path, key } => {
+                run_sessions_import(&runtime, &path, key.as_deref()).await?;
+            }
+        },
+        Commands::Memory { command } => match command {
+            MemoryCommands::MigrateScope { scope } => {
+                run_memory_migrate_scope(&runtime, &scope)?;
+            }
         },
+        Commands::Skills { command } => match command {
+            SkillCommands::Install { source, reference } => {
+                if !structured_output {
+                    info!("Installing skill package from {}", source);
+                }
+                run_skills_install(&runtime, &source, reference.as_deref())?;
+            }
+            SkillCommands::Update { name } => {
+                if !structured_output {
+                    info!("Updating skill packages");
+                }
+                run_skills_update(&runtime, name.as_deref())?;
+            }
+            SkillCommands::Remove { name, force } => {
+                if !structured_output {
+                    info!("Removing skill: {}", name);
+                }
+                run_skills_remove(&runtime, &name, force)?;
+            }
+            SkillCommands::List { json } => {
+                run_skills_list(&runtime, json)?;
+            }
+        },
+        Commands::Workflow { command } => match command {
+            WorkflowCommands::Run {
+                file,
+                inputs,
+                model,
+                json,
+            } => {
+                run_workflow(
+                    &runtime,
+                    WorkflowRunOptions {
+                        file,
+                        inputs,
+                        model,
+                        json,
+                    },
+                )
+                .await?;
+            }
+            WorkflowCommands::Validate { file } => run_workflow_validate(&file)?,
+        },
+        Commands::McpServe {
+            transport,
+            host,
+            port,
+            model,
+            session,
+        } => {
+            run_mcp_serve(
+                &runtime,
+                McpServeOptions {
+                    transport,
+                    host,
+                    port,
+                    model,
+                    session,
+                },
+            )
+            .await?;
+        }
     }
 
     Ok(())
@@ -1051,9 +1349,13 @@ async fn run_tui(
         builtin: build_builtin_tools_config(&config),
         network: build_network_tool_config(&config),
         exec_timeout: config.tools.exec.timeout,
+        exec_sandbox: ExecSandboxConfig::from(&config.tools.exec),
+        shell_jobs: Arc::new(ShellJobManager::new()),
         restrict_to_workspace: config.tools.restrict_to_workspace,
         mcp_servers: config.tools.active_mcp_servers(),
+        mcp_manager: Arc::new(McpManager::new()),
         subagent_policy: SubagentPolicy::from(config.tools.subagent.clone()),
+        subagent_profiles: build_subagent_profiles(&config),
         cron_service: Some(Arc::new(CronService::new(runtime.cron_store_path(), None))),
         soul_context: SoulContextSettings {
             enabled: config.agents.soul.enabled,
@@ -1066,6 +1368,7 @@ async fn run_tui(
             context_budget_tokens: config.agents.defaults.context_budget_tokens as usize,
             reserve_tokens: config.agents.defaults.context_budget_reserve_tokens as usize,
             overflow_retry_enabled: config.agents.defaults.context_overflow_retry_enabled,
+            compaction_strategy: config.agents.defaults.context_compaction,
         },
         trace_logger: Some(build_runtime_trace_logger(&config.logging)),
         debug_logger: None,
@@ -1075,18 +1378,18 @@ async fn run_tui(
             frequent_change_threshold: config.agents.soul.frequent_change_threshold,
             boundary_confirmation_hint: config.agents.soul.boundary_confirmation_hint,
         },
+        memory: config.agents.memory.clone(),
+        sessions: config.agents.sessions.clone(),
+        skills: config.agents.skills.clone(),
     };
 
     let (runtime_control_tx, runtime_control_rx) = mpsc::unbounded_channel();
 
     // Initialize shared FileManager for attachment handling
-    let storage_path = dirs::data_local_dir()
-        .map(|p| p.join("agent-diva").join("files"))
-        .unwrap_or_else(|| PathBuf::from(".agent-diva/files"));
-    let file_config = FileConfig::with_path(&storage_path);
-    let file_manager = Arc::new(FileManager::new(file_config).await?);
+    let file_manager = Arc::new(open_file_manager().await?);
 
-    let mut agent = AgentLoop::with_tools(
+    let memory_provider = open_memory_provider(&config.agents.memory, &workspace).await?;
+    let mut agent = AgentLoop::with_tools_and_memory_provider(
         bus,
         provider,
         workspace,
@@ -1095,6 +1398,7 @@ async fn run_tui(
         tool_config,
         Some(runtime_control_rx),
         file_manager,
+        memory_provider,
     )
     .await
     .map_err(|e| anyhow::anyhow!("Failed to create agent loop: {}", e))?;
@@ -1352,6 +1656,12 @@ fn is_structured_output(command: &Commands) -> bool {
             | ProviderCommands::Models { json, .. }
             | ProviderCommands::Login { json, .. } => *json,
         },
+        Commands::Sessions {
+            command: SessionCommands::Search { json, .. },
+        } => *json,
+        Commands::Sessions {
+            command: SessionCommands::Export { output, .. },
+        } => output.is_none(),
         Commands::Config { command } => match command {
             ConfigCommands::Path(args)
             | ConfigCommands::Validate(args)
@@ -1359,6 +1669,9 @@ fn is_structured_output(command: &Commands) -> bool {
             ConfigCommands::Show { format } => matches!(format, ConfigOutputFormat::Json),
             _ => false,
         },
+        Commands::Workflow {
+            command: WorkflowCommands::Run { json, .. },
+        } => *json,
         _ => false,
     }
 }
@@ -1796,6 +2109,223 @@ async fn run_cron_remove(runtime: &CliRuntime, job_id: String) -> Result<()> {
     Ok(())
 }
 
+/// Search session transcripts
+async fn run_sessions_search(
+    runtime: &CliRuntime,
+    query: &SessionSearchQuery,
+    json: bool,
+) -> Result<()> {
+    let config = runtime.load_config()?;
+    let workspace = runtime.effective_workspace(&config);
+    let index = SessionSearchIndex::for_workspace(&workspace).await?;
+    let hits = index.search(query).await?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&hits)?);
+        return Ok(());
+    }
+    if hits.is_empty() {
+        println!("No matching messages.");
+        return Ok(());
+    }
+
+    for hit in hits {
+        println!(
+            "{} {} {}",
+            style(format!("{} #{}", hit.session_key, hit.message_index))
+                .bold()
+                .cyan(),
+            style(hit.timestamp.format("%Y-%m-%d %H:%M")).dim(),
+            style(&hit.role).yellow()
+        );
+        println!("  {}", hit.snippet.replace('\n', " "));
+    }
+
+    Ok(())
+}
+
+/// File store shared with the chat commands
+async fn open_file_manager() -> Result<FileManager> {
+    let storage_path = dirs::data_local_dir()
+        .map(|p| p.join("agent-diva").join("files"))
+        .unwrap_or_else(|| PathBuf::from(".agent-diva/files"));
+    Ok(FileManager::new(FileConfig::with_path(&storage_path)).await?)
+}
+
+async fn run_sessions_export(
+    runtime: &CliRuntime,
+    key: &str,
+    format: SessionExportFormat,
+    options: SessionExportOptions,
+    output: Option<PathBuf>,
+) -> Result<()> {
+    let config = runtime.load_config()?;
+    let workspace = runtime.effective_workspace(&config);
+    let mut sessions = SessionManager::open(&workspace, &config.agents.sessions)?;
+    let files = open_file_manager().await?;
+    let content = export_session(&mut sessions, Some(&files), key, format, options).await?;
+
+    match output {
+        Some(path) => {
+            std::fs::write(&path, content)?;
+            println!(
+                "{} Exported {} to {}",
+                style("✓").green(),
+                key,
+                path.display()
+            );
+        }
+        None => print!("{}", content),
+    }
+    Ok(())
+}
+
+async fn run_sessions_import(runtime: &CliRuntime, path: &Path, key: Option<&str>) -> Result<()> {
+    let config = runtime.load_config()?;
+    let workspace = runtime.effective_workspace(&config);
+    let bundle: SessionBundle = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+    let mut sessions = SessionManager::open(&workspace, &config.agents.sessions)?;
+    let files = open_file_manager().await?;
+    let session = import_bundle(&mut sessions, Some(&files), bundle, key).await?;
+
+    println!(
+        "{} Imported {} messages as {}",
+        style("✓").green(),
+        session.messages.len(),
+        style(&session.key).cyan()
+    );
+    Ok(())
+}
+
+/// Move global memory files into a scoped memory directory
+fn run_memory_migrate_scope(runtime: &CliRuntime, scope: &str) -> Result<()> {
+    let config = runtime.load_config()?;
+    let workspace = runtime.effective_workspace(&config);
+    let scope = MemoryScope::parse(scope)?;
+
+    let moved = migrate_global_memory(&workspace, &scope)?;
+    if moved.is_empty() {
+        println!("No global memory files to migrate.");
+        return Ok(());
+    }
+
+    println!(
+        "{} Moved {} file(s) to {}",
+        style("✓").green().bold(),
+        moved.len(),
+        scope.memory_dir(&workspace).display()
+    );
+    for name in moved {
+        println!("  - {}", name);
+    }
+
+    Ok(())
+}
+
+fn skill_service(runtime: &CliRuntime) -> Result<SkillService> {
+    let config = runtime.load_config()?;
+    Ok(SkillService::new(runtime.loader().clone())
+        .with_workspace(runtime.effective_workspace(&config)))
+}
+
+fn print_unmet_requirements(unmet: &[String]) {
+    for requirement in unmet {
+        println!("  {} missing {}", style("!").yellow().bold(), requirement);
+    }
+}
+
+fn run_skills_install(runtime: &CliRuntime, source: &str, reference: Option<&str>) -> Result<()> {
+    let outcome = skill_service(runtime)?.install_package(source, reference)?;
+    let version = outcome.skill.version.as_deref().unwrap_or("-");
+    match outcome.previous_version {
+        Some(previous) => println!(
+            "{} Replaced {} {} with {}",
+            style("✓").green().bold(),
+            outcome.skill.name,
+            previous,
+            version
+        ),
+        None => println!(
+            "{} Installed {} {}",
+            style("✓").green().bold(),
+            outcome.skill.name,
+            version
+        ),
+    }
+    print_unmet_requirements(&outcome.skill.unmet_requirements);
+    Ok(())
+}
+
+fn run_skills_update(runtime: &CliRuntime, name: Option<&str>) -> Result<()> {
+    let outcomes = skill_service(runtime)?.update_packages(name)?;
+    if outcomes.is_empty() {
+        println!("No skill packages installed.");
+        return Ok(());
+    }
+
+    for outcome in outcomes {
+        let to_version = outcome.to_version.as_deref().unwrap_or("-");
+        match outcome.status {
+            SkillUpdateStatus::Updated => println!(
+                "{} {} {} -> {}",
+                style("✓").green().bold(),
+                outcome.name,
+                outcome.from_version,
+                to_version
+            ),
+            SkillUpdateStatus::Unchanged => println!(
+                "{} {} {} is up to date",
+                style("-").dim(),
+                outcome.name,
+                outcome.from_version
+            ),
+            SkillUpdateStatus::Skipped | SkillUpdateStatus::Failed => println!(
+                "{} {}: {}",
+                style("✗").red(),
+                outcome.name,
+                outcome.message.as_deref().unwrap_or("not updated")
+            ),
+        }
+        print_unmet_requirements(&outcome.unmet_requirements);
+    }
+    Ok(())
+}
+
+fn run_skills_remove(runtime: &CliRuntime, name: &str, force: bool) -> Result<()> {
+    skill_service(runtime)?.remove_skill(name, force)?;
+    println!("{} Removed skill {}", style("✓").green().bold(), name);
+    Ok(())
+}
+
+fn run_skills_list(runtime: &CliRuntime, json: bool) -> Result<()> {
+    let skills = skill_service(runtime)?.list_skills()?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&skills)?);
+        return Ok(());
+    }
+    if skills.is_empty() {
+        println!("No skills found.");
+        return Ok(());
+    }
+
+    for skill in skills {
+        let marker = if skill.unmet_requirements.is_empty() {
+            style("✓").green()
+        } else {
+            style("!").yellow()
+        };
+        println!(
+            "{} {} {} {}",
+            marker,
+            style(&skill.name).bold().cyan(),
+            style(skill.version.as_deref().unwrap_or("-")).dim(),
+            style(skill.package_source.as_deref().unwrap_or(&skill.source)).dim()
+        );
+        print_unmet_requirements(&skill.unmet_requirements);
+    }
+    Ok(())
+}
+
 /// Enable or disable a cron job
 async fn run_cron_enable(runtime: &CliRuntime, job_id: String, enabled: bool) -> Result<()> {
     let store_path = runtime.cron_store_path();
@@ -1851,6 +2381,58 @@ async fn run_cron_run(runtime: &CliRuntime, job_id: String, force: bool) -> Resu
     Ok(())
 }
 
+/// Show the run history of a cron job
+async fn run_cron_runs(runtime: &CliRuntime, job_id: String, limit: usize) -> Result<()> {
+    // Read-only: the service is not started, so no job can fire from here.
+    let service = CronService::new(runtime.cron_store_path(), None);
+    if service.get_job(&job_id).await.is_none() {
+        println!("{} Job {} not found", style("✗").red(), job_id);
+        return Ok(());
+    }
+
+    let runs = service.list_runs(&job_id, limit).await;
+    if runs.is_empty() {
+        println!("No runs recorded for job {}.", job_id);
+        return Ok(());
+    }
+
+    println!("{}", style(format!("Runs of {}", job_id)).bold().cyan());
+    println!();
+    for run in runs {
+        use chrono::TimeZone;
+        let started = chrono::Local
+            .timestamp_millis_opt(run.started_at_ms)
+            .single()
+            .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|| "(invalid)".to_string());
+        let status = match run.status {
+            CronRunStatus::Ok => style("ok").green(),
+            CronRunStatus::Error => style("error").red(),
+            CronRunStatus::Cancelled => style("cancelled").yellow(),
+        };
+        let duration_secs = (run.finished_at_ms - run.started_at_ms) as f64 / 1000.0;
+
+        println!(
+            "  {} {} ({:?}, attempt {}, {:.1}s)",
+            started, status, run.trigger, run.attempt, duration_secs
+        );
+        println!("    Run: {}", run.run_id);
+        if let Some(trace_id) = &run.trace_id {
+            println!("    Trace: {}", trace_id);
+        }
+        println!("    Tool calls: {}", run.tool_calls);
+        if let Some(error) = &run.error {
+            println!("    Error: {}", error);
+        } else if let Some(response) = &run.response {
+            let preview: String = response.replace('\n', " ").chars().take(120).collect();
+            println!("    Response: {}", preview);
+        }
+        println!();
+    }
+
+    Ok(())
+}
+
 async fn run_tui_remote(api_url: Option<String>, session: Option<String>) -> Result<()> {
     let current_session = session.unwrap_or_else(|| "cli:tui:remote".to_string());
     let (request_tx, mut request_rx) = mpsc::unbounded_channel::<(String, String)>();
@@ -2064,6 +2646,25 @@ mod tests {
         assert!(!command_shows_startup_branding(&command));
     }
 
+    #[test]
+    fn mcp_serve_keeps_stdout_clean_only_for_stdio() {
+        let serve = |transport| Commands::McpServe {
+            transport,
+            host: "127.0.0.1".to_string(),
+            port: 3921,
+            model: None,
+            session: DEFAULT_MCP_SESSION.to_string(),
+        };
+
+        let stdio = serve(McpServeTransport::Stdio);
+        assert!(!command_writes_logs_to_terminal(&stdio));
+        assert!(!command_shows_startup_branding(&stdio));
+
+        let http = serve(McpServeTransport::Http);
+        assert!(command_writes_logs_to_terminal(&http));
+        assert!(command_shows_startup_branding(&http));
+    }
+
     #[test]
     fn gateway_debug_run_is_detected_only_for_run_debug() {
         assert!(gateway_debug_run(&Commands::Gateway {
@@ -0,0 +1,828 @@
+//! `agent-diva mcp-serve`: expose the local agent to other MCP hosts.
+//!
+//! Tools are taken from the agent loop's own `ToolRegistry`, so filesystem and
+//! exec tools keep the workspace `SecurityPolicy` and sandbox they were built
+//! with. Memory files of the server's memory scope, the facts of the `sqlite`
+//! memory backend and session transcripts are published as resources, and the
+//! `chat` tool runs a full agent turn in a dedicated session.
+
+use crate::chat_commands::build_local_cli_agent;
+use crate::cli_runtime::{session_channel_and_chat_id, CliRuntime};
+use agent_diva_agent::AgentLoop;
+use agent_diva_core::config::{AgentMemoryConfig, AgentSessionsConfig, MemoryBackend};
+use agent_diva_core::memory::{
+    HashingEmbedder, MemoryFactKind, MemoryManager, MemoryScope, SqliteMemoryProvider,
+};
+use agent_diva_core::session::{Session, SessionManager};
+use agent_diva_tools::{ToolCallContext, ToolRegistry};
+use anyhow::Result;
+use async_trait::async_trait;
+use rust_mcp_sdk::{
+    mcp_server::{
+        hyper_server, server_runtime, HyperServerOptions, McpServerOptions, ServerHandler,
+        ToMcpServerHandler,
+    },
+    schema::{
+        CallToolError, CallToolRequestParams, CallToolResult, Implementation, InitializeResult,
+        ListResourcesResult, ListToolsResult, PaginatedRequestParams, ReadResourceContent,
+        ReadResourceRequestParams, ReadResourceResult, Resource, RpcError, ServerCapabilities,
+        ServerCapabilitiesResources, ServerCapabilitiesTools, TextContent, TextResourceContents,
+        Tool, LATEST_PROTOCOL_VERSION,
+    },
+    McpServer, StdioTransport, TransportOptions,
+};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::info;
+
+/// Name of the tool that runs a full agent turn.
+pub const CHAT_TOOL_NAME: &str = "chat";
+/// Session key used by the `chat` tool unless overridden.
+pub const DEFAULT_MCP_SESSION: &str = "mcp:serve";
+
+const MEMORY_URI_PREFIX: &str = "agent-diva://memory/";
+/// Stored facts of the `sqlite` memory backend
+const FACTS_URI: &str = "agent-diva://facts";
+/// Most facts published in the facts resource, newest first
+const MAX_PUBLISHED_FACTS: usize = 500;
+const SESSION_URI_PREFIX: &str = "agent-diva://sessions/";
+
+/// Transport used by `agent-diva mcp-serve`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum McpServeTransport {
+    /// JSON-RPC over stdin/stdout (for hosts that launch the process)
+    Stdio,
+    /// Streamable HTTP (with legacy SSE) on `--host`/`--port`
+    Http,
+}
+
+/// Options for [`run_mcp_serve`].
+#[derive(Debug, Clone)]
+pub struct McpServeOptions {
+    pub transport: McpServeTransport,
+    pub host: String,
+    pub port: u16,
+    pub model: Option<String>,
+    pub session: String,
+}
+
+/// MCP server handler backed by the local agent.
+pub struct DivaMcpServer {
+    tools: ToolRegistry,
+    workspace: PathBuf,
+    session_key: String,
+    sessions_config: AgentSessionsConfig,
+    memory_config: AgentMemoryConfig,
+    /// Context injected into every tool call; callers cannot supply their own.
+    tool_context: ToolCallContext,
+    agent: Option<Mutex<AgentLoop>>,
+}
+
+impl DivaMcpServer {
+    /// Serve `tools` plus the memory and sessions of `workspace`.
+    ///
+    /// The `chat` tool is only published once an agent is attached with
+    /// [`Self::with_agent`].
+    pub fn new(tools: ToolRegistry, workspace: PathBuf, session_key: impl Into<String>) -> Self {
+        let session_key = session_key.into();
+        let (channel, chat_id) = session_channel_and_chat_id(&session_key);
+        let tool_context =
+            ToolCallContext::new(channel, chat_id).with_session_key(session_key.clone());
+        Self {
+            tools,
+            workspace,
+            session_key,
+            sessions_config: AgentSessionsConfig::default(),
+            memory_config: AgentMemoryConfig::default(),
+            tool_context,
+            agent: None,
+        }
+    }
+
+    /// Build a server publishing the agent's own tools and a `chat` tool.
+    pub fn from_agent(
+        agent: AgentLoop,
+        workspace: PathBuf,
+        session_key: impl Into<String>,
+    ) -> Self {
+        let source = agent.tools();
+        let mut tools = ToolRegistry::with_timeout_secs(source.timeout_secs());
+        for name in source.tool_names() {
+            if let Some(tool) = source.get(&name) {
+                tools.register(tool);
+            }
+        }
+        Self::new(tools, workspace, session_key).with_agent(agent)
+    }
+
+    pub fn with_agent(mut self, agent: AgentLoop) -> Self {
+        self.agent = Some(Mutex::new(agent));
+        self
+    }
+
+    /// Read sessions from the configured backend instead of JSONL files.
+    pub fn with_sessions_config(mut self, config: AgentSessionsConfig) -> Self {
+        self.sessions_config = config;
+        self
+    }
+
+    /// Run tools with `scope` as their memory scope and publish its memory.
+    pub fn with_memory_scope(mut self, scope: MemoryScope) -> Self {
+        self.tool_context = self.tool_context.with_memory_scope(scope);
+        self
+    }
+
+    /// Publish the facts of the configured memory backend.
+    pub fn with_memory_config(mut self, config: AgentMemoryConfig) -> Self {
+        self.memory_config = config;
+        self
+    }
+
+    fn memory(&self) -> MemoryManager {
+        MemoryManager::for_scope(&self.workspace, &self.tool_context.memory_scope)
+    }
+
+    fn session_manager(&self) -> Result<SessionManager, String> {
+        SessionManager::open(&self.workspace, &self.sessions_config).map_err(|err| err.to_string())
+    }
+
+    fn server_details(&self) -> InitializeResult {
+        InitializeResult {
+            server_info: Implementation {
+                name: "agent-diva".into(),
+                version: env!("CARGO_PKG_VERSION").into(),
+                title: Some("Agent Diva".into()),
+                description: Some("Agent Diva tools, memory and sessions over MCP".into()),
+                icons: vec![],
+                website_url: None,
+            },
+            capabilities: ServerCapabilities {
+                tools: Some(ServerCapabilitiesTools { list_changed: None }),
+                resources: Some(ServerCapabilitiesResources {
+                    list_changed: None,
+                    subscribe: None,
+                }),
+                ..Default::default()
+            },
+            protocol_version: LATEST_PROTOCOL_VERSION.into(),
+            instructions: Some(format!(
+                "Use '{}' to ask the agent; other tools run directly in its workspace.",
+                CHAT_TOOL_NAME
+            )),
+            meta: None,
+        }
+    }
+
+    /// Tool definitions in MCP form, `chat` first when an agent is attached.
+    pub fn list_tools(&self) -> Vec<Tool> {
+        let mut tools = Vec::new();
+        if self.agent.is_some() {
+            tools.push(mcp_tool(
+                CHAT_TOOL_NAME,
+                "Send a message to the agent and return its reply. Runs a full agent turn \
+                 (memory, skills and tools) in a dedicated session.",
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "message": {"type": "string", "description": "Message for the agent"}
+                    },
+                    "required": ["message"]
+                }),
+            ));
+        }
+        let mut names = self.tools.tool_names();
+        names.sort();
+        for name in names {
+            if self.agent.is_some() && name == CHAT_TOOL_NAME {
+                continue;
+            }
+            // Skill tools only run inside a turn that activated their skill.
+            if let Some(tool) = self.tools.get(&name).filter(|tool| tool.scope().is_none()) {
+                tools.push(mcp_tool(&name, tool.description(), tool.parameters()));
+            }
+        }
+        tools
+    }
+
+    /// Run a published tool. Registry errors come back as `is_error` results.
+    ///
+    /// Caller-supplied `context_*` arguments are replaced by the server's own.
+    pub async fn call_tool(&self, name: &str, mut arguments: Value) -> Option<CallToolResult> {
+        if name == CHAT_TOOL_NAME {
+            if let Some(agent) = &self.agent {
+                return Some(self.chat(agent, &arguments).await);
+            }
+        }
+        if !self.tools.has(name) {
+            return None;
+        }
+        self.tool_context.apply(name, &mut arguments);
+        let output = self.tools.execute_outside_turn(name, arguments).await;
+        let is_error = output.starts_with("Error");
+        Some(text_result(output, is_error))
+    }
+
+    async fn chat(&self, agent: &Mutex<AgentLoop>, arguments: &Value) -> CallToolResult {
+        let Some(message) = arguments
+            .get("message")
+            .and_then(Value::as_str)
+            .filter(|message| !message.trim().is_empty())
+        else {
+            return text_result("Error: 'message' is required".to_string(), true);
+        };
+        let (channel, chat_id) = session_channel_and_chat_id(&self.session_key);
+        let mut agent = agent.lock().await;
+        match agent
+            .process_direct(message, self.session_key.as_str(), channel, chat_id)
+            .await
+        {
+            Ok(reply) => text_result(reply, false),
+            Err(err) => text_result(format!("Error: agent turn failed: {}", err), true),
+        }
+    }
+
+    /// Memory files, stored facts and session transcripts.
+    pub fn list_resources(&self) -> Vec<Resource> {
+        let mut resources = Vec::new();
+        for file in self.memory().list_files() {
+            resources.push(Resource {
+                uri: format!("{}{}", MEMORY_URI_PREFIX, file.name),
+                name: format!("memory/{}", file.name),
+                description: Some(memory_description(&file.name)),
+                mime_type: Some("text/markdown".to_string()),
+                size: Some(file.size as i64),
+                annotations: None,
+                icons: vec![],
+                meta: None,
+                title: None,
+            });
+        }
+        if self.memory_config.backend == MemoryBackend::Sqlite {
+            resources.push(Resource {
+                uri: FACTS_URI.to_string(),
+                name: "memory/facts".to_string(),
+                description: Some("Long-term memory facts".to_string()),
+                mime_type: Some("text/markdown".to_string()),
+                size: None,
+                annotations: None,
+                icons: vec![],
+                meta: None,
+                title: None,
+            });
+        }
+        let sessions = self
+            .session_manager()
+            .map(|sessions| sessions.list_sessions())
+            .unwrap_or_default();
+        for session in sessions {
+            resources.push(Resource {
+                uri: format!("{}{}", SESSION_URI_PREFIX, session.key),
+                name: format!("session/{}", session.key),
+                description: Some(match session.updated_at {
+                    Some(updated) => format!("Session transcript (updated {})", updated),
+                    None => "Session transcript".to_string(),
+                }),
+                mime_type: Some("text/plain".to_string()),
+                size: None,
+                annotations: None,
+                icons: vec![],
+                meta: None,
+                title: None,
+            });
+        }
+        resources
+    }
+
+    /// Read a memory file, render the stored facts or a session transcript.
+    pub async fn read_resource(&self, uri: &str) -> Result<String, String> {
+        if uri == FACTS_URI {
+            return self.render_facts().await;
+        }
+        if let Some(name) = uri.strip_prefix(MEMORY_URI_PREFIX) {
+            // Names are checked against the memory file layout, ruling out `..`.
+            return self
+                .memory()
+                .read_file(name)
+                .map(|snapshot| snapshot.content)
+                .map_err(|err| err.to_string());
+        }
+        if let Some(key) = uri.strip_prefix(SESSION_URI_PREFIX) {
+            let mut sessions = self.session_manager()?;
+            let session = sessions
+                .get_or_load(key)
+                .map_err(|err| err.to_string())?
+                .ok_or_else(|| format!("session '{}' not found", key))?;
+            return Ok(render_transcript(session));
+        }
+        Err(format!("unknown resource '{}'", uri))
+    }
+
+    /// Facts of the `sqlite` backend in the server's memory scope.
+    async fn render_facts(&self) -> Result<String, String> {
+        if self.memory_config.backend != MemoryBackend::Sqlite {
+            return Err("the memory backend does not store facts".to_string());
+        }
+        let provider = SqliteMemoryProvider::open_scope(
+            &self.workspace,
+            &self.tool_context.memory_scope,
+            Arc::new(HashingEmbedder::default()),
+            self.memory_config.recall_limit,
+        )
+        .await
+        .map_err(|err| err.to_string())?;
+        let facts = provider
+            .store()
+            .recent_facts(MemoryFactKind::Fact, MAX_PUBLISHED_FACTS)
+            .await
+            .map_err(|err| err.to_string())?;
+        let mut out = String::from("# Long-term Memory\n");
+        for fact in facts {
+            out.push_str(&format!(
+                "- {} ({})\n",
+                fact.content,
+                fact.updated_at.format("%Y-%m-%d")
+            ));
+        }
+        Ok(out)
+    }
+}
+
+#[async_trait]
+impl ServerHandler for DivaMcpServer {
+    async fn handle_list_tools_request(
+        &self,
+        _params: Option<PaginatedRequestParams>,
+        _runtime: Arc<dyn McpServer>,
+    ) -> std::result::Result<ListToolsResult, RpcError> {
+        Ok(ListToolsResult {
+            tools: self.list_tools(),
+            meta: None,
+            next_cursor: None,
+        })
+    }
+
+    async fn handle_call_tool_request(
+        &self,
+        params: CallToolRequestParams,
+        _runtime: Arc<dyn McpServer>,
+    ) -> std::result::Result<CallToolResult, CallToolError> {
+        let arguments = Value::Object(params.arguments.unwrap_or_default());
+        self.call_tool(&params.name, arguments)
+            .await
+            .ok_or_else(|| CallToolError::unknown_tool(params.name))
+    }
+
+    async fn handle_list_resources_request(
+        &self,
+        _params: Option<PaginatedRequestParams>,
+        _runtime: Arc<dyn McpServer>,
+    ) -> std::result::Result<ListResourcesResult, RpcError> {
+        Ok(ListResourcesResult {
+            resources: self.list_resources(),
+            meta: None,
+            next_cursor: None,
+        })
+    }
+
+    async fn handle_read_resource_request(
+        &self,
+        params: ReadResourceRequestParams,
+        _runtime: Arc<dyn McpServer>,
+    ) -> std::result::Result<ReadResourceResult, RpcError> {
+        let text = self
+            .read_resource(&params.uri)
+            .await
+            .map_err(|err| RpcError::invalid_params().with_message(err))?;
+        let mime_type = if params.uri.starts_with(MEMORY_URI_PREFIX) || params.uri == FACTS_URI {
+            "text/markdown"
+        } else {
+            "text/plain"
+        };
+        Ok(ReadResourceResult {
+            contents: vec![ReadResourceContent::TextResourceContents(
+                TextResourceContents {
+                    uri: params.uri,
+                    mime_type: Some(mime_type.to_string()),
+                    text,
+                    meta: None,
+                },
+            )],
+            meta: None,
+        })
+    }
+}
+
+/// Build the local agent and serve it over the selected transport until the
+/// client disconnects (stdio) or the process is stopped (HTTP).
+pub async fn run_mcp_serve(runtime: &CliRuntime, options: McpServeOptions) -> Result<()> {
+    if options.transport == McpServeTransport::Http && !is_loopback_host(&options.host) {
+        anyhow::bail!(
+            "The MCP HTTP transport has no authentication and only binds to loopback addresses \
+             (got '{}'); put an authenticating proxy in front of it for remote access",
+            options.host
+        );
+    }
+    let (config, _model, agent, _runtime_control_tx) =
+        build_local_cli_agent(runtime, options.model, false).await?;
+    let workspace = runtime.effective_workspace(&config);
+    // Tools see the same memory scope the `chat` tool's turns resolve to.
+    let (channel, chat_id) = session_channel_and_chat_id(&options.session);
+    let memory_scope = MemoryScope::resolve(
+        config.agents.memory.scope_policy(channel),
+        channel,
+        chat_id,
+        "user",
+    );
+    let server = DivaMcpServer::from_agent(agent, workspace, options.session.clone())
+        .with_sessions_config(config.agents.sessions.clone())
+        .with_memory_config(config.agents.memory.clone())
+        .with_memory_scope(memory_scope);
+    let details = server.server_details();
+
+    match options.transport {
+        McpServeTransport::Stdio => {
+            let transport = StdioTransport::new(TransportOptions::default())
+                .map_err(|err| anyhow::anyhow!("Failed to open stdio transport: {}", err))?;
+            let server = server_runtime::create_server(McpServerOptions {
+                server_details: details,
+                transport,
+                handler: server.to_mcp_server_handler(),
+                task_store: None,
+                client_task_store: None,
+                message_observer: None,
+            });
+            server
+                .start()
+                .await
+                .map_err(|err| anyhow::anyhow!("MCP stdio server failed: {}", err))
+        }
+        McpServeTransport::Http => {
+            info!(
+                "Serving MCP over HTTP at http://{}:{}/mcp",
+                options.host, options.port
+            );
+            let server = hyper_server::create_server(
+                details,
+                server.to_mcp_server_handler(),
+                HyperServerOptions {
+                    // Reject requests whose Host is not this loopback address so
+                    // web pages cannot reach the server through DNS rebinding.
+                    allowed_hosts: Some(loopback_authorities(&options.host, options.port)),
+                    dns_rebinding_protection: true,
+                    host: options.host,
+                    port: options.port,
+                    health_endpoint: Some("/health".into()),
+                    ..HyperServerOptions::default()
+                },
+            );
+            server
+                .start()
+                .await
+                .map_err(|err| anyhow::anyhow!("MCP HTTP server failed: {}", err))
+        }
+    }
+}
+
+/// Whether `host` only accepts connections from this machine.
+pub fn is_loopback_host(host: &str) -> bool {
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    host.eq_ignore_ascii_case("localhost")
+        || host
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+/// `Host` header values a local client may send for `host:port`.
+fn loopback_authorities(host: &str, port: u16) -> Vec<String> {
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let mut hosts: Vec<String> = ["localhost", "127.0.0.1", "[::1]"]
+        .iter()
+        .map(|name| format!("{}:{}", name, port))
+        .collect();
+    let own = if host.contains(':') {
+        format!("[{}]:{}", host, port)
+    } else {
+        format!("{}:{}", host, port)
+    };
+    if !hosts.iter().any(|known| known.eq_ignore_ascii_case(&own)) {
+        hosts.push(own);
+    }
+    hosts
+}
+
+fn mcp_tool(name: &str, description: &str, mut parameters: Value) -> Tool {
+    // MCP requires an object schema; tools without parameters often omit it.
+    if !parameters.is_object() {
+        parameters = json!({});
+    }
+    parameters["type"] = json!("object");
+    serde_json::from_value(json!({
+        "name": name,
+        "description": description,
+        "inputSchema": parameters,
+    }))
+    .unwrap_or_else(|_| {
+        serde_json::from_value(json!({
+            "name": name,
+            "description": description,
+            "inputSchema": {"type": "object"},
+        }))
+        .expect("minimal MCP tool definition")
+    })
+}
+
+fn text_result(text: String, is_error: bool) -> CallToolResult {
+    let mut result = CallToolResult::text_content(vec![TextContent::new(text, None, None)]);
+    if is_error {
+        result.is_error = Some(true);
+    }
+    result
+}
+
+fn memory_description(name: &str) -> String {
+    match name {
+        "MEMORY.md" => "Long-term memory".to_string(),
+        "HISTORY.md" => "Consolidated conversation history".to_string(),
+        other => format!("Daily note {}", other.trim_end_matches(".md")),
+    }
+}
+
+fn render_transcript(session: &Session) -> String {
+    let mut out = format!("# Session {}\n", session.key);
+    for message in &session.messages {
+        let role = match &message.name {
+            Some(name) if message.role == "tool" => format!("tool:{}", name),
+            _ => message.role.clone(),
+        };
+        out.push_str(&format!(
+            "\n[{}] {}\n{}\n",
+            message.timestamp.to_rfc3339(),
+            role,
+            message.content
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agent_diva_tools::{Tool, ToolError};
+    use rust_mcp_sdk::schema::ContentBlock;
+    use tempfile::TempDir;
+
+    struct EchoTool;
+
+    #[async_trait]
+    impl Tool for EchoTool {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        fn description(&self) -> &str {
+            "Echo the text back"
+        }
+
+        fn parameters(&self) -> Value {
+            json!({
+                "type": "object",
+                "properties": {"text": {"type": "string"}},
+                "required": ["text"]
+            })
+        }
+
+        async fn execute(&self, args: Value) -> agent_diva_tools::Result<String> {
+            match args.get("text").and_then(Value::as_str) {
+                Some(text) => Ok(text.to_string()),
+                None => Err(ToolError::InvalidArguments("text is required".to_string())),
+            }
+        }
+    }
+
+    /// Returns its arguments so tests can see what the tool received.
+    struct ArgsTool;
+
+    #[async_trait]
+    impl Tool for ArgsTool {
+        fn name(&self) -> &str {
+            "memory_args"
+        }
+
+        fn description(&self) -> &str {
+            "Return the arguments"
+        }
+
+        fn parameters(&self) -> Value {
+            json!({"type": "object", "properties": {}})
+        }
+
+        async fn execute(&self, args: Value) -> agent_diva_tools::Result<String> {
+            Ok(args.to_string())
+        }
+    }
+
+    /// A tool that belongs to the `pdf` skill.
+    struct SkillTool;
+
+    #[async_trait]
+    impl Tool for SkillTool {
+        fn name(&self) -> &str {
+            "pdf_extract"
+        }
+
+        fn description(&self) -> &str {
+            "Extract text from a PDF"
+        }
+
+        fn parameters(&self) -> Value {
+            json!({"type": "object", "properties": {}})
+        }
+
+        fn scope(&self) -> Option<&str> {
+            Some("pdf")
+        }
+
+        async fn execute(&self, _args: Value) -> agent_diva_tools::Result<String> {
+            Ok("extracted".to_string())
+        }
+    }
+
+    fn server(workspace: &TempDir) -> DivaMcpServer {
+        let mut tools = ToolRegistry::new();
+        tools.register(Arc::new(EchoTool));
+        DivaMcpServer::new(tools, workspace.path().to_path_buf(), DEFAULT_MCP_SESSION)
+    }
+
+    #[tokio::test]
+    async fn registry_tools_are_published_and_callable() {
+        let workspace = TempDir::new().unwrap();
+        let server = server(&workspace);
+
+        let tools = server.list_tools();
+        assert_eq!(tools.len(), 1, "chat needs an attached agent");
+        assert_eq!(tools[0].name, "echo");
+        assert_eq!(tools[0].input_schema.required, vec!["text".to_string()]);
+
+        let ok = server
+            .call_tool("echo", json!({"text": "hi"}))
+            .await
+            .unwrap();
+        assert_eq!(ok.is_error, None);
+
+        let failed = server.call_tool("echo", json!({})).await.unwrap();
+        assert_eq!(failed.is_error, Some(true));
+
+        assert!(server.call_tool("missing", json!({})).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn skill_tools_are_not_published_or_callable() {
+        let workspace = TempDir::new().unwrap();
+        let mut tools = ToolRegistry::new();
+        tools.register(Arc::new(EchoTool));
+        tools.register(Arc::new(SkillTool));
+        let server = DivaMcpServer::new(tools, workspace.path().to_path_buf(), "mcp:serve");
+
+        let names: Vec<String> = server.list_tools().into_iter().map(|t| t.name).collect();
+        assert_eq!(names, vec!["echo".to_string()]);
+        let refused = server.call_tool("pdf_extract", json!({})).await.unwrap();
+        assert_eq!(refused.is_error, Some(true));
+    }
+
+    #[tokio::test]
+    async fn tool_calls_get_the_server_context() {
+        let workspace = TempDir::new().unwrap();
+        let mut tools = ToolRegistry::new();
+        tools.register(Arc::new(ArgsTool));
+        let server = DivaMcpServer::new(tools, workspace.path().to_path_buf(), "mcp:serve")
+            .with_memory_scope(MemoryScope::Chat {
+                channel: "mcp".to_string(),
+                chat_id: "serve".to_string(),
+            });
+
+        let result = server
+            .call_tool(
+                "memory_args",
+                json!({
+                    "query": "tea",
+                    "context_memory_scope": {"kind": "global"},
+                    "context_session_key": "telegram:victim"
+                }),
+            )
+            .await
+            .unwrap();
+        let Some(ContentBlock::TextContent(text)) = result.content.first() else {
+            panic!("expected text content");
+        };
+        let args: Value = serde_json::from_str(&text.text).unwrap();
+        assert_eq!(args["query"], "tea");
+        assert_eq!(args["context_memory_scope"]["kind"], "chat");
+        assert_eq!(args["context_memory_scope"]["chat_id"], "serve");
+        assert_eq!(args["context_session_key"], "mcp:serve");
+    }
+
+    #[test]
+    fn http_transport_only_binds_loopback() {
+        assert!(is_loopback_host("127.0.0.1"));
+        assert!(is_loopback_host("localhost"));
+        assert!(is_loopback_host("::1"));
+        assert!(is_loopback_host("[::1]"));
+        assert!(!is_loopback_host("0.0.0.0"));
+        assert!(!is_loopback_host("192.168.1.10"));
+        assert!(!is_loopback_host("example.com"));
+
+        let hosts = loopback_authorities("::1", 3921);
+        assert!(hosts.contains(&"[::1]:3921".to_string()));
+        assert!(hosts.contains(&"localhost:3921".to_string()));
+        assert_eq!(hosts.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn memory_and_sessions_are_published_as_resources() {
+        let workspace = TempDir::new().unwrap();
+        let memory_dir = workspace.path().join("memory");
+        std::fs::create_dir_all(&memory_dir).unwrap();
+        std::fs::write(memory_dir.join("MEMORY.md"), "likes tea").unwrap();
+
+        let mut sessions = SessionManager::new(workspace.path());
+        let session = sessions.get_or_create("telegram:42").unwrap();
+        session.add_message("user", "hello");
+        session.add_message("assistant", "hi there");
+        let session = session.clone();
+        sessions.save(&session).unwrap();
+
+        let server = server(&workspace);
+        let uris: Vec<String> = server
+            .list_resources()
+            .into_iter()
+            .map(|resource| resource.uri)
+            .collect();
+        assert!(uris.contains(&"agent-diva://memory/MEMORY.md".to_string()));
+        assert!(uris.contains(&"agent-diva://sessions/telegram:42".to_string()));
+
+        assert_eq!(
+            server
+                .read_resource("agent-diva://memory/MEMORY.md")
+                .await
+                .unwrap(),
+            "likes tea"
+        );
+        let transcript = server
+            .read_resource("agent-diva://sessions/telegram:42")
+            .await
+            .unwrap();
+        assert!(transcript.contains("user\nhello"));
+        assert!(transcript.contains("assistant\nhi there"));
+    }
+
+    #[tokio::test]
+    async fn memory_resources_reject_unlisted_paths() {
+        let workspace = TempDir::new().unwrap();
+        std::fs::write(workspace.path().join("secret.md"), "nope").unwrap();
+        let server = server(&workspace);
+
+        assert!(server
+            .read_resource("agent-diva://memory/../secret.md")
+            .await
+            .is_err());
+        assert!(server.read_resource("file:///etc/passwd").await.is_err());
+        assert!(server.read_resource(FACTS_URI).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn memory_resources_follow_scope_and_backend() {
+        let workspace = TempDir::new().unwrap();
+        let scope = MemoryScope::Chat {
+            channel: "mcp".to_string(),
+            chat_id: "serve".to_string(),
+        };
+        std::fs::create_dir_all(workspace.path().join("memory")).unwrap();
+        std::fs::write(workspace.path().join("memory/MEMORY.md"), "global notes").unwrap();
+        MemoryManager::for_scope(workspace.path(), &scope)
+            .write_file("MEMORY.md", "- Scoped: likes tea\n", None)
+            .unwrap();
+
+        let server = server(&workspace)
+            .with_memory_scope(scope)
+            .with_memory_config(AgentMemoryConfig {
+                backend: MemoryBackend::Sqlite,
+                ..AgentMemoryConfig::default()
+            });
+        assert_eq!(
+            server
+                .read_resource("agent-diva://memory/MEMORY.md")
+                .await
+                .unwrap(),
+            "- Scoped: likes tea\n"
+        );
+        assert!(server
+            .list_resources()
+            .iter()
+            .any(|resource| resource.uri == FACTS_URI));
+        let facts = server.read_resource(FACTS_URI).await.unwrap();
+        assert!(facts.contains("likes tea"), "{}", facts);
+        assert!(!facts.contains("global notes"));
+    }
+}
@@ -0,0 +1,195 @@
+//! `agent-diva workflow`: run and check workflow files locally.
+//!
+//! Neuron nodes use the configured provider and tool nodes call the same
+//! `ToolRegistry` the local agent is built with.
+
+use crate::chat_commands::build_local_cli_agent;
+use crate::cli_runtime::{build_provider, CliRuntime};
+use agent_diva_core::memory::MemoryScope;
+use agent_diva_neuron::{NeuronEvent, Workflow, WorkflowEvent, WorkflowExecutor, WorkflowTools};
+use agent_diva_tools::{ToolCallContext, ToolRegistry};
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use console::style;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// Options for [`run_workflow`].
+#[derive(Debug, Clone)]
+pub struct WorkflowRunOptions {
+    pub file: PathBuf,
+    /// `KEY=VALUE` pairs overriding the workflow's input defaults.
+    pub inputs: Vec<String>,
+    pub model: Option<String>,
+    /// Print events as JSON lines instead of progress text.
+    pub json: bool,
+}
+
+/// Conversation that workflow tool nodes run in.
+const WORKFLOW_SESSION: (&str, &str) = ("cli", "direct");
+
+/// Tool nodes backed by the local agent's registry.
+///
+/// Tool calls get the runtime context of the local CLI conversation; any
+/// `context_*` arguments written in the workflow are replaced.
+struct RegistryTools {
+    tools: ToolRegistry,
+    context: ToolCallContext,
+}
+
+#[async_trait]
+impl WorkflowTools for RegistryTools {
+    async fn call_tool(&self, name: &str, mut args: Value) -> Result<String, String> {
+        if !self.tools.has(name) {
+            return Err(format!("unknown tool '{}'", name));
+        }
+        self.context.apply(name, &mut args);
+        let output = self.tools.execute_outside_turn(name, args).await;
+        if output.starts_with("Error") {
+            Err(output)
+        } else {
+            Ok(output)
+        }
+    }
+}
+
+/// Parse `KEY=VALUE` arguments into workflow inputs.
+pub fn parse_workflow_inputs(pairs: &[String]) -> Result<BTreeMap<String, String>> {
+    let mut inputs = BTreeMap::new();
+    for pair in pairs {
+        let Some((key, value)) = pair.split_once('=') else {
+            bail!("Invalid input '{}': expected KEY=VALUE", pair);
+        };
+        let key = key.trim();
+        if key.is_empty() {
+            bail!("Invalid input '{}': the key is empty", pair);
+        }
+        inputs.insert(key.to_string(), value.to_string());
+    }
+    Ok(inputs)
+}
+
+/// Load a workflow and report whether it is valid.
+pub fn run_workflow_validate(file: &Path) -> Result<()> {
+    let workflow = Workflow::from_path(file)?;
+    println!(
+        "{} {} ({} nodes)",
+        style("✓").green(),
+        workflow.name,
+        workflow.nodes.len()
+    );
+    for (name, default) in &workflow.inputs {
+        println!("  input {} (default: {:?})", name, default);
+    }
+    Ok(())
+}
+
+/// Run a workflow against the local agent's provider and tools, printing
+/// progress as it goes and the workflow output at the end.
+pub async fn run_workflow(runtime: &CliRuntime, options: WorkflowRunOptions) -> Result<()> {
+    let workflow = Workflow::from_path(&options.file)?;
+    let inputs = parse_workflow_inputs(&options.inputs)?;
+    let (config, model, agent, _runtime_control_tx) =
+        build_local_cli_agent(runtime, options.model, false).await?;
+    let source = agent.tools();
+    let mut tools = ToolRegistry::with_timeout_secs(source.timeout_secs());
+    for name in source.tool_names() {
+        if let Some(tool) = source.get(&name) {
+            tools.register(tool);
+        }
+    }
+    let (channel, chat_id) = WORKFLOW_SESSION;
+    let context = ToolCallContext::new(channel, chat_id).with_memory_scope(MemoryScope::resolve(
+        config.agents.memory.scope_policy(channel),
+        channel,
+        chat_id,
+        "user",
+    ));
+    let provider = Arc::new(build_provider(&config, &model)?);
+    let executor = WorkflowExecutor::new(provider)
+        .with_tools(Arc::new(RegistryTools { tools, context }))
+        .with_model(model);
+
+    let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+    let json = options.json;
+    let printer = tokio::spawn(async move {
+        while let Some(event) = event_rx.recv().await {
+            if json {
+                if let Ok(line) = serde_json::to_string(&event) {
+                    println!("{}", line);
+                }
+            } else {
+                print_progress(&event);
+            }
+        }
+    });
+
+    let result = executor.run(&workflow, inputs, Some(event_tx)).await;
+    let _ = printer.await;
+    let run = result?;
+    if !json {
+        println!();
+        println!("{}", run.output.unwrap_or_default());
+    }
+    Ok(())
+}
+
+fn print_progress(event: &WorkflowEvent) {
+    match event {
+        WorkflowEvent::NodeStarted { node_id, attempt } if *attempt == 1 => {
+            eprintln!("{} {}", style("▶").cyan(), node_id);
+        }
+        WorkflowEvent::NodeRetrying {
+            node_id,
+            attempt,
+            error,
+        } => {
+            eprintln!(
+                "{} {} (retry {}): {}",
+                style("↻").yellow(),
+                node_id,
+                attempt,
+                error
+            );
+        }
+        WorkflowEvent::NodeCompleted { node_id, .. } => {
+            eprintln!("{} {}", style("✓").green(), node_id);
+        }
+        WorkflowEvent::NodeSkipped { node_id } => {
+            eprintln!("{} {} (skipped)", style("-").dim(), node_id);
+        }
+        WorkflowEvent::NodeFailed { node_id, error } => {
+            eprintln!("{} {}: {}", style("✗").red(), node_id, error);
+        }
+        WorkflowEvent::Neuron {
+            event: NeuronEvent::TextDelta { delta, .. },
+        } => {
+            eprint!("{}", style(delta).dim());
+            let _ = std::io::stderr().flush();
+        }
+        WorkflowEvent::Neuron {
+            event: NeuronEvent::Completed { .. },
+        } => eprintln!(),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_key_value_inputs() {
+        let inputs =
+            parse_workflow_inputs(&["topic=rust async".to_string(), "query=a=b".to_string()])
+                .unwrap();
+        assert_eq!(inputs["topic"], "rust async");
+        assert_eq!(inputs["query"], "a=b");
+        assert!(parse_workflow_inputs(&["missing".to_string()]).is_err());
+        assert!(parse_workflow_inputs(&["=x".to_string()]).is_err());
+    }
+}
@@ -45,6 +45,9 @@ pub enum AgentEvent {
 pub struct AgentBusEvent {
     pub channel: String,
     pub chat_id: String,
+    /// Trace id of the inbound message being handled, when there is one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_id: Option<String>,
     pub event: AgentEvent,
 }
 
@@ -25,6 +25,8 @@ pub struct MessageBus {
     /// Inbound messages from channels
     inbound_tx: mpsc::UnboundedSender<InboundMessage>,
     inbound_rx: Arc<RwLock<Option<mpsc::UnboundedReceiver<InboundMessage>>>>,
+    /// Copies of inbound messages for observers such as automations
+    inbound_tap: broadcast::Sender<InboundMessage>,
     /// Outbound messages to channels
     outbound_tx: mpsc::UnboundedSender<OutboundMessage>,
     outbound_rx: Arc<RwLock<Option<mpsc::UnboundedReceiver<OutboundMessage>>>>,
@@ -42,10 +44,12 @@ impl MessageBus {
         let (inbound_tx, inbound_rx) = mpsc::unbounded_channel();
         let (outbound_tx, outbound_rx) = mpsc::unbounded_channel();
         let (event_tx, _) = broadcast::channel(1024);
+        let (inbound_tap, _) = broadcast::channel(256);
 
         Self {
             inbound_tx,
             inbound_rx: Arc::new(RwLock::new(Some(inbound_rx))),
+            inbound_tap,
             outbound_tx,
             outbound_rx: Arc::new(RwLock::new(Some(outbound_rx))),
             subscribers: Arc::new(RwLock::new(HashMap::new())),
@@ -61,11 +65,34 @@ impl MessageBus {
         chat_id: impl Into<String>,
         event: AgentEvent,
     ) -> crate::Result<()> {
-        let bus_event = AgentBusEvent {
+        self.send_event(AgentBusEvent {
             channel: channel.into(),
             chat_id: chat_id.into(),
+            trace_id: None,
             event,
-        };
+        })
+    }
+
+    /// Publish an event produced while handling `msg`, tagged with the
+    /// message's trace id so waiters can tell concurrent turns apart
+    pub fn publish_message_event(
+        &self,
+        msg: &InboundMessage,
+        event: AgentEvent,
+    ) -> crate::Result<()> {
+        self.send_event(AgentBusEvent {
+            channel: msg.channel.clone(),
+            chat_id: msg.chat_id.clone(),
+            trace_id: msg
+                .metadata
+                .get("trace_id")
+                .and_then(|value| value.as_str())
+                .map(str::to_string),
+            event,
+        })
+    }
+
+    fn send_event(&self, bus_event: AgentBusEvent) -> crate::Result<()> {
         // We ignore the error if there are no receivers
         let _ = self.event_tx.send(bus_event);
         Ok(())
@@ -76,6 +103,11 @@ impl MessageBus {
         self.event_tx.subscribe()
     }
 
+    /// Observe inbound messages without consuming them
+    pub fn subscribe_inbound(&self) -> broadcast::Receiver<InboundMessage> {
+        self.inbound_tap.subscribe()
+    }
+
     /// Take the inbound receiver (can only be called once)
     pub async fn take_inbound_receiver(&self) -> Option<mpsc::UnboundedReceiver<InboundMessage>> {
         self.inbound_rx.write().await.take()
@@ -88,6 +120,9 @@ impl MessageBus {
 
     /// Publish a message from a channel to the agent
     pub fn publish_inbound(&self, msg: InboundMessage) -> crate::Result<()> {
+        if self.inbound_tap.receiver_count() > 0 {
+            let _ = self.inbound_tap.send(msg.clone());
+        }
         self.inbound_tx
             .send(msg)
             .map_err(|_| crate::Error::Channel("Inbound channel closed".to_string()))
@@ -196,6 +231,38 @@ mod tests {
         assert!(received.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_message_events_carry_the_trace_id() {
+        let bus = MessageBus::new();
+        let mut events = bus.subscribe_events();
+        let msg = InboundMessage::new("test", "user1", "chat1", "Hello")
+            .with_metadata("trace_id", "tr_demo");
+        bus.publish_message_event(
+            &msg,
+            AgentEvent::FinalResponse {
+                content: "Hi".to_string(),
+            },
+        )
+        .unwrap();
+
+        let event = events.recv().await.unwrap();
+        assert_eq!(event.chat_id, "chat1");
+        assert_eq!(event.trace_id.as_deref(), Some("tr_demo"));
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_inbound_sees_copies() {
+        let bus = MessageBus::new();
+        let mut inbound_rx = bus.take_inbound_receiver().await.unwrap();
+        let mut tap = bus.subscribe_inbound();
+
+        let msg = InboundMessage::new("test", "user1", "chat1", "Hello");
+        assert!(bus.publish_inbound(msg).is_ok());
+
+        assert_eq!(tap.try_recv().unwrap().content, "Hello");
+        assert!(inbound_rx.try_recv().is_ok());
+    }
+
     #[tokio::test]
     async fn test_subscribe_outbound() {
         let bus = MessageBus::new();
@@ -1,7 +1,7 @@
 //! Configuration schema definitions
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// Root configuration for agent-diva
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -89,6 +89,15 @@ pub struct AgentsConfig {
     /// Soul and identity behavior
     #[serde(default)]
     pub soul: AgentSoulConfig,
+    /// Long-term memory backend
+    #[serde(default)]
+    pub memory: AgentMemoryConfig,
+    /// Conversation history storage
+    #[serde(default)]
+    pub sessions: AgentSessionsConfig,
+    /// Which skills are loaded into each turn
+    #[serde(default)]
+    pub skills: AgentSkillsConfig,
 }
 
 /// Default agent settings
@@ -119,6 +128,9 @@ pub struct AgentDefaults {
     /// Whether to retry once with stronger compaction after overflow-like errors.
     #[serde(default = "default_true")]
     pub context_overflow_retry_enabled: bool,
+    /// How over-budget history is compacted (`trim` or `summarize`).
+    #[serde(default)]
+    pub context_compaction: ContextCompactionStrategy,
 }
 
 impl Default for AgentDefaults {
@@ -134,10 +146,22 @@ impl Default for AgentDefaults {
             context_budget_tokens: default_context_budget_tokens(),
             context_budget_reserve_tokens: default_context_budget_reserve_tokens(),
             context_overflow_retry_enabled: true,
+            context_compaction: ContextCompactionStrategy::default(),
         }
     }
 }
 
+/// Strategy used when session history exceeds the context budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ContextCompactionStrategy {
+    /// Drop the oldest history messages until the request fits.
+    #[default]
+    Trim,
+    /// Replace the oldest turns with a cached, model-generated summary.
+    Summarize,
+}
+
 fn default_context_budget_tokens() -> u32 {
     24_000
 }
@@ -146,6 +170,140 @@ fn default_context_budget_reserve_tokens() -> u32 {
     4_000
 }
 
+/// Long-term memory settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentMemoryConfig {
+    /// Storage backend (`markdown` or `sqlite`).
+    #[serde(default)]
+    pub backend: MemoryBackend,
+    /// Maximum facts recalled into a turn by the semantic backend.
+    #[serde(default = "default_memory_recall_limit")]
+    pub recall_limit: usize,
+    /// Default scope policy for channels without an entry in `channel_scopes`.
+    #[serde(default)]
+    pub scope: MemoryScopePolicy,
+    /// Per-channel scope policy overrides, keyed by channel name.
+    #[serde(default)]
+    pub channel_scopes: HashMap<String, MemoryScopePolicy>,
+}
+
+impl Default for AgentMemoryConfig {
+    fn default() -> Self {
+        Self {
+            backend: MemoryBackend::default(),
+            recall_limit: default_memory_recall_limit(),
+            scope: MemoryScopePolicy::default(),
+            channel_scopes: HashMap::new(),
+        }
+    }
+}
+
+impl AgentMemoryConfig {
+    /// Scope policy that applies to messages from `channel`.
+    pub fn scope_policy(&self, channel: &str) -> MemoryScopePolicy {
+        self.channel_scopes
+            .get(channel)
+            .copied()
+            .unwrap_or(self.scope)
+    }
+}
+
+/// How a message is mapped to a memory scope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryScopePolicy {
+    /// One memory shared by every channel and chat.
+    #[default]
+    Global,
+    /// One memory per channel.
+    Channel,
+    /// One memory per chat (group or direct conversation).
+    Chat,
+    /// One memory per sender, shared across that sender's chats on a channel.
+    Sender,
+}
+
+/// Where long-term memory is stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryBackend {
+    /// `MEMORY.md`/`HISTORY.md`, loaded whole into the system prompt.
+    #[default]
+    Markdown,
+    /// SQLite fact store with hybrid keyword/vector recall per turn.
+    Sqlite,
+}
+
+/// Conversation history storage settings
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AgentSessionsConfig {
+    /// Storage backend (`jsonl` or `sqlite`).
+    #[serde(default)]
+    pub backend: SessionBackend,
+}
+
+/// Where session transcripts are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionBackend {
+    /// One JSONL file per session, rewritten on every save.
+    #[default]
+    Jsonl,
+    /// A single SQLite database; new messages are appended incrementally.
+    Sqlite,
+}
+
+fn default_memory_recall_limit() -> usize {
+    8
+}
+
+/// Per-turn skill selection settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentSkillsConfig {
+    /// How skill bodies reach the prompt (`relevance` or `summary`).
+    #[serde(default)]
+    pub activation: SkillActivationMode,
+    /// Maximum skills loaded per turn in addition to `always` skills.
+    #[serde(default = "default_skills_top_k")]
+    pub top_k: usize,
+    /// Minimum relevance score for a skill to be loaded.
+    #[serde(default = "default_skills_min_score")]
+    pub min_score: f32,
+    /// Add embedding similarity between the message and each skill to its score.
+    #[serde(default)]
+    pub embeddings: bool,
+}
+
+impl Default for AgentSkillsConfig {
+    fn default() -> Self {
+        Self {
+            activation: SkillActivationMode::default(),
+            top_k: default_skills_top_k(),
+            min_score: default_skills_min_score(),
+            embeddings: false,
+        }
+    }
+}
+
+/// How skills are offered to the model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SkillActivationMode {
+    /// Load the skills that best match the user message and list the rest by name.
+    #[default]
+    Relevance,
+    /// List every skill with its location and let the model read the files.
+    Summary,
+}
+
+fn default_skills_top_k() -> usize {
+    3
+}
+
+fn default_skills_min_score() -> f32 {
+    0.5
+}
+
 /// Soul/identity settings
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentSoulConfig {
@@ -927,6 +1085,28 @@ pub struct GatewayConfig {
     pub host: String,
     #[serde(default = "default_port")]
     pub port: u16,
+    #[serde(default)]
+    pub cron: CronConfig,
+}
+
+/// Cron scheduler configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CronConfig {
+    /// Finished runs kept per job in the run log; 0 disables it
+    #[serde(default = "default_cron_run_history")]
+    pub run_history: usize,
+}
+
+fn default_cron_run_history() -> usize {
+    crate::cron::DEFAULT_RUN_RETENTION
+}
+
+impl Default for CronConfig {
+    fn default() -> Self {
+        Self {
+            run_history: default_cron_run_history(),
+        }
+    }
 }
 
 fn default_host() -> String {
@@ -941,6 +1121,7 @@ impl Default for GatewayConfig {
         Self {
             host: default_host(),
             port: default_port(),
+            cron: CronConfig::default(),
         }
     }
 }
@@ -980,6 +1161,36 @@ pub struct SubagentToolsConfig {
     pub allow_web_search: bool,
     #[serde(default)]
     pub allow_mcp: bool,
+    /// Named setups the main agent can pick with the `spawn` tool's `profile` argument
+    #[serde(default)]
+    pub profiles: BTreeMap<String, SubagentProfileConfig>,
+}
+
+/// A named subagent setup
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SubagentProfileConfig {
+    /// When to use the profile; shown to the main agent
+    #[serde(default)]
+    pub description: String,
+    /// Instructions added to the subagent system prompt
+    #[serde(default)]
+    pub system_prompt: Option<String>,
+    /// Model to run; the main agent's model when unset
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Provider serving `model`; inferred from the model name when unset
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Tool names, `prefix*` patterns or groups (`filesystem`, `shell`, `web`, `mcp`).
+    /// Replaces the `allow_*` switches when set; empty keeps them.
+    #[serde(default)]
+    pub tools: Vec<String>,
+    /// Maximum tool iterations
+    #[serde(default)]
+    pub max_iterations: Option<usize>,
+    /// Wall-clock limit for the whole task in seconds
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
 }
 
 fn default_subagent_max_concurrent() -> usize {
@@ -1000,6 +1211,7 @@ impl Default for SubagentToolsConfig {
             allow_web_fetch: false,
             allow_web_search: false,
             allow_mcp: false,
+            profiles: BTreeMap::new(),
         }
     }
 }
@@ -1022,6 +1234,8 @@ pub struct BuiltInToolsConfig {
     pub mcp: bool,
     #[serde(default = "default_enabled")]
     pub attachment: bool,
+    #[serde(default = "default_enabled")]
+    pub memory: bool,
 }
 
 impl Default for BuiltInToolsConfig {
@@ -1035,6 +1249,7 @@ impl Default for BuiltInToolsConfig {
             cron: false,
             mcp: true,
             attachment: true,
+            memory: true,
         }
     }
 }
@@ -1062,9 +1277,40 @@ impl ToolsConfig {
     }
 }
 
+/// Transport used to reach an MCP server.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MCPTransport {
+    /// Launch `command` and talk JSON-RPC over stdin/stdout.
+    Stdio,
+    /// Legacy HTTP+SSE transport.
+    Sse,
+    /// Streamable HTTP transport (single endpoint, `Mcp-Session-Id` sessions).
+    StreamableHttp,
+}
+
+impl MCPTransport {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Stdio => "stdio",
+            Self::Sse => "sse",
+            Self::StreamableHttp => "streamable_http",
+        }
+    }
+}
+
+impl std::fmt::Display for MCPTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// MCP server connection configuration (stdio or HTTP)
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct MCPServerConfig {
+    /// Transport to use; inferred when unset (`command` -> stdio, `url` -> sse).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transport: Option<MCPTransport>,
     #[serde(default)]
     pub command: String,
     #[serde(default)]
@@ -1073,11 +1319,87 @@ pub struct MCPServerConfig {
     pub env: HashMap<String, String>,
     #[serde(default)]
     pub url: String,
+    /// Static HTTP headers for `sse`/`streamable_http`; values may reference `${ENV_VAR}`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
     /// Per-tool timeout in seconds (default: 30)
     #[serde(default = "default_tool_timeout")]
     pub tool_timeout: u64,
 }
 
+impl MCPServerConfig {
+    /// Explicit transport, or the one implied by `command`/`url`.
+    pub fn effective_transport(&self) -> Option<MCPTransport> {
+        if self.transport.is_some() {
+            self.transport
+        } else if !self.command.trim().is_empty() {
+            Some(MCPTransport::Stdio)
+        } else if !self.url.trim().is_empty() {
+            Some(MCPTransport::Sse)
+        } else {
+            None
+        }
+    }
+
+    /// Check that the fields required by the transport are present.
+    pub fn validate(&self) -> Result<(), String> {
+        let has_command = !self.command.trim().is_empty();
+        let has_url = !self.url.trim().is_empty();
+        match self.transport {
+            None if !has_command && !has_url => {
+                return Err("must set either command (stdio) or url (http)".to_string());
+            }
+            Some(MCPTransport::Stdio) if !has_command => {
+                return Err("transport 'stdio' requires command".to_string());
+            }
+            Some(transport @ (MCPTransport::Sse | MCPTransport::StreamableHttp)) if !has_url => {
+                return Err(format!("transport '{}' requires url", transport));
+            }
+            _ => {}
+        }
+        if !self.headers.is_empty() && self.effective_transport() == Some(MCPTransport::Stdio) {
+            return Err("headers are only supported for sse and streamable_http".to_string());
+        }
+        Ok(())
+    }
+
+    /// Headers with `${ENV_VAR}` references expanded.
+    pub fn resolved_headers(&self) -> Result<HashMap<String, String>, String> {
+        self.headers
+            .iter()
+            .map(|(name, value)| {
+                interpolate_env_vars(value)
+                    .map(|value| (name.clone(), value))
+                    .map_err(|err| format!("header '{}': {}", name, err))
+            })
+            .collect()
+    }
+}
+
+/// Expand `${NAME}` references from the process environment. Unset variables are an
+/// error so a missing token is reported instead of sending an empty credential.
+pub fn interpolate_env_vars(value: &str) -> Result<String, String> {
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after
+            .find('}')
+            .ok_or_else(|| format!("unterminated '${{' in '{}'", value))?;
+        let name = &after[..end];
+        if name.is_empty() {
+            return Err("empty variable name in '${}'".to_string());
+        }
+        let resolved =
+            std::env::var(name).map_err(|_| format!("environment variable {} is not set", name))?;
+        out.push_str(&resolved);
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
 fn default_tool_timeout() -> u64 {
     30
 }
@@ -1141,21 +1463,68 @@ impl Default for WebFetchConfig {
     }
 }
 
+/// Isolation backend used by the `exec` tool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExecSandboxMode {
+    /// Run commands directly on the host (regex guard only).
+    #[default]
+    None,
+    /// Run commands inside a bubblewrap (`bwrap`) container.
+    Bwrap,
+    /// Run commands under a Landlock ruleset with network namespace isolation.
+    Landlock,
+}
+
+impl ExecSandboxMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Bwrap => "bwrap",
+            Self::Landlock => "landlock",
+        }
+    }
+}
+
 /// Default execution timeout configuration for tool calls.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExecToolConfig {
     #[serde(default = "default_timeout")]
     pub timeout: u64,
+    /// Sandbox backend for shell commands (`none`, `bwrap` or `landlock`).
+    #[serde(default)]
+    pub sandbox: ExecSandboxMode,
+    /// Allow network access from sandboxed commands.
+    #[serde(default)]
+    pub sandbox_network: bool,
+    /// Address-space limit for sandboxed commands in MiB (0 disables the limit).
+    #[serde(default = "default_sandbox_memory_mb")]
+    pub sandbox_memory_mb: u64,
+    /// CPU time limit for sandboxed commands in seconds (0 disables the limit).
+    #[serde(default = "default_sandbox_cpu_secs")]
+    pub sandbox_cpu_secs: u64,
 }
 
 fn default_timeout() -> u64 {
     60
 }
 
+fn default_sandbox_memory_mb() -> u64 {
+    2048
+}
+
+fn default_sandbox_cpu_secs() -> u64 {
+    120
+}
+
 impl Default for ExecToolConfig {
     fn default() -> Self {
         Self {
             timeout: default_timeout(),
+            sandbox: ExecSandboxMode::default(),
+            sandbox_network: false,
+            sandbox_memory_mb: default_sandbox_memory_mb(),
+            sandbox_cpu_secs: default_sandbox_cpu_secs(),
         }
     }
 }
@@ -71,13 +71,8 @@ pub fn validate_config(config: &Config) -> crate::Result<()> {
     }
 
     for (name, server) in &config.tools.mcp_servers {
-        let has_stdio = !server.command.trim().is_empty();
-        let has_http = !server.url.trim().is_empty();
-        if !has_stdio && !has_http {
-            errors.push(format!(
-                "tools.mcp_servers.{} must set either command (stdio) or url (http)",
-                name
-            ));
+        if let Err(err) = server.validate() {
+            errors.push(format!("tools.mcp_servers.{} {}", name, err));
         }
     }
 
@@ -145,6 +140,37 @@ mod tests {
         assert!(err.to_string().contains("tools.mcp_servers.bad"));
     }
 
+    #[test]
+    fn test_validate_mcp_server_checks_explicit_transport() {
+        use super::super::schema::{MCPServerConfig, MCPTransport};
+
+        let mut config = Config::default();
+        config.tools.mcp_servers.insert(
+            "remote".to_string(),
+            MCPServerConfig {
+                transport: Some(MCPTransport::StreamableHttp),
+                command: "npx".to_string(),
+                ..MCPServerConfig::default()
+            },
+        );
+        let err = validate_config(&config).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("transport 'streamable_http' requires url"));
+
+        let mut config = Config::default();
+        config.tools.mcp_servers.insert(
+            "local".to_string(),
+            MCPServerConfig {
+                command: "npx".to_string(),
+                headers: [("Authorization".to_string(), "Bearer x".to_string())].into(),
+                ..MCPServerConfig::default()
+            },
+        );
+        let err = validate_config(&config).unwrap_err();
+        assert!(err.to_string().contains("headers are only supported"));
+    }
+
     #[test]
     fn test_validate_bocha_accepts_higher_max_results() {
         let mut config = Config::default();
@@ -0,0 +1,457 @@
+//! Event-triggered automation rules
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Component, Path};
+use std::sync::Mutex;
+
+use crate::cron::types::{
+    CronCatchUpPolicy, CronJob, CronJobState, CronPayload, CronPayloadKind, CronRetryPolicy,
+    CronRunSnapshot, CronSchedule,
+};
+
+/// Sender id of inbound messages produced by cron and automation runs.
+/// Messages from it never trigger rules, so a rule cannot feed itself.
+pub const AUTOMATION_SENDER: &str = "cron";
+
+/// Shown instead of webhook secrets in list and get views. Saving a rule with
+/// it keeps the stored secret.
+pub const REDACTED_SECRET: &str = "********";
+
+/// Distinct patterns kept compiled before the cache starts over.
+const PATTERN_CACHE_LIMIT: usize = 256;
+
+/// Compiled rule patterns, keyed by pattern text, so matching an event does
+/// not recompile every rule's regex.
+static PATTERNS: Lazy<Mutex<HashMap<String, Option<regex::Regex>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Event a rule listens for
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AutomationTrigger {
+    /// A new file appears in `dir`, relative to the workspace
+    FileCreated {
+        dir: String,
+        /// Regex the file name must match
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pattern: Option<String>,
+    },
+    /// An inbound message whose content matches `pattern`
+    Message {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        channel: Option<String>,
+        pattern: String,
+    },
+    /// An email from `from`; a value starting with `@` matches a whole domain
+    Email { from: String },
+    /// A POST to `/api/automations/:id/webhook`
+    Webhook {
+        /// Required in the `X-Automation-Secret` header; generated when the
+        /// rule is saved without one
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        secret: Option<String>,
+    },
+}
+
+impl AutomationTrigger {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            Self::FileCreated { dir, pattern } => {
+                if dir.trim().is_empty() {
+                    return Err("file_created triggers need a directory".to_string());
+                }
+                let path = Path::new(dir.trim());
+                if path.has_root()
+                    || path
+                        .components()
+                        .any(|part| !matches!(part, Component::Normal(_) | Component::CurDir))
+                {
+                    return Err(format!(
+                        "file_created directory '{}' must be relative to the workspace",
+                        dir
+                    ));
+                }
+                if let Some(pattern) = pattern {
+                    compile(pattern)?;
+                }
+                Ok(())
+            }
+            Self::Message { pattern, .. } => compile(pattern).map(|_| ()),
+            Self::Email { from } => {
+                if from.trim().is_empty() {
+                    return Err("email triggers need a sender".to_string());
+                }
+                Ok(())
+            }
+            Self::Webhook { .. } => Ok(()),
+        }
+    }
+
+    /// Give a webhook trigger a secret when it has none. A missing or
+    /// redacted secret keeps the one of `previous`, the trigger being replaced.
+    pub fn fill_webhook_secret(&mut self, previous: Option<&AutomationTrigger>) {
+        let Self::Webhook { secret } = self else {
+            return;
+        };
+        if secret
+            .as_deref()
+            .is_some_and(|value| !value.trim().is_empty() && value != REDACTED_SECRET)
+        {
+            return;
+        }
+        *secret = match previous {
+            Some(Self::Webhook {
+                secret: Some(previous),
+            }) => Some(previous.clone()),
+            _ => Some(uuid::Uuid::new_v4().simple().to_string()),
+        };
+    }
+
+    /// Whether `provided` is this webhook trigger's secret. Compared in
+    /// constant time; triggers without a secret accept nothing.
+    pub fn webhook_secret_matches(&self, provided: Option<&str>) -> bool {
+        match (self, provided) {
+            (
+                Self::Webhook {
+                    secret: Some(secret),
+                },
+                Some(provided),
+            ) => constant_time_eq(secret.as_bytes(), provided.as_bytes()),
+            _ => false,
+        }
+    }
+
+    /// Whether `event` fires this trigger
+    pub fn matches(&self, event: &AutomationEvent) -> bool {
+        match (self, event) {
+            (
+                Self::FileCreated { dir, pattern },
+                AutomationEvent::FileCreated {
+                    dir: event_dir,
+                    name,
+                    ..
+                },
+            ) => dir == event_dir && pattern_matches(pattern.as_deref(), name),
+            (
+                Self::Message { channel, pattern },
+                AutomationEvent::Message {
+                    channel: event_channel,
+                    sender_id,
+                    content,
+                    ..
+                },
+            ) => {
+                sender_id != AUTOMATION_SENDER
+                    && channel
+                        .as_ref()
+                        .map_or(true, |channel| channel == event_channel)
+                    && pattern_matches(Some(pattern), content)
+            }
+            (
+                Self::Email { from },
+                AutomationEvent::Message {
+                    channel, sender_id, ..
+                },
+            ) => {
+                let from = from.trim().to_ascii_lowercase();
+                let sender = sender_id.to_ascii_lowercase();
+                channel == "email"
+                    && if from.starts_with('@') {
+                        sender.ends_with(&from)
+                    } else {
+                        sender == from
+                    }
+            }
+            _ => false,
+        }
+    }
+}
+
+fn compile(pattern: &str) -> Result<regex::Regex, String> {
+    regex::Regex::new(pattern).map_err(|e| format!("invalid pattern '{}': {}", pattern, e))
+}
+
+fn pattern_matches(pattern: Option<&str>, text: &str) -> bool {
+    match pattern {
+        None => true,
+        Some(pattern) => cached_pattern(pattern).is_some_and(|regex| regex.is_match(text)),
+    }
+}
+
+fn cached_pattern(pattern: &str) -> Option<regex::Regex> {
+    let Ok(mut patterns) = PATTERNS.lock() else {
+        return compile(pattern).ok();
+    };
+    if let Some(regex) = patterns.get(pattern) {
+        return regex.clone();
+    }
+    if patterns.len() >= PATTERN_CACHE_LIMIT {
+        patterns.clear();
+    }
+    let regex = compile(pattern).ok();
+    patterns.insert(pattern.to_string(), regex.clone());
+    regex
+}
+
+fn constant_time_eq(left: &[u8], right: &[u8]) -> bool {
+    left.len() == right.len()
+        && left
+            .iter()
+            .zip(right)
+            .fold(0u8, |diff, (l, r)| diff | (l ^ r))
+            == 0
+}
+
+/// Something that happened and may fire rules
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AutomationEvent {
+    FileCreated {
+        /// Directory as configured on the rule
+        dir: String,
+        name: String,
+        path: String,
+    },
+    Message {
+        channel: String,
+        chat_id: String,
+        sender_id: String,
+        content: String,
+    },
+    Webhook {
+        #[serde(default)]
+        body: serde_json::Value,
+    },
+}
+
+impl AutomationEvent {
+    /// Human-readable description handed to the payload as context
+    pub fn describe(&self) -> String {
+        match self {
+            Self::FileCreated { path, .. } => format!("A new file appeared: {}", path),
+            Self::Message {
+                channel,
+                chat_id,
+                sender_id,
+                content,
+            } => format!(
+                "Message from {} on {} (chat {}):\n{}",
+                sender_id, channel, chat_id, content
+            ),
+            Self::Webhook { body } => format!(
+                "Webhook received with body:\n{}",
+                serde_json::to_string_pretty(body).unwrap_or_default()
+            ),
+        }
+    }
+}
+
+/// A rule that runs a payload whenever its trigger fires
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutomationRule {
+    pub id: String,
+    pub name: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    pub trigger: AutomationTrigger,
+    #[serde(default)]
+    pub payload: CronPayload,
+    #[serde(default)]
+    pub state: CronJobState,
+    #[serde(rename = "createdAtMs", default)]
+    pub created_at_ms: i64,
+    #[serde(rename = "updatedAtMs", default)]
+    pub updated_at_ms: i64,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl AutomationRule {
+    /// Copy for list and get views, with the webhook secret hidden
+    pub fn redacted(&self) -> Self {
+        let mut rule = self.clone();
+        if let AutomationTrigger::Webhook {
+            secret: Some(secret),
+        } = &mut rule.trigger
+        {
+            *secret = REDACTED_SECRET.to_string();
+        }
+        rule
+    }
+
+    /// The job the cron callback runs for `event`.
+    ///
+    /// The event description is appended to the prompt of `agent_turn` and
+    /// `http` payloads, and replaces `{event}` in `message` payloads.
+    pub fn job_for(&self, event: &AutomationEvent) -> CronJob {
+        let mut payload = self.payload.clone();
+        let context = event.describe();
+        match payload.kind {
+            CronPayloadKind::AgentTurn | CronPayloadKind::Http => {
+                payload.message = if payload.message.trim().is_empty() {
+                    format!("[Triggering event]\n{}", context)
+                } else {
+                    format!("{}\n\n[Triggering event]\n{}", payload.message, context)
+                };
+            }
+            CronPayloadKind::Message => {
+                payload.message = payload.message.replace("{event}", &context);
+            }
+            CronPayloadKind::Tool | CronPayloadKind::Workflow => {}
+        }
+        CronJob {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            enabled: true,
+            schedule: CronSchedule::at(0),
+            payload,
+            state: CronJobState::default(),
+            created_at_ms: self.created_at_ms,
+            updated_at_ms: self.updated_at_ms,
+            delete_after_run: false,
+            retry: CronRetryPolicy::default(),
+            catch_up: CronCatchUpPolicy::Skip,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutomationDto {
+    #[serde(flatten)]
+    pub rule: AutomationRule,
+    #[serde(rename = "isRunning")]
+    pub is_running: bool,
+    #[serde(rename = "activeRun", skip_serializing_if = "Option::is_none")]
+    pub active_run: Option<CronRunSnapshot>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutomationRequest {
+    pub name: String,
+    pub trigger: AutomationTrigger,
+    #[serde(default)]
+    pub payload: CronPayload,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(channel: &str, sender: &str, content: &str) -> AutomationEvent {
+        AutomationEvent::Message {
+            channel: channel.to_string(),
+            chat_id: "c1".to_string(),
+            sender_id: sender.to_string(),
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_trigger_matching() {
+        let deploy = AutomationTrigger::Message {
+            channel: Some("slack".to_string()),
+            pattern: r"(?i)deploy(ed)? failed".to_string(),
+        };
+        assert!(deploy.matches(&message("slack", "u1", "Deploy failed on prod")));
+        assert!(!deploy.matches(&message("discord", "u1", "deploy failed")));
+        assert!(!deploy.matches(&message("slack", AUTOMATION_SENDER, "deploy failed")));
+
+        let email = AutomationTrigger::Email {
+            from: "@Example.com".to_string(),
+        };
+        assert!(email.matches(&message("email", "alerts@example.com", "hi")));
+        assert!(!email.matches(&message("telegram", "alerts@example.com", "hi")));
+
+        let files = AutomationTrigger::FileCreated {
+            dir: "inbox".to_string(),
+            pattern: Some(r"\.pdf$".to_string()),
+        };
+        let file = |name: &str| AutomationEvent::FileCreated {
+            dir: "inbox".to_string(),
+            name: name.to_string(),
+            path: format!("/ws/inbox/{}", name),
+        };
+        assert!(files.matches(&file("invoice.pdf")));
+        assert!(!files.matches(&file("notes.txt")));
+
+        assert!(AutomationTrigger::Message {
+            channel: None,
+            pattern: "(".to_string(),
+        }
+        .validate()
+        .is_err());
+        for dir in ["/etc", "../outside", "inbox/../../outside"] {
+            let escaping = AutomationTrigger::FileCreated {
+                dir: dir.to_string(),
+                pattern: None,
+            };
+            assert!(escaping.validate().is_err(), "{dir}");
+        }
+    }
+
+    #[test]
+    fn test_webhook_secrets_are_generated_and_kept() {
+        let mut trigger = AutomationTrigger::Webhook { secret: None };
+        trigger.fill_webhook_secret(None);
+        let AutomationTrigger::Webhook {
+            secret: Some(secret),
+        } = trigger.clone()
+        else {
+            panic!("secret was not generated");
+        };
+        assert!(trigger.webhook_secret_matches(Some(&secret)));
+        assert!(!trigger.webhook_secret_matches(Some("guess")));
+        assert!(!trigger.webhook_secret_matches(None));
+        assert!(!AutomationTrigger::Webhook { secret: None }.webhook_secret_matches(Some("")));
+
+        let mut resaved = AutomationTrigger::Webhook {
+            secret: Some(REDACTED_SECRET.to_string()),
+        };
+        resaved.fill_webhook_secret(Some(&trigger));
+        assert_eq!(resaved, trigger);
+    }
+
+    #[test]
+    fn test_event_context_is_injected() {
+        let rule = AutomationRule {
+            id: "r1".to_string(),
+            name: "Triage".to_string(),
+            enabled: true,
+            trigger: AutomationTrigger::Webhook { secret: None },
+            payload: CronPayload {
+                message: "Triage this alert".to_string(),
+                ..Default::default()
+            },
+            state: CronJobState::default(),
+            created_at_ms: 0,
+            updated_at_ms: 0,
+        };
+        let job = rule.job_for(&AutomationEvent::Webhook {
+            body: serde_json::json!({"alert": "disk full"}),
+        });
+        assert_eq!(job.id, "r1");
+        assert!(job.payload.message.starts_with("Triage this alert"));
+        assert!(job.payload.message.contains("disk full"));
+
+        let secret = AutomationTrigger::Webhook {
+            secret: Some("s3cret".to_string()),
+        };
+        let redacted = AutomationRule {
+            trigger: secret,
+            ..rule
+        }
+        .redacted();
+        assert_eq!(
+            redacted.trigger,
+            AutomationTrigger::Webhook {
+                secret: Some(REDACTED_SECRET.to_string())
+            }
+        );
+    }
+}
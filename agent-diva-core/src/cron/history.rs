@@ -0,0 +1,102 @@
+//! Persisted run log for cron jobs
+
+use std::path::{Path, PathBuf};
+
+use tracing::warn;
+
+use crate::cron::types::CronRunRecord;
+
+/// Runs kept per job when nothing else is configured
+pub const DEFAULT_RUN_RETENTION: usize = 50;
+
+/// Append-only run history, one `<job_id>.jsonl` file per job
+#[derive(Debug, Clone)]
+pub struct CronRunLog {
+    dir: PathBuf,
+    retention: usize,
+}
+
+impl CronRunLog {
+    /// Log stored in `cron_runs/` next to the cron store file
+    pub fn for_store(store_path: &Path) -> Self {
+        let dir = store_path
+            .parent()
+            .map(|parent| parent.join("cron_runs"))
+            .unwrap_or_else(|| PathBuf::from("cron_runs"));
+        Self {
+            dir,
+            retention: DEFAULT_RUN_RETENTION,
+        }
+    }
+
+    /// Keep at most `retention` runs per job; 0 disables the log
+    pub fn with_retention(mut self, retention: usize) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    fn job_path(&self, job_id: &str) -> PathBuf {
+        let safe_id = job_id.replace([':', '/', '\\'], "_");
+        self.dir.join(format!("{}.jsonl", safe_id))
+    }
+
+    async fn read_all(&self, job_id: &str) -> Vec<CronRunRecord> {
+        let path = self.job_path(job_id);
+        let Ok(content) = tokio::fs::read_to_string(&path).await else {
+            return Vec::new();
+        };
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| match serde_json::from_str::<CronRunRecord>(line) {
+                Ok(record) => Some(record),
+                Err(e) => {
+                    warn!("Skipping malformed cron run record in {:?}: {}", path, e);
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Record a finished run and prune the job's log to the retention limit
+    pub async fn append(&self, record: &CronRunRecord) {
+        if self.retention == 0 {
+            return;
+        }
+        let mut records = self.read_all(&record.job_id).await;
+        records.push(record.clone());
+        let excess = records.len().saturating_sub(self.retention);
+        let lines = records[excess..]
+            .iter()
+            .filter_map(|record| serde_json::to_string(record).ok())
+            .collect::<Vec<_>>();
+
+        if let Err(e) = tokio::fs::create_dir_all(&self.dir).await {
+            warn!("Failed to create cron run log dir {:?}: {}", self.dir, e);
+            return;
+        }
+        let path = self.job_path(&record.job_id);
+        let content = lines.join("\n") + "\n";
+        if let Err(e) = crate::utils::atomic_write(&path, content.as_bytes()) {
+            warn!("Failed to write cron run log {:?}: {}", path, e);
+        }
+    }
+
+    /// Most recent runs first, at most `limit` of them
+    pub async fn list(&self, job_id: &str, limit: usize) -> Vec<CronRunRecord> {
+        let mut records = self.read_all(job_id).await;
+        records.reverse();
+        records.truncate(limit);
+        records
+    }
+
+    /// Drop the history of a deleted job
+    pub async fn remove(&self, job_id: &str) {
+        let path = self.job_path(job_id);
+        if path.exists() {
+            if let Err(e) = tokio::fs::remove_file(&path).await {
+                warn!("Failed to remove cron run log {:?}: {}", path, e);
+            }
+        }
+    }
+}
@@ -1,10 +1,19 @@
 //! Cron scheduling module
 
+pub mod automation;
+pub mod history;
 pub mod service;
 pub mod types;
 
+pub use automation::{
+    AutomationDto, AutomationEvent, AutomationRequest, AutomationRule, AutomationTrigger,
+    AUTOMATION_SENDER,
+};
+pub use history::{CronRunLog, DEFAULT_RUN_RETENTION};
 pub use service::CronService;
 pub use types::{
-    CreateCronJobRequest, CronJob, CronJobDto, CronJobLifecycleStatus, CronJobState, CronPayload,
-    CronRunSnapshot, CronSchedule, CronStore, CronTrigger, UpdateCronJobRequest,
+    CreateCronJobRequest, CronCatchUpPolicy, CronHttpRequest, CronJob, CronJobDto,
+    CronJobLifecycleStatus, CronJobOutcome, CronJobState, CronPayload, CronPayloadKind,
+    CronRetryPolicy, CronRunRecord, CronRunSnapshot, CronRunStatus, CronSchedule, CronStore,
+    CronToolCall, CronTrigger, CronWorkflowRun, UpdateCronJobRequest,
 };
@@ -10,9 +10,14 @@ use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 
+use crate::cron::automation::{
+    AutomationDto, AutomationEvent, AutomationRequest, AutomationRule, AutomationTrigger,
+};
+use crate::cron::history::CronRunLog;
 use crate::cron::types::{
-    CreateCronJobRequest, CronJob, CronJobDto, CronJobLifecycleStatus, CronJobState, CronPayload,
-    CronRunSnapshot, CronSchedule, CronStore, CronTrigger, UpdateCronJobRequest,
+    CreateCronJobRequest, CronCatchUpPolicy, CronJob, CronJobDto, CronJobLifecycleStatus,
+    CronJobOutcome, CronJobState, CronPayload, CronRetryPolicy, CronRunRecord, CronRunSnapshot,
+    CronRunStatus, CronSchedule, CronStore, CronTrigger, UpdateCronJobRequest,
 };
 
 fn now_ms() -> i64 {
@@ -84,11 +89,135 @@ pub type JobCallback = Arc<
     dyn Fn(
             CronJob,
             CancellationToken,
-        ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Option<String>> + Send>>
+        ) -> std::pin::Pin<Box<dyn std::future::Future<Output = CronJobOutcome> + Send>>
         + Send
         + Sync,
 >;
 
+/// Invoke the job callback once and classify the outcome
+async fn invoke_job(
+    on_job: Option<&JobCallback>,
+    job: &CronJob,
+    cancel_token: &CancellationToken,
+) -> (Result<(), String>, CronJobOutcome) {
+    if cancel_token.is_cancelled() {
+        return (
+            Err("job cancelled before start".to_string()),
+            CronJobOutcome::default(),
+        );
+    }
+    let Some(callback) = on_job else {
+        return (Ok(()), CronJobOutcome::default());
+    };
+    let outcome = (callback)(job.clone(), cancel_token.clone()).await;
+    let result = match &outcome.error {
+        _ if cancel_token.is_cancelled() => Err("job cancelled".to_string()),
+        Some(error) => Err(error.clone()),
+        None => Ok(()),
+    };
+    (result, outcome)
+}
+
+/// Apply a finished run to the job state and build its log record.
+///
+/// Failed non-manual runs are rescheduled while the retry policy allows it.
+/// Returns whether the job should be removed from the store.
+fn settle_run(
+    job: &mut CronJob,
+    snapshot: &CronRunSnapshot,
+    attempt: u32,
+    result: Result<(), String>,
+    outcome: CronJobOutcome,
+    cancelled: bool,
+) -> (CronRunRecord, bool) {
+    let now = now_ms();
+    job.state.last_run_at_ms = Some(snapshot.started_at_ms);
+    job.updated_at_ms = now;
+    let status = match (&result, cancelled) {
+        (Ok(()), _) => CronRunStatus::Ok,
+        (Err(_), true) => CronRunStatus::Cancelled,
+        (Err(_), false) => CronRunStatus::Error,
+    };
+    let record = CronRunRecord {
+        run_id: snapshot.run_id.clone(),
+        job_id: job.id.clone(),
+        trigger: snapshot.trigger.clone(),
+        attempt,
+        started_at_ms: snapshot.started_at_ms,
+        finished_at_ms: now,
+        status,
+        response: outcome.response,
+        error: result.as_ref().err().cloned(),
+        tool_calls: outcome.tool_calls,
+        trace_id: outcome.trace_id,
+    };
+
+    match result {
+        Ok(()) => {
+            job.state.last_status = Some("ok".to_string());
+            job.state.last_error = None;
+            info!(
+                "Cron: job '{}' ({}) completed successfully",
+                job.name, job.id
+            );
+        }
+        Err(err) => {
+            job.state.last_status = Some("error".to_string());
+            job.state.last_error = Some(err);
+            warn!(
+                "Cron: job '{}' ({}) failed on attempt {}",
+                job.name, job.id, attempt
+            );
+        }
+    }
+
+    let retry = status == CronRunStatus::Error
+        && snapshot.trigger != CronTrigger::Manual
+        && attempt < job.retry.max_attempts;
+    if retry {
+        let delay = job.retry.backoff_for(attempt);
+        job.state.retry_attempt = attempt;
+        job.state.pending_trigger = Some(CronTrigger::Retry);
+        job.state.next_run_at_ms = Some(now.saturating_add(delay));
+        info!(
+            "Cron: retrying job '{}' ({}) in {} ms",
+            job.name, job.id, delay
+        );
+        return (record, false);
+    }
+    job.state.retry_attempt = 0;
+    job.state.pending_trigger = None;
+
+    let should_remove = match &job.schedule {
+        CronSchedule::At { .. } => {
+            if job.delete_after_run && job.state.last_status.as_deref() == Some("ok") {
+                true
+            } else {
+                job.enabled = false;
+                job.state.next_run_at_ms = None;
+                false
+            }
+        }
+        _ => {
+            job.state.next_run_at_ms = if job.enabled {
+                compute_next_run(&job.schedule, now_ms())
+            } else {
+                None
+            };
+            false
+        }
+    };
+    (record, should_remove)
+}
+
+/// Attempt number of a run started by `trigger`
+fn attempt_for(job: &CronJob, trigger: &CronTrigger) -> u32 {
+    match trigger {
+        CronTrigger::Retry => job.state.retry_attempt + 1,
+        _ => 1,
+    }
+}
+
 #[derive(Clone)]
 struct ActiveCronRun {
     snapshot: CronRunSnapshot,
@@ -102,11 +231,13 @@ pub struct CronService {
     timer_task: Arc<Mutex<Option<JoinHandle<()>>>>,
     running: Arc<RwLock<bool>>,
     active_runs: Arc<RwLock<HashMap<String, ActiveCronRun>>>,
+    run_log: CronRunLog,
 }
 
 impl CronService {
     pub fn new(store_path: PathBuf, on_job: Option<JobCallback>) -> Self {
         Self {
+            run_log: CronRunLog::for_store(&store_path),
             store_path,
             on_job,
             store: Arc::new(RwLock::new(None)),
@@ -116,6 +247,12 @@ impl CronService {
         }
     }
 
+    /// Keep at most `retention` logged runs per job
+    pub fn with_run_retention(mut self, retention: usize) -> Self {
+        self.run_log = self.run_log.with_retention(retention);
+        self
+    }
+
     async fn load_store(&self) -> CronStore {
         {
             let store_guard = self.store.read().await;
@@ -212,10 +349,24 @@ impl CronService {
     async fn recompute_next_runs(&self, store: &mut CronStore) {
         let now = now_ms();
         for job in &mut store.jobs {
-            if job.enabled {
-                job.state.next_run_at_ms = compute_next_run(&job.schedule, now);
-            } else {
+            if !job.enabled {
                 job.state.next_run_at_ms = None;
+                continue;
+            }
+            let missed = job.state.next_run_at_ms.is_some_and(|due| due <= now);
+            if missed && job.catch_up == CronCatchUpPolicy::RunOnce {
+                info!(
+                    "Cron: catching up missed run of '{}' ({})",
+                    job.name, job.id
+                );
+                job.state.next_run_at_ms = Some(now);
+                if job.state.pending_trigger.is_none() {
+                    job.state.pending_trigger = Some(CronTrigger::CatchUp);
+                }
+            } else {
+                job.state.next_run_at_ms = compute_next_run(&job.schedule, now);
+                job.state.pending_trigger = None;
+                job.state.retry_attempt = 0;
             }
         }
     }
@@ -259,13 +410,15 @@ impl CronService {
             on_job: self.on_job.clone(),
             store_path: self.store_path.clone(),
             active_runs: Arc::clone(&self.active_runs),
+            run_log: self.run_log.clone(),
         });
 
         let task = tokio::spawn(async move {
             tokio::time::sleep(delay).await;
             let is_running = *service.running.read().await;
             if is_running {
-                service.on_timer().await;
+                // Re-arming aborts this task; runs in flight must survive that.
+                tokio::spawn(async move { service.on_timer().await });
             }
         });
 
@@ -299,6 +452,11 @@ impl CronService {
         active_guard.get(job_id).map(|run| run.snapshot.clone())
     }
 
+    /// Logged runs of a job, most recent first
+    pub async fn list_runs(&self, job_id: &str, limit: usize) -> Vec<CronRunRecord> {
+        self.run_log.list(job_id, limit).await
+    }
+
     async fn register_active_run(
         &self,
         job_id: &str,
@@ -348,78 +506,44 @@ impl CronService {
             "Cron: executing job '{}' ({}) trigger={:?}",
             job.name, job.id, trigger
         );
-        let _ = self.register_active_run(&job.id, trigger).await?;
+        let attempt = attempt_for(&job, &trigger);
+        let snapshot = self.register_active_run(&job.id, trigger).await?;
         let cancel_token = self
             .cancel_token_for(&job.id)
             .await
             .ok_or_else(|| format!("missing cancel token for {}", job.id))?;
 
-        let callback_result = if cancel_token.is_cancelled() {
-            Err("job cancelled before start".to_string())
-        } else if let Some(callback) = &self.on_job {
-            match (callback)(job.clone(), cancel_token.clone()).await {
-                Some(_) if cancel_token.is_cancelled() => Err("job cancelled".to_string()),
-                Some(response) if response.to_ascii_lowercase().starts_with("error") => {
-                    Err(response)
-                }
-                Some(_) | None => Ok(()),
-            }
-        } else if cancel_token.is_cancelled() {
-            Err("job cancelled".to_string())
-        } else {
-            Ok(())
-        };
-
-        let now = now_ms();
-        job.state.last_run_at_ms = Some(now);
-        job.updated_at_ms = now;
-        match callback_result {
-            Ok(()) => {
-                job.state.last_status = Some("ok".to_string());
-                job.state.last_error = None;
-                info!(
-                    "Cron: job '{}' ({}) completed successfully",
-                    job.name, job.id
-                );
-            }
-            Err(err) => {
-                job.state.last_status = Some("error".to_string());
-                job.state.last_error = Some(err);
-                warn!("Cron: job '{}' ({}) failed", job.name, job.id);
-            }
-        }
-
-        let should_remove = match &job.schedule {
-            CronSchedule::At { .. } => {
-                if job.delete_after_run && job.state.last_status.as_deref() == Some("ok") {
-                    true
-                } else {
-                    job.enabled = false;
-                    job.state.next_run_at_ms = None;
-                    false
-                }
-            }
-            _ => {
-                job.state.next_run_at_ms = if job.enabled {
-                    compute_next_run(&job.schedule, now_ms())
-                } else {
-                    None
-                };
-                false
-            }
-        };
+        let (result, outcome) = invoke_job(self.on_job.as_ref(), &job, &cancel_token).await;
+        let (record, should_remove) = settle_run(
+            &mut job,
+            &snapshot,
+            attempt,
+            result,
+            outcome,
+            cancel_token.is_cancelled(),
+        );
 
-        {
+        let still_exists = {
             let mut store_guard = self.store.write().await;
-            if let Some(store) = store_guard.as_mut() {
-                if should_remove {
+            match store_guard.as_mut() {
+                Some(store) if should_remove => {
+                    let before = store.jobs.len();
                     store.jobs.retain(|existing| existing.id != job.id);
-                } else if let Some(existing) =
-                    store.jobs.iter_mut().find(|existing| existing.id == job.id)
-                {
-                    *existing = job.clone();
+                    store.jobs.len() < before
                 }
+                Some(store) => match store.jobs.iter_mut().find(|existing| existing.id == job.id) {
+                    Some(existing) => {
+                        *existing = job.clone();
+                        true
+                    }
+                    None => false,
+                },
+                None => false,
             }
+        };
+        // A job deleted mid-run takes its history with it.
+        if still_exists {
+            self.run_log.append(&record).await;
         }
 
         self.clear_active_run(&job.id).await;
@@ -480,11 +604,11 @@ impl CronService {
             enabled: true,
             schedule: schedule.clone(),
             payload: CronPayload {
-                kind: "agent_turn".to_string(),
                 message,
                 deliver,
                 channel,
                 to,
+                ..Default::default()
             },
             state: CronJobState {
                 next_run_at_ms: compute_next_run(&schedule, now),
@@ -493,6 +617,8 @@ impl CronService {
             created_at_ms: now,
             updated_at_ms: now,
             delete_after_run,
+            retry: CronRetryPolicy::default(),
+            catch_up: CronCatchUpPolicy::default(),
         };
 
         {
@@ -512,7 +638,13 @@ impl CronService {
         job
     }
 
-    pub async fn create_job(&self, request: CreateCronJobRequest) -> Result<CronJobDto, String> {
+    pub async fn create_job(
+        &self,
+        mut request: CreateCronJobRequest,
+    ) -> Result<CronJobDto, String> {
+        request.payload.validate()?;
+        request.retry.validate()?;
+        request.payload.strip_context_args();
         let now = now_ms();
         let id = uuid::Uuid::new_v4().to_string()[..8].to_string();
         let schedule = request.schedule.clone();
@@ -533,6 +665,8 @@ impl CronService {
             created_at_ms: now,
             updated_at_ms: now,
             delete_after_run: request.delete_after_run,
+            retry: request.retry,
+            catch_up: request.catch_up,
         };
 
         {
@@ -554,8 +688,11 @@ impl CronService {
     pub async fn update_job(
         &self,
         job_id: &str,
-        request: UpdateCronJobRequest,
+        mut request: UpdateCronJobRequest,
     ) -> Result<CronJobDto, String> {
+        request.payload.validate()?;
+        request.retry.validate()?;
+        request.payload.strip_context_args();
         let updated_job = {
             let mut store_guard = self.store.write().await;
             let store = store_guard
@@ -572,6 +709,10 @@ impl CronService {
             job.payload = request.payload;
             job.delete_after_run = request.delete_after_run;
             job.enabled = request.enabled;
+            job.retry = request.retry;
+            job.catch_up = request.catch_up;
+            job.state.retry_attempt = 0;
+            job.state.pending_trigger = None;
             job.updated_at_ms = now_ms();
             job.state.next_run_at_ms = if job.enabled {
                 compute_next_run(&job.schedule, now_ms())
@@ -605,6 +746,7 @@ impl CronService {
         self.clear_active_run(job_id).await;
 
         if removed {
+            self.run_log.remove(job_id).await;
             self.save_store().await;
             self.arm_timer().await;
             Ok(())
@@ -692,6 +834,248 @@ impl CronService {
         Ok(active.snapshot)
     }
 
+    fn to_automation_dto(
+        rule: &AutomationRule,
+        active_run: Option<CronRunSnapshot>,
+    ) -> AutomationDto {
+        AutomationDto {
+            rule: rule.clone(),
+            is_running: active_run.is_some(),
+            active_run,
+        }
+    }
+
+    pub async fn list_automations(&self) -> Vec<AutomationDto> {
+        let store = self.load_store().await;
+        let mut views = Vec::with_capacity(store.automations.len());
+        for rule in &store.automations {
+            let active = self.active_snapshot_for(&rule.id).await;
+            views.push(Self::to_automation_dto(&rule.redacted(), active));
+        }
+        views
+    }
+
+    pub async fn get_automation(&self, rule_id: &str) -> Option<AutomationDto> {
+        let store = self.load_store().await;
+        let rule = store
+            .automations
+            .into_iter()
+            .find(|rule| rule.id == rule_id)?;
+        let active = self.active_snapshot_for(rule_id).await;
+        Some(Self::to_automation_dto(&rule.redacted(), active))
+    }
+
+    /// Check the secret sent to a webhook rule
+    pub async fn check_webhook_secret(
+        &self,
+        rule_id: &str,
+        provided: Option<&str>,
+    ) -> Result<(), String> {
+        let store = self.load_store().await;
+        let rule = store
+            .automations
+            .iter()
+            .find(|rule| rule.id == rule_id)
+            .ok_or_else(|| format!("automation {} not found", rule_id))?;
+        if !matches!(rule.trigger, AutomationTrigger::Webhook { .. }) {
+            return Err(format!("automation {} is not a webhook rule", rule_id));
+        }
+        if !rule.trigger.webhook_secret_matches(provided) {
+            return Err("invalid webhook secret".to_string());
+        }
+        Ok(())
+    }
+
+    pub async fn create_automation(
+        &self,
+        mut request: AutomationRequest,
+    ) -> Result<AutomationDto, String> {
+        request.trigger.validate()?;
+        request.payload.validate()?;
+        request.payload.strip_context_args();
+        request.trigger.fill_webhook_secret(None);
+        let now = now_ms();
+        let rule = AutomationRule {
+            id: uuid::Uuid::new_v4().to_string()[..8].to_string(),
+            name: request.name,
+            enabled: request.enabled,
+            trigger: request.trigger,
+            payload: request.payload,
+            state: CronJobState::default(),
+            created_at_ms: now,
+            updated_at_ms: now,
+        };
+
+        self.load_store().await;
+        {
+            let mut store_guard = self.store.write().await;
+            if let Some(store) = store_guard.as_mut() {
+                store.automations.push(rule.clone());
+            }
+        }
+
+        self.save_store().await;
+        info!("Cron: added automation '{}' ({})", rule.name, rule.id);
+        Ok(Self::to_automation_dto(&rule, None))
+    }
+
+    pub async fn update_automation(
+        &self,
+        rule_id: &str,
+        mut request: AutomationRequest,
+    ) -> Result<AutomationDto, String> {
+        request.trigger.validate()?;
+        request.payload.validate()?;
+        request.payload.strip_context_args();
+        self.load_store().await;
+        let updated = {
+            let mut store_guard = self.store.write().await;
+            let store = store_guard
+                .as_mut()
+                .ok_or_else(|| "cron store not initialized".to_string())?;
+            let rule = store
+                .automations
+                .iter_mut()
+                .find(|rule| rule.id == rule_id)
+                .ok_or_else(|| format!("automation {} not found", rule_id))?;
+
+            request.trigger.fill_webhook_secret(Some(&rule.trigger));
+            rule.name = request.name;
+            rule.enabled = request.enabled;
+            rule.trigger = request.trigger;
+            rule.payload = request.payload;
+            rule.updated_at_ms = now_ms();
+            rule.clone()
+        };
+
+        self.save_store().await;
+        Ok(Self::to_automation_dto(
+            &updated,
+            self.active_snapshot_for(rule_id).await,
+        ))
+    }
+
+    pub async fn delete_automation(&self, rule_id: &str) -> Result<(), String> {
+        let _ = self.stop_run(rule_id).await;
+        self.load_store().await;
+        let removed = {
+            let mut store_guard = self.store.write().await;
+            match store_guard.as_mut() {
+                Some(store) => {
+                    let before = store.automations.len();
+                    store.automations.retain(|rule| rule.id != rule_id);
+                    store.automations.len() < before
+                }
+                None => false,
+            }
+        };
+
+        self.clear_active_run(rule_id).await;
+
+        if removed {
+            self.run_log.remove(rule_id).await;
+            self.save_store().await;
+            Ok(())
+        } else {
+            Err(format!("automation {} not found", rule_id))
+        }
+    }
+
+    /// Enabled rules whose trigger fires on `event`
+    pub async fn matching_automations(&self, event: &AutomationEvent) -> Vec<AutomationRule> {
+        let store = self.load_store().await;
+        store
+            .automations
+            .into_iter()
+            .filter(|rule| rule.enabled && rule.trigger.matches(event))
+            .collect()
+    }
+
+    /// Start a run of every rule matching `event`; returns how many started.
+    /// Rules that are still busy with an earlier event are skipped.
+    pub async fn dispatch_event(self: &Arc<Self>, event: AutomationEvent) -> usize {
+        let mut started = 0;
+        for rule in self.matching_automations(&event).await {
+            match self.start_automation_run(rule, event.clone()).await {
+                Ok(_) => started += 1,
+                Err(e) => debug!("Cron: automation not started: {}", e),
+            }
+        }
+        started
+    }
+
+    /// Start a run of one rule in the background, regardless of its trigger.
+    /// Used for webhooks, which address their rule directly.
+    pub async fn trigger_automation(
+        self: &Arc<Self>,
+        rule_id: &str,
+        event: AutomationEvent,
+    ) -> Result<CronRunSnapshot, String> {
+        let rule = self
+            .load_store()
+            .await
+            .automations
+            .into_iter()
+            .find(|rule| rule.id == rule_id && rule.enabled)
+            .ok_or_else(|| format!("automation {} not found or disabled", rule_id))?;
+        self.start_automation_run(rule, event).await
+    }
+
+    async fn start_automation_run(
+        self: &Arc<Self>,
+        rule: AutomationRule,
+        event: AutomationEvent,
+    ) -> Result<CronRunSnapshot, String> {
+        info!("Cron: automation '{}' ({}) triggered", rule.name, rule.id);
+        let snapshot = self
+            .register_active_run(&rule.id, CronTrigger::Event)
+            .await?;
+        let service = Arc::clone(self);
+        let run = snapshot.clone();
+        tokio::spawn(async move { service.execute_automation(rule, run, event).await });
+        Ok(snapshot)
+    }
+
+    async fn execute_automation(
+        &self,
+        rule: AutomationRule,
+        snapshot: CronRunSnapshot,
+        event: AutomationEvent,
+    ) {
+        let cancel_token = self.cancel_token_for(&rule.id).await.unwrap_or_default();
+        let mut job = rule.job_for(&event);
+        let (result, outcome) = invoke_job(self.on_job.as_ref(), &job, &cancel_token).await;
+        let (record, _) = settle_run(
+            &mut job,
+            &snapshot,
+            1,
+            result,
+            outcome,
+            cancel_token.is_cancelled(),
+        );
+
+        let still_exists = {
+            let mut store_guard = self.store.write().await;
+            let existing = store_guard
+                .as_mut()
+                .and_then(|store| store.automations.iter_mut().find(|r| r.id == rule.id));
+            match existing {
+                Some(existing) => {
+                    existing.state.last_run_at_ms = job.state.last_run_at_ms;
+                    existing.state.last_status = job.state.last_status.clone();
+                    existing.state.last_error = job.state.last_error.clone();
+                    true
+                }
+                None => false,
+            }
+        };
+        if still_exists {
+            self.run_log.append(&record).await;
+            self.save_store().await;
+        }
+        self.clear_active_run(&rule.id).await;
+    }
+
     pub async fn status(&self) -> serde_json::Value {
         let jobs = self.list_job_views(true).await;
         let is_running = *self.running.read().await;
@@ -701,6 +1085,7 @@ impl CronService {
             "enabled": is_running,
             "jobs": jobs.len(),
             "runningJobs": jobs.iter().filter(|job| job.is_running).count(),
+            "automations": self.load_store().await.automations.len(),
             "nextWakeAtMs": next_wake,
         })
     }
@@ -713,102 +1098,71 @@ struct CronServiceHandle {
     on_job: Option<JobCallback>,
     store_path: PathBuf,
     active_runs: Arc<RwLock<HashMap<String, ActiveCronRun>>>,
+    run_log: CronRunLog,
 }
 
 impl CronServiceHandle {
-    async fn execute_due_job(&self, mut job: CronJob) {
-        info!("Cron: due job fired '{}' ({})", job.name, job.id);
+    async fn execute_due_job(&self, mut job: CronJob, trigger: CronTrigger) {
+        info!(
+            "Cron: due job fired '{}' ({}) trigger={:?}",
+            job.name, job.id, trigger
+        );
         let mut active_guard = self.active_runs.write().await;
         if active_guard.contains_key(&job.id) {
             return;
         }
 
+        let attempt = attempt_for(&job, &trigger);
         let timestamp = now_ms();
+        let snapshot = CronRunSnapshot {
+            run_id: uuid::Uuid::new_v4().to_string(),
+            job_id: job.id.clone(),
+            started_at_ms: timestamp,
+            last_heartbeat_at_ms: timestamp,
+            trigger,
+            cancelable: true,
+        };
+        let cancel_token = CancellationToken::new();
         active_guard.insert(
             job.id.clone(),
             ActiveCronRun {
-                snapshot: CronRunSnapshot {
-                    run_id: uuid::Uuid::new_v4().to_string(),
-                    job_id: job.id.clone(),
-                    started_at_ms: timestamp,
-                    last_heartbeat_at_ms: timestamp,
-                    trigger: CronTrigger::Scheduled,
-                    cancelable: true,
-                },
-                cancel_token: CancellationToken::new(),
+                snapshot: snapshot.clone(),
+                cancel_token: cancel_token.clone(),
             },
         );
-        let cancel_token = active_guard
-            .get(&job.id)
-            .map(|run| run.cancel_token.clone())
-            .expect("inserted active run");
         drop(active_guard);
 
-        let result = if cancel_token.is_cancelled() {
-            Err("job cancelled before start".to_string())
-        } else if let Some(callback) = &self.on_job {
-            match (callback)(job.clone(), cancel_token.clone()).await {
-                Some(_) if cancel_token.is_cancelled() => Err("job cancelled".to_string()),
-                Some(response) if response.to_ascii_lowercase().starts_with("error") => {
-                    Err(response)
-                }
-                Some(_) | None => Ok(()),
-            }
-        } else if cancel_token.is_cancelled() {
-            Err("job cancelled".to_string())
-        } else {
-            Ok(())
-        };
-
-        job.state.last_run_at_ms = Some(timestamp);
-        job.updated_at_ms = now_ms();
-        match result {
-            Ok(()) => {
-                job.state.last_status = Some("ok".to_string());
-                job.state.last_error = None;
-                info!(
-                    "Cron: scheduled job '{}' ({}) completed successfully",
-                    job.name, job.id
-                );
-            }
-            Err(err) => {
-                job.state.last_status = Some("error".to_string());
-                job.state.last_error = Some(err);
-                warn!("Cron: scheduled job '{}' ({}) failed", job.name, job.id);
-            }
-        }
-
-        let should_remove = match &job.schedule {
-            CronSchedule::At { .. } => {
-                if job.delete_after_run && job.state.last_status.as_deref() == Some("ok") {
-                    true
-                } else {
-                    job.enabled = false;
-                    job.state.next_run_at_ms = None;
-                    false
-                }
-            }
-            _ => {
-                job.state.next_run_at_ms = if job.enabled {
-                    compute_next_run(&job.schedule, now_ms())
-                } else {
-                    None
-                };
-                false
-            }
-        };
+        let (result, outcome) = invoke_job(self.on_job.as_ref(), &job, &cancel_token).await;
+        let (record, should_remove) = settle_run(
+            &mut job,
+            &snapshot,
+            attempt,
+            result,
+            outcome,
+            cancel_token.is_cancelled(),
+        );
 
-        {
+        let still_exists = {
             let mut store_guard = self.store.write().await;
-            if let Some(store) = store_guard.as_mut() {
-                if should_remove {
+            match store_guard.as_mut() {
+                Some(store) if should_remove => {
+                    let before = store.jobs.len();
                     store.jobs.retain(|existing| existing.id != job.id);
-                } else if let Some(existing) =
-                    store.jobs.iter_mut().find(|existing| existing.id == job.id)
-                {
-                    *existing = job.clone();
+                    store.jobs.len() < before
                 }
+                Some(store) => match store.jobs.iter_mut().find(|existing| existing.id == job.id) {
+                    Some(existing) => {
+                        *existing = job.clone();
+                        true
+                    }
+                    None => false,
+                },
+                None => false,
             }
+        };
+        // A job deleted mid-run takes its history with it.
+        if still_exists {
+            self.run_log.append(&record).await;
         }
 
         let mut active_guard = self.active_runs.write().await;
@@ -817,26 +1171,32 @@ impl CronServiceHandle {
 
     async fn on_timer(&self) {
         let now = now_ms();
+        // Claim due jobs by moving their next run forward, so a timer re-armed
+        // while they execute does not fire them again.
         let due_jobs = {
-            let store_guard = self.store.read().await;
-            if let Some(store) = store_guard.as_ref() {
-                store
-                    .jobs
-                    .iter()
-                    .filter(|job| {
-                        job.enabled
-                            && job.state.next_run_at_ms.is_some()
-                            && now >= job.state.next_run_at_ms.unwrap()
-                    })
-                    .cloned()
-                    .collect::<Vec<_>>()
-            } else {
-                Vec::new()
+            let mut store_guard = self.store.write().await;
+            let mut due = Vec::new();
+            if let Some(store) = store_guard.as_mut() {
+                for job in store.jobs.iter_mut().filter(|job| {
+                    job.enabled && job.state.next_run_at_ms.is_some_and(|next| now >= next)
+                }) {
+                    let trigger = job
+                        .state
+                        .pending_trigger
+                        .take()
+                        .unwrap_or(CronTrigger::Scheduled);
+                    due.push((job.clone(), trigger));
+                    job.state.next_run_at_ms = match job.schedule {
+                        CronSchedule::At { .. } => None,
+                        _ => compute_next_run(&job.schedule, now),
+                    };
+                }
             }
+            due
         };
 
-        for job in due_jobs {
-            self.execute_due_job(job).await;
+        for (job, trigger) in due_jobs {
+            self.execute_due_job(job, trigger).await;
         }
 
         if let Some(parent) = self.store_path.parent() {
@@ -861,6 +1221,7 @@ impl CronServiceHandle {
         let on_job = self.on_job.clone();
         let store_path = self.store_path.clone();
         let active_runs = Arc::clone(&self.active_runs);
+        let run_log = self.run_log.clone();
 
         tokio::spawn(async move {
             {
@@ -900,13 +1261,14 @@ impl CronServiceHandle {
                 on_job: on_job.clone(),
                 store_path: store_path.clone(),
                 active_runs: Arc::clone(&active_runs),
+                run_log: run_log.clone(),
             });
 
             let task = tokio::spawn(async move {
                 tokio::time::sleep(delay).await;
                 let is_running = *service.running.read().await;
                 if is_running {
-                    service.on_timer().await;
+                    tokio::spawn(async move { service.on_timer().await });
                 }
             });
 
@@ -919,6 +1281,7 @@ impl CronServiceHandle {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cron::types::MAX_RETRY_ATTEMPTS;
     use tempfile::TempDir;
 
     #[test]
@@ -948,6 +1311,8 @@ mod tests {
                 payload: CronPayload::default(),
                 delete_after_run: false,
                 enabled: true,
+                retry: CronRetryPolicy::default(),
+                catch_up: CronCatchUpPolicy::default(),
             })
             .await
             .unwrap();
@@ -967,8 +1332,8 @@ mod tests {
         let callback: JobCallback = Arc::new(|_job, token| {
             Box::pin(async move {
                 tokio::select! {
-                    _ = tokio::time::sleep(tokio::time::Duration::from_millis(200)) => Some("done".to_string()),
-                    _ = token.cancelled() => Some("Error: cancelled".to_string()),
+                    _ = tokio::time::sleep(tokio::time::Duration::from_millis(200)) => CronJobOutcome::with_response("done"),
+                    _ = token.cancelled() => CronJobOutcome::failed("Error: cancelled"),
                 }
             })
         });
@@ -982,6 +1347,8 @@ mod tests {
                 payload: CronPayload::default(),
                 delete_after_run: false,
                 enabled: true,
+                retry: CronRetryPolicy::default(),
+                catch_up: CronCatchUpPolicy::default(),
             })
             .await
             .unwrap();
@@ -1001,4 +1368,295 @@ mod tests {
         let _ = runner.await;
         service.stop().await;
     }
+
+    fn request(name: &str) -> CreateCronJobRequest {
+        CreateCronJobRequest {
+            name: name.to_string(),
+            schedule: CronSchedule::every(60_000),
+            payload: CronPayload::default(),
+            delete_after_run: false,
+            enabled: true,
+            retry: CronRetryPolicy::default(),
+            catch_up: CronCatchUpPolicy::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_runs_are_logged_with_retention() {
+        let temp_dir = TempDir::new().unwrap();
+        let store_path = temp_dir.path().join("cron.json");
+        let callback: JobCallback = Arc::new(|_job, _token| {
+            Box::pin(async move {
+                CronJobOutcome {
+                    response: Some("all done".to_string()),
+                    error: None,
+                    tool_calls: 2,
+                    trace_id: Some("trace-1".to_string()),
+                }
+            })
+        });
+        let service = CronService::new(store_path, Some(callback)).with_run_retention(2);
+        service.start().await;
+        let job = service.create_job(request("Logged")).await.unwrap();
+
+        for _ in 0..3 {
+            service.run_job_now(&job.job.id, true).await.unwrap();
+        }
+        let runs = service.list_runs(&job.job.id, 10).await;
+        assert_eq!(runs.len(), 2);
+        assert!(runs[0].started_at_ms >= runs[1].started_at_ms);
+        assert_eq!(runs[0].status, CronRunStatus::Ok);
+        assert_eq!(runs[0].trigger, CronTrigger::Manual);
+        assert_eq!(runs[0].response.as_deref(), Some("all done"));
+        assert_eq!(runs[0].tool_calls, 2);
+        assert_eq!(runs[0].trace_id.as_deref(), Some("trace-1"));
+
+        service.delete_job(&job.job.id).await.unwrap();
+        assert!(service.list_runs(&job.job.id, 10).await.is_empty());
+        service.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_run_outcome_comes_from_the_runner_error() {
+        let temp_dir = TempDir::new().unwrap();
+        let store_path = temp_dir.path().join("cron.json");
+        let callback: JobCallback = Arc::new(|job, _token| {
+            Box::pin(async move {
+                if job.name == "Broken" {
+                    CronJobOutcome::failed("delivery failed")
+                } else {
+                    CronJobOutcome::with_response("Error budget review at 9")
+                }
+            })
+        });
+        let service = CronService::new(store_path, Some(callback));
+        service.start().await;
+
+        let reminder = service.create_job(request("Reminder")).await.unwrap();
+        service.run_job_now(&reminder.job.id, true).await.unwrap();
+        let runs = service.list_runs(&reminder.job.id, 10).await;
+        assert_eq!(runs[0].status, CronRunStatus::Ok);
+        assert!(runs[0].error.is_none());
+
+        let broken = service.create_job(request("Broken")).await.unwrap();
+        service.run_job_now(&broken.job.id, true).await.unwrap();
+        let runs = service.list_runs(&broken.job.id, 10).await;
+        assert_eq!(runs[0].status, CronRunStatus::Error);
+        assert_eq!(runs[0].error.as_deref(), Some("delivery failed"));
+        service.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_automation_runs_on_matching_event() {
+        let temp_dir = TempDir::new().unwrap();
+        let store_path = temp_dir.path().join("cron.json");
+        let callback: JobCallback = Arc::new(|job, _token| {
+            Box::pin(async move { CronJobOutcome::with_response(job.payload.message) })
+        });
+        let service = Arc::new(CronService::new(store_path.clone(), Some(callback)));
+        service.start().await;
+        let rule = service
+            .create_automation(AutomationRequest {
+                name: "Invoices".to_string(),
+                trigger: crate::cron::AutomationTrigger::Message {
+                    channel: None,
+                    pattern: "invoice".to_string(),
+                },
+                payload: CronPayload {
+                    message: "File it".to_string(),
+                    ..Default::default()
+                },
+                enabled: true,
+            })
+            .await
+            .unwrap();
+        let event = |content: &str| AutomationEvent::Message {
+            channel: "telegram".to_string(),
+            chat_id: "c1".to_string(),
+            sender_id: "u1".to_string(),
+            content: content.to_string(),
+        };
+
+        assert_eq!(service.dispatch_event(event("hello")).await, 0);
+        assert_eq!(service.dispatch_event(event("new invoice")).await, 1);
+        let mut runs = Vec::new();
+        for _ in 0..50 {
+            runs = service.list_runs(&rule.rule.id, 10).await;
+            if !runs.is_empty() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].trigger, CronTrigger::Event);
+        let response = runs[0].response.clone().unwrap();
+        assert!(response.starts_with("File it"));
+        assert!(response.contains("new invoice"));
+
+        let reloaded = CronService::new(store_path, None);
+        let stored = reloaded.get_automation(&rule.rule.id).await.unwrap();
+        assert_eq!(stored.rule.state.last_status.as_deref(), Some("ok"));
+        service.delete_automation(&rule.rule.id).await.unwrap();
+        assert!(service.list_automations().await.is_empty());
+        service.stop().await;
+    }
+
+    #[test]
+    fn test_failed_scheduled_run_is_retried_with_backoff() {
+        let mut job = CronJob {
+            id: "j1".to_string(),
+            name: "Flaky".to_string(),
+            enabled: true,
+            schedule: CronSchedule::every(3_600_000),
+            payload: CronPayload::default(),
+            state: CronJobState::default(),
+            created_at_ms: 0,
+            updated_at_ms: 0,
+            delete_after_run: false,
+            retry: CronRetryPolicy {
+                max_attempts: 2,
+                backoff_ms: 1000,
+            },
+            catch_up: CronCatchUpPolicy::Skip,
+        };
+        let snapshot = |trigger| CronRunSnapshot {
+            run_id: "r".to_string(),
+            job_id: "j1".to_string(),
+            started_at_ms: now_ms(),
+            last_heartbeat_at_ms: now_ms(),
+            trigger,
+            cancelable: true,
+        };
+
+        let failure = || Err("Error: provider down".to_string());
+        let (record, _) = settle_run(
+            &mut job,
+            &snapshot(CronTrigger::Scheduled),
+            1,
+            failure(),
+            CronJobOutcome::default(),
+            false,
+        );
+        assert_eq!(record.status, CronRunStatus::Error);
+        assert_eq!(job.state.retry_attempt, 1);
+        assert_eq!(job.state.pending_trigger, Some(CronTrigger::Retry));
+        let retry_at = job.state.next_run_at_ms.unwrap();
+        assert!(retry_at - now_ms() <= 1000);
+        assert_eq!(attempt_for(&job, &CronTrigger::Retry), 2);
+
+        let (record, _) = settle_run(
+            &mut job,
+            &snapshot(CronTrigger::Retry),
+            2,
+            failure(),
+            CronJobOutcome::default(),
+            false,
+        );
+        assert_eq!(record.attempt, 2);
+        assert_eq!(job.state.retry_attempt, 0);
+        assert!(job.state.pending_trigger.is_none());
+        assert!(job.state.next_run_at_ms.unwrap() - now_ms() > 1000);
+    }
+
+    #[test]
+    fn test_retry_policy_is_bounded() {
+        assert!(CronRetryPolicy::default().validate().is_ok());
+        for policy in [
+            CronRetryPolicy {
+                max_attempts: 0,
+                backoff_ms: 1000,
+            },
+            CronRetryPolicy {
+                max_attempts: MAX_RETRY_ATTEMPTS + 1,
+                backoff_ms: 1000,
+            },
+            CronRetryPolicy {
+                max_attempts: 2,
+                backoff_ms: -1,
+            },
+            CronRetryPolicy {
+                max_attempts: 2,
+                backoff_ms: i64::MAX,
+            },
+        ] {
+            assert!(policy.validate().is_err(), "{:?}", policy);
+        }
+
+        // Stored jobs from before the bounds never overflow the retry time
+        let mut job = CronJob {
+            id: "j1".to_string(),
+            name: "overflow".to_string(),
+            enabled: true,
+            schedule: CronSchedule::every(60_000),
+            payload: CronPayload::default(),
+            state: CronJobState::default(),
+            created_at_ms: 0,
+            updated_at_ms: 0,
+            delete_after_run: false,
+            retry: CronRetryPolicy {
+                max_attempts: 5,
+                backoff_ms: i64::MAX,
+            },
+            catch_up: CronCatchUpPolicy::Skip,
+        };
+        let snapshot = CronRunSnapshot {
+            run_id: "r".to_string(),
+            job_id: "j1".to_string(),
+            started_at_ms: now_ms(),
+            last_heartbeat_at_ms: now_ms(),
+            trigger: CronTrigger::Scheduled,
+            cancelable: true,
+        };
+        settle_run(
+            &mut job,
+            &snapshot,
+            3,
+            Err("Error: down".to_string()),
+            CronJobOutcome::default(),
+            false,
+        );
+        assert_eq!(job.state.next_run_at_ms, Some(i64::MAX));
+    }
+
+    #[tokio::test]
+    async fn test_catch_up_policy_on_start() {
+        let temp_dir = TempDir::new().unwrap();
+        let store_path = temp_dir.path().join("cron.json");
+        let missed = |id: &str, catch_up| CronJob {
+            id: id.to_string(),
+            name: id.to_string(),
+            enabled: true,
+            schedule: CronSchedule::every(3_600_000),
+            payload: CronPayload::default(),
+            state: CronJobState {
+                next_run_at_ms: Some(now_ms() - 60_000),
+                ..Default::default()
+            },
+            created_at_ms: 0,
+            updated_at_ms: 0,
+            delete_after_run: false,
+            retry: CronRetryPolicy::default(),
+            catch_up,
+        };
+        let store = CronStore {
+            version: 1,
+            jobs: vec![
+                missed("skip", CronCatchUpPolicy::Skip),
+                missed("once", CronCatchUpPolicy::RunOnce),
+            ],
+            automations: Vec::new(),
+        };
+        std::fs::write(&store_path, serde_json::to_string(&store).unwrap()).unwrap();
+
+        let service = CronService::new(store_path, None);
+        let mut loaded = service.load_store().await;
+        service.recompute_next_runs(&mut loaded).await;
+        let now = now_ms();
+        let skip = &loaded.jobs[0].state;
+        assert!(skip.next_run_at_ms.unwrap() > now);
+        assert!(skip.pending_trigger.is_none());
+        let once = &loaded.jobs[1].state;
+        assert!(once.next_run_at_ms.unwrap() <= now);
+        assert_eq!(once.pending_trigger, Some(CronTrigger::CatchUp));
+    }
 }
@@ -1,6 +1,9 @@
 //! Cron types for scheduled jobs
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::cron::automation::AutomationRule;
 
 /// Schedule definition for a cron job
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -44,11 +47,79 @@ impl CronSchedule {
     }
 }
 
-/// What to do when the job runs
+/// Kind of work a job performs
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CronPayloadKind {
+    /// Send `message` to the agent as a user turn
+    #[default]
+    AgentTurn,
+    /// Deliver `message` verbatim without involving the LLM
+    Message,
+    /// Run a registered tool with fixed arguments
+    Tool,
+    /// Call a URL and hand the response to an agent turn
+    Http,
+    /// Run a workflow file and report its output
+    Workflow,
+}
+
+impl CronPayloadKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::AgentTurn => "agent_turn",
+            Self::Message => "message",
+            Self::Tool => "tool",
+            Self::Http => "http",
+            Self::Workflow => "workflow",
+        }
+    }
+}
+
+/// Tool invocation for `tool` payloads
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CronToolCall {
+    pub name: String,
+    #[serde(default)]
+    pub args: serde_json::Value,
+}
+
+/// Request for `http` payloads
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CronHttpRequest {
+    pub url: String,
+    #[serde(default = "default_http_method")]
+    pub method: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+}
+
+/// Workflow run for `workflow` payloads
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CronWorkflowRun {
+    /// Workflow file; relative paths are resolved against the workspace
+    pub path: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub inputs: BTreeMap<String, String>,
+}
+
+fn default_http_method() -> String {
+    "GET".to_string()
+}
+
+const HTTP_METHODS: &[&str] = &["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD"];
+
+/// What to do when the job runs.
+///
+/// `message` is the prompt for `agent_turn`, the delivered text for
+/// `message`, and an optional instruction placed before the response body
+/// for `http`. `workflow` payloads deliver the workflow's output.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CronPayload {
-    #[serde(default = "default_payload_kind")]
-    pub kind: String,
+    #[serde(default)]
+    pub kind: CronPayloadKind,
     #[serde(default)]
     pub message: String,
     #[serde(default)]
@@ -57,20 +128,111 @@ pub struct CronPayload {
     pub channel: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub to: Option<String>,
-}
-
-fn default_payload_kind() -> String {
-    "agent_turn".to_string()
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool: Option<CronToolCall>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http: Option<CronHttpRequest>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workflow: Option<CronWorkflowRun>,
 }
 
 impl Default for CronPayload {
     fn default() -> Self {
         Self {
-            kind: "agent_turn".to_string(),
+            kind: CronPayloadKind::AgentTurn,
             message: String::new(),
             deliver: false,
             channel: None,
             to: None,
+            tool: None,
+            http: None,
+            workflow: None,
+        }
+    }
+}
+
+impl CronPayload {
+    /// Drop the runtime-reserved arguments (`context_*`, `_in_cron_context`)
+    /// of a `tool` payload. The runtime injects its own when the job runs, so
+    /// stored values could only be forged.
+    pub fn strip_context_args(&mut self) {
+        if let Some(args) = self
+            .tool
+            .as_mut()
+            .and_then(|tool| tool.args.as_object_mut())
+        {
+            args.retain(|key, _| !key.starts_with("context_") && key != "_in_cron_context");
+        }
+    }
+
+    /// Check that the fields required by `kind` are present and well-formed
+    pub fn validate(&self) -> Result<(), String> {
+        let kind = self.kind.as_str();
+        if self.tool.is_some() && self.kind != CronPayloadKind::Tool {
+            return Err(format!("'tool' is not allowed for {} payloads", kind));
+        }
+        if self.http.is_some() && self.kind != CronPayloadKind::Http {
+            return Err(format!("'http' is not allowed for {} payloads", kind));
+        }
+        if self.workflow.is_some() && self.kind != CronPayloadKind::Workflow {
+            return Err(format!("'workflow' is not allowed for {} payloads", kind));
+        }
+
+        match self.kind {
+            CronPayloadKind::AgentTurn => Ok(()),
+            CronPayloadKind::Message => {
+                if self.message.trim().is_empty() {
+                    return Err("message payloads need a non-empty message".to_string());
+                }
+                if self.channel.is_none() || self.to.is_none() {
+                    return Err("message payloads need both channel and to".to_string());
+                }
+                Ok(())
+            }
+            CronPayloadKind::Tool => {
+                let tool = self
+                    .tool
+                    .as_ref()
+                    .ok_or_else(|| "tool payloads need a 'tool' definition".to_string())?;
+                if tool.name.trim().is_empty() {
+                    return Err("tool payloads need a tool name".to_string());
+                }
+                if tool.name == "cron" {
+                    return Err("tool payloads cannot invoke the cron tool".to_string());
+                }
+                if !(tool.args.is_object() || tool.args.is_null()) {
+                    return Err("tool arguments must be a JSON object".to_string());
+                }
+                Ok(())
+            }
+            CronPayloadKind::Http => {
+                let http = self
+                    .http
+                    .as_ref()
+                    .ok_or_else(|| "http payloads need an 'http' definition".to_string())?;
+                let host = http
+                    .url
+                    .strip_prefix("https://")
+                    .or_else(|| http.url.strip_prefix("http://"))
+                    .ok_or_else(|| format!("invalid http url '{}'", http.url))?;
+                if host.is_empty() || host.starts_with('/') {
+                    return Err(format!("invalid http url '{}'", http.url));
+                }
+                if !HTTP_METHODS.contains(&http.method.to_ascii_uppercase().as_str()) {
+                    return Err(format!("unsupported http method '{}'", http.method));
+                }
+                Ok(())
+            }
+            CronPayloadKind::Workflow => {
+                let workflow = self
+                    .workflow
+                    .as_ref()
+                    .ok_or_else(|| "workflow payloads need a 'workflow' definition".to_string())?;
+                if workflow.path.trim().is_empty() {
+                    return Err("workflow payloads need a workflow path".to_string());
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -86,6 +248,85 @@ pub struct CronJobState {
     pub last_status: Option<String>,
     #[serde(rename = "lastError", skip_serializing_if = "Option::is_none")]
     pub last_error: Option<String>,
+    /// Attempts already spent on the current failing occurrence
+    #[serde(rename = "retryAttempt", default, skip_serializing_if = "is_zero")]
+    pub retry_attempt: u32,
+    /// Why the next run is due when it is not a plain scheduled run
+    #[serde(rename = "pendingTrigger", skip_serializing_if = "Option::is_none")]
+    pub pending_trigger: Option<CronTrigger>,
+}
+
+fn is_zero(value: &u32) -> bool {
+    *value == 0
+}
+
+/// How failed scheduled runs are retried
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CronRetryPolicy {
+    /// Total attempts per occurrence, including the first one
+    #[serde(rename = "maxAttempts", default = "default_max_attempts")]
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubles on every further attempt
+    #[serde(rename = "backoffMs", default = "default_backoff_ms")]
+    pub backoff_ms: i64,
+}
+
+/// Most attempts a retry policy may ask for per occurrence
+pub const MAX_RETRY_ATTEMPTS: u32 = 10;
+/// Longest initial retry delay: one day
+pub const MAX_RETRY_BACKOFF_MS: i64 = 24 * 60 * 60 * 1000;
+
+fn default_max_attempts() -> u32 {
+    1
+}
+
+fn default_backoff_ms() -> i64 {
+    60_000
+}
+
+impl CronRetryPolicy {
+    /// Check that the policy stays within the supported attempt and delay range
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=MAX_RETRY_ATTEMPTS).contains(&self.max_attempts) {
+            return Err(format!(
+                "retry maxAttempts must be between 1 and {}",
+                MAX_RETRY_ATTEMPTS
+            ));
+        }
+        if !(0..=MAX_RETRY_BACKOFF_MS).contains(&self.backoff_ms) {
+            return Err(format!(
+                "retry backoffMs must be between 0 and {}",
+                MAX_RETRY_BACKOFF_MS
+            ));
+        }
+        Ok(())
+    }
+
+    /// Delay before retry number `attempt` (1-based)
+    pub fn backoff_for(&self, attempt: u32) -> i64 {
+        let exponent = attempt.saturating_sub(1).min(16);
+        self.backoff_ms.max(0).saturating_mul(1_i64 << exponent)
+    }
+}
+
+impl Default for CronRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_max_attempts(),
+            backoff_ms: default_backoff_ms(),
+        }
+    }
+}
+
+/// What to do with runs missed while the service was down
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CronCatchUpPolicy {
+    /// Drop missed runs and wait for the next occurrence
+    #[default]
+    Skip,
+    /// Run once right after startup, however many occurrences were missed
+    RunOnce,
 }
 
 /// A scheduled job
@@ -106,6 +347,10 @@ pub struct CronJob {
     pub updated_at_ms: i64,
     #[serde(rename = "deleteAfterRun", default)]
     pub delete_after_run: bool,
+    #[serde(default)]
+    pub retry: CronRetryPolicy,
+    #[serde(rename = "catchUp", default)]
+    pub catch_up: CronCatchUpPolicy,
 }
 
 fn default_true() -> bool {
@@ -119,6 +364,8 @@ pub struct CronStore {
     pub version: i32,
     #[serde(default)]
     pub jobs: Vec<CronJob>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub automations: Vec<AutomationRule>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -126,6 +373,10 @@ pub struct CronStore {
 pub enum CronTrigger {
     Scheduled,
     Manual,
+    Retry,
+    CatchUp,
+    /// Fired by an automation rule
+    Event,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -172,6 +423,10 @@ pub struct CreateCronJobRequest {
     pub delete_after_run: bool,
     #[serde(default = "default_true")]
     pub enabled: bool,
+    #[serde(default)]
+    pub retry: CronRetryPolicy,
+    #[serde(rename = "catchUp", default)]
+    pub catch_up: CronCatchUpPolicy,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -184,6 +439,74 @@ pub struct UpdateCronJobRequest {
     pub delete_after_run: bool,
     #[serde(default = "default_true")]
     pub enabled: bool,
+    #[serde(default)]
+    pub retry: CronRetryPolicy,
+    #[serde(rename = "catchUp", default)]
+    pub catch_up: CronCatchUpPolicy,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CronRunStatus {
+    Ok,
+    Error,
+    Cancelled,
+}
+
+/// One finished execution of a job, as kept in the run log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CronRunRecord {
+    pub run_id: String,
+    pub job_id: String,
+    pub trigger: CronTrigger,
+    pub attempt: u32,
+    #[serde(rename = "startedAtMs")]
+    pub started_at_ms: i64,
+    #[serde(rename = "finishedAtMs")]
+    pub finished_at_ms: i64,
+    pub status: CronRunStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(rename = "toolCalls", default)]
+    pub tool_calls: usize,
+    #[serde(rename = "traceId", skip_serializing_if = "Option::is_none")]
+    pub trace_id: Option<String>,
+}
+
+/// What a job callback reports back about its run
+#[derive(Debug, Clone, Default)]
+pub struct CronJobOutcome {
+    pub response: Option<String>,
+    /// Set by the runner when the run failed; the response text is never
+    /// inspected, so a reminder may well start with "Error"
+    pub error: Option<String>,
+    pub tool_calls: usize,
+    pub trace_id: Option<String>,
+}
+
+impl CronJobOutcome {
+    pub fn with_response(response: impl Into<String>) -> Self {
+        Self {
+            response: Some(response.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Outcome of a failed run; the error is also kept as its response
+    pub fn failed(error: impl Into<String>) -> Self {
+        let mut outcome = Self::default();
+        outcome.fail(error);
+        outcome
+    }
+
+    /// Mark the run as failed with `error`
+    pub fn fail(&mut self, error: impl Into<String>) {
+        let error = error.into();
+        self.response = Some(error.clone());
+        self.error = Some(error);
+    }
 }
 
 fn default_version() -> i32 {
@@ -195,6 +518,7 @@ impl Default for CronStore {
         Self {
             version: 1,
             jobs: Vec::new(),
+            automations: Vec::new(),
         }
     }
 }
@@ -224,7 +548,7 @@ mod tests {
     #[test]
     fn test_cron_payload_default() {
         let payload = CronPayload::default();
-        assert_eq!(payload.kind, "agent_turn");
+        assert_eq!(payload.kind, CronPayloadKind::AgentTurn);
         assert_eq!(payload.message, "");
         assert!(!payload.deliver);
         assert!(payload.channel.is_none());
@@ -258,4 +582,99 @@ mod tests {
             CronSchedule::Every { every_ms: 5000 }
         ));
     }
+
+    #[test]
+    fn test_legacy_job_gets_default_policies() {
+        let job: CronJob = serde_json::from_str(
+            r#"{"id":"a1","name":"n","schedule":{"kind":"every","everyMs":1000}}"#,
+        )
+        .unwrap();
+        assert_eq!(job.retry, CronRetryPolicy::default());
+        assert_eq!(job.catch_up, CronCatchUpPolicy::Skip);
+        assert_eq!(job.state.retry_attempt, 0);
+    }
+
+    #[test]
+    fn test_payload_validation() {
+        let message = CronPayload {
+            kind: CronPayloadKind::Message,
+            message: "Stand-up in 5 minutes".to_string(),
+            ..Default::default()
+        };
+        assert!(message.validate().is_err());
+        let message = CronPayload {
+            channel: Some("telegram".to_string()),
+            to: Some("42".to_string()),
+            ..message
+        };
+        assert!(message.validate().is_ok());
+
+        let tool: CronPayload = serde_json::from_str(
+            r#"{"kind":"tool","tool":{"name":"web_fetch","args":{"url":"https://example.com"}}}"#,
+        )
+        .unwrap();
+        assert!(tool.validate().is_ok());
+        let mut forged: CronPayload = serde_json::from_str(
+            r#"{"kind":"tool","tool":{"name":"exec","args":{"command":"ls","context_session_key":"telegram:42","_in_cron_context":false}}}"#,
+        )
+        .unwrap();
+        forged.strip_context_args();
+        assert_eq!(
+            forged.tool.unwrap().args,
+            serde_json::json!({"command": "ls"})
+        );
+        let recursive = CronPayload {
+            tool: Some(CronToolCall {
+                name: "cron".to_string(),
+                args: serde_json::Value::Null,
+            }),
+            ..tool.clone()
+        };
+        assert!(recursive.validate().is_err());
+        let mismatched = CronPayload {
+            kind: CronPayloadKind::AgentTurn,
+            ..tool
+        };
+        assert!(mismatched.validate().is_err());
+
+        let http: CronPayload =
+            serde_json::from_str(r#"{"kind":"http","http":{"url":"https://example.com/status"}}"#)
+                .unwrap();
+        assert_eq!(http.http.as_ref().unwrap().method, "GET");
+        assert!(http.validate().is_ok());
+        let bad_url = CronPayload {
+            http: Some(CronHttpRequest {
+                url: "ftp://example.com".to_string(),
+                ..http.http.clone().unwrap()
+            }),
+            ..http
+        };
+        assert!(bad_url.validate().is_err());
+
+        let workflow: CronPayload = serde_json::from_str(
+            r#"{"kind":"workflow","workflow":{"path":"flows/digest.yaml","inputs":{"topic":"rust"}}}"#,
+        )
+        .unwrap();
+        assert!(workflow.validate().is_ok());
+        assert_eq!(workflow.workflow.as_ref().unwrap().inputs["topic"], "rust");
+        let empty_path = CronPayload {
+            workflow: Some(CronWorkflowRun {
+                path: " ".to_string(),
+                inputs: BTreeMap::new(),
+            }),
+            ..workflow
+        };
+        assert!(empty_path.validate().is_err());
+    }
+
+    #[test]
+    fn test_retry_backoff_doubles() {
+        let policy = CronRetryPolicy {
+            max_attempts: 4,
+            backoff_ms: 1000,
+        };
+        assert_eq!(policy.backoff_for(1), 1000);
+        assert_eq!(policy.backoff_for(2), 2000);
+        assert_eq!(policy.backoff_for(3), 4000);
+    }
 }
@@ -1,10 +1,12 @@
 //! Heartbeat service for periodic agent wake-up
 
 pub mod service;
+pub mod tasks;
 pub mod types;
 
 pub use service::{HeartbeatDecideCallback, HeartbeatExecuteCallback, HeartbeatService};
+pub use tasks::{parse_heartbeat_tasks, HeartbeatTask, QuietHours, DEFAULT_HEARTBEAT_TASK};
 pub use types::{
     heartbeat_tool_definition, is_heartbeat_empty, HeartbeatConfig, HeartbeatDecision,
-    DEFAULT_HEARTBEAT_INTERVAL_S, HEARTBEAT_SYSTEM_PROMPT,
+    HeartbeatRun, DEFAULT_HEARTBEAT_INTERVAL_S, DEFAULT_HEARTBEAT_TICK_S, HEARTBEAT_SYSTEM_PROMPT,
 };
@@ -1,17 +1,23 @@
 //! Heartbeat service for periodic agent wake-up
 
+use std::collections::HashMap;
 use std::future::Future;
 use std::path::{Path, PathBuf};
 use std::pin::Pin;
 use std::sync::Arc;
 
+use chrono::Utc;
 use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
 use tracing::{debug, error, info, warn};
 
-use crate::heartbeat::types::{is_heartbeat_empty, HeartbeatConfig, HeartbeatDecision};
+use crate::heartbeat::tasks::{parse_heartbeat_tasks, HeartbeatTask};
+use crate::heartbeat::types::{
+    is_heartbeat_empty, HeartbeatConfig, HeartbeatDecision, HeartbeatRun,
+};
+use crate::trace::{TraceEvent, TraceId, TraceLogger};
 
-/// Callback for the LLM decision phase: takes HEARTBEAT.md content and returns a HeartbeatDecision.
+/// Callback for the LLM decision phase: takes the prompt of one due task and returns a HeartbeatDecision.
 pub type HeartbeatDecideCallback = Arc<
     dyn Fn(
             String,
@@ -28,20 +34,23 @@ pub type HeartbeatDecideCallback = Arc<
         + Sync,
 >;
 
-/// Callback for the task execution phase: takes a tasks summary string and runs the agent loop.
+/// Callback for the task execution phase: runs the agent loop for one task the decision pass chose to run.
 pub type HeartbeatExecuteCallback =
-    Arc<dyn Fn(String) -> Pin<Box<dyn Future<Output = String> + Send>> + Send + Sync>;
+    Arc<dyn Fn(HeartbeatRun) -> Pin<Box<dyn Future<Output = String> + Send>> + Send + Sync>;
 
 /// Periodic heartbeat service that wakes the agent to check for tasks.
 ///
-/// Two-phase design:
-/// 1. **Decide** — read HEARTBEAT.md, call the LLM with a tool to decide skip/run.
+/// HEARTBEAT.md is split into tasks (see [`crate::heartbeat::tasks`]), each
+/// with its own cadence. Every tick the due tasks go through two phases:
+/// 1. **Decide** — call the LLM with a tool to decide skip/run for the task.
 /// 2. **Execute** — if the decision is "run", invoke the full agent loop with the tasks summary.
 pub struct HeartbeatService {
     workspace: PathBuf,
     config: HeartbeatConfig,
     on_decide: Option<HeartbeatDecideCallback>,
     on_execute: Option<HeartbeatExecuteCallback>,
+    trace_logger: Option<Arc<TraceLogger>>,
+    last_runs: Arc<RwLock<HashMap<String, i64>>>,
     running: Arc<RwLock<bool>>,
     task: Arc<RwLock<Option<JoinHandle<()>>>>,
 }
@@ -59,16 +68,36 @@ impl HeartbeatService {
             config,
             on_decide,
             on_execute,
+            trace_logger: None,
+            last_runs: Arc::new(RwLock::new(HashMap::new())),
             running: Arc::new(RwLock::new(false)),
             task: Arc::new(RwLock::new(None)),
         }
     }
 
+    /// Log skip/run decisions to the runtime trace.
+    pub fn with_trace_logger(mut self, trace_logger: Arc<TraceLogger>) -> Self {
+        self.trace_logger = Some(trace_logger);
+        self
+    }
+
     /// Get the path to HEARTBEAT.md
     fn heartbeat_file(&self) -> PathBuf {
         self.workspace.join("HEARTBEAT.md")
     }
 
+    fn runner(&self, started_at_ms: i64) -> HeartbeatRunner {
+        HeartbeatRunner {
+            workspace: self.workspace.clone(),
+            config: self.config.clone(),
+            on_decide: self.on_decide.clone(),
+            on_execute: self.on_execute.clone(),
+            trace_logger: self.trace_logger.clone(),
+            last_runs: Arc::clone(&self.last_runs),
+            started_at_ms,
+        }
+    }
+
     /// Start the heartbeat service
     pub async fn start(&self) {
         if !self.config.enabled {
@@ -86,24 +115,19 @@ impl HeartbeatService {
 
         *self.running.write().await = true;
 
-        let interval_s = self.config.interval_s;
+        let tick_s = self.config.tick_s.max(1);
         let running = Arc::clone(&self.running);
-        let on_decide = self.on_decide.clone();
-        let on_execute = self.on_execute.clone();
-        let workspace = self.workspace.clone();
+        let runner = self.runner(Utc::now().timestamp_millis());
 
         let task = tokio::spawn(async move {
-            let handle = HeartbeatServiceHandle {
-                workspace,
-                on_decide,
-                on_execute,
-                running: Arc::clone(&running),
-            };
-            handle.run_loop(interval_s).await;
+            runner.run_loop(tick_s, running).await;
         });
 
         *self.task.write().await = Some(task);
-        info!("Heartbeat started (every {}s)", interval_s);
+        info!(
+            "Heartbeat started (checking every {}s, default cadence {}s)",
+            tick_s, self.config.interval_s
+        );
     }
 
     /// Stop the heartbeat service
@@ -121,33 +145,28 @@ impl HeartbeatService {
         *self.running.read().await
     }
 
-    /// Manually trigger a heartbeat tick (decide + optionally execute).
+    /// Manually trigger a heartbeat tick for every task, due or not
+    /// (decide + optionally execute).
     pub async fn trigger_now(&self) -> Option<String> {
-        let on_decide = self.on_decide.as_ref()?;
-        let workspace = self.workspace.clone();
+        self.on_decide.as_ref()?;
 
-        let content = read_heartbeat_file(&workspace).await;
+        let content = read_heartbeat_file(&self.workspace).await;
         if is_heartbeat_empty(content.as_deref()) {
             return Some("skip (empty)".to_string());
         }
 
-        let heartbeat_content = content.unwrap_or_default();
-        match (on_decide)(heartbeat_content).await {
-            Ok(decision) if decision.is_run() => {
-                let tasks = decision.tasks.unwrap_or_default();
-                if let Some(on_execute) = &self.on_execute {
-                    let result = (on_execute)(tasks).await;
-                    Some(result)
-                } else {
-                    Some("run (no execute callback)".to_string())
-                }
-            }
-            Ok(_) => Some("skip".to_string()),
-            Err(e) => {
-                warn!("Heartbeat decide error: {}", e);
-                Some(format!("error: {}", e))
-            }
+        let runner = self.runner(Utc::now().timestamp_millis());
+        let mut outcomes = runner.tick(true).await;
+        if outcomes.len() == 1 {
+            return outcomes.pop().map(|(_, outcome)| outcome);
         }
+        Some(
+            outcomes
+                .into_iter()
+                .map(|(task, outcome)| format!("{}: {}", task, outcome))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        )
     }
 
     /// Get service status
@@ -156,14 +175,37 @@ impl HeartbeatService {
         let has_decide = self.on_decide.is_some();
         let has_execute = self.on_execute.is_some();
         let heartbeat_file_exists = self.heartbeat_file().exists();
+        let tasks = read_heartbeat_file(&self.workspace)
+            .await
+            .map(|content| parse_heartbeat_tasks(&content, self.config.interval_s))
+            .unwrap_or_default();
+        let last_runs = self.last_runs.read().await;
+        let tasks: Vec<_> = tasks
+            .iter()
+            .map(|task| {
+                let last_run = last_runs.get(&task.name).copied();
+                serde_json::json!({
+                    "name": task.name,
+                    "every_s": task.every_s,
+                    "quiet_hours": task.quiet_hours,
+                    "timezone": task.timezone,
+                    "channel": task.channel,
+                    "chat_id": task.chat_id,
+                    "last_run_at_ms": last_run,
+                })
+            })
+            .collect();
 
         serde_json::json!({
             "enabled": self.config.enabled,
             "running": is_running,
             "interval_s": self.config.interval_s,
+            "tick_s": self.config.tick_s,
+            "timezone": self.config.timezone,
             "has_decide_callback": has_decide,
             "has_execute_callback": has_execute,
             "heartbeat_file_exists": heartbeat_file_exists,
+            "tasks": tasks,
         })
     }
 }
@@ -184,77 +226,164 @@ async fn read_heartbeat_file(workspace: &Path) -> Option<String> {
     }
 }
 
-/// Handle for async task (to avoid circular references)
-struct HeartbeatServiceHandle {
+/// Tick logic shared by the background loop and `trigger_now`
+struct HeartbeatRunner {
     workspace: PathBuf,
+    config: HeartbeatConfig,
     on_decide: Option<HeartbeatDecideCallback>,
     on_execute: Option<HeartbeatExecuteCallback>,
-    running: Arc<RwLock<bool>>,
+    trace_logger: Option<Arc<TraceLogger>>,
+    /// Last run per task name; tasks never run count from `started_at_ms`
+    last_runs: Arc<RwLock<HashMap<String, i64>>>,
+    started_at_ms: i64,
 }
 
-impl HeartbeatServiceHandle {
-    async fn run_loop(&self, interval_s: i64) {
-        let interval = tokio::time::Duration::from_secs(interval_s as u64);
+impl HeartbeatRunner {
+    async fn run_loop(&self, tick_s: i64, running: Arc<RwLock<bool>>) {
+        let interval = tokio::time::Duration::from_secs(tick_s as u64);
 
         loop {
             tokio::time::sleep(interval).await;
 
-            let is_running = *self.running.read().await;
+            let is_running = *running.read().await;
             if !is_running {
                 break;
             }
 
-            if let Err(e) = self.tick().await {
-                error!("Heartbeat error: {}", e);
-            }
+            self.tick(false).await;
         }
     }
 
-    async fn tick(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    /// Run the due tasks, or all of them when `force` is set. Returns the
+    /// outcome per task that went through the decision pass.
+    async fn tick(&self, force: bool) -> Vec<(String, String)> {
         let content = read_heartbeat_file(&self.workspace).await;
 
         // Skip if HEARTBEAT.md is empty or doesn't exist
         if is_heartbeat_empty(content.as_deref()) {
             debug!("Heartbeat: no tasks (HEARTBEAT.md empty)");
-            return Ok(());
+            return Vec::new();
         }
 
-        info!("Heartbeat: checking for tasks...");
+        let now = Utc::now();
+        let now_ms = now.timestamp_millis();
+        let tasks = parse_heartbeat_tasks(&content.unwrap_or_default(), self.config.interval_s);
+        let mut outcomes = Vec::new();
+        for task in tasks {
+            if !force {
+                let last_run = self
+                    .last_runs
+                    .read()
+                    .await
+                    .get(&task.name)
+                    .copied()
+                    .unwrap_or(self.started_at_ms);
+                if now_ms - last_run < task.every_s.saturating_mul(1000) {
+                    continue;
+                }
+                // Held back, not skipped: the task runs once quiet hours end.
+                if task.is_quiet(now, self.config.timezone.as_deref()) {
+                    debug!("Heartbeat: task '{}' is in quiet hours", task.name);
+                    continue;
+                }
+            }
+            self.last_runs
+                .write()
+                .await
+                .insert(task.name.clone(), now_ms);
+            let outcome = self.run_task(&task).await;
+            outcomes.push((task.name, outcome));
+        }
+        outcomes
+    }
 
+    async fn run_task(&self, task: &HeartbeatTask) -> String {
         let on_decide = match &self.on_decide {
             Some(cb) => cb,
             None => {
                 debug!("Heartbeat: no decide callback");
-                return Ok(());
+                return "skip (no decide callback)".to_string();
             }
         };
 
-        let heartbeat_content = content.unwrap_or_default();
-        let decision = match (on_decide)(heartbeat_content).await {
+        info!("Heartbeat: checking task '{}'...", task.name);
+        let trace_id = TraceId::new();
+        let decision = match (on_decide)(task.prompt()).await {
             Ok(d) => d,
             Err(e) => {
-                warn!("Heartbeat decide failed, defaulting to skip: {}", e);
-                HeartbeatDecision {
-                    action: "skip".to_string(),
-                    tasks: None,
-                }
+                warn!(
+                    "Heartbeat decide failed for '{}', defaulting to skip: {}",
+                    task.name, e
+                );
+                self.trace_decision(&trace_id, task, "skip", None, Some(e.to_string()));
+                return format!("error: {}", e);
             }
         };
 
-        if decision.is_run() {
-            let tasks = decision.tasks.unwrap_or_default();
-            info!("Heartbeat: running tasks");
-            if let Some(on_execute) = &self.on_execute {
-                let _result = (on_execute)(tasks).await;
-                info!("Heartbeat: completed task execution");
-            } else {
+        if !decision.is_run() {
+            info!("Heartbeat: task '{}' OK (no action needed)", task.name);
+            self.trace_decision(&trace_id, task, "skip", None, None);
+            return "skip".to_string();
+        }
+
+        let tasks = decision.tasks.unwrap_or_default();
+        self.trace_decision(&trace_id, task, "run", Some(&tasks), None);
+        info!("Heartbeat: running task '{}'", task.name);
+        match &self.on_execute {
+            Some(on_execute) => {
+                let result = (on_execute)(HeartbeatRun {
+                    task: task.name.clone(),
+                    tasks,
+                    channel: task.channel.clone(),
+                    chat_id: task.chat_id.clone(),
+                    trace_id,
+                })
+                .await;
+                info!("Heartbeat: completed task '{}'", task.name);
+                result
+            }
+            None => {
                 warn!("Heartbeat: decision was 'run' but no execute callback");
+                "run (no execute callback)".to_string()
             }
-        } else {
-            info!("Heartbeat: OK (no action needed)");
         }
+    }
 
-        Ok(())
+    fn trace_decision(
+        &self,
+        trace_id: &TraceId,
+        task: &HeartbeatTask,
+        action: &str,
+        tasks: Option<&str>,
+        error: Option<String>,
+    ) {
+        let Some(logger) = &self.trace_logger else {
+            return;
+        };
+        let summary = match (&error, tasks) {
+            (Some(error), _) => format!("{}: skip after decide error: {}", task.name, error),
+            (None, Some(tasks)) => format!("{}: run: {}", task.name, tasks),
+            (None, None) => format!("{}: {}", task.name, action),
+        };
+        let event = TraceEvent::new(
+            if error.is_some() { "warn" } else { "info" },
+            trace_id.clone(),
+            format!("heartbeat:{}", task.name),
+            task.channel.as_deref().unwrap_or("heartbeat"),
+            "heartbeat",
+            format!("heartbeat_{}", action),
+            summary,
+            serde_json::json!({
+                "task": task.name,
+                "action": action,
+                "every_s": task.every_s,
+                "tasks": tasks,
+                "error": error,
+            }),
+        );
+        if let Err(e) = logger.write_event(&event) {
+            error!("Failed to write heartbeat trace event: {}", e);
+        }
     }
 }
 
@@ -305,6 +434,7 @@ mod tests {
         let config = HeartbeatConfig {
             enabled: false,
             interval_s: 60,
+            ..Default::default()
         };
         let service = HeartbeatService::new(temp_dir.path().to_path_buf(), config, None, None);
         service.start().await;
@@ -317,6 +447,7 @@ mod tests {
         let config = HeartbeatConfig {
             enabled: true,
             interval_s: 3600,
+            ..Default::default()
         };
         let service = HeartbeatService::new(temp_dir.path().to_path_buf(), config, None, None);
         service.start().await;
@@ -375,11 +506,11 @@ mod tests {
 
         let execute_counter = Arc::new(AtomicUsize::new(0));
         let counter_clone = Arc::clone(&execute_counter);
-        let on_execute: HeartbeatExecuteCallback = Arc::new(move |tasks: String| {
+        let on_execute: HeartbeatExecuteCallback = Arc::new(move |run: HeartbeatRun| {
             let counter = Arc::clone(&counter_clone);
             Box::pin(async move {
                 counter.fetch_add(1, Ordering::SeqCst);
-                format!("executed: {}", tasks)
+                format!("executed: {}", run.tasks)
             })
         });
 
@@ -420,7 +551,7 @@ mod tests {
 
         let execute_counter = Arc::new(AtomicUsize::new(0));
         let ec = Arc::clone(&execute_counter);
-        let on_execute: HeartbeatExecuteCallback = Arc::new(move |_tasks: String| {
+        let on_execute: HeartbeatExecuteCallback = Arc::new(move |_run: HeartbeatRun| {
             let ec = Arc::clone(&ec);
             Box::pin(async move {
                 ec.fetch_add(1, Ordering::SeqCst);
@@ -431,6 +562,8 @@ mod tests {
         let config = HeartbeatConfig {
             enabled: true,
             interval_s: 1,
+            tick_s: 1,
+            ..Default::default()
         };
         let service = HeartbeatService::new(
             temp_dir.path().to_path_buf(),
@@ -439,7 +572,7 @@ mod tests {
             Some(on_execute),
         );
         service.start().await;
-        tokio::time::sleep(tokio::time::Duration::from_millis(1500)).await;
+        tokio::time::sleep(tokio::time::Duration::from_millis(2500)).await;
         service.stop().await;
 
         assert!(decide_counter.load(Ordering::SeqCst) >= 1);
@@ -472,11 +605,13 @@ mod tests {
         let config = HeartbeatConfig {
             enabled: true,
             interval_s: 1,
+            tick_s: 1,
+            ..Default::default()
         };
         let service =
             HeartbeatService::new(temp_dir.path().to_path_buf(), config, Some(on_decide), None);
         service.start().await;
-        tokio::time::sleep(tokio::time::Duration::from_millis(1500)).await;
+        tokio::time::sleep(tokio::time::Duration::from_millis(2500)).await;
         service.stop().await;
 
         // Decide should NOT have been called (file is empty/non-actionable)
@@ -495,7 +630,7 @@ mod tests {
 
         let execute_counter = Arc::new(AtomicUsize::new(0));
         let ec = Arc::clone(&execute_counter);
-        let on_execute: HeartbeatExecuteCallback = Arc::new(move |_tasks: String| {
+        let on_execute: HeartbeatExecuteCallback = Arc::new(move |_run: HeartbeatRun| {
             let ec = Arc::clone(&ec);
             Box::pin(async move {
                 ec.fetch_add(1, Ordering::SeqCst);
@@ -506,6 +641,8 @@ mod tests {
         let config = HeartbeatConfig {
             enabled: true,
             interval_s: 1,
+            tick_s: 1,
+            ..Default::default()
         };
         let service = HeartbeatService::new(
             temp_dir.path().to_path_buf(),
@@ -514,10 +651,58 @@ mod tests {
             Some(on_execute),
         );
         service.start().await;
-        tokio::time::sleep(tokio::time::Duration::from_millis(1500)).await;
+        tokio::time::sleep(tokio::time::Duration::from_millis(2500)).await;
         service.stop().await;
 
         // Execute should NOT have been called (error defaults to skip)
         assert_eq!(execute_counter.load(Ordering::SeqCst), 0);
     }
+
+    #[tokio::test]
+    async fn test_only_due_tasks_are_decided_and_skips_are_traced() {
+        let temp_dir = TempDir::new().unwrap();
+        tokio::fs::write(
+            temp_dir.path().join("HEARTBEAT.md"),
+            "## task: fast\nevery: 1s\n\nPoll the queue.\n\n## task: slow\nevery: 1h\n\nSummarize the news.\n",
+        )
+        .await
+        .unwrap();
+
+        let prompts = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = Arc::clone(&prompts);
+        let on_decide: HeartbeatDecideCallback = Arc::new(move |prompt: String| {
+            seen.lock().unwrap().push(prompt);
+            Box::pin(async move {
+                Ok(HeartbeatDecision {
+                    action: "skip".to_string(),
+                    tasks: None,
+                })
+            })
+        });
+        let trace_dir = temp_dir.path().join("traces");
+        let service = HeartbeatService::new(
+            temp_dir.path().to_path_buf(),
+            HeartbeatConfig::default(),
+            Some(on_decide),
+            None,
+        )
+        .with_trace_logger(Arc::new(TraceLogger::new(
+            true, &trace_dir, 7, 1000, 4000, false,
+        )));
+
+        let runner = service.runner(Utc::now().timestamp_millis() - 5_000);
+        let outcomes = runner.tick(false).await;
+        assert_eq!(outcomes, vec![("fast".to_string(), "skip".to_string())]);
+        assert!(prompts.lock().unwrap()[0].contains("Poll the queue."));
+
+        // Just ran, so nothing is due on the next tick.
+        assert!(runner.tick(false).await.is_empty());
+
+        let mut trace = String::new();
+        for entry in std::fs::read_dir(&trace_dir).unwrap() {
+            trace.push_str(&std::fs::read_to_string(entry.unwrap().path()).unwrap());
+        }
+        assert!(trace.contains("heartbeat_skip"));
+        assert!(trace.contains("\"task\":\"fast\""));
+    }
 }
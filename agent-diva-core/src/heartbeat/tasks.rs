@@ -0,0 +1,305 @@
+//! Structured task blocks in HEARTBEAT.md
+//!
+//! A task block is a `## task: <name>` heading followed by optional
+//! `key: value` settings and a free-form body:
+//!
+//! ```markdown
+//! ## task: inbox
+//! every: 1h
+//! quiet_hours: 22:00-07:00
+//! timezone: Europe/Berlin
+//! channel: telegram
+//! chat_id: 12345
+//!
+//! Check the inbox and summarize anything urgent.
+//! ```
+//!
+//! Content outside task blocks keeps the old behaviour and becomes the
+//! implicit `default` task, run at the configured heartbeat interval.
+
+use chrono::{DateTime, NaiveTime, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::heartbeat::types::is_heartbeat_empty;
+
+/// Name of the task built from content outside task blocks
+pub const DEFAULT_HEARTBEAT_TASK: &str = "default";
+
+const TASK_HEADING_PREFIX: &str = "task:";
+
+/// Local time window during which a task is held back
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuietHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl QuietHours {
+    /// Parse `HH:MM-HH:MM`; the window may wrap past midnight
+    pub fn parse(value: &str) -> Option<Self> {
+        let (start, end) = value.split_once('-')?;
+        let start = NaiveTime::parse_from_str(start.trim(), "%H:%M").ok()?;
+        let end = NaiveTime::parse_from_str(end.trim(), "%H:%M").ok()?;
+        Some(Self { start, end })
+    }
+
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        let time = time.with_second(0).unwrap_or(time);
+        if self.start <= self.end {
+            time >= self.start && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+/// One schedulable unit of heartbeat work
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HeartbeatTask {
+    pub name: String,
+    /// Cadence in seconds
+    pub every_s: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quiet_hours: Option<QuietHours>,
+    /// IANA timezone for quiet hours; falls back to the service timezone
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chat_id: Option<String>,
+    pub body: String,
+}
+
+impl HeartbeatTask {
+    /// Whether `now` falls in the task's quiet hours
+    pub fn is_quiet(&self, now: DateTime<Utc>, default_timezone: Option<&str>) -> bool {
+        let Some(quiet) = self.quiet_hours else {
+            return false;
+        };
+        let timezone = self.timezone.as_deref().or(default_timezone);
+        let local = match timezone.map(|tz| tz.parse::<chrono_tz::Tz>()) {
+            Some(Ok(tz)) => now.with_timezone(&tz).time(),
+            Some(Err(_)) => {
+                warn!(
+                    "Heartbeat task '{}' has an invalid timezone, using local time",
+                    self.name
+                );
+                now.with_timezone(&chrono::Local).time()
+            }
+            None => now.with_timezone(&chrono::Local).time(),
+        };
+        quiet.contains(local)
+    }
+
+    /// Text handed to the decision pass
+    pub fn prompt(&self) -> String {
+        format!("## Task: {}\n\n{}", self.name, self.body.trim())
+    }
+}
+
+/// Parse `1h`, `30m`, `90s`, `1d`, `hourly`, `daily` or plain seconds.
+/// Zero and intervals too large to count in seconds are rejected.
+pub fn parse_every(value: &str) -> Option<i64> {
+    let value = value.trim().to_ascii_lowercase();
+    match value.as_str() {
+        "hourly" => return Some(3600),
+        "daily" => return Some(86_400),
+        "weekly" => return Some(7 * 86_400),
+        _ => {}
+    }
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (amount, unit) = value.split_at(split);
+    let amount: i64 = amount.parse().ok()?;
+    let scale = match unit.trim() {
+        "" | "s" | "sec" | "secs" => 1,
+        "m" | "min" | "mins" => 60,
+        "h" | "hr" | "hrs" => 3600,
+        "d" | "day" | "days" => 86_400,
+        _ => return None,
+    };
+    amount.checked_mul(scale).filter(|every_s| *every_s > 0)
+}
+
+/// Split HEARTBEAT.md into tasks. `default_every_s` applies to tasks
+/// without an `every` setting and to the implicit default task.
+pub fn parse_heartbeat_tasks(content: &str, default_every_s: i64) -> Vec<HeartbeatTask> {
+    let mut tasks = Vec::new();
+    let mut loose = Vec::new();
+    let mut current: Option<(String, Vec<&str>)> = None;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        let heading = trimmed.strip_prefix("## ").map(str::trim);
+        let task_name = heading.and_then(|heading| {
+            heading
+                .get(..TASK_HEADING_PREFIX.len())
+                .filter(|prefix| prefix.eq_ignore_ascii_case(TASK_HEADING_PREFIX))
+                .map(|_| heading[TASK_HEADING_PREFIX.len()..].trim().to_string())
+        });
+        if let Some(name) = task_name {
+            if let Some((name, lines)) = current.take() {
+                tasks.push(build_task(name, &lines, default_every_s));
+            }
+            let name = if name.is_empty() {
+                format!("task-{}", tasks.len() + 1)
+            } else {
+                name
+            };
+            current = Some((name, Vec::new()));
+        } else if trimmed.starts_with("# ") || trimmed.starts_with("## ") {
+            // Any other heading of the same or a higher level closes the block.
+            if let Some((name, lines)) = current.take() {
+                tasks.push(build_task(name, &lines, default_every_s));
+            }
+            loose.push(line);
+        } else if let Some((_, lines)) = current.as_mut() {
+            lines.push(line);
+        } else {
+            loose.push(line);
+        }
+    }
+    if let Some((name, lines)) = current.take() {
+        tasks.push(build_task(name, &lines, default_every_s));
+    }
+
+    let loose = loose.join("\n");
+    if !is_heartbeat_empty(Some(&loose)) {
+        tasks.insert(
+            0,
+            HeartbeatTask {
+                name: DEFAULT_HEARTBEAT_TASK.to_string(),
+                every_s: default_every_s,
+                quiet_hours: None,
+                timezone: None,
+                channel: None,
+                chat_id: None,
+                body: loose.trim().to_string(),
+            },
+        );
+    }
+    tasks.retain(|task| !is_heartbeat_empty(Some(&task.body)));
+    tasks
+}
+
+fn build_task(name: String, lines: &[&str], default_every_s: i64) -> HeartbeatTask {
+    let mut task = HeartbeatTask {
+        name,
+        every_s: default_every_s,
+        quiet_hours: None,
+        timezone: None,
+        channel: None,
+        chat_id: None,
+        body: String::new(),
+    };
+
+    // Settings are the leading `key: value` lines, up to the first blank line
+    // or the first line that is not a known key.
+    let mut body_start = lines.len();
+    for (index, line) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            body_start = index + 1;
+            break;
+        }
+        let Some((key, value)) = trimmed.split_once(':') else {
+            body_start = index;
+            break;
+        };
+        let value = value.trim();
+        match key.trim().to_ascii_lowercase().as_str() {
+            "every" => match parse_every(value) {
+                Some(every_s) => task.every_s = every_s,
+                None => warn!("Heartbeat task '{}': invalid every '{}'", task.name, value),
+            },
+            "quiet_hours" | "quiet" => match QuietHours::parse(value) {
+                Some(quiet) => task.quiet_hours = Some(quiet),
+                None => warn!(
+                    "Heartbeat task '{}': invalid quiet hours '{}'",
+                    task.name, value
+                ),
+            },
+            "timezone" | "tz" => task.timezone = Some(value.to_string()),
+            "channel" => task.channel = Some(value.to_string()),
+            "chat_id" | "to" => task.chat_id = Some(value.to_string()),
+            _ => {
+                body_start = index;
+                break;
+            }
+        }
+    }
+    task.body = lines[body_start.min(lines.len())..]
+        .join("\n")
+        .trim()
+        .to_string();
+    task
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "# Heartbeat\n\nLook around.\n\n## task: inbox\nevery: 1h\nquiet_hours: 22:00-07:00\ntimezone: UTC\nchannel: telegram\nchat_id: 42\n\nCheck the inbox.\n\n## Task: news\nevery: daily\nSummarize the news.\n\n## Notes\n";
+
+    #[test]
+    fn test_parse_task_blocks() {
+        let tasks = parse_heartbeat_tasks(SAMPLE, 1800);
+        let names: Vec<_> = tasks.iter().map(|task| task.name.as_str()).collect();
+        assert_eq!(names, vec![DEFAULT_HEARTBEAT_TASK, "inbox", "news"]);
+
+        assert_eq!(tasks[0].every_s, 1800);
+        assert!(tasks[0].body.contains("Look around."));
+
+        let inbox = &tasks[1];
+        assert_eq!(inbox.every_s, 3600);
+        assert_eq!(inbox.channel.as_deref(), Some("telegram"));
+        assert_eq!(inbox.chat_id.as_deref(), Some("42"));
+        assert_eq!(inbox.body, "Check the inbox.");
+
+        assert_eq!(tasks[2].every_s, 86_400);
+        assert_eq!(tasks[2].body, "Summarize the news.");
+    }
+
+    #[test]
+    fn test_plain_file_is_single_default_task() {
+        let tasks = parse_heartbeat_tasks("Check logs", 600);
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].name, DEFAULT_HEARTBEAT_TASK);
+        assert!(parse_heartbeat_tasks("# Title\n- [ ]\n", 600).is_empty());
+    }
+
+    #[test]
+    fn test_parse_every() {
+        assert_eq!(parse_every("90s"), Some(90));
+        assert_eq!(parse_every("30m"), Some(1800));
+        assert_eq!(parse_every("2 h"), Some(7200));
+        assert_eq!(parse_every("600"), Some(600));
+        assert_eq!(parse_every("soon"), None);
+        assert_eq!(parse_every("0m"), None);
+        assert_eq!(parse_every("9223372036854775807d"), None);
+        assert_eq!(parse_every("99999999999999999999"), None);
+    }
+
+    #[test]
+    fn test_quiet_hours_wrap_midnight() {
+        let quiet = QuietHours::parse("22:00-07:00").unwrap();
+        let at = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+        assert!(quiet.contains(at(23, 30)));
+        assert!(quiet.contains(at(6, 59)));
+        assert!(!quiet.contains(at(7, 0)));
+        assert!(!quiet.contains(at(12, 0)));
+
+        let task = &parse_heartbeat_tasks(SAMPLE, 1800)[1];
+        let night = DateTime::parse_from_rfc3339("2026-01-01T23:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let noon = DateTime::parse_from_rfc3339("2026-01-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert!(task.is_quiet(night, None));
+        assert!(!task.is_quiet(noon, None));
+    }
+}
@@ -3,9 +3,14 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::trace::TraceId;
+
 /// Default heartbeat interval: 30 minutes (in seconds)
 pub const DEFAULT_HEARTBEAT_INTERVAL_S: i64 = 30 * 60;
 
+/// Default interval between due-task checks: 1 minute (in seconds)
+pub const DEFAULT_HEARTBEAT_TICK_S: i64 = 60;
+
 /// System prompt for the heartbeat decision LLM call
 pub const HEARTBEAT_SYSTEM_PROMPT: &str =
     "You are a heartbeat agent. Call the heartbeat tool to report your decision.";
@@ -67,15 +72,38 @@ pub fn heartbeat_tool_definition() -> serde_json::Value {
     })
 }
 
+/// A due task that the decision pass chose to run
+#[derive(Debug, Clone)]
+pub struct HeartbeatRun {
+    /// Name of the HEARTBEAT.md task
+    pub task: String,
+    /// Summary of the work, as reported by the decision pass
+    pub tasks: String,
+    /// Delivery channel configured on the task
+    pub channel: Option<String>,
+    /// Delivery chat configured on the task
+    pub chat_id: Option<String>,
+    /// Trace the decision was logged under
+    pub trace_id: TraceId,
+}
+
 /// Heartbeat configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HeartbeatConfig {
     /// Whether heartbeat is enabled
     #[serde(default = "default_true")]
     pub enabled: bool,
-    /// Interval in seconds between heartbeats
+    /// Interval in seconds between heartbeats; the cadence of tasks that
+    /// do not set their own
     #[serde(default = "default_interval")]
     pub interval_s: i64,
+    /// Seconds between checks for due tasks. Checks are cheap: the model is
+    /// only called for tasks that are due.
+    #[serde(default = "default_tick")]
+    pub tick_s: i64,
+    /// IANA timezone for quiet hours of tasks that do not set one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
 }
 
 impl Default for HeartbeatConfig {
@@ -83,6 +111,8 @@ impl Default for HeartbeatConfig {
         Self {
             enabled: true,
             interval_s: DEFAULT_HEARTBEAT_INTERVAL_S,
+            tick_s: DEFAULT_HEARTBEAT_TICK_S,
+            timezone: None,
         }
     }
 }
@@ -95,6 +125,10 @@ fn default_interval() -> i64 {
     DEFAULT_HEARTBEAT_INTERVAL_S
 }
 
+fn default_tick() -> i64 {
+    DEFAULT_HEARTBEAT_TICK_S
+}
+
 /// Check if HEARTBEAT.md has no actionable content
 pub fn is_heartbeat_empty(content: Option<&str>) -> bool {
     let content = match content {
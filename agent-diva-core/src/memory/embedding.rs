@@ -0,0 +1,162 @@
+//! Text embeddings for semantic memory recall
+
+/// Produces fixed-size vectors whose cosine similarity tracks semantic overlap.
+#[async_trait::async_trait]
+pub trait Embedder: Send + Sync {
+    /// Stable identifier stored next to each vector; rows embedded by a
+    /// different model are re-embedded when the store opens.
+    fn model_id(&self) -> &str;
+
+    /// Embed each text, returning one vector per input in order.
+    async fn embed(&self, texts: &[String]) -> crate::Result<Vec<Vec<f32>>>;
+}
+
+/// Offline embedder based on feature hashing.
+///
+/// Latin text contributes lowercase words, CJK text contributes character
+/// unigrams and bigrams, so related wording lands on shared buckets without a
+/// model download or network access.
+#[derive(Debug, Clone)]
+pub struct HashingEmbedder {
+    dimensions: usize,
+    model_id: String,
+}
+
+impl HashingEmbedder {
+    pub const DEFAULT_DIMENSIONS: usize = 384;
+
+    pub fn new(dimensions: usize) -> Self {
+        let dimensions = dimensions.max(8);
+        Self {
+            dimensions,
+            model_id: format!("hashing-v1-{}", dimensions),
+        }
+    }
+
+    /// Embed a single text synchronously.
+    pub fn embed_text(&self, text: &str) -> Vec<f32> {
+        let mut vector = vec![0.0f32; self.dimensions];
+        for feature in features(text) {
+            let hash = fnv1a(feature.as_bytes());
+            let index = (hash % self.dimensions as u64) as usize;
+            let sign = if (hash >> 63) == 0 { 1.0 } else { -1.0 };
+            vector[index] += sign;
+        }
+        normalize(&mut vector);
+        vector
+    }
+}
+
+impl Default for HashingEmbedder {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_DIMENSIONS)
+    }
+}
+
+#[async_trait::async_trait]
+impl Embedder for HashingEmbedder {
+    fn model_id(&self) -> &str {
+        &self.model_id
+    }
+
+    async fn embed(&self, texts: &[String]) -> crate::Result<Vec<Vec<f32>>> {
+        Ok(texts.iter().map(|text| self.embed_text(text)).collect())
+    }
+}
+
+/// Cosine similarity of two vectors; mismatched lengths score zero.
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+fn features(text: &str) -> Vec<String> {
+    let mut features = Vec::new();
+    let mut word = String::new();
+    let mut previous_cjk: Option<char> = None;
+    for ch in text.chars().flat_map(char::to_lowercase) {
+        if is_cjk(ch) {
+            flush_word(&mut word, &mut features);
+            features.push(ch.to_string());
+            if let Some(prev) = previous_cjk {
+                features.push(format!("{}{}", prev, ch));
+            }
+            previous_cjk = Some(ch);
+        } else if ch.is_alphanumeric() {
+            word.push(ch);
+            previous_cjk = None;
+        } else {
+            flush_word(&mut word, &mut features);
+            previous_cjk = None;
+        }
+    }
+    flush_word(&mut word, &mut features);
+    features
+}
+
+fn flush_word(word: &mut String, features: &mut Vec<String>) {
+    if word.chars().count() > 1 {
+        features.push(std::mem::take(word));
+    } else {
+        word.clear();
+    }
+}
+
+fn is_cjk(ch: char) -> bool {
+    matches!(
+        ch as u32,
+        0x3040..=0x30FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xAC00..=0xD7AF | 0xF900..=0xFAFF
+    )
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
+
+fn normalize(vector: &mut [f32]) {
+    let norm: f32 = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        for value in vector {
+            *value /= norm;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn related_texts_score_higher_than_unrelated() {
+        let embedder = HashingEmbedder::default();
+        let query = embedder.embed_text("which editor theme does the user prefer");
+        let related = embedder.embed_text("User prefers a dark editor theme");
+        let unrelated = embedder.embed_text("The deploy pipeline runs on Fridays");
+
+        assert!(cosine_similarity(&query, &related) > cosine_similarity(&query, &unrelated));
+    }
+
+    #[test]
+    fn cjk_text_shares_bigram_features() {
+        let embedder = HashingEmbedder::default();
+        let query = embedder.embed_text("用户喜欢深色主题");
+        let related = embedder.embed_text("喜欢深色主题的编辑器");
+        let unrelated = embedder.embed_text("部署流程在周五运行");
+
+        assert!(cosine_similarity(&query, &related) > cosine_similarity(&query, &unrelated));
+    }
+}
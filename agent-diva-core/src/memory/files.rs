@@ -0,0 +1,371 @@
+//! Versioned access to the markdown memory files
+//!
+//! Editors read a file together with its etag and hand the etag back when
+//! writing, so an edit based on stale content is rejected instead of
+//! overwriting a concurrent consolidation write.
+
+use super::manager::MemoryManager;
+use chrono::{DateTime, NaiveDate, Utc};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Serializes read-check-write sequences on memory files within the process.
+pub(crate) static MEMORY_WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+const MEMORY_FILE: &str = "MEMORY.md";
+const HISTORY_FILE: &str = "HISTORY.md";
+
+/// Role of a file in the memory directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryFileKind {
+    /// `MEMORY.md`
+    LongTerm,
+    /// `HISTORY.md`
+    History,
+    /// `YYYY-MM-DD.md`
+    DailyNote,
+}
+
+/// Listing entry for a memory file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MemoryFileInfo {
+    pub name: String,
+    pub kind: MemoryFileKind,
+    pub size: u64,
+    pub etag: String,
+    pub modified_at: Option<DateTime<Utc>>,
+}
+
+/// Full content of a memory file at a specific version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MemoryFileSnapshot {
+    pub name: String,
+    pub kind: MemoryFileKind,
+    pub content: String,
+    pub etag: String,
+}
+
+/// One `HISTORY.md` entry; entries are separated by blank lines.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HistoryEntry {
+    pub index: usize,
+    pub text: String,
+}
+
+/// A line in a memory file matching a search.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MemoryFileMatch {
+    pub name: String,
+    pub line: usize,
+    pub text: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum MemoryFileError {
+    #[error("invalid memory file name '{0}'")]
+    InvalidName(String),
+    #[error("memory file '{0}' not found")]
+    NotFound(String),
+    #[error("memory file '{0}' cannot be deleted")]
+    NotDeletable(String),
+    #[error(
+        "memory file '{0}' is not used by the configured memory backend; edit its facts instead"
+    )]
+    ReadOnly(String),
+    #[error("history entry {0} not found")]
+    EntryNotFound(usize),
+    #[error("memory file '{name}' changed since it was read")]
+    Conflict { name: String, current_etag: String },
+    #[error(transparent)]
+    Storage(#[from] crate::Error),
+}
+
+impl From<std::io::Error> for MemoryFileError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Storage(err.into())
+    }
+}
+
+/// Content version used for optimistic concurrency (FNV-1a over the bytes).
+pub fn memory_etag(content: &str) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in content.as_bytes() {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    format!("{:016x}", hash)
+}
+
+impl MemoryManager {
+    /// List `MEMORY.md`, `HISTORY.md` and daily notes that exist on disk.
+    pub fn list_files(&self) -> Vec<MemoryFileInfo> {
+        let mut names = vec![MEMORY_FILE.to_string(), HISTORY_FILE.to_string()];
+        names.extend(
+            self.list_memory_files()
+                .iter()
+                .filter_map(|path| path.file_name()?.to_str().map(str::to_string)),
+        );
+        names
+            .into_iter()
+            .filter_map(|name| {
+                let (path, kind) = self.resolve_file(&name).ok()?;
+                let metadata = std::fs::metadata(&path).ok()?;
+                let content = std::fs::read_to_string(&path).ok()?;
+                Some(MemoryFileInfo {
+                    etag: memory_etag(&content),
+                    size: metadata.len(),
+                    modified_at: metadata.modified().ok().map(DateTime::<Utc>::from),
+                    name,
+                    kind,
+                })
+            })
+            .collect()
+    }
+
+    /// Read a memory file by name.
+    pub fn read_file(&self, name: &str) -> Result<MemoryFileSnapshot, MemoryFileError> {
+        let (path, kind) = self.resolve_file(name)?;
+        if !path.exists() {
+            return Err(MemoryFileError::NotFound(name.to_string()));
+        }
+        let content = std::fs::read_to_string(&path)?;
+        Ok(MemoryFileSnapshot {
+            name: name.to_string(),
+            kind,
+            etag: memory_etag(&content),
+            content,
+        })
+    }
+
+    /// Replace a memory file, creating it if needed.
+    ///
+    /// With `expected_etag`, the write only happens if the file still has that
+    /// version; a missing file has the etag of empty content.
+    pub fn write_file(
+        &self,
+        name: &str,
+        content: &str,
+        expected_etag: Option<&str>,
+    ) -> Result<MemoryFileSnapshot, MemoryFileError> {
+        let (path, kind) = self.resolve_file(name)?;
+        let _guard = MEMORY_WRITE_LOCK.lock();
+        let current = std::fs::read_to_string(&path).unwrap_or_default();
+        check_etag(name, &current, expected_etag)?;
+        crate::utils::atomic_write(&path, content.as_bytes())?;
+        Ok(MemoryFileSnapshot {
+            name: name.to_string(),
+            kind,
+            etag: memory_etag(content),
+            content: content.to_string(),
+        })
+    }
+
+    /// Delete `MEMORY.md` or a daily note. `HISTORY.md` is an audit log and is
+    /// only edited entry by entry.
+    pub fn delete_file(
+        &self,
+        name: &str,
+        expected_etag: Option<&str>,
+    ) -> Result<(), MemoryFileError> {
+        let (path, kind) = self.resolve_file(name)?;
+        if kind == MemoryFileKind::History {
+            return Err(MemoryFileError::NotDeletable(name.to_string()));
+        }
+        let _guard = MEMORY_WRITE_LOCK.lock();
+        if !path.exists() {
+            return Err(MemoryFileError::NotFound(name.to_string()));
+        }
+        let current = std::fs::read_to_string(&path)?;
+        check_etag(name, &current, expected_etag)?;
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    /// `HISTORY.md` split into entries, oldest first.
+    pub fn history_entries(&self) -> Vec<HistoryEntry> {
+        split_history(&self.load_history())
+            .into_iter()
+            .enumerate()
+            .map(|(index, text)| HistoryEntry { index, text })
+            .collect()
+    }
+
+    /// Remove one `HISTORY.md` entry by index.
+    pub fn delete_history_entry(
+        &self,
+        index: usize,
+        expected_etag: Option<&str>,
+    ) -> Result<MemoryFileSnapshot, MemoryFileError> {
+        let (path, kind) = self.resolve_file(HISTORY_FILE)?;
+        let _guard = MEMORY_WRITE_LOCK.lock();
+        let current = std::fs::read_to_string(&path).unwrap_or_default();
+        check_etag(HISTORY_FILE, &current, expected_etag)?;
+        let mut entries = split_history(&current);
+        if index >= entries.len() {
+            return Err(MemoryFileError::EntryNotFound(index));
+        }
+        entries.remove(index);
+        let content = entries
+            .iter()
+            .map(|entry| format!("{}\n\n", entry))
+            .collect::<String>();
+        crate::utils::atomic_write(&path, content.as_bytes())?;
+        Ok(MemoryFileSnapshot {
+            name: HISTORY_FILE.to_string(),
+            kind,
+            etag: memory_etag(&content),
+            content,
+        })
+    }
+
+    /// Case-insensitive search for lines containing every word of `query`.
+    pub fn search_files(&self, query: &str, limit: usize) -> Vec<MemoryFileMatch> {
+        let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        if terms.is_empty() {
+            return Vec::new();
+        }
+        let mut matches = Vec::new();
+        for file in self.list_files() {
+            let Ok(snapshot) = self.read_file(&file.name) else {
+                continue;
+            };
+            for (number, line) in snapshot.content.lines().enumerate() {
+                let lower = line.to_lowercase();
+                if terms.iter().all(|term| lower.contains(term)) {
+                    matches.push(MemoryFileMatch {
+                        name: file.name.clone(),
+                        line: number + 1,
+                        text: line.trim().to_string(),
+                    });
+                    if matches.len() >= limit {
+                        return matches;
+                    }
+                }
+            }
+        }
+        matches
+    }
+
+    fn resolve_file(&self, name: &str) -> Result<(PathBuf, MemoryFileKind), MemoryFileError> {
+        let kind = match name {
+            MEMORY_FILE => MemoryFileKind::LongTerm,
+            HISTORY_FILE => MemoryFileKind::History,
+            _ => {
+                let date = name
+                    .strip_suffix(".md")
+                    .ok_or_else(|| MemoryFileError::InvalidName(name.to_string()))?;
+                NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                    .map_err(|_| MemoryFileError::InvalidName(name.to_string()))?;
+                MemoryFileKind::DailyNote
+            }
+        };
+        Ok((self.memory_dir().join(name), kind))
+    }
+}
+
+fn check_etag(name: &str, current: &str, expected: Option<&str>) -> Result<(), MemoryFileError> {
+    let current_etag = memory_etag(current);
+    match expected {
+        Some(expected) if expected != current_etag => Err(MemoryFileError::Conflict {
+            name: name.to_string(),
+            current_etag,
+        }),
+        _ => Ok(()),
+    }
+}
+
+fn split_history(content: &str) -> Vec<String> {
+    content
+        .split("\n\n")
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::Memory;
+    use tempfile::TempDir;
+
+    #[test]
+    fn write_file_rejects_stale_etag() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = MemoryManager::new(temp_dir.path());
+        manager
+            .save_memory(&Memory::with_content("- Original fact"))
+            .unwrap();
+        let snapshot = manager.read_file("MEMORY.md").unwrap();
+
+        // A consolidation write lands after the editor read the file.
+        manager
+            .save_memory(&Memory::with_content("- Consolidated fact"))
+            .unwrap();
+        let err = manager
+            .write_file("MEMORY.md", "- Edited fact", Some(&snapshot.etag))
+            .unwrap_err();
+        assert!(matches!(err, MemoryFileError::Conflict { .. }));
+        assert_eq!(manager.load_memory().content, "- Consolidated fact");
+
+        let current = manager.read_file("MEMORY.md").unwrap();
+        let written = manager
+            .write_file("MEMORY.md", "- Edited fact", Some(&current.etag))
+            .unwrap();
+        assert_eq!(written.etag, memory_etag("- Edited fact"));
+        assert_eq!(manager.load_memory().content, "- Edited fact");
+    }
+
+    #[test]
+    fn file_names_are_restricted_to_memory_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = MemoryManager::new(temp_dir.path());
+
+        for name in [
+            "../config.json",
+            "notes.md",
+            "2026-13-01.md",
+            "sessions/x.md",
+        ] {
+            assert!(matches!(
+                manager.write_file(name, "x", None),
+                Err(MemoryFileError::InvalidName(_))
+            ));
+        }
+        manager.write_file("2026-05-01.md", "# Note", None).unwrap();
+        assert!(matches!(
+            manager.delete_file("HISTORY.md", None),
+            Err(MemoryFileError::NotDeletable(_))
+        ));
+        manager.delete_file("2026-05-01.md", None).unwrap();
+        assert!(manager.list_files().is_empty());
+    }
+
+    #[test]
+    fn history_entries_can_be_listed_searched_and_deleted() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = MemoryManager::new(temp_dir.path());
+        manager
+            .append_history("[2026-05-01 10:00 UTC] First")
+            .unwrap();
+        manager
+            .append_history("[2026-05-02 10:00 UTC] Second topic")
+            .unwrap();
+
+        let entries = manager.history_entries();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].text, "[2026-05-02 10:00 UTC] Second topic");
+
+        let matches = manager.search_files("second TOPIC", 10);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].name, "HISTORY.md");
+
+        let etag = manager.read_file("HISTORY.md").unwrap().etag;
+        manager.delete_history_entry(0, Some(&etag)).unwrap();
+        let entries = manager.history_entries();
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].text.ends_with("Second topic"));
+    }
+}
@@ -1,10 +1,16 @@
 //! Memory manager for handling long-term memory
 
+use super::files::MEMORY_WRITE_LOCK;
 use super::provider::{
-    MemoryProvider, PrefetchRequest, PrefetchResponse, PrefetchStatus, SessionEndRequest,
-    SessionEndResponse, SessionEndStatus, StartupInjectionShape, SyncTurnRequest, SyncTurnResponse,
-    SyncTurnStatus, SystemPromptBlock, SystemPromptRequest, SystemPromptResponse,
+    ForgetRequest, ForgetResponse, ForgetStatus, MemoryProvider, MemorySearchHit,
+    MemorySearchRequest, MemorySearchResponse, MemorySearchStatus, PrefetchRequest,
+    PrefetchResponse, PrefetchStatus, RememberRequest, RememberResponse, RememberStatus,
+    SessionEndRequest, SessionEndResponse, SessionEndStatus, StartupInjectionShape,
+    SyncTurnRequest, SyncTurnResponse, SyncTurnStatus, SystemPromptBlock, SystemPromptRequest,
+    SystemPromptResponse,
 };
+use super::scope::MemoryScope;
+use super::semantic::{markdown_facts, normalize_fact, strip_list_marker};
 use super::storage::{DailyNote, Memory};
 use parking_lot::Mutex;
 use std::collections::HashSet;
@@ -14,7 +20,7 @@ use std::path::{Path, PathBuf};
 #[derive(Debug)]
 pub struct MemoryManager {
     /// Workspace directory
-    _workspace: PathBuf,
+    workspace: PathBuf,
     /// Memory file path
     memory_path: PathBuf,
     /// Daily notes directory
@@ -28,13 +34,18 @@ pub struct MemoryManager {
 impl MemoryManager {
     /// Create a new memory manager
     pub fn new<P: AsRef<Path>>(workspace: P) -> Self {
+        Self::for_scope(workspace, &MemoryScope::Global)
+    }
+
+    /// Create a memory manager over the directory of `scope`
+    pub fn for_scope<P: AsRef<Path>>(workspace: P, scope: &MemoryScope) -> Self {
         let workspace = workspace.as_ref().to_path_buf();
-        let memory_path = workspace.join("memory").join("MEMORY.md");
-        let history_path = workspace.join("memory").join("HISTORY.md");
-        let notes_dir = workspace.join("memory");
+        let notes_dir = scope.memory_dir(&workspace);
+        let memory_path = notes_dir.join("MEMORY.md");
+        let history_path = notes_dir.join("HISTORY.md");
 
         Self {
-            _workspace: workspace,
+            workspace,
             memory_path,
             notes_dir,
             history_path,
@@ -56,6 +67,7 @@ impl MemoryManager {
 
     /// Save the long-term memory
     pub fn save_memory(&self, memory: &Memory) -> crate::Result<()> {
+        let _guard = MEMORY_WRITE_LOCK.lock();
         if let Some(parent) = self.memory_path.parent() {
             std::fs::create_dir_all(parent)?;
         }
@@ -76,6 +88,7 @@ impl MemoryManager {
         if entry.trim().is_empty() {
             return Ok(());
         }
+        let _guard = MEMORY_WRITE_LOCK.lock();
         if let Some(parent) = self.history_path.parent() {
             std::fs::create_dir_all(parent)?;
         }
@@ -115,6 +128,7 @@ impl MemoryManager {
 
     /// Save a daily note
     pub fn save_daily_note(&self, note: &DailyNote) -> crate::Result<()> {
+        let _guard = MEMORY_WRITE_LOCK.lock();
         std::fs::create_dir_all(&self.notes_dir)?;
         let path = self.notes_dir.join(note.filename());
         crate::utils::atomic_write(&path, note.content.as_bytes())
@@ -206,6 +220,132 @@ impl MemoryManager {
         files
     }
 
+    /// Append `fact` as a bullet to `MEMORY.md` unless an equivalent line exists.
+    ///
+    /// Returns `false` when the fact was already present.
+    pub fn remember_fact(&self, fact: &str) -> crate::Result<bool> {
+        let normalized = normalize_fact(fact);
+        let mut memory = self.load_memory();
+        let known = memory
+            .content
+            .lines()
+            .any(|line| normalize_fact(strip_list_marker(line.trim())) == normalized);
+        if known {
+            return Ok(false);
+        }
+        memory.append(format!("- {}", fact.trim()));
+        self.save_memory(&memory)?;
+        Ok(true)
+    }
+
+    /// Keyword search over `MEMORY.md` facts and `HISTORY.md` entries.
+    ///
+    /// Entries are ranked by how many query words they contain.
+    pub fn search_memory(&self, query: &str, limit: usize) -> Vec<String> {
+        let terms: Vec<String> = query
+            .split(|ch: char| !ch.is_alphanumeric())
+            .filter(|term| term.chars().count() > 1)
+            .map(str::to_lowercase)
+            .collect();
+        if terms.is_empty() {
+            return Vec::new();
+        }
+
+        let history = self.load_history();
+        let entries = markdown_facts(&self.load_memory().content)
+            .into_iter()
+            .chain(
+                history
+                    .split("\n\n")
+                    .map(str::trim)
+                    .filter(|entry| !entry.is_empty())
+                    .map(str::to_string),
+            );
+        let mut scored: Vec<(usize, String)> = entries
+            .filter_map(|entry| {
+                let lower = entry.to_lowercase();
+                let score = terms.iter().filter(|term| lower.contains(*term)).count();
+                (score > 0).then_some((score, entry))
+            })
+            .collect();
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        scored
+            .into_iter()
+            .take(limit)
+            .map(|(_, entry)| entry)
+            .collect()
+    }
+
+    /// Remove the `MEMORY.md` lines whose fact is exactly `target`.
+    ///
+    /// `target` may be the line's text or the `Section: text` form returned by
+    /// [`Self::search_memory`], compared ignoring case and whitespace. Headings
+    /// are never removed. Each removal is recorded in `HISTORY.md`.
+    pub fn forget_fact(
+        &self,
+        target: &str,
+        session_key: Option<&str>,
+    ) -> crate::Result<Vec<String>> {
+        let target = normalize_fact(target);
+        if target.is_empty() {
+            return Ok(Vec::new());
+        }
+        let memory = self.load_memory();
+        let mut kept = Vec::new();
+        let mut forgotten = Vec::new();
+        let mut section: Option<String> = None;
+        for line in memory.content.lines() {
+            let trimmed = line.trim();
+            if let Some(heading) = trimmed.strip_prefix('#') {
+                let level = 1 + heading.chars().take_while(|ch| *ch == '#').count();
+                let title = heading.trim_start_matches('#').trim();
+                section = (level > 1 && !title.is_empty()).then(|| title.to_string());
+                kept.push(line);
+                continue;
+            }
+            let text = strip_list_marker(trimmed);
+            let matches = !text.is_empty()
+                && (normalize_fact(text) == target
+                    || section.as_ref().is_some_and(|section| {
+                        normalize_fact(&format!("{}: {}", section, text)) == target
+                    }));
+            if matches {
+                forgotten.push(text.to_string());
+            } else {
+                kept.push(line);
+            }
+        }
+        if forgotten.is_empty() {
+            return Ok(forgotten);
+        }
+
+        let content = kept.join("\n");
+        let mut updated = memory;
+        updated.update(content);
+        self.save_memory(&updated)?;
+        self.record_forgotten(&forgotten, session_key)?;
+        Ok(forgotten)
+    }
+
+    /// Append an audit entry to `HISTORY.md` for each forgotten fact.
+    pub fn record_forgotten(
+        &self,
+        forgotten: &[String],
+        session_key: Option<&str>,
+    ) -> crate::Result<()> {
+        let timestamp = chrono::Utc::now().format("%Y-%m-%d %H:%M UTC");
+        let requested_by = session_key
+            .map(|key| format!(" (requested in {})", key))
+            .unwrap_or_default();
+        for fact in forgotten {
+            self.append_history(&format!(
+                "[{}] Forgot memory{}: {}",
+                timestamp, requested_by, fact
+            ))?;
+        }
+        Ok(())
+    }
+
     /// Get memory context for the agent.
     /// The redesigned memory model injects only long-term memory into prompts.
     pub fn get_memory_context(&self) -> String {
@@ -216,15 +356,21 @@ impl MemoryManager {
             format!("## Long-term Memory\n{}", memory.content)
         }
     }
+
+    /// Manager for a non-global `scope` of the same workspace; `None` means use `self`.
+    fn scoped(&self, scope: &MemoryScope) -> Option<MemoryManager> {
+        (!scope.is_global()).then(|| Self::for_scope(&self.workspace, scope))
+    }
 }
 
 #[async_trait::async_trait]
 impl MemoryProvider for MemoryManager {
     fn system_prompt_block(
         &self,
-        _request: &SystemPromptRequest,
+        request: &SystemPromptRequest,
     ) -> crate::Result<SystemPromptResponse> {
-        let context = self.get_memory_context();
+        let scoped = self.scoped(&request.scope);
+        let context = scoped.as_ref().unwrap_or(self).get_memory_context();
         if context.is_empty() {
             Ok(SystemPromptResponse::degraded(
                 "startup continuity unavailable; no long-term memory available",
@@ -257,12 +403,14 @@ impl MemoryProvider for MemoryManager {
     }
 
     async fn sync_turn(&self, request: SyncTurnRequest) -> crate::Result<SyncTurnResponse> {
+        let scoped = self.scoped(&request.scope);
+        let manager = scoped.as_ref().unwrap_or(self);
         let mut persisted = false;
 
         if let Some(memory_update) = request.memory_update_markdown.as_deref() {
             if !memory_update.trim().is_empty() {
                 let memory = Memory::with_content(memory_update);
-                if let Err(err) = self.save_memory(&memory) {
+                if let Err(err) = manager.save_memory(&memory) {
                     return Ok(SyncTurnResponse {
                         status: SyncTurnStatus::Failed {
                             reason: format!("failed to persist MEMORY.md: {err}"),
@@ -275,7 +423,7 @@ impl MemoryProvider for MemoryManager {
 
         if let Some(history_entry) = request.history_entry.as_deref() {
             if !history_entry.trim().is_empty() {
-                if let Err(err) = self.append_history(history_entry) {
+                if let Err(err) = manager.append_history(history_entry) {
                     return Ok(SyncTurnResponse {
                         status: SyncTurnStatus::Failed {
                             reason: format!("failed to append HISTORY.md: {err}"),
@@ -312,14 +460,70 @@ impl MemoryProvider for MemoryManager {
             status: SessionEndStatus::Noop,
         })
     }
+
+    async fn remember(&self, request: RememberRequest) -> crate::Result<RememberResponse> {
+        let scoped = self.scoped(&request.scope);
+        let manager = scoped.as_ref().unwrap_or(self);
+        let status = match manager.remember_fact(&request.fact) {
+            Ok(true) => RememberStatus::Stored,
+            Ok(false) => RememberStatus::AlreadyKnown,
+            Err(err) => RememberStatus::Failed {
+                reason: format!("failed to persist MEMORY.md: {err}"),
+            },
+        };
+        Ok(RememberResponse { status })
+    }
+
+    async fn search(&self, request: MemorySearchRequest) -> crate::Result<MemorySearchResponse> {
+        let scoped = self.scoped(&request.scope);
+        let hits = scoped
+            .as_ref()
+            .unwrap_or(self)
+            .search_memory(&request.query, request.limit)
+            .into_iter()
+            .map(|content| MemorySearchHit {
+                id: None,
+                content,
+                recorded_at: None,
+                source_session: None,
+            })
+            .collect();
+        Ok(MemorySearchResponse {
+            status: MemorySearchStatus::Ready,
+            hits,
+        })
+    }
+
+    async fn forget(&self, request: ForgetRequest) -> crate::Result<ForgetResponse> {
+        let scoped = self.scoped(&request.scope);
+        let manager = scoped.as_ref().unwrap_or(self);
+        match manager.forget_fact(&request.target, request.session_key.as_deref()) {
+            Ok(forgotten) if forgotten.is_empty() => Ok(ForgetResponse {
+                status: ForgetStatus::NotFound,
+                forgotten,
+            }),
+            Ok(forgotten) => Ok(ForgetResponse {
+                status: ForgetStatus::Forgotten,
+                forgotten,
+            }),
+            Err(err) => Ok(ForgetResponse {
+                status: ForgetStatus::Failed {
+                    reason: format!("failed to update MEMORY.md: {err}"),
+                },
+                forgotten: Vec::new(),
+            }),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::MemoryScopePolicy;
     use crate::memory::{
-        MemoryProvider, PrefetchRequest, PrefetchStatus, SessionEndRequest, SessionEndStatus,
-        StartupStatus, SyncTurnRequest, SyncTurnStatus, SystemPromptRequest,
+        ForgetRequest, ForgetStatus, MemoryProvider, MemorySearchRequest, MemorySearchStatus,
+        PrefetchRequest, PrefetchStatus, RememberRequest, RememberStatus, SessionEndRequest,
+        SessionEndStatus, StartupStatus, SyncTurnRequest, SyncTurnStatus, SystemPromptRequest,
     };
     use tempfile::TempDir;
 
@@ -346,6 +550,7 @@ mod tests {
         let response = manager
             .system_prompt_block(&SystemPromptRequest {
                 workspace_root: temp_dir.path().to_path_buf(),
+                scope: MemoryScope::Global,
             })
             .unwrap();
 
@@ -371,8 +576,10 @@ mod tests {
         let result = manager
             .sync_turn(SyncTurnRequest {
                 workspace_root: temp_dir.path().to_path_buf(),
+                scope: MemoryScope::Global,
                 memory_update_markdown: Some("Updated memory from sync_turn".to_string()),
                 history_entry: Some("[2026-05-08 10:00 UTC] synchronized turn".to_string()),
+                session_key: None,
             })
             .await
             .unwrap();
@@ -385,6 +592,101 @@ mod tests {
         assert!(manager.load_history().contains("synchronized turn"));
     }
 
+    #[tokio::test]
+    async fn test_memory_provider_remember_search_and_forget() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = MemoryManager::new(temp_dir.path());
+        manager
+            .save_memory(&Memory::with_content(
+                "# Long-term Memory\n\n## Facts\n- Lives at 12 Old Street",
+            ))
+            .unwrap();
+
+        let remember = |fact: &str| RememberRequest {
+            workspace_root: temp_dir.path().to_path_buf(),
+            scope: MemoryScope::Global,
+            fact: fact.to_string(),
+            session_key: None,
+        };
+        let stored = manager
+            .remember(remember("Prefers metric units"))
+            .await
+            .unwrap();
+        assert_eq!(stored.status, RememberStatus::Stored);
+        let repeated = manager
+            .remember(remember("prefers metric units."))
+            .await
+            .unwrap();
+        assert_eq!(repeated.status, RememberStatus::AlreadyKnown);
+        let prompt = manager
+            .system_prompt_block(&SystemPromptRequest {
+                workspace_root: temp_dir.path().to_path_buf(),
+                scope: MemoryScope::Global,
+            })
+            .unwrap();
+        assert!(prompt
+            .prompt_block
+            .unwrap()
+            .markdown
+            .contains("- Prefers metric units"));
+
+        let search = manager
+            .search(MemorySearchRequest {
+                workspace_root: temp_dir.path().to_path_buf(),
+                scope: MemoryScope::Global,
+                query: "which units".to_string(),
+                limit: 5,
+            })
+            .await
+            .unwrap();
+        assert_eq!(search.status, MemorySearchStatus::Ready);
+        assert_eq!(search.hits[0].content, "Facts: Prefers metric units");
+
+        let forgotten = manager
+            .forget(ForgetRequest {
+                workspace_root: temp_dir.path().to_path_buf(),
+                scope: MemoryScope::Global,
+                target: "old street".to_string(),
+                session_key: Some("cli:test".to_string()),
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            forgotten.status,
+            ForgetStatus::NotFound,
+            "no substring deletes"
+        );
+
+        let forgotten = manager
+            .forget(ForgetRequest {
+                workspace_root: temp_dir.path().to_path_buf(),
+                scope: MemoryScope::Global,
+                target: "facts: lives at 12 old street".to_string(),
+                session_key: Some("cli:test".to_string()),
+            })
+            .await
+            .unwrap();
+        assert_eq!(forgotten.status, ForgetStatus::Forgotten);
+        assert_eq!(forgotten.forgotten, vec!["Lives at 12 Old Street"]);
+        let memory = manager.load_memory().content;
+        assert!(!memory.contains("Old Street"));
+        assert!(memory.contains("## Facts"));
+        assert!(manager
+            .load_history()
+            .contains("Forgot memory (requested in cli:test): Lives at 12 Old Street"));
+
+        let missing = manager
+            .forget(ForgetRequest {
+                workspace_root: temp_dir.path().to_path_buf(),
+                scope: MemoryScope::Global,
+                target: "Lives at 12 Old Street".to_string(),
+                session_key: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(missing.status, ForgetStatus::NotFound);
+    }
+
     #[tokio::test]
     async fn test_memory_provider_session_end_is_noop_by_default() {
         let temp_dir = TempDir::new().unwrap();
@@ -409,6 +711,7 @@ mod tests {
         let response = manager
             .system_prompt_block(&SystemPromptRequest {
                 workspace_root: temp_dir.path().to_path_buf(),
+                scope: MemoryScope::Global,
             })
             .unwrap();
 
@@ -438,6 +741,7 @@ mod tests {
         let skipped = manager
             .prefetch(PrefetchRequest {
                 workspace_root: temp_dir.path().to_path_buf(),
+                scope: MemoryScope::Global,
                 intent: "   ".to_string(),
                 current_room: None,
                 user_message: Some("help".to_string()),
@@ -450,6 +754,7 @@ mod tests {
         let failed = manager
             .prefetch(PrefetchRequest {
                 workspace_root: temp_dir.path().to_path_buf(),
+                scope: MemoryScope::Global,
                 intent: "recall-project-status".to_string(),
                 current_room: Some("roadmap".to_string()),
                 user_message: Some("what changed?".to_string()),
@@ -478,8 +783,10 @@ mod tests {
         let result = manager
             .sync_turn(SyncTurnRequest {
                 workspace_root: workspace,
+                scope: MemoryScope::Global,
                 memory_update_markdown: Some("cannot persist".to_string()),
                 history_entry: None,
+                session_key: None,
             })
             .await
             .unwrap();
@@ -515,4 +822,43 @@ mod tests {
             .unwrap();
         assert_eq!(duplicate.status, SessionEndStatus::AlreadyHandled);
     }
+
+    #[tokio::test]
+    async fn test_memory_provider_keeps_scopes_apart() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = MemoryManager::new(temp_dir.path());
+        let alice = MemoryScope::resolve(MemoryScopePolicy::Sender, "discord", "guild", "alice");
+        let bob = MemoryScope::resolve(MemoryScopePolicy::Sender, "telegram", "dm", "bob");
+
+        manager
+            .sync_turn(SyncTurnRequest {
+                workspace_root: temp_dir.path().to_path_buf(),
+                scope: alice.clone(),
+                memory_update_markdown: Some("- Alice is allergic to nuts".to_string()),
+                history_entry: None,
+                session_key: None,
+            })
+            .await
+            .unwrap();
+
+        let prompt_for = |scope: &MemoryScope| {
+            manager
+                .system_prompt_block(&SystemPromptRequest {
+                    workspace_root: temp_dir.path().to_path_buf(),
+                    scope: scope.clone(),
+                })
+                .unwrap()
+                .prompt_block
+                .map(|block| block.markdown)
+        };
+        assert!(prompt_for(&alice).unwrap().contains("allergic to nuts"));
+        assert!(!prompt_for(&bob).unwrap().contains("allergic"));
+        assert!(!prompt_for(&MemoryScope::Global)
+            .unwrap()
+            .contains("allergic"));
+        assert!(MemoryManager::for_scope(temp_dir.path(), &alice)
+            .load_memory()
+            .content
+            .contains("allergic to nuts"));
+    }
 }
@@ -1,16 +1,34 @@
 //! Memory management for long-term storage.
 //!
-//! Handles loading and updating of `MEMORY.md` and `HISTORY.md`.
+//! Handles loading and updating of `MEMORY.md` and `HISTORY.md`, and the
+//! SQLite-backed semantic store with hybrid keyword/vector recall.
 
+pub mod embedding;
+pub mod files;
 pub mod manager;
 pub mod provider;
+pub mod scope;
+pub mod semantic;
 pub mod storage;
 
+pub use embedding::{Embedder, HashingEmbedder};
+pub use files::{
+    memory_etag, HistoryEntry, MemoryFileError, MemoryFileInfo, MemoryFileKind, MemoryFileMatch,
+    MemoryFileSnapshot,
+};
+
 pub use manager::MemoryManager;
 pub use provider::{
-    MemoryProvider, PrefetchRequest, PrefetchResponse, PrefetchStatus, RhythmTrigger,
-    SessionEndRequest, SessionEndResponse, SessionEndStatus, StartupContextSnapshot,
+    ForgetRequest, ForgetResponse, ForgetStatus, MemoryProvider, MemorySearchHit,
+    MemorySearchRequest, MemorySearchResponse, MemorySearchStatus, PrefetchRequest,
+    PrefetchResponse, PrefetchStatus, RememberRequest, RememberResponse, RememberStatus,
+    RhythmTrigger, SessionEndRequest, SessionEndResponse, SessionEndStatus, StartupContextSnapshot,
     StartupInjectionShape, StartupStatus, SyncTurnRequest, SyncTurnResponse, SyncTurnStatus,
     SystemPromptBlock, SystemPromptRequest, SystemPromptResponse, WakeupPackSummary,
 };
+pub use scope::{migrate_global_memory, MemoryScope};
+pub use semantic::{
+    open_memory_provider, MemoryFact, MemoryFactKind, RecalledFact, SemanticMemoryStore,
+    SqliteMemoryProvider, UpsertOutcome,
+};
 pub use storage::{DailyNote, Memory};
@@ -6,6 +6,7 @@
 //! by Laputa's adapter layer and keeps long-memory ownership outside prompt
 //! assembly and loop execution code.
 
+use super::scope::MemoryScope;
 use std::path::PathBuf;
 
 /// Deterministic status for startup wakeup injection.
@@ -253,6 +254,8 @@ fn render_wakeup_pack_summary(pack: &WakeupPackSummary) -> String {
 pub struct SystemPromptRequest {
     /// Workspace root for the active agent session.
     pub workspace_root: PathBuf,
+    /// Memory scope resolved for the turn's channel, chat and sender.
+    pub scope: MemoryScope,
 }
 
 /// Startup block injected into the Agent-Diva system prompt.
@@ -273,6 +276,8 @@ pub struct SystemPromptBlock {
 pub struct PrefetchRequest {
     /// Workspace root for the active agent session.
     pub workspace_root: PathBuf,
+    /// Memory scope resolved for the turn's channel, chat and sender.
+    pub scope: MemoryScope,
     /// Intent inferred from the current turn.
     pub intent: String,
     /// Optional current room or topic context.
@@ -309,10 +314,14 @@ impl Default for PrefetchResponse {
 pub struct SyncTurnRequest {
     /// Workspace root for the active agent session.
     pub workspace_root: PathBuf,
+    /// Memory scope resolved for the turn's channel, chat and sender.
+    pub scope: MemoryScope,
     /// Optional full replacement or refreshed long-memory markdown.
     pub memory_update_markdown: Option<String>,
     /// Optional history/evidence line derived from the completed turn.
     pub history_entry: Option<String>,
+    /// Session that produced the update, recorded as the source of stored facts.
+    pub session_key: Option<String>,
 }
 
 /// Result of turn synchronization.
@@ -357,6 +366,118 @@ impl Default for SessionEndResponse {
     }
 }
 
+/// Deterministic status for an explicit memory write.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RememberStatus {
+    /// The fact was written and is visible from the next system prompt on.
+    Stored,
+    /// An equivalent fact was already stored.
+    AlreadyKnown,
+    /// The backend does not accept explicit writes.
+    Unsupported,
+    /// The write was attempted but failed.
+    Failed { reason: String },
+}
+
+/// Deterministic status for an explicit memory search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MemorySearchStatus {
+    /// Search completed; `hits` may be empty.
+    Ready,
+    /// The backend does not support explicit search.
+    Unsupported,
+    /// Search was attempted but failed.
+    Failed { reason: String },
+}
+
+/// Deterministic status for an explicit memory deletion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ForgetStatus {
+    /// At least one stored fact was removed.
+    Forgotten,
+    /// Nothing matched the target.
+    NotFound,
+    /// The backend does not accept explicit deletions.
+    Unsupported,
+    /// Deletion was attempted but failed.
+    Failed { reason: String },
+}
+
+/// Input for a user-requested "remember this" write.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RememberRequest {
+    /// Workspace root for the active agent session.
+    pub workspace_root: PathBuf,
+    /// Memory scope resolved for the turn's channel, chat and sender.
+    pub scope: MemoryScope,
+    /// Single self-contained fact to store.
+    pub fact: String,
+    /// Session that asked for the write, when known.
+    pub session_key: Option<String>,
+}
+
+/// Result of an explicit memory write.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RememberResponse {
+    pub status: RememberStatus,
+}
+
+/// Input for an explicit search over long-term memory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemorySearchRequest {
+    /// Workspace root for the active agent session.
+    pub workspace_root: PathBuf,
+    /// Memory scope resolved for the turn's channel, chat and sender.
+    pub scope: MemoryScope,
+    /// Free-text query.
+    pub query: String,
+    /// Maximum number of hits to return.
+    pub limit: usize,
+}
+
+/// One stored memory matching a search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemorySearchHit {
+    /// Id accepted by [`ForgetRequest::target`], if the backend has one for this entry.
+    pub id: Option<String>,
+    /// Stored fact or history entry text.
+    pub content: String,
+    /// When the entry was last written, if the backend tracks it.
+    pub recorded_at: Option<String>,
+    /// Session that produced the entry, if the backend tracks it.
+    pub source_session: Option<String>,
+}
+
+/// Result of an explicit memory search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemorySearchResponse {
+    pub status: MemorySearchStatus,
+    /// Hits ordered from most to least relevant.
+    pub hits: Vec<MemorySearchHit>,
+}
+
+/// Input for a user-requested "forget this" deletion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForgetRequest {
+    /// Workspace root for the active agent session.
+    pub workspace_root: PathBuf,
+    /// Memory scope resolved for the turn's channel, chat and sender.
+    pub scope: MemoryScope,
+    /// Fact to remove: an id from a search hit, or the fact's exact text
+    /// (compared ignoring case, whitespace and a trailing period).
+    pub target: String,
+    /// Session that asked for the deletion, when known.
+    pub session_key: Option<String>,
+}
+
+/// Result of an explicit memory deletion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForgetResponse {
+    pub status: ForgetStatus,
+    /// Text of every removed fact, for confirmation and auditing.
+    pub forgotten: Vec<String>,
+}
+
 /// Isolation layer between Agent-Diva and any long-memory backend.
 ///
 /// Contract rules:
@@ -384,6 +505,39 @@ pub trait MemoryProvider: Send + Sync {
     /// Trigger shutdown/session-end rhythm work if needed.
     async fn on_session_end(&self, request: SessionEndRequest)
         -> crate::Result<SessionEndResponse>;
+
+    /// Load whatever `scope` needs before a turn in it starts.
+    ///
+    /// Called before prompt assembly so backends that open per-scope storage
+    /// lazily can answer the synchronous `system_prompt_block()`.
+    async fn prepare_scope(&self, _scope: &MemoryScope) -> crate::Result<()> {
+        Ok(())
+    }
+
+    /// Store a fact the user explicitly asked to be remembered.
+    async fn remember(&self, _request: RememberRequest) -> crate::Result<RememberResponse> {
+        Ok(RememberResponse {
+            status: RememberStatus::Unsupported,
+        })
+    }
+
+    /// Search long-term memory on demand.
+    async fn search(&self, _request: MemorySearchRequest) -> crate::Result<MemorySearchResponse> {
+        Ok(MemorySearchResponse {
+            status: MemorySearchStatus::Unsupported,
+            hits: Vec::new(),
+        })
+    }
+
+    /// Remove facts the user explicitly asked to be forgotten.
+    ///
+    /// Implementations record each removal in `HISTORY.md` so deletions stay auditable.
+    async fn forget(&self, _request: ForgetRequest) -> crate::Result<ForgetResponse> {
+        Ok(ForgetResponse {
+            status: ForgetStatus::Unsupported,
+            forgotten: Vec::new(),
+        })
+    }
 }
 
 #[cfg(test)]
@@ -447,6 +601,7 @@ mod tests {
         let prompt = provider
             .system_prompt_block(&SystemPromptRequest {
                 workspace_root: PathBuf::from("/tmp/diva"),
+                scope: MemoryScope::Global,
             })
             .unwrap()
             .prompt_block
@@ -457,6 +612,7 @@ mod tests {
         let prefetch = provider
             .prefetch(PrefetchRequest {
                 workspace_root: PathBuf::from("/tmp/diva"),
+                scope: MemoryScope::Global,
                 intent: "recall-project-status".to_string(),
                 current_room: Some("roadmap".to_string()),
                 user_message: Some("what changed?".to_string()),
@@ -472,8 +628,10 @@ mod tests {
         let sync = provider
             .sync_turn(SyncTurnRequest {
                 workspace_root: PathBuf::from("/tmp/diva"),
+                scope: MemoryScope::Global,
                 memory_update_markdown: Some("updated".to_string()),
                 history_entry: None,
+                session_key: None,
             })
             .await
             .unwrap();
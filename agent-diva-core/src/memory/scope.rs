@@ -0,0 +1,253 @@
+//! Memory scopes: which memory directory a turn reads from and writes to
+//!
+//! The global scope is the workspace-wide `memory/` directory. Every other
+//! scope gets its own directory under `memory/scopes/`, so facts learned in one
+//! chat never reach prompts in another.
+
+use crate::config::MemoryScopePolicy;
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Directory under `memory/` that holds the non-global scopes.
+const SCOPES_DIR: &str = "scopes";
+
+/// A memory partition resolved from the incoming message.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MemoryScope {
+    #[default]
+    Global,
+    Channel {
+        channel: String,
+    },
+    Chat {
+        channel: String,
+        chat_id: String,
+    },
+    Sender {
+        channel: String,
+        sender_id: String,
+    },
+}
+
+impl MemoryScope {
+    /// Apply `policy` to a message's origin.
+    pub fn resolve(
+        policy: MemoryScopePolicy,
+        channel: &str,
+        chat_id: &str,
+        sender_id: &str,
+    ) -> Self {
+        match policy {
+            MemoryScopePolicy::Global => Self::Global,
+            MemoryScopePolicy::Channel => Self::Channel {
+                channel: channel.to_string(),
+            },
+            MemoryScopePolicy::Chat => Self::Chat {
+                channel: channel.to_string(),
+                chat_id: chat_id.to_string(),
+            },
+            MemoryScopePolicy::Sender => Self::Sender {
+                channel: channel.to_string(),
+                sender_id: sender_id.to_string(),
+            },
+        }
+    }
+
+    pub fn is_global(&self) -> bool {
+        matches!(self, Self::Global)
+    }
+
+    /// Memory directory for this scope inside `workspace`.
+    pub fn memory_dir(&self, workspace: &Path) -> PathBuf {
+        let root = workspace.join("memory");
+        match self {
+            Self::Global => root,
+            Self::Channel { channel } => root
+                .join(SCOPES_DIR)
+                .join("channel")
+                .join(path_component(channel)),
+            Self::Chat { channel, chat_id } => root
+                .join(SCOPES_DIR)
+                .join("chat")
+                .join(path_component(channel))
+                .join(path_component(chat_id)),
+            Self::Sender { channel, sender_id } => root
+                .join(SCOPES_DIR)
+                .join("sender")
+                .join(path_component(channel))
+                .join(path_component(sender_id)),
+        }
+    }
+
+    /// Parse the `global`, `channel:<c>`, `chat:<c>:<id>` and `sender:<c>:<id>`
+    /// forms produced by `Display`.
+    pub fn parse(value: &str) -> Result<Self> {
+        let invalid = || {
+            Error::Validation(format!(
+                "invalid memory scope '{}'; expected global, channel:<channel>, chat:<channel>:<chat_id> or sender:<channel>:<sender_id>",
+                value
+            ))
+        };
+        let mut parts = value.splitn(3, ':');
+        let kind = parts.next().unwrap_or_default();
+        let channel = parts.next().filter(|s| !s.is_empty());
+        let id = parts.next().filter(|s| !s.is_empty());
+        match (kind, channel, id) {
+            ("global", None, None) => Ok(Self::Global),
+            ("channel", Some(channel), None) => Ok(Self::Channel {
+                channel: channel.to_string(),
+            }),
+            ("chat", Some(channel), Some(chat_id)) => Ok(Self::Chat {
+                channel: channel.to_string(),
+                chat_id: chat_id.to_string(),
+            }),
+            ("sender", Some(channel), Some(sender_id)) => Ok(Self::Sender {
+                channel: channel.to_string(),
+                sender_id: sender_id.to_string(),
+            }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl fmt::Display for MemoryScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Global => write!(f, "global"),
+            Self::Channel { channel } => write!(f, "channel:{}", channel),
+            Self::Chat { channel, chat_id } => write!(f, "chat:{}:{}", channel, chat_id),
+            Self::Sender { channel, sender_id } => write!(f, "sender:{}:{}", channel, sender_id),
+        }
+    }
+}
+
+/// Move the global memory files into `target`, for switching an existing
+/// workspace from the global policy to scoped memory.
+///
+/// Everything in `memory/` except the `scopes/` directory is moved. The target
+/// scope must not hold any memory yet. Returns the moved file names.
+pub fn migrate_global_memory(workspace: &Path, target: &MemoryScope) -> Result<Vec<String>> {
+    if target.is_global() {
+        return Err(Error::Validation(
+            "target scope must not be the global scope".to_string(),
+        ));
+    }
+    let source = MemoryScope::Global.memory_dir(workspace);
+    let destination = target.memory_dir(workspace);
+    let occupied = std::fs::read_dir(&destination)
+        .map(|mut entries| entries.next().is_some())
+        .unwrap_or(false);
+    if occupied {
+        return Err(Error::Validation(format!(
+            "memory scope '{}' already has files in {}",
+            target,
+            destination.display()
+        )));
+    }
+
+    let mut moved = Vec::new();
+    let entries = match std::fs::read_dir(&source) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(moved),
+        Err(err) => return Err(err.into()),
+    };
+    for entry in entries {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name == SCOPES_DIR || !entry.file_type()?.is_file() {
+            continue;
+        }
+        std::fs::create_dir_all(&destination)?;
+        std::fs::rename(entry.path(), destination.join(&name))?;
+        moved.push(name);
+    }
+    moved.sort();
+    Ok(moved)
+}
+
+/// Encode an identifier as a single path component. Anything outside
+/// `[A-Za-z0-9_-]` becomes `%XX`, so distinct ids never share a directory.
+fn path_component(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    if encoded.is_empty() {
+        encoded.push('%');
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn scope_dirs_are_isolated_and_path_safe() {
+        let workspace = Path::new("/ws");
+        let scope = MemoryScope::resolve(MemoryScopePolicy::Sender, "discord", "guild-1", "../42");
+        assert_eq!(
+            scope.memory_dir(workspace),
+            PathBuf::from("/ws/memory/scopes/sender/discord/%2E%2E%2F42")
+        );
+        assert_eq!(
+            MemoryScope::resolve(MemoryScopePolicy::Global, "discord", "g", "u")
+                .memory_dir(workspace),
+            PathBuf::from("/ws/memory")
+        );
+        assert_ne!(
+            MemoryScope::resolve(MemoryScopePolicy::Chat, "telegram", "a/b", "u")
+                .memory_dir(workspace),
+            MemoryScope::resolve(MemoryScopePolicy::Chat, "telegram", "a_b", "u")
+                .memory_dir(workspace)
+        );
+    }
+
+    #[test]
+    fn scope_display_round_trips() {
+        for scope in [
+            MemoryScope::Global,
+            MemoryScope::Channel {
+                channel: "slack".to_string(),
+            },
+            MemoryScope::Chat {
+                channel: "telegram".to_string(),
+                chat_id: "-100:5".to_string(),
+            },
+        ] {
+            assert_eq!(MemoryScope::parse(&scope.to_string()).unwrap(), scope);
+        }
+        assert!(MemoryScope::parse("chat:telegram").is_err());
+    }
+
+    #[test]
+    fn migrate_global_memory_moves_files_once() {
+        let temp_dir = TempDir::new().unwrap();
+        let memory_dir = temp_dir.path().join("memory");
+        std::fs::create_dir_all(memory_dir.join("scopes")).unwrap();
+        std::fs::write(memory_dir.join("MEMORY.md"), "- fact").unwrap();
+        std::fs::write(memory_dir.join("2026-01-02.md"), "note").unwrap();
+        let target = MemoryScope::Chat {
+            channel: "telegram".to_string(),
+            chat_id: "7".to_string(),
+        };
+
+        let moved = migrate_global_memory(temp_dir.path(), &target).unwrap();
+        assert_eq!(moved, vec!["2026-01-02.md", "MEMORY.md"]);
+        assert!(!memory_dir.join("MEMORY.md").exists());
+        assert_eq!(
+            std::fs::read_to_string(target.memory_dir(temp_dir.path()).join("MEMORY.md")).unwrap(),
+            "- fact"
+        );
+
+        std::fs::write(memory_dir.join("MEMORY.md"), "- other").unwrap();
+        assert!(migrate_global_memory(temp_dir.path(), &target).is_err());
+    }
+}
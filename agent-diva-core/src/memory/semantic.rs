@@ -0,0 +1,1398 @@
+//! SQLite-backed semantic memory
+//!
+//! Long-term memory is stored as individual facts with an embedding, timestamps
+//! and the session that produced them. Recall fuses FTS5 BM25 ranking with
+//! vector similarity, so only facts relevant to the current turn reach the prompt.
+
+use super::embedding::{cosine_similarity, Embedder, HashingEmbedder};
+use super::manager::MemoryManager;
+use super::provider::{
+    ForgetRequest, ForgetResponse, ForgetStatus, MemoryProvider, MemorySearchHit,
+    MemorySearchRequest, MemorySearchResponse, MemorySearchStatus, PrefetchRequest,
+    PrefetchResponse, PrefetchStatus, RememberRequest, RememberResponse, RememberStatus,
+    SessionEndRequest, SessionEndResponse, SessionEndStatus, StartupInjectionShape,
+    SyncTurnRequest, SyncTurnResponse, SyncTurnStatus, SystemPromptBlock, SystemPromptRequest,
+    SystemPromptResponse,
+};
+use super::scope::MemoryScope;
+use crate::config::{AgentMemoryConfig, MemoryBackend};
+use crate::{Error, Result};
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use sqlx::{Row, SqlitePool};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Facts at least this similar to an existing fact replace it instead of being added.
+const NEAR_DUPLICATE_SIMILARITY: f32 = 0.92;
+/// Vector matches below this similarity are ignored during recall.
+const MIN_RECALL_SIMILARITY: f32 = 0.12;
+/// Candidates taken from each ranking before fusion.
+const RECALL_CANDIDATES: usize = 50;
+/// Reciprocal-rank-fusion constant.
+const RRF_K: f32 = 60.0;
+/// Facts rendered into the startup system prompt block.
+const STARTUP_FACTS: usize = 12;
+/// Most recently updated rows compared by brute-force vector search.
+///
+/// Embeddings are scanned in Rust rather than through a vector index, so older
+/// facts beyond this window are only reachable through keyword recall.
+const VECTOR_SCAN_LIMIT: usize = 5_000;
+const MARKDOWN_IMPORT_KEY: &str = "markdown_import_v1";
+
+/// Kind of record held by the semantic store.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MemoryFactKind {
+    /// Durable fact about the user, their projects or preferences.
+    Fact,
+    /// Timestamped summary of what happened in a conversation segment.
+    History,
+}
+
+impl MemoryFactKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Fact => "fact",
+            Self::History => "history",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "history" => Self::History,
+            _ => Self::Fact,
+        }
+    }
+}
+
+/// A single stored memory record.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemoryFact {
+    pub id: String,
+    pub kind: MemoryFactKind,
+    pub content: String,
+    pub source_session: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A fact returned by recall with its fused relevance score.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecalledFact {
+    pub fact: MemoryFact,
+    pub score: f32,
+}
+
+/// Result of writing one fact.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpsertOutcome {
+    /// A new record was created.
+    Inserted,
+    /// A near-duplicate record was rewritten with the new wording.
+    Merged,
+    /// An identical record already existed; only its timestamp moved.
+    Unchanged,
+}
+
+/// SQLite store for memory facts, their FTS index and embeddings.
+pub struct SemanticMemoryStore {
+    pool: SqlitePool,
+    embedder: Arc<dyn Embedder>,
+}
+
+impl SemanticMemoryStore {
+    /// Open (or create) the store at `db_path`.
+    pub async fn open(db_path: impl AsRef<Path>, embedder: Arc<dyn Embedder>) -> Result<Self> {
+        let db_path = db_path.as_ref();
+        if let Some(parent) = db_path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let options = SqliteConnectOptions::new()
+            .filename(db_path)
+            .create_if_missing(true);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(4)
+            .connect_with(options)
+            .await
+            .map_err(db_error)?;
+
+        let store = Self { pool, embedder };
+        store.init().await?;
+        store.reembed_stale().await?;
+        Ok(store)
+    }
+
+    async fn init(&self) -> Result<()> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS memory_facts (
+                id TEXT PRIMARY KEY NOT NULL,
+                kind TEXT NOT NULL,
+                content TEXT NOT NULL,
+                normalized TEXT NOT NULL,
+                embedding BLOB NOT NULL,
+                embedding_model TEXT NOT NULL,
+                source_session TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            );
+
+            CREATE UNIQUE INDEX IF NOT EXISTS idx_memory_facts_normalized
+                ON memory_facts(kind, normalized);
+            CREATE INDEX IF NOT EXISTS idx_memory_facts_updated_at
+                ON memory_facts(updated_at);
+
+            CREATE VIRTUAL TABLE IF NOT EXISTS memory_facts_fts
+                USING fts5(fact_id UNINDEXED, content);
+
+            CREATE TABLE IF NOT EXISTS memory_meta (
+                key TEXT PRIMARY KEY NOT NULL,
+                value TEXT NOT NULL
+            );
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+        Ok(())
+    }
+
+    /// Re-embed rows written by a different embedder so vectors stay comparable.
+    async fn reembed_stale(&self) -> Result<()> {
+        let rows = sqlx::query("SELECT id, content FROM memory_facts WHERE embedding_model != ?")
+            .bind(self.embedder.model_id())
+            .fetch_all(&self.pool)
+            .await
+            .map_err(db_error)?;
+        if rows.is_empty() {
+            return Ok(());
+        }
+
+        let ids: Vec<String> = rows.iter().map(|row| row.get("id")).collect();
+        let contents: Vec<String> = rows.iter().map(|row| row.get("content")).collect();
+        let embeddings = self.embedder.embed(&contents).await?;
+        for (id, embedding) in ids.iter().zip(embeddings) {
+            sqlx::query("UPDATE memory_facts SET embedding = ?, embedding_model = ? WHERE id = ?")
+                .bind(encode_embedding(&embedding))
+                .bind(self.embedder.model_id())
+                .bind(id)
+                .execute(&self.pool)
+                .await
+                .map_err(db_error)?;
+        }
+        tracing::info!("Re-embedded {} memory facts", ids.len());
+        Ok(())
+    }
+
+    /// Insert a fact, or fold it into an identical or near-duplicate record.
+    ///
+    /// History entries are an append-only log: only an identical entry is
+    /// folded, similar ones (the same event on another day) are kept apart.
+    pub async fn upsert_fact(
+        &self,
+        kind: MemoryFactKind,
+        content: &str,
+        source_session: Option<&str>,
+    ) -> Result<UpsertOutcome> {
+        let content = content.trim();
+        let normalized = normalize_fact(content);
+        if normalized.is_empty() {
+            return Ok(UpsertOutcome::Unchanged);
+        }
+        let now = Utc::now().to_rfc3339();
+
+        let exact = sqlx::query("SELECT id FROM memory_facts WHERE kind = ? AND normalized = ?")
+            .bind(kind.as_str())
+            .bind(&normalized)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(db_error)?;
+        if let Some(row) = exact {
+            let id: String = row.get("id");
+            sqlx::query(
+                "UPDATE memory_facts SET updated_at = ?, source_session = COALESCE(?, source_session) WHERE id = ?",
+            )
+            .bind(&now)
+            .bind(source_session)
+            .bind(&id)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+            return Ok(UpsertOutcome::Unchanged);
+        }
+
+        let embedding = self
+            .embedder
+            .embed(&[content.to_string()])
+            .await?
+            .pop()
+            .unwrap_or_default();
+
+        let near_duplicate = if kind == MemoryFactKind::History {
+            None
+        } else {
+            self.vector_matches(Some(kind), &embedding)
+                .await?
+                .into_iter()
+                .next()
+                .filter(|(_, similarity)| *similarity >= NEAR_DUPLICATE_SIMILARITY)
+        };
+
+        let mut tx = self.pool.begin().await.map_err(db_error)?;
+        let outcome = if let Some((id, _)) = near_duplicate {
+            sqlx::query(
+                r#"
+                UPDATE memory_facts
+                SET content = ?, normalized = ?, embedding = ?, embedding_model = ?,
+                    source_session = COALESCE(?, source_session), updated_at = ?
+                WHERE id = ?
+                "#,
+            )
+            .bind(content)
+            .bind(&normalized)
+            .bind(encode_embedding(&embedding))
+            .bind(self.embedder.model_id())
+            .bind(source_session)
+            .bind(&now)
+            .bind(&id)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+            sqlx::query("UPDATE memory_facts_fts SET content = ? WHERE fact_id = ?")
+                .bind(content)
+                .bind(&id)
+                .execute(&mut *tx)
+                .await
+                .map_err(db_error)?;
+            UpsertOutcome::Merged
+        } else {
+            let id = uuid::Uuid::new_v4().to_string();
+            sqlx::query(
+                r#"
+                INSERT INTO memory_facts
+                    (id, kind, content, normalized, embedding, embedding_model,
+                     source_session, created_at, updated_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(&id)
+            .bind(kind.as_str())
+            .bind(content)
+            .bind(&normalized)
+            .bind(encode_embedding(&embedding))
+            .bind(self.embedder.model_id())
+            .bind(source_session)
+            .bind(&now)
+            .bind(&now)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+            sqlx::query("INSERT INTO memory_facts_fts (fact_id, content) VALUES (?, ?)")
+                .bind(&id)
+                .bind(content)
+                .execute(&mut *tx)
+                .await
+                .map_err(db_error)?;
+            UpsertOutcome::Inserted
+        };
+        tx.commit().await.map_err(db_error)?;
+        Ok(outcome)
+    }
+
+    /// Hybrid recall: BM25 keyword ranking fused with vector similarity.
+    pub async fn recall(&self, query: &str, limit: usize) -> Result<Vec<RecalledFact>> {
+        if query.trim().is_empty() || limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut scores: HashMap<String, f32> = HashMap::new();
+        for (rank, id) in self.keyword_matches(query).await?.into_iter().enumerate() {
+            *scores.entry(id).or_default() += 1.0 / (RRF_K + rank as f32 + 1.0);
+        }
+
+        let embedding = self
+            .embedder
+            .embed(&[query.to_string()])
+            .await?
+            .pop()
+            .unwrap_or_default();
+        let vector_matches = self
+            .vector_matches(None, &embedding)
+            .await?
+            .into_iter()
+            .filter(|(_, similarity)| *similarity >= MIN_RECALL_SIMILARITY)
+            .take(RECALL_CANDIDATES);
+        for (rank, (id, _)) in vector_matches.enumerate() {
+            *scores.entry(id).or_default() += 1.0 / (RRF_K + rank as f32 + 1.0);
+        }
+
+        let mut ranked: Vec<(String, f32)> = scores.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        ranked.truncate(limit);
+
+        let mut recalled = Vec::with_capacity(ranked.len());
+        for (id, score) in ranked {
+            if let Some(fact) = self.get_fact(&id).await? {
+                recalled.push(RecalledFact { fact, score });
+            }
+        }
+        Ok(recalled)
+    }
+
+    /// Most recently updated facts of `kind`.
+    pub async fn recent_facts(
+        &self,
+        kind: MemoryFactKind,
+        limit: usize,
+    ) -> Result<Vec<MemoryFact>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, kind, content, source_session, created_at, updated_at
+            FROM memory_facts WHERE kind = ?
+            ORDER BY updated_at DESC LIMIT ?
+            "#,
+        )
+        .bind(kind.as_str())
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+        rows.iter().map(row_to_fact).collect()
+    }
+
+    /// Fetch one fact by id.
+    pub async fn get_fact(&self, id: &str) -> Result<Option<MemoryFact>> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, kind, content, source_session, created_at, updated_at
+            FROM memory_facts WHERE id = ?
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+        row.as_ref().map(row_to_fact).transpose()
+    }
+
+    /// Delete the fact whose id is `target`, or the facts whose content is
+    /// exactly `target` once normalized, returning the removed records.
+    pub async fn delete_fact(&self, target: &str) -> Result<Vec<MemoryFact>> {
+        let target = target.trim();
+        if target.is_empty() {
+            return Ok(Vec::new());
+        }
+        let rows = sqlx::query(
+            r#"
+            SELECT id, kind, content, source_session, created_at, updated_at
+            FROM memory_facts
+            WHERE kind = 'fact' AND (id = ? OR normalized = ?)
+            "#,
+        )
+        .bind(target)
+        .bind(normalize_fact(target))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+        let facts = rows.iter().map(row_to_fact).collect::<Result<Vec<_>>>()?;
+        self.delete_facts(&facts).await?;
+        Ok(facts)
+    }
+
+    async fn delete_facts(&self, facts: &[MemoryFact]) -> Result<()> {
+        let mut tx = self.pool.begin().await.map_err(db_error)?;
+        for fact in facts {
+            sqlx::query("DELETE FROM memory_facts WHERE id = ?")
+                .bind(&fact.id)
+                .execute(&mut *tx)
+                .await
+                .map_err(db_error)?;
+            sqlx::query("DELETE FROM memory_facts_fts WHERE fact_id = ?")
+                .bind(&fact.id)
+                .execute(&mut *tx)
+                .await
+                .map_err(db_error)?;
+        }
+        tx.commit().await.map_err(db_error)?;
+        Ok(())
+    }
+
+    /// Delete the facts among `ids` whose content is not in `keep` (normalized),
+    /// returning the removed records.
+    pub async fn delete_dropped(
+        &self,
+        ids: &[String],
+        keep: &HashSet<String>,
+    ) -> Result<Vec<MemoryFact>> {
+        let mut dropped = Vec::new();
+        for id in ids {
+            if let Some(fact) = self.get_fact(id).await? {
+                if !keep.contains(&normalize_fact(&fact.content)) {
+                    dropped.push(fact);
+                }
+            }
+        }
+        self.delete_facts(&dropped).await?;
+        Ok(dropped)
+    }
+
+    /// Total number of stored facts.
+    pub async fn count(&self) -> Result<usize> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM memory_facts")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(db_error)?;
+        Ok(count as usize)
+    }
+
+    /// One-time migration of `MEMORY.md`, daily notes and `HISTORY.md` into the store.
+    ///
+    /// Returns the number of records written; later calls are no-ops.
+    pub async fn import_markdown(&self, manager: &MemoryManager) -> Result<usize> {
+        let done = sqlx::query("SELECT value FROM memory_meta WHERE key = ?")
+            .bind(MARKDOWN_IMPORT_KEY)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(db_error)?;
+        if done.is_some() {
+            return Ok(0);
+        }
+
+        let mut imported = 0;
+        let mut facts = markdown_facts(&manager.load_memory().content);
+        for path in manager.list_memory_files() {
+            if let Ok(content) = std::fs::read_to_string(&path) {
+                facts.extend(markdown_facts(&content));
+            }
+        }
+        for fact in facts {
+            if self.upsert_fact(MemoryFactKind::Fact, &fact, None).await?
+                != UpsertOutcome::Unchanged
+            {
+                imported += 1;
+            }
+        }
+        for entry in manager.load_history().split("\n\n") {
+            if self
+                .upsert_fact(MemoryFactKind::History, entry, None)
+                .await?
+                != UpsertOutcome::Unchanged
+            {
+                imported += 1;
+            }
+        }
+
+        sqlx::query("INSERT INTO memory_meta (key, value) VALUES (?, ?)")
+            .bind(MARKDOWN_IMPORT_KEY)
+            .bind(Utc::now().to_rfc3339())
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+        if imported > 0 {
+            tracing::info!("Imported {} markdown memory records into SQLite", imported);
+        }
+        Ok(imported)
+    }
+
+    async fn keyword_matches(&self, query: &str) -> Result<Vec<String>> {
+        let Some(fts_query) = fts_query(query) else {
+            return Ok(Vec::new());
+        };
+        let rows = sqlx::query(
+            r#"
+            SELECT fact_id FROM memory_facts_fts
+            WHERE memory_facts_fts MATCH ?
+            ORDER BY bm25(memory_facts_fts) LIMIT ?
+            "#,
+        )
+        .bind(fts_query)
+        .bind(RECALL_CANDIDATES as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+        Ok(rows.iter().map(|row| row.get("fact_id")).collect())
+    }
+
+    /// Facts (optionally of one kind) ordered by similarity to `embedding`.
+    ///
+    /// Only the [`VECTOR_SCAN_LIMIT`] most recently updated facts are compared.
+    async fn vector_matches(
+        &self,
+        kind: Option<MemoryFactKind>,
+        embedding: &[f32],
+    ) -> Result<Vec<(String, f32)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, embedding FROM memory_facts
+            WHERE ? IS NULL OR kind = ?
+            ORDER BY updated_at DESC LIMIT ?
+            "#,
+        )
+        .bind(kind.map(|kind| kind.as_str()))
+        .bind(kind.map(|kind| kind.as_str()))
+        .bind(VECTOR_SCAN_LIMIT as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+        let mut matches: Vec<(String, f32)> = rows
+            .iter()
+            .map(|row| {
+                let stored = decode_embedding(row.get::<&[u8], _>("embedding"));
+                (row.get("id"), cosine_similarity(embedding, &stored))
+            })
+            .collect();
+        matches.sort_by(|a, b| b.1.total_cmp(&a.1));
+        Ok(matches)
+    }
+}
+
+/// [`MemoryProvider`] backed by [`SemanticMemoryStore`].
+///
+/// The startup block carries only the most recently updated facts; everything
+/// else reaches the prompt through intent-aware `prefetch` recall.
+///
+/// Each memory scope has its own database in the scope's memory directory.
+/// Requests for another scope are served by a provider opened for that scope
+/// on first use.
+pub struct SqliteMemoryProvider {
+    store: SemanticMemoryStore,
+    /// Markdown files kept for the `HISTORY.md` audit trail of deletions.
+    markdown: MemoryManager,
+    recall_limit: usize,
+    startup_block: Mutex<Option<String>>,
+    /// Facts rendered in `startup_block`, i.e. the memory consolidation rewrites.
+    startup_fact_ids: Mutex<Vec<String>>,
+    handled_session_end_ids: Mutex<HashSet<String>>,
+    workspace: PathBuf,
+    embedder: Arc<dyn Embedder>,
+    scope: MemoryScope,
+    scopes: Mutex<HashMap<MemoryScope, Arc<SqliteMemoryProvider>>>,
+}
+
+impl SqliteMemoryProvider {
+    /// Open the store at `<workspace>/memory/memory.db`, importing markdown memory on first use.
+    pub async fn open(
+        workspace: impl AsRef<Path>,
+        embedder: Arc<dyn Embedder>,
+        recall_limit: usize,
+    ) -> Result<Self> {
+        Self::open_scope(workspace, &MemoryScope::Global, embedder, recall_limit).await
+    }
+
+    /// Open the store of `scope`, importing that scope's markdown memory on first use.
+    pub async fn open_scope(
+        workspace: impl AsRef<Path>,
+        scope: &MemoryScope,
+        embedder: Arc<dyn Embedder>,
+        recall_limit: usize,
+    ) -> Result<Self> {
+        let workspace = workspace.as_ref();
+        let store =
+            SemanticMemoryStore::open(Self::scope_db_path(workspace, scope), embedder.clone())
+                .await?;
+        let markdown = MemoryManager::for_scope(workspace, scope);
+        store.import_markdown(&markdown).await?;
+        let provider = Self {
+            store,
+            markdown,
+            recall_limit: recall_limit.max(1),
+            startup_block: Mutex::new(None),
+            startup_fact_ids: Mutex::new(Vec::new()),
+            handled_session_end_ids: Mutex::new(HashSet::new()),
+            workspace: workspace.to_path_buf(),
+            embedder,
+            scope: scope.clone(),
+            scopes: Mutex::new(HashMap::new()),
+        };
+        provider.refresh_startup_block().await?;
+        Ok(provider)
+    }
+
+    /// Location of the SQLite database inside a workspace.
+    pub fn db_path(workspace: &Path) -> PathBuf {
+        Self::scope_db_path(workspace, &MemoryScope::Global)
+    }
+
+    /// Location of the SQLite database of `scope`.
+    pub fn scope_db_path(workspace: &Path, scope: &MemoryScope) -> PathBuf {
+        scope.memory_dir(workspace).join("memory.db")
+    }
+
+    /// Provider that serves `scope`; `None` when this provider does.
+    async fn scoped(&self, scope: &MemoryScope) -> Result<Option<Arc<SqliteMemoryProvider>>> {
+        if scope.is_global() || *scope == self.scope {
+            return Ok(None);
+        }
+        let cached = self.scopes.lock().get(scope).cloned();
+        if let Some(provider) = cached {
+            return Ok(Some(provider));
+        }
+        let opened = Arc::new(
+            Self::open_scope(
+                &self.workspace,
+                scope,
+                self.embedder.clone(),
+                self.recall_limit,
+            )
+            .await?,
+        );
+        Ok(Some(
+            self.scopes
+                .lock()
+                .entry(scope.clone())
+                .or_insert(opened)
+                .clone(),
+        ))
+    }
+
+    pub fn store(&self) -> &SemanticMemoryStore {
+        &self.store
+    }
+
+    async fn refresh_startup_block(&self) -> Result<()> {
+        let facts = self
+            .store
+            .recent_facts(MemoryFactKind::Fact, STARTUP_FACTS)
+            .await?;
+        let block = (!facts.is_empty()).then(|| {
+            let lines = facts
+                .iter()
+                .map(|fact| format!("- {}", fact.content))
+                .collect::<Vec<_>>()
+                .join("\n");
+            format!("## Long-term Memory\n{}", lines)
+        });
+        *self.startup_block.lock() = block;
+        *self.startup_fact_ids.lock() = facts.into_iter().map(|fact| fact.id).collect();
+        Ok(())
+    }
+
+    /// Apply a consolidation update.
+    ///
+    /// `memory_update_markdown` replaces the memory shown in the startup block,
+    /// so shown facts that the update no longer contains are deleted (and
+    /// recorded in `HISTORY.md`). Facts that were not shown are left alone.
+    async fn write_turn(&self, request: &SyncTurnRequest) -> Result<bool> {
+        let session = request.session_key.as_deref();
+        let mut changed = false;
+        if let Some(markdown) = request.memory_update_markdown.as_deref() {
+            let shown = self.startup_fact_ids.lock().clone();
+            let facts = markdown_facts(markdown);
+            for fact in &facts {
+                changed |= self
+                    .store
+                    .upsert_fact(MemoryFactKind::Fact, fact, session)
+                    .await?
+                    != UpsertOutcome::Unchanged;
+            }
+            let keep: HashSet<String> = facts.iter().map(|fact| normalize_fact(fact)).collect();
+            let dropped = self.store.delete_dropped(&shown, &keep).await?;
+            if !dropped.is_empty() {
+                changed = true;
+                let forgotten: Vec<String> = dropped.into_iter().map(|fact| fact.content).collect();
+                if let Err(err) = self.markdown.record_forgotten(&forgotten, session) {
+                    tracing::warn!("Failed to record dropped memory in HISTORY.md: {}", err);
+                }
+            }
+        }
+        if let Some(entry) = request.history_entry.as_deref() {
+            let inserted = self
+                .store
+                .upsert_fact(MemoryFactKind::History, entry, session)
+                .await?
+                != UpsertOutcome::Unchanged;
+            // `HISTORY.md` stays the readable log behind the memory history view.
+            if inserted {
+                if let Err(err) = self.markdown.append_history(entry) {
+                    tracing::warn!("Failed to append history entry to HISTORY.md: {}", err);
+                }
+            }
+            changed |= inserted;
+        }
+        Ok(changed)
+    }
+}
+
+#[async_trait::async_trait]
+impl MemoryProvider for SqliteMemoryProvider {
+    fn system_prompt_block(
+        &self,
+        request: &SystemPromptRequest,
+    ) -> crate::Result<SystemPromptResponse> {
+        if !request.scope.is_global() && request.scope != self.scope {
+            let scoped = self.scopes.lock().get(&request.scope).cloned();
+            return match scoped {
+                Some(provider) => provider.system_prompt_block(request),
+                None => Ok(SystemPromptResponse::degraded(format!(
+                    "startup continuity unavailable; memory scope '{}' is not loaded",
+                    request.scope
+                ))),
+            };
+        }
+        match self.startup_block.lock().clone() {
+            Some(markdown) => Ok(SystemPromptResponse::ready(SystemPromptBlock {
+                shape: StartupInjectionShape::CompactRenderedMarkdown,
+                markdown,
+            })),
+            None => Ok(SystemPromptResponse::degraded(
+                "startup continuity unavailable; no long-term memory available",
+            )),
+        }
+    }
+
+    async fn prefetch(&self, request: PrefetchRequest) -> crate::Result<PrefetchResponse> {
+        if let Some(provider) = self.scoped(&request.scope).await? {
+            return provider.prefetch(request).await;
+        }
+        if request.intent.trim().is_empty() {
+            return Ok(PrefetchResponse {
+                status: PrefetchStatus::SkippedNoIntent,
+                prompt_block: None,
+            });
+        }
+
+        let query = match request.user_message.as_deref() {
+            Some(message) if !message.trim().is_empty() => {
+                format!("{} {}", request.intent.trim(), message.trim())
+            }
+            _ => request.intent.trim().to_string(),
+        };
+        match self.store.recall(&query, self.recall_limit).await {
+            Ok(recalled) => Ok(PrefetchResponse {
+                status: PrefetchStatus::Ready,
+                prompt_block: render_recall_block(&recalled),
+            }),
+            Err(err) => Ok(PrefetchResponse {
+                status: PrefetchStatus::Failed {
+                    reason: format!("semantic recall failed: {err}"),
+                },
+                prompt_block: None,
+            }),
+        }
+    }
+
+    async fn sync_turn(&self, request: SyncTurnRequest) -> crate::Result<SyncTurnResponse> {
+        if let Some(provider) = self.scoped(&request.scope).await? {
+            return provider.sync_turn(request).await;
+        }
+        let status = match self.write_turn(&request).await {
+            Ok(true) => {
+                if let Err(err) = self.refresh_startup_block().await {
+                    tracing::warn!("Failed to refresh memory startup block: {}", err);
+                }
+                SyncTurnStatus::Persisted
+            }
+            Ok(false) => SyncTurnStatus::Noop,
+            Err(err) => SyncTurnStatus::Failed {
+                reason: format!("failed to persist semantic memory: {err}"),
+            },
+        };
+        Ok(SyncTurnResponse { status })
+    }
+
+    async fn on_session_end(
+        &self,
+        request: SessionEndRequest,
+    ) -> crate::Result<SessionEndResponse> {
+        if let Some(session_id) = request.session_id {
+            let mut handled = self.handled_session_end_ids.lock();
+            if !handled.insert(session_id) {
+                return Ok(SessionEndResponse {
+                    status: SessionEndStatus::AlreadyHandled,
+                });
+            }
+        }
+
+        Ok(SessionEndResponse {
+            status: SessionEndStatus::Noop,
+        })
+    }
+
+    async fn prepare_scope(&self, scope: &MemoryScope) -> crate::Result<()> {
+        self.scoped(scope).await?;
+        Ok(())
+    }
+
+    async fn remember(&self, request: RememberRequest) -> crate::Result<RememberResponse> {
+        if let Some(provider) = self.scoped(&request.scope).await? {
+            return provider.remember(request).await;
+        }
+        let outcome = self
+            .store
+            .upsert_fact(
+                MemoryFactKind::Fact,
+                &request.fact,
+                request.session_key.as_deref(),
+            )
+            .await;
+        let status = match outcome {
+            Ok(UpsertOutcome::Unchanged) => RememberStatus::AlreadyKnown,
+            Ok(_) => {
+                if let Err(err) = self.refresh_startup_block().await {
+                    tracing::warn!("Failed to refresh memory startup block: {}", err);
+                }
+                RememberStatus::Stored
+            }
+            Err(err) => RememberStatus::Failed {
+                reason: format!("failed to persist semantic memory: {err}"),
+            },
+        };
+        Ok(RememberResponse { status })
+    }
+
+    async fn search(&self, request: MemorySearchRequest) -> crate::Result<MemorySearchResponse> {
+        if let Some(provider) = self.scoped(&request.scope).await? {
+            return provider.search(request).await;
+        }
+        match self.store.recall(&request.query, request.limit).await {
+            Ok(recalled) => Ok(MemorySearchResponse {
+                status: MemorySearchStatus::Ready,
+                hits: recalled
+                    .into_iter()
+                    .map(|item| MemorySearchHit {
+                        id: (item.fact.kind == MemoryFactKind::Fact).then_some(item.fact.id),
+                        content: item.fact.content,
+                        recorded_at: Some(item.fact.updated_at.to_rfc3339()),
+                        source_session: item.fact.source_session,
+                    })
+                    .collect(),
+            }),
+            Err(err) => Ok(MemorySearchResponse {
+                status: MemorySearchStatus::Failed {
+                    reason: format!("semantic recall failed: {err}"),
+                },
+                hits: Vec::new(),
+            }),
+        }
+    }
+
+    async fn forget(&self, request: ForgetRequest) -> crate::Result<ForgetResponse> {
+        if let Some(provider) = self.scoped(&request.scope).await? {
+            return provider.forget(request).await;
+        }
+        let removed = match self.store.delete_fact(&request.target).await {
+            Ok(removed) => removed,
+            Err(err) => {
+                return Ok(ForgetResponse {
+                    status: ForgetStatus::Failed {
+                        reason: format!("failed to delete semantic memory: {err}"),
+                    },
+                    forgotten: Vec::new(),
+                })
+            }
+        };
+        if removed.is_empty() {
+            return Ok(ForgetResponse {
+                status: ForgetStatus::NotFound,
+                forgotten: Vec::new(),
+            });
+        }
+
+        let forgotten: Vec<String> = removed.into_iter().map(|fact| fact.content).collect();
+        if let Err(err) = self.refresh_startup_block().await {
+            tracing::warn!("Failed to refresh memory startup block: {}", err);
+        }
+        if let Err(err) = self
+            .markdown
+            .record_forgotten(&forgotten, request.session_key.as_deref())
+        {
+            tracing::warn!("Failed to record forgotten memory in HISTORY.md: {}", err);
+        }
+        Ok(ForgetResponse {
+            status: ForgetStatus::Forgotten,
+            forgotten,
+        })
+    }
+}
+
+/// Build the memory provider selected by `config`.
+///
+/// Returns `None` for the markdown backend so callers keep the default `MemoryManager`.
+pub async fn open_memory_provider(
+    config: &AgentMemoryConfig,
+    workspace: &Path,
+) -> Result<Option<Arc<dyn MemoryProvider>>> {
+    match config.backend {
+        MemoryBackend::Markdown => Ok(None),
+        MemoryBackend::Sqlite => {
+            let provider = SqliteMemoryProvider::open(
+                workspace,
+                Arc::new(HashingEmbedder::default()),
+                config.recall_limit,
+            )
+            .await?;
+            Ok(Some(Arc::new(provider)))
+        }
+    }
+}
+
+fn render_recall_block(recalled: &[RecalledFact]) -> Option<String> {
+    if recalled.is_empty() {
+        return None;
+    }
+    let lines = recalled
+        .iter()
+        .map(|item| {
+            let date = item.fact.updated_at.format("%Y-%m-%d");
+            match item.fact.kind {
+                MemoryFactKind::Fact => format!("- {} ({})", item.fact.content, date),
+                MemoryFactKind::History => format!("- [history] {}", item.fact.content),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    Some(format!("## Recalled Memory\n{}", lines))
+}
+
+/// Split long-term memory markdown into standalone facts.
+///
+/// List items and paragraphs become facts; the nearest sub-heading is kept as
+/// a prefix so a fact like `dark mode` still reads as `Preferences: dark mode`.
+pub fn markdown_facts(markdown: &str) -> Vec<String> {
+    let mut facts = Vec::new();
+    let mut section: Option<String> = None;
+    let mut in_code_block = false;
+    for line in markdown.lines() {
+        let line = line.trim();
+        if line.starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block || line.is_empty() || line.chars().all(|ch| ch == '-' || ch == '*') {
+            continue;
+        }
+        if let Some(heading) = line.strip_prefix('#') {
+            let level = 1 + heading.chars().take_while(|ch| *ch == '#').count();
+            let title = heading.trim_start_matches('#').trim();
+            section = (level > 1 && !title.is_empty()).then(|| title.to_string());
+            continue;
+        }
+        let text = strip_list_marker(line);
+        if text.is_empty() {
+            continue;
+        }
+        facts.push(match &section {
+            Some(section) => format!("{}: {}", section, text),
+            None => text.to_string(),
+        });
+    }
+    facts
+}
+
+pub(crate) fn strip_list_marker(line: &str) -> &str {
+    let line = line
+        .strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .or_else(|| line.strip_prefix("+ "))
+        .unwrap_or(line);
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    if digits > 0 {
+        if let Some(rest) = line[digits..].strip_prefix(". ") {
+            return rest.trim();
+        }
+    }
+    line.trim()
+}
+
+pub(crate) fn normalize_fact(content: &str) -> String {
+    content
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches(['.', '。'])
+        .to_lowercase()
+}
+
+/// Build an FTS5 query that ORs every word of `query`, quoting each term.
+fn fts_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split(|ch: char| !ch.is_alphanumeric())
+        .filter(|term| term.chars().count() > 1)
+        .map(|term| format!("\"{}\"", term.to_lowercase()))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" OR "))
+}
+
+fn encode_embedding(embedding: &[f32]) -> Vec<u8> {
+    embedding
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .collect()
+}
+
+fn decode_embedding(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect()
+}
+
+fn row_to_fact(row: &sqlx::sqlite::SqliteRow) -> Result<MemoryFact> {
+    Ok(MemoryFact {
+        id: row.get("id"),
+        kind: MemoryFactKind::parse(row.get::<&str, _>("kind")),
+        content: row.get("content"),
+        source_session: row.get("source_session"),
+        created_at: parse_timestamp(row.get("created_at"))?,
+        updated_at: parse_timestamp(row.get("updated_at"))?,
+    })
+}
+
+fn parse_timestamp(value: &str) -> Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|ts| ts.with_timezone(&Utc))
+        .map_err(|err| Error::Internal(format!("invalid memory timestamp '{value}': {err}")))
+}
+
+fn db_error(err: sqlx::Error) -> Error {
+    Error::Internal(format!("semantic memory store: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::Memory;
+    use tempfile::TempDir;
+
+    async fn open_provider(workspace: &Path) -> SqliteMemoryProvider {
+        SqliteMemoryProvider::open(workspace, Arc::new(HashingEmbedder::default()), 5)
+            .await
+            .unwrap()
+    }
+
+    fn sync_request(workspace: &Path, markdown: &str) -> SyncTurnRequest {
+        SyncTurnRequest {
+            workspace_root: workspace.to_path_buf(),
+            scope: MemoryScope::Global,
+            memory_update_markdown: Some(markdown.to_string()),
+            history_entry: None,
+            session_key: Some("cli:test".to_string()),
+        }
+    }
+
+    #[test]
+    fn markdown_facts_keeps_section_context() {
+        let facts = markdown_facts(
+            "# Long-term Memory\n\n## Preferences\n- Dark mode\n* Vim keybindings\n\n## Projects\n1. agent-diva uses Rust\n\n```\nignored\n```\n---\n",
+        );
+        assert_eq!(
+            facts,
+            vec![
+                "Preferences: Dark mode",
+                "Preferences: Vim keybindings",
+                "Projects: agent-diva uses Rust",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn history_entries_are_appended_not_merged() {
+        let temp_dir = TempDir::new().unwrap();
+        let provider = open_provider(temp_dir.path()).await;
+        let entries = [
+            "[2026-01-05 09:00] Reviewed the weekly budget with the team",
+            "[2026-01-12 09:00] Reviewed the weekly budget with the team",
+            "[2026-01-12 09:00] Reviewed the weekly budget with the team",
+        ];
+        for entry in entries {
+            provider
+                .sync_turn(SyncTurnRequest {
+                    memory_update_markdown: None,
+                    history_entry: Some(entry.to_string()),
+                    ..sync_request(temp_dir.path(), "")
+                })
+                .await
+                .unwrap();
+        }
+
+        let stored = provider
+            .store()
+            .recent_facts(MemoryFactKind::History, 10)
+            .await
+            .unwrap();
+        assert_eq!(stored.len(), 2);
+        let history = MemoryManager::new(temp_dir.path()).load_history();
+        assert!(history.contains(entries[0]));
+        assert_eq!(history.matches(entries[1]).count(), 1);
+    }
+
+    #[tokio::test]
+    async fn sync_turn_deduplicates_facts() {
+        let temp_dir = TempDir::new().unwrap();
+        let provider = open_provider(temp_dir.path()).await;
+
+        let first = provider
+            .sync_turn(sync_request(
+                temp_dir.path(),
+                "## Preferences\n- User prefers dark mode in every editor\n- Lives in Berlin",
+            ))
+            .await
+            .unwrap();
+        assert_eq!(first.status, SyncTurnStatus::Persisted);
+        assert_eq!(provider.store().count().await.unwrap(), 2);
+
+        let repeat = provider
+            .sync_turn(sync_request(
+                temp_dir.path(),
+                "## Preferences\n- user prefers dark mode in every editor.\n- Lives in  Berlin",
+            ))
+            .await
+            .unwrap();
+        assert_eq!(repeat.status, SyncTurnStatus::Noop);
+        assert_eq!(provider.store().count().await.unwrap(), 2);
+
+        let reworded = provider
+            .store()
+            .upsert_fact(
+                MemoryFactKind::Fact,
+                "Preferences: User prefers dark mode in every editor!",
+                Some("cli:other"),
+            )
+            .await
+            .unwrap();
+        assert_eq!(reworded, UpsertOutcome::Merged);
+        assert_eq!(provider.store().count().await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn sync_turn_deletes_facts_dropped_from_the_update() {
+        let temp_dir = TempDir::new().unwrap();
+        let provider = open_provider(temp_dir.path()).await;
+        provider
+            .sync_turn(sync_request(
+                temp_dir.path(),
+                "- Lives in Berlin\n- Works on agent-diva",
+            ))
+            .await
+            .unwrap();
+
+        // Written outside consolidation, so the update below never saw it.
+        provider
+            .store()
+            .upsert_fact(MemoryFactKind::Fact, "Has a cat named Miso", None)
+            .await
+            .unwrap();
+
+        let moved = provider
+            .sync_turn(sync_request(
+                temp_dir.path(),
+                "- Lives in Lisbon\n- Works on agent-diva",
+            ))
+            .await
+            .unwrap();
+        assert_eq!(moved.status, SyncTurnStatus::Persisted);
+
+        let facts: Vec<String> = provider
+            .store()
+            .recent_facts(MemoryFactKind::Fact, 10)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|fact| fact.content)
+            .collect();
+        assert!(facts.contains(&"Lives in Lisbon".to_string()));
+        assert!(facts.contains(&"Works on agent-diva".to_string()));
+        assert!(facts.contains(&"Has a cat named Miso".to_string()));
+        assert!(!facts.contains(&"Lives in Berlin".to_string()));
+        assert!(provider.markdown.load_history().contains("Lives in Berlin"));
+    }
+
+    #[tokio::test]
+    async fn prefetch_recalls_relevant_facts() {
+        let temp_dir = TempDir::new().unwrap();
+        let provider = open_provider(temp_dir.path()).await;
+        provider
+            .sync_turn(sync_request(
+                temp_dir.path(),
+                "- The staging database runs PostgreSQL 16\n- User's cat is named Miso\n- Deploys happen every Friday afternoon",
+            ))
+            .await
+            .unwrap();
+
+        let response = provider
+            .prefetch(PrefetchRequest {
+                workspace_root: temp_dir.path().to_path_buf(),
+                scope: MemoryScope::Global,
+                intent: "what database does staging use".to_string(),
+                current_room: None,
+                user_message: None,
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(response.status, PrefetchStatus::Ready);
+        let block = response.prompt_block.unwrap();
+        let first_line = block.lines().nth(1).unwrap();
+        assert!(first_line.contains("PostgreSQL"), "{block}");
+    }
+
+    #[tokio::test]
+    async fn forget_removes_facts_and_records_history() {
+        let temp_dir = TempDir::new().unwrap();
+        let provider = open_provider(temp_dir.path()).await;
+        provider
+            .remember(RememberRequest {
+                workspace_root: temp_dir.path().to_path_buf(),
+                scope: MemoryScope::Global,
+                fact: "Home address is 12 Old Street".to_string(),
+                session_key: Some("cli:test".to_string()),
+            })
+            .await
+            .unwrap();
+        let startup = provider
+            .system_prompt_block(&SystemPromptRequest {
+                workspace_root: temp_dir.path().to_path_buf(),
+                scope: MemoryScope::Global,
+            })
+            .unwrap();
+        assert!(startup
+            .prompt_block
+            .unwrap()
+            .markdown
+            .contains("Old Street"));
+
+        let response = provider
+            .forget(ForgetRequest {
+                workspace_root: temp_dir.path().to_path_buf(),
+                scope: MemoryScope::Global,
+                target: "old street".to_string(),
+                session_key: Some("cli:test".to_string()),
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            response.status,
+            ForgetStatus::NotFound,
+            "no substring deletes"
+        );
+
+        let response = provider
+            .forget(ForgetRequest {
+                workspace_root: temp_dir.path().to_path_buf(),
+                scope: MemoryScope::Global,
+                target: "home address is 12 old street.".to_string(),
+                session_key: Some("cli:test".to_string()),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(response.status, ForgetStatus::Forgotten);
+        assert_eq!(provider.store().count().await.unwrap(), 0);
+        assert!(MemoryManager::new(temp_dir.path())
+            .load_history()
+            .contains("Forgot memory (requested in cli:test): Home address is 12 Old Street"));
+        let hits = provider
+            .search(MemorySearchRequest {
+                workspace_root: temp_dir.path().to_path_buf(),
+                scope: MemoryScope::Global,
+                query: "address".to_string(),
+                limit: 5,
+            })
+            .await
+            .unwrap()
+            .hits;
+        assert!(hits.is_empty());
+
+        provider
+            .remember(RememberRequest {
+                workspace_root: temp_dir.path().to_path_buf(),
+                scope: MemoryScope::Global,
+                fact: "Office is on Harbour Road".to_string(),
+                session_key: None,
+            })
+            .await
+            .unwrap();
+        let hits = provider
+            .search(MemorySearchRequest {
+                workspace_root: temp_dir.path().to_path_buf(),
+                scope: MemoryScope::Global,
+                query: "office".to_string(),
+                limit: 5,
+            })
+            .await
+            .unwrap()
+            .hits;
+        let id = hits[0].id.clone().expect("facts have ids");
+        let response = provider
+            .forget(ForgetRequest {
+                workspace_root: temp_dir.path().to_path_buf(),
+                scope: MemoryScope::Global,
+                target: id,
+                session_key: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(response.forgotten, vec!["Office is on Harbour Road"]);
+        assert_eq!(provider.store().count().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn open_imports_markdown_memory_once() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = MemoryManager::new(temp_dir.path());
+        manager
+            .save_memory(&Memory::with_content(
+                "# Long-term Memory\n\n## Facts\n- Favourite language is Rust",
+            ))
+            .unwrap();
+        manager
+            .append_history("[2026-01-01 10:00 UTC] Set up the workspace")
+            .unwrap();
+
+        let provider = open_provider(temp_dir.path()).await;
+        assert_eq!(provider.store().count().await.unwrap(), 2);
+        let startup = provider
+            .system_prompt_block(&SystemPromptRequest {
+                workspace_root: temp_dir.path().to_path_buf(),
+                scope: MemoryScope::Global,
+            })
+            .unwrap();
+        assert!(startup
+            .prompt_block
+            .unwrap()
+            .markdown
+            .contains("Facts: Favourite language is Rust"));
+        drop(provider);
+
+        manager
+            .save_memory(&Memory::with_content("- Added after import"))
+            .unwrap();
+        let reopened = open_provider(temp_dir.path()).await;
+        assert_eq!(reopened.store().count().await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn scoped_requests_use_separate_databases() {
+        let temp_dir = TempDir::new().unwrap();
+        let provider = open_provider(temp_dir.path()).await;
+        let scope = MemoryScope::Chat {
+            channel: "discord".to_string(),
+            chat_id: "guild-1".to_string(),
+        };
+        let mut request = sync_request(temp_dir.path(), "- Guild raid night is Thursday");
+        request.scope = scope.clone();
+        provider.sync_turn(request).await.unwrap();
+
+        assert_eq!(provider.store().count().await.unwrap(), 0);
+        assert!(SqliteMemoryProvider::scope_db_path(temp_dir.path(), &scope).exists());
+
+        let prompt_for = |scope: MemoryScope| {
+            provider
+                .system_prompt_block(&SystemPromptRequest {
+                    workspace_root: temp_dir.path().to_path_buf(),
+                    scope,
+                })
+                .unwrap()
+                .prompt_block
+                .map(|block| block.markdown)
+        };
+        assert!(prompt_for(scope.clone())
+            .unwrap()
+            .contains("Guild raid night is Thursday"));
+        assert!(!prompt_for(MemoryScope::Global)
+            .unwrap()
+            .contains("Guild raid night"));
+    }
+}
@@ -0,0 +1,41 @@
+//! Pluggable persistence for sessions
+
+use super::jsonl::JsonlSessionStore;
+use super::manager::SessionInfo;
+use super::sqlite::SqliteSessionStore;
+use super::store::Session;
+use crate::config::schema::SessionBackend;
+use std::path::Path;
+
+/// Persistent storage behind [`super::SessionManager`].
+///
+/// Calls are synchronous; implementations that need async I/O run it off
+/// the caller's runtime.
+pub trait SessionStore: Send + Sync + std::fmt::Debug {
+    /// Load a session, or `None` if it was never saved
+    fn load(&self, key: &str) -> crate::Result<Option<Session>>;
+
+    /// Persist the full state of a session
+    fn save(&self, session: &Session) -> crate::Result<()>;
+
+    /// Remove a session. Returns whether it existed.
+    fn delete(&self, key: &str) -> crate::Result<bool>;
+
+    /// Move a session out of the way so its key starts fresh. Returns
+    /// whether it existed.
+    fn archive(&self, key: &str) -> crate::Result<bool>;
+
+    /// Summaries of all live sessions, most recently updated first
+    fn list(&self) -> Vec<SessionInfo>;
+}
+
+/// Open the store for `backend` over `sessions_dir`
+pub fn open_session_store(
+    sessions_dir: &Path,
+    backend: SessionBackend,
+) -> crate::Result<Box<dyn SessionStore>> {
+    Ok(match backend {
+        SessionBackend::Jsonl => Box::new(JsonlSessionStore::new(sessions_dir)),
+        SessionBackend::Sqlite => Box::new(SqliteSessionStore::open(sessions_dir)?),
+    })
+}
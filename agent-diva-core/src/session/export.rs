@@ -0,0 +1,547 @@
+//! Portable session export and import
+//!
+//! Sessions can be rendered as Markdown or a standalone HTML page for
+//! reading, or packed into a versioned JSON bundle that carries the
+//! attachment bytes from the file store so it can be imported elsewhere.
+
+use super::manager::SessionManager;
+use super::store::{ChatMessage, Session};
+use crate::redaction::redact_secrets;
+use agent_diva_files::handle::FileMetadata;
+use agent_diva_files::FileManager;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+use std::str::FromStr;
+
+/// Current version of the JSON bundle layout
+pub const SESSION_BUNDLE_VERSION: u32 = 1;
+
+/// Output format for a session export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionExportFormat {
+    Markdown,
+    Html,
+    Json,
+}
+
+impl SessionExportFormat {
+    /// File extension for exported files
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Html => "html",
+            Self::Json => "json",
+        }
+    }
+
+    /// MIME type of the exported document
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Markdown => "text/markdown; charset=utf-8",
+            Self::Html => "text/html; charset=utf-8",
+            Self::Json => "application/json",
+        }
+    }
+}
+
+impl FromStr for SessionExportFormat {
+    type Err = crate::Error;
+
+    fn from_str(value: &str) -> crate::Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "md" | "markdown" => Ok(Self::Markdown),
+            "html" | "htm" => Ok(Self::Html),
+            "json" | "bundle" => Ok(Self::Json),
+            other => Err(crate::Error::Validation(format!(
+                "unknown export format '{}'; expected markdown, html or json",
+                other
+            ))),
+        }
+    }
+}
+
+/// Options controlling a session export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionExportOptions {
+    /// Mask API keys, bearer tokens and similar secrets in the transcript
+    pub redact: bool,
+}
+
+impl Default for SessionExportOptions {
+    fn default() -> Self {
+        Self { redact: true }
+    }
+}
+
+/// Versioned, self-contained session archive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionBundle {
+    /// Bundle layout version, see [`SESSION_BUNDLE_VERSION`]
+    pub version: u32,
+    /// When the bundle was produced
+    pub exported_at: DateTime<Utc>,
+    /// Whether secrets were masked during export
+    #[serde(default)]
+    pub redacted: bool,
+    /// The exported session
+    pub session: Session,
+    /// Attachment payloads referenced by the session's messages
+    #[serde(default)]
+    pub attachments: Vec<BundledAttachment>,
+}
+
+/// An attachment carried inside a [`SessionBundle`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundledAttachment {
+    /// File ID the session messages refer to
+    pub file_id: String,
+    /// Original filename
+    pub filename: String,
+    /// MIME type if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    /// Base64-encoded file contents
+    pub data: String,
+}
+
+/// Export the session `key` in the requested format.
+///
+/// Attachments are only embedded in JSON bundles, and only when a file
+/// manager is supplied.
+pub async fn export_session(
+    sessions: &mut SessionManager,
+    files: Option<&FileManager>,
+    key: &str,
+    format: SessionExportFormat,
+    options: SessionExportOptions,
+) -> crate::Result<String> {
+    let Some(session) = sessions.get_or_load(key)? else {
+        return Err(crate::Error::NotFound(format!("session '{}'", key)));
+    };
+    let session = if options.redact {
+        redact_session(session)
+    } else {
+        session.clone()
+    };
+
+    match format {
+        SessionExportFormat::Markdown => Ok(render_markdown(&session)),
+        SessionExportFormat::Html => Ok(render_html(&session)),
+        SessionExportFormat::Json => {
+            let mut bundle = build_bundle(session, files).await?;
+            bundle.redacted = options.redact;
+            Ok(serde_json::to_string_pretty(&bundle)?)
+        }
+    }
+}
+
+/// Pack a session and its attachments into a bundle. Attachments that are
+/// missing from the file store are left out; the message references stay.
+pub async fn build_bundle(
+    session: Session,
+    files: Option<&FileManager>,
+) -> crate::Result<SessionBundle> {
+    let mut attachments = Vec::new();
+    if let Some(files) = files {
+        // Collect first: iterator closures held across an await make the
+        // future unusable from `tokio::spawn`.
+        let mut seen = HashSet::new();
+        let mut referenced = Vec::new();
+        for message in &session.messages {
+            for attachment in message.attachments.iter().flatten() {
+                if seen.insert(attachment.file_id.clone()) {
+                    referenced.push(attachment.clone());
+                }
+            }
+        }
+        for attachment in referenced {
+            let data = match files.get(&attachment.file_id).await {
+                Ok(handle) => files.read(&handle).await,
+                Err(e) => Err(e),
+            };
+            match data {
+                Ok(data) => attachments.push(BundledAttachment {
+                    file_id: attachment.file_id,
+                    filename: attachment.filename,
+                    mime_type: attachment.mime_type,
+                    data: STANDARD.encode(data),
+                }),
+                Err(e) => tracing::warn!(
+                    "Skipping attachment {} in export of {}: {}",
+                    attachment.file_id,
+                    session.key,
+                    e
+                ),
+            }
+        }
+    }
+
+    Ok(SessionBundle {
+        version: SESSION_BUNDLE_VERSION,
+        exported_at: Utc::now(),
+        redacted: false,
+        session,
+        attachments,
+    })
+}
+
+/// Import a bundle as a new session and return it.
+///
+/// The session is stored under `key`, or under the bundled key prefixed with
+/// `import:` when `None` so an import never continues a live channel chat;
+/// if that key is taken, an `:import-N` suffix is appended. Attachments are
+/// written to the file store and message references are remapped to the
+/// stored IDs.
+pub async fn import_bundle(
+    sessions: &mut SessionManager,
+    files: Option<&FileManager>,
+    bundle: SessionBundle,
+    key: Option<&str>,
+) -> crate::Result<Session> {
+    if bundle.version > SESSION_BUNDLE_VERSION {
+        return Err(crate::Error::Validation(format!(
+            "session bundle version {} is newer than supported version {}",
+            bundle.version, SESSION_BUNDLE_VERSION
+        )));
+    }
+
+    let mut session = bundle.session;
+    let base_key = match key {
+        Some(key) => key.to_string(),
+        None => format!("{}{}", IMPORT_KEY_PREFIX, session.key),
+    };
+    if base_key.trim().is_empty() {
+        return Err(crate::Error::Validation(
+            "imported session key must not be empty".to_string(),
+        ));
+    }
+    session.key = available_key(sessions, &base_key)?;
+
+    let mut remapped = HashMap::new();
+    if let Some(files) = files {
+        for attachment in bundle.attachments {
+            let data = STANDARD.decode(attachment.data.as_bytes()).map_err(|e| {
+                crate::Error::Serialization(format!(
+                    "invalid attachment data for {}: {}",
+                    attachment.file_id, e
+                ))
+            })?;
+            let metadata = FileMetadata {
+                name: attachment.filename,
+                size: data.len() as u64,
+                mime_type: attachment.mime_type,
+                source: Some("import".to_string()),
+                created_at: Utc::now(),
+                last_accessed_at: None,
+                preview: None,
+            };
+            let handle = files.store(&data, metadata).await.map_err(|e| {
+                crate::Error::Internal(format!("failed to store attachment: {}", e))
+            })?;
+            remapped.insert(attachment.file_id, handle.id);
+        }
+    }
+    for attachment in session
+        .messages
+        .iter_mut()
+        .filter_map(|m| m.attachments.as_mut())
+        .flatten()
+    {
+        if let Some(new_id) = remapped.get(&attachment.file_id) {
+            attachment.file_id = new_id.clone();
+        }
+    }
+
+    sessions.save(&session)?;
+    Ok(session)
+}
+
+/// Namespace for imported sessions stored without an explicit key
+const IMPORT_KEY_PREFIX: &str = "import:";
+
+fn available_key(sessions: &mut SessionManager, base_key: &str) -> crate::Result<String> {
+    if sessions.get_or_load(base_key)?.is_none() {
+        return Ok(base_key.to_string());
+    }
+    for n in 2.. {
+        let candidate = format!("{}:import-{}", base_key, n);
+        if sessions.get_or_load(&candidate)?.is_none() {
+            return Ok(candidate);
+        }
+    }
+    unreachable!("unbounded key search always returns")
+}
+
+/// Copy of `session` with secrets masked in message text, reasoning, tool
+/// call arguments and metadata
+pub fn redact_session(session: &Session) -> Session {
+    let mut session = session.clone();
+    for message in &mut session.messages {
+        message.content = redact_secrets(&message.content);
+        if let Some(reasoning) = message.reasoning_content.as_mut() {
+            *reasoning = redact_secrets(reasoning);
+        }
+        for value in message
+            .tool_calls
+            .iter_mut()
+            .chain(message.thinking_blocks.iter_mut())
+            .flatten()
+        {
+            redact_value(value);
+        }
+    }
+    redact_value(&mut session.metadata);
+    session
+}
+
+fn redact_value(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::String(text) => *text = redact_secrets(text),
+        serde_json::Value::Array(items) => items.iter_mut().for_each(redact_value),
+        serde_json::Value::Object(map) => map.values_mut().for_each(redact_value),
+        _ => {}
+    }
+}
+
+/// Render a session as a Markdown transcript
+pub fn render_markdown(session: &Session) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# Session `{}`\n", session.key);
+    let _ = writeln!(out, "- Created: {}", session.created_at.to_rfc3339());
+    let _ = writeln!(out, "- Updated: {}", session.updated_at.to_rfc3339());
+    let _ = writeln!(out, "- Messages: {}", session.messages.len());
+    if let Some(lineage) = &session.lineage {
+        let _ = writeln!(
+            out,
+            "- Forked from: `{}` at message {}",
+            lineage.parent_key, lineage.forked_at_message
+        );
+    }
+
+    for message in &session.messages {
+        let _ = writeln!(
+            out,
+            "\n## {} · {}\n",
+            message_heading(message),
+            message.timestamp.to_rfc3339()
+        );
+        if message.role == "tool" {
+            let _ = writeln!(out, "```\n{}\n```", message.content.trim_end());
+        } else if !message.content.is_empty() {
+            let _ = writeln!(out, "{}", message.content.trim_end());
+        }
+        for name in tool_call_names(message) {
+            let _ = writeln!(out, "\n> Called tool `{}`", name);
+        }
+        for attachment in message.attachments.iter().flatten() {
+            let _ = writeln!(
+                out,
+                "\n- Attachment: {} ({} bytes)",
+                attachment.filename, attachment.size
+            );
+        }
+    }
+    out
+}
+
+/// Render a session as a standalone HTML page
+pub fn render_html(session: &Session) -> String {
+    let mut out = String::new();
+    let title = escape_html(&session.key);
+    let _ = write!(
+        out,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Session {title}</title>\n\
+<style>\nbody{{font-family:sans-serif;max-width:48rem;margin:2rem auto;padding:0 1rem;}}\n\
+.message{{border-left:3px solid #ccc;margin:1rem 0;padding:0.25rem 1rem;}}\n\
+.user{{border-color:#3b82f6;}}\n.assistant{{border-color:#10b981;}}\n.tool{{border-color:#f59e0b;}}\n\
+.meta{{color:#666;font-size:0.85rem;}}\npre{{white-space:pre-wrap;}}\n</style>\n</head>\n<body>\n\
+<h1>Session <code>{title}</code></h1>\n"
+    );
+    let _ = writeln!(
+        out,
+        "<p class=\"meta\">Created {} · Updated {} · {} messages</p>",
+        session.created_at.to_rfc3339(),
+        session.updated_at.to_rfc3339(),
+        session.messages.len()
+    );
+    if let Some(lineage) = &session.lineage {
+        let _ = writeln!(
+            out,
+            "<p class=\"meta\">Forked from <code>{}</code> at message {}</p>",
+            escape_html(&lineage.parent_key),
+            lineage.forked_at_message
+        );
+    }
+
+    for message in &session.messages {
+        let _ = writeln!(
+            out,
+            "<div class=\"message {}\">\n<p class=\"meta\">{} · {}</p>",
+            escape_html(&message.role),
+            escape_html(&message_heading(message)),
+            message.timestamp.to_rfc3339()
+        );
+        if !message.content.is_empty() {
+            let _ = writeln!(
+                out,
+                "<pre>{}</pre>",
+                escape_html(message.content.trim_end())
+            );
+        }
+        for name in tool_call_names(message) {
+            let _ = writeln!(
+                out,
+                "<p class=\"meta\">Called tool <code>{}</code></p>",
+                escape_html(&name)
+            );
+        }
+        for attachment in message.attachments.iter().flatten() {
+            let _ = writeln!(
+                out,
+                "<p class=\"meta\">Attachment: {} ({} bytes)</p>",
+                escape_html(&attachment.filename),
+                attachment.size
+            );
+        }
+        out.push_str("</div>\n");
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn message_heading(message: &ChatMessage) -> String {
+    let mut role = message.role.clone();
+    if let Some(first) = role.get_mut(0..1) {
+        first.make_ascii_uppercase();
+    }
+    match &message.name {
+        Some(name) if message.role == "tool" => format!("{} `{}`", role, name),
+        _ => role,
+    }
+}
+
+fn tool_call_names(message: &ChatMessage) -> Vec<String> {
+    message
+        .tool_calls
+        .iter()
+        .flatten()
+        .filter_map(|call| {
+            call.pointer("/function/name")
+                .or_else(|| call.get("name"))
+                .and_then(|v| v.as_str())
+                .map(str::to_string)
+        })
+        .collect()
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attachment::FileAttachmentRef;
+    use agent_diva_files::FileConfig;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_markdown_and_html_redact_by_default() {
+        let mut session = Session::new("cli:demo");
+        session.add_message("user", "my key is sk-abc123secret <b>");
+        session.add_message("assistant", "noted");
+        let session = redact_session(&session);
+
+        let markdown = render_markdown(&session);
+        assert!(markdown.contains("# Session `cli:demo`"));
+        assert!(markdown.contains("***REDACTED***"));
+        assert!(!markdown.contains("sk-abc123secret"));
+
+        let html = render_html(&session);
+        assert!(html.contains("&lt;b&gt;"));
+        assert!(!html.contains("sk-abc123secret"));
+    }
+
+    #[tokio::test]
+    async fn test_bundle_round_trip_remaps_key_and_attachments() {
+        let temp = TempDir::new().unwrap();
+        let files = FileManager::new(FileConfig::with_path(temp.path().join("files")))
+            .await
+            .unwrap();
+        let mut sessions = SessionManager::new(temp.path());
+
+        let metadata = FileMetadata {
+            name: "notes.txt".to_string(),
+            size: 5,
+            mime_type: Some("text/plain".to_string()),
+            source: None,
+            created_at: Utc::now(),
+            last_accessed_at: None,
+            preview: None,
+        };
+        let handle = files.store(b"hello", metadata).await.unwrap();
+        let session = sessions.get_or_create("cli:demo").unwrap();
+        session.add_full_message(ChatMessage::with_attachments(
+            "user",
+            "see attached",
+            vec![FileAttachmentRef {
+                file_id: handle.id.clone(),
+                filename: "notes.txt".to_string(),
+                mime_type: Some("text/plain".to_string()),
+                size: 5,
+            }],
+        ));
+        let snapshot = session.clone();
+        sessions.save(&snapshot).unwrap();
+
+        let exported = export_session(
+            &mut sessions,
+            Some(&files),
+            "cli:demo",
+            SessionExportFormat::Json,
+            SessionExportOptions::default(),
+        )
+        .await
+        .unwrap();
+        let bundle: SessionBundle = serde_json::from_str(&exported).unwrap();
+        assert_eq!(bundle.version, SESSION_BUNDLE_VERSION);
+        assert!(bundle.redacted);
+        assert_eq!(bundle.attachments.len(), 1);
+
+        let imported = import_bundle(&mut sessions, Some(&files), bundle.clone(), None)
+            .await
+            .unwrap();
+        assert_eq!(imported.key, "import:cli:demo");
+        assert_eq!(imported.messages.len(), 1);
+        let attachment = &imported.messages[0].attachments.as_ref().unwrap()[0];
+        let stored = files.get(&attachment.file_id).await.unwrap();
+        assert_eq!(files.read(&stored).await.unwrap(), b"hello");
+
+        let mut reloaded = SessionManager::new(temp.path());
+        assert!(reloaded.get_or_load("import:cli:demo").unwrap().is_some());
+
+        let again = import_bundle(&mut sessions, Some(&files), bundle.clone(), None)
+            .await
+            .unwrap();
+        assert_eq!(again.key, "import:cli:demo:import-2");
+        let keyed = import_bundle(&mut sessions, Some(&files), bundle, Some("cli:demo"))
+            .await
+            .unwrap();
+        assert_eq!(keyed.key, "cli:demo:import-2");
+    }
+}
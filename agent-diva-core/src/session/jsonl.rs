@@ -0,0 +1,221 @@
+//! JSONL session files, one file per session
+
+use super::backend::SessionStore;
+use super::manager::{SessionInfo, SessionLoadError};
+use super::store::{ChatMessage, Session, SessionLineage};
+use std::path::{Path, PathBuf};
+
+/// Stores each session as `<key>.jsonl`: a metadata line followed by one
+/// line per message. Every save rewrites the file atomically.
+#[derive(Debug, Clone)]
+pub struct JsonlSessionStore {
+    sessions_dir: PathBuf,
+}
+
+impl JsonlSessionStore {
+    /// Create a store over `sessions_dir`
+    pub fn new(sessions_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            sessions_dir: sessions_dir.into(),
+        }
+    }
+
+    /// Read a session file, falling back to its `.bak` copy
+    pub fn read(&self, key: &str) -> Result<Option<Session>, SessionLoadError> {
+        let path = self.session_path(key);
+        let backup_path = self.backup_path(key);
+        let path_to_read = if path.exists() {
+            path
+        } else if backup_path.exists() {
+            backup_path
+        } else {
+            return Ok(None);
+        };
+
+        let content = std::fs::read_to_string(&path_to_read).map_err(|error| {
+            SessionLoadError::Unreadable {
+                path: path_to_read.clone(),
+                error: error.to_string(),
+            }
+        })?;
+        let mut messages = Vec::new();
+        let mut metadata = serde_json::Value::Object(serde_json::Map::new());
+        let mut created_at = None;
+        let mut updated_at = None;
+        let mut last_consolidated: usize = 0;
+        let mut lineage = None;
+
+        for (line_index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let value = serde_json::from_str::<serde_json::Value>(line).map_err(|error| {
+                SessionLoadError::Parse {
+                    path: path_to_read.clone(),
+                    line: line_index + 1,
+                    error: error.to_string(),
+                }
+            })?;
+
+            if value.get("_type").and_then(|v| v.as_str()) == Some("metadata") {
+                metadata = value.get("metadata").cloned().unwrap_or(metadata);
+                created_at = value
+                    .get("created_at")
+                    .and_then(|v| v.as_str())
+                    .and_then(|s| s.parse().ok());
+                updated_at = value
+                    .get("updated_at")
+                    .and_then(|v| v.as_str())
+                    .and_then(|s| s.parse().ok());
+                last_consolidated = value
+                    .get("last_consolidated")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(0) as usize;
+                lineage = value
+                    .get("lineage")
+                    .cloned()
+                    .and_then(|v| serde_json::from_value::<SessionLineage>(v).ok());
+            } else {
+                let msg = serde_json::from_value::<ChatMessage>(value).map_err(|error| {
+                    SessionLoadError::Parse {
+                        path: path_to_read.clone(),
+                        line: line_index + 1,
+                        error: error.to_string(),
+                    }
+                })?;
+                messages.push(msg);
+            }
+        }
+
+        Ok(Some(Session {
+            key: key.to_string(),
+            messages,
+            created_at: created_at.unwrap_or_else(chrono::Utc::now),
+            updated_at: updated_at.unwrap_or_else(chrono::Utc::now),
+            metadata,
+            last_consolidated,
+            lineage,
+        }))
+    }
+
+    /// Get the file path for a session
+    pub(crate) fn session_path(&self, key: &str) -> PathBuf {
+        let safe_key = key.replace([':', '/', '\\'], "_");
+        self.sessions_dir.join(format!("{}.jsonl", safe_key))
+    }
+
+    pub(crate) fn backup_path(&self, key: &str) -> PathBuf {
+        let safe_key = key.replace([':', '/', '\\'], "_");
+        self.sessions_dir.join(format!("{}.jsonl.bak", safe_key))
+    }
+
+    fn write_session_atomically(&self, path: &Path, content: &[u8]) -> crate::Result<()> {
+        crate::utils::atomic_write(path, content)
+    }
+}
+
+impl SessionStore for JsonlSessionStore {
+    fn load(&self, key: &str) -> crate::Result<Option<Session>> {
+        Ok(self.read(key)?)
+    }
+
+    fn save(&self, session: &Session) -> crate::Result<()> {
+        std::fs::create_dir_all(&self.sessions_dir)?;
+        let path = self.session_path(&session.key);
+
+        let mut lines = Vec::new();
+
+        // Write metadata
+        let mut metadata = serde_json::json!({
+            "_type": "metadata",
+            "created_at": session.created_at.to_rfc3339(),
+            "updated_at": session.updated_at.to_rfc3339(),
+            "metadata": session.metadata,
+            "last_consolidated": session.last_consolidated,
+        });
+        // Older readers ignore unknown keys; only branches carry lineage.
+        if let Some(lineage) = &session.lineage {
+            metadata["lineage"] = serde_json::to_value(lineage)?;
+        }
+        lines.push(serde_json::to_string(&metadata)?);
+
+        // Write messages
+        for msg in &session.messages {
+            lines.push(serde_json::to_string(msg)?);
+        }
+
+        self.write_session_atomically(&path, lines.join("\n").as_bytes())
+    }
+
+    fn delete(&self, key: &str) -> crate::Result<bool> {
+        let path = self.session_path(key);
+        if path.exists() {
+            std::fs::remove_file(&path)?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn archive(&self, key: &str) -> crate::Result<bool> {
+        let path = self.session_path(key);
+        if path.exists() {
+            let safe_key = key.replace([':', '/', '\\'], "_");
+            let timestamp = chrono::Utc::now().timestamp_millis();
+            let archive_filename = format!("{}.reset.{}.jsonl", safe_key, timestamp);
+            let archive_path = self.sessions_dir.join(archive_filename);
+
+            std::fs::rename(&path, &archive_path)?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn list(&self) -> Vec<SessionInfo> {
+        let mut sessions = Vec::new();
+
+        if let Ok(entries) = std::fs::read_dir(&self.sessions_dir) {
+            for entry in entries.flatten() {
+                if let Some(name) = entry.file_name().to_str() {
+                    if name.ends_with(".jsonl") {
+                        let key = name.trim_end_matches(".jsonl").replace('_', ":");
+                        if let Ok(content) = std::fs::read_to_string(entry.path()) {
+                            if let Some(first_line) = content.lines().next() {
+                                if let Ok(value) =
+                                    serde_json::from_str::<serde_json::Value>(first_line)
+                                {
+                                    if value.get("_type").and_then(|v| v.as_str())
+                                        == Some("metadata")
+                                    {
+                                        sessions.push(SessionInfo {
+                                            key,
+                                            created_at: value
+                                                .get("created_at")
+                                                .and_then(|v| v.as_str())
+                                                .map(|s| s.to_string()),
+                                            updated_at: value
+                                                .get("updated_at")
+                                                .and_then(|v| v.as_str())
+                                                .map(|s| s.to_string()),
+                                            path: entry.path().to_string_lossy().to_string(),
+                                            lineage: value
+                                                .get("lineage")
+                                                .cloned()
+                                                .and_then(|v| serde_json::from_value(v).ok()),
+                                        });
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        sessions.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+        sessions
+    }
+}
@@ -1,6 +1,9 @@
 //! Session manager for handling multiple sessions
 
-use super::store::Session;
+use super::backend::{open_session_store, SessionStore};
+use super::jsonl::JsonlSessionStore;
+use super::store::{ChatMessage, Session, SessionLineage};
+use crate::config::schema::AgentSessionsConfig;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use thiserror::Error;
@@ -22,16 +25,31 @@ pub enum SessionLoadError {
 pub struct SessionManager {
     /// Sessions directory
     sessions_dir: PathBuf,
+    /// Persistent storage backend
+    store: Box<dyn SessionStore>,
     /// In-memory cache of sessions
     cache: HashMap<String, Session>,
 }
 
 impl SessionManager {
-    /// Create a new session manager
+    /// Create a new session manager backed by JSONL files
     pub fn new<P: AsRef<Path>>(workspace: P) -> Self {
         let sessions_dir = workspace.as_ref().join("sessions");
+        let store = Box::new(JsonlSessionStore::new(&sessions_dir));
+        Self::with_store(workspace, store)
+    }
+
+    /// Create a session manager using the backend selected in `config`
+    pub fn open<P: AsRef<Path>>(workspace: P, config: &AgentSessionsConfig) -> crate::Result<Self> {
+        let store = open_session_store(&workspace.as_ref().join("sessions"), config.backend)?;
+        Ok(Self::with_store(workspace, store))
+    }
+
+    /// Create a session manager over an explicit store
+    pub fn with_store<P: AsRef<Path>>(workspace: P, store: Box<dyn SessionStore>) -> Self {
         Self {
-            sessions_dir,
+            sessions_dir: workspace.as_ref().join("sessions"),
+            store,
             cache: HashMap::new(),
         }
     }
@@ -65,192 +83,131 @@ impl SessionManager {
         Ok(self.cache.get(key))
     }
 
-    /// Load a session from disk
-    fn load(&self, key: &str) -> Result<Option<Session>, SessionLoadError> {
-        let path = self.session_path(key);
-        let backup_path = self.backup_path(key);
-        let path_to_read = if path.exists() {
-            path
-        } else if backup_path.exists() {
-            backup_path
-        } else {
-            return Ok(None);
-        };
+    /// Load a session from the store
+    fn load(&self, key: &str) -> crate::Result<Option<Session>> {
+        self.store.load(key)
+    }
 
-        let content = std::fs::read_to_string(&path_to_read).map_err(|error| {
-            SessionLoadError::Unreadable {
-                path: path_to_read.clone(),
-                error: error.to_string(),
-            }
-        })?;
-        let mut messages = Vec::new();
-        let mut metadata = serde_json::Value::Object(serde_json::Map::new());
-        let mut created_at = None;
-        let mut updated_at = None;
-        let mut last_consolidated: usize = 0;
-
-        for (line_index, line) in content.lines().enumerate() {
-            let line = line.trim();
-            if line.is_empty() {
-                continue;
-            }
+    /// Save a session to the store
+    pub fn save(&self, session: &Session) -> crate::Result<()> {
+        std::fs::create_dir_all(&self.sessions_dir)?;
+        self.store.save(session)?;
+        super::search::index_in_background(&self.sessions_dir, session);
+        Ok(())
+    }
 
-            let value = serde_json::from_str::<serde_json::Value>(line).map_err(|error| {
-                SessionLoadError::Parse {
-                    path: path_to_read.clone(),
-                    line: line_index + 1,
-                    error: error.to_string(),
-                }
-            })?;
-
-            if value.get("_type").and_then(|v| v.as_str()) == Some("metadata") {
-                metadata = value.get("metadata").cloned().unwrap_or(metadata);
-                created_at = value
-                    .get("created_at")
-                    .and_then(|v| v.as_str())
-                    .and_then(|s| s.parse().ok());
-                updated_at = value
-                    .get("updated_at")
-                    .and_then(|v| v.as_str())
-                    .and_then(|s| s.parse().ok());
-                last_consolidated = value
-                    .get("last_consolidated")
-                    .and_then(|v| v.as_u64())
-                    .unwrap_or(0) as usize;
-            } else {
-                let msg = serde_json::from_value::<super::store::ChatMessage>(value).map_err(
-                    |error| SessionLoadError::Parse {
-                        path: path_to_read.clone(),
-                        line: line_index + 1,
-                        error: error.to_string(),
-                    },
-                )?;
-                messages.push(msg);
-            }
+    /// Rewind a session to its first `message_count` messages and save it.
+    /// The cut must fall on a turn boundary. Returns the dropped messages.
+    pub fn rewind(&mut self, key: &str, message_count: usize) -> crate::Result<Vec<ChatMessage>> {
+        let session = self.load_existing(key)?;
+        if message_count > session.messages.len() {
+            return Err(crate::Error::Validation(format!(
+                "session '{}' has only {} messages",
+                key,
+                session.messages.len()
+            )));
         }
-
-        Ok(Some(Session {
-            key: key.to_string(),
-            messages,
-            created_at: created_at.unwrap_or_else(chrono::Utc::now),
-            updated_at: updated_at.unwrap_or_else(chrono::Utc::now),
-            metadata,
-            last_consolidated,
-        }))
+        if !session.is_turn_boundary(message_count) {
+            return Err(crate::Error::Validation(format!(
+                "message {} is not the start of a turn; rewind to a user message",
+                message_count
+            )));
+        }
+        let removed = session.truncate(message_count);
+        let session = session.clone();
+        self.save(&session)?;
+        Ok(removed)
     }
 
-    /// Save a session to disk
-    pub fn save(&self, session: &Session) -> crate::Result<()> {
-        std::fs::create_dir_all(&self.sessions_dir)?;
-        let path = self.session_path(&session.key);
+    /// Rewind a session to just before the user message at `index`, so it can
+    /// be edited and sent again. Returns the removed user message.
+    pub fn rewind_to_user_message(
+        &mut self,
+        key: &str,
+        index: usize,
+    ) -> crate::Result<ChatMessage> {
+        let session = self.load_existing(key)?;
+        match session.messages.get(index) {
+            Some(message) if message.role == "user" => {}
+            Some(message) => {
+                return Err(crate::Error::Validation(format!(
+                    "message {} is a {} message, not a user message",
+                    index, message.role
+                )))
+            }
+            None => {
+                return Err(crate::Error::Validation(format!(
+                    "session '{}' has no message {}",
+                    key, index
+                )))
+            }
+        }
+        let mut removed = self.rewind(key, index)?;
+        Ok(removed.remove(0))
+    }
 
-        let mut lines = Vec::new();
+    /// Copy the first `message_count` messages of `key` (all of them when
+    /// `None`) into a new session `new_key`, recording the lineage.
+    pub fn fork(
+        &mut self,
+        key: &str,
+        new_key: &str,
+        message_count: Option<usize>,
+    ) -> crate::Result<Session> {
+        if self.get_or_load(new_key)?.is_some() {
+            return Err(crate::Error::Validation(format!(
+                "session '{}' already exists",
+                new_key
+            )));
+        }
+        let parent = self.load_existing(key)?;
+        let message_count = message_count.unwrap_or(parent.messages.len());
+        if message_count > parent.messages.len() || !parent.is_turn_boundary(message_count) {
+            return Err(crate::Error::Validation(format!(
+                "cannot fork session '{}' at message {}; fork at a user message or the end",
+                key, message_count
+            )));
+        }
 
-        // Write metadata
-        let metadata = serde_json::json!({
-            "_type": "metadata",
-            "created_at": session.created_at.to_rfc3339(),
-            "updated_at": session.updated_at.to_rfc3339(),
-            "metadata": session.metadata,
-            "last_consolidated": session.last_consolidated,
+        let mut fork = Session::new(new_key);
+        fork.messages = parent.messages[..message_count].to_vec();
+        fork.metadata = parent.metadata.clone();
+        fork.last_consolidated = parent.last_consolidated.min(message_count);
+        fork.lineage = Some(SessionLineage {
+            parent_key: key.to_string(),
+            forked_at_message: message_count,
+            forked_at: fork.created_at,
         });
-        lines.push(serde_json::to_string(&metadata)?);
+        self.save(&fork)?;
+        self.cache.insert(new_key.to_string(), fork.clone());
+        Ok(fork)
+    }
 
-        // Write messages
-        for msg in &session.messages {
-            lines.push(serde_json::to_string(msg)?);
+    fn load_existing(&mut self, key: &str) -> crate::Result<&mut Session> {
+        if self.get_or_load(key)?.is_none() {
+            return Err(crate::Error::Session(format!(
+                "session '{}' not found",
+                key
+            )));
         }
-
-        self.write_session_atomically(&path, lines.join("\n").as_bytes())?;
-        Ok(())
+        Ok(self.cache.get_mut(key).expect("session was just loaded"))
     }
 
     /// Delete a session
     pub fn delete(&mut self, key: &str) -> crate::Result<bool> {
         self.cache.remove(key);
-
-        let path = self.session_path(key);
-        if path.exists() {
-            std::fs::remove_file(&path)?;
-            Ok(true)
-        } else {
-            Ok(false)
-        }
+        self.store.delete(key)
     }
 
     /// Archive an existing session and clear it from memory, forcing a fresh start
     pub fn archive_and_reset(&mut self, key: &str) -> crate::Result<bool> {
         self.cache.remove(key);
-
-        let path = self.session_path(key);
-        if path.exists() {
-            let safe_key = key.replace([':', '/', '\\'], "_");
-            let timestamp = chrono::Utc::now().timestamp_millis();
-            let archive_filename = format!("{}.reset.{}.jsonl", safe_key, timestamp);
-            let archive_path = self.sessions_dir.join(archive_filename);
-
-            std::fs::rename(&path, &archive_path)?;
-            Ok(true)
-        } else {
-            Ok(false)
-        }
+        self.store.archive(key)
     }
 
     /// List all sessions
     pub fn list_sessions(&self) -> Vec<SessionInfo> {
-        let mut sessions = Vec::new();
-
-        if let Ok(entries) = std::fs::read_dir(&self.sessions_dir) {
-            for entry in entries.flatten() {
-                if let Some(name) = entry.file_name().to_str() {
-                    if name.ends_with(".jsonl") {
-                        let key = name.trim_end_matches(".jsonl").replace('_', ":");
-                        if let Ok(content) = std::fs::read_to_string(entry.path()) {
-                            if let Some(first_line) = content.lines().next() {
-                                if let Ok(value) =
-                                    serde_json::from_str::<serde_json::Value>(first_line)
-                                {
-                                    if value.get("_type").and_then(|v| v.as_str())
-                                        == Some("metadata")
-                                    {
-                                        sessions.push(SessionInfo {
-                                            key,
-                                            created_at: value
-                                                .get("created_at")
-                                                .and_then(|v| v.as_str())
-                                                .map(|s| s.to_string()),
-                                            updated_at: value
-                                                .get("updated_at")
-                                                .and_then(|v| v.as_str())
-                                                .map(|s| s.to_string()),
-                                            path: entry.path().to_string_lossy().to_string(),
-                                        });
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        sessions.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
-        sessions
-    }
-
-    /// Get the file path for a session
-    fn session_path(&self, key: &str) -> PathBuf {
-        let safe_key = key.replace([':', '/', '\\'], "_");
-        self.sessions_dir.join(format!("{}.jsonl", safe_key))
-    }
-
-    fn backup_path(&self, key: &str) -> PathBuf {
-        let safe_key = key.replace([':', '/', '\\'], "_");
-        self.sessions_dir.join(format!("{}.jsonl.bak", safe_key))
-    }
-
-    fn write_session_atomically(&self, path: &Path, content: &[u8]) -> crate::Result<()> {
-        crate::utils::atomic_write(path, content)
+        self.store.list()
     }
 }
 
@@ -265,6 +222,9 @@ pub struct SessionInfo {
     pub updated_at: Option<String>,
     /// File path
     pub path: String,
+    /// Parent session, for forked sessions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lineage: Option<SessionLineage>,
 }
 
 #[cfg(test)]
@@ -314,6 +274,93 @@ mod tests {
         assert_eq!(session.messages[0].content, "Test message");
     }
 
+    fn saved_session(manager: &mut SessionManager, key: &str, turns: &[(&str, &str)]) {
+        let session = manager.get_or_create(key).unwrap();
+        for (user, assistant) in turns {
+            session.add_message("user", *user);
+            session.add_message("assistant", *assistant);
+        }
+        let session = session.clone();
+        manager.save(&session).unwrap();
+    }
+
+    #[test]
+    fn test_rewind_and_edit_user_message() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager = SessionManager::new(temp_dir.path());
+        saved_session(
+            &mut manager,
+            "cli:chat",
+            &[
+                ("hi", "hello"),
+                ("wrong question", "answer"),
+                ("more", "ok"),
+            ],
+        );
+
+        assert!(manager.rewind("cli:chat", 3).is_err());
+        assert!(manager.rewind("cli:chat", 9).is_err());
+        assert!(manager.rewind_to_user_message("cli:chat", 1).is_err());
+        let removed = manager.rewind("cli:chat", 4).unwrap();
+        assert_eq!(removed.len(), 2);
+
+        let edited = manager.rewind_to_user_message("cli:chat", 2).unwrap();
+        assert_eq!(edited.content, "wrong question");
+
+        let reloaded = SessionManager::new(temp_dir.path())
+            .get_or_load("cli:chat")
+            .unwrap()
+            .cloned()
+            .unwrap();
+        assert_eq!(reloaded.messages.len(), 2);
+        assert!(manager.rewind("missing:key", 0).is_err());
+    }
+
+    #[test]
+    fn test_fork_records_lineage() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager = SessionManager::new(temp_dir.path());
+        saved_session(&mut manager, "cli:chat", &[("a", "b"), ("c", "d")]);
+
+        let fork = manager.fork("cli:chat", "cli:chat:alt", Some(2)).unwrap();
+        assert_eq!(fork.messages.len(), 2);
+        assert!(manager.fork("cli:chat", "cli:chat:alt", None).is_err());
+        assert!(manager.fork("cli:chat", "cli:chat:bad", Some(1)).is_err());
+
+        let reloaded = SessionManager::new(temp_dir.path())
+            .get_or_load("cli:chat:alt")
+            .unwrap()
+            .cloned()
+            .unwrap();
+        let lineage = reloaded.lineage.unwrap();
+        assert_eq!(lineage.parent_key, "cli:chat");
+        assert_eq!(lineage.forked_at_message, 2);
+        assert!(SessionManager::new(temp_dir.path())
+            .get_or_load("cli:chat")
+            .unwrap()
+            .unwrap()
+            .lineage
+            .is_none());
+    }
+
+    #[test]
+    fn test_sqlite_backend_selected_by_config() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = AgentSessionsConfig {
+            backend: crate::config::schema::SessionBackend::Sqlite,
+        };
+        let mut manager = SessionManager::open(temp_dir.path(), &config).unwrap();
+        saved_session(&mut manager, "cli:chat", &[("a", "b"), ("c", "d")]);
+        manager.rewind("cli:chat", 2).unwrap();
+
+        assert!(temp_dir.path().join("sessions/sessions.db").exists());
+        assert!(!temp_dir.path().join("sessions/cli_chat.jsonl").exists());
+        let mut reopened = SessionManager::open(temp_dir.path(), &config).unwrap();
+        let session = reopened.get_or_load("cli:chat").unwrap().unwrap();
+        assert_eq!(session.messages.len(), 2);
+        assert_eq!(reopened.list_sessions()[0].key, "cli:chat");
+    }
+
     #[test]
     fn test_archive_and_reset_session() {
         let temp_dir = TempDir::new().unwrap();
@@ -423,7 +470,8 @@ mod tests {
         let key = session.key.clone();
 
         manager.save(manager.cache.get(&key).unwrap()).unwrap();
-        let content = std::fs::read_to_string(manager.session_path(&key)).unwrap();
+        let files = JsonlSessionStore::new(temp_dir.path().join("sessions"));
+        let content = std::fs::read_to_string(files.session_path(&key)).unwrap();
         assert!(content.contains("\"attachments\""));
         assert!(content.contains("\"file_id\":\"sha256:image123\""));
         assert!(content.contains("\"filename\":\"image.png\""));
@@ -453,8 +501,9 @@ mod tests {
         let key = session.key.clone();
         manager.save(manager.cache.get(&key).unwrap()).unwrap();
 
-        let primary_path = manager.session_path(&key);
-        let backup_path = manager.backup_path(&key);
+        let files = JsonlSessionStore::new(temp_dir.path().join("sessions"));
+        let primary_path = files.session_path(&key);
+        let backup_path = files.backup_path(&key);
         std::fs::rename(&primary_path, &backup_path).unwrap();
         manager.cache.clear();
 
@@ -466,12 +515,12 @@ mod tests {
     #[test]
     fn test_get_or_load_reports_parse_errors() {
         let temp_dir = TempDir::new().unwrap();
-        let manager = SessionManager::new(temp_dir.path());
-        let path = manager.session_path("gui:broken");
+        let files = JsonlSessionStore::new(temp_dir.path().join("sessions"));
+        let path = files.session_path("gui:broken");
         std::fs::create_dir_all(path.parent().unwrap()).unwrap();
         std::fs::write(&path, "{not json").unwrap();
 
-        let error = manager.load("gui:broken").unwrap_err();
+        let error = files.read("gui:broken").unwrap_err();
         assert!(matches!(error, SessionLoadError::Parse { .. }));
     }
 }
@@ -1,10 +1,24 @@
 //! Session management for conversation history
 //!
-//! Sessions store conversation history in JSONL format for easy
-//! reading and persistence.
+//! Sessions store conversation history through a [`SessionStore`]: JSONL
+//! files by default, or a single SQLite database.
 
+pub mod backend;
+pub mod export;
+pub mod jsonl;
 pub mod manager;
+pub mod search;
+pub mod sqlite;
 pub mod store;
 
+pub use backend::{open_session_store, SessionStore};
+pub use export::{
+    build_bundle, export_session, import_bundle, redact_session, render_html, render_markdown,
+    BundledAttachment, SessionBundle, SessionExportFormat, SessionExportOptions,
+    SESSION_BUNDLE_VERSION,
+};
+pub use jsonl::JsonlSessionStore;
 pub use manager::{SessionInfo, SessionLoadError, SessionManager};
-pub use store::{ChatMessage, Session};
+pub use search::{parse_date_bound, SessionSearchHit, SessionSearchIndex, SessionSearchQuery};
+pub use sqlite::{SqliteSessionStore, SQLITE_SESSION_FILE};
+pub use store::{ChatMessage, Session, SessionLineage};
@@ -0,0 +1,671 @@
+//! Full-text search over session transcripts
+//!
+//! The index is an SQLite FTS5 table in the sessions directory. `SessionManager::save`
+//! refreshes it in the background, and every search first catches up with the
+//! session store on disk, so sessions written by other processes (or before the index
+//! existed) are still found. Catching up only re-reads transcripts whose size or
+//! modification time changed since the last refresh.
+
+use super::sqlite::{SqliteSessionDb, SQLITE_SESSION_FILE};
+use super::store::{ChatMessage, Session};
+use crate::{Error, Result};
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use sqlx::{Row, SqlitePool};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+/// Index database file inside the sessions directory.
+pub const SESSION_INDEX_FILE: &str = "search.db";
+
+const DEFAULT_LIMIT: usize = 20;
+const MAX_LIMIT: usize = 200;
+/// Tokens of context around each match in a snippet.
+const SNIPPET_TOKENS: i64 = 16;
+
+/// Indexes opened in this process, keyed by sessions directory.
+static SHARED_INDEXES: Lazy<tokio::sync::Mutex<HashMap<PathBuf, Arc<SessionSearchIndex>>>> =
+    Lazy::new(|| tokio::sync::Mutex::new(HashMap::new()));
+
+/// Filters for a transcript search.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionSearchQuery {
+    /// Words that must all appear in the message.
+    pub text: String,
+    /// Only sessions from this channel.
+    #[serde(default)]
+    pub channel: Option<String>,
+    /// Only this session.
+    #[serde(default)]
+    pub session_key: Option<String>,
+    /// Only messages with this role (`user`, `assistant`, `tool`, `system`).
+    #[serde(default)]
+    pub role: Option<String>,
+    /// Only messages sent at or after this time.
+    #[serde(default)]
+    pub from: Option<DateTime<Utc>>,
+    /// Only messages sent at or before this time.
+    #[serde(default)]
+    pub to: Option<DateTime<Utc>>,
+    /// Maximum number of hits (default 20).
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+/// One matching message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionSearchHit {
+    pub session_key: String,
+    /// Position of the message in the session transcript.
+    pub message_index: usize,
+    pub role: String,
+    pub timestamp: DateTime<Utc>,
+    /// Excerpt around the match, with matched terms wrapped in `**`.
+    pub snippet: String,
+}
+
+/// Size and modification time of a transcript file when it was last refreshed.
+type FileStat = (u64, Option<SystemTime>);
+
+/// FTS5 index over the messages of every session in a sessions directory.
+pub struct SessionSearchIndex {
+    sessions_dir: PathBuf,
+    pool: SqlitePool,
+    /// Transcript files seen by `refresh`, keyed by file stem.
+    file_stats: Mutex<HashMap<String, FileStat>>,
+}
+
+impl SessionSearchIndex {
+    /// Open (or create) the index for `sessions_dir`.
+    pub async fn open(sessions_dir: impl AsRef<Path>) -> Result<Self> {
+        let sessions_dir = sessions_dir.as_ref().to_path_buf();
+        tokio::fs::create_dir_all(&sessions_dir).await?;
+        let options = SqliteConnectOptions::new()
+            .filename(sessions_dir.join(SESSION_INDEX_FILE))
+            .create_if_missing(true)
+            .busy_timeout(Duration::from_secs(5));
+        let pool = SqlitePoolOptions::new()
+            .max_connections(2)
+            .connect_with(options)
+            .await
+            .map_err(db_error)?;
+
+        let index = Self {
+            sessions_dir,
+            pool,
+            file_stats: Mutex::new(HashMap::new()),
+        };
+        index.init().await?;
+        Ok(index)
+    }
+
+    /// Open the index for `sessions_dir`, reusing one already opened in this process.
+    pub async fn open_shared(sessions_dir: impl AsRef<Path>) -> Result<Arc<Self>> {
+        let sessions_dir = sessions_dir.as_ref().to_path_buf();
+        let mut indexes = SHARED_INDEXES.lock().await;
+        if let Some(index) = indexes.get(&sessions_dir) {
+            return Ok(index.clone());
+        }
+        let index = Arc::new(Self::open(&sessions_dir).await?);
+        indexes.insert(sessions_dir, index.clone());
+        Ok(index)
+    }
+
+    /// Open the shared index of the sessions stored in `workspace`.
+    pub async fn for_workspace(workspace: impl AsRef<Path>) -> Result<Arc<Self>> {
+        Self::open_shared(workspace.as_ref().join("sessions")).await
+    }
+
+    async fn init(&self) -> Result<()> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS indexed_sessions (
+                file_stem TEXT PRIMARY KEY NOT NULL,
+                session_key TEXT NOT NULL,
+                updated_at INTEGER NOT NULL
+            );
+
+            CREATE VIRTUAL TABLE IF NOT EXISTS session_messages_fts
+                USING fts5(
+                    content,
+                    file_stem UNINDEXED,
+                    session_key UNINDEXED,
+                    channel UNINDEXED,
+                    role UNINDEXED,
+                    message_index UNINDEXED,
+                    timestamp UNINDEXED
+                );
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+        Ok(())
+    }
+
+    /// Replace the indexed messages of `session`, unless the index already
+    /// holds the same or a newer version of it.
+    pub async fn index_session(&self, session: &Session) -> Result<()> {
+        self.index_messages(
+            &session_file_stem(&session.key),
+            &session.key,
+            session.updated_at,
+            &session.messages,
+        )
+        .await
+    }
+
+    async fn index_messages(
+        &self,
+        file_stem: &str,
+        session_key: &str,
+        updated_at: DateTime<Utc>,
+        messages: &[ChatMessage],
+    ) -> Result<()> {
+        let updated_at = updated_at.timestamp_micros();
+        let mut tx = self.pool.begin().await.map_err(db_error)?;
+        // Write first, so the transaction takes the write lock up front
+        // instead of upgrading a read lock under contention.
+        let claimed = sqlx::query(
+            r#"
+            INSERT INTO indexed_sessions (file_stem, session_key, updated_at)
+            VALUES (?, ?, ?)
+            ON CONFLICT(file_stem) DO UPDATE SET
+                session_key = excluded.session_key,
+                updated_at = excluded.updated_at
+            WHERE indexed_sessions.updated_at < excluded.updated_at
+            "#,
+        )
+        .bind(file_stem)
+        .bind(session_key)
+        .bind(updated_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?
+        .rows_affected();
+        if claimed == 0 {
+            return Ok(());
+        }
+
+        sqlx::query("DELETE FROM session_messages_fts WHERE file_stem = ?")
+            .bind(file_stem)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+        let channel = session_channel(session_key);
+        for (index, message) in messages.iter().enumerate() {
+            if message.content.trim().is_empty() {
+                continue;
+            }
+            sqlx::query(
+                r#"
+                INSERT INTO session_messages_fts
+                    (content, file_stem, session_key, channel, role, message_index, timestamp)
+                VALUES (?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(&message.content)
+            .bind(file_stem)
+            .bind(session_key)
+            .bind(channel)
+            .bind(&message.role)
+            .bind(index as i64)
+            .bind(message.timestamp.timestamp_micros())
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+        }
+        tx.commit().await.map_err(db_error)?;
+        Ok(())
+    }
+
+    /// Bring the index in line with the sessions on disk, whether JSONL files
+    /// or the SQLite session store: index new or changed transcripts and drop
+    /// deleted ones. JSONL files with the same size and modification time as
+    /// on the previous refresh are not read again. Returns the number of
+    /// sessions reindexed.
+    pub async fn refresh(&self) -> Result<usize> {
+        let indexed: HashMap<String, (String, i64)> =
+            sqlx::query("SELECT file_stem, session_key, updated_at FROM indexed_sessions")
+                .fetch_all(&self.pool)
+                .await
+                .map_err(db_error)?
+                .iter()
+                .map(|row| {
+                    (
+                        row.get::<String, _>("file_stem"),
+                        (row.get("session_key"), row.get("updated_at")),
+                    )
+                })
+                .collect();
+
+        let mut on_disk = HashSet::new();
+        let mut reindexed = 0;
+        let mut entries = match tokio::fs::read_dir(&self.sessions_dir).await {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(err) => return Err(err.into()),
+        };
+        while let Some(entry) = entries.next_entry().await? {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let Some(file_stem) = name.strip_suffix(".jsonl") else {
+                continue;
+            };
+            on_disk.insert(file_stem.to_string());
+            let stat = match entry.metadata().await {
+                Ok(metadata) => Some((metadata.len(), metadata.modified().ok())),
+                Err(_) => None,
+            };
+            if stat.is_some()
+                && indexed.contains_key(file_stem)
+                && self.file_stat(file_stem) == stat
+            {
+                continue;
+            }
+            let content = match tokio::fs::read_to_string(entry.path()).await {
+                Ok(content) => content,
+                Err(err) => {
+                    tracing::warn!("Skipping unreadable session file {}: {}", name, err);
+                    continue;
+                }
+            };
+            let Some((updated_at, messages)) = parse_transcript(&content) else {
+                continue;
+            };
+            let existing = indexed.get(file_stem);
+            if existing.map_or(true, |(_, indexed_at)| {
+                *indexed_at < updated_at.timestamp_micros()
+            }) {
+                // Keep the exact key recorded at save time; file names lose `:`.
+                let session_key = existing
+                    .map(|(key, _)| key.clone())
+                    .unwrap_or_else(|| file_stem.replace('_', ":"));
+                self.index_messages(file_stem, &session_key, updated_at, &messages)
+                    .await?;
+                reindexed += 1;
+            }
+            if let Some(stat) = stat {
+                self.set_file_stat(file_stem, stat);
+            }
+        }
+        if let Ok(mut stats) = self.file_stats.lock() {
+            stats.retain(|stem, _| on_disk.contains(stem));
+        }
+
+        if self.sessions_dir.join(SQLITE_SESSION_FILE).exists() {
+            let db = SqliteSessionDb::open(&self.sessions_dir).await?;
+            for info in db.list().await? {
+                let file_stem = session_file_stem(&info.key);
+                on_disk.insert(file_stem.clone());
+                let updated_at = info
+                    .updated_at
+                    .as_deref()
+                    .and_then(|s| s.parse::<DateTime<Utc>>().ok());
+                if let (Some(updated_at), Some((_, indexed_at))) =
+                    (updated_at, indexed.get(&file_stem))
+                {
+                    if *indexed_at >= updated_at.timestamp_micros() {
+                        continue;
+                    }
+                }
+                let Some(session) = db.load(&info.key).await? else {
+                    continue;
+                };
+                self.index_messages(
+                    &file_stem,
+                    &session.key,
+                    session.updated_at,
+                    &session.messages,
+                )
+                .await?;
+                reindexed += 1;
+            }
+        }
+
+        for file_stem in indexed.keys().filter(|stem| !on_disk.contains(*stem)) {
+            sqlx::query("DELETE FROM session_messages_fts WHERE file_stem = ?")
+                .bind(file_stem)
+                .execute(&self.pool)
+                .await
+                .map_err(db_error)?;
+            sqlx::query("DELETE FROM indexed_sessions WHERE file_stem = ?")
+                .bind(file_stem)
+                .execute(&self.pool)
+                .await
+                .map_err(db_error)?;
+        }
+        Ok(reindexed)
+    }
+
+    fn file_stat(&self, file_stem: &str) -> Option<FileStat> {
+        self.file_stats
+            .lock()
+            .ok()
+            .and_then(|stats| stats.get(file_stem).copied())
+    }
+
+    fn set_file_stat(&self, file_stem: &str, stat: FileStat) {
+        if let Ok(mut stats) = self.file_stats.lock() {
+            stats.insert(file_stem.to_string(), stat);
+        }
+    }
+
+    /// Refresh the index, then return the best matches for `query`.
+    pub async fn search(&self, query: &SessionSearchQuery) -> Result<Vec<SessionSearchHit>> {
+        let Some(fts_query) = fts_query(&query.text) else {
+            return Err(Error::Validation(
+                "search text must contain at least one word".to_string(),
+            ));
+        };
+        self.refresh().await?;
+
+        let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+        let rows = sqlx::query(
+            r#"
+            SELECT session_key, message_index, role, timestamp,
+                   snippet(session_messages_fts, 0, '**', '**', '…', ?) AS snippet
+            FROM session_messages_fts
+            WHERE session_messages_fts MATCH ?
+              AND (? IS NULL OR channel = ?)
+              AND (? IS NULL OR session_key = ?)
+              AND (? IS NULL OR role = ?)
+              AND (? IS NULL OR timestamp >= ?)
+              AND (? IS NULL OR timestamp <= ?)
+            ORDER BY bm25(session_messages_fts), timestamp DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(SNIPPET_TOKENS)
+        .bind(fts_query)
+        .bind(&query.channel)
+        .bind(&query.channel)
+        .bind(&query.session_key)
+        .bind(&query.session_key)
+        .bind(&query.role)
+        .bind(&query.role)
+        .bind(query.from.map(|ts| ts.timestamp_micros()))
+        .bind(query.from.map(|ts| ts.timestamp_micros()))
+        .bind(query.to.map(|ts| ts.timestamp_micros()))
+        .bind(query.to.map(|ts| ts.timestamp_micros()))
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.iter()
+            .map(|row| {
+                let micros: i64 = row.get("timestamp");
+                Ok(SessionSearchHit {
+                    session_key: row.get("session_key"),
+                    message_index: row.get::<i64, _>("message_index") as usize,
+                    role: row.get("role"),
+                    timestamp: DateTime::from_timestamp_micros(micros).ok_or_else(|| {
+                        Error::Internal(format!("invalid indexed timestamp {micros}"))
+                    })?,
+                    snippet: row.get("snippet"),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Queue `session` for indexing when called inside a Tokio runtime. Failures
+/// only cost freshness: the next search catches up from disk.
+pub(crate) fn index_in_background(sessions_dir: &Path, session: &Session) {
+    let Ok(handle) = tokio::runtime::Handle::try_current() else {
+        return;
+    };
+    let sessions_dir = sessions_dir.to_path_buf();
+    let session = session.clone();
+    handle.spawn(async move {
+        let result = match SessionSearchIndex::open_shared(&sessions_dir).await {
+            Ok(index) => index.index_session(&session).await,
+            Err(err) => Err(err),
+        };
+        if let Err(err) = result {
+            tracing::debug!(
+                "Session search index update failed for {}: {}",
+                session.key,
+                err
+            );
+        }
+    });
+}
+
+/// Parse a date range bound given as RFC 3339 or `YYYY-MM-DD` (UTC). A bare
+/// date covers the whole day: its start for `from`, its end for `to`.
+pub fn parse_date_bound(value: &str, end_of_day: bool) -> Result<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(ts) = DateTime::parse_from_rfc3339(value) {
+        return Ok(ts.with_timezone(&Utc));
+    }
+    let date = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| {
+        Error::Validation(format!(
+            "invalid date '{value}'; expected YYYY-MM-DD or an RFC 3339 timestamp"
+        ))
+    })?;
+    let time = if end_of_day {
+        date.and_hms_micro_opt(23, 59, 59, 999_999)
+    } else {
+        date.and_hms_opt(0, 0, 0)
+    };
+    time.map(|time| time.and_utc())
+        .ok_or_else(|| Error::Validation(format!("invalid date '{value}'")))
+}
+
+/// File name (without extension) a session key is stored under.
+pub(crate) fn session_file_stem(key: &str) -> String {
+    key.replace([':', '/', '\\'], "_")
+}
+
+fn session_channel(session_key: &str) -> &str {
+    session_key
+        .split_once(':')
+        .map(|(channel, _)| channel)
+        .unwrap_or(session_key)
+}
+
+/// Parse a JSONL transcript leniently: unreadable lines are skipped rather
+/// than failing the whole index refresh.
+fn parse_transcript(content: &str) -> Option<(DateTime<Utc>, Vec<ChatMessage>)> {
+    let mut updated_at = None;
+    let mut messages = Vec::new();
+    for line in content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+    {
+        let Ok(value) = serde_json::from_str::<serde_json::Value>(line) else {
+            continue;
+        };
+        if value.get("_type").and_then(|v| v.as_str()) == Some("metadata") {
+            updated_at = value
+                .get("updated_at")
+                .and_then(|v| v.as_str())
+                .and_then(|s| s.parse::<DateTime<Utc>>().ok());
+        } else if let Ok(message) = serde_json::from_value::<ChatMessage>(value) {
+            messages.push(message);
+        }
+    }
+    updated_at.map(|updated_at| (updated_at, messages))
+}
+
+/// All words of `text` as quoted FTS5 terms, so every word must match.
+fn fts_query(text: &str) -> Option<String> {
+    let terms: Vec<String> = text
+        .split(|ch: char| !ch.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(|term| format!("\"{}\"", term.to_lowercase()))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+fn db_error(err: sqlx::Error) -> Error {
+    Error::Internal(format!("session search index: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::SessionManager;
+    use tempfile::TempDir;
+
+    fn save_session(manager: &SessionManager, key: &str, messages: &[(&str, &str)]) -> Session {
+        let mut session = Session::new(key);
+        for (role, content) in messages {
+            session.add_message(*role, *content);
+        }
+        manager.save(&session).unwrap();
+        session
+    }
+
+    #[tokio::test]
+    async fn search_filters_by_channel_and_role() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = SessionManager::new(temp_dir.path());
+        let index = SessionSearchIndex::open(temp_dir.path().join("sessions"))
+            .await
+            .unwrap();
+        let session = save_session(
+            &manager,
+            "telegram:42",
+            &[
+                ("user", "Can we plan the Q3 migration?"),
+                (
+                    "assistant",
+                    "Sure, the Q3 migration starts with the database.",
+                ),
+            ],
+        );
+        index.index_session(&session).await.unwrap();
+        save_session(
+            &manager,
+            "discord:7",
+            &[("user", "The Q3 migration slipped a week")],
+        );
+
+        let all = index
+            .search(&SessionSearchQuery {
+                text: "q3 migration".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(all.len(), 3);
+
+        let hits = index
+            .search(&SessionSearchQuery {
+                text: "migration".to_string(),
+                channel: Some("telegram".to_string()),
+                role: Some("assistant".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].session_key, "telegram:42");
+        assert_eq!(hits[0].message_index, 1);
+        assert!(hits[0].snippet.contains("**migration**"));
+
+        let future = index
+            .search(&SessionSearchQuery {
+                text: "migration".to_string(),
+                from: Some(Utc::now() + chrono::Duration::days(1)),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert!(future.is_empty());
+    }
+
+    #[test]
+    fn date_bounds_cover_whole_days() {
+        let from = parse_date_bound("2026-03-01", false).unwrap();
+        let to = parse_date_bound("2026-03-01", true).unwrap();
+        assert_eq!(from.to_rfc3339(), "2026-03-01T00:00:00+00:00");
+        assert!(to > from && to < from + chrono::Duration::days(1));
+        assert!(parse_date_bound("March 1st", false).is_err());
+    }
+
+    #[tokio::test]
+    async fn refresh_picks_up_saved_and_deleted_sessions() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager = SessionManager::new(temp_dir.path());
+        let session = manager.get_or_create("cli:main").unwrap();
+        session.add_message("user", "remember the lighthouse photo");
+        let session = session.clone();
+        manager.save(&session).unwrap();
+
+        let index = SessionSearchIndex::open(temp_dir.path().join("sessions"))
+            .await
+            .unwrap();
+        let query = SessionSearchQuery {
+            text: "lighthouse".to_string(),
+            ..Default::default()
+        };
+        let hits = index.search(&query).await.unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].session_key, "cli:main");
+
+        let scoped = index
+            .search(&SessionSearchQuery {
+                session_key: Some("cli:other".to_string()),
+                ..query.clone()
+            })
+            .await
+            .unwrap();
+        assert!(scoped.is_empty());
+
+        manager.delete("cli:main").unwrap();
+        assert!(index.search(&query).await.unwrap().is_empty());
+        assert!(index
+            .search(&SessionSearchQuery {
+                text: " ?! ".to_string(),
+                ..Default::default()
+            })
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn refresh_skips_files_with_unchanged_size_and_mtime() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = SessionManager::new(temp_dir.path());
+        save_session(&manager, "cli:main", &[("user", "the lighthouse photo")]);
+        let index = SessionSearchIndex::open(temp_dir.path().join("sessions"))
+            .await
+            .unwrap();
+        assert_eq!(index.refresh().await.unwrap(), 1);
+
+        // Same length, newer `updated_at`, but the original mtime.
+        let path = temp_dir.path().join("sessions").join("cli_main.jsonl");
+        let modified = std::fs::metadata(&path).unwrap().modified().unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        let stamp_at = content.find("\"updated_at\":\"").unwrap() + "\"updated_at\":\"".len();
+        let edited = format!(
+            "{}2099{}",
+            &content[..stamp_at],
+            &content[stamp_at + 4..].replace("lighthouse", "harbourage")
+        );
+        std::fs::write(&path, &edited).unwrap();
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(modified).unwrap();
+        assert_eq!(index.refresh().await.unwrap(), 0);
+
+        file.set_modified(modified + Duration::from_secs(1))
+            .unwrap();
+        assert_eq!(index.refresh().await.unwrap(), 1);
+        let hits = index
+            .search(&SessionSearchQuery {
+                text: "harbourage".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+    }
+}
@@ -0,0 +1,472 @@
+//! SQLite session storage
+//!
+//! All sessions of a workspace live in one database next to the JSONL files.
+//! Saves append only the messages that are new since the last save, so long
+//! sessions stay cheap to persist, and SQLite's locking lets the gateway and
+//! the GUI share the store safely.
+
+use super::backend::SessionStore;
+use super::manager::SessionInfo;
+use super::store::{ChatMessage, Session, SessionLineage};
+use crate::{Error, Result};
+use chrono::{DateTime, Utc};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions};
+use sqlx::{Row, SqlitePool};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// Session database file inside the sessions directory.
+pub const SQLITE_SESSION_FILE: &str = "sessions.db";
+
+type Job =
+    Box<dyn for<'a> FnOnce(&'a SqliteSessionDb) -> Pin<Box<dyn Future<Output = ()> + 'a>> + Send>;
+
+/// [`SessionStore`] backed by SQLite.
+///
+/// The connection pool lives on a dedicated thread with its own runtime, so
+/// the synchronous store API can be called from inside or outside Tokio.
+#[derive(Debug)]
+pub struct SqliteSessionStore {
+    path: PathBuf,
+    jobs: mpsc::Sender<Job>,
+}
+
+impl SqliteSessionStore {
+    /// Open (or create) `sessions.db` in `sessions_dir`.
+    pub fn open(sessions_dir: &Path) -> Result<Self> {
+        let sessions_dir = sessions_dir.to_path_buf();
+        let path = sessions_dir.join(SQLITE_SESSION_FILE);
+        let (jobs_tx, jobs_rx) = mpsc::channel::<Job>();
+        let (ready_tx, ready_rx) = mpsc::channel::<Result<()>>();
+
+        std::thread::Builder::new()
+            .name("session-store".to_string())
+            .spawn(move || {
+                let runtime = match tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                {
+                    Ok(runtime) => runtime,
+                    Err(err) => {
+                        let _ = ready_tx.send(Err(err.into()));
+                        return;
+                    }
+                };
+                let db = match runtime.block_on(SqliteSessionDb::open(&sessions_dir)) {
+                    Ok(db) => db,
+                    Err(err) => {
+                        let _ = ready_tx.send(Err(err));
+                        return;
+                    }
+                };
+                let _ = ready_tx.send(Ok(()));
+                while let Ok(job) = jobs_rx.recv() {
+                    runtime.block_on(job(&db));
+                }
+                runtime.block_on(db.pool.close());
+            })?;
+
+        ready_rx.recv().map_err(|_| worker_gone())??;
+        Ok(Self {
+            path,
+            jobs: jobs_tx,
+        })
+    }
+
+    /// Path of the database file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn call<T, F>(&self, job: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: for<'a> FnOnce(&'a SqliteSessionDb) -> Pin<Box<dyn Future<Output = Result<T>> + 'a>>
+            + Send
+            + 'static,
+    {
+        let (reply_tx, reply_rx) = mpsc::channel();
+        let job: Job = Box::new(move |db: &SqliteSessionDb| {
+            Box::pin(async move {
+                let _ = reply_tx.send(job(db).await);
+            })
+        });
+        self.jobs.send(job).map_err(|_| worker_gone())?;
+        reply_rx.recv().map_err(|_| worker_gone())?
+    }
+}
+
+impl SessionStore for SqliteSessionStore {
+    fn load(&self, key: &str) -> Result<Option<Session>> {
+        let key = key.to_string();
+        self.call(move |db| Box::pin(async move { db.load(&key).await }))
+    }
+
+    fn save(&self, session: &Session) -> Result<()> {
+        let session = session.clone();
+        self.call(move |db| Box::pin(async move { db.save(&session).await }))
+    }
+
+    fn delete(&self, key: &str) -> Result<bool> {
+        let key = key.to_string();
+        self.call(move |db| Box::pin(async move { db.delete(&key).await }))
+    }
+
+    fn archive(&self, key: &str) -> Result<bool> {
+        let key = key.to_string();
+        self.call(move |db| Box::pin(async move { db.archive(&key).await }))
+    }
+
+    fn list(&self) -> Vec<SessionInfo> {
+        self.call(|db| Box::pin(db.list())).unwrap_or_else(|err| {
+            tracing::warn!(
+                "Failed to list sessions in {}: {}",
+                self.path.display(),
+                err
+            );
+            Vec::new()
+        })
+    }
+}
+
+/// Async access to the session database.
+pub(crate) struct SqliteSessionDb {
+    path: PathBuf,
+    pool: SqlitePool,
+}
+
+impl SqliteSessionDb {
+    pub(crate) async fn open(sessions_dir: &Path) -> Result<Self> {
+        tokio::fs::create_dir_all(sessions_dir).await?;
+        let path = sessions_dir.join(SQLITE_SESSION_FILE);
+        let options = SqliteConnectOptions::new()
+            .filename(&path)
+            .create_if_missing(true)
+            .journal_mode(SqliteJournalMode::Wal)
+            .busy_timeout(Duration::from_secs(5));
+        let pool = SqlitePoolOptions::new()
+            .max_connections(2)
+            .connect_with(options)
+            .await
+            .map_err(db_error)?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS sessions (
+                key TEXT PRIMARY KEY NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                metadata TEXT NOT NULL,
+                last_consolidated INTEGER NOT NULL DEFAULT 0,
+                lineage TEXT
+            );
+
+            CREATE TABLE IF NOT EXISTS session_messages (
+                session_key TEXT NOT NULL,
+                idx INTEGER NOT NULL,
+                message TEXT NOT NULL,
+                PRIMARY KEY (session_key, idx)
+            );
+
+            CREATE TABLE IF NOT EXISTS session_archives (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                session_key TEXT NOT NULL,
+                archived_at TEXT NOT NULL,
+                session TEXT NOT NULL
+            );
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(Self { path, pool })
+    }
+
+    pub(crate) async fn load(&self, key: &str) -> Result<Option<Session>> {
+        let Some(row) = sqlx::query(
+            "SELECT created_at, updated_at, metadata, last_consolidated, lineage \
+             FROM sessions WHERE key = ?",
+        )
+        .bind(key)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?
+        else {
+            return Ok(None);
+        };
+
+        let messages = sqlx::query(
+            "SELECT idx, message FROM session_messages WHERE session_key = ? ORDER BY idx",
+        )
+        .bind(key)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?
+        .iter()
+        .map(|row| {
+            serde_json::from_str::<ChatMessage>(row.get("message")).map_err(|err| {
+                Error::Serialization(format!(
+                    "session '{}' message {}: {}",
+                    key,
+                    row.get::<i64, _>("idx"),
+                    err
+                ))
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+        Ok(Some(Session {
+            key: key.to_string(),
+            messages,
+            created_at: parse_timestamp(row.get("created_at")),
+            updated_at: parse_timestamp(row.get("updated_at")),
+            metadata: serde_json::from_str(row.get("metadata"))?,
+            last_consolidated: row.get::<i64, _>("last_consolidated").max(0) as usize,
+            lineage: row
+                .get::<Option<String>, _>("lineage")
+                .and_then(|value| serde_json::from_str::<SessionLineage>(&value).ok()),
+        }))
+    }
+
+    /// Upsert the session row and append the messages added since the last
+    /// save. Rewound sessions drop their extra rows; if the stored tail no
+    /// longer matches, the messages are rewritten.
+    pub(crate) async fn save(&self, session: &Session) -> Result<()> {
+        let lineage = session
+            .lineage
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()?;
+        let mut tx = self.pool.begin().await.map_err(db_error)?;
+        // Write first, so the transaction takes the write lock up front.
+        sqlx::query(
+            r#"
+            INSERT INTO sessions (key, created_at, updated_at, metadata, last_consolidated, lineage)
+            VALUES (?, ?, ?, ?, ?, ?)
+            ON CONFLICT(key) DO UPDATE SET
+                created_at = excluded.created_at,
+                updated_at = excluded.updated_at,
+                metadata = excluded.metadata,
+                last_consolidated = excluded.last_consolidated,
+                lineage = excluded.lineage
+            "#,
+        )
+        .bind(&session.key)
+        .bind(session.created_at.to_rfc3339())
+        .bind(session.updated_at.to_rfc3339())
+        .bind(serde_json::to_string(&session.metadata)?)
+        .bind(session.last_consolidated as i64)
+        .bind(lineage)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?;
+
+        let stored: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM session_messages WHERE session_key = ?")
+                .bind(&session.key)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(db_error)?;
+        let mut kept = (stored.max(0) as usize).min(session.messages.len());
+        if stored as usize > kept {
+            sqlx::query("DELETE FROM session_messages WHERE session_key = ? AND idx >= ?")
+                .bind(&session.key)
+                .bind(kept as i64)
+                .execute(&mut *tx)
+                .await
+                .map_err(db_error)?;
+        }
+        if kept > 0 {
+            let last: String = sqlx::query_scalar(
+                "SELECT message FROM session_messages WHERE session_key = ? AND idx = ?",
+            )
+            .bind(&session.key)
+            .bind(kept as i64 - 1)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(db_error)?;
+            if last != serde_json::to_string(&session.messages[kept - 1])? {
+                sqlx::query("DELETE FROM session_messages WHERE session_key = ?")
+                    .bind(&session.key)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(db_error)?;
+                kept = 0;
+            }
+        }
+
+        for (idx, message) in session.messages.iter().enumerate().skip(kept) {
+            sqlx::query(
+                "INSERT INTO session_messages (session_key, idx, message) VALUES (?, ?, ?)",
+            )
+            .bind(&session.key)
+            .bind(idx as i64)
+            .bind(serde_json::to_string(message)?)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+        }
+        tx.commit().await.map_err(db_error)?;
+        Ok(())
+    }
+
+    pub(crate) async fn delete(&self, key: &str) -> Result<bool> {
+        let mut tx = self.pool.begin().await.map_err(db_error)?;
+        let deleted = sqlx::query("DELETE FROM sessions WHERE key = ?")
+            .bind(key)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?
+            .rows_affected();
+        sqlx::query("DELETE FROM session_messages WHERE session_key = ?")
+            .bind(key)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+        tx.commit().await.map_err(db_error)?;
+        Ok(deleted > 0)
+    }
+
+    /// Copy the session into `session_archives`, then remove it.
+    pub(crate) async fn archive(&self, key: &str) -> Result<bool> {
+        let Some(session) = self.load(key).await? else {
+            return Ok(false);
+        };
+        let snapshot = serde_json::to_string(&session)?;
+        let mut tx = self.pool.begin().await.map_err(db_error)?;
+        sqlx::query(
+            "INSERT INTO session_archives (session_key, archived_at, session) VALUES (?, ?, ?)",
+        )
+        .bind(key)
+        .bind(Utc::now().to_rfc3339())
+        .bind(snapshot)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        sqlx::query("DELETE FROM sessions WHERE key = ?")
+            .bind(key)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+        sqlx::query("DELETE FROM session_messages WHERE session_key = ?")
+            .bind(key)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+        tx.commit().await.map_err(db_error)?;
+        Ok(true)
+    }
+
+    pub(crate) async fn list(&self) -> Result<Vec<SessionInfo>> {
+        let path = self.path.to_string_lossy().to_string();
+        Ok(sqlx::query(
+            "SELECT key, created_at, updated_at, lineage FROM sessions ORDER BY updated_at DESC",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?
+        .iter()
+        .map(|row| SessionInfo {
+            key: row.get("key"),
+            created_at: Some(row.get("created_at")),
+            updated_at: Some(row.get("updated_at")),
+            path: path.clone(),
+            lineage: row
+                .get::<Option<String>, _>("lineage")
+                .and_then(|value| serde_json::from_str(&value).ok()),
+        })
+        .collect())
+    }
+}
+
+fn parse_timestamp(value: &str) -> DateTime<Utc> {
+    value.parse().unwrap_or_else(|_| Utc::now())
+}
+
+fn worker_gone() -> Error {
+    Error::Internal("session store worker has stopped".to_string())
+}
+
+fn db_error(err: sqlx::Error) -> Error {
+    Error::Session(format!("session database: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn message_rows(store: &SqliteSessionStore, key: &str) -> i64 {
+        let key = key.to_string();
+        store
+            .call(move |db| {
+                Box::pin(async move {
+                    sqlx::query_scalar(
+                        "SELECT COUNT(*) FROM session_messages WHERE session_key = ?",
+                    )
+                    .bind(key)
+                    .fetch_one(&db.pool)
+                    .await
+                    .map_err(db_error)
+                })
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_save_appends_and_handles_rewind() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = SqliteSessionStore::open(temp_dir.path()).unwrap();
+
+        let mut session = Session::new("telegram:42");
+        session.add_message("user", "hello");
+        session.add_message("assistant", "hi");
+        store.save(&session).unwrap();
+        session.add_message("user", "again");
+        session.last_consolidated = 2;
+        store.save(&session).unwrap();
+        assert_eq!(message_rows(&store, "telegram:42"), 3);
+
+        let loaded = store.load("telegram:42").unwrap().unwrap();
+        assert_eq!(loaded.messages.len(), 3);
+        assert_eq!(loaded.messages[2].content, "again");
+        assert_eq!(loaded.last_consolidated, 2);
+
+        session.truncate(2);
+        session.add_message("user", "edited");
+        store.save(&session).unwrap();
+        let loaded = store.load("telegram:42").unwrap().unwrap();
+        assert_eq!(loaded.messages.len(), 3);
+        assert_eq!(loaded.messages[2].content, "edited");
+        assert_eq!(store.list().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_archive_moves_session_out_of_list() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = SqliteSessionStore::open(temp_dir.path()).unwrap();
+        let mut session = Session::new("gui:archive");
+        session.add_message("user", "keep me");
+        store.save(&session).unwrap();
+
+        assert!(store.archive("gui:archive").unwrap());
+        assert!(!store.archive("gui:archive").unwrap());
+        assert!(store.load("gui:archive").unwrap().is_none());
+        assert!(store.list().is_empty());
+        let archived: i64 = store
+            .call(|db| {
+                Box::pin(async move {
+                    sqlx::query_scalar("SELECT COUNT(*) FROM session_archives")
+                        .fetch_one(&db.pool)
+                        .await
+                        .map_err(db_error)
+                })
+            })
+            .unwrap();
+        assert_eq!(archived, 1);
+    }
+}
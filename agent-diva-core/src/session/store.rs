@@ -20,6 +20,20 @@ pub struct Session {
     /// Index of last consolidated message (for memory consolidation)
     #[serde(default)]
     pub last_consolidated: usize,
+    /// Session this one was forked from, if it is a branch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lineage: Option<SessionLineage>,
+}
+
+/// Where a forked session branched off
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionLineage {
+    /// Key of the session this one was forked from
+    pub parent_key: String,
+    /// Number of parent messages copied into the fork
+    pub forked_at_message: usize,
+    /// When the fork was created
+    pub forked_at: DateTime<Utc>,
 }
 
 impl Session {
@@ -33,6 +47,7 @@ impl Session {
             updated_at: now,
             metadata: serde_json::Value::Object(serde_json::Map::new()),
             last_consolidated: 0,
+            lineage: None,
         }
     }
 
@@ -60,8 +75,14 @@ impl Session {
 
     /// Get message history for LLM context
     pub fn get_history(&self, max_messages: usize) -> Vec<ChatMessage> {
+        self.get_history_from(0, max_messages)
+    }
+
+    /// Get message history for LLM context, skipping messages before `start`
+    /// (e.g. messages already folded into a context summary)
+    pub fn get_history_from(&self, start: usize, max_messages: usize) -> Vec<ChatMessage> {
         // Clamp last_consolidated to avoid out-of-bounds on corrupted data
-        let consolidated = self.last_consolidated.min(self.messages.len());
+        let consolidated = self.last_consolidated.max(start).min(self.messages.len());
         let unconsolidated = &self.messages[consolidated..];
         let start = unconsolidated.len().saturating_sub(max_messages);
         let mut sliced: Vec<ChatMessage> = unconsolidated[start..]
@@ -76,6 +97,27 @@ impl Session {
         sliced
     }
 
+    /// Whether the first `message_count` messages form whole turns, i.e. the
+    /// cut does not separate a tool call from its result
+    pub fn is_turn_boundary(&self, message_count: usize) -> bool {
+        message_count == self.messages.len()
+            || self
+                .messages
+                .get(message_count)
+                .is_some_and(|m| m.role == "user")
+    }
+
+    /// Drop every message from `message_count` on and return the dropped messages
+    pub fn truncate(&mut self, message_count: usize) -> Vec<ChatMessage> {
+        if message_count >= self.messages.len() {
+            return Vec::new();
+        }
+        let removed = self.messages.split_off(message_count);
+        self.last_consolidated = self.last_consolidated.min(message_count);
+        self.updated_at = Utc::now();
+        removed
+    }
+
     /// Clear all messages
     pub fn clear(&mut self) {
         self.messages.clear();
@@ -183,6 +225,25 @@ mod tests {
         assert!(session.messages.is_empty());
     }
 
+    #[test]
+    fn test_truncate_keeps_prefix_and_clamps_consolidation() {
+        let mut session = Session::new("test");
+        session.add_message("user", "one");
+        session.add_message("assistant", "two");
+        session.add_message("user", "three");
+        session.add_message("assistant", "four");
+        session.last_consolidated = 3;
+
+        assert!(session.is_turn_boundary(2));
+        assert!(!session.is_turn_boundary(1));
+        let removed = session.truncate(2);
+        assert_eq!(removed.len(), 2);
+        assert_eq!(removed[0].content, "three");
+        assert_eq!(session.messages.len(), 2);
+        assert_eq!(session.last_consolidated, 2);
+        assert!(session.is_turn_boundary(2));
+    }
+
     #[test]
     fn test_add_message() {
         let mut session = Session::new("test");
@@ -205,6 +266,23 @@ mod tests {
         assert_eq!(history.len(), 50);
     }
 
+    #[test]
+    fn test_get_history_from_skips_leading_messages() {
+        let mut session = Session::new("test");
+        for i in 0..10 {
+            session.add_message("user", format!("Message {}", i));
+            session.add_message("assistant", format!("Reply {}", i));
+        }
+
+        let history = session.get_history_from(6, 200);
+        assert_eq!(history.len(), 14);
+        assert_eq!(history[0].content, "Message 3");
+
+        session.last_consolidated = 10;
+        let history = session.get_history_from(6, 200);
+        assert_eq!(history[0].content, "Message 5");
+    }
+
     #[test]
     fn test_chat_message_deserializes_old_json_without_attachments() {
         let json = r#"{
@@ -132,6 +132,8 @@ pub struct McpServerDto {
     pub args: Vec<String>,
     pub env: std::collections::HashMap<String, String>,
     pub url: String,
+    #[serde(default)]
+    pub headers: std::collections::HashMap<String, String>,
     pub tool_timeout: u64,
     pub status: McpConnectionStatusDto,
 }
@@ -140,10 +142,14 @@ pub struct McpServerDto {
 pub struct McpServerPayload {
     pub name: String,
     pub enabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transport: Option<String>,
     pub command: String,
     pub args: Vec<String>,
     pub env: std::collections::HashMap<String, String>,
     pub url: String,
+    #[serde(default)]
+    pub headers: std::collections::HashMap<String, String>,
     pub tool_timeout: u64,
 }
 
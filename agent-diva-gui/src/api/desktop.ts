@@ -41,11 +41,12 @@ export interface McpConnectionStatusDto {
 export interface McpServerDto {
   name: string;
   enabled: boolean;
-  transport: 'stdio' | 'http' | 'invalid' | string;
+  transport: 'stdio' | 'sse' | 'streamable_http' | 'invalid' | string;
   command: string;
   args: string[];
   env: Record<string, string>;
   url: string;
+  headers: Record<string, string>;
   tool_timeout: number;
   status: McpConnectionStatusDto;
 }
@@ -53,10 +54,12 @@ export interface McpServerDto {
 export interface McpServerPayload {
   name: string;
   enabled: boolean;
+  transport?: 'stdio' | 'sse' | 'streamable_http';
   command: string;
   args: string[];
   env: Record<string, string>;
   url: string;
+  headers: Record<string, string>;
   tool_timeout: number;
 }
 
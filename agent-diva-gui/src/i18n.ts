@@ -94,7 +94,8 @@ zhPatched.mcp = {
   name: zhPatched.mcp?.name || '名称',
   transport: zhPatched.mcp?.transport || '连接方式',
   transportStdio: zhPatched.mcp?.transportStdio || 'Stdio',
-  transportHttp: zhPatched.mcp?.transportHttp || 'HTTP',
+  transportHttp: zhPatched.mcp?.transportHttp || 'HTTP (SSE)',
+  transportStreamableHttp: zhPatched.mcp?.transportStreamableHttp || 'Streamable HTTP',
   command: zhPatched.mcp?.command || '命令',
   args: zhPatched.mcp?.args || '参数',
   url: zhPatched.mcp?.url || 'URL',
@@ -129,6 +130,10 @@ zhPatched.mcp = {
   envValuePlaceholder: zhPatched.mcp?.envValuePlaceholder || '变量值',
   addArg: zhPatched.mcp?.addArg || '添加参数',
   addEnv: zhPatched.mcp?.addEnv || '添加 env',
+  headers: zhPatched.mcp?.headers || '请求头',
+  addHeader: zhPatched.mcp?.addHeader || '添加请求头',
+  headerKeyPlaceholder: zhPatched.mcp?.headerKeyPlaceholder || '请求头名称',
+  headerValuePlaceholder: zhPatched.mcp?.headerValuePlaceholder || '值（可引用环境变量）',
   noActive: zhPatched.mcp?.noActive || '当前没有启用的 MCP 服务。',
   statusError: zhPatched.mcp?.statusError || '错误',
 };
@@ -229,7 +229,8 @@ export default {
     name: 'Name',
     transport: 'Transport',
     transportStdio: 'Stdio',
-    transportHttp: 'HTTP',
+    transportHttp: 'HTTP (SSE)',
+    transportStreamableHttp: 'Streamable HTTP',
     command: 'Command',
     args: 'Arguments',
     url: 'URL',
@@ -261,6 +262,10 @@ export default {
     envValuePlaceholder: 'VALUE',
     addArg: 'Add arg',
     addEnv: 'Add env',
+    headers: 'Headers',
+    addHeader: 'Add header',
+    headerKeyPlaceholder: 'Header',
+    headerValuePlaceholder: 'Value (env vars allowed)',
     noActive: 'No enabled MCP servers are currently applied.',
     statusError: 'Error',
   },
@@ -1,5 +1,20 @@
+mod automations;
+mod memory;
 mod provider_companion;
 
+pub use automations::{
+    automation_webhook_handler, create_automation_handler, delete_automation_handler,
+    get_automation_handler, list_automation_runs_handler, list_automations_handler,
+    stop_automation_handler, update_automation_handler,
+};
+
+pub use memory::{
+    consolidate_session_memory_handler, delete_memory_file_handler,
+    delete_memory_history_entry_handler, forget_memory_fact_handler, list_memory_files_handler,
+    list_memory_history_handler, read_memory_file_handler, remember_memory_fact_handler,
+    search_memory_handler, write_memory_file_handler,
+};
+
 pub use provider_companion::{
     add_provider_model_handler, create_provider_handler, delete_provider_handler,
     delete_provider_model_handler, get_provider_handler, get_provider_models_handler,
@@ -9,6 +24,9 @@ pub use provider_companion::{
 use agent_diva_agent::AgentEvent;
 use agent_diva_core::bus::InboundMessage;
 use agent_diva_core::config::schema::ChannelsConfig;
+use agent_diva_core::session::{
+    parse_date_bound, SessionExportFormat, SessionExportOptions, SessionSearchQuery,
+};
 use axum::{
     extract::{Multipart, Path, Query, State},
     response::sse::{Event, Sse},
@@ -21,10 +39,12 @@ use tokio_stream::wrappers::BroadcastStream;
 use tokio_stream::wrappers::UnboundedReceiverStream;
 
 use crate::state::{
-    ApiRequest, AppState, ChannelUpdate, ConfigResponse, ConfigUpdate, FileUploadRequest,
-    ManagerCommand, McpRefreshRequest, RunCronJobRequest, SetCronJobEnabledRequest,
-    SetMcpEnabledRequest, SkillUploadRequest, StopChatRequest, ToolsConfigResponse,
-    ToolsConfigUpdate,
+    ApiRequest, AppState, ChannelUpdate, ConfigResponse, ConfigUpdate, CronRunsParams,
+    EditSessionMessageRequest, FileUploadRequest, ForkSessionRequest, ImportSessionRequest,
+    ManagerCommand, McpRefreshRequest, RewindSessionRequest, RunCronJobRequest,
+    SessionExportParams, SessionSearchParams, SetCronJobEnabledRequest, SetMcpEnabledRequest,
+    SkillDeleteParams, SkillInstallRequest, SkillUpdateRequest, SkillUploadRequest,
+    StopChatRequest, ToolsConfigResponse, ToolsConfigUpdate,
 };
 
 #[derive(serde::Deserialize)]
@@ -210,17 +230,265 @@ pub async fn get_sessions_handler(State(state): State<AppState>) -> Json<serde_j
     }
 }
 
+/// Session key addressed by an `/api/sessions/:id` path. Bare ids belong to
+/// the GUI channel.
+fn session_key_from_path(id: String) -> String {
+    if id.contains(':') {
+        id
+    } else {
+        format!("gui:{}", id)
+    }
+}
+
+pub async fn rewind_session_handler(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(payload): Json<RewindSessionRequest>,
+) -> Json<serde_json::Value> {
+    let session_key = session_key_from_path(id);
+    let (tx, rx) = oneshot::channel();
+    if let Err(e) = state
+        .api_tx
+        .send(ManagerCommand::RewindSession(
+            session_key,
+            payload.message_count,
+            tx,
+        ))
+        .await
+    {
+        tracing::error!("Failed to send RewindSession request: {}", e);
+        return Json(serde_json::json!({ "status": "error", "message": e.to_string() }));
+    }
+
+    match rx.await {
+        Ok(Ok(removed)) => Json(serde_json::json!({ "status": "ok", "removed": removed })),
+        Ok(Err(e)) => Json(serde_json::json!({ "status": "error", "message": e })),
+        Err(e) => {
+            tracing::error!("Failed to receive RewindSession response: {}", e);
+            Json(serde_json::json!({ "status": "error", "message": e.to_string() }))
+        }
+    }
+}
+
+pub async fn fork_session_handler(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(payload): Json<ForkSessionRequest>,
+) -> Json<serde_json::Value> {
+    let session_key = session_key_from_path(id);
+    let (tx, rx) = oneshot::channel();
+    if let Err(e) = state
+        .api_tx
+        .send(ManagerCommand::ForkSession(session_key, payload, tx))
+        .await
+    {
+        tracing::error!("Failed to send ForkSession request: {}", e);
+        return Json(serde_json::json!({ "status": "error", "message": e.to_string() }));
+    }
+
+    match rx.await {
+        Ok(Ok(new_key)) => Json(serde_json::json!({ "status": "ok", "session_key": new_key })),
+        Ok(Err(e)) => Json(serde_json::json!({ "status": "error", "message": e })),
+        Err(e) => {
+            tracing::error!("Failed to receive ForkSession response: {}", e);
+            Json(serde_json::json!({ "status": "error", "message": e.to_string() }))
+        }
+    }
+}
+
+/// Rewind to just before a user message, then send the edited text as a new
+/// turn. Streams the same events as `/api/chat`.
+pub async fn edit_session_message_handler(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(payload): Json<EditSessionMessageRequest>,
+) -> Sse<futures::stream::BoxStream<'static, Result<Event, Infallible>>> {
+    let session_key = session_key_from_path(id);
+    let (tx, rx) = oneshot::channel();
+    let rewind = match state
+        .api_tx
+        .send(ManagerCommand::RewindToUserMessage(
+            session_key.clone(),
+            payload.index,
+            tx,
+        ))
+        .await
+    {
+        Ok(()) => rx
+            .await
+            .map_err(|e| format!("Failed to receive rewind response: {}", e))
+            .and_then(|result| result),
+        Err(e) => Err(format!("Failed to send rewind request: {}", e)),
+    };
+    if let Err(message) = rewind {
+        let stream =
+            futures::stream::once(async move { Ok(Event::default().event("error").data(message)) })
+                .boxed();
+        return Sse::new(stream).keep_alive(axum::response::sse::KeepAlive::default());
+    }
+
+    let (channel, chat_id) = session_key
+        .split_once(':')
+        .map(|(channel, chat_id)| (channel.to_string(), chat_id.to_string()))
+        .unwrap_or_else(|| ("gui".to_string(), session_key.clone()));
+    chat_handler(
+        State(state),
+        Json(ChatRequest {
+            message: payload.message,
+            channel: Some(channel),
+            chat_id: Some(chat_id),
+            attachments: None,
+        }),
+    )
+    .await
+}
+
+/// Export a session as Markdown, HTML or a JSON bundle. Text formats come
+/// back in `content`; bundles come back as an object in `bundle` so they can
+/// be posted to `/api/sessions/import` unchanged.
+pub async fn export_session_handler(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(params): Query<SessionExportParams>,
+) -> Json<serde_json::Value> {
+    let format: SessionExportFormat = match params.format.as_deref().unwrap_or("markdown").parse() {
+        Ok(format) => format,
+        Err(e) => {
+            return Json(serde_json::json!({ "status": "error", "message": e.to_string() }));
+        }
+    };
+    let options = SessionExportOptions {
+        redact: params.redact.unwrap_or(true),
+    };
+    let session_key = session_key_from_path(id);
+    let (tx, rx) = oneshot::channel();
+    if let Err(e) = state
+        .api_tx
+        .send(ManagerCommand::ExportSession(
+            session_key.clone(),
+            format,
+            options,
+            tx,
+        ))
+        .await
+    {
+        tracing::error!("Failed to send ExportSession request: {}", e);
+        return Json(serde_json::json!({ "status": "error", "message": e.to_string() }));
+    }
+
+    match rx.await {
+        Ok(Ok(content)) => {
+            let mut body = serde_json::json!({
+                "status": "ok",
+                "session_key": session_key,
+                "format": format,
+                "content_type": format.content_type(),
+                "filename": format!("{}.{}", session_key.replace(':', "_"), format.extension()),
+            });
+            if format == SessionExportFormat::Json {
+                match serde_json::from_str::<serde_json::Value>(&content) {
+                    Ok(bundle) => body["bundle"] = bundle,
+                    Err(e) => {
+                        return Json(
+                            serde_json::json!({ "status": "error", "message": e.to_string() }),
+                        )
+                    }
+                }
+            } else {
+                body["content"] = serde_json::Value::String(content);
+            }
+            Json(body)
+        }
+        Ok(Err(e)) => Json(serde_json::json!({ "status": "error", "message": e })),
+        Err(e) => {
+            tracing::error!("Failed to receive ExportSession response: {}", e);
+            Json(serde_json::json!({ "status": "error", "message": e.to_string() }))
+        }
+    }
+}
+
+/// Import a JSON session bundle; the stored key may differ from the
+/// requested one when it is already taken.
+pub async fn import_session_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<ImportSessionRequest>,
+) -> Json<serde_json::Value> {
+    let (tx, rx) = oneshot::channel();
+    if let Err(e) = state
+        .api_tx
+        .send(ManagerCommand::ImportSession(payload, tx))
+        .await
+    {
+        tracing::error!("Failed to send ImportSession request: {}", e);
+        return Json(serde_json::json!({ "status": "error", "message": e.to_string() }));
+    }
+
+    match rx.await {
+        Ok(Ok(session_key)) => {
+            Json(serde_json::json!({ "status": "ok", "session_key": session_key }))
+        }
+        Ok(Err(e)) => Json(serde_json::json!({ "status": "error", "message": e })),
+        Err(e) => {
+            tracing::error!("Failed to receive ImportSession response: {}", e);
+            Json(serde_json::json!({ "status": "error", "message": e.to_string() }))
+        }
+    }
+}
+
+pub async fn search_sessions_handler(
+    State(state): State<AppState>,
+    Query(params): Query<SessionSearchParams>,
+) -> Json<serde_json::Value> {
+    let bound = |value: Option<&String>, end_of_day: bool| {
+        value
+            .map(|value| parse_date_bound(value, end_of_day))
+            .transpose()
+    };
+    let query = match (
+        bound(params.from.as_ref(), false),
+        bound(params.to.as_ref(), true),
+    ) {
+        (Ok(from), Ok(to)) => SessionSearchQuery {
+            text: params.q,
+            channel: params.channel,
+            session_key: None,
+            role: params.role,
+            from,
+            to,
+            limit: params.limit,
+        },
+        (Err(e), _) | (_, Err(e)) => {
+            return Json(serde_json::json!({ "status": "error", "message": e.to_string() }));
+        }
+    };
+
+    let (tx, rx) = oneshot::channel();
+    if let Err(e) = state
+        .api_tx
+        .send(ManagerCommand::SearchSessions(query, tx))
+        .await
+    {
+        tracing::error!("Failed to send SearchSessions request: {}", e);
+        return Json(serde_json::json!({ "status": "error", "message": e.to_string() }));
+    }
+
+    match rx.await {
+        Ok(Ok(hits)) => Json(serde_json::json!({ "status": "ok", "results": hits })),
+        Ok(Err(e)) => Json(serde_json::json!({ "status": "error", "message": e })),
+        Err(e) => {
+            tracing::error!("Failed to receive SearchSessions response: {}", e);
+            Json(serde_json::json!({ "status": "error", "message": e.to_string() }))
+        }
+    }
+}
+
 pub async fn get_session_history_handler(
     State(state): State<AppState>,
     Path(id): Path<String>,
 ) -> Json<serde_json::Value> {
     // If the path just gives an id (e.g. from frontend gui), then assume channel is implicit, normally the id comes as format `channel:chat_id` but frontend may just send `chat_id`. Wait, let the frontend send `channel:chat_id` via the path or query.
     // To support fetching any session_key, we will decode the path parameter if it's url encoded, or just use it as is.
-    let session_key = if !id.contains(':') {
-        format!("gui:{}", id) // fallback for backwards compatibility or assumptions
-    } else {
-        id
-    };
+    let session_key = session_key_from_path(id);
 
     let (tx, rx) = oneshot::channel();
     if let Err(e) = state
@@ -253,11 +521,7 @@ pub async fn delete_session_handler(
 }
 
 async fn do_delete_session(state: AppState, id: String) -> Json<serde_json::Value> {
-    let session_key = if !id.contains(':') {
-        format!("gui:{}", id)
-    } else {
-        id
-    };
+    let session_key = session_key_from_path(id);
 
     let (tx, rx) = oneshot::channel();
     if let Err(e) = state
@@ -677,14 +941,61 @@ pub async fn upload_file_handler(
     }
 }
 
+pub async fn install_skill_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<SkillInstallRequest>,
+) -> Json<serde_json::Value> {
+    let (tx, rx) = oneshot::channel();
+    if let Err(e) = state
+        .api_tx
+        .send(ManagerCommand::InstallSkill(payload, tx))
+        .await
+    {
+        tracing::error!("Failed to send InstallSkill request: {}", e);
+        return Json(serde_json::json!({ "status": "error", "message": e.to_string() }));
+    }
+    match rx.await {
+        Ok(Ok(outcome)) => Json(serde_json::json!({
+            "status": "ok",
+            "skill": outcome.skill,
+            "previous_version": outcome.previous_version,
+            "unmet_requirements": outcome.skill.unmet_requirements,
+        })),
+        Ok(Err(e)) => Json(serde_json::json!({ "status": "error", "message": e })),
+        Err(e) => Json(serde_json::json!({ "status": "error", "message": e.to_string() })),
+    }
+}
+
+pub async fn update_skills_handler(
+    State(state): State<AppState>,
+    payload: Option<Json<SkillUpdateRequest>>,
+) -> Json<serde_json::Value> {
+    let payload = payload.map(|Json(payload)| payload).unwrap_or_default();
+    let (tx, rx) = oneshot::channel();
+    if let Err(e) = state
+        .api_tx
+        .send(ManagerCommand::UpdateSkills(payload, tx))
+        .await
+    {
+        tracing::error!("Failed to send UpdateSkills request: {}", e);
+        return Json(serde_json::json!({ "status": "error", "message": e.to_string() }));
+    }
+    match rx.await {
+        Ok(Ok(updates)) => Json(serde_json::json!({ "status": "ok", "updates": updates })),
+        Ok(Err(e)) => Json(serde_json::json!({ "status": "error", "message": e })),
+        Err(e) => Json(serde_json::json!({ "status": "error", "message": e.to_string() })),
+    }
+}
+
 pub async fn delete_skill_handler(
     State(state): State<AppState>,
     Path(name): Path<String>,
+    Query(params): Query<SkillDeleteParams>,
 ) -> Json<serde_json::Value> {
     let (tx, rx) = oneshot::channel();
     if let Err(e) = state
         .api_tx
-        .send(ManagerCommand::DeleteSkill(name, tx))
+        .send(ManagerCommand::DeleteSkill(name, params.force, tx))
         .await
     {
         tracing::error!("Failed to send DeleteSkill request: {}", e);
@@ -841,6 +1152,27 @@ pub async fn run_cron_job_handler(
     }
 }
 
+pub async fn list_cron_runs_handler(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(params): Query<CronRunsParams>,
+) -> Json<serde_json::Value> {
+    let limit = params.limit.unwrap_or(20);
+    let (tx, rx) = oneshot::channel();
+    if let Err(e) = state
+        .api_tx
+        .send(ManagerCommand::ListCronRuns(id, limit, tx))
+        .await
+    {
+        return Json(serde_json::json!({ "status": "error", "message": e.to_string() }));
+    }
+    match rx.await {
+        Ok(Ok(runs)) => Json(serde_json::json!({ "status": "ok", "runs": runs })),
+        Ok(Err(e)) => Json(serde_json::json!({ "status": "error", "message": e })),
+        Err(e) => Json(serde_json::json!({ "status": "error", "message": e.to_string() })),
+    }
+}
+
 pub async fn stop_cron_job_handler(
     State(state): State<AppState>,
     Path(id): Path<String>,
@@ -927,6 +1259,80 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn search_sessions_handler_parses_date_filters() {
+        let (state, mut api_rx) = test_state();
+        let manager = tokio::spawn(async move {
+            match api_rx.recv().await.expect("manager command") {
+                ManagerCommand::SearchSessions(query, reply_tx) => {
+                    assert_eq!(query.text, "q3 migration");
+                    assert_eq!(query.channel.as_deref(), Some("telegram"));
+                    assert_eq!(
+                        query.from.map(|ts| ts.to_rfc3339()).as_deref(),
+                        Some("2026-07-01T00:00:00+00:00")
+                    );
+                    let _ = reply_tx.send(Ok(Vec::new()));
+                }
+                _ => panic!("expected SearchSessions command"),
+            }
+        });
+
+        let params = |from: &str| SessionSearchParams {
+            q: "q3 migration".to_string(),
+            channel: Some("telegram".to_string()),
+            role: None,
+            from: Some(from.to_string()),
+            to: None,
+            limit: None,
+        };
+        let Json(response) =
+            search_sessions_handler(State(state.clone()), Query(params("2026-07-01"))).await;
+        manager.await.expect("manager task");
+        assert_eq!(response["status"], "ok");
+        assert_eq!(response["results"], serde_json::json!([]));
+
+        let Json(invalid) = search_sessions_handler(State(state), Query(params("July"))).await;
+        assert_eq!(invalid["status"], "error");
+    }
+
+    #[tokio::test]
+    async fn export_session_handler_defaults_to_redacted_markdown() {
+        let (state, mut api_rx) = test_state();
+        let manager = tokio::spawn(async move {
+            match api_rx.recv().await.expect("manager command") {
+                ManagerCommand::ExportSession(session_key, format, options, reply_tx) => {
+                    assert_eq!(session_key, "gui:abc");
+                    assert_eq!(format, SessionExportFormat::Markdown);
+                    assert!(options.redact);
+                    let _ = reply_tx.send(Ok("# Session `gui:abc`".to_string()));
+                }
+                _ => panic!("expected ExportSession command"),
+            }
+        });
+
+        let Json(response) = export_session_handler(
+            State(state.clone()),
+            Path("abc".to_string()),
+            Query(SessionExportParams::default()),
+        )
+        .await;
+        manager.await.expect("manager task");
+        assert_eq!(response["status"], "ok");
+        assert_eq!(response["filename"], "gui_abc.md");
+        assert_eq!(response["content"], "# Session `gui:abc`");
+
+        let Json(invalid) = export_session_handler(
+            State(state),
+            Path("abc".to_string()),
+            Query(SessionExportParams {
+                format: Some("pdf".to_string()),
+                redact: None,
+            }),
+        )
+        .await;
+        assert_eq!(invalid["status"], "error");
+    }
+
     #[tokio::test]
     async fn get_config_handler_returns_manager_response() {
         let (state, mut api_rx) = test_state();
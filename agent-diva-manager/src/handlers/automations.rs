@@ -0,0 +1,136 @@
+use agent_diva_core::cron::AutomationRequest;
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    Json,
+};
+use serde::Serialize;
+use tokio::sync::oneshot;
+
+use crate::state::{AppState, AutomationCommand, CronRunsParams, ManagerCommand};
+
+/// Header carrying the secret of a webhook rule
+const WEBHOOK_SECRET_HEADER: &str = "x-automation-secret";
+
+/// Send an automation command and render the reply under `key`.
+async fn dispatch<T: Serialize>(
+    state: &AppState,
+    label: &str,
+    key: &str,
+    build: impl FnOnce(oneshot::Sender<Result<T, String>>) -> AutomationCommand,
+) -> Json<serde_json::Value> {
+    let (tx, rx) = oneshot::channel();
+    if let Err(e) = state
+        .api_tx
+        .send(ManagerCommand::Automation(build(tx)))
+        .await
+    {
+        tracing::error!("Failed to send {} request: {}", label, e);
+        return Json(serde_json::json!({ "status": "error", "message": e.to_string() }));
+    }
+    match rx.await {
+        Ok(Ok(value)) => {
+            let mut body = serde_json::json!({ "status": "ok" });
+            let value = serde_json::json!(value);
+            if !value.is_null() {
+                body[key] = value;
+            }
+            Json(body)
+        }
+        Ok(Err(e)) => Json(serde_json::json!({ "status": "error", "message": e })),
+        Err(e) => {
+            tracing::error!("Failed to receive {} response: {}", label, e);
+            Json(serde_json::json!({ "status": "error", "message": e.to_string() }))
+        }
+    }
+}
+
+pub async fn list_automations_handler(State(state): State<AppState>) -> Json<serde_json::Value> {
+    dispatch(
+        &state,
+        "ListAutomations",
+        "automations",
+        AutomationCommand::List,
+    )
+    .await
+}
+
+pub async fn get_automation_handler(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Json<serde_json::Value> {
+    dispatch(&state, "GetAutomation", "automation", |tx| {
+        AutomationCommand::Get(id, tx)
+    })
+    .await
+}
+
+pub async fn create_automation_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<AutomationRequest>,
+) -> Json<serde_json::Value> {
+    dispatch(&state, "CreateAutomation", "automation", |tx| {
+        AutomationCommand::Create(payload, tx)
+    })
+    .await
+}
+
+pub async fn update_automation_handler(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(payload): Json<AutomationRequest>,
+) -> Json<serde_json::Value> {
+    dispatch(&state, "UpdateAutomation", "automation", |tx| {
+        AutomationCommand::Update(id, payload, tx)
+    })
+    .await
+}
+
+pub async fn delete_automation_handler(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Json<serde_json::Value> {
+    dispatch(&state, "DeleteAutomation", "automation", |tx| {
+        AutomationCommand::Delete(id, tx)
+    })
+    .await
+}
+
+pub async fn list_automation_runs_handler(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(params): Query<CronRunsParams>,
+) -> Json<serde_json::Value> {
+    let limit = params.limit.unwrap_or(20);
+    dispatch(&state, "ListAutomationRuns", "runs", |tx| {
+        AutomationCommand::ListRuns(id, limit, tx)
+    })
+    .await
+}
+
+pub async fn stop_automation_handler(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Json<serde_json::Value> {
+    dispatch(&state, "StopAutomation", "run", |tx| {
+        AutomationCommand::Stop(id, tx)
+    })
+    .await
+}
+
+pub async fn automation_webhook_handler(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    body: Option<Json<serde_json::Value>>,
+) -> Json<serde_json::Value> {
+    let secret = headers
+        .get(WEBHOOK_SECRET_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(ToOwned::to_owned);
+    let body = body.map(|Json(body)| body).unwrap_or_default();
+    dispatch(&state, "AutomationWebhook", "run", |tx| {
+        AutomationCommand::Webhook(id, secret, body, tx)
+    })
+    .await
+}
@@ -0,0 +1,213 @@
+use agent_diva_core::memory::MemoryFileError;
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::Serialize;
+use tokio::sync::oneshot;
+
+use crate::memory_service::{
+    ConsolidateSessionRequest, MemoryEtagQuery, MemoryFileWrite, MemoryForgetQuery,
+    MemoryRememberRequest, MemoryScopeQuery, MemorySearchQuery,
+};
+use crate::state::{AppState, ManagerCommand, MemoryCommand};
+
+/// Send a memory command and render the reply under `key`.
+///
+/// Etag mismatches use `"status": "conflict"` and carry the current etag so the
+/// client can reload before retrying.
+async fn dispatch<T: Serialize>(
+    state: &AppState,
+    label: &str,
+    key: &str,
+    build: impl FnOnce(oneshot::Sender<Result<T, MemoryFileError>>) -> MemoryCommand,
+) -> Json<serde_json::Value> {
+    let (tx, rx) = oneshot::channel();
+    if let Err(e) = state.api_tx.send(ManagerCommand::Memory(build(tx))).await {
+        tracing::error!("Failed to send {} request: {}", label, e);
+        return Json(serde_json::json!({ "status": "error", "message": e.to_string() }));
+    }
+    match rx.await {
+        Ok(Ok(value)) => {
+            let mut body = serde_json::json!({ "status": "ok" });
+            let value = serde_json::json!(value);
+            if !value.is_null() {
+                body[key] = value;
+            }
+            Json(body)
+        }
+        Ok(Err(MemoryFileError::Conflict { name, current_etag })) => Json(serde_json::json!({
+            "status": "conflict",
+            "message": format!("memory file '{}' changed since it was read", name),
+            "etag": current_etag,
+        })),
+        Ok(Err(e)) => Json(serde_json::json!({ "status": "error", "message": e.to_string() })),
+        Err(e) => {
+            tracing::error!("Failed to receive {} response: {}", label, e);
+            Json(serde_json::json!({ "status": "error", "message": e.to_string() }))
+        }
+    }
+}
+
+pub async fn list_memory_files_handler(
+    State(state): State<AppState>,
+    Query(query): Query<MemoryScopeQuery>,
+) -> Json<serde_json::Value> {
+    dispatch(&state, "ListMemoryFiles", "files", |tx| {
+        MemoryCommand::ListFiles(query.scope, tx)
+    })
+    .await
+}
+
+pub async fn read_memory_file_handler(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Query(query): Query<MemoryScopeQuery>,
+) -> Json<serde_json::Value> {
+    dispatch(&state, "ReadMemoryFile", "file", |tx| {
+        MemoryCommand::ReadFile(name, query.scope, tx)
+    })
+    .await
+}
+
+pub async fn write_memory_file_handler(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Query(query): Query<MemoryScopeQuery>,
+    Json(payload): Json<MemoryFileWrite>,
+) -> Json<serde_json::Value> {
+    dispatch(&state, "WriteMemoryFile", "file", |tx| {
+        MemoryCommand::WriteFile(name, query.scope, payload, tx)
+    })
+    .await
+}
+
+pub async fn delete_memory_file_handler(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Query(query): Query<MemoryEtagQuery>,
+) -> Json<serde_json::Value> {
+    dispatch(&state, "DeleteMemoryFile", "file", |tx| {
+        MemoryCommand::DeleteFile(name, query, tx)
+    })
+    .await
+}
+
+pub async fn list_memory_history_handler(
+    State(state): State<AppState>,
+    Query(query): Query<MemoryScopeQuery>,
+) -> Json<serde_json::Value> {
+    dispatch(&state, "ListMemoryHistory", "history", |tx| {
+        MemoryCommand::ListHistory(query.scope, tx)
+    })
+    .await
+}
+
+pub async fn delete_memory_history_entry_handler(
+    State(state): State<AppState>,
+    Path(index): Path<usize>,
+    Query(query): Query<MemoryEtagQuery>,
+) -> Json<serde_json::Value> {
+    dispatch(&state, "DeleteMemoryHistoryEntry", "file", |tx| {
+        MemoryCommand::DeleteHistoryEntry(index, query, tx)
+    })
+    .await
+}
+
+pub async fn search_memory_handler(
+    State(state): State<AppState>,
+    Query(query): Query<MemorySearchQuery>,
+) -> Json<serde_json::Value> {
+    dispatch(&state, "SearchMemory", "results", |tx| {
+        MemoryCommand::Search(query, tx)
+    })
+    .await
+}
+
+pub async fn remember_memory_fact_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<MemoryRememberRequest>,
+) -> Json<serde_json::Value> {
+    dispatch(&state, "RememberMemoryFact", "stored", |tx| {
+        MemoryCommand::Remember(payload, tx)
+    })
+    .await
+}
+
+pub async fn forget_memory_fact_handler(
+    State(state): State<AppState>,
+    Query(query): Query<MemoryForgetQuery>,
+) -> Json<serde_json::Value> {
+    dispatch(&state, "ForgetMemoryFact", "forgotten", |tx| {
+        MemoryCommand::Forget(query, tx)
+    })
+    .await
+}
+
+pub async fn consolidate_session_memory_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<ConsolidateSessionRequest>,
+) -> Json<serde_json::Value> {
+    let (tx, rx) = oneshot::channel();
+    if let Err(e) = state
+        .api_tx
+        .send(ManagerCommand::Memory(MemoryCommand::ConsolidateSession(
+            payload.session_key,
+            tx,
+        )))
+        .await
+    {
+        tracing::error!("Failed to send ConsolidateSession request: {}", e);
+        return Json(serde_json::json!({ "status": "error", "message": e.to_string() }));
+    }
+    match rx.await {
+        Ok(Ok(consolidated)) => Json(serde_json::json!({
+            "status": "ok",
+            "consolidated": consolidated,
+        })),
+        Ok(Err(e)) => Json(serde_json::json!({ "status": "error", "message": e })),
+        Err(e) => {
+            tracing::error!("Failed to receive ConsolidateSession response: {}", e);
+            Json(serde_json::json!({ "status": "error", "message": e.to_string() }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agent_diva_core::bus::MessageBus;
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn write_conflict_reports_current_etag() {
+        let (api_tx, mut api_rx) = mpsc::channel(1);
+        let state = AppState {
+            api_tx,
+            bus: MessageBus::new(),
+        };
+        tokio::spawn(async move {
+            if let Some(ManagerCommand::Memory(MemoryCommand::WriteFile(name, _, _, reply))) =
+                api_rx.recv().await
+            {
+                let _ = reply.send(Err(MemoryFileError::Conflict {
+                    name,
+                    current_etag: "abc123".to_string(),
+                }));
+            }
+        });
+
+        let Json(body) = write_memory_file_handler(
+            State(state),
+            Path("MEMORY.md".to_string()),
+            Query(MemoryScopeQuery::default()),
+            Json(MemoryFileWrite {
+                content: "- fact".to_string(),
+                etag: Some("stale".to_string()),
+            }),
+        )
+        .await;
+        assert_eq!(body["status"], "conflict");
+        assert_eq!(body["etag"], "abc123");
+    }
+}
@@ -3,8 +3,10 @@ pub mod file_service;
 pub mod handlers;
 pub mod manager;
 pub mod mcp_service;
+pub mod memory_service;
 pub mod runtime;
 pub mod server;
+pub mod skill_package;
 pub mod skill_service;
 pub mod state;
 
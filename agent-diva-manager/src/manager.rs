@@ -1,4 +1,6 @@
+mod automation_admin;
 mod companion_admin;
+mod memory_admin;
 mod provider_admin;
 mod runtime_control;
 
@@ -32,6 +34,8 @@ pub struct Manager {
     runtime_control_tx: Option<mpsc::UnboundedSender<RuntimeControlCommand>>,
     cron_service: Arc<CronService>,
     file_manager: Arc<FileManager>,
+    /// Shared so the memory provider is opened once across API requests.
+    memory_service: crate::memory_service::MemoryService,
 }
 
 enum ProviderConfigTarget<'a> {
@@ -75,6 +79,7 @@ impl Manager {
             api_rx,
             bus,
             provider,
+            memory_service: crate::memory_service::MemoryService::new(loader.clone()),
             loader,
             current_provider: initial_provider
                 .or_else(|| Self::provider_name_for_model(None, &initial_model)),
@@ -265,6 +270,49 @@ impl Manager {
                         ManagerCommand::DeleteSession(session_key, reply) => {
                             self.handle_delete_session(session_key, reply).await;
                         }
+                        ManagerCommand::RewindSession(session_key, message_count, reply) => {
+                            self.forward_session_edit(
+                                |reply_tx| RuntimeControlCommand::RewindSession {
+                                    session_key,
+                                    message_count,
+                                    reply_tx,
+                                },
+                                reply,
+                            )
+                            .await;
+                        }
+                        ManagerCommand::RewindToUserMessage(session_key, index, reply) => {
+                            self.forward_session_edit(
+                                |reply_tx| RuntimeControlCommand::RewindToUserMessage {
+                                    session_key,
+                                    index,
+                                    reply_tx,
+                                },
+                                reply,
+                            )
+                            .await;
+                        }
+                        ManagerCommand::ForkSession(session_key, request, reply) => {
+                            self.forward_session_edit(
+                                |reply_tx| RuntimeControlCommand::ForkSession {
+                                    session_key,
+                                    new_key: request.new_key,
+                                    message_count: request.message_count,
+                                    reply_tx,
+                                },
+                                reply,
+                            )
+                            .await;
+                        }
+                        ManagerCommand::ExportSession(session_key, format, options, reply) => {
+                            self.handle_export_session(session_key, format, options, reply);
+                        }
+                        ManagerCommand::ImportSession(request, reply) => {
+                            self.handle_import_session(request, reply);
+                        }
+                        ManagerCommand::SearchSessions(query, reply) => {
+                            self.handle_search_sessions(query, reply);
+                        }
                         ManagerCommand::ListCronJobs(reply) => {
                             self.handle_list_cron_jobs(reply).await;
                         }
@@ -287,6 +335,9 @@ impl Manager {
                         ManagerCommand::RunCronJobNow(job_id, force, reply) => {
                             self.handle_run_cron_job_now(job_id, force, reply).await;
                         }
+                        ManagerCommand::ListCronRuns(job_id, limit, reply) => {
+                            self.handle_list_cron_runs(job_id, limit, reply).await;
+                        }
                         ManagerCommand::StopCronJobRun(job_id, reply) => {
                             self.handle_stop_cron_job_run(job_id, reply).await;
                         }
@@ -322,8 +373,14 @@ impl Manager {
                         ManagerCommand::UploadSkill(request, reply) => {
                             self.handle_upload_skill(request, reply);
                         }
-                        ManagerCommand::DeleteSkill(name, reply) => {
-                            self.handle_delete_skill(name, reply);
+                        ManagerCommand::InstallSkill(request, reply) => {
+                            self.handle_install_skill(request, reply);
+                        }
+                        ManagerCommand::UpdateSkills(request, reply) => {
+                            self.handle_update_skills(request, reply);
+                        }
+                        ManagerCommand::DeleteSkill(name, force, reply) => {
+                            self.handle_delete_skill(name, force, reply);
                         }
                         ManagerCommand::UploadFile(request, reply) => {
                             self.handle_upload_file(request, reply).await;
@@ -331,6 +388,12 @@ impl Manager {
                         ManagerCommand::Provider(command) => {
                             self.handle_provider_command(command).await;
                         }
+                        ManagerCommand::Automation(command) => {
+                            self.handle_automation_command(command).await;
+                        }
+                        ManagerCommand::Memory(command) => {
+                            self.handle_memory_command(command).await;
+                        }
                         ManagerCommand::UpdateTools(update) => {
                             self.handle_update_tools(update);
                         }
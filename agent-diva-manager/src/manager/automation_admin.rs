@@ -0,0 +1,55 @@
+use agent_diva_core::cron::AutomationEvent;
+
+use super::Manager;
+use crate::state::AutomationCommand;
+
+impl Manager {
+    pub(super) async fn handle_automation_command(&self, command: AutomationCommand) {
+        let service = &self.cron_service;
+        match command {
+            AutomationCommand::List(reply) => {
+                let _ = reply.send(Ok(service.list_automations().await));
+            }
+            AutomationCommand::Get(rule_id, reply) => {
+                let response = service
+                    .get_automation(&rule_id)
+                    .await
+                    .ok_or_else(|| format!("automation {} not found", rule_id));
+                let _ = reply.send(response);
+            }
+            AutomationCommand::Create(request, reply) => {
+                let _ = reply.send(service.create_automation(request).await);
+            }
+            AutomationCommand::Update(rule_id, request, reply) => {
+                let _ = reply.send(service.update_automation(&rule_id, request).await);
+            }
+            AutomationCommand::Delete(rule_id, reply) => {
+                let _ = reply.send(service.delete_automation(&rule_id).await);
+            }
+            AutomationCommand::ListRuns(rule_id, limit, reply) => {
+                let response = match service.get_automation(&rule_id).await {
+                    Some(_) => Ok(service.list_runs(&rule_id, limit).await),
+                    None => Err(format!("automation {} not found", rule_id)),
+                };
+                let _ = reply.send(response);
+            }
+            AutomationCommand::Stop(rule_id, reply) => {
+                let _ = reply.send(service.stop_run(&rule_id).await);
+            }
+            AutomationCommand::Webhook(rule_id, secret, body, reply) => {
+                let response = match service
+                    .check_webhook_secret(&rule_id, secret.as_deref())
+                    .await
+                {
+                    Ok(()) => {
+                        service
+                            .trigger_automation(&rule_id, AutomationEvent::Webhook { body })
+                            .await
+                    }
+                    Err(e) => Err(e),
+                };
+                let _ = reply.send(response);
+            }
+        }
+    }
+}
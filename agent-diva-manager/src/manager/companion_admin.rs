@@ -1,7 +1,7 @@
 use tokio::sync::oneshot;
 
 use super::Manager;
-use crate::state::SkillUploadRequest;
+use crate::state::{SkillInstallRequest, SkillUpdateRequest, SkillUploadRequest};
 
 impl Manager {
     pub(super) fn handle_get_skills(
@@ -109,14 +109,45 @@ impl Manager {
         let _ = reply.send(result);
     }
 
+    /// Fetching a package may clone a git repository, so installs and
+    /// updates run off the manager loop.
+    pub(super) fn handle_install_skill(
+        &self,
+        request: SkillInstallRequest,
+        reply: oneshot::Sender<Result<crate::skill_package::SkillInstallOutcome, String>>,
+    ) {
+        let service = self.skill_service();
+        tokio::task::spawn_blocking(move || {
+            let result = service
+                .install_package(&request.source, request.reference.as_deref())
+                .map_err(|e| e.to_string());
+            let _ = reply.send(result);
+        });
+    }
+
+    pub(super) fn handle_update_skills(
+        &self,
+        request: SkillUpdateRequest,
+        reply: oneshot::Sender<Result<Vec<crate::skill_package::SkillUpdateOutcome>, String>>,
+    ) {
+        let service = self.skill_service();
+        tokio::task::spawn_blocking(move || {
+            let result = service
+                .update_packages(request.name.as_deref())
+                .map_err(|e| e.to_string());
+            let _ = reply.send(result);
+        });
+    }
+
     pub(super) fn handle_delete_skill(
         &self,
         name: String,
+        force: bool,
         reply: oneshot::Sender<Result<(), String>>,
     ) {
         let result = self
             .skill_service()
-            .delete_skill(&name)
+            .remove_skill(&name, force)
             .map_err(|e| e.to_string());
         let _ = reply.send(result);
     }
@@ -0,0 +1,80 @@
+use agent_diva_agent::runtime_control::RuntimeControlCommand;
+use tokio::sync::oneshot;
+
+use super::Manager;
+use crate::state::MemoryCommand;
+
+impl Manager {
+    pub(super) async fn handle_memory_command(&self, command: MemoryCommand) {
+        let service = self.memory_service();
+        match command {
+            MemoryCommand::ListFiles(scope, reply) => {
+                let _ = reply.send(service.list_files(scope.as_deref()));
+            }
+            MemoryCommand::ReadFile(name, scope, reply) => {
+                let _ = reply.send(service.read_file(&name, scope.as_deref()));
+            }
+            MemoryCommand::WriteFile(name, scope, write, reply) => {
+                let _ = reply.send(service.write_file(&name, scope.as_deref(), write));
+            }
+            MemoryCommand::DeleteFile(name, query, reply) => {
+                let _ = reply.send(service.delete_file(&name, &query));
+            }
+            MemoryCommand::ListHistory(scope, reply) => {
+                let _ = reply.send(service.list_history(scope.as_deref()));
+            }
+            MemoryCommand::DeleteHistoryEntry(index, query, reply) => {
+                let _ = reply.send(service.delete_history_entry(index, &query));
+            }
+            MemoryCommand::Search(query, reply) => {
+                // Opening the semantic store can take a moment; keep the command loop free.
+                tokio::spawn(async move {
+                    let _ = reply.send(service.search(query).await);
+                });
+            }
+            MemoryCommand::Remember(request, reply) => {
+                tokio::spawn(async move {
+                    let _ = reply.send(service.remember(request).await);
+                });
+            }
+            MemoryCommand::Forget(query, reply) => {
+                tokio::spawn(async move {
+                    let _ = reply.send(service.forget(query).await);
+                });
+            }
+            MemoryCommand::ConsolidateSession(session_key, reply) => {
+                self.handle_consolidate_session(session_key, reply).await;
+            }
+        }
+    }
+
+    async fn handle_consolidate_session(
+        &self,
+        session_key: String,
+        reply: oneshot::Sender<Result<bool, String>>,
+    ) {
+        let Some(tx) = self.runtime_control_tx.clone() else {
+            let _ = reply.send(Err("runtime control channel is not initialized".to_string()));
+            return;
+        };
+        // Consolidation calls the LLM; wait for it off the command loop.
+        tokio::spawn(async move {
+            let (reply_tx, reply_rx) = oneshot::channel();
+            let response = match tx.send(RuntimeControlCommand::ConsolidateSession {
+                session_key,
+                reply_tx,
+            }) {
+                Ok(()) => reply_rx
+                    .await
+                    .map_err(|e| format!("failed to receive consolidation result: {}", e))
+                    .and_then(|result| result),
+                Err(e) => Err(format!("failed to send ConsolidateSession command: {}", e)),
+            };
+            let _ = reply.send(response);
+        });
+    }
+
+    fn memory_service(&self) -> crate::memory_service::MemoryService {
+        self.memory_service.clone()
+    }
+}
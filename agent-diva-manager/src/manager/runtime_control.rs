@@ -4,17 +4,23 @@ use std::sync::Arc;
 use agent_diva_agent::runtime_control::RuntimeControlCommand;
 use agent_diva_core::bus::AgentEvent;
 use agent_diva_core::config::schema::{
-    ChannelsConfig, Config, DingTalkConfig, DiscordConfig, EmailConfig, FeishuConfig, MatrixConfig,
-    QQConfig, SlackConfig, TelegramConfig, WebToolsConfig, WhatsAppConfig,
+    AgentSessionsConfig, ChannelsConfig, Config, DingTalkConfig, DiscordConfig, EmailConfig,
+    FeishuConfig, MatrixConfig, QQConfig, SlackConfig, TelegramConfig, WebToolsConfig,
+    WhatsAppConfig,
+};
+use agent_diva_core::session::{
+    export_session, import_bundle, SessionExportFormat, SessionExportOptions, SessionManager,
+    SessionSearchHit, SessionSearchIndex, SessionSearchQuery,
 };
 use agent_diva_providers::{LiteLLMClient, ProviderAccess, ProviderCatalogService};
 use tokio::sync::oneshot;
 use tracing::{debug, error, info, warn};
 
 use super::Manager;
+use crate::skill_service::expand_tilde;
 use crate::state::{
-    ApiRequest, ChannelUpdate, ConfigResponse, ConfigUpdate, ResetSessionRequest, StopChatRequest,
-    ToolsConfigResponse, ToolsConfigUpdate,
+    ApiRequest, ChannelUpdate, ConfigResponse, ConfigUpdate, ImportSessionRequest,
+    ResetSessionRequest, StopChatRequest, ToolsConfigResponse, ToolsConfigUpdate,
 };
 
 impl Manager {
@@ -149,6 +155,142 @@ impl Manager {
         let _ = reply.send(response);
     }
 
+    /// Send a session rewind/fork command to the agent loop, which owns the
+    /// session cache, and relay its reply.
+    pub(super) async fn forward_session_edit<T>(
+        &self,
+        build: impl FnOnce(oneshot::Sender<Result<T, String>>) -> RuntimeControlCommand,
+        reply: oneshot::Sender<Result<T, String>>,
+    ) {
+        let response = self
+            .with_runtime_control(
+                |tx| async move {
+                    let (reply_tx, reply_rx) = oneshot::channel();
+                    tx.send(build(reply_tx))
+                        .map_err(|e| format!("failed to send session command: {}", e))?;
+                    reply_rx
+                        .await
+                        .map_err(|e| format!("failed to receive session result: {}", e))?
+                },
+                "runtime control channel is not initialized",
+            )
+            .await;
+        let _ = reply.send(response);
+    }
+
+    pub(super) fn handle_search_sessions(
+        &self,
+        query: SessionSearchQuery,
+        reply: oneshot::Sender<Result<Vec<SessionSearchHit>, String>>,
+    ) {
+        let workspace = match self.session_workspace() {
+            Ok(workspace) => workspace,
+            Err(e) => {
+                let _ = reply.send(Err(e));
+                return;
+            }
+        };
+        // Searching refreshes the index from disk first; keep the command loop free.
+        tokio::spawn(async move {
+            let response = match SessionSearchIndex::for_workspace(&workspace).await {
+                Ok(index) => index.search(&query).await.map_err(|e| e.to_string()),
+                Err(e) => Err(e.to_string()),
+            };
+            let _ = reply.send(response);
+        });
+    }
+
+    fn session_workspace(&self) -> Result<std::path::PathBuf, String> {
+        self.session_storage().map(|(workspace, _)| workspace)
+    }
+
+    /// Workspace and session backend from the current config
+    fn session_storage(&self) -> Result<(std::path::PathBuf, AgentSessionsConfig), String> {
+        self.loader
+            .load()
+            .map(|config| {
+                (
+                    expand_tilde(&config.agents.defaults.workspace),
+                    config.agents.sessions,
+                )
+            })
+            .map_err(|e| e.to_string())
+    }
+
+    pub(super) fn handle_export_session(
+        &self,
+        session_key: String,
+        format: SessionExportFormat,
+        options: SessionExportOptions,
+        reply: oneshot::Sender<Result<String, String>>,
+    ) {
+        let (workspace, sessions_config) = match self.session_storage() {
+            Ok(storage) => storage,
+            Err(e) => {
+                let _ = reply.send(Err(e));
+                return;
+            }
+        };
+        let file_manager = Arc::clone(&self.file_manager);
+        // Sessions are persisted after every turn, so exports read from disk.
+        tokio::spawn(async move {
+            let mut sessions = match SessionManager::open(&workspace, &sessions_config) {
+                Ok(sessions) => sessions,
+                Err(e) => {
+                    let _ = reply.send(Err(e.to_string()));
+                    return;
+                }
+            };
+            let response = export_session(
+                &mut sessions,
+                Some(&file_manager),
+                &session_key,
+                format,
+                options,
+            )
+            .await
+            .map_err(|e| e.to_string());
+            let _ = reply.send(response);
+        });
+    }
+
+    pub(super) fn handle_import_session(
+        &self,
+        request: ImportSessionRequest,
+        reply: oneshot::Sender<Result<String, String>>,
+    ) {
+        let (workspace, sessions_config) = match self.session_storage() {
+            Ok(storage) => storage,
+            Err(e) => {
+                let _ = reply.send(Err(e));
+                return;
+            }
+        };
+        let file_manager = Arc::clone(&self.file_manager);
+        tokio::spawn(async move {
+            let mut sessions = match SessionManager::open(&workspace, &sessions_config) {
+                Ok(sessions) => sessions,
+                Err(e) => {
+                    let _ = reply.send(Err(e.to_string()));
+                    return;
+                }
+            };
+            let response = import_bundle(
+                &mut sessions,
+                Some(&file_manager),
+                request.bundle,
+                request.key.as_deref(),
+            )
+            .await
+            .map(|session| {
+                info!("Imported session bundle as {}", session.key);
+                session.key
+            })
+            .map_err(|e| e.to_string());
+            let _ = reply.send(response);
+        });
+    }
+
     pub(super) async fn handle_list_cron_jobs(
         &self,
         reply: oneshot::Sender<Result<Vec<agent_diva_core::cron::CronJobDto>, String>>,
@@ -205,7 +347,25 @@ impl Manager {
         force: bool,
         reply: oneshot::Sender<Result<agent_diva_core::cron::CronJobDto, String>>,
     ) {
-        let _ = reply.send(self.cron_service.run_job_now(&job_id, force).await);
+        // A run lasts as long as the agent turn; keep the command loop free
+        // so the run can still be stopped.
+        let cron_service = Arc::clone(&self.cron_service);
+        tokio::spawn(async move {
+            let _ = reply.send(cron_service.run_job_now(&job_id, force).await);
+        });
+    }
+
+    pub(super) async fn handle_list_cron_runs(
+        &self,
+        job_id: String,
+        limit: usize,
+        reply: oneshot::Sender<Result<Vec<agent_diva_core::cron::CronRunRecord>, String>>,
+    ) {
+        let response = match self.cron_service.get_job(&job_id).await {
+            Some(_) => Ok(self.cron_service.list_runs(&job_id, limit).await),
+            None => Err(format!("job {} not found", job_id)),
+        };
+        let _ = reply.send(response);
     }
 
     pub(super) async fn handle_stop_cron_job_run(
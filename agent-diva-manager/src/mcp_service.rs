@@ -1,4 +1,4 @@
-use agent_diva_core::config::schema::{Config, MCPServerConfig};
+use agent_diva_core::config::schema::{Config, MCPServerConfig, MCPTransport};
 use agent_diva_core::config::ConfigLoader;
 use agent_diva_tools::probe_mcp_server_sync;
 use anyhow::anyhow;
@@ -25,6 +25,7 @@ pub struct McpServerDto {
     pub args: Vec<String>,
     pub env: HashMap<String, String>,
     pub url: String,
+    pub headers: HashMap<String, String>,
     pub tool_timeout: u64,
     pub status: McpConnectionStatusDto,
 }
@@ -34,6 +35,8 @@ pub struct McpServerUpsert {
     pub name: String,
     pub enabled: bool,
     #[serde(default)]
+    pub transport: Option<MCPTransport>,
+    #[serde(default)]
     pub command: String,
     #[serde(default)]
     pub args: Vec<String>,
@@ -41,6 +44,8 @@ pub struct McpServerUpsert {
     pub env: HashMap<String, String>,
     #[serde(default)]
     pub url: String,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
     #[serde(default = "default_tool_timeout")]
     pub tool_timeout: u64,
 }
@@ -166,13 +171,11 @@ impl McpService {
 
     fn to_dto(&self, config: &Config, name: &str, server: &MCPServerConfig) -> McpServerDto {
         let enabled = !config.tools.is_mcp_server_disabled(name);
-        let transport = if !server.command.trim().is_empty() {
-            "stdio"
-        } else if !server.url.trim().is_empty() {
-            "http"
-        } else {
-            "invalid"
-        };
+        let invalid = server.validate().err();
+        let transport = server
+            .effective_transport()
+            .map(|transport| transport.as_str())
+            .unwrap_or("invalid");
         let status = if !enabled {
             McpConnectionStatusDto {
                 state: "disabled".to_string(),
@@ -182,6 +185,15 @@ impl McpService {
                 error: None,
                 checked_at: None,
             }
+        } else if let Some(error) = invalid {
+            McpConnectionStatusDto {
+                state: "invalid".to_string(),
+                connected: false,
+                applied: false,
+                tool_count: 0,
+                error: Some(error),
+                checked_at: Some(Utc::now().to_rfc3339()),
+            }
         } else {
             match probe_mcp_server_sync(name, server) {
                 Ok(tool_count) => McpConnectionStatusDto {
@@ -193,11 +205,7 @@ impl McpService {
                     checked_at: Some(Utc::now().to_rfc3339()),
                 },
                 Err(error) => McpConnectionStatusDto {
-                    state: if transport == "invalid" {
-                        "invalid".to_string()
-                    } else {
-                        "degraded".to_string()
-                    },
+                    state: "degraded".to_string(),
                     connected: false,
                     applied: true,
                     tool_count: 0,
@@ -215,6 +223,7 @@ impl McpService {
             args: server.args.clone(),
             env: server.env.clone(),
             url: server.url.clone(),
+            headers: server.headers.clone(),
             tool_timeout: server.tool_timeout,
             status,
         }
@@ -222,10 +231,12 @@ impl McpService {
 
     fn payload_to_config(payload: &McpServerUpsert) -> anyhow::Result<MCPServerConfig> {
         let config = MCPServerConfig {
+            transport: payload.transport,
             command: payload.command.trim().to_string(),
             args: payload.args.clone(),
             env: payload.env.clone(),
             url: payload.url.trim().to_string(),
+            headers: payload.headers.clone(),
             tool_timeout: payload.tool_timeout.max(1),
         };
         Self::validate_server(&payload.name, &config)?;
@@ -240,14 +251,11 @@ impl McpService {
     }
 
     fn validate_server(name: &str, server: &MCPServerConfig) -> anyhow::Result<()> {
+        server
+            .validate()
+            .map_err(|err| anyhow!("tools.mcp_servers.{} {}", name, err))?;
         let has_stdio = !server.command.trim().is_empty();
         let has_http = !server.url.trim().is_empty();
-        if !has_stdio && !has_http {
-            return Err(anyhow!(
-                "tools.mcp_servers.{} must set either command (stdio) or url (http)",
-                name
-            ));
-        }
         if has_stdio && has_http {
             return Err(anyhow!(
                 "tools.mcp_servers.{} cannot set both command and url at the same time",
@@ -296,10 +304,12 @@ mod tests {
             .create_mcp(McpServerUpsert {
                 name: "remote".to_string(),
                 enabled: false,
+                transport: None,
                 command: String::new(),
                 args: Vec::new(),
                 env: HashMap::new(),
                 url: "http://127.0.0.1:9000/mcp".to_string(),
+                headers: HashMap::new(),
                 tool_timeout: 30,
             })
             .unwrap();
@@ -319,10 +329,12 @@ mod tests {
             .create_mcp(McpServerUpsert {
                 name: "stdio".to_string(),
                 enabled: false,
+                transport: None,
                 command: "uvx".to_string(),
                 args: vec!["mcp-server-filesystem".to_string()],
                 env: HashMap::new(),
                 url: String::new(),
+                headers: HashMap::new(),
                 tool_timeout: 30,
             })
             .unwrap();
@@ -333,4 +345,60 @@ mod tests {
         assert!(!config.tools.is_mcp_server_disabled("stdio"));
         let _ = fs::remove_dir_all(config_dir.path());
     }
+
+    #[test]
+    fn streamable_http_mcp_keeps_transport_and_headers() {
+        let config_dir = TempDir::new().unwrap();
+        let loader = write_config(&config_dir);
+        let service = McpService::new(loader.clone());
+
+        let dto = service
+            .create_mcp(McpServerUpsert {
+                name: "hosted".to_string(),
+                enabled: false,
+                transport: Some(MCPTransport::StreamableHttp),
+                command: String::new(),
+                args: Vec::new(),
+                env: HashMap::new(),
+                url: "https://mcp.example.com/mcp".to_string(),
+                headers: [(
+                    "Authorization".to_string(),
+                    "Bearer ${HOSTED_MCP_TOKEN}".to_string(),
+                )]
+                .into(),
+                tool_timeout: 30,
+            })
+            .unwrap();
+        assert_eq!(dto.transport, "streamable_http");
+        assert_eq!(
+            dto.headers.get("Authorization").map(String::as_str),
+            Some("Bearer ${HOSTED_MCP_TOKEN}")
+        );
+
+        let config = loader.load().unwrap();
+        let server = &config.tools.mcp_servers["hosted"];
+        assert_eq!(server.transport, Some(MCPTransport::StreamableHttp));
+    }
+
+    #[test]
+    fn create_mcp_rejects_transport_without_url() {
+        let config_dir = TempDir::new().unwrap();
+        let loader = write_config(&config_dir);
+        let service = McpService::new(loader);
+
+        let err = service
+            .create_mcp(McpServerUpsert {
+                name: "hosted".to_string(),
+                enabled: true,
+                transport: Some(MCPTransport::Sse),
+                command: "npx".to_string(),
+                args: Vec::new(),
+                env: HashMap::new(),
+                url: String::new(),
+                headers: HashMap::new(),
+                tool_timeout: 30,
+            })
+            .unwrap_err();
+        assert!(err.to_string().contains("transport 'sse' requires url"));
+    }
 }
@@ -0,0 +1,468 @@
+use agent_diva_core::config::{ConfigLoader, MemoryBackend};
+use agent_diva_core::memory::{
+    open_memory_provider, ForgetRequest, ForgetStatus, HistoryEntry, MemoryFileError,
+    MemoryFileInfo, MemoryFileMatch, MemoryFileSnapshot, MemoryManager, MemoryProvider,
+    MemoryScope, MemorySearchRequest, MemorySearchStatus, RememberRequest, RememberStatus,
+};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::skill_service::expand_tilde;
+
+const DEFAULT_SEARCH_LIMIT: usize = 20;
+const MAX_SEARCH_LIMIT: usize = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryFileWrite {
+    pub content: String,
+    /// Etag from the read the edit is based on; omit to overwrite unconditionally.
+    #[serde(default)]
+    pub etag: Option<String>,
+}
+
+/// Memory scope an endpoint works on, in the `global`, `channel:<c>`,
+/// `chat:<c>:<id>` or `sender:<c>:<id>` form; omitted means global.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MemoryScopeQuery {
+    #[serde(default)]
+    pub scope: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MemoryEtagQuery {
+    #[serde(default)]
+    pub etag: Option<String>,
+    #[serde(default)]
+    pub scope: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemorySearchQuery {
+    pub q: String,
+    #[serde(default)]
+    pub limit: Option<usize>,
+    #[serde(default)]
+    pub scope: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryRememberRequest {
+    pub fact: String,
+    #[serde(default)]
+    pub scope: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryForgetQuery {
+    /// Fact id from a search result, or the fact's exact text.
+    pub target: String,
+    #[serde(default)]
+    pub scope: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsolidateSessionRequest {
+    pub session_key: String,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct MemoryHistoryDto {
+    pub entries: Vec<HistoryEntry>,
+    /// Etag of `HISTORY.md`, required to delete entries safely.
+    pub etag: String,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct MemoryRecallDto {
+    /// Id accepted by the forget endpoint, when the backend has one.
+    pub id: Option<String>,
+    pub content: String,
+    pub recorded_at: Option<String>,
+    pub source_session: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct MemorySearchDto {
+    /// Matching lines in the markdown memory files.
+    pub matches: Vec<MemoryFileMatch>,
+    /// Hits from the configured memory backend.
+    pub recalled: Vec<MemoryRecallDto>,
+}
+
+/// Provider opened for one workspace and backend configuration.
+struct OpenProvider {
+    key: (PathBuf, MemoryBackend, usize),
+    provider: Arc<dyn MemoryProvider>,
+}
+
+/// Memory files plus the facts of the configured backend.
+///
+/// File endpoints edit the markdown files. With the `sqlite` backend those
+/// files are only an import source and audit log, so facts are read and
+/// written through the memory provider instead, which is opened once and
+/// shared by clones of the service.
+#[derive(Clone)]
+pub struct MemoryService {
+    loader: ConfigLoader,
+    provider: Arc<Mutex<Option<OpenProvider>>>,
+}
+
+impl MemoryService {
+    pub fn new(loader: ConfigLoader) -> Self {
+        Self {
+            loader,
+            provider: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub fn list_files(&self, scope: Option<&str>) -> Result<Vec<MemoryFileInfo>, MemoryFileError> {
+        Ok(self.memory_manager(scope)?.list_files())
+    }
+
+    pub fn read_file(
+        &self,
+        name: &str,
+        scope: Option<&str>,
+    ) -> Result<MemoryFileSnapshot, MemoryFileError> {
+        self.memory_manager(scope)?.read_file(name)
+    }
+
+    pub fn write_file(
+        &self,
+        name: &str,
+        scope: Option<&str>,
+        write: MemoryFileWrite,
+    ) -> Result<MemoryFileSnapshot, MemoryFileError> {
+        self.ensure_file_is_live(name)?;
+        self.memory_manager(scope)?
+            .write_file(name, &write.content, write.etag.as_deref())
+    }
+
+    pub fn delete_file(&self, name: &str, query: &MemoryEtagQuery) -> Result<(), MemoryFileError> {
+        self.ensure_file_is_live(name)?;
+        self.memory_manager(query.scope.as_deref())?
+            .delete_file(name, query.etag.as_deref())
+    }
+
+    /// Reject edits to files the configured backend no longer reads: with the
+    /// `sqlite` backend only `HISTORY.md` is still in use.
+    fn ensure_file_is_live(&self, name: &str) -> Result<(), MemoryFileError> {
+        let config = self.loader.load()?;
+        if config.agents.memory.backend == MemoryBackend::Sqlite && name != "HISTORY.md" {
+            return Err(MemoryFileError::ReadOnly(name.to_string()));
+        }
+        Ok(())
+    }
+
+    pub fn list_history(&self, scope: Option<&str>) -> Result<MemoryHistoryDto, MemoryFileError> {
+        let manager = self.memory_manager(scope)?;
+        let content = manager.load_history();
+        Ok(MemoryHistoryDto {
+            entries: manager.history_entries(),
+            etag: agent_diva_core::memory::memory_etag(&content),
+        })
+    }
+
+    pub fn delete_history_entry(
+        &self,
+        index: usize,
+        query: &MemoryEtagQuery,
+    ) -> Result<MemoryFileSnapshot, MemoryFileError> {
+        self.memory_manager(query.scope.as_deref())?
+            .delete_history_entry(index, query.etag.as_deref())
+    }
+
+    /// Search the markdown files and, through the configured memory provider,
+    /// the backend's stored facts.
+    pub async fn search(
+        &self,
+        query: MemorySearchQuery,
+    ) -> Result<MemorySearchDto, MemoryFileError> {
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_SEARCH_LIMIT)
+            .clamp(1, MAX_SEARCH_LIMIT);
+        let scope = parse_scope(query.scope.as_deref())?;
+        let (workspace, provider) = self.provider().await?;
+        let matches = MemoryManager::for_scope(&workspace, &scope).search_files(&query.q, limit);
+
+        let response = provider
+            .search(MemorySearchRequest {
+                workspace_root: workspace,
+                scope,
+                query: query.q,
+                limit,
+            })
+            .await?;
+        let recalled = match response.status {
+            MemorySearchStatus::Ready => response
+                .hits
+                .into_iter()
+                .map(|hit| MemoryRecallDto {
+                    id: hit.id,
+                    content: hit.content,
+                    recorded_at: hit.recorded_at,
+                    source_session: hit.source_session,
+                })
+                .collect(),
+            MemorySearchStatus::Unsupported => Vec::new(),
+            MemorySearchStatus::Failed { reason } => {
+                tracing::warn!("Memory provider search failed: {}", reason);
+                Vec::new()
+            }
+        };
+        Ok(MemorySearchDto { matches, recalled })
+    }
+
+    /// Store a fact through the configured backend; `false` when it was already known.
+    pub async fn remember(&self, request: MemoryRememberRequest) -> Result<bool, MemoryFileError> {
+        let scope = parse_scope(request.scope.as_deref())?;
+        let (workspace, provider) = self.provider().await?;
+        let response = provider
+            .remember(RememberRequest {
+                workspace_root: workspace,
+                scope,
+                fact: request.fact,
+                session_key: None,
+            })
+            .await?;
+        match response.status {
+            RememberStatus::Stored => Ok(true),
+            RememberStatus::AlreadyKnown => Ok(false),
+            RememberStatus::Unsupported => Err(backend_error(
+                "the memory backend does not support saving facts",
+            )),
+            RememberStatus::Failed { reason } => Err(backend_error(reason)),
+        }
+    }
+
+    /// Delete a fact through the configured backend, returning the removed facts.
+    pub async fn forget(&self, query: MemoryForgetQuery) -> Result<Vec<String>, MemoryFileError> {
+        let scope = parse_scope(query.scope.as_deref())?;
+        let (workspace, provider) = self.provider().await?;
+        let response = provider
+            .forget(ForgetRequest {
+                workspace_root: workspace,
+                scope,
+                target: query.target,
+                session_key: None,
+            })
+            .await?;
+        match response.status {
+            ForgetStatus::Forgotten | ForgetStatus::NotFound => Ok(response.forgotten),
+            ForgetStatus::Unsupported => Err(backend_error(
+                "the memory backend does not support deleting facts",
+            )),
+            ForgetStatus::Failed { reason } => Err(backend_error(reason)),
+        }
+    }
+
+    /// The configured memory provider, reopened only when the workspace or
+    /// backend settings change.
+    async fn provider(&self) -> Result<(PathBuf, Arc<dyn MemoryProvider>), MemoryFileError> {
+        let config = self.loader.load()?;
+        let workspace = expand_tilde(&config.agents.defaults.workspace);
+        let memory = &config.agents.memory;
+        let key = (workspace.clone(), memory.backend, memory.recall_limit);
+
+        let mut cached = self.provider.lock().await;
+        if let Some(open) = cached.as_ref().filter(|open| open.key == key) {
+            return Ok((workspace, open.provider.clone()));
+        }
+        let provider: Arc<dyn MemoryProvider> =
+            match open_memory_provider(memory, &workspace).await? {
+                Some(provider) => provider,
+                None => Arc::new(MemoryManager::new(&workspace)),
+            };
+        *cached = Some(OpenProvider {
+            key,
+            provider: provider.clone(),
+        });
+        Ok((workspace, provider))
+    }
+
+    fn memory_manager(&self, scope: Option<&str>) -> Result<MemoryManager, MemoryFileError> {
+        let scope = parse_scope(scope)?;
+        Ok(MemoryManager::for_scope(self.workspace_dir()?, &scope))
+    }
+
+    fn workspace_dir(&self) -> Result<PathBuf, MemoryFileError> {
+        let config = self.loader.load()?;
+        Ok(expand_tilde(&config.agents.defaults.workspace))
+    }
+}
+
+fn parse_scope(scope: Option<&str>) -> Result<MemoryScope, MemoryFileError> {
+    match scope.map(str::trim).filter(|scope| !scope.is_empty()) {
+        Some(scope) => Ok(MemoryScope::parse(scope)?),
+        None => Ok(MemoryScope::Global),
+    }
+}
+
+fn backend_error(reason: impl Into<String>) -> MemoryFileError {
+    MemoryFileError::Storage(agent_diva_core::Error::Internal(reason.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agent_diva_core::config::Config;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    fn write_config(config_dir: &Path, workspace: &Path) {
+        write_config_with_backend(config_dir, workspace, MemoryBackend::Markdown);
+    }
+
+    fn write_config_with_backend(config_dir: &Path, workspace: &Path, backend: MemoryBackend) {
+        let loader = ConfigLoader::with_dir(config_dir);
+        let mut config = Config::default();
+        config.agents.defaults.workspace = workspace.display().to_string();
+        config.agents.memory.backend = backend;
+        loader.save(&config).unwrap();
+    }
+
+    #[tokio::test]
+    async fn sqlite_facts_go_through_the_provider() {
+        let config_dir = TempDir::new().unwrap();
+        let workspace = TempDir::new().unwrap();
+        write_config_with_backend(config_dir.path(), workspace.path(), MemoryBackend::Sqlite);
+        let service = MemoryService::new(ConfigLoader::with_dir(config_dir.path()));
+
+        let edit = service.write_file(
+            "MEMORY.md",
+            None,
+            MemoryFileWrite {
+                content: "- Lives in Porto\n".to_string(),
+                etag: None,
+            },
+        );
+        assert!(matches!(edit, Err(MemoryFileError::ReadOnly(_))));
+
+        let stored = service
+            .remember(MemoryRememberRequest {
+                fact: "Lives in Porto".to_string(),
+                scope: None,
+            })
+            .await
+            .unwrap();
+        assert!(stored);
+        let result = service
+            .search(MemorySearchQuery {
+                q: "porto".to_string(),
+                limit: None,
+                scope: None,
+            })
+            .await
+            .unwrap();
+        assert!(result.matches.is_empty(), "MEMORY.md is untouched");
+        let id = result.recalled[0].id.clone().unwrap();
+
+        let forgotten = service
+            .forget(MemoryForgetQuery {
+                target: id,
+                scope: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(forgotten, vec!["Lives in Porto"]);
+        assert!(workspace.path().join("memory").join("memory.db").exists());
+    }
+
+    #[tokio::test]
+    async fn scoped_requests_stay_in_their_scope() {
+        let config_dir = TempDir::new().unwrap();
+        let workspace = TempDir::new().unwrap();
+        write_config(config_dir.path(), workspace.path());
+        let service = MemoryService::new(ConfigLoader::with_dir(config_dir.path()));
+        let chat = Some("chat:telegram:42");
+
+        service
+            .write_file(
+                "MEMORY.md",
+                chat,
+                MemoryFileWrite {
+                    content: "- Lives in Porto\n".to_string(),
+                    etag: None,
+                },
+            )
+            .unwrap();
+        assert!(service.read_file("MEMORY.md", chat).is_ok());
+        assert!(matches!(
+            service.read_file("MEMORY.md", None),
+            Err(MemoryFileError::NotFound(_))
+        ));
+
+        let search = |scope: Option<&str>| MemorySearchQuery {
+            q: "porto".to_string(),
+            limit: None,
+            scope: scope.map(str::to_string),
+        };
+        assert!(!service
+            .search(search(chat))
+            .await
+            .unwrap()
+            .matches
+            .is_empty());
+        assert!(service
+            .search(search(None))
+            .await
+            .unwrap()
+            .matches
+            .is_empty());
+        assert!(service.list_files(Some("chat:telegram")).is_err());
+    }
+
+    #[tokio::test]
+    async fn edits_are_versioned_and_searchable() {
+        let config_dir = TempDir::new().unwrap();
+        let workspace = TempDir::new().unwrap();
+        write_config(config_dir.path(), workspace.path());
+        let service = MemoryService::new(ConfigLoader::with_dir(config_dir.path()));
+
+        let created = service
+            .write_file(
+                "MEMORY.md",
+                None,
+                MemoryFileWrite {
+                    content: "## Facts\n- Lives in Lisbon\n".to_string(),
+                    etag: None,
+                },
+            )
+            .unwrap();
+        let stale = service.write_file(
+            "MEMORY.md",
+            None,
+            MemoryFileWrite {
+                content: "## Facts\n- Lives in Porto\n".to_string(),
+                etag: Some("0000000000000000".to_string()),
+            },
+        );
+        assert!(matches!(stale, Err(MemoryFileError::Conflict { .. })));
+        service
+            .write_file(
+                "MEMORY.md",
+                None,
+                MemoryFileWrite {
+                    content: "## Facts\n- Lives in Porto\n".to_string(),
+                    etag: Some(created.etag),
+                },
+            )
+            .unwrap();
+
+        let files = service.list_files(None).unwrap();
+        assert_eq!(files.len(), 1);
+        let result = service
+            .search(MemorySearchQuery {
+                q: "porto".to_string(),
+                limit: None,
+                scope: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(result.matches.len(), 1);
+        assert_eq!(result.matches[0].text, "- Lives in Porto");
+    }
+}
@@ -1,4 +1,6 @@
+mod automation_runner;
 mod bootstrap;
+mod cron_runner;
 mod shutdown;
 mod task_runtime;
 
@@ -8,13 +10,13 @@ use agent_diva_agent::{
     context_budget::ContextBudgetPolicy, runtime_control::RuntimeControlCommand,
     tool_config::network::NetworkToolConfig, tool_config::network::WebFetchRuntimeConfig,
     tool_config::network::WebRuntimeConfig, tool_config::network::WebSearchRuntimeConfig,
-    AgentLoop, BuiltInToolsConfig, SubagentPolicy, ToolConfig,
+    AgentLoop, BuiltInToolsConfig, SubagentPolicy, SubagentProfile, ToolConfig,
 };
 use agent_diva_channels::ChannelManager;
-use agent_diva_core::bus::{InboundMessage, MessageBus};
+use agent_diva_core::bus::{AgentEvent, InboundMessage, MessageBus};
 use agent_diva_core::config::{Config, ConfigLoader};
 use agent_diva_core::cron::service::JobCallback;
-use agent_diva_core::cron::CronService;
+use agent_diva_core::cron::{CronJobOutcome, CronService};
 use agent_diva_core::debug::{DebugEvent, DebugEventLogger, DebugRun};
 use agent_diva_core::logging::build_runtime_trace_logger;
 use agent_diva_core::trace::TraceId;
@@ -23,7 +25,10 @@ use agent_diva_providers::{
     DynamicProvider, LLMProvider, LiteLLMClient, ProviderAccess, ProviderCatalogService,
     ProviderRegistry,
 };
+use agent_diva_tools::{ExecSandboxConfig, McpManager, ShellJobManager};
 use anyhow::Result;
+use automation_runner::spawn_automation_runtime;
+use cron_runner::start_cron_service;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::{broadcast, mpsc, watch};
@@ -57,6 +62,7 @@ impl EmbeddedGatewayRuntime {
 struct GatewayBootstrap {
     config: Config,
     loader: ConfigLoader,
+    workspace: PathBuf,
     port: u16,
     bus: MessageBus,
     cron_service: Arc<CronService>,
@@ -66,6 +72,7 @@ struct GatewayBootstrap {
     provider_api_base: Option<String>,
     agent: AgentLoop,
     file_manager: Arc<FileManager>,
+    shell_jobs: Arc<ShellJobManager>,
     debug_logger: Option<Arc<DebugEventLogger>>,
 }
 
@@ -78,10 +85,12 @@ struct ChannelBootstrap {
 struct GatewayTasks {
     bus: MessageBus,
     cron_service: Arc<CronService>,
+    shell_jobs: Arc<ShellJobManager>,
     channel_manager: Arc<ChannelManager>,
     server_shutdown_tx: broadcast::Sender<()>,
     inbound_bridge_handle: JoinHandle<()>,
     neuro_link_bridge_handle: Option<JoinHandle<()>>,
+    automation_handle: JoinHandle<()>,
     outbound_dispatch_handle: JoinHandle<()>,
     channel_handle: JoinHandle<()>,
     agent_handle: JoinHandle<()>,
@@ -143,13 +152,25 @@ fn resolve_provider_name_for_model(
 }
 
 fn build_provider(config: &Config, model: &str) -> Result<LiteLLMClient> {
+    build_provider_for(config, model, None)
+}
+
+/// Build a client for `model`, preferring `provider` when given and the
+/// configured default provider when `model` is the default model.
+fn build_provider_for(
+    config: &Config,
+    model: &str,
+    provider: Option<&str>,
+) -> Result<LiteLLMClient> {
     let catalog = ProviderCatalogService::new();
     let provider_name = resolve_provider_name_for_model(
         config,
         model,
-        (model == config.agents.defaults.model)
-            .then_some(config.agents.defaults.provider.as_deref())
-            .flatten(),
+        provider.or_else(|| {
+            (model == config.agents.defaults.model)
+                .then_some(config.agents.defaults.provider.as_deref())
+                .flatten()
+        }),
     )
     .ok_or_else(|| anyhow::anyhow!("No provider found for model: {}", model))?;
     let access = catalog
@@ -172,6 +193,40 @@ fn build_provider(config: &Config, model: &str) -> Result<LiteLLMClient> {
     ))
 }
 
+/// Subagent profiles from config; profiles naming a model or provider get
+/// their own client.
+fn build_subagent_profiles(config: &Config) -> Vec<SubagentProfile> {
+    config
+        .tools
+        .subagent
+        .profiles
+        .iter()
+        .filter_map(|(name, profile_config)| {
+            let profile = SubagentProfile::from_config(name, profile_config);
+            if profile_config.model.is_none() && profile_config.provider.is_none() {
+                return Some(profile);
+            }
+            let model = profile_config
+                .model
+                .clone()
+                .unwrap_or_else(|| config.agents.defaults.model.clone());
+            match build_provider_for(config, &model, profile_config.provider.as_deref()) {
+                Ok(provider) => Some(
+                    SubagentProfile {
+                        model: Some(model),
+                        ..profile
+                    }
+                    .with_provider(Arc::new(provider)),
+                ),
+                Err(e) => {
+                    tracing::warn!("Skipping subagent profile '{}': {}", name, e);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
 fn build_network_tool_config(config: &Config) -> NetworkToolConfig {
     let api_key = config.tools.web.search.api_key.trim().to_string();
     NetworkToolConfig {
@@ -203,6 +258,7 @@ fn build_builtin_tools_config(config: &Config) -> BuiltInToolsConfig {
         cron: config.tools.builtin.cron,
         mcp: config.tools.builtin.mcp,
         attachment: config.tools.builtin.attachment,
+        memory: config.tools.builtin.memory,
     }
 }
 
@@ -247,109 +303,6 @@ pub async fn start_embedded_gateway_runtime(
     Ok(EmbeddedGatewayRuntime { tasks: Some(tasks) })
 }
 
-async fn start_cron_service(
-    cron_store: PathBuf,
-    bus: MessageBus,
-    debug_logger: Option<Arc<DebugEventLogger>>,
-) -> Arc<CronService> {
-    let cron_service = Arc::new(CronService::new(
-        cron_store,
-        Some(build_cron_callback(bus, debug_logger)),
-    ));
-    cron_service.start().await;
-    cron_service
-}
-
-fn build_cron_callback(
-    bus: MessageBus,
-    debug_logger: Option<Arc<DebugEventLogger>>,
-) -> JobCallback {
-    Arc::new(
-        move |job: agent_diva_core::cron::CronJob,
-              cancel_token|
-              -> std::pin::Pin<Box<dyn std::future::Future<Output = Option<String>> + Send>> {
-            let bus = bus.clone();
-            let debug_logger = debug_logger.clone();
-            Box::pin(async move {
-                if cancel_token.is_cancelled() {
-                    return Some("Error: cancelled".to_string());
-                }
-                let deliver = job.payload.deliver;
-                if !deliver {
-                    return Some("skipped (deliver=false)".to_string());
-                }
-
-                let target_channel = job
-                    .payload
-                    .channel
-                    .clone()
-                    .unwrap_or_else(|| "cli".to_string());
-                let target_chat_id = job
-                    .payload
-                    .to
-                    .clone()
-                    .unwrap_or_else(|| "direct".to_string());
-                let (conversation_channel, conversation_chat_id) = if target_channel == "gui" {
-                    let chat_id = if target_chat_id.starts_with("cron:") {
-                        target_chat_id
-                    } else {
-                        format!("cron:{}", target_chat_id)
-                    };
-                    ("api".to_string(), chat_id)
-                } else {
-                    (target_channel.clone(), target_chat_id)
-                };
-
-                let trace_id = TraceId::new();
-                let inbound = InboundMessage::new(
-                    conversation_channel,
-                    "cron",
-                    conversation_chat_id,
-                    job.payload.message,
-                )
-                .with_metadata("trace_id", trace_id.as_str().to_string())
-                .with_metadata("cron_job_id", job.id.clone())
-                .with_metadata("cron_trigger", "scheduled")
-                .with_metadata("cron_delivery_channel", target_channel);
-
-                if let Some(logger) = &debug_logger {
-                    let session_id = inbound.session_key();
-                    let _ = logger.write_event(DebugEvent::new(
-                        Some(trace_id.as_str().to_string()),
-                        Some(session_id.clone()),
-                        "gateway",
-                        "cron_inbound",
-                        serde_json::json!({
-                            "job_id": job.id,
-                            "channel": inbound.channel,
-                            "chat_id": inbound.chat_id,
-                        }),
-                    ));
-                    let _ = logger.write_raw(DebugEvent::new(
-                        Some(trace_id.as_str().to_string()),
-                        Some(session_id),
-                        "gateway",
-                        "cron_inbound_raw",
-                        serde_json::to_value(&inbound).unwrap_or_else(|error| {
-                            serde_json::json!({"serialization_error": error.to_string()})
-                        }),
-                    ));
-                }
-
-                if let Err(e) = bus.publish_inbound(inbound) {
-                    error!("Failed to publish cron inbound job {}: {}", job.id, e);
-                    return Some(format!(
-                        "failed to publish cron inbound job {}: {}",
-                        job.id, e
-                    ));
-                }
-
-                Some("triggered agent turn".to_string())
-            })
-        },
-    )
-}
-
 #[allow(clippy::too_many_arguments)]
 async fn build_agent_loop(
     config: &Config,
@@ -359,6 +312,7 @@ async fn build_agent_loop(
     runtime_control_rx: mpsc::UnboundedReceiver<RuntimeControlCommand>,
     cron_service: Arc<CronService>,
     file_manager: Arc<FileManager>,
+    shell_jobs: Arc<ShellJobManager>,
     debug_logger: Option<Arc<DebugEventLogger>>,
 ) -> Result<AgentLoop> {
     let agent_provider: Arc<dyn LLMProvider> = dynamic_provider;
@@ -366,9 +320,13 @@ async fn build_agent_loop(
         builtin: build_builtin_tools_config(config),
         network: build_network_tool_config(config),
         exec_timeout: config.tools.exec.timeout,
+        exec_sandbox: ExecSandboxConfig::from(&config.tools.exec),
+        shell_jobs,
         restrict_to_workspace: config.tools.restrict_to_workspace,
         mcp_servers: config.tools.active_mcp_servers(),
+        mcp_manager: Arc::new(McpManager::new()),
         subagent_policy: SubagentPolicy::from(config.tools.subagent.clone()),
+        subagent_profiles: build_subagent_profiles(config),
         cron_service: Some(cron_service),
         soul_context: SoulContextSettings {
             enabled: config.agents.soul.enabled,
@@ -381,6 +339,7 @@ async fn build_agent_loop(
             context_budget_tokens: config.agents.defaults.context_budget_tokens as usize,
             reserve_tokens: config.agents.defaults.context_budget_reserve_tokens as usize,
             overflow_retry_enabled: config.agents.defaults.context_overflow_retry_enabled,
+            compaction_strategy: config.agents.defaults.context_compaction,
         },
         trace_logger: Some(build_runtime_trace_logger(&config.logging)),
         debug_logger,
@@ -390,6 +349,9 @@ async fn build_agent_loop(
             frequent_change_threshold: config.agents.soul.frequent_change_threshold,
             boundary_confirmation_hint: config.agents.soul.boundary_confirmation_hint,
         },
+        memory: config.agents.memory.clone(),
+        sessions: config.agents.sessions.clone(),
+        skills: config.agents.skills.clone(),
     };
 
     // Memory provider wiring — Task 6 (Phase 4).
@@ -424,7 +386,8 @@ async fn build_agent_loop(
         );
     }
 
-    let memory_provider: Option<Arc<dyn agent_diva_core::memory::MemoryProvider>> = None;
+    let memory_provider =
+        agent_diva_core::memory::open_memory_provider(&config.agents.memory, &workspace).await?;
 
     AgentLoop::with_tools_and_memory_provider(
         bus,
@@ -0,0 +1,126 @@
+//! Event sources feeding automation rules inside the gateway
+
+use super::*;
+use agent_diva_core::cron::{AutomationEvent, AutomationTrigger, AUTOMATION_SENDER};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// How often watched workspace directories are scanned for new files
+const FILE_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Watch inbound messages and workspace directories and start the
+/// automation rules they fire
+pub(super) fn spawn_automation_runtime(
+    bus: MessageBus,
+    cron_service: Arc<CronService>,
+    workspace: PathBuf,
+) -> JoinHandle<()> {
+    let mut inbound_rx = bus.subscribe_inbound();
+    tokio::spawn(async move {
+        let mut watcher = DirectoryWatcher::new(workspace);
+        let mut poll = tokio::time::interval(FILE_POLL_INTERVAL);
+        loop {
+            tokio::select! {
+                received = inbound_rx.recv() => match received {
+                    Ok(msg) if msg.sender_id != AUTOMATION_SENDER => {
+                        let event = AutomationEvent::Message {
+                            channel: msg.channel,
+                            chat_id: msg.chat_id,
+                            sender_id: msg.sender_id,
+                            content: msg.content,
+                        };
+                        cron_service.dispatch_event(event).await;
+                    }
+                    Ok(_) => {}
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Automations skipped {} inbound messages", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                _ = poll.tick() => {
+                    for event in watcher.scan(&cron_service).await {
+                        cron_service.dispatch_event(event).await;
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// Polls the directories of `file_created` rules. Files present when a
+/// directory is first seen are taken as existing and never fire. Directories
+/// that resolve outside the workspace, e.g. through a symlink, are skipped.
+struct DirectoryWatcher {
+    workspace: PathBuf,
+    seen: HashMap<String, HashSet<String>>,
+}
+
+impl DirectoryWatcher {
+    fn new(workspace: PathBuf) -> Self {
+        Self {
+            workspace,
+            seen: HashMap::new(),
+        }
+    }
+
+    async fn scan(&mut self, cron_service: &CronService) -> Vec<AutomationEvent> {
+        let dirs: HashSet<String> = cron_service
+            .list_automations()
+            .await
+            .into_iter()
+            .filter(|view| view.rule.enabled)
+            .filter_map(|view| match view.rule.trigger {
+                AutomationTrigger::FileCreated { dir, .. } => Some(dir),
+                _ => None,
+            })
+            .collect();
+        self.seen.retain(|dir, _| dirs.contains(dir));
+
+        let mut events = Vec::new();
+        let workspace = tokio::fs::canonicalize(&self.workspace).await.ok();
+        for dir in dirs {
+            // A missing directory counts as empty, so files fire once it appears.
+            let Ok(path) = tokio::fs::canonicalize(self.workspace.join(&dir)).await else {
+                self.seen.insert(dir, HashSet::new());
+                continue;
+            };
+            if workspace
+                .as_ref()
+                .map_or(true, |workspace| !path.starts_with(workspace))
+            {
+                tracing::debug!("Automation directory '{}' is outside the workspace", dir);
+                continue;
+            }
+            let names = list_files(&path).await;
+            match self.seen.get_mut(&dir) {
+                None => {
+                    self.seen.insert(dir, names);
+                }
+                Some(known) => {
+                    for name in names.difference(known) {
+                        events.push(AutomationEvent::FileCreated {
+                            dir: dir.clone(),
+                            name: name.clone(),
+                            path: path.join(name).display().to_string(),
+                        });
+                    }
+                    *known = names;
+                }
+            }
+        }
+        events
+    }
+}
+
+async fn list_files(dir: &Path) -> HashSet<String> {
+    let mut names = HashSet::new();
+    let Ok(mut entries) = tokio::fs::read_dir(dir).await else {
+        return names;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        if entry.file_type().await.is_ok_and(|kind| kind.is_file()) {
+            names.insert(entry.file_name().to_string_lossy().into_owned());
+        }
+    }
+    names
+}
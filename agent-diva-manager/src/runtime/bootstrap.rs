@@ -15,18 +15,28 @@ pub(super) async fn bootstrap_runtime(runtime: GatewayRuntimeConfig) -> Result<G
         Some(run) => Some(DebugEventLogger::new(run)?),
         None => None,
     };
-    let cron_service = start_cron_service(cron_store, bus.clone(), debug_logger.clone()).await;
+    let (runtime_control_tx, runtime_control_rx) = mpsc::unbounded_channel();
     let dynamic_provider = Arc::new(DynamicProvider::new(Arc::new(build_provider(
         &config,
         &config.agents.defaults.model,
     )?)));
+    let cron_service = start_cron_service(
+        cron_store,
+        config.gateway.cron.run_history,
+        bus.clone(),
+        runtime_control_tx.clone(),
+        dynamic_provider.clone(),
+        workspace.clone(),
+        debug_logger.clone(),
+    )
+    .await;
 
     // Initialize shared FileManager for attachment handling
     let storage_path = default_data_dir_or_fallback();
     let file_config = FileConfig::with_path(&storage_path);
     let file_manager = Arc::new(FileManager::new(file_config).await?);
 
-    let (runtime_control_tx, runtime_control_rx) = mpsc::unbounded_channel();
+    let shell_jobs = Arc::new(ShellJobManager::new());
     let agent = build_agent_loop(
         &config,
         bus.clone(),
@@ -35,6 +45,7 @@ pub(super) async fn bootstrap_runtime(runtime: GatewayRuntimeConfig) -> Result<G
         runtime_control_rx,
         Arc::clone(&cron_service),
         Arc::clone(&file_manager),
+        Arc::clone(&shell_jobs),
         debug_logger.clone(),
     )
     .await?;
@@ -43,6 +54,7 @@ pub(super) async fn bootstrap_runtime(runtime: GatewayRuntimeConfig) -> Result<G
     Ok(GatewayBootstrap {
         config,
         loader,
+        workspace,
         port,
         bus,
         cron_service,
@@ -52,6 +64,7 @@ pub(super) async fn bootstrap_runtime(runtime: GatewayRuntimeConfig) -> Result<G
         provider_api_base,
         agent,
         file_manager,
+        shell_jobs,
         debug_logger,
     })
 }
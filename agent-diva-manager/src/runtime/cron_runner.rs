@@ -0,0 +1,473 @@
+//! Execution of cron job payloads inside the gateway
+
+use super::*;
+use agent_diva_core::bus::OutboundMessage;
+use agent_diva_core::cron::{CronHttpRequest, CronJob, CronPayloadKind};
+use agent_diva_neuron::{Workflow, WorkflowExecutor, WorkflowNodeKind, WorkflowTools};
+use async_trait::async_trait;
+use tokio::sync::oneshot;
+use tokio_util::sync::CancellationToken;
+
+/// How long a cron-triggered turn may stay silent before its run is failed
+const CRON_TURN_IDLE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(600);
+const CRON_HTTP_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+/// Response bytes of an `http` payload passed on to the agent
+const CRON_HTTP_BODY_LIMIT: usize = 8_000;
+
+pub(super) async fn start_cron_service(
+    cron_store: PathBuf,
+    run_history: usize,
+    bus: MessageBus,
+    runtime_control_tx: mpsc::UnboundedSender<RuntimeControlCommand>,
+    provider: Arc<dyn LLMProvider>,
+    workspace: PathBuf,
+    debug_logger: Option<Arc<DebugEventLogger>>,
+) -> Arc<CronService> {
+    let runner = CronJobRunner {
+        bus,
+        runtime_control_tx,
+        provider,
+        workspace,
+        debug_logger,
+    };
+    let cron_service = Arc::new(
+        CronService::new(cron_store, Some(build_cron_callback(runner)))
+            .with_run_retention(run_history),
+    );
+    cron_service.start().await;
+    cron_service
+}
+
+fn build_cron_callback(runner: CronJobRunner) -> JobCallback {
+    Arc::new(
+        move |job: CronJob,
+              cancel_token|
+              -> std::pin::Pin<Box<dyn std::future::Future<Output = CronJobOutcome> + Send>> {
+            let runner = runner.clone();
+            Box::pin(async move { runner.run(job, cancel_token).await })
+        },
+    )
+}
+
+/// Where a job delivers to, and the conversation its agent turns run in
+struct DeliveryTarget {
+    channel: String,
+    conversation_channel: String,
+    conversation_chat_id: String,
+}
+
+impl DeliveryTarget {
+    fn for_job(job: &CronJob) -> Self {
+        let channel = job
+            .payload
+            .channel
+            .clone()
+            .unwrap_or_else(|| "cli".to_string());
+        let chat_id = job
+            .payload
+            .to
+            .clone()
+            .unwrap_or_else(|| "direct".to_string());
+        let (conversation_channel, conversation_chat_id) = if channel == "gui" {
+            let chat_id = if chat_id.starts_with("cron:") {
+                chat_id
+            } else {
+                format!("cron:{}", chat_id)
+            };
+            ("api".to_string(), chat_id)
+        } else {
+            (channel.clone(), chat_id)
+        };
+        Self {
+            channel,
+            conversation_channel,
+            conversation_chat_id,
+        }
+    }
+}
+
+/// Tools report failures as text starting with "Error"
+fn is_error_result(result: &str) -> bool {
+    result.to_ascii_lowercase().starts_with("error")
+}
+
+/// Call a tool of the running agent for the job's conversation and wait for
+/// its result
+async fn invoke_runtime_tool(
+    runtime_control_tx: &mpsc::UnboundedSender<RuntimeControlCommand>,
+    target: &DeliveryTarget,
+    name: &str,
+    args: serde_json::Value,
+) -> String {
+    let (reply_tx, reply_rx) = oneshot::channel();
+    let sent = runtime_control_tx.send(RuntimeControlCommand::InvokeTool {
+        name: name.to_string(),
+        args,
+        channel: target.conversation_channel.clone(),
+        chat_id: target.conversation_chat_id.clone(),
+        reply_tx,
+    });
+    if sent.is_err() {
+        return "Error: agent runtime is not available".to_string();
+    }
+    reply_rx
+        .await
+        .unwrap_or_else(|_| format!("Error: tool '{}' did not report a result", name))
+}
+
+/// Runs the tool nodes of a workflow against the agent's tool registry
+struct RuntimeWorkflowTools {
+    runtime_control_tx: mpsc::UnboundedSender<RuntimeControlCommand>,
+    target: DeliveryTarget,
+}
+
+#[async_trait]
+impl WorkflowTools for RuntimeWorkflowTools {
+    async fn call_tool(
+        &self,
+        name: &str,
+        args: serde_json::Value,
+    ) -> std::result::Result<String, String> {
+        let result = invoke_runtime_tool(&self.runtime_control_tx, &self.target, name, args).await;
+        if is_error_result(&result) {
+            Err(result)
+        } else {
+            Ok(result)
+        }
+    }
+}
+
+#[derive(Clone)]
+struct CronJobRunner {
+    bus: MessageBus,
+    runtime_control_tx: mpsc::UnboundedSender<RuntimeControlCommand>,
+    provider: Arc<dyn LLMProvider>,
+    workspace: PathBuf,
+    debug_logger: Option<Arc<DebugEventLogger>>,
+}
+
+impl CronJobRunner {
+    async fn run(&self, job: CronJob, cancel_token: CancellationToken) -> CronJobOutcome {
+        if cancel_token.is_cancelled() {
+            return CronJobOutcome::failed("Error: cancelled");
+        }
+        match job.payload.kind {
+            CronPayloadKind::AgentTurn => {
+                if !job.payload.deliver {
+                    return CronJobOutcome::with_response("skipped (deliver=false)");
+                }
+                let message = job.payload.message.clone();
+                self.agent_turn(&job, message, &cancel_token).await
+            }
+            CronPayloadKind::Message => match self.deliver_text(&job, &job.payload.message) {
+                Ok(()) => CronJobOutcome::with_response(job.payload.message.clone()),
+                Err(e) => CronJobOutcome::failed(e),
+            },
+            CronPayloadKind::Tool => self.invoke_tool(&job, &cancel_token).await,
+            CronPayloadKind::Http => self.http_turn(&job, &cancel_token).await,
+            CronPayloadKind::Workflow => self.run_workflow(&job, &cancel_token).await,
+        }
+    }
+
+    /// Send fixed text to the job's target without an agent turn
+    fn deliver_text(&self, job: &CronJob, text: &str) -> std::result::Result<(), String> {
+        let target = DeliveryTarget::for_job(job);
+        let published = if target.channel == "gui" {
+            // The GUI follows the event stream of its cron conversation.
+            self.bus.publish_event(
+                target.conversation_channel,
+                target.conversation_chat_id,
+                AgentEvent::FinalResponse {
+                    content: text.to_string(),
+                },
+            )
+        } else {
+            self.bus.publish_outbound(
+                OutboundMessage::new(target.channel, target.conversation_chat_id, text)
+                    .with_metadata("cron_job_id", job.id.clone()),
+            )
+        };
+        published.map_err(|e| {
+            error!("Failed to deliver cron job {}: {}", job.id, e);
+            format!("Error: failed to deliver cron job {}: {}", job.id, e)
+        })
+    }
+
+    async fn invoke_tool(&self, job: &CronJob, cancel_token: &CancellationToken) -> CronJobOutcome {
+        let Some(tool) = job.payload.tool.clone() else {
+            return CronJobOutcome::failed("Error: tool payload without a tool");
+        };
+        let args = if tool.args.is_null() {
+            serde_json::json!({})
+        } else {
+            tool.args
+        };
+        let target = DeliveryTarget::for_job(job);
+        let result = tokio::select! {
+            _ = cancel_token.cancelled() => "Error: cancelled".to_string(),
+            result = invoke_runtime_tool(
+                &self.runtime_control_tx,
+                &target,
+                &tool.name,
+                args,
+            ) => result,
+        };
+        let mut outcome = CronJobOutcome {
+            tool_calls: 1,
+            ..CronJobOutcome::with_response(result.clone())
+        };
+        if is_error_result(&result) {
+            outcome.fail(result);
+        } else if job.payload.deliver {
+            if let Err(e) = self.deliver_text(job, &result) {
+                outcome.fail(e);
+            }
+        }
+        outcome
+    }
+
+    async fn run_workflow(
+        &self,
+        job: &CronJob,
+        cancel_token: &CancellationToken,
+    ) -> CronJobOutcome {
+        let Some(run) = job.payload.workflow.as_ref() else {
+            return CronJobOutcome::failed("Error: workflow payload without a workflow");
+        };
+        let path = PathBuf::from(&run.path);
+        let path = if path.is_relative() {
+            self.workspace.join(path)
+        } else {
+            path
+        };
+        let workflow = match Workflow::from_path(&path) {
+            Ok(workflow) => workflow,
+            Err(e) => {
+                return CronJobOutcome::failed(format!(
+                    "Error: failed to load workflow {}: {}",
+                    path.display(),
+                    e
+                ))
+            }
+        };
+        let executor = WorkflowExecutor::new(self.provider.clone()).with_tools(Arc::new(
+            RuntimeWorkflowTools {
+                runtime_control_tx: self.runtime_control_tx.clone(),
+                target: DeliveryTarget::for_job(job),
+            },
+        ));
+
+        let result = tokio::select! {
+            _ = cancel_token.cancelled() => {
+                return CronJobOutcome::failed("Error: cancelled");
+            }
+            result = executor.run(&workflow, run.inputs.clone(), None) => result,
+        };
+        let finished = match result {
+            Ok(finished) => finished,
+            Err(e) => {
+                return CronJobOutcome::failed(format!(
+                    "Error: workflow '{}' failed: {}",
+                    workflow.name, e
+                ))
+            }
+        };
+        let tool_calls = workflow
+            .nodes
+            .iter()
+            .filter(|node| {
+                matches!(node.kind, WorkflowNodeKind::Tool { .. })
+                    && finished.outputs.contains_key(&node.id)
+            })
+            .count();
+        let response = finished.output.unwrap_or_default();
+        let mut outcome = CronJobOutcome {
+            tool_calls,
+            ..CronJobOutcome::with_response(response.clone())
+        };
+        if job.payload.deliver && !response.trim().is_empty() {
+            if let Err(e) = self.deliver_text(job, &response) {
+                outcome.fail(e);
+            }
+        }
+        outcome
+    }
+
+    async fn http_turn(&self, job: &CronJob, cancel_token: &CancellationToken) -> CronJobOutcome {
+        let Some(http) = job.payload.http.as_ref() else {
+            return CronJobOutcome::failed("Error: http payload without a request");
+        };
+        let fetched = tokio::select! {
+            _ = cancel_token.cancelled() => Err("cancelled".to_string()),
+            fetched = fetch(http) => fetched,
+        };
+        let (status, body) = match fetched {
+            Ok(response) => response,
+            Err(e) => {
+                return CronJobOutcome::failed(format!(
+                    "Error: {} {} failed: {}",
+                    http.method, http.url, e
+                ))
+            }
+        };
+        // Error statuses fail the run, so the job's retry policy applies.
+        if status >= 400 {
+            return CronJobOutcome::failed(format!(
+                "Error: {} {} returned HTTP {}",
+                http.method.to_ascii_uppercase(),
+                http.url,
+                status
+            ));
+        }
+        if !job.payload.deliver {
+            return CronJobOutcome::with_response(format!(
+                "HTTP {} (deliver=false, agent turn skipped)",
+                status
+            ));
+        }
+
+        let mut prompt = String::new();
+        if !job.payload.message.trim().is_empty() {
+            prompt.push_str(job.payload.message.trim());
+            prompt.push_str("\n\n");
+        }
+        prompt.push_str(&format!(
+            "Scheduled request {} {} returned HTTP {}:\n\n{}",
+            http.method.to_ascii_uppercase(),
+            http.url,
+            status,
+            body
+        ));
+        self.agent_turn(job, prompt, cancel_token).await
+    }
+
+    /// Run `message` as a user turn in the job's conversation and wait for
+    /// the agent's final response
+    async fn agent_turn(
+        &self,
+        job: &CronJob,
+        message: String,
+        cancel_token: &CancellationToken,
+    ) -> CronJobOutcome {
+        let target = DeliveryTarget::for_job(job);
+        let trace_id = TraceId::new();
+        let inbound = InboundMessage::new(
+            target.conversation_channel,
+            "cron",
+            target.conversation_chat_id,
+            message,
+        )
+        .with_metadata("trace_id", trace_id.as_str().to_string())
+        .with_metadata("cron_job_id", job.id.clone())
+        .with_metadata("cron_trigger", "scheduled")
+        .with_metadata("cron_delivery_channel", target.channel);
+
+        if let Some(logger) = &self.debug_logger {
+            let session_id = inbound.session_key();
+            let _ = logger.write_event(DebugEvent::new(
+                Some(trace_id.as_str().to_string()),
+                Some(session_id.clone()),
+                "gateway",
+                "cron_inbound",
+                serde_json::json!({
+                    "job_id": job.id,
+                    "channel": inbound.channel,
+                    "chat_id": inbound.chat_id,
+                }),
+            ));
+            let _ = logger.write_raw(DebugEvent::new(
+                Some(trace_id.as_str().to_string()),
+                Some(session_id),
+                "gateway",
+                "cron_inbound_raw",
+                serde_json::to_value(&inbound).unwrap_or_else(
+                    |error| serde_json::json!({"serialization_error": error.to_string()}),
+                ),
+            ));
+        }
+
+        let mut event_rx = self.bus.subscribe_events();
+        let mut outcome = CronJobOutcome {
+            trace_id: Some(trace_id.as_str().to_string()),
+            ..Default::default()
+        };
+        if let Err(e) = self.bus.publish_inbound(inbound) {
+            error!("Failed to publish cron inbound job {}: {}", job.id, e);
+            outcome.fail(format!(
+                "Error: failed to publish cron inbound job {}: {}",
+                job.id, e
+            ));
+            return outcome;
+        }
+
+        loop {
+            let bus_event = tokio::select! {
+                _ = cancel_token.cancelled() => {
+                    outcome.fail("Error: cancelled");
+                    break;
+                }
+                received = tokio::time::timeout(CRON_TURN_IDLE_TIMEOUT, event_rx.recv()) => {
+                    match received {
+                        Ok(Ok(bus_event)) => bus_event,
+                        Ok(Err(broadcast::error::RecvError::Lagged(_))) => continue,
+                        Ok(Err(broadcast::error::RecvError::Closed)) => {
+                            outcome.fail("Error: event bus closed");
+                            break;
+                        }
+                        Err(_) => {
+                            outcome.fail("Error: timed out waiting for agent");
+                            break;
+                        }
+                    }
+                }
+            };
+            // Other turns may run in the same conversation; only follow this one.
+            if bus_event.trace_id.as_deref() != Some(trace_id.as_str()) {
+                continue;
+            }
+            match bus_event.event {
+                AgentEvent::ToolCallStarted { .. } => outcome.tool_calls += 1,
+                AgentEvent::FinalResponse { content } => {
+                    outcome.response = Some(content);
+                    break;
+                }
+                AgentEvent::Error { message } => {
+                    outcome.fail(format!("Error: {}", message));
+                    break;
+                }
+                _ => {}
+            }
+        }
+        outcome
+    }
+}
+
+/// Perform the request of an `http` payload. Returns the status code and
+/// the (truncated) response body.
+async fn fetch(http: &CronHttpRequest) -> std::result::Result<(u16, String), String> {
+    let method = reqwest::Method::from_bytes(http.method.to_ascii_uppercase().as_bytes())
+        .map_err(|e| e.to_string())?;
+    let client = reqwest::Client::builder()
+        .timeout(CRON_HTTP_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let mut request = client.request(method, &http.url);
+    for (name, value) in &http.headers {
+        request = request.header(name, value);
+    }
+    if let Some(body) = &http.body {
+        request = request.body(body.clone());
+    }
+    let response = request.send().await.map_err(|e| e.to_string())?;
+    let status = response.status().as_u16();
+    let mut body = response.text().await.map_err(|e| e.to_string())?;
+    if body.len() > CRON_HTTP_BODY_LIMIT {
+        let mut end = CRON_HTTP_BODY_LIMIT;
+        while !body.is_char_boundary(end) {
+            end -= 1;
+        }
+        body.truncate(end);
+        body.push_str("\n[truncated]");
+    }
+    Ok((status, body))
+}
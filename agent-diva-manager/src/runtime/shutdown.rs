@@ -35,11 +35,15 @@ pub(super) async fn shutdown_runtime(tasks: GatewayTasks, manager_handle_complet
         let _ = handle.await;
     }
 
+    tasks.automation_handle.abort();
+    let _ = tasks.automation_handle.await;
+
     tasks.outbound_dispatch_handle.abort();
     let _ = tasks.outbound_dispatch_handle.await;
 
     tasks.agent_handle.abort();
     let _ = tasks.agent_handle.await;
+    tasks.shell_jobs.shutdown().await;
 
     tasks.channel_handle.abort();
     let _ = tasks.channel_handle.await;
@@ -44,6 +44,7 @@ async fn start_runtime_tasks_inner(
     let GatewayBootstrap {
         config,
         loader,
+        workspace,
         port,
         bus,
         cron_service,
@@ -53,6 +54,7 @@ async fn start_runtime_tasks_inner(
         provider_api_base,
         agent,
         file_manager,
+        shell_jobs,
         debug_logger,
     } = bootstrap;
     let ChannelBootstrap {
@@ -91,6 +93,8 @@ async fn start_runtime_tasks_inner(
     );
     let api_tx_keepalive = api_tx.clone();
 
+    let automation_handle =
+        spawn_automation_runtime(bus.clone(), Arc::clone(&cron_service), workspace);
     let outbound_dispatch_handle = spawn_outbound_dispatch(bus.clone());
     let channel_handle = spawn_channel_runtime(channel_manager.clone());
     let agent_handle = spawn_agent_runtime(agent);
@@ -119,10 +123,12 @@ async fn start_runtime_tasks_inner(
     GatewayTasks {
         bus,
         cron_service,
+        shell_jobs,
         channel_manager,
         server_shutdown_tx,
         inbound_bridge_handle,
         neuro_link_bridge_handle,
+        automation_handle,
         outbound_dispatch_handle,
         channel_handle,
         agent_handle,
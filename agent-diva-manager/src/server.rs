@@ -9,17 +9,26 @@ use tower_http::cors::CorsLayer;
 use tower_http::trace::TraceLayer;
 
 use crate::handlers::{
-    add_provider_model_handler, chat_handler, create_cron_job_handler, create_mcp_handler,
-    create_provider_handler, delete_cron_job_handler, delete_mcp_handler, delete_provider_handler,
-    delete_provider_model_handler, delete_session_handler, delete_skill_handler, events_handler,
-    get_channels_handler, get_config_handler, get_cron_job_handler, get_mcps_handler,
-    get_provider_handler, get_provider_models_handler, get_providers_handler,
+    add_provider_model_handler, automation_webhook_handler, chat_handler,
+    consolidate_session_memory_handler, create_automation_handler, create_cron_job_handler,
+    create_mcp_handler, create_provider_handler, delete_automation_handler,
+    delete_cron_job_handler, delete_mcp_handler, delete_memory_file_handler,
+    delete_memory_history_entry_handler, delete_provider_handler, delete_provider_model_handler,
+    delete_session_handler, delete_skill_handler, edit_session_message_handler, events_handler,
+    export_session_handler, forget_memory_fact_handler, fork_session_handler,
+    get_automation_handler, get_channels_handler, get_config_handler, get_cron_job_handler,
+    get_mcps_handler, get_provider_handler, get_provider_models_handler, get_providers_handler,
     get_session_history_handler, get_sessions_handler, get_skills_handler, get_tools_handler,
-    heartbeat_handler, list_cron_jobs_handler, refresh_mcp_status_handler, reset_session_handler,
-    resolve_provider_handler, run_cron_job_handler, set_cron_job_enabled_handler,
-    set_mcp_enabled_handler, stop_chat_handler, stop_cron_job_handler, update_channel_handler,
-    update_config_handler, update_cron_job_handler, update_mcp_handler, update_provider_handler,
-    update_tools_handler, upload_file_handler, upload_skill_handler,
+    heartbeat_handler, import_session_handler, install_skill_handler, list_automation_runs_handler,
+    list_automations_handler, list_cron_jobs_handler, list_cron_runs_handler,
+    list_memory_files_handler, list_memory_history_handler, read_memory_file_handler,
+    refresh_mcp_status_handler, remember_memory_fact_handler, reset_session_handler,
+    resolve_provider_handler, rewind_session_handler, run_cron_job_handler, search_memory_handler,
+    search_sessions_handler, set_cron_job_enabled_handler, set_mcp_enabled_handler,
+    stop_automation_handler, stop_chat_handler, stop_cron_job_handler, update_automation_handler,
+    update_channel_handler, update_config_handler, update_cron_job_handler, update_mcp_handler,
+    update_provider_handler, update_skills_handler, update_tools_handler, upload_file_handler,
+    upload_skill_handler, write_memory_file_handler,
 };
 use crate::state::AppState;
 
@@ -88,6 +97,8 @@ fn runtime_routes() -> Router<AppState> {
         .route("/api/chat/stop", post(stop_chat_handler))
         .route("/api/events", get(events_handler))
         .route("/api/sessions", get(get_sessions_handler))
+        .route("/api/sessions/search", get(search_sessions_handler))
+        .route("/api/sessions/import", post(import_session_handler))
         .route(
             "/api/sessions/:id",
             get(get_session_history_handler)
@@ -95,6 +106,10 @@ fn runtime_routes() -> Router<AppState> {
                 .post(delete_session_handler),
         )
         .route("/api/sessions/reset", post(reset_session_handler))
+        .route("/api/sessions/:id/rewind", post(rewind_session_handler))
+        .route("/api/sessions/:id/fork", post(fork_session_handler))
+        .route("/api/sessions/:id/edit", post(edit_session_message_handler))
+        .route("/api/sessions/:id/export", get(export_session_handler))
         .route(
             "/api/config",
             get(get_config_handler).post(update_config_handler),
@@ -111,11 +126,34 @@ fn runtime_routes() -> Router<AppState> {
             "/api/skills",
             get(get_skills_handler).post(upload_skill_handler),
         )
+        .route("/api/skills/install", post(install_skill_handler))
+        .route("/api/skills/update", post(update_skills_handler))
         .route("/api/skills/:name", delete(delete_skill_handler))
         .route(
             "/api/files/upload",
             post(upload_file_handler).layer(DefaultBodyLimit::max(50 * 1024 * 1024)),
         ) // 50MB limit
+        .route("/api/memory/files", get(list_memory_files_handler))
+        .route(
+            "/api/memory/files/:name",
+            get(read_memory_file_handler)
+                .put(write_memory_file_handler)
+                .delete(delete_memory_file_handler),
+        )
+        .route("/api/memory/history", get(list_memory_history_handler))
+        .route(
+            "/api/memory/history/:index",
+            delete(delete_memory_history_entry_handler),
+        )
+        .route("/api/memory/search", get(search_memory_handler))
+        .route(
+            "/api/memory/facts",
+            post(remember_memory_fact_handler).delete(forget_memory_fact_handler),
+        )
+        .route(
+            "/api/memory/consolidate",
+            post(consolidate_session_memory_handler),
+        )
         .route("/api/mcps", get(get_mcps_handler).post(create_mcp_handler))
         .route(
             "/api/mcps/:name",
@@ -139,6 +177,26 @@ fn runtime_routes() -> Router<AppState> {
         )
         .route("/api/cron/jobs/:id/run", post(run_cron_job_handler))
         .route("/api/cron/jobs/:id/stop", post(stop_cron_job_handler))
+        .route("/api/cron/jobs/:id/runs", get(list_cron_runs_handler))
+        .route(
+            "/api/automations",
+            get(list_automations_handler).post(create_automation_handler),
+        )
+        .route(
+            "/api/automations/:id",
+            get(get_automation_handler)
+                .put(update_automation_handler)
+                .delete(delete_automation_handler),
+        )
+        .route("/api/automations/:id/stop", post(stop_automation_handler))
+        .route(
+            "/api/automations/:id/runs",
+            get(list_automation_runs_handler),
+        )
+        .route(
+            "/api/automations/:id/webhook",
+            post(automation_webhook_handler),
+        )
 }
 
 fn provider_routes() -> Router<AppState> {
@@ -0,0 +1,875 @@
+//! Skill packages
+//!
+//! A package is a skill folder, or a zip/tarball of one, with an optional
+//! `skill.json` manifest next to `SKILL.md`:
+//!
+//! ```json
+//! {
+//!   "name": "pdf-tools",
+//!   "version": "1.2.0",
+//!   "requires": { "bins": ["pdftotext"], "env": [] },
+//!   "dependencies": { "ocr": "^0.3" },
+//!   "scripts": ["scripts/extract.sh"],
+//!   "checksums": { "scripts/extract.sh": "<sha256 hex>" }
+//! }
+//! ```
+//!
+//! Folders without a manifest install as version `0.0.0` with requirements
+//! taken from the SKILL.md runtime metadata. Installed packages are recorded
+//! in `skills/skills.lock.json` so they can be updated from their source.
+
+use agent_diva_agent::skills::{SkillRuntimeMetadata, SkillsLoader};
+use anyhow::{anyhow, Context};
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::skill_service::{
+    extract_archive, list_archive_entries, normalize_archive_path, sanitize_skill_name,
+    shared_archive_root, SkillDto,
+};
+
+pub const MANIFEST_FILE: &str = "skill.json";
+pub const LOCKFILE_NAME: &str = "skills.lock.json";
+/// Version given to skills without a manifest
+pub const UNVERSIONED: &str = "0.0.0";
+const STAGING_DIR: &str = ".staging";
+
+fn default_version() -> String {
+    UNVERSIONED.to_string()
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SkillRequirements {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bins: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub env: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SkillManifest {
+    pub name: String,
+    #[serde(default = "default_version")]
+    pub version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub requires: SkillRequirements,
+    /// Other skills this one needs, as name -> semver requirement
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub dependencies: BTreeMap<String, String>,
+    /// Bundled scripts relative to the package root; made executable on install
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scripts: Vec<String>,
+    /// SHA-256 of bundled files, relative path -> hex digest
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub checksums: BTreeMap<String, String>,
+}
+
+impl SkillManifest {
+    /// Read `skill.json` from `root`, or derive a manifest from SKILL.md.
+    /// Requirements declared in the SKILL.md runtime metadata are merged in.
+    pub fn load(root: &Path) -> anyhow::Result<Self> {
+        let skill_md = fs::read_to_string(root.join("SKILL.md"))
+            .map_err(|_| anyhow!("package must contain SKILL.md"))?;
+        let frontmatter = SkillsLoader::parse_skill_metadata(&skill_md);
+        let runtime = SkillsLoader::runtime_metadata_of(&frontmatter);
+
+        let manifest_path = root.join(MANIFEST_FILE);
+        let mut manifest = if manifest_path.exists() {
+            let raw = fs::read_to_string(&manifest_path)
+                .with_context(|| format!("failed to read {}", manifest_path.display()))?;
+            serde_json::from_str::<Self>(&raw)
+                .with_context(|| format!("invalid {}", MANIFEST_FILE))?
+        } else {
+            let name = frontmatter
+                .name
+                .clone()
+                .or_else(|| {
+                    root.file_name()
+                        .and_then(|name| name.to_str())
+                        .map(str::to_string)
+                })
+                .unwrap_or_default();
+            Self {
+                name,
+                version: default_version(),
+                description: None,
+                requires: SkillRequirements::default(),
+                dependencies: BTreeMap::new(),
+                scripts: Vec::new(),
+                checksums: BTreeMap::new(),
+            }
+        };
+        if manifest.description.is_none() {
+            manifest.description = frontmatter.description;
+        }
+        for bin in runtime.requires_bins {
+            if !manifest.requires.bins.contains(&bin) {
+                manifest.requires.bins.push(bin);
+            }
+        }
+        for env in runtime.requires_env {
+            if !manifest.requires.env.contains(&env) {
+                manifest.requires.env.push(env);
+            }
+        }
+        Ok(manifest)
+    }
+
+    /// Check the manifest against the files under `root`
+    pub fn verify(&self, root: &Path) -> anyhow::Result<()> {
+        if sanitize_skill_name(&self.name).is_empty() {
+            return Err(anyhow!("package manifest has no usable name"));
+        }
+        Version::parse(&self.version)
+            .with_context(|| format!("invalid package version '{}'", self.version))?;
+        for (dependency, requirement) in &self.dependencies {
+            VersionReq::parse(requirement).with_context(|| {
+                format!(
+                    "invalid version requirement '{}' for dependency '{}'",
+                    requirement, dependency
+                )
+            })?;
+        }
+        for script in &self.scripts {
+            if !package_file(root, script)?.is_file() {
+                return Err(anyhow!("bundled script '{}' is missing", script));
+            }
+        }
+        for (file, expected) in &self.checksums {
+            let path = package_file(root, file)?;
+            let actual = sha256_file(&path)
+                .with_context(|| format!("checksummed file '{}' is missing", file))?;
+            if !actual.eq_ignore_ascii_case(expected.trim()) {
+                return Err(anyhow!("checksum mismatch for '{}'", file));
+            }
+        }
+        Ok(())
+    }
+
+    /// Missing binaries, environment variables and skill dependencies.
+    ///
+    /// `installed` maps skill names to their package version; skills without
+    /// one (plain folders, builtins) satisfy any requirement.
+    pub fn unmet_requirements(&self, installed: &HashMap<String, Option<Version>>) -> Vec<String> {
+        let mut unmet = SkillRuntimeMetadata {
+            requires_bins: self.requires.bins.clone(),
+            requires_env: self.requires.env.clone(),
+            ..Default::default()
+        }
+        .missing();
+        for (dependency, requirement) in &self.dependencies {
+            let satisfied = match installed.get(dependency) {
+                None => false,
+                Some(None) => true,
+                Some(Some(version)) => {
+                    VersionReq::parse(requirement).is_ok_and(|req| req.matches(version))
+                }
+            };
+            if !satisfied {
+                unmet.push(format!("SKILL: {} {}", dependency, requirement));
+            }
+        }
+        unmet
+    }
+}
+
+/// Where a package was installed from
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PackageSource {
+    Directory {
+        path: String,
+    },
+    Archive {
+        path: String,
+    },
+    Git {
+        url: String,
+        /// Branch or tag to clone
+        #[serde(rename = "ref", default, skip_serializing_if = "Option::is_none")]
+        reference: Option<String>,
+        /// Commit that was installed
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        commit: Option<String>,
+    },
+    Upload {
+        file_name: String,
+    },
+}
+
+impl PackageSource {
+    /// Classify a CLI/API source string: a git URL, a package directory or
+    /// a `.zip`, `.tar`, `.tar.gz` or `.tgz` archive
+    pub fn parse(source: &str, reference: Option<&str>) -> anyhow::Result<Self> {
+        let source = source.trim();
+        if source.is_empty() {
+            return Err(anyhow!("package source is empty"));
+        }
+        let reference = reference
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string);
+        let git_url = source.strip_prefix("git+").unwrap_or(source);
+        let is_git = source.starts_with("git+")
+            || source.contains("://")
+            || source.contains("::")
+            || is_scp_like(source)
+            || source.ends_with(".git");
+        if is_git {
+            check_git_url(git_url)?;
+            if reference
+                .as_deref()
+                .is_some_and(|value| value.starts_with('-'))
+            {
+                return Err(anyhow!("invalid git ref"));
+            }
+            return Ok(Self::Git {
+                url: git_url.to_string(),
+                reference,
+                commit: None,
+            });
+        }
+        if reference.is_some() {
+            return Err(anyhow!("a ref can only be used with git sources"));
+        }
+
+        let path = crate::skill_service::expand_tilde(source);
+        let path = fs::canonicalize(&path)
+            .with_context(|| format!("package source {} does not exist", path.display()))?;
+        let display = path.display().to_string();
+        if path.is_dir() {
+            Ok(Self::Directory { path: display })
+        } else if archive_kind(&path).is_some() {
+            Ok(Self::Archive { path: display })
+        } else {
+            Err(anyhow!(
+                "unsupported package source {}: expected a directory, git URL, .zip or tarball",
+                display
+            ))
+        }
+    }
+}
+
+/// Schemes git sources may use; anything else (`http://`, `ext::` and other
+/// remote helpers) is refused.
+const GIT_SCHEMES: &[&str] = &["https", "ssh", "git", "file"];
+
+/// Refuse git URLs that git could read as an option or hand to a transport
+/// other than [`GIT_SCHEMES`].
+fn check_git_url(url: &str) -> anyhow::Result<()> {
+    if url.starts_with('-') {
+        return Err(anyhow!("invalid git URL {}", url));
+    }
+    let allowed = match url.split_once("://") {
+        Some((scheme, _)) => GIT_SCHEMES.contains(&scheme.to_ascii_lowercase().as_str()),
+        None => is_scp_like(url),
+    };
+    if allowed {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "unsupported git URL {}: use an https://, ssh://, git:// or file:// URL, or user@host:path",
+            url
+        ))
+    }
+}
+
+/// `user@host:path`, git's short form of an ssh URL
+fn is_scp_like(url: &str) -> bool {
+    url.split_once(':').is_some_and(|(host, path)| {
+        host.contains('@')
+            && !host.contains('/')
+            && !host.starts_with('-')
+            && !path.starts_with(':')
+    })
+}
+
+impl fmt::Display for PackageSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Directory { path } | Self::Archive { path } => write!(f, "{}", path),
+            Self::Git { url, reference, .. } => match reference {
+                Some(reference) => write!(f, "{}#{}", url, reference),
+                None => write!(f, "{}", url),
+            },
+            Self::Upload { file_name } => write!(f, "upload:{}", file_name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LockedSkill {
+    pub version: String,
+    pub source: PackageSource,
+    /// SHA-256 over every installed file
+    pub checksum: String,
+    pub installed_at: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub dependencies: BTreeMap<String, String>,
+}
+
+/// `skills/skills.lock.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SkillLockfile {
+    #[serde(default)]
+    pub skills: BTreeMap<String, LockedSkill>,
+}
+
+impl SkillLockfile {
+    pub fn load(skills_dir: &Path) -> anyhow::Result<Self> {
+        let path = skills_dir.join(LOCKFILE_NAME);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let raw = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        serde_json::from_str(&raw).with_context(|| format!("invalid {}", path.display()))
+    }
+
+    pub fn save(&self, skills_dir: &Path) -> anyhow::Result<()> {
+        let path = skills_dir.join(LOCKFILE_NAME);
+        let tmp = skills_dir.join(format!(".{}.tmp", LOCKFILE_NAME));
+        fs::write(&tmp, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("failed to write {}", tmp.display()))?;
+        fs::rename(&tmp, &path).with_context(|| format!("failed to write {}", path.display()))
+    }
+
+    /// Locked skills that declare a dependency on `name`
+    pub fn dependents(&self, name: &str) -> Vec<String> {
+        self.skills
+            .iter()
+            .filter(|(other, locked)| *other != name && locked.dependencies.contains_key(name))
+            .map(|(other, _)| other.clone())
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SkillInstallOutcome {
+    pub skill: SkillDto,
+    /// Locked version that was replaced, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_version: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SkillUpdateStatus {
+    Updated,
+    Unchanged,
+    Skipped,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SkillUpdateOutcome {
+    pub name: String,
+    pub status: SkillUpdateStatus,
+    pub from_version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unmet_requirements: Vec<String>,
+}
+
+/// A fetched package in `skills/.staging`, removed when dropped
+pub(crate) struct StagedPackage {
+    staging: PathBuf,
+    pub root: PathBuf,
+    pub source: PackageSource,
+}
+
+impl Drop for StagedPackage {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.staging);
+    }
+}
+
+fn new_staging_dir(skills_dir: &Path) -> anyhow::Result<PathBuf> {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos())
+        .unwrap_or_default();
+    let staging = skills_dir
+        .join(STAGING_DIR)
+        .join(format!("{}-{}", std::process::id(), nanos));
+    fs::create_dir_all(&staging)
+        .with_context(|| format!("failed to create staging directory {}", staging.display()))?;
+    Ok(staging)
+}
+
+/// Fetch `source` into the staging area
+pub(crate) fn stage_package(
+    skills_dir: &Path,
+    source: &PackageSource,
+) -> anyhow::Result<StagedPackage> {
+    let staging = new_staging_dir(skills_dir)?;
+    let mut staged = StagedPackage {
+        root: staging.clone(),
+        staging,
+        source: source.clone(),
+    };
+    let fetched = staged.staging.join("package");
+    match source {
+        PackageSource::Directory { path } => copy_dir(Path::new(path), &fetched)?,
+        PackageSource::Archive { path } => {
+            fs::create_dir_all(&fetched)?;
+            let path = Path::new(path);
+            let bytes =
+                fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+            match archive_kind(path) {
+                Some(ArchiveKind::Zip) => {
+                    let root = shared_archive_root(&list_archive_entries(&bytes)?);
+                    extract_archive(&bytes, &fetched, root.as_deref())?;
+                }
+                Some(ArchiveKind::Tar) => extract_tar(&bytes[..], &fetched)?,
+                Some(ArchiveKind::TarGz) => {
+                    extract_tar(flate2::read::GzDecoder::new(&bytes[..]), &fetched)?
+                }
+                None => return Err(anyhow!("unsupported archive {}", path.display())),
+            }
+        }
+        PackageSource::Git { url, reference, .. } => {
+            let commit = git_clone(url, reference.as_deref(), &fetched)?;
+            staged.source = PackageSource::Git {
+                url: url.clone(),
+                reference: reference.clone(),
+                commit: Some(commit),
+            };
+        }
+        PackageSource::Upload { .. } => {
+            return Err(anyhow!("uploaded packages must be staged from their bytes"))
+        }
+    }
+    reject_symlinks(&fetched)?;
+    staged.root = locate_package_root(&fetched)?;
+    Ok(staged)
+}
+
+/// Stage an uploaded zip, stripping a shared top-level folder
+pub(crate) fn stage_upload(
+    skills_dir: &Path,
+    file_name: &str,
+    bytes: &[u8],
+    archive_root: Option<&str>,
+) -> anyhow::Result<StagedPackage> {
+    let staging = new_staging_dir(skills_dir)?;
+    let staged = StagedPackage {
+        root: staging.clone(),
+        staging,
+        source: PackageSource::Upload {
+            file_name: file_name.to_string(),
+        },
+    };
+    extract_archive(bytes, &staged.staging, archive_root)?;
+    Ok(staged)
+}
+
+/// The package root is the fetched folder itself, or its only subfolder
+fn locate_package_root(fetched: &Path) -> anyhow::Result<PathBuf> {
+    if fetched.join("SKILL.md").is_file() {
+        return Ok(fetched.to_path_buf());
+    }
+    let mut children = fs::read_dir(fetched)
+        .with_context(|| format!("failed to read {}", fetched.display()))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path());
+    if let (Some(only), None) = (children.next(), children.next()) {
+        if only.join("SKILL.md").is_file() {
+            return Ok(only);
+        }
+    }
+    Err(anyhow!("package must contain SKILL.md"))
+}
+
+enum ArchiveKind {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+fn archive_kind(path: &Path) -> Option<ArchiveKind> {
+    let name = path.file_name()?.to_str()?.to_ascii_lowercase();
+    if name.ends_with(".zip") {
+        Some(ArchiveKind::Zip)
+    } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        Some(ArchiveKind::TarGz)
+    } else if name.ends_with(".tar") {
+        Some(ArchiveKind::Tar)
+    } else {
+        None
+    }
+}
+
+fn extract_tar<R: Read>(reader: R, target_dir: &Path) -> anyhow::Result<()> {
+    let mut archive = tar::Archive::new(reader);
+    for entry in archive.entries().context("failed to read tarball")? {
+        let mut entry = entry.context("failed to read tarball entry")?;
+        let entry_path = entry
+            .path()
+            .context("invalid tarball entry path")?
+            .into_owned();
+        let relative = normalize_archive_path(&entry_path, None)
+            .ok_or_else(|| anyhow!("tarball contains invalid path: {}", entry_path.display()))?;
+        if relative.as_os_str().is_empty() {
+            continue;
+        }
+        let kind = entry.header().entry_type();
+        if !(kind.is_file() || kind.is_dir()) {
+            continue;
+        }
+        let output_path = target_dir.join(&relative);
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        entry
+            .unpack(&output_path)
+            .with_context(|| format!("failed to extract {}", output_path.display()))?;
+    }
+    Ok(())
+}
+
+/// Shallow-clone `url` into `target` and return the checked out commit
+fn git_clone(url: &str, reference: Option<&str>, target: &Path) -> anyhow::Result<String> {
+    let mut clone = Command::new("git");
+    // Also covers submodules and redirects, which `check_git_url` never sees.
+    clone.env("GIT_ALLOW_PROTOCOL", GIT_SCHEMES.join(":"));
+    clone.args(["clone", "--depth", "1", "--quiet"]);
+    if let Some(reference) = reference {
+        clone.args(["--branch", reference]);
+    }
+    let output = clone
+        .arg("--")
+        .arg(url)
+        .arg(target)
+        .output()
+        .context("failed to run git; is it installed?")?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "git clone {} failed: {}",
+            url,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let head = Command::new("git")
+        .arg("-C")
+        .arg(target)
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .context("failed to run git rev-parse")?;
+    let commit = String::from_utf8_lossy(&head.stdout).trim().to_string();
+    let _ = fs::remove_dir_all(target.join(".git"));
+    Ok(commit)
+}
+
+/// Refuse packages that contain symlinks; a cloned repository keeps them and
+/// they could point anywhere on the host.
+fn reject_symlinks(dir: &Path) -> anyhow::Result<()> {
+    for entry in fs::read_dir(dir).with_context(|| format!("failed to read {}", dir.display()))? {
+        let path = entry?.path();
+        let metadata = fs::symlink_metadata(&path)?;
+        if metadata.file_type().is_symlink() {
+            return Err(anyhow!(
+                "package contains a symlink: {}",
+                path.strip_prefix(dir).unwrap_or(&path).display()
+            ));
+        }
+        if metadata.is_dir() {
+            reject_symlinks(&path)?;
+        }
+    }
+    Ok(())
+}
+
+fn copy_dir(from: &Path, to: &Path) -> anyhow::Result<()> {
+    fs::create_dir_all(to).with_context(|| format!("failed to create {}", to.display()))?;
+    for entry in fs::read_dir(from).with_context(|| format!("failed to read {}", from.display()))? {
+        let entry = entry?;
+        if entry.file_name() == ".git" {
+            continue;
+        }
+        let target = to.join(entry.file_name());
+        let kind = entry.file_type()?;
+        if kind.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else if kind.is_file() {
+            fs::copy(entry.path(), &target)
+                .with_context(|| format!("failed to copy {}", entry.path().display()))?;
+        }
+    }
+    Ok(())
+}
+
+fn package_file(root: &Path, relative: &str) -> anyhow::Result<PathBuf> {
+    match normalize_archive_path(Path::new(relative), None) {
+        Some(path) if !path.as_os_str().is_empty() => Ok(root.join(path)),
+        _ => Err(anyhow!("invalid package path '{}'", relative)),
+    }
+}
+
+pub(crate) fn sha256_file(path: &Path) -> anyhow::Result<String> {
+    let bytes = fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    Ok(hex::encode(Sha256::digest(&bytes)))
+}
+
+/// Digest over the relative path and content of every file in `root`
+pub(crate) fn package_checksum(root: &Path) -> anyhow::Result<String> {
+    fn collect(root: &Path, dir: &Path, files: &mut Vec<PathBuf>) -> anyhow::Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let metadata = fs::symlink_metadata(&path)?;
+            if metadata.file_type().is_symlink() {
+                return Err(anyhow!("package contains a symlink: {}", path.display()));
+            }
+            if metadata.is_dir() {
+                collect(root, &path, files)?;
+            } else {
+                files.push(path.strip_prefix(root)?.to_path_buf());
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = Vec::new();
+    collect(root, root, &mut files)?;
+    files.sort();
+    let mut hasher = Sha256::new();
+    for file in files {
+        hasher.update(file.to_string_lossy().replace('\\', "/").as_bytes());
+        hasher.update([0]);
+        hasher.update(sha256_file(&root.join(&file))?.as_bytes());
+        hasher.update([b'\n']);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+pub(crate) fn mark_scripts_executable(root: &Path, scripts: &[String]) -> anyhow::Result<()> {
+    #[cfg(unix)]
+    for script in scripts {
+        use std::os::unix::fs::PermissionsExt;
+        let path = package_file(root, script)?;
+        let metadata = fs::symlink_metadata(&path)?;
+        if !metadata.is_file() {
+            return Err(anyhow!("script '{}' is not a regular file", script));
+        }
+        let mut permissions = metadata.permissions();
+        permissions.set_mode(permissions.mode() | 0o755);
+        fs::set_permissions(&path, permissions)?;
+    }
+    #[cfg(not(unix))]
+    let _ = (root, scripts);
+    Ok(())
+}
+
+/// Compare two manifest versions; unparsable versions sort first
+pub(crate) fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
+    match (Version::parse(a), Version::parse(b)) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        (Ok(_), Err(_)) => std::cmp::Ordering::Greater,
+        (Err(_), Ok(_)) => std::cmp::Ordering::Less,
+        (Err(_), Err(_)) => std::cmp::Ordering::Equal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn manifest_falls_back_to_skill_frontmatter() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("SKILL.md"),
+            "---\nname: weather\ndescription: Forecasts\nmetadata: '{\"nanobot\":{\"requires\":{\"bins\":[\"curl\"]}}}'\n---\n\n# Weather\n",
+        )
+        .unwrap();
+
+        let manifest = SkillManifest::load(dir.path()).unwrap();
+        assert_eq!(manifest.name, "weather");
+        assert_eq!(manifest.version, UNVERSIONED);
+        assert_eq!(manifest.description.as_deref(), Some("Forecasts"));
+        assert_eq!(manifest.requires.bins, vec!["curl".to_string()]);
+        manifest.verify(dir.path()).unwrap();
+    }
+
+    #[test]
+    fn verify_rejects_checksum_mismatch_and_missing_scripts() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("SKILL.md"), "# Tool\n").unwrap();
+        fs::create_dir_all(dir.path().join("scripts")).unwrap();
+        fs::write(dir.path().join("scripts/run.sh"), "echo hi\n").unwrap();
+        let digest = sha256_file(&dir.path().join("scripts/run.sh")).unwrap();
+
+        let mut manifest = SkillManifest {
+            name: "tool".to_string(),
+            version: "1.0.0".to_string(),
+            description: None,
+            requires: SkillRequirements::default(),
+            dependencies: BTreeMap::new(),
+            scripts: vec!["scripts/run.sh".to_string()],
+            checksums: BTreeMap::from([("scripts/run.sh".to_string(), digest)]),
+        };
+        manifest.verify(dir.path()).unwrap();
+
+        manifest
+            .checksums
+            .insert("scripts/run.sh".to_string(), "00".repeat(32));
+        let err = manifest.verify(dir.path()).unwrap_err();
+        assert!(err.to_string().contains("checksum mismatch"));
+
+        manifest.checksums.clear();
+        manifest.scripts = vec!["../outside.sh".to_string()];
+        assert!(manifest.verify(dir.path()).is_err());
+    }
+
+    #[test]
+    fn unmet_requirements_cover_env_and_dependencies() {
+        let manifest = SkillManifest {
+            name: "report".to_string(),
+            version: "1.0.0".to_string(),
+            description: None,
+            requires: SkillRequirements {
+                bins: Vec::new(),
+                env: vec!["AGENT_DIVA_TEST_SKILL_PACKAGE_UNSET".to_string()],
+            },
+            dependencies: BTreeMap::from([
+                ("charts".to_string(), "^1.2".to_string()),
+                ("fonts".to_string(), "*".to_string()),
+                ("plain".to_string(), "^3".to_string()),
+            ]),
+            scripts: Vec::new(),
+            checksums: BTreeMap::new(),
+        };
+        let installed = HashMap::from([
+            ("charts".to_string(), Some(Version::new(1, 1, 0))),
+            ("plain".to_string(), None),
+        ]);
+
+        let unmet = manifest.unmet_requirements(&installed);
+        assert_eq!(
+            unmet,
+            vec![
+                "ENV: AGENT_DIVA_TEST_SKILL_PACKAGE_UNSET".to_string(),
+                "SKILL: charts ^1.2".to_string(),
+                "SKILL: fonts *".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn stage_tarball_descends_into_single_folder() {
+        let skills_dir = TempDir::new().unwrap();
+        let source = TempDir::new().unwrap();
+        let archive = source.path().join("notes-1.0.0.tgz");
+        {
+            let encoder = flate2::write::GzEncoder::new(
+                fs::File::create(&archive).unwrap(),
+                flate2::Compression::default(),
+            );
+            let mut builder = tar::Builder::new(encoder);
+            let body = b"---\nname: notes\n---\n\n# Notes\n";
+            let mut header = tar::Header::new_gnu();
+            header.set_size(body.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, "notes/SKILL.md", &body[..])
+                .unwrap();
+            builder.into_inner().unwrap().finish().unwrap();
+        }
+
+        let source = PackageSource::parse(&archive.display().to_string(), None).unwrap();
+        let staged = stage_package(skills_dir.path(), &source).unwrap();
+        assert!(staged.root.ends_with("notes"));
+        assert_eq!(SkillManifest::load(&staged.root).unwrap().name, "notes");
+
+        let staging = staged.staging.clone();
+        drop(staged);
+        assert!(!staging.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_are_refused() {
+        let dir = TempDir::new().unwrap();
+        let package = dir.path().join("package");
+        fs::create_dir_all(package.join("scripts")).unwrap();
+        fs::write(package.join("SKILL.md"), "---\nname: notes\n---\n").unwrap();
+        fs::write(dir.path().join("outside.sh"), "#!/bin/sh\n").unwrap();
+        std::os::unix::fs::symlink(
+            dir.path().join("outside.sh"),
+            package.join("scripts/run.sh"),
+        )
+        .unwrap();
+        std::os::unix::fs::symlink(&package, package.join("scripts/loop")).unwrap();
+
+        assert!(reject_symlinks(&package)
+            .unwrap_err()
+            .to_string()
+            .contains("symlink"));
+        assert!(package_checksum(&package).is_err());
+        assert!(mark_scripts_executable(&package, &["scripts/run.sh".to_string()]).is_err());
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(dir.path().join("outside.sh"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o111, 0);
+    }
+
+    #[test]
+    fn parse_source_classifies_inputs() {
+        let dir = TempDir::new().unwrap();
+        let archive = dir.path().join("pkg.tar.gz");
+        fs::write(&archive, b"").unwrap();
+
+        assert!(matches!(
+            PackageSource::parse("https://example.com/skills/pdf.git", Some("v1")).unwrap(),
+            PackageSource::Git { reference: Some(reference), .. } if reference == "v1"
+        ));
+        assert!(matches!(
+            PackageSource::parse(&dir.path().display().to_string(), None).unwrap(),
+            PackageSource::Directory { .. }
+        ));
+        assert!(matches!(
+            PackageSource::parse(&archive.display().to_string(), None).unwrap(),
+            PackageSource::Archive { .. }
+        ));
+        assert!(PackageSource::parse(&dir.path().display().to_string(), Some("main")).is_err());
+    }
+
+    #[test]
+    fn parse_source_rejects_unsafe_git_urls() {
+        for url in [
+            "ssh://git@example.com/skills/pdf.git",
+            "git+https://example.com/skills/pdf",
+            "git://example.com/skills/pdf.git",
+            "git@example.com:skills/pdf.git",
+        ] {
+            assert!(PackageSource::parse(url, None).is_ok(), "{url}");
+        }
+        for url in [
+            "-uhttps://example.com/pdf.git",
+            "git+--upload-pack=touch /tmp/pwned",
+            "ext::sh -c touch% /tmp/pwned",
+            "git+ext::sh -c id",
+            "http://example.com/skills/pdf.git",
+            "pdf.git",
+        ] {
+            assert!(PackageSource::parse(url, None).is_err(), "{url}");
+        }
+        assert!(PackageSource::parse("https://example.com/pdf.git", Some("--help")).is_err());
+    }
+}
@@ -1,12 +1,19 @@
 use agent_diva_agent::skills::{SkillSource, SkillsLoader};
 use agent_diva_core::config::ConfigLoader;
 use anyhow::{anyhow, Context};
+use semver::Version;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io::{Cursor, Read};
 use std::path::{Component, Path, PathBuf};
 
+use crate::skill_package::{
+    compare_versions, mark_scripts_executable, package_checksum, stage_package, stage_upload,
+    LockedSkill, PackageSource, SkillInstallOutcome, SkillLockfile, SkillManifest,
+    SkillUpdateOutcome, SkillUpdateStatus, StagedPackage, MANIFEST_FILE,
+};
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct SkillDto {
     pub name: String,
@@ -16,16 +23,35 @@ pub struct SkillDto {
     pub active: bool,
     pub path: String,
     pub can_delete: bool,
+    /// Package version, for skills with a manifest or a lockfile entry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Where the package was installed from, per the lockfile
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub package_source: Option<String>,
+    /// Missing binaries, environment variables and skill dependencies
+    #[serde(default)]
+    pub unmet_requirements: Vec<String>,
 }
 
 #[derive(Clone)]
 pub struct SkillService {
     loader: ConfigLoader,
+    workspace: Option<PathBuf>,
 }
 
 impl SkillService {
     pub fn new(loader: ConfigLoader) -> Self {
-        Self { loader }
+        Self {
+            loader,
+            workspace: None,
+        }
+    }
+
+    /// Use `workspace` instead of the configured one
+    pub fn with_workspace(mut self, workspace: PathBuf) -> Self {
+        self.workspace = Some(workspace);
+        self
     }
 
     pub fn list_skills(&self) -> anyhow::Result<Vec<SkillDto>> {
@@ -37,11 +63,40 @@ impl SkillService {
             .map(|skill| skill.name)
             .collect();
         let active_names: HashSet<String> = loader.get_always_skills().into_iter().collect();
+        let lockfile = SkillLockfile::load(&workspace.join("skills")).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring unreadable skills lockfile: {}", e);
+            SkillLockfile::default()
+        });
 
-        let mut skills = loader
-            .list_skills(false)
+        let listed = loader.list_skills(false);
+        let manifests: HashMap<String, (Option<SkillManifest>, Option<Version>)> = listed
+            .iter()
+            .map(|skill| {
+                let root = skill.path.parent().unwrap_or(&skill.path);
+                let manifest = SkillManifest::load(root).ok();
+                let version = lockfile
+                    .skills
+                    .get(&skill.name)
+                    .map(|locked| locked.version.clone())
+                    .or_else(|| {
+                        manifest
+                            .as_ref()
+                            .filter(|_| root.join(MANIFEST_FILE).exists())
+                            .map(|manifest| manifest.version.clone())
+                    })
+                    .and_then(|version| Version::parse(&version).ok());
+                (skill.name.clone(), (manifest, version))
+            })
+            .collect();
+        let installed: HashMap<String, Option<Version>> = manifests
+            .iter()
+            .map(|(name, (_, version))| (name.clone(), version.clone()))
+            .collect();
+
+        let mut skills = listed
             .into_iter()
             .map(|skill| {
+                let (manifest, version) = &manifests[&skill.name];
                 let description = loader
                     .get_skill_metadata(&skill.name)
                     .description
@@ -58,6 +113,15 @@ impl SkillService {
                     active: active_names.contains(&skill.name),
                     path: skill.path.display().to_string(),
                     can_delete: matches!(skill.source, SkillSource::Workspace),
+                    version: version.as_ref().map(Version::to_string),
+                    package_source: lockfile
+                        .skills
+                        .get(&skill.name)
+                        .map(|locked| locked.source.to_string()),
+                    unmet_requirements: manifest
+                        .as_ref()
+                        .map(|manifest| manifest.unmet_requirements(&installed))
+                        .unwrap_or_default(),
                 }
             })
             .collect::<Vec<_>>();
@@ -66,33 +130,132 @@ impl SkillService {
     }
 
     pub fn upload_skill_zip(&self, file_name: &str, bytes: Vec<u8>) -> anyhow::Result<SkillDto> {
-        let workspace = self.workspace_dir()?;
-        let skills_dir = workspace.join("skills");
-        fs::create_dir_all(&skills_dir).with_context(|| {
-            format!("failed to create skills directory {}", skills_dir.display())
-        })?;
-
+        let skills_dir = self.skills_dir()?;
         let archive_paths = list_archive_entries(&bytes)?;
         let single_root = shared_archive_root(&archive_paths);
         let skill_name = derive_skill_name(file_name, &bytes, single_root.as_deref())?;
 
-        let target_dir = skills_dir.join(&skill_name);
-        let tmp_dir = skills_dir.join(format!(".upload-{}-{}", skill_name, std::process::id()));
-        if tmp_dir.exists() {
-            fs::remove_dir_all(&tmp_dir)
-                .with_context(|| format!("failed to clean temp directory {}", tmp_dir.display()))?;
+        let staged = stage_upload(&skills_dir, file_name, &bytes, single_root.as_deref())?;
+        if !staged.root.join("SKILL.md").exists() {
+            return Err(anyhow!("uploaded zip must contain SKILL.md"));
         }
-        fs::create_dir_all(&tmp_dir)
-            .with_context(|| format!("failed to create temp directory {}", tmp_dir.display()))?;
+        Ok(self
+            .install_staged(&skills_dir, staged, Some(skill_name))?
+            .skill)
+    }
 
-        extract_archive(&bytes, &tmp_dir, single_root.as_deref())?;
+    /// Install a package from a directory, archive or git URL and record it
+    /// in the lockfile. Unmet requirements are reported on the result, not
+    /// treated as errors.
+    pub fn install_package(
+        &self,
+        source: &str,
+        reference: Option<&str>,
+    ) -> anyhow::Result<SkillInstallOutcome> {
+        let skills_dir = self.skills_dir()?;
+        let source = PackageSource::parse(source, reference)?;
+        let staged = stage_package(&skills_dir, &source)?;
+        self.install_staged(&skills_dir, staged, None)
+    }
 
-        let skill_file = tmp_dir.join("SKILL.md");
-        if !skill_file.exists() {
-            let _ = fs::remove_dir_all(&tmp_dir);
-            return Err(anyhow!("uploaded zip must contain SKILL.md"));
+    /// Re-fetch locked packages (all, or just `name`) from their recorded
+    /// source and reinstall those whose content changed
+    pub fn update_packages(&self, name: Option<&str>) -> anyhow::Result<Vec<SkillUpdateOutcome>> {
+        let skills_dir = self.skills_dir()?;
+        let lockfile = SkillLockfile::load(&skills_dir)?;
+        let names: Vec<String> = match name {
+            Some(name) if !lockfile.skills.contains_key(name) => {
+                return Err(anyhow!("skill '{}' was not installed as a package", name));
+            }
+            Some(name) => vec![name.to_string()],
+            None => lockfile.skills.keys().cloned().collect(),
+        };
+
+        let mut outcomes = Vec::new();
+        for name in names {
+            let locked = lockfile.skills[&name].clone();
+            let outcome = self
+                .update_package(&skills_dir, &name, &locked)
+                .unwrap_or_else(|e| SkillUpdateOutcome {
+                    name: name.clone(),
+                    status: SkillUpdateStatus::Failed,
+                    from_version: locked.version.clone(),
+                    to_version: None,
+                    message: Some(e.to_string()),
+                    unmet_requirements: Vec::new(),
+                });
+            outcomes.push(outcome);
+        }
+        Ok(outcomes)
+    }
+
+    fn update_package(
+        &self,
+        skills_dir: &Path,
+        name: &str,
+        locked: &LockedSkill,
+    ) -> anyhow::Result<SkillUpdateOutcome> {
+        let mut outcome = SkillUpdateOutcome {
+            name: name.to_string(),
+            status: SkillUpdateStatus::Unchanged,
+            from_version: locked.version.clone(),
+            to_version: None,
+            message: None,
+            unmet_requirements: Vec::new(),
+        };
+        let source = match &locked.source {
+            PackageSource::Upload { .. } => {
+                outcome.status = SkillUpdateStatus::Skipped;
+                outcome.message = Some("uploaded packages have no source to update from".into());
+                return Ok(outcome);
+            }
+            PackageSource::Git { url, reference, .. } => PackageSource::Git {
+                url: url.clone(),
+                reference: reference.clone(),
+                commit: None,
+            },
+            source => source.clone(),
+        };
+
+        let staged = stage_package(skills_dir, &source)?;
+        let manifest = SkillManifest::load(&staged.root)?;
+        outcome.to_version = Some(manifest.version.clone());
+        if package_checksum(&staged.root)? == locked.checksum {
+            return Ok(outcome);
+        }
+        if compare_versions(&manifest.version, &locked.version).is_lt() {
+            outcome.status = SkillUpdateStatus::Skipped;
+            outcome.message = Some(format!(
+                "source has older version {} than installed {}",
+                manifest.version, locked.version
+            ));
+            return Ok(outcome);
         }
 
+        let installed = self.install_staged(skills_dir, staged, Some(name.to_string()))?;
+        outcome.status = SkillUpdateStatus::Updated;
+        outcome.unmet_requirements = installed.skill.unmet_requirements;
+        Ok(outcome)
+    }
+
+    fn install_staged(
+        &self,
+        skills_dir: &Path,
+        staged: StagedPackage,
+        name_hint: Option<String>,
+    ) -> anyhow::Result<SkillInstallOutcome> {
+        let mut manifest = SkillManifest::load(&staged.root)?;
+        if let Some(name) = name_hint {
+            if !staged.root.join(MANIFEST_FILE).exists() {
+                manifest.name = name;
+            }
+        }
+        manifest.verify(&staged.root)?;
+        let skill_name = sanitize_skill_name(&manifest.name);
+        let checksum = package_checksum(&staged.root)?;
+        mark_scripts_executable(&staged.root, &manifest.scripts)?;
+
+        let target_dir = skills_dir.join(&skill_name);
         if target_dir.exists() {
             fs::remove_dir_all(&target_dir).with_context(|| {
                 format!(
@@ -101,23 +264,57 @@ impl SkillService {
                 )
             })?;
         }
-        fs::rename(&tmp_dir, &target_dir).with_context(|| {
+        fs::rename(&staged.root, &target_dir).with_context(|| {
             format!(
-                "failed to move uploaded skill into place: {} -> {}",
-                tmp_dir.display(),
+                "failed to move skill into place: {} -> {}",
+                staged.root.display(),
                 target_dir.display()
             )
         })?;
 
-        self.list_skills()?
+        let mut lockfile = SkillLockfile::load(skills_dir)?;
+        let previous_version = lockfile.skills.insert(
+            skill_name.clone(),
+            LockedSkill {
+                version: manifest.version.clone(),
+                source: staged.source.clone(),
+                checksum,
+                installed_at: chrono::Utc::now().to_rfc3339(),
+                dependencies: manifest.dependencies.clone(),
+            },
+        );
+        lockfile.save(skills_dir)?;
+
+        let skill = self
+            .list_skills()?
             .into_iter()
             .find(|skill| skill.name == skill_name)
-            .ok_or_else(|| anyhow!("uploaded skill was not visible after install"))
+            .ok_or_else(|| anyhow!("installed skill was not visible after install"))?;
+        Ok(SkillInstallOutcome {
+            skill,
+            previous_version: previous_version.map(|locked| locked.version),
+        })
     }
 
     pub fn delete_skill(&self, name: &str) -> anyhow::Result<()> {
-        let workspace = self.workspace_dir()?;
-        let workspace_dir = workspace.join("skills").join(name);
+        self.remove_skill(name, false)
+    }
+
+    /// Delete a workspace skill and its lockfile entry. Skills other locked
+    /// packages depend on are kept unless `force` is set.
+    pub fn remove_skill(&self, name: &str, force: bool) -> anyhow::Result<()> {
+        let skills_dir = self.skills_dir()?;
+        let mut lockfile = SkillLockfile::load(&skills_dir)?;
+        let dependents = lockfile.dependents(name);
+        if !dependents.is_empty() && !force {
+            return Err(anyhow!(
+                "skill '{}' is required by {}; force the removal to delete it anyway",
+                name,
+                dependents.join(", ")
+            ));
+        }
+
+        let workspace_dir = skills_dir.join(name);
         if workspace_dir.exists() {
             fs::remove_dir_all(&workspace_dir).with_context(|| {
                 format!(
@@ -125,6 +322,9 @@ impl SkillService {
                     workspace_dir.display()
                 )
             })?;
+            if lockfile.skills.remove(name).is_some() {
+                lockfile.save(&skills_dir)?;
+            }
             return Ok(());
         }
 
@@ -140,12 +340,23 @@ impl SkillService {
     }
 
     fn workspace_dir(&self) -> anyhow::Result<PathBuf> {
+        if let Some(workspace) = &self.workspace {
+            return Ok(workspace.clone());
+        }
         let config = self.loader.load()?;
         Ok(expand_tilde(&config.agents.defaults.workspace))
     }
+
+    fn skills_dir(&self) -> anyhow::Result<PathBuf> {
+        let skills_dir = self.workspace_dir()?.join("skills");
+        fs::create_dir_all(&skills_dir).with_context(|| {
+            format!("failed to create skills directory {}", skills_dir.display())
+        })?;
+        Ok(skills_dir)
+    }
 }
 
-fn expand_tilde(path: &str) -> PathBuf {
+pub(crate) fn expand_tilde(path: &str) -> PathBuf {
     if let Some(stripped) = path.strip_prefix("~/") {
         if let Some(home) = dirs::home_dir() {
             return home.join(stripped);
@@ -154,7 +365,7 @@ fn expand_tilde(path: &str) -> PathBuf {
     PathBuf::from(path)
 }
 
-fn list_archive_entries(bytes: &[u8]) -> anyhow::Result<Vec<PathBuf>> {
+pub(crate) fn list_archive_entries(bytes: &[u8]) -> anyhow::Result<Vec<PathBuf>> {
     let mut archive =
         zip::ZipArchive::new(Cursor::new(bytes)).context("failed to open uploaded zip archive")?;
     let mut paths = Vec::new();
@@ -170,7 +381,7 @@ fn list_archive_entries(bytes: &[u8]) -> anyhow::Result<Vec<PathBuf>> {
     Ok(paths)
 }
 
-fn shared_archive_root(paths: &[PathBuf]) -> Option<String> {
+pub(crate) fn shared_archive_root(paths: &[PathBuf]) -> Option<String> {
     let mut root: Option<String> = None;
     for path in paths {
         let mut components = path.components();
@@ -219,7 +430,7 @@ fn derive_skill_name(
     Ok(fallback)
 }
 
-fn sanitize_skill_name(input: &str) -> String {
+pub(crate) fn sanitize_skill_name(input: &str) -> String {
     let mut out = String::new();
     let mut previous_dash = false;
     for ch in input.chars() {
@@ -278,7 +489,7 @@ fn parse_frontmatter_name(content: &str) -> Option<String> {
     None
 }
 
-fn extract_archive(
+pub(crate) fn extract_archive(
     bytes: &[u8],
     target_dir: &Path,
     archive_root: Option<&str>,
@@ -319,7 +530,7 @@ fn extract_archive(
     Ok(())
 }
 
-fn normalize_archive_path(path: &Path, archive_root: Option<&str>) -> Option<PathBuf> {
+pub(crate) fn normalize_archive_path(path: &Path, archive_root: Option<&str>) -> Option<PathBuf> {
     let mut parts = Vec::new();
     for component in path.components() {
         match component {
@@ -468,6 +679,124 @@ mod tests {
         assert!(err.to_string().contains("invalid path"));
     }
 
+    fn write_package(dir: &Path, name: &str, version: &str, dependencies: &str) {
+        fs::create_dir_all(dir.join("scripts")).unwrap();
+        fs::write(
+            dir.join("SKILL.md"),
+            format!("---\nname: {name}\ndescription: Packaged\n---\n\n# {name}\n"),
+        )
+        .unwrap();
+        fs::write(dir.join("scripts/run.sh"), "#!/bin/sh\necho run\n").unwrap();
+        let digest = crate::skill_package::sha256_file(&dir.join("scripts/run.sh")).unwrap();
+        fs::write(
+            dir.join(MANIFEST_FILE),
+            format!(
+                r#"{{"name":"{name}","version":"{version}","dependencies":{dependencies},"scripts":["scripts/run.sh"],"checksums":{{"scripts/run.sh":"{digest}"}}}}"#
+            ),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn install_package_from_directory_writes_lockfile() {
+        let config_dir = TempDir::new().unwrap();
+        let workspace = TempDir::new().unwrap();
+        let source = TempDir::new().unwrap();
+        write_config(config_dir.path(), workspace.path());
+        write_package(source.path(), "report", "1.0.0", r#"{"charts":"^2"}"#);
+        let service = SkillService::new(ConfigLoader::with_dir(config_dir.path()));
+
+        let outcome = service
+            .install_package(&source.path().display().to_string(), None)
+            .unwrap();
+        assert_eq!(outcome.skill.name, "report");
+        assert_eq!(outcome.skill.version.as_deref(), Some("1.0.0"));
+        assert_eq!(outcome.previous_version, None);
+        assert_eq!(
+            outcome.skill.unmet_requirements,
+            vec!["SKILL: charts ^2".to_string()]
+        );
+
+        let skills_dir = workspace.path().join("skills");
+        let lockfile = SkillLockfile::load(&skills_dir).unwrap();
+        let locked = &lockfile.skills["report"];
+        assert_eq!(locked.version, "1.0.0");
+        assert!(matches!(locked.source, PackageSource::Directory { .. }));
+        assert!(skills_dir.join("report/scripts/run.sh").exists());
+        assert!(!skills_dir
+            .join(".staging")
+            .read_dir()
+            .unwrap()
+            .any(|_| true));
+    }
+
+    #[test]
+    fn install_package_rejects_checksum_mismatch() {
+        let config_dir = TempDir::new().unwrap();
+        let workspace = TempDir::new().unwrap();
+        let source = TempDir::new().unwrap();
+        write_config(config_dir.path(), workspace.path());
+        write_package(source.path(), "report", "1.0.0", "{}");
+        fs::write(source.path().join("scripts/run.sh"), "tampered\n").unwrap();
+        let service = SkillService::new(ConfigLoader::with_dir(config_dir.path()));
+
+        let err = service
+            .install_package(&source.path().display().to_string(), None)
+            .unwrap_err();
+        assert!(err.to_string().contains("checksum mismatch"));
+        assert!(!workspace.path().join("skills/report").exists());
+    }
+
+    #[test]
+    fn update_package_reinstalls_changed_source() {
+        let config_dir = TempDir::new().unwrap();
+        let workspace = TempDir::new().unwrap();
+        let source = TempDir::new().unwrap();
+        write_config(config_dir.path(), workspace.path());
+        write_package(source.path(), "report", "1.0.0", "{}");
+        let service = SkillService::new(ConfigLoader::with_dir(config_dir.path()));
+        service
+            .install_package(&source.path().display().to_string(), None)
+            .unwrap();
+
+        let unchanged = service.update_packages(None).unwrap();
+        assert_eq!(unchanged[0].status, SkillUpdateStatus::Unchanged);
+
+        write_package(source.path(), "report", "1.1.0", "{}");
+        let updated = service.update_packages(Some("report")).unwrap();
+        assert_eq!(updated[0].status, SkillUpdateStatus::Updated);
+        assert_eq!(updated[0].to_version.as_deref(), Some("1.1.0"));
+        let lockfile = SkillLockfile::load(&workspace.path().join("skills")).unwrap();
+        assert_eq!(lockfile.skills["report"].version, "1.1.0");
+    }
+
+    #[test]
+    fn remove_skill_respects_dependents() {
+        let config_dir = TempDir::new().unwrap();
+        let workspace = TempDir::new().unwrap();
+        let charts = TempDir::new().unwrap();
+        let report = TempDir::new().unwrap();
+        write_config(config_dir.path(), workspace.path());
+        write_package(charts.path(), "charts", "2.0.0", "{}");
+        write_package(report.path(), "report", "1.0.0", r#"{"charts":"^2"}"#);
+        let service = SkillService::new(ConfigLoader::with_dir(config_dir.path()));
+        service
+            .install_package(&charts.path().display().to_string(), None)
+            .unwrap();
+        let report = service
+            .install_package(&report.path().display().to_string(), None)
+            .unwrap();
+        assert!(report.skill.unmet_requirements.is_empty());
+
+        let err = service.delete_skill("charts").unwrap_err();
+        assert!(err.to_string().contains("required by report"));
+
+        service.remove_skill("charts", true).unwrap();
+        let lockfile = SkillLockfile::load(&workspace.path().join("skills")).unwrap();
+        assert!(!lockfile.skills.contains_key("charts"));
+        assert!(!workspace.path().join("skills/charts").exists());
+    }
+
     #[test]
     fn delete_workspace_skill_and_restore_builtin_view() {
         let config_dir = TempDir::new().unwrap();
@@ -3,13 +3,22 @@ use agent_diva_core::bus::{InboundMessage, MessageBus};
 use agent_diva_core::config::schema::{
     ChannelsConfig, MCPServerConfig, WebFetchConfig, WebSearchConfig, WebToolsConfig,
 };
-use agent_diva_core::cron::{CreateCronJobRequest, CronJobDto, UpdateCronJobRequest};
+use agent_diva_core::cron::{
+    AutomationDto, AutomationRequest, CreateCronJobRequest, CronJobDto, CronRunRecord,
+    CronRunSnapshot, UpdateCronJobRequest,
+};
+use agent_diva_core::memory::{MemoryFileError, MemoryFileInfo, MemoryFileSnapshot};
 use agent_diva_providers::{CustomProviderUpsert, ProviderModelCatalogView, ProviderView};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use tokio::sync::{mpsc, oneshot};
 
 use crate::mcp_service::{McpServerDto, McpServerUpsert};
+use crate::memory_service::{
+    MemoryEtagQuery, MemoryFileWrite, MemoryForgetQuery, MemoryHistoryDto, MemoryRememberRequest,
+    MemorySearchDto, MemorySearchQuery,
+};
+use crate::skill_package::{SkillInstallOutcome, SkillUpdateOutcome};
 use crate::skill_service::SkillDto;
 
 #[derive(Clone)]
@@ -40,6 +49,79 @@ pub enum ProviderCommand {
     DeleteProvider(String, oneshot::Sender<Result<(), String>>),
 }
 
+pub enum MemoryCommand {
+    ListFiles(
+        Option<String>,
+        oneshot::Sender<Result<Vec<MemoryFileInfo>, MemoryFileError>>,
+    ),
+    ReadFile(
+        String,
+        Option<String>,
+        oneshot::Sender<Result<MemoryFileSnapshot, MemoryFileError>>,
+    ),
+    WriteFile(
+        String,
+        Option<String>,
+        MemoryFileWrite,
+        oneshot::Sender<Result<MemoryFileSnapshot, MemoryFileError>>,
+    ),
+    DeleteFile(
+        String,
+        MemoryEtagQuery,
+        oneshot::Sender<Result<(), MemoryFileError>>,
+    ),
+    ListHistory(
+        Option<String>,
+        oneshot::Sender<Result<MemoryHistoryDto, MemoryFileError>>,
+    ),
+    DeleteHistoryEntry(
+        usize,
+        MemoryEtagQuery,
+        oneshot::Sender<Result<MemoryFileSnapshot, MemoryFileError>>,
+    ),
+    Search(
+        MemorySearchQuery,
+        oneshot::Sender<Result<MemorySearchDto, MemoryFileError>>,
+    ),
+    Remember(
+        MemoryRememberRequest,
+        oneshot::Sender<Result<bool, MemoryFileError>>,
+    ),
+    Forget(
+        MemoryForgetQuery,
+        oneshot::Sender<Result<Vec<String>, MemoryFileError>>,
+    ),
+    ConsolidateSession(String, oneshot::Sender<Result<bool, String>>),
+}
+
+pub enum AutomationCommand {
+    List(oneshot::Sender<Result<Vec<AutomationDto>, String>>),
+    Get(String, oneshot::Sender<Result<AutomationDto, String>>),
+    Create(
+        AutomationRequest,
+        oneshot::Sender<Result<AutomationDto, String>>,
+    ),
+    Update(
+        String,
+        AutomationRequest,
+        oneshot::Sender<Result<AutomationDto, String>>,
+    ),
+    Delete(String, oneshot::Sender<Result<(), String>>),
+    ListRuns(
+        String,
+        usize,
+        oneshot::Sender<Result<Vec<CronRunRecord>, String>>,
+    ),
+    Stop(String, oneshot::Sender<Result<CronRunSnapshot, String>>),
+    /// Fire a webhook rule; carries the `X-Automation-Secret` header and body
+    Webhook(
+        String,
+        Option<String>,
+        serde_json::Value,
+        oneshot::Sender<Result<CronRunSnapshot, String>>,
+    ),
+}
+
 pub enum ManagerCommand {
     // Core runtime control plane used by the formal CLI runtime.
     Chat(ApiRequest),
@@ -69,13 +151,51 @@ pub enum ManagerCommand {
         SkillUploadRequest,
         oneshot::Sender<Result<SkillDto, String>>,
     ),
-    DeleteSkill(String, oneshot::Sender<Result<(), String>>),
+    InstallSkill(
+        SkillInstallRequest,
+        oneshot::Sender<Result<SkillInstallOutcome, String>>,
+    ),
+    UpdateSkills(
+        SkillUpdateRequest,
+        oneshot::Sender<Result<Vec<SkillUpdateOutcome>, String>>,
+    ),
+    /// Skill name and whether to remove it despite dependents
+    DeleteSkill(String, bool, oneshot::Sender<Result<(), String>>),
     GetSessions(oneshot::Sender<Result<Vec<agent_diva_core::session::SessionInfo>, String>>),
     GetSessionHistory(
         String,
         oneshot::Sender<Result<Option<agent_diva_core::session::store::Session>, String>>,
     ),
     DeleteSession(String, oneshot::Sender<Result<bool, String>>),
+    RewindSession(
+        String,
+        usize,
+        oneshot::Sender<Result<Vec<agent_diva_core::session::ChatMessage>, String>>,
+    ),
+    RewindToUserMessage(
+        String,
+        usize,
+        oneshot::Sender<Result<agent_diva_core::session::ChatMessage, String>>,
+    ),
+    ForkSession(
+        String,
+        ForkSessionRequest,
+        oneshot::Sender<Result<String, String>>,
+    ),
+    ExportSession(
+        String,
+        agent_diva_core::session::SessionExportFormat,
+        agent_diva_core::session::SessionExportOptions,
+        oneshot::Sender<Result<String, String>>,
+    ),
+    ImportSession(
+        ImportSessionRequest,
+        oneshot::Sender<Result<String, String>>,
+    ),
+    SearchSessions(
+        agent_diva_core::session::SessionSearchQuery,
+        oneshot::Sender<Result<Vec<agent_diva_core::session::SessionSearchHit>, String>>,
+    ),
     ListCronJobs(oneshot::Sender<Result<Vec<CronJobDto>, String>>),
     GetCronJob(String, oneshot::Sender<Result<Option<CronJobDto>, String>>),
     CreateCronJob(
@@ -90,6 +210,11 @@ pub enum ManagerCommand {
     DeleteCronJob(String, oneshot::Sender<Result<(), String>>),
     SetCronJobEnabled(String, bool, oneshot::Sender<Result<CronJobDto, String>>),
     RunCronJobNow(String, bool, oneshot::Sender<Result<CronJobDto, String>>),
+    ListCronRuns(
+        String,
+        usize,
+        oneshot::Sender<Result<Vec<CronRunRecord>, String>>,
+    ),
     StopCronJobRun(
         String,
         oneshot::Sender<Result<agent_diva_core::cron::CronRunSnapshot, String>>,
@@ -100,6 +225,8 @@ pub enum ManagerCommand {
     ),
     // Companion / HTTP management plane for GUI and remote administration.
     Provider(ProviderCommand),
+    Memory(MemoryCommand),
+    Automation(AutomationCommand),
 }
 
 pub struct ApiRequest {
@@ -119,6 +246,66 @@ pub struct ResetSessionRequest {
     pub chat_id: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RewindSessionRequest {
+    /// Number of messages to keep; must fall on a turn boundary.
+    pub message_count: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ForkSessionRequest {
+    /// Key of the new session; generated from the parent key when omitted.
+    #[serde(default)]
+    pub new_key: Option<String>,
+    /// Number of parent messages to copy; all of them when omitted.
+    #[serde(default)]
+    pub message_count: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EditSessionMessageRequest {
+    /// Index of the user message to replace.
+    pub index: usize,
+    /// New message text, sent as a fresh turn after the rewind.
+    pub message: String,
+}
+
+/// Query string of `GET /api/sessions/:id/export`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionExportParams {
+    /// `markdown` (default), `html` or `json`.
+    #[serde(default)]
+    pub format: Option<String>,
+    /// Mask secrets in the export; on unless explicitly disabled.
+    #[serde(default)]
+    pub redact: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportSessionRequest {
+    /// Bundle produced by a JSON export.
+    pub bundle: agent_diva_core::session::SessionBundle,
+    /// Key for the imported session; `import:<bundled key>` when omitted.
+    #[serde(default)]
+    pub key: Option<String>,
+}
+
+/// Query string of `GET /api/sessions/search`. Dates are `YYYY-MM-DD` or RFC 3339.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSearchParams {
+    pub q: String,
+    #[serde(default)]
+    pub channel: Option<String>,
+    #[serde(default)]
+    pub role: Option<String>,
+    #[serde(default)]
+    pub from: Option<String>,
+    #[serde(default)]
+    pub to: Option<String>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConfigUpdate {
     pub api_base: Option<String>,
@@ -160,6 +347,13 @@ pub struct RunCronJobRequest {
     pub force: bool,
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CronRunsParams {
+    /// Most recent runs to return; defaults to 20.
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConfigResponse {
     pub provider: Option<String>,
@@ -175,6 +369,31 @@ pub struct SkillUploadRequest {
     pub bytes: Vec<u8>,
 }
 
+/// Body of `POST /api/skills/install`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillInstallRequest {
+    /// Package directory, `.zip`/tarball path or git URL
+    pub source: String,
+    /// Branch or tag for git sources
+    #[serde(default, rename = "ref")]
+    pub reference: Option<String>,
+}
+
+/// Body of `POST /api/skills/update`; updates every package without a name.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SkillUpdateRequest {
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// Query string of `DELETE /api/skills/:name`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SkillDeleteParams {
+    /// Remove the skill even when other packages depend on it
+    #[serde(default)]
+    pub force: bool,
+}
+
 #[derive(Debug, Clone)]
 pub struct FileUploadRequest {
     pub file_name: String,
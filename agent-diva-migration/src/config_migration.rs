@@ -536,8 +536,12 @@ impl ConfigMigrator {
                     context_budget_tokens: 24_000,
                     context_budget_reserve_tokens: 4_000,
                     context_overflow_retry_enabled: true,
+                    context_compaction: ContextCompactionStrategy::Trim,
                 },
                 soul: AgentSoulConfig::default(),
+                memory: AgentMemoryConfig::default(),
+                sessions: AgentSessionsConfig::default(),
+                skills: AgentSkillsConfig::default(),
             },
             channels: ChannelsConfig {
                 telegram: TelegramConfig {
@@ -646,6 +650,7 @@ impl ConfigMigrator {
             gateway: GatewayConfig {
                 host: py.gateway.host,
                 port: py.gateway.port,
+                cron: Default::default(),
             },
             tools: ToolsConfig {
                 builtin: Default::default(),
@@ -665,6 +670,7 @@ impl ConfigMigrator {
                     } else {
                         py.tools.exec.timeout
                     },
+                    ..ExecToolConfig::default()
                 },
                 restrict_to_workspace: py.tools.restrict_to_workspace,
                 mcp_servers: py
@@ -680,6 +686,7 @@ impl ConfigMigrator {
                                 env: server.env,
                                 url: server.url,
                                 tool_timeout: 30,
+                                ..MCPServerConfig::default()
                             },
                         )
                     })
@@ -10,12 +10,16 @@ use std::path::PathBuf;
 
 mod config_migration;
 mod memory_migration;
+mod session_backend_migration;
 mod session_migration;
 
 use config_migration::ConfigMigrator;
 use memory_migration::MemoryMigrator;
+use session_backend_migration::SessionBackendConverter;
 use session_migration::SessionMigrator;
 
+use agent_diva_core::config::schema::SessionBackend;
+
 #[derive(Parser)]
 #[command(name = "agent-diva-migrate")]
 #[command(about = "Migration tool for agent-diva - migrate from Python to Rust version")]
@@ -48,6 +52,26 @@ struct Cli {
     /// Auto-confirm all prompts
     #[arg(short, long)]
     yes: bool,
+
+    /// Instead of migrating from Python, copy the sessions of a workspace
+    /// into this backend (`jsonl` or `sqlite`)
+    #[arg(long, value_name = "BACKEND", value_parser = parse_session_backend)]
+    convert_sessions: Option<SessionBackend>,
+
+    /// Workspace whose sessions are converted (default: <target>/workspace)
+    #[arg(long)]
+    workspace: Option<PathBuf>,
+}
+
+fn parse_session_backend(value: &str) -> std::result::Result<SessionBackend, String> {
+    match value {
+        "jsonl" => Ok(SessionBackend::Jsonl),
+        "sqlite" => Ok(SessionBackend::Sqlite),
+        other => Err(format!(
+            "unknown session backend '{}'; expected jsonl or sqlite",
+            other
+        )),
+    }
 }
 
 #[tokio::main]
@@ -71,6 +95,17 @@ async fn main() -> Result<()> {
     let source_dir = cli.source.unwrap_or_else(get_default_agent_diva_dir);
     let target_dir = cli.target.unwrap_or_else(get_default_agent_diva_dir);
 
+    if let Some(backend) = cli.convert_sessions {
+        let workspace = cli
+            .workspace
+            .unwrap_or_else(|| target_dir.join("workspace"));
+        println!("Workspace: {}", style(workspace.display()).cyan());
+        let converter = SessionBackendConverter::new(&workspace);
+        let result = converter.convert(backend, cli.dry_run)?;
+        print_conversion_result(backend, &result);
+        return Ok(());
+    }
+
     println!("Source (Python): {}", style(source_dir.display()).cyan());
     println!("Target (Rust):   {}", style(target_dir.display()).cyan());
     println!();
@@ -214,6 +249,38 @@ fn print_session_result(result: &session_migration::MigrationResult) {
     }
 }
 
+fn print_conversion_result(
+    backend: SessionBackend,
+    result: &session_backend_migration::ConversionResult,
+) {
+    println!();
+    println!("{}", style("Session Backend Conversion").bold());
+    println!("{}", style("──────────────────────────").dim());
+
+    if result.total > 0 {
+        println!(
+            "{} Converted {}/{} sessions to {:?}",
+            style("✓").green(),
+            style(result.converted).green(),
+            style(result.total).cyan(),
+            backend
+        );
+        if result.skipped > 0 {
+            println!(
+                "  {} {} already up to date",
+                style("○").yellow(),
+                result.skipped
+            );
+        }
+        if result.failed > 0 {
+            println!("  {} {} failed", style("✗").red(), result.failed);
+        }
+        println!("Set agents.sessions.backend in the config to use the converted store.");
+    } else {
+        println!("{} No sessions found to convert", style("○").dim());
+    }
+}
+
 fn print_memory_result(result: &memory_migration::MigrationResult) {
     println!();
     println!("{}", style("Memory Migration").bold());
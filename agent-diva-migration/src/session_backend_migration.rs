@@ -0,0 +1,145 @@
+//! Session conversion between the JSONL and SQLite backends
+//!
+//! Copies every live session from one backend of a workspace into the other.
+//! The source is left untouched, so switching `agents.sessions.backend` back
+//! is always possible.
+
+use agent_diva_core::config::schema::SessionBackend;
+use agent_diva_core::session::{open_session_store, SessionStore, SQLITE_SESSION_FILE};
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use tracing::{error, info};
+
+/// Result of a backend conversion
+#[derive(Debug, Default)]
+pub struct ConversionResult {
+    pub total: usize,
+    pub converted: usize,
+    /// Sessions already present in the target with the same or newer update time
+    pub skipped: usize,
+    pub failed: usize,
+}
+
+/// Converts the sessions of one workspace between backends
+pub struct SessionBackendConverter {
+    sessions_dir: PathBuf,
+}
+
+impl SessionBackendConverter {
+    /// Create a converter for the sessions of `workspace`
+    pub fn new(workspace: impl AsRef<Path>) -> Self {
+        Self {
+            sessions_dir: workspace.as_ref().join("sessions"),
+        }
+    }
+
+    /// Copy all sessions into the `target` backend
+    pub fn convert(&self, target: SessionBackend, dry_run: bool) -> Result<ConversionResult> {
+        let source = match target {
+            SessionBackend::Jsonl => SessionBackend::Sqlite,
+            SessionBackend::Sqlite => SessionBackend::Jsonl,
+        };
+        let mut result = ConversionResult::default();
+        if !self.sessions_dir.exists() {
+            info!("No sessions directory found at {:?}", self.sessions_dir);
+            return Ok(result);
+        }
+        if source == SessionBackend::Sqlite && !self.sessions_dir.join(SQLITE_SESSION_FILE).exists()
+        {
+            info!("No SQLite session store found in {:?}", self.sessions_dir);
+            return Ok(result);
+        }
+
+        let source_store = open_session_store(&self.sessions_dir, source)
+            .with_context(|| format!("Failed to open {:?} session store", source))?;
+        let target_store: Option<Box<dyn SessionStore>> = if dry_run {
+            None
+        } else {
+            Some(
+                open_session_store(&self.sessions_dir, target)
+                    .with_context(|| format!("Failed to open {:?} session store", target))?,
+            )
+        };
+
+        for info in source_store.list() {
+            result.total += 1;
+            match self.convert_session(&*source_store, target_store.as_deref(), &info.key) {
+                Ok(true) => result.converted += 1,
+                Ok(false) => result.skipped += 1,
+                Err(e) => {
+                    result.failed += 1;
+                    error!("Failed to convert session {}: {}", info.key, e);
+                }
+            }
+        }
+
+        info!(
+            "Session conversion to {:?} complete: {}/{} converted, {} skipped",
+            target, result.converted, result.total, result.skipped
+        );
+        Ok(result)
+    }
+
+    /// Returns `false` when the target already holds an up-to-date copy
+    fn convert_session(
+        &self,
+        source: &dyn SessionStore,
+        target: Option<&dyn SessionStore>,
+        key: &str,
+    ) -> Result<bool> {
+        let session = source
+            .load(key)?
+            .with_context(|| format!("Session {} disappeared during conversion", key))?;
+        let Some(target) = target else {
+            info!(
+                "Dry run: would convert session {} with {} messages",
+                key,
+                session.messages.len()
+            );
+            return Ok(true);
+        };
+        if let Some(existing) = target.load(key)? {
+            if existing.updated_at >= session.updated_at {
+                return Ok(false);
+            }
+        }
+        target.save(&session)?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agent_diva_core::session::{JsonlSessionStore, Session};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_round_trip_between_backends() {
+        let temp_dir = TempDir::new().unwrap();
+        let sessions_dir = temp_dir.path().join("sessions");
+        let jsonl = JsonlSessionStore::new(&sessions_dir);
+        let mut session = Session::new("telegram:7");
+        session.add_message("user", "hello");
+        session.add_message("assistant", "hi");
+        std::fs::create_dir_all(&sessions_dir).unwrap();
+        jsonl.save(&session).unwrap();
+
+        let converter = SessionBackendConverter::new(temp_dir.path());
+        let dry = converter.convert(SessionBackend::Sqlite, true).unwrap();
+        assert_eq!((dry.total, dry.converted), (1, 1));
+        assert!(!sessions_dir.join("sessions.db").exists());
+
+        let result = converter.convert(SessionBackend::Sqlite, false).unwrap();
+        assert_eq!((result.converted, result.skipped), (1, 0));
+        let again = converter.convert(SessionBackend::Sqlite, false).unwrap();
+        assert_eq!((again.converted, again.skipped), (0, 1));
+
+        std::fs::remove_file(sessions_dir.join("telegram_7.jsonl")).unwrap();
+        let back = converter.convert(SessionBackend::Jsonl, false).unwrap();
+        assert_eq!(back.converted, 1);
+        let restored = jsonl.load("telegram:7").unwrap().unwrap();
+        assert_eq!(restored.messages.len(), 2);
+        assert_eq!(restored.messages[1].content, "hi");
+    }
+}
@@ -1,16 +1,22 @@
 ﻿//! Neuron foundations for non-looping, single-turn LLM execution.
 //!
-//! This crate intentionally provides only building blocks:
+//! This crate provides building blocks plus a small graph layer on top:
 //! - request/response contracts
 //! - node trait and default provider-backed executor
-//! - local event protocol for future graph-level orchestration
+//! - local event protocol shared by neurons and workflows
+//! - workflow DAGs of neuron, tool and branch nodes
 
 pub mod events;
 pub mod executor;
 pub mod node;
 pub mod types;
+pub mod workflow;
 
 pub use events::NeuronEvent;
 pub use executor::LlmNeuron;
 pub use node::{NeuronError, NeuronNode};
 pub use types::{NeuronRequest, NeuronResponse};
+pub use workflow::{
+    Workflow, WorkflowError, WorkflowEvent, WorkflowExecutor, WorkflowNode, WorkflowNodeKind,
+    WorkflowRun, WorkflowTools,
+};
@@ -0,0 +1,268 @@
+//! Workflow definitions as loaded from YAML or JSON.
+
+use super::template::{references, value_references, Reference};
+use super::WorkflowError;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+/// A DAG of nodes plus the inputs it accepts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Workflow {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Accepted inputs and their default values.
+    #[serde(default)]
+    pub inputs: BTreeMap<String, String>,
+    pub nodes: Vec<WorkflowNode>,
+    /// Template for the run's result. Defaults to the output of the last
+    /// non-branch node (in definition order) that ran.
+    #[serde(default)]
+    pub output: Option<String>,
+}
+
+/// One step of a workflow.
+///
+/// A node runs once all of its dependencies have finished. Dependencies are
+/// `depends_on`, every node referenced by its templates, and the branch node
+/// that lists it in `then` or `else`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowNode {
+    pub id: String,
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// Extra attempts after a failed or timed-out one.
+    #[serde(default)]
+    pub retries: u32,
+    /// Limit for each attempt.
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    #[serde(flatten)]
+    pub kind: WorkflowNodeKind,
+}
+
+/// What a node does, selected by its `kind` field.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum WorkflowNodeKind {
+    /// One single-turn LLM call.
+    Neuron {
+        prompt: String,
+        #[serde(default)]
+        system: Option<String>,
+        #[serde(default)]
+        model: Option<String>,
+        #[serde(default)]
+        max_tokens: Option<i32>,
+        #[serde(default)]
+        temperature: Option<f64>,
+    },
+    /// One tool call; string values in `args` are templates.
+    Tool {
+        tool: String,
+        #[serde(default)]
+        args: serde_json::Value,
+    },
+    /// Chooses between `then` and `else`; the nodes of the other list are
+    /// skipped. Without `equals` or `contains`, the rendered condition is
+    /// true unless it is empty, `false`, `0`, `no`, `none` or `null`.
+    Branch {
+        condition: String,
+        #[serde(default)]
+        equals: Option<String>,
+        #[serde(default)]
+        contains: Option<String>,
+        #[serde(default)]
+        then: Vec<String>,
+        #[serde(default, rename = "else")]
+        otherwise: Vec<String>,
+    },
+}
+
+impl Workflow {
+    /// Parse and validate a YAML workflow.
+    pub fn from_yaml_str(source: &str) -> Result<Self, WorkflowError> {
+        let workflow: Self =
+            serde_yaml::from_str(source).map_err(|e| WorkflowError::Parse(e.to_string()))?;
+        workflow.validate()?;
+        Ok(workflow)
+    }
+
+    /// Parse and validate a JSON workflow.
+    pub fn from_json_str(source: &str) -> Result<Self, WorkflowError> {
+        let workflow: Self =
+            serde_json::from_str(source).map_err(|e| WorkflowError::Parse(e.to_string()))?;
+        workflow.validate()?;
+        Ok(workflow)
+    }
+
+    /// Load a workflow file; `.json` files are read as JSON, anything else as YAML.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, WorkflowError> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path)?;
+        let is_json = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        if is_json {
+            Self::from_json_str(&source)
+        } else {
+            Self::from_yaml_str(&source)
+        }
+    }
+
+    /// Look up a node by id.
+    pub fn node(&self, id: &str) -> Option<&WorkflowNode> {
+        self.nodes.iter().find(|node| node.id == id)
+    }
+
+    /// Check ids, references and that the graph has no cycles.
+    pub fn validate(&self) -> Result<(), WorkflowError> {
+        if self.nodes.is_empty() {
+            return Err(invalid("a workflow needs at least one node"));
+        }
+        let mut ids = HashSet::new();
+        for node in &self.nodes {
+            if node.id.trim().is_empty() {
+                return Err(invalid("node ids cannot be empty"));
+            }
+            if !ids.insert(node.id.as_str()) {
+                return Err(invalid(format!("duplicate node id '{}'", node.id)));
+            }
+        }
+
+        let dependencies = self.dependencies()?;
+        for node in &self.nodes {
+            for dependency in &dependencies[&node.id] {
+                if dependency == &node.id {
+                    return Err(invalid(format!("node '{}' depends on itself", node.id)));
+                }
+                if !ids.contains(dependency.as_str()) {
+                    return Err(invalid(format!(
+                        "node '{}' refers to unknown node '{}'",
+                        node.id, dependency
+                    )));
+                }
+            }
+            for reference in node_references(node)? {
+                if let Reference::Input(name) = reference {
+                    if !self.inputs.contains_key(&name) {
+                        return Err(invalid(format!(
+                            "node '{}' uses undeclared input '{}'",
+                            node.id, name
+                        )));
+                    }
+                }
+            }
+        }
+        if let Some(output) = &self.output {
+            for reference in references(output)? {
+                let unknown = match &reference {
+                    Reference::Input(name) if !self.inputs.contains_key(name) => {
+                        format!("input '{}'", name)
+                    }
+                    Reference::Node { id, .. } if !ids.contains(id.as_str()) => {
+                        format!("node '{}'", id)
+                    }
+                    _ => continue,
+                };
+                return Err(invalid(format!(
+                    "workflow output refers to unknown {}",
+                    unknown
+                )));
+            }
+        }
+
+        // Kahn's algorithm: every node must become ready eventually.
+        let mut remaining: HashMap<&str, usize> = dependencies
+            .iter()
+            .map(|(id, deps)| (id.as_str(), deps.len()))
+            .collect();
+        let mut ready: Vec<&str> = remaining
+            .iter()
+            .filter(|(_, count)| **count == 0)
+            .map(|(id, _)| *id)
+            .collect();
+        let mut visited = 0;
+        while let Some(id) = ready.pop() {
+            visited += 1;
+            for (other, deps) in &dependencies {
+                if deps.iter().any(|dep| dep == id) {
+                    let count = remaining.get_mut(other.as_str()).expect("known node");
+                    *count -= 1;
+                    if *count == 0 {
+                        ready.push(other.as_str());
+                    }
+                }
+            }
+        }
+        if visited != self.nodes.len() {
+            let mut cyclic: Vec<&str> = remaining
+                .into_iter()
+                .filter(|(_, count)| *count > 0)
+                .map(|(id, _)| id)
+                .collect();
+            cyclic.sort_unstable();
+            return Err(invalid(format!(
+                "dependency cycle between nodes: {}",
+                cyclic.join(", ")
+            )));
+        }
+        Ok(())
+    }
+
+    /// Direct dependencies of every node, deduplicated and in first-seen order.
+    pub(crate) fn dependencies(&self) -> Result<HashMap<String, Vec<String>>, WorkflowError> {
+        let mut dependencies: HashMap<String, Vec<String>> = HashMap::new();
+        for node in &self.nodes {
+            let mut deps = node.depends_on.clone();
+            for reference in node_references(node)? {
+                if let Reference::Node { id, .. } = reference {
+                    deps.push(id);
+                }
+            }
+            dependencies.insert(node.id.clone(), deps);
+        }
+        for node in &self.nodes {
+            if let WorkflowNodeKind::Branch {
+                then, otherwise, ..
+            } = &node.kind
+            {
+                for target in then.iter().chain(otherwise) {
+                    let deps = dependencies.get_mut(target).ok_or_else(|| {
+                        invalid(format!(
+                            "branch '{}' targets unknown node '{}'",
+                            node.id, target
+                        ))
+                    })?;
+                    deps.push(node.id.clone());
+                }
+            }
+        }
+        for deps in dependencies.values_mut() {
+            let mut seen = HashSet::new();
+            deps.retain(|dep| seen.insert(dep.clone()));
+        }
+        Ok(dependencies)
+    }
+}
+
+/// Placeholders used by a node's templates.
+fn node_references(node: &WorkflowNode) -> Result<Vec<Reference>, WorkflowError> {
+    match &node.kind {
+        WorkflowNodeKind::Neuron { prompt, system, .. } => {
+            let mut found = references(prompt)?;
+            if let Some(system) = system {
+                found.extend(references(system)?);
+            }
+            Ok(found)
+        }
+        WorkflowNodeKind::Tool { args, .. } => value_references(args),
+        WorkflowNodeKind::Branch { condition, .. } => references(condition),
+    }
+}
+
+fn invalid(message: impl Into<String>) -> WorkflowError {
+    WorkflowError::Invalid(message.into())
+}
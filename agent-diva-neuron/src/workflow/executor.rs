@@ -0,0 +1,433 @@
+//! Parallel execution of workflow graphs.
+
+use super::definition::{Workflow, WorkflowNode, WorkflowNodeKind};
+use super::template::{render, render_value, TemplateScope};
+use super::WorkflowError;
+use crate::events::NeuronEvent;
+use crate::executor::LlmNeuron;
+use crate::node::NeuronNode;
+use crate::types::NeuronRequest;
+use agent_diva_providers::{LLMProvider, Message};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinSet;
+
+/// Tools available to `tool` nodes.
+#[async_trait]
+pub trait WorkflowTools: Send + Sync {
+    /// Run `name` with `args`. An `Err` fails the attempt.
+    async fn call_tool(&self, name: &str, args: Value) -> Result<String, String>;
+}
+
+/// Progress of a workflow run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WorkflowEvent {
+    NodeStarted {
+        node_id: String,
+        attempt: u32,
+    },
+    /// Streamed output of a neuron node; `neuron_id` is the node id.
+    Neuron {
+        event: NeuronEvent,
+    },
+    NodeRetrying {
+        node_id: String,
+        attempt: u32,
+        error: String,
+    },
+    NodeCompleted {
+        node_id: String,
+        output: String,
+    },
+    NodeSkipped {
+        node_id: String,
+    },
+    NodeFailed {
+        node_id: String,
+        error: String,
+    },
+    Finished {
+        output: Option<String>,
+    },
+}
+
+/// Result of a successful run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowRun {
+    pub name: String,
+    /// Outputs of the nodes that ran, by node id.
+    pub outputs: BTreeMap<String, String>,
+    /// Nodes skipped by branches, in the order they were skipped.
+    pub skipped: Vec<String>,
+    pub output: Option<String>,
+}
+
+/// Runs workflows against one provider and an optional tool set.
+pub struct WorkflowExecutor {
+    provider: Arc<dyn LLMProvider>,
+    tools: Option<Arc<dyn WorkflowTools>>,
+    model: Option<String>,
+}
+
+type EventSender = Option<mpsc::UnboundedSender<WorkflowEvent>>;
+
+fn emit(event_tx: &EventSender, event: WorkflowEvent) {
+    if let Some(tx) = event_tx {
+        let _ = tx.send(event);
+    }
+}
+
+/// A node with its templates already rendered.
+enum PreparedNode {
+    Neuron { request: NeuronRequest },
+    Tool { name: String, args: Value },
+}
+
+impl WorkflowExecutor {
+    /// Create an executor whose neuron nodes call `provider`.
+    pub fn new(provider: Arc<dyn LLMProvider>) -> Self {
+        Self {
+            provider,
+            tools: None,
+            model: None,
+        }
+    }
+
+    /// Make tools available to `tool` nodes.
+    pub fn with_tools(mut self, tools: Arc<dyn WorkflowTools>) -> Self {
+        self.tools = Some(tools);
+        self
+    }
+
+    /// Model for neuron nodes that do not set one.
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    /// Run `workflow` to completion.
+    ///
+    /// `inputs` override the workflow's defaults. Nodes whose dependencies
+    /// are done run concurrently; the first node that fails after its retries
+    /// fails the run and cancels the nodes still in flight.
+    pub async fn run(
+        &self,
+        workflow: &Workflow,
+        inputs: BTreeMap<String, String>,
+        event_tx: EventSender,
+    ) -> Result<WorkflowRun, WorkflowError> {
+        workflow.validate()?;
+        let mut values = workflow.inputs.clone();
+        for (name, value) in inputs {
+            if !values.contains_key(&name) {
+                return Err(WorkflowError::Invalid(format!(
+                    "workflow '{}' has no input '{}'",
+                    workflow.name, name
+                )));
+            }
+            values.insert(name, value);
+        }
+
+        let dependencies = workflow.dependencies()?;
+        let mut outputs: HashMap<String, Option<String>> = HashMap::new();
+        let mut skipped = Vec::new();
+        let mut disabled: HashSet<String> = HashSet::new();
+        let mut pending: Vec<&WorkflowNode> = workflow.nodes.iter().collect();
+        let mut running: JoinSet<(String, Result<String, String>)> = JoinSet::new();
+
+        loop {
+            // Start (or skip) every node whose dependencies are done; skipping
+            // and branching can unblock further nodes, so repeat until stable.
+            let mut progressed = true;
+            while progressed {
+                progressed = false;
+                let mut index = 0;
+                while index < pending.len() {
+                    let node = pending[index];
+                    let deps = &dependencies[&node.id];
+                    if !deps.iter().all(|dep| outputs.contains_key(dep)) {
+                        index += 1;
+                        continue;
+                    }
+                    pending.remove(index);
+                    progressed = true;
+
+                    let all_deps_skipped =
+                        !deps.is_empty() && deps.iter().all(|dep| outputs[dep].is_none());
+                    if disabled.contains(&node.id) || all_deps_skipped {
+                        outputs.insert(node.id.clone(), None);
+                        skipped.push(node.id.clone());
+                        emit(
+                            &event_tx,
+                            WorkflowEvent::NodeSkipped {
+                                node_id: node.id.clone(),
+                            },
+                        );
+                        continue;
+                    }
+
+                    let scope = TemplateScope {
+                        inputs: &values,
+                        outputs: &outputs,
+                    };
+                    if let WorkflowNodeKind::Branch {
+                        condition,
+                        equals,
+                        contains,
+                        then,
+                        otherwise,
+                    } = &node.kind
+                    {
+                        emit(
+                            &event_tx,
+                            WorkflowEvent::NodeStarted {
+                                node_id: node.id.clone(),
+                                attempt: 1,
+                            },
+                        );
+                        let value = render(condition, &scope)
+                            .map_err(|e| fail(&event_tx, &node.id, e.to_string()))?;
+                        let taken =
+                            evaluate_condition(&value, equals.as_deref(), contains.as_deref());
+                        disabled.extend(if taken { otherwise } else { then }.iter().cloned());
+                        let output = taken.to_string();
+                        emit(
+                            &event_tx,
+                            WorkflowEvent::NodeCompleted {
+                                node_id: node.id.clone(),
+                                output: output.clone(),
+                            },
+                        );
+                        outputs.insert(node.id.clone(), Some(output));
+                        continue;
+                    }
+
+                    let prepared = self
+                        .prepare(node, &scope)
+                        .map_err(|e| fail(&event_tx, &node.id, e.to_string()))?;
+                    running.spawn(run_node(
+                        Arc::clone(&self.provider),
+                        self.tools.clone(),
+                        node.clone(),
+                        prepared,
+                        event_tx.clone(),
+                    ));
+                }
+            }
+
+            let Some(joined) = running.join_next().await else {
+                break;
+            };
+            let (node_id, result) = joined.map_err(|e| WorkflowError::NodeFailed {
+                node_id: "<unknown>".to_string(),
+                error: format!("node task panicked: {}", e),
+            })?;
+            match result {
+                Ok(output) => {
+                    emit(
+                        &event_tx,
+                        WorkflowEvent::NodeCompleted {
+                            node_id: node_id.clone(),
+                            output: output.clone(),
+                        },
+                    );
+                    outputs.insert(node_id, Some(output));
+                }
+                Err(error) => return Err(fail(&event_tx, &node_id, error)),
+            }
+        }
+
+        if !pending.is_empty() {
+            return Err(WorkflowError::Invalid(format!(
+                "nodes never became ready: {}",
+                pending
+                    .iter()
+                    .map(|node| node.id.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )));
+        }
+
+        let output = match &workflow.output {
+            Some(template) => Some(render(
+                template,
+                &TemplateScope {
+                    inputs: &values,
+                    outputs: &outputs,
+                },
+            )?),
+            None => workflow
+                .nodes
+                .iter()
+                .rev()
+                .filter(|node| !matches!(node.kind, WorkflowNodeKind::Branch { .. }))
+                .find_map(|node| outputs.get(&node.id).cloned().flatten()),
+        };
+        emit(
+            &event_tx,
+            WorkflowEvent::Finished {
+                output: output.clone(),
+            },
+        );
+        Ok(WorkflowRun {
+            name: workflow.name.clone(),
+            outputs: outputs
+                .into_iter()
+                .filter_map(|(id, output)| output.map(|output| (id, output)))
+                .collect(),
+            skipped,
+            output,
+        })
+    }
+
+    fn prepare(
+        &self,
+        node: &WorkflowNode,
+        scope: &TemplateScope<'_>,
+    ) -> Result<PreparedNode, WorkflowError> {
+        match &node.kind {
+            WorkflowNodeKind::Neuron {
+                prompt,
+                system,
+                model,
+                max_tokens,
+                temperature,
+            } => {
+                let mut messages = Vec::new();
+                if let Some(system) = system {
+                    messages.push(Message::system(render(system, scope)?));
+                }
+                messages.push(Message::user(render(prompt, scope)?));
+                let defaults = NeuronRequest::default();
+                let mut request = NeuronRequest::new(
+                    messages,
+                    max_tokens.unwrap_or(defaults.max_tokens),
+                    temperature.unwrap_or(defaults.temperature),
+                );
+                request.model = model.clone().or_else(|| self.model.clone());
+                Ok(PreparedNode::Neuron { request })
+            }
+            WorkflowNodeKind::Tool { tool, args } => Ok(PreparedNode::Tool {
+                name: tool.clone(),
+                args: if args.is_null() {
+                    Value::Object(Default::default())
+                } else {
+                    render_value(args, scope)?
+                },
+            }),
+            WorkflowNodeKind::Branch { .. } => Err(WorkflowError::Invalid(format!(
+                "branch node '{}' cannot be dispatched",
+                node.id
+            ))),
+        }
+    }
+}
+
+fn fail(event_tx: &EventSender, node_id: &str, error: String) -> WorkflowError {
+    emit(
+        event_tx,
+        WorkflowEvent::NodeFailed {
+            node_id: node_id.to_string(),
+            error: error.clone(),
+        },
+    );
+    WorkflowError::NodeFailed {
+        node_id: node_id.to_string(),
+        error,
+    }
+}
+
+fn evaluate_condition(value: &str, equals: Option<&str>, contains: Option<&str>) -> bool {
+    let value = value.trim();
+    if let Some(expected) = equals {
+        return value == expected.trim();
+    }
+    if let Some(needle) = contains {
+        return value.contains(needle);
+    }
+    !matches!(
+        value.to_ascii_lowercase().as_str(),
+        "" | "false" | "0" | "no" | "none" | "null"
+    )
+}
+
+/// Run one node with its retries and per-attempt timeout.
+async fn run_node(
+    provider: Arc<dyn LLMProvider>,
+    tools: Option<Arc<dyn WorkflowTools>>,
+    node: WorkflowNode,
+    prepared: PreparedNode,
+    event_tx: EventSender,
+) -> (String, Result<String, String>) {
+    let attempts = node.retries.saturating_add(1);
+    let mut attempt = 1;
+    let result = loop {
+        emit(
+            &event_tx,
+            WorkflowEvent::NodeStarted {
+                node_id: node.id.clone(),
+                attempt,
+            },
+        );
+        let run = run_attempt(&provider, tools.as_deref(), &node.id, &prepared, &event_tx);
+        let result = match node.timeout_secs {
+            Some(secs) => tokio::time::timeout(Duration::from_secs(secs), run)
+                .await
+                .unwrap_or_else(|_| Err(format!("timed out after {} seconds", secs))),
+            None => run.await,
+        };
+        match result {
+            Err(error) if attempt < attempts => {
+                emit(
+                    &event_tx,
+                    WorkflowEvent::NodeRetrying {
+                        node_id: node.id.clone(),
+                        attempt,
+                        error,
+                    },
+                );
+                attempt += 1;
+            }
+            result => break result,
+        }
+    };
+    (node.id, result)
+}
+
+async fn run_attempt(
+    provider: &Arc<dyn LLMProvider>,
+    tools: Option<&dyn WorkflowTools>,
+    node_id: &str,
+    prepared: &PreparedNode,
+    event_tx: &EventSender,
+) -> Result<String, String> {
+    match prepared {
+        PreparedNode::Neuron { request } => {
+            let neuron = LlmNeuron::with_id(Arc::clone(provider), node_id);
+            let neuron_tx = event_tx.clone().map(|workflow_tx| {
+                let (tx, mut rx) = mpsc::unbounded_channel();
+                tokio::spawn(async move {
+                    while let Some(event) = rx.recv().await {
+                        let _ = workflow_tx.send(WorkflowEvent::Neuron { event });
+                    }
+                });
+                tx
+            });
+            let response = neuron
+                .run_once_stream(request.clone(), neuron_tx)
+                .await
+                .map_err(|e| e.to_string())?;
+            Ok(response.content.unwrap_or_default())
+        }
+        PreparedNode::Tool { name, args } => {
+            let tools = tools.ok_or_else(|| "no tools are available to this run".to_string())?;
+            tools.call_tool(name, args.clone()).await
+        }
+    }
+}
@@ -0,0 +1,34 @@
+//! Workflow graphs built from neuron, tool and branch nodes.
+//!
+//! A [`Workflow`] is a DAG loaded from YAML or JSON. Node inputs are
+//! templates that reference workflow inputs and upstream outputs, and the
+//! [`WorkflowExecutor`] runs independent nodes in parallel while streaming
+//! [`WorkflowEvent`]s.
+
+mod definition;
+mod executor;
+mod template;
+
+pub use definition::{Workflow, WorkflowNode, WorkflowNodeKind};
+pub use executor::{WorkflowEvent, WorkflowExecutor, WorkflowRun, WorkflowTools};
+
+use thiserror::Error;
+
+/// Error type for loading and running workflows.
+#[derive(Debug, Error)]
+pub enum WorkflowError {
+    #[error("Failed to read workflow: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Failed to parse workflow: {0}")]
+    Parse(String),
+
+    #[error("Invalid workflow: {0}")]
+    Invalid(String),
+
+    #[error("Template error: {0}")]
+    Template(String),
+
+    #[error("Node '{node_id}' failed: {error}")]
+    NodeFailed { node_id: String, error: String },
+}
@@ -0,0 +1,174 @@
+//! `{{ ... }}` placeholders in node inputs.
+//!
+//! Supported references:
+//! - `{{ inputs.<name> }}`
+//! - `{{ nodes.<id>.output }}`
+//! - `{{ nodes.<id>.output.<path> }}`, a dot path into JSON output
+
+use super::WorkflowError;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+/// A parsed placeholder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Reference {
+    Input(String),
+    Node { id: String, path: Vec<String> },
+}
+
+/// Values visible to templates while a workflow runs.
+pub(crate) struct TemplateScope<'a> {
+    pub inputs: &'a BTreeMap<String, String>,
+    /// `None` marks a skipped node, which renders as an empty string.
+    pub outputs: &'a HashMap<String, Option<String>>,
+}
+
+/// Split `template` into literal text and parsed placeholders.
+fn parse(template: &str) -> Result<Vec<(&str, Option<Reference>)>, WorkflowError> {
+    let mut parts = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let end = rest[start..]
+            .find("}}")
+            .map(|offset| start + offset)
+            .ok_or_else(|| {
+                WorkflowError::Template(format!("unclosed placeholder in '{}'", template))
+            })?;
+        parts.push((&rest[..start], None));
+        parts.push(("", Some(parse_reference(rest[start + 2..end].trim())?)));
+        rest = &rest[end + 2..];
+    }
+    parts.push((rest, None));
+    Ok(parts)
+}
+
+fn parse_reference(expr: &str) -> Result<Reference, WorkflowError> {
+    let segments: Vec<&str> = expr.split('.').map(str::trim).collect();
+    if segments.iter().any(|segment| segment.is_empty()) {
+        return Err(WorkflowError::Template(format!(
+            "invalid placeholder '{{{{ {} }}}}'",
+            expr
+        )));
+    }
+    match segments.as_slice() {
+        ["inputs", name] => Ok(Reference::Input(name.to_string())),
+        ["nodes", id, "output", path @ ..] => Ok(Reference::Node {
+            id: id.to_string(),
+            path: path.iter().map(|segment| segment.to_string()).collect(),
+        }),
+        _ => Err(WorkflowError::Template(format!(
+            "unknown placeholder '{{{{ {} }}}}'; use inputs.<name> or nodes.<id>.output",
+            expr
+        ))),
+    }
+}
+
+/// Every placeholder in `template`.
+pub(crate) fn references(template: &str) -> Result<Vec<Reference>, WorkflowError> {
+    Ok(parse(template)?
+        .into_iter()
+        .filter_map(|(_, reference)| reference)
+        .collect())
+}
+
+/// Every placeholder in the string values of `value`.
+pub(crate) fn value_references(value: &Value) -> Result<Vec<Reference>, WorkflowError> {
+    let mut found = Vec::new();
+    match value {
+        Value::String(text) => found.extend(references(text)?),
+        Value::Array(items) => {
+            for item in items {
+                found.extend(value_references(item)?);
+            }
+        }
+        Value::Object(map) => {
+            for item in map.values() {
+                found.extend(value_references(item)?);
+            }
+        }
+        _ => {}
+    }
+    Ok(found)
+}
+
+/// Substitute every placeholder in `template`.
+pub(crate) fn render(template: &str, scope: &TemplateScope<'_>) -> Result<String, WorkflowError> {
+    let mut rendered = String::with_capacity(template.len());
+    for (text, reference) in parse(template)? {
+        rendered.push_str(text);
+        if let Some(reference) = reference {
+            rendered.push_str(&resolve(&reference, scope)?);
+        }
+    }
+    Ok(rendered)
+}
+
+/// Render the string values of `value`; keys and other values are kept.
+pub(crate) fn render_value(
+    value: &Value,
+    scope: &TemplateScope<'_>,
+) -> Result<Value, WorkflowError> {
+    Ok(match value {
+        Value::String(text) => Value::String(render(text, scope)?),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| render_value(item, scope))
+                .collect::<Result<_, _>>()?,
+        ),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, item)| Ok((key.clone(), render_value(item, scope)?)))
+                .collect::<Result<_, WorkflowError>>()?,
+        ),
+        other => other.clone(),
+    })
+}
+
+fn resolve(reference: &Reference, scope: &TemplateScope<'_>) -> Result<String, WorkflowError> {
+    match reference {
+        Reference::Input(name) => scope
+            .inputs
+            .get(name)
+            .cloned()
+            .ok_or_else(|| WorkflowError::Template(format!("unknown input '{}'", name))),
+        Reference::Node { id, path } => {
+            let output = scope
+                .outputs
+                .get(id)
+                .ok_or_else(|| {
+                    WorkflowError::Template(format!("output of node '{}' is not available", id))
+                })?
+                .as_deref()
+                .unwrap_or_default();
+            if path.is_empty() {
+                return Ok(output.to_string());
+            }
+            let json: Value = serde_json::from_str(output).map_err(|e| {
+                WorkflowError::Template(format!("output of node '{}' is not JSON: {}", id, e))
+            })?;
+            let mut current = &json;
+            for segment in path {
+                current = match current {
+                    Value::Object(map) => map.get(segment),
+                    Value::Array(items) => segment
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|index| items.get(index)),
+                    _ => None,
+                }
+                .ok_or_else(|| {
+                    WorkflowError::Template(format!(
+                        "output of node '{}' has no field '{}'",
+                        id,
+                        path.join(".")
+                    ))
+                })?;
+            }
+            Ok(match current {
+                Value::String(text) => text.clone(),
+                other => other.to_string(),
+            })
+        }
+    }
+}
@@ -0,0 +1,339 @@
+use agent_diva_neuron::{
+    NeuronEvent, Workflow, WorkflowError, WorkflowEvent, WorkflowExecutor, WorkflowTools,
+};
+use agent_diva_providers::{LLMProvider, LLMResponse, Message, ProviderResult};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{mpsc, Barrier};
+
+/// Answers `echo: <last user message>`; prompts starting with `sync` meet at
+/// a barrier, and the first `slow_calls` calls hang.
+struct EchoProvider {
+    barrier: Option<Arc<Barrier>>,
+    slow_calls: usize,
+    calls: AtomicUsize,
+}
+
+impl EchoProvider {
+    fn new() -> Self {
+        Self {
+            barrier: None,
+            slow_calls: 0,
+            calls: AtomicUsize::new(0),
+        }
+    }
+}
+
+#[async_trait]
+impl LLMProvider for EchoProvider {
+    async fn chat(
+        &self,
+        messages: Vec<Message>,
+        _tools: Option<Vec<serde_json::Value>>,
+        _model: Option<String>,
+        _max_tokens: i32,
+        _temperature: f64,
+    ) -> ProviderResult<LLMResponse> {
+        let call = self.calls.fetch_add(1, Ordering::SeqCst);
+        if call < self.slow_calls {
+            std::future::pending::<()>().await;
+        }
+        let prompt = messages
+            .last()
+            .map(|message| message.content.to_text_lossy())
+            .unwrap_or_default();
+        if let Some(barrier) = &self.barrier {
+            if prompt.starts_with("sync") {
+                barrier.wait().await;
+            }
+        }
+        Ok(LLMResponse {
+            content: Some(format!("echo: {}", prompt)),
+            tool_calls: Vec::new(),
+            finish_reason: "stop".to_string(),
+            usage: HashMap::new(),
+            reasoning_content: None,
+        })
+    }
+
+    fn get_default_model(&self) -> String {
+        "mock-model".to_string()
+    }
+}
+
+#[derive(Default)]
+struct RecordingTools {
+    calls: Mutex<Vec<(String, Value)>>,
+}
+
+#[async_trait]
+impl WorkflowTools for RecordingTools {
+    async fn call_tool(&self, name: &str, args: Value) -> Result<String, String> {
+        self.calls
+            .lock()
+            .unwrap()
+            .push((name.to_string(), args.clone()));
+        match name {
+            "fetch" => Ok(json!({"items": ["alpha", "beta"]}).to_string()),
+            "broken" => Err("tool exploded".to_string()),
+            _ => Ok(format!("{} done", name)),
+        }
+    }
+}
+
+fn drain(mut rx: mpsc::UnboundedReceiver<WorkflowEvent>) -> Vec<WorkflowEvent> {
+    let mut events = Vec::new();
+    while let Ok(event) = rx.try_recv() {
+        events.push(event);
+    }
+    events
+}
+
+#[tokio::test]
+async fn yaml_workflow_renders_templates_across_nodes() {
+    let workflow = Workflow::from_yaml_str(
+        r#"
+name: digest
+inputs:
+  topic: rust
+nodes:
+  - id: fetch
+    kind: tool
+    tool: fetch
+    args:
+      query: "{{ inputs.topic }} news"
+  - id: summarize
+    kind: neuron
+    system: You summarize.
+    prompt: "Second item: {{ nodes.fetch.output.items.1 }}"
+  - id: save
+    kind: tool
+    tool: write
+    args:
+      content: "{{nodes.summarize.output}}"
+      tags: ["{{ inputs.topic }}", 3]
+output: "saved {{ nodes.save.output }}"
+"#,
+    )
+    .expect("workflow should parse");
+    let tools = Arc::new(RecordingTools::default());
+    let executor = WorkflowExecutor::new(Arc::new(EchoProvider::new())).with_tools(tools.clone());
+    let (tx, rx) = mpsc::unbounded_channel();
+
+    let run = executor
+        .run(
+            &workflow,
+            BTreeMap::from([("topic".to_string(), "tokio".to_string())]),
+            Some(tx),
+        )
+        .await
+        .expect("workflow should run");
+
+    assert_eq!(run.outputs["summarize"], "echo: Second item: beta");
+    assert_eq!(run.output.as_deref(), Some("saved write done"));
+    let calls = tools.calls.lock().unwrap().clone();
+    assert_eq!(
+        calls[0],
+        ("fetch".to_string(), json!({"query": "tokio news"}))
+    );
+    assert_eq!(
+        calls[1].1,
+        json!({"content": "echo: Second item: beta", "tags": ["tokio", 3]})
+    );
+
+    let events = drain(rx);
+    assert!(events.iter().any(|event| matches!(
+        event,
+        WorkflowEvent::Neuron {
+            event: NeuronEvent::TextDelta { neuron_id, .. }
+        } if neuron_id == "summarize"
+    )));
+    assert!(matches!(
+        events.last(),
+        Some(WorkflowEvent::Finished { .. })
+    ));
+}
+
+#[tokio::test]
+async fn branch_skips_the_other_path_and_joins() {
+    let workflow = Workflow::from_json_str(
+        &json!({
+            "name": "triage",
+            "inputs": {"status": "disk full"},
+            "nodes": [
+                {"id": "check", "kind": "branch", "condition": "{{ inputs.status }}",
+                 "contains": "full", "then": ["alert"], "else": ["ok"]},
+                {"id": "alert", "kind": "tool", "tool": "page"},
+                {"id": "ok", "kind": "tool", "tool": "log"},
+                {"id": "after_ok", "kind": "tool", "tool": "archive", "depends_on": ["ok"]},
+                {"id": "report", "kind": "neuron",
+                 "prompt": "alert={{ nodes.alert.output }} ok={{ nodes.ok.output }}"}
+            ]
+        })
+        .to_string(),
+    )
+    .expect("workflow should parse");
+    let tools = Arc::new(RecordingTools::default());
+    let executor = WorkflowExecutor::new(Arc::new(EchoProvider::new())).with_tools(tools.clone());
+
+    let run = executor
+        .run(&workflow, BTreeMap::new(), None)
+        .await
+        .expect("workflow should run");
+
+    assert_eq!(run.outputs["check"], "true");
+    assert_eq!(run.skipped, vec!["ok", "after_ok"]);
+    assert_eq!(run.output.as_deref(), Some("echo: alert=page done ok="));
+    let called: Vec<String> = tools
+        .calls
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(name, _)| name.clone())
+        .collect();
+    assert_eq!(called, vec!["page"]);
+}
+
+#[tokio::test]
+async fn independent_nodes_run_in_parallel() {
+    let workflow = Workflow::from_yaml_str(
+        r#"
+name: fan-out
+nodes:
+  - id: left
+    kind: neuron
+    prompt: sync left
+  - id: right
+    kind: neuron
+    prompt: sync right
+  - id: join
+    kind: neuron
+    prompt: "{{ nodes.left.output }} + {{ nodes.right.output }}"
+"#,
+    )
+    .unwrap();
+    let provider = EchoProvider {
+        barrier: Some(Arc::new(Barrier::new(2))),
+        ..EchoProvider::new()
+    };
+    let executor = WorkflowExecutor::new(Arc::new(provider));
+
+    let run = tokio::time::timeout(
+        Duration::from_secs(5),
+        executor.run(&workflow, BTreeMap::new(), None),
+    )
+    .await
+    .expect("both branches should reach the barrier together")
+    .unwrap();
+    assert_eq!(
+        run.output.as_deref(),
+        Some("echo: echo: sync left + echo: sync right")
+    );
+}
+
+#[tokio::test]
+async fn timed_out_attempts_are_retried() {
+    let workflow = Workflow::from_yaml_str(
+        r#"
+name: flaky
+nodes:
+  - id: ask
+    kind: neuron
+    prompt: hello
+    retries: 1
+    timeout_secs: 1
+"#,
+    )
+    .unwrap();
+    let provider = EchoProvider {
+        slow_calls: 1,
+        ..EchoProvider::new()
+    };
+    let executor = WorkflowExecutor::new(Arc::new(provider));
+    let (tx, rx) = mpsc::unbounded_channel();
+
+    let run = executor
+        .run(&workflow, BTreeMap::new(), Some(tx))
+        .await
+        .expect("second attempt should succeed");
+    assert_eq!(run.output.as_deref(), Some("echo: hello"));
+    assert!(drain(rx).iter().any(|event| matches!(
+        event,
+        WorkflowEvent::NodeRetrying { node_id, attempt: 1, error }
+            if node_id == "ask" && error.contains("timed out")
+    )));
+}
+
+#[tokio::test]
+async fn failing_node_fails_the_run() {
+    let workflow = Workflow::from_yaml_str(
+        r#"
+name: broken
+nodes:
+  - id: step
+    kind: tool
+    tool: broken
+    retries: 2
+"#,
+    )
+    .unwrap();
+    let tools = Arc::new(RecordingTools::default());
+    let executor = WorkflowExecutor::new(Arc::new(EchoProvider::new())).with_tools(tools.clone());
+
+    let err = executor
+        .run(&workflow, BTreeMap::new(), None)
+        .await
+        .expect_err("tool failure should fail the run");
+    assert!(matches!(err, WorkflowError::NodeFailed { ref node_id, .. } if node_id == "step"));
+    assert_eq!(tools.calls.lock().unwrap().len(), 3);
+
+    let err = executor
+        .run(
+            &workflow,
+            BTreeMap::from([("missing".to_string(), "x".to_string())]),
+            None,
+        )
+        .await
+        .expect_err("unknown inputs are rejected");
+    assert!(err.to_string().contains("no input 'missing'"));
+}
+
+#[test]
+fn invalid_graphs_are_rejected() {
+    let cycle = Workflow::from_yaml_str(
+        r#"
+name: loop
+nodes:
+  - id: a
+    kind: neuron
+    prompt: "{{ nodes.b.output }}"
+  - id: b
+    kind: neuron
+    prompt: x
+    depends_on: [a]
+"#,
+    )
+    .unwrap_err();
+    assert!(cycle.to_string().contains("dependency cycle"));
+
+    let unknown = Workflow::from_yaml_str(
+        r#"
+name: typo
+nodes:
+  - id: a
+    kind: tool
+    tool: t
+    args: { q: "{{ inputs.query }}" }
+"#,
+    )
+    .unwrap_err();
+    assert!(unknown.to_string().contains("undeclared input 'query'"));
+
+    let bad_kind =
+        Workflow::from_yaml_str("name: x\nnodes:\n  - id: a\n    kind: magic\n").unwrap_err();
+    assert!(matches!(bad_kind, WorkflowError::Parse(_)));
+}
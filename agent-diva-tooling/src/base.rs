@@ -15,6 +15,12 @@ pub trait Tool: Send + Sync {
     /// Get the tool parameters schema (JSON Schema format).
     fn parameters(&self) -> Value;
 
+    /// Name of the skill that contributes this tool. Scoped tools are only
+    /// offered to the model while that skill is active.
+    fn scope(&self) -> Option<&str> {
+        None
+    }
+
     /// Execute the tool with arguments.
     async fn execute(&self, args: Value) -> Result<String>;
 
@@ -3,7 +3,7 @@
 use crate::Tool;
 use agent_diva_core::error_context::{find_problematic_chars, ErrorContext};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::time::timeout;
@@ -13,6 +13,7 @@ const ERROR_HINT: &str = "\n\n[Analyze the error above and try a different appro
 const DEFAULT_TOOL_TIMEOUT_SECS: u64 = 60;
 
 /// Registry of available tools.
+#[derive(Clone)]
 pub struct ToolRegistry {
     tools: HashMap<String, Arc<dyn Tool>>,
     timeout_secs: u64,
@@ -61,6 +62,48 @@ impl ToolRegistry {
         self.tools.values().map(|tool| tool.to_schema()).collect()
     }
 
+    /// Get definitions of unscoped tools and of tools whose scope is active.
+    pub fn get_scoped_definitions(&self, active_scopes: &HashSet<String>) -> Vec<Value> {
+        self.tools
+            .values()
+            .filter(|tool| {
+                tool.scope()
+                    .map_or(true, |scope| active_scopes.contains(scope))
+            })
+            .map(|tool| tool.to_schema())
+            .collect()
+    }
+
+    /// Scope of a registered tool, if it has one.
+    pub fn scope_of(&self, name: &str) -> Option<String> {
+        self.tools
+            .get(name)
+            .and_then(|tool| tool.scope().map(str::to_string))
+    }
+
+    /// Error text for calling `name` while only `active_scopes` are active, if
+    /// the tool belongs to a skill that is not among them.
+    pub fn scope_error(&self, name: &str, active_scopes: &HashSet<String>) -> Option<String> {
+        self.scope_of(name)
+            .filter(|scope| !active_scopes.contains(scope))
+            .map(|scope| {
+                format!(
+                    "Error: tool '{}' belongs to skill '{}', which is not active",
+                    name, scope
+                )
+            })
+    }
+
+    /// Execute a tool for a caller outside an agent turn (scheduled jobs,
+    /// workflows, MCP clients). No skill is active there, so tools scoped to a
+    /// skill are refused.
+    pub async fn execute_outside_turn(&self, name: &str, params: Value) -> String {
+        match self.scope_error(name, &HashSet::new()) {
+            Some(error) => error,
+            None => self.execute(name, params).await,
+        }
+    }
+
     /// Get the registry-level default tool timeout in seconds.
     pub fn timeout_secs(&self) -> u64 {
         self.timeout_secs
@@ -203,6 +246,7 @@ mod tests {
 
     struct MockTool;
     struct SlowTool;
+    struct ScopedTool;
 
     #[async_trait]
     impl Tool for MockTool {
@@ -227,6 +271,29 @@ mod tests {
         }
     }
 
+    #[async_trait]
+    impl Tool for ScopedTool {
+        fn name(&self) -> &str {
+            "scoped"
+        }
+
+        fn description(&self) -> &str {
+            "A skill tool"
+        }
+
+        fn parameters(&self) -> Value {
+            serde_json::json!({ "type": "object", "properties": {} })
+        }
+
+        fn scope(&self) -> Option<&str> {
+            Some("pdf")
+        }
+
+        async fn execute(&self, _args: Value) -> crate::Result<String> {
+            Ok("scoped result".to_string())
+        }
+    }
+
     #[async_trait]
     impl Tool for SlowTool {
         fn name(&self) -> &str {
@@ -299,4 +366,47 @@ mod tests {
         assert!(result.contains("tool timed out after 0 seconds"));
         assert!(result.contains("[Analyze the error above"));
     }
+
+    #[test]
+    fn test_scoped_definitions_follow_active_scopes() {
+        let mut registry = ToolRegistry::new();
+        registry.register(Arc::new(MockTool));
+        registry.register(Arc::new(ScopedTool));
+        assert_eq!(registry.scope_of("scoped").as_deref(), Some("pdf"));
+        assert_eq!(registry.scope_of("mock"), None);
+
+        let names = |scopes: &[&str]| {
+            let active = scopes.iter().map(|s| s.to_string()).collect();
+            let mut names: Vec<String> = registry
+                .get_scoped_definitions(&active)
+                .iter()
+                .filter_map(|def| def["function"]["name"].as_str().map(str::to_string))
+                .collect();
+            names.sort();
+            names
+        };
+        assert_eq!(names(&[]), vec!["mock"]);
+        assert_eq!(names(&["pdf"]), vec!["mock", "scoped"]);
+    }
+
+    #[tokio::test]
+    async fn test_scoped_tools_are_refused_outside_a_turn() {
+        let mut registry = ToolRegistry::new();
+        registry.register(Arc::new(MockTool));
+        registry.register(Arc::new(ScopedTool));
+        let active = HashSet::from(["pdf".to_string()]);
+        assert_eq!(registry.scope_error("scoped", &active), None);
+
+        let refused = registry
+            .execute_outside_turn("scoped", serde_json::json!({}))
+            .await;
+        assert_eq!(
+            refused,
+            "Error: tool 'scoped' belongs to skill 'pdf', which is not active"
+        );
+        let result = registry
+            .execute_outside_turn("mock", serde_json::json!({}))
+            .await;
+        assert_eq!(result, "mock result");
+    }
 }
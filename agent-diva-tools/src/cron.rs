@@ -1,6 +1,9 @@
 //! Cron tool for scheduling reminders and tasks
 
-use agent_diva_core::cron::{CronSchedule, CronService};
+use agent_diva_core::cron::{
+    CreateCronJobRequest, CronHttpRequest, CronPayload, CronPayloadKind, CronSchedule, CronService,
+    CronToolCall, CronWorkflowRun,
+};
 use agent_diva_tooling::{Tool, ToolError};
 use async_trait::async_trait;
 use serde_json::{json, Value};
@@ -34,13 +37,17 @@ impl CronTool {
     /// Add a job
     async fn add_job(
         &self,
-        message: String,
+        payload: CronPayload,
         every_seconds: Option<i64>,
         cron_expr: Option<String>,
         at: Option<String>,
         timezone: Option<String>,
     ) -> String {
-        if message.is_empty() {
+        let needs_message = matches!(
+            payload.kind,
+            CronPayloadKind::AgentTurn | CronPayloadKind::Message
+        );
+        if needs_message && payload.message.is_empty() {
             return "Error: message is required for add".to_string();
         }
 
@@ -69,30 +76,40 @@ impl CronTool {
             return "Error: either every_seconds, cron_expr, or at is required".to_string();
         };
 
-        let name = if message.len() > 30 {
+        let label = match (&payload.tool, &payload.http, &payload.workflow) {
+            (Some(tool), _, _) => format!("tool: {}", tool.name),
+            (_, Some(http), _) => format!("http: {}", http.url),
+            (_, _, Some(workflow)) => format!("workflow: {}", workflow.path),
+            _ => payload.message.clone(),
+        };
+        let name = if label.len() > 30 {
             let mut end = 30;
-            while !message.is_char_boundary(end) {
+            while !label.is_char_boundary(end) {
                 end -= 1;
             }
-            message[..end].to_string()
+            label[..end].to_string()
         } else {
-            message.clone()
+            label
         };
 
-        let job = self
-            .cron_service
-            .add_job(
-                name,
-                schedule,
-                message,
-                true,
-                Some(channel),
-                Some(chat_id),
-                false,
-            )
-            .await;
-
-        format!("Created job '{}' (id: {})", job.name, job.id)
+        let request = CreateCronJobRequest {
+            name,
+            schedule,
+            payload: CronPayload {
+                deliver: true,
+                channel: Some(channel),
+                to: Some(chat_id),
+                ..payload
+            },
+            delete_after_run: false,
+            enabled: true,
+            retry: Default::default(),
+            catch_up: Default::default(),
+        };
+        match self.cron_service.create_job(request).await {
+            Ok(job) => format!("Created job '{}' (id: {})", job.job.name, job.job.id),
+            Err(e) => format!("Error: {}", e),
+        }
     }
 
     /// List all jobs
@@ -110,7 +127,13 @@ impl CronTool {
                     CronSchedule::Every { .. } => "every",
                     CronSchedule::Cron { .. } => "cron",
                 };
-                format!("- {} (id: {}, {})", j.name, j.id, kind)
+                format!(
+                    "- {} (id: {}, {}, {})",
+                    j.name,
+                    j.id,
+                    kind,
+                    j.payload.kind.as_str()
+                )
             })
             .collect();
 
@@ -158,7 +181,10 @@ impl Tool for CronTool {
     }
 
     fn description(&self) -> &str {
-        "Schedule reminders and recurring tasks. Actions: add, list, remove."
+        "Schedule reminders and recurring tasks. Actions: add, list, remove. \
+         Kinds: agent_turn (default) runs the message as a prompt, message sends it verbatim, \
+         tool runs a tool with fixed arguments, http fetches a URL and passes the response to you, \
+         workflow runs a workflow file and delivers its output."
     }
 
     fn parameters(&self) -> Value {
@@ -170,9 +196,42 @@ impl Tool for CronTool {
                     "enum": ["add", "list", "remove"],
                     "description": "Action to perform"
                 },
+                "kind": {
+                    "type": "string",
+                    "enum": ["agent_turn", "message", "tool", "http", "workflow"],
+                    "description": "What the job does when it fires (for add, default agent_turn)"
+                },
                 "message": {
                     "type": "string",
-                    "description": "Reminder message (for add)"
+                    "description": "Prompt for agent_turn, text for message, optional instruction for http"
+                },
+                "tool_name": {
+                    "type": "string",
+                    "description": "Tool to run (for kind=tool)"
+                },
+                "tool_args": {
+                    "type": "object",
+                    "description": "Fixed tool arguments (for kind=tool)"
+                },
+                "url": {
+                    "type": "string",
+                    "description": "URL to call (for kind=http)"
+                },
+                "method": {
+                    "type": "string",
+                    "description": "HTTP method (for kind=http, default GET)"
+                },
+                "body": {
+                    "type": "string",
+                    "description": "Request body (for kind=http)"
+                },
+                "workflow_path": {
+                    "type": "string",
+                    "description": "Workflow YAML/JSON file, relative to the workspace (for kind=workflow)"
+                },
+                "workflow_inputs": {
+                    "type": "object",
+                    "description": "String inputs for the workflow (for kind=workflow)"
                 },
                 "every_seconds": {
                     "type": "integer",
@@ -222,14 +281,14 @@ impl Tool for CronTool {
 
         match action {
             "add" => {
-                let message = args["message"].as_str().unwrap_or("").to_string();
+                let payload = payload_from_args(&args)?;
                 let every_seconds = args["every_seconds"].as_i64();
                 let cron_expr = args["cron_expr"].as_str().map(|s| s.to_string());
                 let at = args["at"].as_str().map(|s| s.to_string());
                 let timezone = args["timezone"].as_str().map(|s| s.to_string());
 
                 Ok(self
-                    .add_job(message, every_seconds, cron_expr, at, timezone)
+                    .add_job(payload, every_seconds, cron_expr, at, timezone)
                     .await)
             }
             "list" => Ok(self.list_jobs().await),
@@ -248,6 +307,51 @@ impl Tool for CronTool {
     }
 }
 
+/// Build the job payload from the `add` arguments; delivery is filled in later
+fn payload_from_args(args: &Value) -> agent_diva_tooling::Result<CronPayload> {
+    let kind = match args.get("kind") {
+        Some(kind) => serde_json::from_value::<CronPayloadKind>(kind.clone())
+            .map_err(|e| ToolError::InvalidArguments(format!("invalid kind: {}", e)))?,
+        None => CronPayloadKind::AgentTurn,
+    };
+    let tool = args["tool_name"].as_str().map(|name| CronToolCall {
+        name: name.to_string(),
+        args: args.get("tool_args").cloned().unwrap_or(Value::Null),
+    });
+    let http = args["url"].as_str().map(|url| CronHttpRequest {
+        url: url.to_string(),
+        method: args["method"].as_str().unwrap_or("GET").to_string(),
+        headers: Default::default(),
+        body: args["body"].as_str().map(|s| s.to_string()),
+    });
+    let workflow = args["workflow_path"].as_str().map(|path| CronWorkflowRun {
+        path: path.to_string(),
+        inputs: args["workflow_inputs"]
+            .as_object()
+            .map(|inputs| {
+                inputs
+                    .iter()
+                    .map(|(name, value)| {
+                        let value = match value {
+                            Value::String(text) => text.clone(),
+                            other => other.to_string(),
+                        };
+                        (name.clone(), value)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+    });
+    Ok(CronPayload {
+        kind,
+        message: args["message"].as_str().unwrap_or("").to_string(),
+        tool,
+        http,
+        workflow,
+        ..Default::default()
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -302,6 +406,59 @@ mod tests {
         service.stop().await;
     }
 
+    #[tokio::test]
+    async fn test_cron_tool_add_validates_payload_kind() {
+        let temp_dir = TempDir::new().unwrap();
+        let store_path = temp_dir.path().join("cron.json");
+        let service = Arc::new(CronService::new(store_path, None));
+        service.start().await;
+
+        let tool = CronTool::new(Arc::clone(&service));
+        tool.set_context("test".to_string(), "123".to_string())
+            .await;
+
+        let missing_url = tool
+            .execute(json!({ "action": "add", "kind": "http", "every_seconds": 60 }))
+            .await
+            .unwrap();
+        assert!(missing_url.starts_with("Error:"));
+
+        let created = tool
+            .execute(json!({
+                "action": "add",
+                "kind": "tool",
+                "tool_name": "web_fetch",
+                "tool_args": { "url": "https://example.com" },
+                "every_seconds": 60
+            }))
+            .await
+            .unwrap();
+        assert!(created.contains("Created job 'tool: web_fetch'"));
+        let jobs = service.list_jobs(false).await;
+        assert_eq!(jobs[0].payload.kind, CronPayloadKind::Tool);
+        assert_eq!(jobs[0].payload.channel.as_deref(), Some("test"));
+
+        let created = tool
+            .execute(json!({
+                "action": "add",
+                "kind": "workflow",
+                "workflow_path": "flows/digest.yaml",
+                "workflow_inputs": { "topic": "rust", "limit": 5 },
+                "every_seconds": 3600
+            }))
+            .await
+            .unwrap();
+        assert!(created.contains("Created job 'workflow: flows/digest.yaml'"));
+        let jobs = service.list_jobs(false).await;
+        let workflow = jobs
+            .iter()
+            .find_map(|job| job.payload.workflow.as_ref())
+            .unwrap();
+        assert_eq!(workflow.inputs["limit"], "5");
+
+        service.stop().await;
+    }
+
     #[tokio::test]
     async fn test_cron_tool_list_jobs() {
         let temp_dir = TempDir::new().unwrap();
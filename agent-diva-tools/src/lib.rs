@@ -6,10 +6,16 @@ pub mod attachment;
 pub mod cron;
 pub mod filesystem;
 pub mod mcp_sdk;
+pub mod memory;
 pub mod message;
+pub mod sandbox;
 pub mod sanitize;
+pub mod session_search;
 pub mod shell;
+pub mod shell_jobs;
+pub mod skill_script;
 pub mod spawn;
+pub mod tool_context;
 pub mod web;
 pub mod wtf;
 
@@ -17,15 +23,22 @@ pub use agent_diva_tooling::{Result, Tool, ToolError, ToolRegistry};
 pub use attachment::ReadAttachmentTool;
 pub use cron::CronTool;
 pub use filesystem::{EditFileTool, ListDirTool, ReadFileTool, WriteFileTool};
+pub use memory::{MemoryForgetTool, MemorySaveTool, MemorySearchTool};
 pub use message::MessageTool;
+pub use sandbox::{ExecSandboxConfig, SandboxError};
 pub use sanitize::sanitize_for_json;
+pub use session_search::SessionSearchTool;
 pub use shell::ExecTool;
-pub use spawn::SpawnTool;
+pub use shell_jobs::{job_wait_cap_secs, ExecJobTool, ShellJobManager};
+pub use skill_script::{SkillScriptTool, SkillToolSpec};
+pub use spawn::{SpawnArgs, SpawnMode, SpawnTool};
+pub use tool_context::{strip_context_args, ToolCallContext};
 pub use web::{WebFetchTool, WebSearchTool};
 pub use wtf::{print_ascii_agent_diva_logo, ASCII_AGENT_DIVA_LOGO};
 
 // MCP implementation using rust-mcp-sdk from crates.io
 pub use mcp_sdk::{
-    load_mcp_tools, load_mcp_tools_sync, probe_mcp_server, probe_mcp_server_sync, DiscoveredTool,
-    McpClientWrapper, McpError, McpSdkTool,
+    load_mcp_tools, load_mcp_tools_sync, probe_mcp_server, probe_mcp_server_sync, DiscoveredPrompt,
+    DiscoveredPromptArgument, DiscoveredResource, DiscoveredTool, McpClientWrapper, McpError,
+    McpGetPromptTool, McpListChanges, McpManager, McpReadResourceTool, McpSdkTool,
 };
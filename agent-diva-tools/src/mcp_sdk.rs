@@ -4,24 +4,27 @@
 //! similar to nanobot's MCP implementation pattern.
 
 use crate::sanitize::sanitize_for_json;
-use agent_diva_core::config::MCPServerConfig;
+use agent_diva_core::config::{MCPServerConfig, MCPTransport};
 use agent_diva_tooling::{Tool, ToolError};
 use async_trait::async_trait;
 use rust_mcp_sdk::{
+    error::{McpSdkError, SdkResult},
     mcp_client::{client_runtime, ClientHandler, ClientRuntime, McpClientOptions},
     schema::{
-        CallToolRequestParams, ClientCapabilities, Implementation, InitializeRequestParams,
+        CallToolRequestParams, ClientCapabilities, ContentBlock, EmbeddedResourceResource,
+        GetPromptRequestParams, Implementation, InitializeRequestParams, NotificationParams,
+        PaginatedRequestParams, ReadResourceContent, ReadResourceRequestParams, RpcError,
         LATEST_PROTOCOL_VERSION,
     },
-    ClientSseTransport, ClientSseTransportOptions, McpClient, StdioTransport, ToMcpClientHandler,
-    TransportOptions,
+    ClientSseTransport, ClientSseTransportOptions, McpClient, RequestOptions, StdioTransport,
+    StreamableTransportOptions, ToMcpClientHandler, TransportError, TransportOptions,
 };
-use serde_json::Value;
-use std::collections::HashMap;
-use std::sync::Arc;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex as StdMutex, RwLock as StdRwLock};
 use std::time::Duration;
 use tokio::sync::RwLock;
-use tracing::warn;
+use tracing::{info, warn};
 
 /// Sanitize a JSON value by recursively cleaning all string values.
 /// This removes control characters and ANSI sequences from strings within the JSON.
@@ -44,6 +47,9 @@ fn sanitize_json_strings(value: &mut Value) {
     }
 }
 
+/// Upper bound on pages fetched for paginated resource/prompt listings.
+const MAX_LIST_PAGES: usize = 20;
+
 /// Default timeout for MCP operations in seconds.
 #[allow(dead_code)]
 const DEFAULT_TIMEOUT_SECS: u64 = 30;
@@ -67,11 +73,23 @@ pub enum McpError {
     #[error("MCP SDK error: {0}")]
     Sdk(String),
 
+    #[error("MCP session expired: {0}")]
+    SessionExpired(String),
+
+    #[error("MCP response stream interrupted: {0}")]
+    StreamInterrupted(String),
+
     #[error("MCP server error: {0}")]
     Server(String),
 
     #[error("Configuration error: {0}")]
     Config(String),
+
+    #[error("{transport} transport error: {message}")]
+    Transport {
+        transport: MCPTransport,
+        message: String,
+    },
 }
 
 // ============================================================================
@@ -86,10 +104,87 @@ pub struct DiscoveredTool {
     pub input_schema: Value,
 }
 
+/// Resource exposed by an MCP server.
+#[derive(Debug, Clone)]
+pub struct DiscoveredResource {
+    pub uri: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub mime_type: Option<String>,
+}
+
+/// Argument accepted by an MCP prompt template.
+#[derive(Debug, Clone)]
+pub struct DiscoveredPromptArgument {
+    pub name: String,
+    pub description: Option<String>,
+    pub required: bool,
+}
+
+/// Prompt template exposed by an MCP server.
+#[derive(Debug, Clone)]
+pub struct DiscoveredPrompt {
+    pub name: String,
+    pub description: Option<String>,
+    pub arguments: Vec<DiscoveredPromptArgument>,
+}
+
+/// Servers that sent `notifications/tools/list_changed` or
+/// `notifications/prompts/list_changed` since the last refresh.
+#[derive(Debug, Clone, Default)]
+pub struct McpListChanges {
+    servers: Arc<StdMutex<HashSet<String>>>,
+}
+
+impl McpListChanges {
+    pub fn mark(&self, server_name: &str) {
+        self.servers
+            .lock()
+            .expect("mcp list changes lock")
+            .insert(server_name.to_string());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.servers
+            .lock()
+            .expect("mcp list changes lock")
+            .is_empty()
+    }
+
+    /// Drain the set of changed servers, sorted by name.
+    pub fn take(&self) -> Vec<String> {
+        let mut servers: Vec<String> = self
+            .servers
+            .lock()
+            .expect("mcp list changes lock")
+            .drain()
+            .collect();
+        servers.sort();
+        servers
+    }
+}
+
+/// Connection shared by all tools of one MCP server; `None` once shut down.
+pub type SharedMcpClient = Arc<RwLock<Option<McpClientWrapper>>>;
+
 /// MCP client wrapper that manages connection and tool calls.
+///
+/// Streamable HTTP sessions are tracked by the SDK runtime (`Mcp-Session-Id`).
+/// When the server forgets a session (HTTP 404) the wrapper opens a new one.
+/// Read-only requests are then retried once; tool calls are not, since the
+/// server may already have acted on them.
+///
+/// The SDK resumes its standalone GET stream with `Last-Event-ID`, but it does
+/// not expose the event ids of a POST response stream, so an interrupted
+/// response cannot be resumed from where it broke off. Read-only requests are
+/// sent again once on the same session instead; a tool call fails with
+/// [`McpError::StreamInterrupted`] and is left to the caller.
 pub struct McpClientWrapper {
     server_name: String,
-    client: Arc<ClientRuntime>,
+    transport: MCPTransport,
+    config: MCPServerConfig,
+    changes: McpListChanges,
+    client: StdRwLock<Arc<ClientRuntime>>,
     tool_timeout: u64,
 }
 
@@ -97,127 +192,164 @@ impl std::fmt::Debug for McpClientWrapper {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("McpClientWrapper")
             .field("server_name", &self.server_name)
+            .field("transport", &self.transport)
             .field("tool_timeout", &self.tool_timeout)
             .finish()
     }
 }
 
 impl McpClientWrapper {
+    /// Connect using the configured transport (inferred from `command`/`url` when unset).
+    pub async fn connect(server_name: &str, config: &MCPServerConfig) -> Result<Self, McpError> {
+        Self::connect_tracked(server_name, config, McpListChanges::default()).await
+    }
+
+    /// Like [`Self::connect`], recording list-changed notifications in `changes`.
+    pub async fn connect_tracked(
+        server_name: &str,
+        config: &MCPServerConfig,
+        changes: McpListChanges,
+    ) -> Result<Self, McpError> {
+        config.validate().map_err(McpError::Config)?;
+        let transport = config.effective_transport().ok_or_else(|| {
+            McpError::Config("MCP server requires either command or url".to_string())
+        })?;
+        let client = start_runtime(server_name, config, transport, changes.clone()).await?;
+
+        Ok(Self {
+            server_name: server_name.to_string(),
+            transport,
+            config: config.clone(),
+            changes,
+            client: StdRwLock::new(client),
+            tool_timeout: config.tool_timeout,
+        })
+    }
+
     /// Create a new MCP client for a stdio-based server.
     pub async fn new_stdio(server_name: &str, config: &MCPServerConfig) -> Result<Self, McpError> {
-        let command_str = config.command.trim();
-        if command_str.is_empty() {
-            return Err(McpError::Config(
-                "command is required for stdio transport".to_string(),
-            ));
-        }
+        Self::connect(server_name, &with_transport(config, MCPTransport::Stdio)).await
+    }
 
-        // Resolve command path on Windows
-        let resolved_command = if cfg!(target_os = "windows") {
-            which::which(command_str)
-                .map(|p| p.to_string_lossy().to_string())
-                .unwrap_or_else(|_| command_str.to_string())
-        } else {
-            command_str.to_string()
-        };
+    /// Create a new MCP client for an HTTP-based server (SSE transport).
+    pub async fn new_sse(server_name: &str, config: &MCPServerConfig) -> Result<Self, McpError> {
+        Self::connect(server_name, &with_transport(config, MCPTransport::Sse)).await
+    }
 
-        // Create transport with server launch
-        let transport = StdioTransport::create_with_server_launch(
-            &resolved_command,
-            config.args.to_vec(),
-            if config.env.is_empty() {
-                None
-            } else {
-                Some(config.env.clone())
-            },
-            TransportOptions::default(),
+    /// Create a new MCP client for a Streamable HTTP server.
+    pub async fn new_streamable_http(
+        server_name: &str,
+        config: &MCPServerConfig,
+    ) -> Result<Self, McpError> {
+        Self::connect(
+            server_name,
+            &with_transport(config, MCPTransport::StreamableHttp),
         )
-        .map_err(|e| McpError::ProcessStart(e.to_string()))?;
+        .await
+    }
 
-        Self::create_client(server_name, transport, config.tool_timeout).await
+    /// Transport used by this connection.
+    pub fn transport(&self) -> MCPTransport {
+        self.transport
     }
 
-    /// Create a new MCP client for an HTTP-based server (SSE transport).
-    pub async fn new_sse(server_name: &str, config: &MCPServerConfig) -> Result<Self, McpError> {
-        if config.url.trim().is_empty() {
-            return Err(McpError::Config(
-                "url is required for HTTP transport".to_string(),
-            ));
-        }
+    /// Session ID assigned by a Streamable HTTP server, if any.
+    pub async fn session_id(&self) -> Option<String> {
+        self.runtime().session_id().await
+    }
 
-        // Use SSE transport for HTTP
-        let transport = ClientSseTransport::new(&config.url, ClientSseTransportOptions::default())
-            .map_err(|e| McpError::ConnectionFailed(e.to_string()))?;
+    fn runtime(&self) -> Arc<ClientRuntime> {
+        self.client.read().expect("mcp client lock").clone()
+    }
 
-        Self::create_client(server_name, transport, config.tool_timeout).await
+    /// Replace the runtime with a fresh connection (new session).
+    async fn reconnect(&self) -> Result<(), McpError> {
+        let fresh = start_runtime(
+            &self.server_name,
+            &self.config,
+            self.transport,
+            self.changes.clone(),
+        )
+        .await?;
+        let stale = std::mem::replace(&mut *self.client.write().expect("mcp client lock"), fresh);
+        let _ = stale.shut_down().await;
+        Ok(())
     }
 
-    async fn create_client<T>(
-        server_name: &str,
-        transport: T,
-        tool_timeout: u64,
-    ) -> Result<Self, McpError>
+    /// Run a read-only request with the tool timeout, reconnecting and retrying
+    /// once when a Streamable HTTP server reports that our session no longer exists.
+    async fn request<T, F, Fut>(&self, op: F) -> Result<T, McpError>
     where
-        T: rust_mcp_sdk::TransportDispatcher<
-            rust_mcp_sdk::schema::schema_utils::ServerMessages,
-            rust_mcp_sdk::schema::schema_utils::MessageFromClient,
-            rust_mcp_sdk::schema::schema_utils::ServerMessage,
-            rust_mcp_sdk::schema::schema_utils::ClientMessages,
-            rust_mcp_sdk::schema::schema_utils::ClientMessage,
-        >,
+        F: Fn(Arc<ClientRuntime>) -> Fut,
+        Fut: std::future::Future<Output = SdkResult<T>>,
     {
-        let client_details = InitializeRequestParams {
-            capabilities: ClientCapabilities::default(),
-            client_info: Implementation {
-                name: "agent-diva".into(),
-                version: "0.4.10".into(),
-                title: Some("Agent Diva MCP Client".into()),
-                description: Some("Agent Diva MCP Client using rust-mcp-sdk".into()),
-                icons: vec![],
-                website_url: None,
-            },
-            protocol_version: LATEST_PROTOCOL_VERSION.into(),
-            meta: None,
-        };
-
-        let handler = SimpleClientHandler;
-
-        let client = client_runtime::create_client(McpClientOptions {
-            client_details,
-            transport,
-            handler: handler.to_mcp_client_handler(),
-            task_store: None,
-            server_task_store: None,
-            message_observer: None,
-        });
+        self.request_with_retry(op, true).await
+    }
 
-        // Handshake/start can hang on dead SSE URLs or stuck child processes; `list_tools`
-        // already has a timeout, but we never reach it if `start` never completes.
-        let start_timeout_secs = tool_timeout.clamp(10, 120);
-        let start_timeout = Duration::from_secs(start_timeout_secs);
-        tokio::time::timeout(start_timeout, {
-            let client = client.clone();
-            async move { client.start().await }
-        })
-        .await
-        .map_err(|_| McpError::Timeout)?
-        .map_err(|e| McpError::Sdk(e.to_string()))?;
+    async fn request_with_retry<T, F, Fut>(&self, op: F, retry: bool) -> Result<T, McpError>
+    where
+        F: Fn(Arc<ClientRuntime>) -> Fut,
+        Fut: std::future::Future<Output = SdkResult<T>>,
+    {
+        let timeout_duration = Duration::from_secs(self.tool_timeout);
+        let streamable = self.transport == MCPTransport::StreamableHttp;
+        let run = |client: Arc<ClientRuntime>| {
+            let fut = op(client);
+            async move {
+                tokio::time::timeout(timeout_duration, fut)
+                    .await
+                    .map_err(|_| McpError::Timeout)?
+                    .map_err(|e| {
+                        if streamable && is_session_expired(&e) {
+                            McpError::SessionExpired(e.to_string())
+                        } else if streamable && is_stream_interrupted(&e) {
+                            McpError::StreamInterrupted(e.to_string())
+                        } else {
+                            McpError::Sdk(e.to_string())
+                        }
+                    })
+            }
+        };
 
-        Ok(Self {
-            server_name: server_name.to_string(),
-            client,
-            tool_timeout,
-        })
+        match run(self.runtime()).await {
+            Err(McpError::SessionExpired(message)) => {
+                warn!(
+                    "MCP server '{}' session expired ({}); reconnecting",
+                    self.server_name, message
+                );
+                self.reconnect().await?;
+                if retry {
+                    run(self.runtime()).await
+                } else {
+                    Err(McpError::SessionExpired(format!(
+                        "{}; reconnected, but the request was not repeated",
+                        message
+                    )))
+                }
+            }
+            Err(McpError::StreamInterrupted(message)) => {
+                warn!(
+                    "MCP server '{}' response stream interrupted ({})",
+                    self.server_name, message
+                );
+                if retry {
+                    run(self.runtime()).await
+                } else {
+                    Err(McpError::StreamInterrupted(format!(
+                        "{}; the request was not repeated",
+                        message
+                    )))
+                }
+            }
+            other => other,
+        }
     }
 
     /// List available tools from the server.
     pub async fn list_tools(&self) -> Result<Vec<DiscoveredTool>, McpError> {
-        let timeout_duration = Duration::from_secs(self.tool_timeout);
-
-        let result = tokio::time::timeout(timeout_duration, self.client.request_tool_list(None))
-            .await
-            .map_err(|_| McpError::Timeout)?
-            .map_err(|e| McpError::Sdk(e.to_string()))?;
+        let result = self
+            .request(|client| async move { client.request_tool_list(None).await })
+            .await?;
 
         Ok(result
             .tools
@@ -243,8 +375,6 @@ impl McpClientWrapper {
 
     /// Call a tool on the server.
     pub async fn call_tool(&self, tool_name: &str, arguments: Value) -> Result<String, McpError> {
-        let timeout_duration = Duration::from_secs(self.tool_timeout);
-
         let params = CallToolRequestParams {
             name: tool_name.into(),
             arguments: Some(arguments.as_object().cloned().unwrap_or_default()),
@@ -252,17 +382,173 @@ impl McpClientWrapper {
             task: None,
         };
 
-        let result = tokio::time::timeout(timeout_duration, self.client.request_tool_call(params))
-            .await
-            .map_err(|_| McpError::Timeout)?
-            .map_err(|e| McpError::Sdk(e.to_string()))?;
+        // Tool calls may have side effects, so they are never sent twice.
+        let result = self
+            .request_with_retry(
+                |client| {
+                    let params = params.clone();
+                    async move { client.request_tool_call(params).await }
+                },
+                false,
+            )
+            .await?;
 
         Ok(render_tool_result(&result))
     }
 
+    /// Whether the server advertised the `resources` capability.
+    pub fn supports_resources(&self) -> bool {
+        self.runtime().server_has_resources().unwrap_or(false)
+    }
+
+    /// Whether the server advertised the `prompts` capability.
+    pub fn supports_prompts(&self) -> bool {
+        self.runtime().server_has_prompts().unwrap_or(false)
+    }
+
+    /// List resources exposed by the server, following pagination.
+    pub async fn list_resources(&self) -> Result<Vec<DiscoveredResource>, McpError> {
+        let mut resources = Vec::new();
+        let mut cursor = None;
+        for _ in 0..MAX_LIST_PAGES {
+            let params = cursor.take().map(|cursor| PaginatedRequestParams {
+                cursor: Some(cursor),
+                meta: None,
+            });
+            let page = self
+                .request(|client| {
+                    let params = params.clone();
+                    async move { client.request_resource_list(params).await }
+                })
+                .await?;
+            resources.extend(
+                page.resources
+                    .into_iter()
+                    .map(|resource| DiscoveredResource {
+                        uri: resource.uri,
+                        name: sanitize_for_json(&resource.name),
+                        description: resource.description.map(|d| sanitize_for_json(&d)),
+                        mime_type: resource.mime_type,
+                    }),
+            );
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+        Ok(resources)
+    }
+
+    /// Read a resource and render its contents as text.
+    pub async fn read_resource(&self, uri: &str) -> Result<String, McpError> {
+        let params = ReadResourceRequestParams {
+            uri: uri.to_string(),
+            meta: None,
+        };
+        let result = self
+            .request(|client| {
+                let params = params.clone();
+                async move { client.request_resource_read(params).await }
+            })
+            .await?;
+
+        let parts: Vec<String> = result
+            .contents
+            .iter()
+            .map(|content| match content {
+                ReadResourceContent::TextResourceContents(text) => sanitize_for_json(&text.text),
+                ReadResourceContent::BlobResourceContents(blob) => format!(
+                    "[Binary resource {} ({}, {} base64 chars)]",
+                    blob.uri,
+                    blob.mime_type.as_deref().unwrap_or("unknown type"),
+                    blob.blob.len()
+                ),
+            })
+            .collect();
+        if parts.is_empty() {
+            Ok("(empty resource)".to_string())
+        } else {
+            Ok(parts.join("\n"))
+        }
+    }
+
+    /// List prompt templates exposed by the server, following pagination.
+    pub async fn list_prompts(&self) -> Result<Vec<DiscoveredPrompt>, McpError> {
+        let mut prompts = Vec::new();
+        let mut cursor = None;
+        for _ in 0..MAX_LIST_PAGES {
+            let params = cursor.take().map(|cursor| PaginatedRequestParams {
+                cursor: Some(cursor),
+                meta: None,
+            });
+            let page = self
+                .request(|client| {
+                    let params = params.clone();
+                    async move { client.request_prompt_list(params).await }
+                })
+                .await?;
+            prompts.extend(page.prompts.into_iter().map(|prompt| {
+                DiscoveredPrompt {
+                    name: prompt.name,
+                    description: prompt.description.map(|d| sanitize_for_json(&d)),
+                    arguments: prompt
+                        .arguments
+                        .into_iter()
+                        .map(|arg| DiscoveredPromptArgument {
+                            name: arg.name,
+                            description: arg.description.map(|d| sanitize_for_json(&d)),
+                            required: arg.required.unwrap_or(false),
+                        })
+                        .collect(),
+                }
+            }));
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+        Ok(prompts)
+    }
+
+    /// Render a prompt template with the given arguments.
+    pub async fn get_prompt(
+        &self,
+        name: &str,
+        arguments: BTreeMap<String, String>,
+    ) -> Result<String, McpError> {
+        let params = GetPromptRequestParams {
+            name: name.to_string(),
+            arguments: if arguments.is_empty() {
+                None
+            } else {
+                Some(arguments)
+            },
+            meta: None,
+        };
+        let result = self
+            .request(|client| {
+                let params = params.clone();
+                async move { client.request_prompt(params).await }
+            })
+            .await?;
+
+        let mut parts = Vec::new();
+        if let Some(description) = result.description {
+            parts.push(sanitize_for_json(&description));
+        }
+        for message in &result.messages {
+            parts.push(format!(
+                "[{}]\n{}",
+                message.role,
+                render_content_block(&message.content)
+            ));
+        }
+        Ok(parts.join("\n\n"))
+    }
+
     /// Shutdown the client.
     pub async fn shutdown(&self) {
-        let _ = self.client.shut_down().await;
+        let _ = self.runtime().shut_down().await;
     }
 
     /// Get the server name.
@@ -271,8 +557,167 @@ impl McpClientWrapper {
     }
 }
 
+fn with_transport(config: &MCPServerConfig, transport: MCPTransport) -> MCPServerConfig {
+    MCPServerConfig {
+        transport: Some(transport),
+        ..config.clone()
+    }
+}
+
+/// Streamable HTTP servers answer 404 for requests carrying an unknown session ID.
+fn is_session_expired(error: &McpSdkError) -> bool {
+    match error {
+        McpSdkError::Transport(TransportError::SessionExpired) => true,
+        McpSdkError::Transport(TransportError::Http(status)) => status.as_u16() == 404,
+        _ => false,
+    }
+}
+
+/// A POST response stream that broke off before the response arrived.
+fn is_stream_interrupted(error: &McpSdkError) -> bool {
+    match error {
+        McpSdkError::Transport(TransportError::HttpConnection(_)) => true,
+        McpSdkError::Transport(TransportError::Internal(message)) => {
+            message.contains("Stream has ended")
+        }
+        _ => false,
+    }
+}
+
+fn client_details() -> InitializeRequestParams {
+    InitializeRequestParams {
+        capabilities: ClientCapabilities::default(),
+        client_info: Implementation {
+            name: "agent-diva".into(),
+            version: "0.4.10".into(),
+            title: Some("Agent Diva MCP Client".into()),
+            description: Some("Agent Diva MCP Client using rust-mcp-sdk".into()),
+            icons: vec![],
+            website_url: None,
+        },
+        protocol_version: LATEST_PROTOCOL_VERSION.into(),
+        meta: None,
+    }
+}
+
+/// Build the client runtime for `transport` and complete the MCP handshake.
+async fn start_runtime(
+    server_name: &str,
+    config: &MCPServerConfig,
+    transport: MCPTransport,
+    changes: McpListChanges,
+) -> Result<Arc<ClientRuntime>, McpError> {
+    let transport_error = |message: String| McpError::Transport { transport, message };
+    let handler = SimpleClientHandler {
+        server_name: server_name.to_string(),
+        changes,
+    };
+
+    let client = match transport {
+        MCPTransport::Stdio => {
+            let command_str = config.command.trim();
+            if command_str.is_empty() {
+                return Err(McpError::Config(
+                    "command is required for stdio transport".to_string(),
+                ));
+            }
+
+            // Resolve command path on Windows
+            let resolved_command = if cfg!(target_os = "windows") {
+                which::which(command_str)
+                    .map(|p| p.to_string_lossy().to_string())
+                    .unwrap_or_else(|_| command_str.to_string())
+            } else {
+                command_str.to_string()
+            };
+
+            // Create transport with server launch
+            let stdio = StdioTransport::create_with_server_launch(
+                &resolved_command,
+                config.args.to_vec(),
+                if config.env.is_empty() {
+                    None
+                } else {
+                    Some(config.env.clone())
+                },
+                TransportOptions::default(),
+            )
+            .map_err(|e| McpError::ProcessStart(e.to_string()))?;
+
+            client_runtime::create_client(McpClientOptions {
+                client_details: client_details(),
+                transport: stdio,
+                handler: handler.to_mcp_client_handler(),
+                task_store: None,
+                server_task_store: None,
+                message_observer: None,
+            })
+        }
+        MCPTransport::Sse => {
+            let sse = ClientSseTransport::new(
+                config.url.trim(),
+                ClientSseTransportOptions {
+                    custom_headers: resolve_headers(config)?,
+                    ..ClientSseTransportOptions::default()
+                },
+            )
+            .map_err(|e| transport_error(e.to_string()))?;
+
+            client_runtime::create_client(McpClientOptions {
+                client_details: client_details(),
+                transport: sse,
+                handler: handler.to_mcp_client_handler(),
+                task_store: None,
+                server_task_store: None,
+                message_observer: None,
+            })
+        }
+        MCPTransport::StreamableHttp => client_runtime::with_transport_options(
+            client_details(),
+            StreamableTransportOptions {
+                mcp_url: config.url.trim().to_string(),
+                request_options: RequestOptions {
+                    custom_headers: resolve_headers(config)?,
+                    ..RequestOptions::default()
+                },
+            },
+            handler,
+            None,
+            None,
+            None,
+        ),
+    };
+
+    // Handshake/start can hang on dead URLs or stuck child processes; `list_tools`
+    // already has a timeout, but we never reach it if `start` never completes.
+    let start_timeout_secs = config.tool_timeout.clamp(10, 120);
+    let start_timeout = Duration::from_secs(start_timeout_secs);
+    tokio::time::timeout(start_timeout, {
+        let client = client.clone();
+        async move { client.start().await }
+    })
+    .await
+    .map_err(|_| {
+        transport_error(format!(
+            "handshake timed out after {} seconds",
+            start_timeout_secs
+        ))
+    })?
+    .map_err(|e| transport_error(e.to_string()))?;
+
+    Ok(client)
+}
+
+fn resolve_headers(config: &MCPServerConfig) -> Result<Option<HashMap<String, String>>, McpError> {
+    let headers = config.resolved_headers().map_err(McpError::Config)?;
+    Ok((!headers.is_empty()).then_some(headers))
+}
+
 /// Simple client handler that handles MCP messages.
-struct SimpleClientHandler;
+struct SimpleClientHandler {
+    server_name: String,
+    changes: McpListChanges,
+}
 
 #[async_trait]
 impl ClientHandler for SimpleClientHandler {
@@ -291,6 +736,41 @@ impl ClientHandler for SimpleClientHandler {
         tracing::debug!("MCP server stderr: {}", error_message);
         Ok(())
     }
+
+    async fn handle_tool_list_changed_notification(
+        &self,
+        _params: Option<NotificationParams>,
+        _runtime: &dyn rust_mcp_sdk::McpClient,
+    ) -> std::result::Result<(), RpcError> {
+        info!("MCP server '{}' changed its tool list", self.server_name);
+        self.changes.mark(&self.server_name);
+        Ok(())
+    }
+
+    async fn handle_prompt_list_changed_notification(
+        &self,
+        _params: Option<NotificationParams>,
+        _runtime: &dyn rust_mcp_sdk::McpClient,
+    ) -> std::result::Result<(), RpcError> {
+        info!("MCP server '{}' changed its prompt list", self.server_name);
+        self.changes.mark(&self.server_name);
+        Ok(())
+    }
+}
+
+fn render_content_block(content: &ContentBlock) -> String {
+    match content {
+        ContentBlock::TextContent(text) => sanitize_for_json(&text.text),
+        ContentBlock::EmbeddedResource(embedded) => match &embedded.resource {
+            EmbeddedResourceResource::TextResourceContents(text) => sanitize_for_json(&text.text),
+            EmbeddedResourceResource::BlobResourceContents(blob) => {
+                format!("[Binary resource {}]", blob.uri)
+            }
+        },
+        ContentBlock::ResourceLink(link) => format!("[Resource: {}]", link.uri),
+        ContentBlock::ImageContent(image) => format!("[Image: {}]", image.mime_type),
+        ContentBlock::AudioContent(audio) => format!("[Audio: {}]", audio.mime_type),
+    }
 }
 
 fn render_tool_result(result: &rust_mcp_sdk::schema::CallToolResult) -> String {
@@ -395,6 +875,387 @@ impl Tool for McpSdkTool {
     }
 }
 
+/// Generic tool for listing and reading resources exposed by MCP servers.
+pub struct McpReadResourceTool {
+    servers: BTreeMap<String, SharedMcpClient>,
+    description: String,
+}
+
+impl McpReadResourceTool {
+    pub fn new(servers: BTreeMap<String, SharedMcpClient>) -> Self {
+        let names: Vec<&str> = servers.keys().map(String::as_str).collect();
+        let description = format!(
+            "Read a resource (document, schema, file...) exposed by an MCP server. \
+             Omit 'uri' to list the server's resources. Servers: {}",
+            names.join(", ")
+        );
+        Self {
+            servers,
+            description,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for McpReadResourceTool {
+    fn name(&self) -> &str {
+        "mcp_read_resource"
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "server": {
+                    "type": "string",
+                    "enum": self.servers.keys().collect::<Vec<_>>(),
+                    "description": "MCP server name (optional when only one server exposes resources)"
+                },
+                "uri": {
+                    "type": "string",
+                    "description": "Resource URI to read; omit to list available resources"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: Value) -> agent_diva_tooling::Result<String> {
+        let (server_name, client) = select_server(&self.servers, &args)?;
+        let guard = client.read().await;
+        let client = guard.as_ref().ok_or_else(|| {
+            ToolError::ExecutionFailed(format!("MCP server '{}' session is closed", server_name))
+        })?;
+        let mcp_error = |e: McpError| {
+            ToolError::ExecutionFailed(format!("MCP server '{}': {}", server_name, e))
+        };
+
+        match args.get("uri").and_then(|v| v.as_str()) {
+            Some(uri) => client.read_resource(uri).await.map_err(mcp_error),
+            None => {
+                let resources = client.list_resources().await.map_err(mcp_error)?;
+                if resources.is_empty() {
+                    return Ok(format!("MCP server '{}' has no resources.", server_name));
+                }
+                let lines: Vec<String> = resources
+                    .iter()
+                    .map(|resource| {
+                        let mut line = format!("- {} ({})", resource.uri, resource.name);
+                        if let Some(mime) = &resource.mime_type {
+                            line.push_str(&format!(" [{}]", mime));
+                        }
+                        if let Some(description) = &resource.description {
+                            line.push_str(&format!(": {}", description));
+                        }
+                        line
+                    })
+                    .collect();
+                Ok(format!(
+                    "Resources on MCP server '{}':\n{}",
+                    server_name,
+                    lines.join("\n")
+                ))
+            }
+        }
+    }
+}
+
+/// Generic tool that renders prompt templates exposed by MCP servers. The
+/// available templates are listed in the description so the model can pick
+/// them like skills.
+pub struct McpGetPromptTool {
+    servers: BTreeMap<String, SharedMcpClient>,
+    description: String,
+}
+
+impl McpGetPromptTool {
+    pub fn new(
+        servers: BTreeMap<String, SharedMcpClient>,
+        prompts: &[(String, DiscoveredPrompt)],
+    ) -> Self {
+        let description = format!(
+            "Fetch a prompt template from an MCP server and return its rendered messages. \
+             Follow the returned instructions. Available prompts (* = required argument):\n{}",
+            format_prompt_catalog(prompts)
+        );
+        Self {
+            servers,
+            description,
+        }
+    }
+}
+
+fn format_prompt_catalog(prompts: &[(String, DiscoveredPrompt)]) -> String {
+    prompts
+        .iter()
+        .map(|(server, prompt)| {
+            let args: Vec<String> = prompt
+                .arguments
+                .iter()
+                .map(|arg| {
+                    if arg.required {
+                        format!("{}*", arg.name)
+                    } else {
+                        arg.name.clone()
+                    }
+                })
+                .collect();
+            let mut line = format!("- {}/{}({})", server, prompt.name, args.join(", "));
+            if let Some(description) = &prompt.description {
+                line.push_str(&format!(": {}", description));
+            }
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[async_trait]
+impl Tool for McpGetPromptTool {
+    fn name(&self) -> &str {
+        "mcp_get_prompt"
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "server": {
+                    "type": "string",
+                    "enum": self.servers.keys().collect::<Vec<_>>(),
+                    "description": "MCP server name (optional when only one server exposes prompts)"
+                },
+                "name": {
+                    "type": "string",
+                    "description": "Prompt name"
+                },
+                "arguments": {
+                    "type": "object",
+                    "additionalProperties": { "type": "string" },
+                    "description": "Prompt arguments"
+                }
+            },
+            "required": ["name"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> agent_diva_tooling::Result<String> {
+        let name = args
+            .get("name")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidArguments("Missing 'name' parameter".to_string()))?;
+        let arguments: BTreeMap<String, String> = args
+            .get("arguments")
+            .and_then(|v| v.as_object())
+            .map(|map| {
+                map.iter()
+                    .map(|(key, value)| {
+                        let value = match value {
+                            Value::String(s) => s.clone(),
+                            other => other.to_string(),
+                        };
+                        (key.clone(), value)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let (server_name, client) = select_server(&self.servers, &args)?;
+        let guard = client.read().await;
+        let client = guard.as_ref().ok_or_else(|| {
+            ToolError::ExecutionFailed(format!("MCP server '{}' session is closed", server_name))
+        })?;
+        client
+            .get_prompt(name, arguments)
+            .await
+            .map_err(|e| ToolError::ExecutionFailed(format!("MCP server '{}': {}", server_name, e)))
+    }
+}
+
+fn select_server<'a>(
+    servers: &'a BTreeMap<String, SharedMcpClient>,
+    args: &Value,
+) -> agent_diva_tooling::Result<(&'a str, &'a SharedMcpClient)> {
+    match args.get("server").and_then(|v| v.as_str()) {
+        Some(name) => servers
+            .get_key_value(name)
+            .map(|(name, client)| (name.as_str(), client))
+            .ok_or_else(|| ToolError::InvalidArguments(format!("Unknown MCP server '{}'", name))),
+        None if servers.len() == 1 => servers
+            .iter()
+            .next()
+            .map(|(name, client)| (name.as_str(), client))
+            .ok_or_else(|| ToolError::InvalidArguments("No MCP servers available".to_string())),
+        None => Err(ToolError::InvalidArguments(
+            "Missing 'server' parameter".to_string(),
+        )),
+    }
+}
+
+// ============================================================================
+// Connection Manager
+// ============================================================================
+
+#[derive(Clone)]
+struct McpServerHandle {
+    client: SharedMcpClient,
+    tool_timeout: u64,
+}
+
+/// Owns the MCP connections of an agent and rebuilds their tools when a server
+/// announces that its tool or prompt list changed.
+#[derive(Default)]
+pub struct McpManager {
+    servers: RwLock<BTreeMap<String, McpServerHandle>>,
+    changes: McpListChanges,
+    /// Names of the tools returned by the last connect or refresh.
+    tool_names: StdMutex<Vec<String>>,
+}
+
+impl McpManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace current connections with the given servers and return their tools.
+    pub async fn connect(&self, configs: &HashMap<String, MCPServerConfig>) -> Vec<Arc<dyn Tool>> {
+        self.shutdown().await;
+
+        let mut servers = BTreeMap::new();
+        for (server_name, config) in configs {
+            match McpClientWrapper::connect_tracked(server_name, config, self.changes.clone()).await
+            {
+                Ok(client) => {
+                    servers.insert(
+                        server_name.clone(),
+                        McpServerHandle {
+                            client: Arc::new(RwLock::new(Some(client))),
+                            tool_timeout: config.tool_timeout,
+                        },
+                    );
+                }
+                Err(err) => warn!("MCP server '{}' skipped: {}", server_name, err),
+            }
+        }
+        *self.servers.write().await = servers;
+        self.changes.take();
+
+        self.build_tools().await
+    }
+
+    /// Blocking variant of [`Self::connect`] for non-async call sites.
+    pub fn connect_sync(&self, configs: &HashMap<String, MCPServerConfig>) -> Vec<Arc<dyn Tool>> {
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => tokio::task::block_in_place(|| handle.block_on(self.connect(configs))),
+            Err(_) => match tokio::runtime::Runtime::new() {
+                Ok(rt) => rt.block_on(self.connect(configs)),
+                Err(e) => {
+                    warn!("Failed to create tokio runtime for MCP tools: {}", e);
+                    Vec::new()
+                }
+            },
+        }
+    }
+
+    /// Whether any server announced a list change since the last refresh.
+    pub fn has_pending_changes(&self) -> bool {
+        !self.changes.is_empty()
+    }
+
+    /// Rebuild the full MCP tool set if a server announced a list change.
+    pub async fn refresh_if_changed(&self) -> Option<Vec<Arc<dyn Tool>>> {
+        let changed = self.changes.take();
+        if changed.is_empty() {
+            return None;
+        }
+        info!("Refreshing MCP tools after list change on {:?}", changed);
+        Some(self.build_tools().await)
+    }
+
+    /// Names of the tools handed out by the last [`Self::connect`] or
+    /// [`Self::refresh_if_changed`]; unregister exactly these when replacing them.
+    pub fn tool_names(&self) -> Vec<String> {
+        self.tool_names.lock().expect("mcp tool names lock").clone()
+    }
+
+    /// Close all connections.
+    pub async fn shutdown(&self) {
+        self.tool_names.lock().expect("mcp tool names lock").clear();
+        let servers = std::mem::take(&mut *self.servers.write().await);
+        for handle in servers.into_values() {
+            if let Some(client) = handle.client.write().await.take() {
+                client.shutdown().await;
+            }
+        }
+    }
+
+    async fn build_tools(&self) -> Vec<Arc<dyn Tool>> {
+        let servers = self.servers.read().await.clone();
+        let mut tools: Vec<Arc<dyn Tool>> = Vec::new();
+        let mut resource_servers = BTreeMap::new();
+        let mut prompt_servers = BTreeMap::new();
+        let mut prompts = Vec::new();
+
+        for (server_name, handle) in servers {
+            let guard = handle.client.read().await;
+            let Some(client) = guard.as_ref() else {
+                continue;
+            };
+
+            match client.list_tools().await {
+                Ok(discovered) => {
+                    for tool in discovered {
+                        tools.push(Arc::new(McpSdkTool::new(
+                            &server_name,
+                            handle.client.clone(),
+                            tool,
+                            handle.tool_timeout,
+                        )));
+                    }
+                }
+                Err(err) => warn!("MCP server '{}' tool listing failed: {}", server_name, err),
+            }
+
+            if client.supports_resources() {
+                resource_servers.insert(server_name.clone(), handle.client.clone());
+            }
+
+            if client.supports_prompts() {
+                match client.list_prompts().await {
+                    Ok(listed) if !listed.is_empty() => {
+                        prompt_servers.insert(server_name.clone(), handle.client.clone());
+                        prompts.extend(listed.into_iter().map(|p| (server_name.clone(), p)));
+                    }
+                    Ok(_) => {}
+                    Err(err) => {
+                        warn!(
+                            "MCP server '{}' prompt listing failed: {}",
+                            server_name, err
+                        )
+                    }
+                }
+            }
+        }
+
+        if !resource_servers.is_empty() {
+            tools.push(Arc::new(McpReadResourceTool::new(resource_servers)));
+        }
+        if !prompts.is_empty() {
+            tools.push(Arc::new(McpGetPromptTool::new(prompt_servers, &prompts)));
+        }
+        *self.tool_names.lock().expect("mcp tool names lock") =
+            tools.iter().map(|tool| tool.name().to_string()).collect();
+        tools
+    }
+}
+
 fn sanitize_identifier(input: &str) -> String {
     let mut out = String::with_capacity(input.len());
     for ch in input.chars() {
@@ -420,16 +1281,7 @@ pub async fn probe_mcp_server(
     server_name: &str,
     config: &MCPServerConfig,
 ) -> Result<Vec<DiscoveredTool>, McpError> {
-    let client = if !config.command.trim().is_empty() {
-        McpClientWrapper::new_stdio(server_name, config).await?
-    } else if !config.url.trim().is_empty() {
-        McpClientWrapper::new_sse(server_name, config).await?
-    } else {
-        return Err(McpError::Config(
-            "MCP server requires either command or url".to_string(),
-        ));
-    };
-
+    let client = McpClientWrapper::connect(server_name, config).await?;
     let tools = client.list_tools().await;
     client.shutdown().await;
     tools
@@ -459,16 +1311,7 @@ async fn create_client_and_discover_tools(
     server_name: &str,
     config: &MCPServerConfig,
 ) -> Result<(Arc<RwLock<Option<McpClientWrapper>>>, Vec<DiscoveredTool>), McpError> {
-    let client = if !config.command.trim().is_empty() {
-        McpClientWrapper::new_stdio(server_name, config).await?
-    } else if !config.url.trim().is_empty() {
-        McpClientWrapper::new_sse(server_name, config).await?
-    } else {
-        return Err(McpError::Config(
-            "MCP server requires either command or url".to_string(),
-        ));
-    };
-
+    let client = McpClientWrapper::connect(server_name, config).await?;
     let tools = client.list_tools().await?;
     let client_arc = Arc::new(RwLock::new(Some(client)));
 
@@ -515,43 +1358,365 @@ pub fn probe_mcp_server_sync(
 ///
 /// This function provides backward compatibility with the legacy API,
 /// returning `Vec<Arc<dyn Tool>>` for use in non-async contexts.
-/// It can be called from both async and non-async contexts.
+/// It can be called from both async and non-async contexts. The returned
+/// tools keep their connections alive; list-changed notifications are not
+/// tracked (use [`McpManager`] for that).
 pub fn load_mcp_tools_sync(configs: &HashMap<String, MCPServerConfig>) -> Vec<Arc<dyn Tool>> {
-    let run = || async {
-        let loaded = load_mcp_tools(configs).await;
-        let mut tools: Vec<Arc<dyn Tool>> = Vec::new();
+    McpManager::new().connect_sync(configs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        for (server_name, (client, discovered_tools)) in loaded {
-            let tool_timeout = configs
-                .get(&server_name)
-                .map(|c| c.tool_timeout)
-                .unwrap_or(30);
+    #[test]
+    fn test_list_changes_are_deduplicated_and_drained() {
+        let changes = McpListChanges::default();
+        assert!(changes.is_empty());
 
-            for tool in discovered_tools {
-                let mcp_tool = McpSdkTool::new(&server_name, client.clone(), tool, tool_timeout);
-                tools.push(Arc::new(mcp_tool));
+        let handler_side = changes.clone();
+        handler_side.mark("docs");
+        handler_side.mark("db");
+        handler_side.mark("docs");
+
+        assert_eq!(changes.take(), vec!["db".to_string(), "docs".to_string()]);
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn test_prompt_catalog_marks_required_arguments() {
+        let prompts = vec![(
+            "git".to_string(),
+            DiscoveredPrompt {
+                name: "review".to_string(),
+                description: Some("Review a diff".to_string()),
+                arguments: vec![
+                    DiscoveredPromptArgument {
+                        name: "diff".to_string(),
+                        description: None,
+                        required: true,
+                    },
+                    DiscoveredPromptArgument {
+                        name: "focus".to_string(),
+                        description: None,
+                        required: false,
+                    },
+                ],
+            },
+        )];
+
+        assert_eq!(
+            format_prompt_catalog(&prompts),
+            "- git/review(diff*, focus): Review a diff"
+        );
+    }
+
+    #[test]
+    fn test_select_server_requires_name_when_ambiguous() {
+        let mut servers: BTreeMap<String, SharedMcpClient> = BTreeMap::new();
+        servers.insert("a".to_string(), Arc::new(RwLock::new(None)));
+
+        let (name, _) = select_server(&servers, &json!({})).unwrap();
+        assert_eq!(name, "a");
+
+        servers.insert("b".to_string(), Arc::new(RwLock::new(None)));
+        assert!(select_server(&servers, &json!({})).is_err());
+        assert!(select_server(&servers, &json!({"server": "c"})).is_err());
+        let (name, _) = select_server(&servers, &json!({"server": "b"})).unwrap();
+        assert_eq!(name, "b");
+    }
+
+    #[tokio::test]
+    async fn test_manager_refresh_is_noop_without_changes() {
+        let manager = McpManager::new();
+        assert!(manager.connect(&HashMap::new()).await.is_empty());
+        assert!(!manager.has_pending_changes());
+        assert!(manager.refresh_if_changed().await.is_none());
+
+        manager.changes.mark("gone");
+        assert!(manager.has_pending_changes());
+        let refreshed = manager.refresh_if_changed().await.expect("refresh");
+        assert!(refreshed.is_empty());
+        assert!(manager.tool_names().is_empty());
+    }
+}
+
+#[cfg(test)]
+mod transport_tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    #[derive(Default)]
+    struct FakeServerState {
+        sessions: AtomicUsize,
+        expired_once: std::sync::atomic::AtomicBool,
+        interrupted_once: std::sync::atomic::AtomicBool,
+        tool_calls: AtomicUsize,
+        authorization: StdMutex<Vec<String>>,
+    }
+
+    /// Minimal Streamable HTTP server: JSON responses, `Mcp-Session-Id` per
+    /// initialize, a 404 for the first `tools/list` on session 1 and for every
+    /// `tools/call` on session 2, and an event stream that ends before the
+    /// response for the first `resources/list`.
+    async fn spawn_fake_streamable_server() -> (String, Arc<FakeServerState>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/mcp", listener.local_addr().unwrap());
+        let state = Arc::new(FakeServerState::default());
+        let server_state = state.clone();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let state = server_state.clone();
+                tokio::spawn(async move {
+                    let mut buf = Vec::new();
+                    loop {
+                        let Some(head_end) = find_head_end(&buf) else {
+                            let mut chunk = [0u8; 4096];
+                            match socket.read(&mut chunk).await {
+                                Ok(0) | Err(_) => return,
+                                Ok(n) => buf.extend_from_slice(&chunk[..n]),
+                            }
+                            continue;
+                        };
+                        let head = String::from_utf8_lossy(&buf[..head_end]).to_string();
+                        let header = |name: &str| {
+                            head.lines().find_map(|line| {
+                                let (key, value) = line.split_once(':')?;
+                                key.eq_ignore_ascii_case(name)
+                                    .then(|| value.trim().to_string())
+                            })
+                        };
+                        let length: usize = header("content-length")
+                            .and_then(|v| v.parse().ok())
+                            .unwrap_or(0);
+                        while buf.len() < head_end + 4 + length {
+                            let mut chunk = [0u8; 4096];
+                            match socket.read(&mut chunk).await {
+                                Ok(0) | Err(_) => return,
+                                Ok(n) => buf.extend_from_slice(&chunk[..n]),
+                            }
+                        }
+                        let body: Vec<u8> = buf[head_end + 4..head_end + 4 + length].to_vec();
+                        buf.drain(..head_end + 4 + length);
+
+                        if let Some(auth) = header("authorization") {
+                            state.authorization.lock().unwrap().push(auth);
+                        }
+                        let response =
+                            fake_response(&state, &head, header("mcp-session-id"), &body);
+                        if socket.write_all(response.as_bytes()).await.is_err() {
+                            return;
+                        }
+                    }
+                });
             }
-        }
+        });
+        (url, state)
+    }
 
-        tools
-    };
+    fn find_head_end(buf: &[u8]) -> Option<usize> {
+        buf.windows(4).position(|w| w == b"\r\n\r\n")
+    }
 
-    // Try to get the current runtime handle
-    match tokio::runtime::Handle::try_current() {
-        Ok(handle) => {
-            // We're inside a runtime, use block_in_place
-            tokio::task::block_in_place(|| handle.block_on(run()))
+    fn http_response(status: &str, extra_headers: &str, body: &str) -> String {
+        format!(
+            "HTTP/1.1 {}\r\ncontent-type: application/json\r\n{}content-length: {}\r\n\r\n{}",
+            status,
+            extra_headers,
+            body.len(),
+            body
+        )
+    }
+
+    fn fake_response(
+        state: &FakeServerState,
+        head: &str,
+        session_id: Option<String>,
+        body: &[u8],
+    ) -> String {
+        if !head.starts_with("POST") {
+            return http_response("405 Method Not Allowed", "", "");
         }
-        Err(_) => {
-            // Not in a runtime, create one
-            let rt = match tokio::runtime::Runtime::new() {
-                Ok(rt) => rt,
-                Err(e) => {
-                    warn!("Failed to create tokio runtime for MCP tools: {}", e);
-                    return Vec::new();
+        let message: Value = serde_json::from_slice(body).unwrap_or(Value::Null);
+        let Some(id) = message.get("id").cloned() else {
+            return http_response("202 Accepted", "", "");
+        };
+        match message.get("method").and_then(|m| m.as_str()) {
+            Some("initialize") => {
+                let session = state.sessions.fetch_add(1, Ordering::SeqCst) + 1;
+                let result = json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "result": {
+                        "protocolVersion": LATEST_PROTOCOL_VERSION,
+                        "capabilities": { "tools": {}, "resources": {} },
+                        "serverInfo": { "name": "fake", "version": "1.0.0" }
+                    }
+                });
+                http_response(
+                    "200 OK",
+                    &format!("mcp-session-id: s{}\r\n", session),
+                    &result.to_string(),
+                )
+            }
+            Some("tools/list") => {
+                if session_id.as_deref() == Some("s1")
+                    && !state.expired_once.swap(true, Ordering::SeqCst)
+                {
+                    return http_response("404 Not Found", "", "");
                 }
-            };
-            rt.block_on(run())
+                let result = json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "result": {
+                        "tools": [{
+                            "name": "echo",
+                            "description": "Echo input",
+                            "inputSchema": { "type": "object", "properties": {} }
+                        }]
+                    }
+                });
+                http_response("200 OK", "", &result.to_string())
+            }
+            Some("resources/list") => {
+                if !state.interrupted_once.swap(true, Ordering::SeqCst) {
+                    return "HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\ncontent-length: 0\r\n\r\n"
+                        .to_string();
+                }
+                let result = json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "result": {
+                        "resources": [{ "uri": "file:///notes.md", "name": "notes" }]
+                    }
+                });
+                http_response("200 OK", "", &result.to_string())
+            }
+            Some("tools/call") => {
+                if session_id.as_deref() == Some("s2") {
+                    return http_response("404 Not Found", "", "");
+                }
+                state.tool_calls.fetch_add(1, Ordering::SeqCst);
+                let result = json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "result": {
+                        "content": [{ "type": "text", "text": "Error 404: page missing" }]
+                    }
+                });
+                http_response("200 OK", "", &result.to_string())
+            }
+            _ => http_response(
+                "200 OK",
+                "",
+                &json!({ "jsonrpc": "2.0", "id": id, "result": {} }).to_string(),
+            ),
         }
     }
+
+    #[tokio::test]
+    async fn test_streamable_http_sends_headers_and_recovers_expired_session() {
+        let (url, state) = spawn_fake_streamable_server().await;
+        std::env::set_var("AGENT_DIVA_TEST_MCP_TOKEN", "secret-token");
+        let config = MCPServerConfig {
+            transport: Some(MCPTransport::StreamableHttp),
+            url,
+            headers: [(
+                "Authorization".to_string(),
+                "Bearer ${AGENT_DIVA_TEST_MCP_TOKEN}".to_string(),
+            )]
+            .into(),
+            tool_timeout: 10,
+            ..MCPServerConfig::default()
+        };
+
+        let client = McpClientWrapper::connect("remote", &config).await.unwrap();
+        assert_eq!(client.transport(), MCPTransport::StreamableHttp);
+        assert_eq!(client.session_id().await.as_deref(), Some("s1"));
+
+        let tools = client.list_tools().await.unwrap();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].original_name, "echo");
+        assert_eq!(client.session_id().await.as_deref(), Some("s2"));
+
+        // A call on an expired session reconnects but is not sent again.
+        let err = client.call_tool("echo", json!({})).await.unwrap_err();
+        assert!(matches!(err, McpError::SessionExpired(_)), "{}", err);
+        assert_eq!(client.session_id().await.as_deref(), Some("s3"));
+        assert_eq!(state.tool_calls.load(Ordering::SeqCst), 0);
+
+        // Tool output mentioning 404 is a normal result, not an expired session.
+        let output = client.call_tool("echo", json!({})).await.unwrap();
+        assert!(output.contains("404"), "{}", output);
+        assert_eq!(state.tool_calls.load(Ordering::SeqCst), 1);
+
+        // A read-only request whose response stream breaks off is sent again.
+        let resources = client.list_resources().await.unwrap();
+        assert_eq!(resources.len(), 1);
+        assert!(state.interrupted_once.load(Ordering::SeqCst));
+        assert_eq!(client.session_id().await.as_deref(), Some("s3"));
+
+        let authorization = state.authorization.lock().unwrap().clone();
+        assert!(!authorization.is_empty());
+        assert!(authorization.iter().all(|v| v == "Bearer secret-token"));
+        client.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_missing_header_env_var_is_a_config_error() {
+        let config = MCPServerConfig {
+            transport: Some(MCPTransport::StreamableHttp),
+            url: "http://127.0.0.1:9/mcp".to_string(),
+            headers: [(
+                "Authorization".to_string(),
+                "Bearer ${AGENT_DIVA_TEST_MCP_UNSET_TOKEN}".to_string(),
+            )]
+            .into(),
+            ..MCPServerConfig::default()
+        };
+        let err = McpClientWrapper::connect("remote", &config)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, McpError::Config(_)), "{}", err);
+        assert!(err.to_string().contains("AGENT_DIVA_TEST_MCP_UNSET_TOKEN"));
+    }
+
+    #[tokio::test]
+    async fn test_streamable_http_connection_error_names_transport() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        drop(listener);
+
+        let config = MCPServerConfig {
+            transport: Some(MCPTransport::StreamableHttp),
+            url: format!("http://127.0.0.1:{}/mcp", port),
+            tool_timeout: 5,
+            ..MCPServerConfig::default()
+        };
+        let err = McpClientWrapper::connect("remote", &config)
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("streamable_http transport error"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_session_expiry_detection() {
+        // The transport's `StatusCode` comes from its own `http` version.
+        let status = |code: u16| TransportError::Http(code.try_into().unwrap());
+        assert!(is_session_expired(&McpSdkError::Transport(status(404))));
+        assert!(is_session_expired(&McpSdkError::Transport(
+            TransportError::SessionExpired
+        )));
+        assert!(!is_session_expired(&McpSdkError::Transport(status(401))));
+        assert!(!is_session_expired(&McpSdkError::Internal {
+            description: "upstream returned 404 Not Found".to_string()
+        }));
+    }
 }
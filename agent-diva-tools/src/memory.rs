@@ -0,0 +1,372 @@
+//! Long-term memory tools: save, search and forget facts on request
+
+use agent_diva_core::memory::{
+    ForgetRequest, ForgetStatus, MemoryProvider, MemoryScope, MemorySearchRequest,
+    MemorySearchStatus, RememberRequest, RememberStatus,
+};
+use agent_diva_tooling::{Tool, ToolError};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+const DEFAULT_SEARCH_LIMIT: usize = 8;
+const MAX_SEARCH_LIMIT: usize = 25;
+/// Shorter targets cannot name a fact.
+const MIN_FORGET_TARGET_CHARS: usize = 3;
+
+fn required_str<'a>(params: &'a Value, key: &str) -> Result<&'a str, ToolError> {
+    params
+        .get(key)
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| ToolError::InvalidParams(format!("Missing '{}' parameter", key)))
+}
+
+/// Memory scope of the current turn, injected by the agent loop as
+/// `context_memory_scope`. Calls without it use the global scope.
+fn context_scope(params: &Value) -> MemoryScope {
+    params
+        .get("context_memory_scope")
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default()
+}
+
+/// Session of the current turn, injected as `context_session_key` and recorded
+/// as the source of saved facts and in the `HISTORY.md` audit of deletions.
+fn context_session_key(params: &Value) -> Option<String> {
+    params
+        .get("context_session_key")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+/// Store a fact in long-term memory immediately
+pub struct MemorySaveTool {
+    provider: Arc<dyn MemoryProvider>,
+    workspace: PathBuf,
+}
+
+impl MemorySaveTool {
+    pub fn new(provider: Arc<dyn MemoryProvider>, workspace: PathBuf) -> Self {
+        Self {
+            provider,
+            workspace,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for MemorySaveTool {
+    fn name(&self) -> &str {
+        "memory_save"
+    }
+
+    fn description(&self) -> &str {
+        "Save a fact to long-term memory right away, e.g. when the user says \"remember that ...\". \
+         Write one short, self-contained fact per call. It is available from the next turn on."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "fact": {
+                    "type": "string",
+                    "description": "The fact to remember, phrased so it makes sense without the conversation"
+                }
+            },
+            "required": ["fact"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<String, ToolError> {
+        let fact = required_str(&params, "fact")?;
+        let response = self
+            .provider
+            .remember(RememberRequest {
+                workspace_root: self.workspace.clone(),
+                scope: context_scope(&params),
+                fact: fact.to_string(),
+                session_key: context_session_key(&params),
+            })
+            .await
+            .map_err(|e| ToolError::ExecutionFailed(e.to_string()))?;
+
+        match response.status {
+            RememberStatus::Stored => Ok(format!("Saved to memory: {}", fact)),
+            RememberStatus::AlreadyKnown => Ok(format!("Already in memory: {}", fact)),
+            RememberStatus::Unsupported => Err(ToolError::ExecutionFailed(
+                "The configured memory backend does not support explicit saves".to_string(),
+            )),
+            RememberStatus::Failed { reason } => Err(ToolError::ExecutionFailed(reason)),
+        }
+    }
+}
+
+/// Search long-term memory on demand
+pub struct MemorySearchTool {
+    provider: Arc<dyn MemoryProvider>,
+    workspace: PathBuf,
+}
+
+impl MemorySearchTool {
+    pub fn new(provider: Arc<dyn MemoryProvider>, workspace: PathBuf) -> Self {
+        Self {
+            provider,
+            workspace,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for MemorySearchTool {
+    fn name(&self) -> &str {
+        "memory_search"
+    }
+
+    fn description(&self) -> &str {
+        "Search long-term memory for stored facts and past conversation history. \
+         Use it before memory_forget to find the exact fact to remove."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "What to look for"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum number of results (default 8, max 25)"
+                }
+            },
+            "required": ["query"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<String, ToolError> {
+        let query = required_str(&params, "query")?;
+        let limit = params
+            .get("limit")
+            .and_then(|v| v.as_u64())
+            .map(|v| (v as usize).clamp(1, MAX_SEARCH_LIMIT))
+            .unwrap_or(DEFAULT_SEARCH_LIMIT);
+        let response = self
+            .provider
+            .search(MemorySearchRequest {
+                workspace_root: self.workspace.clone(),
+                scope: context_scope(&params),
+                query: query.to_string(),
+                limit,
+            })
+            .await
+            .map_err(|e| ToolError::ExecutionFailed(e.to_string()))?;
+
+        match response.status {
+            MemorySearchStatus::Ready if response.hits.is_empty() => {
+                Ok(format!("No memories found for '{}'", query))
+            }
+            MemorySearchStatus::Ready => {
+                let lines: Vec<String> = response
+                    .hits
+                    .iter()
+                    .map(|hit| {
+                        let id = hit
+                            .id
+                            .as_ref()
+                            .map(|id| format!("[id: {}] ", id))
+                            .unwrap_or_default();
+                        let mut meta = Vec::new();
+                        if let Some(recorded_at) = &hit.recorded_at {
+                            meta.push(recorded_at.chars().take(10).collect::<String>());
+                        }
+                        if let Some(session) = &hit.source_session {
+                            meta.push(session.clone());
+                        }
+                        if meta.is_empty() {
+                            format!("- {}{}", id, hit.content)
+                        } else {
+                            format!("- {}{} ({})", id, hit.content, meta.join(", "))
+                        }
+                    })
+                    .collect();
+                Ok(format!(
+                    "Found {} memories:\n{}",
+                    lines.len(),
+                    lines.join("\n")
+                ))
+            }
+            MemorySearchStatus::Unsupported => Err(ToolError::ExecutionFailed(
+                "The configured memory backend does not support search".to_string(),
+            )),
+            MemorySearchStatus::Failed { reason } => Err(ToolError::ExecutionFailed(reason)),
+        }
+    }
+}
+
+/// Remove facts from long-term memory on request
+pub struct MemoryForgetTool {
+    provider: Arc<dyn MemoryProvider>,
+    workspace: PathBuf,
+}
+
+impl MemoryForgetTool {
+    pub fn new(provider: Arc<dyn MemoryProvider>, workspace: PathBuf) -> Self {
+        Self {
+            provider,
+            workspace,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for MemoryForgetTool {
+    fn name(&self) -> &str {
+        "memory_forget"
+    }
+
+    fn description(&self) -> &str {
+        "Delete a fact from long-term memory when the user asks to forget something. \
+         Search first, then pass the fact's id from the results or, when there is no id, \
+         its full text exactly as listed. The deletion is logged in HISTORY.md."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "target": {
+                    "type": "string",
+                    "description": "Id of the fact from memory_search, or its exact full text"
+                }
+            },
+            "required": ["target"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<String, ToolError> {
+        let target = required_str(&params, "target")?;
+        if target.chars().count() < MIN_FORGET_TARGET_CHARS {
+            return Err(ToolError::InvalidParams(format!(
+                "'target' must be at least {} characters",
+                MIN_FORGET_TARGET_CHARS
+            )));
+        }
+        let response = self
+            .provider
+            .forget(ForgetRequest {
+                workspace_root: self.workspace.clone(),
+                scope: context_scope(&params),
+                target: target.to_string(),
+                session_key: context_session_key(&params),
+            })
+            .await
+            .map_err(|e| ToolError::ExecutionFailed(e.to_string()))?;
+
+        match response.status {
+            ForgetStatus::Forgotten => Ok(format!(
+                "Forgot {} memories:\n{}",
+                response.forgotten.len(),
+                response
+                    .forgotten
+                    .iter()
+                    .map(|fact| format!("- {}", fact))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            )),
+            ForgetStatus::NotFound => Ok(format!("No memories matched '{}'", target)),
+            ForgetStatus::Unsupported => Err(ToolError::ExecutionFailed(
+                "The configured memory backend does not support forgetting".to_string(),
+            )),
+            ForgetStatus::Failed { reason } => Err(ToolError::ExecutionFailed(reason)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agent_diva_core::memory::MemoryManager;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_memory_tools_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace = temp_dir.path().to_path_buf();
+        let provider: Arc<dyn MemoryProvider> = Arc::new(MemoryManager::new(&workspace));
+        let save = MemorySaveTool::new(provider.clone(), workspace.clone());
+        let search = MemorySearchTool::new(provider.clone(), workspace.clone());
+        let forget = MemoryForgetTool::new(provider, workspace.clone());
+
+        let saved = save
+            .execute(json!({"fact": "User prefers metric units"}))
+            .await
+            .unwrap();
+        assert!(saved.starts_with("Saved to memory"));
+
+        let found = search
+            .execute(json!({"query": "metric units"}))
+            .await
+            .unwrap();
+        assert!(found.contains("User prefers metric units"));
+
+        let partial = forget
+            .execute(json!({"target": "metric units"}))
+            .await
+            .unwrap();
+        assert!(partial.starts_with("No memories matched"));
+
+        let forgotten = forget
+            .execute(json!({
+                "target": "User prefers metric units",
+                "context_session_key": "telegram:42"
+            }))
+            .await
+            .unwrap();
+        assert!(forgotten.starts_with("Forgot 1 memories"));
+        let history = MemoryManager::new(&workspace).load_history();
+        assert!(
+            history.contains("Forgot memory (requested in telegram:42): User prefers metric units")
+        );
+        assert!(!MemoryManager::new(&workspace)
+            .load_memory()
+            .content
+            .contains("metric units"));
+    }
+
+    #[tokio::test]
+    async fn test_memory_tools_use_injected_scope() {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace = temp_dir.path().to_path_buf();
+        let provider: Arc<dyn MemoryProvider> = Arc::new(MemoryManager::new(&workspace));
+        let save = MemorySaveTool::new(provider.clone(), workspace.clone());
+        let search = MemorySearchTool::new(provider, workspace.clone());
+        let scope = json!({"kind": "chat", "channel": "telegram", "chat_id": "42"});
+
+        save.execute(json!({"fact": "Prefers tea", "context_memory_scope": scope}))
+            .await
+            .unwrap();
+
+        let scoped = search
+            .execute(json!({"query": "tea", "context_memory_scope": scope}))
+            .await
+            .unwrap();
+        assert!(scoped.contains("Prefers tea"));
+        let global = search.execute(json!({"query": "tea"})).await.unwrap();
+        assert!(!global.contains("Prefers tea"));
+    }
+
+    #[tokio::test]
+    async fn test_memory_forget_rejects_short_target() {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace = temp_dir.path().to_path_buf();
+        let tool = MemoryForgetTool::new(Arc::new(MemoryManager::new(&workspace)), workspace);
+
+        let result = tool.execute(json!({"target": "a"})).await;
+        assert!(matches!(result, Err(ToolError::InvalidParams(_))));
+    }
+}
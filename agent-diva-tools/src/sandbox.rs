@@ -0,0 +1,554 @@
+//! Sandboxed execution backends for the shell tool
+//!
+//! The regex guard in [`crate::shell::ExecTool`] only catches obvious patterns. When a
+//! sandbox backend is configured, commands additionally run with a read-only view of the
+//! host filesystem, a writable workspace, no network (unless enabled) and CPU/memory
+//! rlimits. Backends are Linux-only; other platforms report a clear error instead of
+//! silently running unsandboxed.
+
+use agent_diva_core::config::{ExecSandboxMode, ExecToolConfig};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use tokio::process::Command;
+
+/// Runtime sandbox settings for the `exec` tool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecSandboxConfig {
+    pub mode: ExecSandboxMode,
+    pub allow_network: bool,
+    /// Address-space limit in MiB (0 disables the limit).
+    pub memory_mb: u64,
+    /// CPU time limit in seconds (0 disables the limit).
+    pub cpu_secs: u64,
+}
+
+impl Default for ExecSandboxConfig {
+    fn default() -> Self {
+        Self::from(&ExecToolConfig::default())
+    }
+}
+
+impl From<&ExecToolConfig> for ExecSandboxConfig {
+    fn from(config: &ExecToolConfig) -> Self {
+        Self {
+            mode: config.sandbox,
+            allow_network: config.sandbox_network,
+            memory_mb: config.sandbox_memory_mb,
+            cpu_secs: config.sandbox_cpu_secs,
+        }
+    }
+}
+
+impl ExecSandboxConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.mode != ExecSandboxMode::None
+    }
+}
+
+/// Errors raised while preparing a sandboxed command.
+#[derive(Debug, Error)]
+pub enum SandboxError {
+    #[error(
+        "sandbox backend '{backend}' is unavailable: {reason} \
+         (set tools.exec.sandbox = \"none\" to run without isolation)"
+    )]
+    Unavailable {
+        backend: &'static str,
+        reason: String,
+    },
+
+    #[error("sandbox backend '{0}' is only supported on Linux")]
+    UnsupportedPlatform(&'static str),
+
+    #[error("working directory {cwd} is outside the sandbox workspace {workspace}")]
+    OutsideWorkspace { cwd: PathBuf, workspace: PathBuf },
+}
+
+/// Build the command that runs `command` through the shell, wrapped by the configured
+/// sandbox backend. `workspace` is the only writable location inside the sandbox.
+pub fn build_command(
+    config: &ExecSandboxConfig,
+    workspace: &Path,
+    cwd: &Path,
+    command: &str,
+) -> Result<Command, SandboxError> {
+    build(config, workspace, cwd, Invocation::Shell(command))
+}
+
+/// Build the command that executes `argv` directly, without a shell, wrapped by the
+/// configured sandbox backend like [`build_command`].
+pub fn build_program_command(
+    config: &ExecSandboxConfig,
+    workspace: &Path,
+    cwd: &Path,
+    argv: &[String],
+) -> Result<Command, SandboxError> {
+    build(config, workspace, cwd, Invocation::Program(argv))
+}
+
+/// What runs inside the sandbox.
+#[derive(Clone, Copy)]
+enum Invocation<'a> {
+    /// A shell command line
+    Shell(&'a str),
+    /// A program and its arguments
+    Program(&'a [String]),
+}
+
+impl Invocation<'_> {
+    fn host_command(self) -> Command {
+        match self {
+            Self::Shell(command) => host_shell_command(command),
+            Self::Program(argv) => {
+                let mut cmd = Command::new(argv.first().map(String::as_str).unwrap_or_default());
+                cmd.args(argv.iter().skip(1));
+                cmd
+            }
+        }
+    }
+
+    fn argv(self) -> Vec<String> {
+        match self {
+            Self::Shell(command) => vec!["sh".to_string(), "-c".to_string(), command.to_string()],
+            Self::Program(argv) => argv.to_vec(),
+        }
+    }
+}
+
+fn build(
+    config: &ExecSandboxConfig,
+    workspace: &Path,
+    cwd: &Path,
+    invocation: Invocation<'_>,
+) -> Result<Command, SandboxError> {
+    if !config.is_enabled() {
+        return Ok(invocation.host_command());
+    }
+
+    let workspace = workspace
+        .canonicalize()
+        .unwrap_or_else(|_| workspace.to_path_buf());
+    let cwd = cwd.canonicalize().unwrap_or_else(|_| cwd.to_path_buf());
+    if !cwd.starts_with(&workspace) {
+        return Err(SandboxError::OutsideWorkspace { cwd, workspace });
+    }
+
+    build_sandboxed_command(config, &workspace, &cwd, invocation)
+}
+
+fn host_shell_command(command: &str) -> Command {
+    let mut cmd = if cfg!(target_os = "windows") {
+        let mut cmd = Command::new("powershell");
+        cmd.args(["-NoProfile", "-NonInteractive", "-Command"]);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c");
+        cmd
+    };
+    cmd.arg(command);
+    cmd
+}
+
+#[cfg(target_os = "linux")]
+fn build_sandboxed_command(
+    config: &ExecSandboxConfig,
+    workspace: &Path,
+    cwd: &Path,
+    invocation: Invocation<'_>,
+) -> Result<Command, SandboxError> {
+    let mut cmd = match config.mode {
+        ExecSandboxMode::None => unreachable!("handled by build"),
+        ExecSandboxMode::Bwrap => {
+            let bwrap = linux::bwrap_path()?;
+            let mut cmd = Command::new(bwrap);
+            cmd.args(bwrap_prefix(config, workspace, cwd));
+            cmd.args(invocation.argv());
+            cmd
+        }
+        ExecSandboxMode::Landlock => {
+            linux::landlock_abi()?;
+            let mut cmd = invocation.host_command();
+            linux::install_landlock(&mut cmd, config, workspace)?;
+            cmd
+        }
+    };
+    linux::install_rlimits(&mut cmd, config);
+    cmd.kill_on_drop(true);
+    Ok(cmd)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn build_sandboxed_command(
+    config: &ExecSandboxConfig,
+    _workspace: &Path,
+    _cwd: &Path,
+    _invocation: Invocation<'_>,
+) -> Result<Command, SandboxError> {
+    Err(SandboxError::UnsupportedPlatform(config.mode.as_str()))
+}
+
+/// Arguments passed to `bwrap`: read-only root, private `/tmp`, `/dev` and `/proc`,
+/// a writable bind of the workspace and (by default) no network namespace sharing.
+pub fn bwrap_args(
+    config: &ExecSandboxConfig,
+    workspace: &Path,
+    cwd: &Path,
+    command: &str,
+) -> Vec<String> {
+    let mut args = bwrap_prefix(config, workspace, cwd);
+    args.extend(Invocation::Shell(command).argv());
+    args
+}
+
+/// `bwrap` arguments up to and including the `--` before the sandboxed program.
+fn bwrap_prefix(config: &ExecSandboxConfig, workspace: &Path, cwd: &Path) -> Vec<String> {
+    let workspace = workspace.to_string_lossy().to_string();
+    let mut args: Vec<String> = ["--die-with-parent", "--new-session", "--unshare-all"]
+        .into_iter()
+        .map(String::from)
+        .collect();
+    if config.allow_network {
+        args.push("--share-net".to_string());
+    }
+    args.extend(
+        [
+            "--ro-bind",
+            "/",
+            "/",
+            "--dev",
+            "/dev",
+            "--proc",
+            "/proc",
+            "--tmpfs",
+            "/tmp",
+        ]
+        .into_iter()
+        .map(String::from),
+    );
+    args.extend([
+        "--bind".to_string(),
+        workspace.clone(),
+        workspace,
+        "--chdir".to_string(),
+        cwd.to_string_lossy().to_string(),
+        "--".to_string(),
+    ]);
+    args
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use super::{ExecSandboxConfig, SandboxError};
+    use std::ffi::CString;
+    use std::io;
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::{Path, PathBuf};
+    use std::sync::OnceLock;
+    use tokio::process::Command;
+
+    /// Locate `bwrap` and verify once per process that it can create namespaces here.
+    pub(super) fn bwrap_path() -> Result<PathBuf, SandboxError> {
+        static PROBE: OnceLock<Result<PathBuf, String>> = OnceLock::new();
+        PROBE
+            .get_or_init(|| {
+                let path = which::which("bwrap").map_err(|_| {
+                    "`bwrap` executable not found in PATH (install bubblewrap)".to_string()
+                })?;
+                let output = std::process::Command::new(&path)
+                    .args(["--unshare-all", "--ro-bind", "/", "/", "true"])
+                    .output()
+                    .map_err(|e| format!("failed to run {}: {}", path.display(), e))?;
+                if output.status.success() {
+                    Ok(path)
+                } else {
+                    Err(format!(
+                        "bwrap cannot create namespaces: {}",
+                        String::from_utf8_lossy(&output.stderr).trim()
+                    ))
+                }
+            })
+            .clone()
+            .map_err(|reason| SandboxError::Unavailable {
+                backend: "bwrap",
+                reason,
+            })
+    }
+
+    const LANDLOCK_CREATE_RULESET_VERSION: libc::c_uint = 1 << 0;
+    const LANDLOCK_RULE_PATH_BENEATH: libc::c_int = 1;
+
+    const ACCESS_FS_EXECUTE: u64 = 1 << 0;
+    const ACCESS_FS_WRITE_FILE: u64 = 1 << 1;
+    const ACCESS_FS_READ_FILE: u64 = 1 << 2;
+    const ACCESS_FS_READ_DIR: u64 = 1 << 3;
+    const ACCESS_FS_V1_ALL: u64 = (1 << 13) - 1;
+    const ACCESS_FS_REFER: u64 = 1 << 13;
+    const ACCESS_FS_TRUNCATE: u64 = 1 << 14;
+
+    const ACCESS_FS_READ: u64 = ACCESS_FS_EXECUTE | ACCESS_FS_READ_FILE | ACCESS_FS_READ_DIR;
+
+    #[repr(C)]
+    struct RulesetAttr {
+        handled_access_fs: u64,
+    }
+
+    #[repr(C, packed)]
+    struct PathBeneathAttr {
+        allowed_access: u64,
+        parent_fd: i32,
+    }
+
+    /// Landlock ABI version supported by the running kernel.
+    pub(super) fn landlock_abi() -> Result<i64, SandboxError> {
+        // SAFETY: querying the ABI version takes no pointers.
+        let abi = unsafe {
+            libc::syscall(
+                libc::SYS_landlock_create_ruleset,
+                std::ptr::null::<RulesetAttr>(),
+                0usize,
+                LANDLOCK_CREATE_RULESET_VERSION,
+            )
+        };
+        if abi < 1 {
+            return Err(SandboxError::Unavailable {
+                backend: "landlock",
+                reason: format!(
+                    "kernel does not support Landlock ({}); Linux 5.13+ with the landlock LSM is required",
+                    io::Error::last_os_error()
+                ),
+            });
+        }
+        Ok(abi)
+    }
+
+    fn handled_access(abi: i64) -> u64 {
+        let mut access = ACCESS_FS_V1_ALL;
+        if abi >= 2 {
+            access |= ACCESS_FS_REFER;
+        }
+        if abi >= 3 {
+            access |= ACCESS_FS_TRUNCATE;
+        }
+        access
+    }
+
+    /// A required path must exist and be usable as a rule.
+    pub(super) fn required_path(path: &Path) -> Result<CString, SandboxError> {
+        let unavailable = |reason: String| SandboxError::Unavailable {
+            backend: "landlock",
+            reason,
+        };
+        if !path.exists() {
+            return Err(unavailable(format!("{} does not exist", path.display())));
+        }
+        CString::new(path.as_os_str().as_bytes())
+            .map_err(|_| unavailable(format!("{} contains a NUL byte", path.display())))
+    }
+
+    /// Restrict the child to read-only host access plus a writable workspace and
+    /// `/dev`. Without network access the child also enters fresh user and network
+    /// namespaces, leaving it only a loopback interface.
+    ///
+    /// The root and workspace rules are required: if either cannot be added the
+    /// command fails instead of running with a partial ruleset.
+    pub(super) fn install_landlock(
+        cmd: &mut Command,
+        config: &ExecSandboxConfig,
+        workspace: &Path,
+    ) -> Result<(), SandboxError> {
+        let abi = landlock_abi()?;
+        let handled = handled_access(abi);
+        // Everything is allocated before fork; the pre_exec hook only issues syscalls.
+        // The flag marks required rules; optional ones are skipped only when missing.
+        let rules = vec![
+            (CString::from(c"/"), ACCESS_FS_READ, true),
+            (
+                CString::from(c"/dev"),
+                ACCESS_FS_READ | ACCESS_FS_WRITE_FILE,
+                false,
+            ),
+            (required_path(workspace)?, handled, true),
+        ];
+        let isolate_network = !config.allow_network;
+
+        // SAFETY: the hook only performs async-signal-safe syscalls on pre-allocated data.
+        unsafe {
+            cmd.pre_exec(move || {
+                if isolate_network && libc::unshare(libc::CLONE_NEWUSER | libc::CLONE_NEWNET) != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                if libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                let attr = RulesetAttr {
+                    handled_access_fs: handled,
+                };
+                let ruleset = libc::syscall(
+                    libc::SYS_landlock_create_ruleset,
+                    &attr as *const RulesetAttr,
+                    std::mem::size_of::<RulesetAttr>(),
+                    0u32,
+                ) as libc::c_int;
+                if ruleset < 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                for (path, access, required) in &rules {
+                    let fd = libc::open(path.as_ptr(), libc::O_PATH | libc::O_CLOEXEC);
+                    if fd < 0 {
+                        let error = io::Error::last_os_error();
+                        if !*required && error.raw_os_error() == Some(libc::ENOENT) {
+                            continue;
+                        }
+                        libc::close(ruleset);
+                        return Err(error);
+                    }
+                    let beneath = PathBeneathAttr {
+                        allowed_access: *access,
+                        parent_fd: fd,
+                    };
+                    let rc = libc::syscall(
+                        libc::SYS_landlock_add_rule,
+                        ruleset,
+                        LANDLOCK_RULE_PATH_BENEATH,
+                        &beneath as *const PathBeneathAttr,
+                        0u32,
+                    );
+                    libc::close(fd);
+                    if rc != 0 {
+                        libc::close(ruleset);
+                        return Err(io::Error::last_os_error());
+                    }
+                }
+                let rc = libc::syscall(libc::SYS_landlock_restrict_self, ruleset, 0u32);
+                libc::close(ruleset);
+                if rc != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+        Ok(())
+    }
+
+    /// Apply CPU time and address-space rlimits in the child before exec.
+    pub(super) fn install_rlimits(cmd: &mut Command, config: &ExecSandboxConfig) {
+        let cpu_secs = config.cpu_secs;
+        let memory_bytes = config.memory_mb.saturating_mul(1024 * 1024);
+        // SAFETY: setrlimit is async-signal-safe and the hook captures only integers.
+        unsafe {
+            cmd.pre_exec(move || {
+                let limits = [
+                    (libc::RLIMIT_CPU, cpu_secs),
+                    (libc::RLIMIT_AS, memory_bytes),
+                    (libc::RLIMIT_CORE, 0),
+                ];
+                for (resource, value) in limits {
+                    if value == 0 && resource != libc::RLIMIT_CORE {
+                        continue;
+                    }
+                    let limit = libc::rlimit {
+                        rlim_cur: value as libc::rlim_t,
+                        rlim_max: value as libc::rlim_t,
+                    };
+                    if libc::setrlimit(resource, &limit) != 0 {
+                        return Err(io::Error::last_os_error());
+                    }
+                }
+                Ok(())
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(mode: ExecSandboxMode) -> ExecSandboxConfig {
+        ExecSandboxConfig {
+            mode,
+            ..ExecSandboxConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_bwrap_args_isolate_network_by_default() {
+        let args = bwrap_args(
+            &config(ExecSandboxMode::Bwrap),
+            Path::new("/work"),
+            Path::new("/work/sub"),
+            "ls",
+        );
+        assert!(args.contains(&"--unshare-all".to_string()));
+        assert!(!args.contains(&"--share-net".to_string()));
+        let joined = args.join(" ");
+        assert!(joined.contains("--ro-bind / /"));
+        assert!(joined.contains("--bind /work /work"));
+        assert!(joined.contains("--chdir /work/sub"));
+        assert!(joined.ends_with("-- sh -c ls"));
+    }
+
+    #[test]
+    fn test_bwrap_args_share_network_when_allowed() {
+        let sandbox = ExecSandboxConfig {
+            allow_network: true,
+            ..config(ExecSandboxMode::Bwrap)
+        };
+        let args = bwrap_args(&sandbox, Path::new("/work"), Path::new("/work"), "ls");
+        assert!(args.contains(&"--share-net".to_string()));
+    }
+
+    #[test]
+    fn test_workspace_bind_comes_after_tmpfs() {
+        // Workspaces under /tmp must not be hidden by the private tmpfs.
+        let args = bwrap_args(
+            &config(ExecSandboxMode::Bwrap),
+            Path::new("/tmp/ws"),
+            Path::new("/tmp/ws"),
+            "ls",
+        );
+        let tmpfs = args.iter().position(|a| a == "--tmpfs").unwrap();
+        let bind = args.iter().position(|a| a == "--bind").unwrap();
+        assert!(tmpfs < bind);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_landlock_requires_existing_workspace() {
+        let missing = Path::new("/nonexistent/agent-diva-workspace");
+        let err = linux::required_path(missing).unwrap_err();
+        assert!(err.to_string().contains("does not exist"));
+        assert!(linux::required_path(&std::env::temp_dir()).is_ok());
+    }
+
+    #[test]
+    fn test_sandbox_rejects_cwd_outside_workspace() {
+        let workspace = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        let err = build_command(
+            &config(ExecSandboxMode::Bwrap),
+            workspace.path(),
+            outside.path(),
+            "ls",
+        )
+        .unwrap_err();
+        assert!(matches!(err, SandboxError::OutsideWorkspace { .. }));
+    }
+
+    #[test]
+    fn test_config_from_exec_tool_config() {
+        let exec = ExecToolConfig {
+            sandbox: ExecSandboxMode::Landlock,
+            sandbox_network: true,
+            sandbox_memory_mb: 64,
+            sandbox_cpu_secs: 5,
+            ..ExecToolConfig::default()
+        };
+        let sandbox = ExecSandboxConfig::from(&exec);
+        assert_eq!(sandbox.mode, ExecSandboxMode::Landlock);
+        assert!(sandbox.allow_network);
+        assert_eq!(sandbox.memory_mb, 64);
+        assert_eq!(sandbox.cpu_secs, 5);
+    }
+}
@@ -0,0 +1,288 @@
+//! Session search tool: find messages in past conversation transcripts
+
+use agent_diva_core::memory::MemoryScope;
+use agent_diva_core::session::{parse_date_bound, SessionSearchIndex, SessionSearchQuery};
+use agent_diva_tooling::{Tool, ToolError};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+
+const DEFAULT_LIMIT: usize = 10;
+const MAX_LIMIT: usize = 50;
+
+/// Full-text search over saved session transcripts: the caller's own session
+/// by default, every session its memory scope can see with `all_sessions`
+pub struct SessionSearchTool {
+    workspace: PathBuf,
+}
+
+impl SessionSearchTool {
+    pub fn new(workspace: PathBuf) -> Self {
+        Self { workspace }
+    }
+}
+
+/// Memory scope of the current turn, injected as `context_memory_scope`.
+/// Calls without it use the global scope, like the memory tools.
+fn context_scope(params: &Value) -> MemoryScope {
+    params
+        .get("context_memory_scope")
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default()
+}
+
+fn optional_str<'a>(params: &'a Value, key: &str) -> Option<&'a str> {
+    params
+        .get(key)
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+}
+
+#[async_trait]
+impl Tool for SessionSearchTool {
+    fn name(&self) -> &str {
+        "session_search"
+    }
+
+    fn description(&self) -> &str {
+        "Search the transcript of the current conversation, or of every past conversation \
+         this chat may see when all_sessions is true. \
+         Returns matching message snippets with their session key and message index."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "Words that must all appear in the message"
+                },
+                "all_sessions": {
+                    "type": "boolean",
+                    "description": "Search every saved conversation this chat may see instead of only the current one (default false)"
+                },
+                "channel": {
+                    "type": "string",
+                    "description": "With all_sessions, only search sessions from this channel (e.g. 'telegram')"
+                },
+                "role": {
+                    "type": "string",
+                    "enum": ["user", "assistant", "tool", "system"],
+                    "description": "Only match messages with this role"
+                },
+                "from": {
+                    "type": "string",
+                    "description": "Earliest message date (YYYY-MM-DD or RFC 3339)"
+                },
+                "to": {
+                    "type": "string",
+                    "description": "Latest message date (YYYY-MM-DD or RFC 3339)"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum number of results (default 10, max 50)"
+                }
+            },
+            "required": ["query"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<String, ToolError> {
+        let text = optional_str(&params, "query")
+            .ok_or_else(|| ToolError::InvalidParams("Missing 'query' parameter".to_string()))?;
+        let bound = |key: &str, end_of_day: bool| {
+            optional_str(&params, key)
+                .map(|value| parse_date_bound(value, end_of_day))
+                .transpose()
+                .map_err(|e| ToolError::InvalidParams(e.to_string()))
+        };
+        let all_sessions = params
+            .get("all_sessions")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let current_session = optional_str(&params, "context_session_key");
+        let mut channel = optional_str(&params, "channel").map(str::to_string);
+        // Other conversations are only visible as far as the caller's memory
+        // scope reaches, so a scoped chat never reads other users' transcripts.
+        let session_key = match (all_sessions, context_scope(&params)) {
+            (true, MemoryScope::Global) => None,
+            (true, MemoryScope::Channel { channel: own }) => {
+                if channel.as_ref().is_some_and(|channel| *channel != own) {
+                    return Ok(format!(
+                        "Error: this chat can only search sessions of channel '{}'",
+                        own
+                    ));
+                }
+                channel = Some(own);
+                None
+            }
+            (true, MemoryScope::Chat { channel, chat_id }) => {
+                Some(format!("{}:{}", channel, chat_id))
+            }
+            (_, _) => {
+                let Some(session_key) = current_session else {
+                    return Ok(
+                        "Error: no current session to search; set all_sessions to search every conversation"
+                            .to_string(),
+                    );
+                };
+                Some(session_key.to_string())
+            }
+        };
+        let query = SessionSearchQuery {
+            text: text.to_string(),
+            channel,
+            session_key,
+            role: optional_str(&params, "role").map(str::to_string),
+            from: bound("from", false)?,
+            to: bound("to", true)?,
+            limit: Some(
+                params
+                    .get("limit")
+                    .and_then(|v| v.as_u64())
+                    .map(|v| (v as usize).clamp(1, MAX_LIMIT))
+                    .unwrap_or(DEFAULT_LIMIT),
+            ),
+        };
+
+        let index = SessionSearchIndex::for_workspace(&self.workspace)
+            .await
+            .map_err(|e| ToolError::ExecutionFailed(e.to_string()))?;
+        let hits = index
+            .search(&query)
+            .await
+            .map_err(|e| ToolError::ExecutionFailed(e.to_string()))?;
+        if hits.is_empty() {
+            return Ok(format!("No past messages found for '{}'", text));
+        }
+
+        let lines: Vec<String> = hits
+            .iter()
+            .map(|hit| {
+                format!(
+                    "- [{} #{}] {} {}: {}",
+                    hit.session_key,
+                    hit.message_index,
+                    hit.timestamp.format("%Y-%m-%d %H:%M"),
+                    hit.role,
+                    hit.snippet.replace('\n', " ")
+                )
+            })
+            .collect();
+        Ok(format!(
+            "Found {} messages:\n{}",
+            lines.len(),
+            lines.join("\n")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agent_diva_core::session::SessionManager;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_session_search_finds_past_messages() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut sessions = SessionManager::new(temp_dir.path());
+        let session = sessions.get_or_create("telegram:42").unwrap();
+        session.add_message("user", "Let's discuss the Q3 migration plan");
+        let session = session.clone();
+        sessions.save(&session).unwrap();
+        let tool = SessionSearchTool::new(temp_dir.path().to_path_buf());
+
+        let found = tool
+            .execute(json!({"query": "q3 migration", "context_session_key": "telegram:42"}))
+            .await
+            .unwrap();
+        assert!(found.contains("[telegram:42 #0]"));
+        assert!(found.contains("**migration**"));
+
+        let other_chat = tool
+            .execute(json!({"query": "migration", "context_session_key": "telegram:7"}))
+            .await
+            .unwrap();
+        assert!(other_chat.starts_with("No past messages"));
+        let no_session = tool.execute(json!({"query": "migration"})).await.unwrap();
+        assert!(no_session.starts_with("Error:"));
+
+        let everywhere = tool
+            .execute(json!({"query": "migration", "all_sessions": true, "channel": "telegram"}))
+            .await
+            .unwrap();
+        assert!(everywhere.contains("[telegram:42 #0]"));
+        let missing = tool
+            .execute(json!({"query": "migration", "all_sessions": true, "channel": "discord"}))
+            .await
+            .unwrap();
+        assert!(missing.starts_with("No past messages"));
+        assert!(tool
+            .execute(json!({"query": "migration", "all_sessions": true, "from": "yesterday"}))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_session_search_all_sessions_stays_in_memory_scope() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut sessions = SessionManager::new(temp_dir.path());
+        for key in ["telegram:42", "telegram:7", "discord:9"] {
+            let session = sessions.get_or_create(key).unwrap();
+            session.add_message("user", &format!("Budget notes from {}", key));
+            let session = session.clone();
+            sessions.save(&session).unwrap();
+        }
+        let tool = SessionSearchTool::new(temp_dir.path().to_path_buf());
+
+        let chat = tool
+            .execute(json!({
+                "query": "budget",
+                "all_sessions": true,
+                "context_session_key": "telegram:7",
+                "context_memory_scope": {"kind": "chat", "channel": "telegram", "chat_id": "7"}
+            }))
+            .await
+            .unwrap();
+        assert!(chat.contains("[telegram:7 #0]"));
+        assert!(!chat.contains("telegram:42"));
+        assert!(!chat.contains("discord:"));
+
+        let channel_scope = json!({"kind": "channel", "channel": "telegram"});
+        let channel = tool
+            .execute(json!({
+                "query": "budget",
+                "all_sessions": true,
+                "context_memory_scope": channel_scope
+            }))
+            .await
+            .unwrap();
+        assert!(channel.contains("telegram:42") && channel.contains("telegram:7"));
+        assert!(!channel.contains("discord:"));
+        let other_channel = tool
+            .execute(json!({
+                "query": "budget",
+                "all_sessions": true,
+                "channel": "discord",
+                "context_memory_scope": channel_scope
+            }))
+            .await
+            .unwrap();
+        assert!(other_channel.starts_with("Error:"));
+
+        let sender = tool
+            .execute(json!({
+                "query": "budget",
+                "all_sessions": true,
+                "context_session_key": "discord:9",
+                "context_memory_scope": {"kind": "sender", "channel": "discord", "sender_id": "u1"}
+            }))
+            .await
+            .unwrap();
+        assert!(sender.contains("[discord:9 #0]"));
+        assert!(!sender.contains("telegram:"));
+    }
+}
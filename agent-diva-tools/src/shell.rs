@@ -1,11 +1,14 @@
 //! Shell execution tool
 
+use crate::sandbox::{self, ExecSandboxConfig};
 use crate::sanitize::sanitize_for_json;
+use crate::shell_jobs::{ShellJobManager, DEFAULT_JOB_SESSION};
 use agent_diva_tooling::{Tool, ToolError};
 use async_trait::async_trait;
 use regex::Regex;
 use serde_json::{json, Value};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::process::Command;
 use tokio::time::timeout;
@@ -15,7 +18,7 @@ use tracing::{debug, info};
 /// On Windows, PowerShell and cmd often emit system ANSI (e.g. GBK on zh-CN); treating that as
 /// UTF-8 produces U+FFFD garbage and can break downstream LLM JSON. Prefer strict UTF-8, then
 /// GB18030 when it yields fewer replacement characters than UTF-8 lossy decoding.
-fn decode_shell_pipe_bytes(bytes: &[u8]) -> String {
+pub(crate) fn decode_shell_pipe_bytes(bytes: &[u8]) -> String {
     if bytes.is_empty() {
         return String::new();
     }
@@ -52,6 +55,8 @@ pub struct ExecTool {
     deny_patterns: Vec<Regex>,
     allow_patterns: Vec<Regex>,
     restrict_to_workspace: bool,
+    sandbox: ExecSandboxConfig,
+    jobs: Option<Arc<ShellJobManager>>,
 }
 
 impl ExecTool {
@@ -63,6 +68,8 @@ impl ExecTool {
             deny_patterns: Self::default_deny_patterns(),
             allow_patterns: Vec::new(),
             restrict_to_workspace: true,
+            sandbox: ExecSandboxConfig::default(),
+            jobs: None,
         }
     }
 
@@ -78,9 +85,24 @@ impl ExecTool {
             deny_patterns: Self::default_deny_patterns(),
             allow_patterns: Vec::new(),
             restrict_to_workspace,
+            sandbox: ExecSandboxConfig::default(),
+            jobs: None,
         }
     }
 
+    /// Run commands through a sandbox backend. The working directory configured for this
+    /// tool becomes the only writable location inside the sandbox.
+    pub fn with_sandbox(mut self, sandbox: ExecSandboxConfig) -> Self {
+        self.sandbox = sandbox;
+        self
+    }
+
+    /// Enable `background` and `persistent` execution backed by a shared job manager.
+    pub fn with_jobs(mut self, jobs: Arc<ShellJobManager>) -> Self {
+        self.jobs = Some(jobs);
+        self
+    }
+
     /// Default dangerous command patterns
     fn default_deny_patterns() -> Vec<Regex> {
         vec![
@@ -164,7 +186,9 @@ impl Tool for ExecTool {
     }
 
     fn description(&self) -> &str {
-        "Execute a shell command and return its output. Use with caution."
+        "Execute a shell command and return its output. Use with caution. Set background=true \
+         for long-running commands (dev servers, builds) and manage them with exec_job; set \
+         persistent=true to keep cwd and environment variables between calls."
     }
 
     fn parameters(&self) -> Value {
@@ -178,6 +202,14 @@ impl Tool for ExecTool {
                 "working_dir": {
                     "type": "string",
                     "description": "Optional working directory for the command"
+                },
+                "background": {
+                    "type": "boolean",
+                    "description": "Start the command detached and return a job ID instead of waiting for it"
+                },
+                "persistent": {
+                    "type": "boolean",
+                    "description": "Run in this session's persistent shell, preserving cwd and environment between calls"
                 }
             },
             "required": ["command"]
@@ -202,6 +234,43 @@ impl Tool for ExecTool {
             return Ok(format!("Error: {}", err));
         }
 
+        let background = params
+            .get("background")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let persistent = params
+            .get("persistent")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        if background || persistent {
+            let Some(jobs) = &self.jobs else {
+                return Ok(
+                    "Error: background jobs and persistent shells are not enabled for this agent"
+                        .to_string(),
+                );
+            };
+            let session_key = params
+                .get("context_session_key")
+                .and_then(|v| v.as_str())
+                .unwrap_or(DEFAULT_JOB_SESSION);
+            let result = if background {
+                match self.prepare_command(command, &working_dir) {
+                    Ok(cmd) => jobs.start(session_key, command, cmd).await,
+                    Err(err) => Err(err),
+                }
+            } else {
+                jobs.run_persistent(
+                    session_key,
+                    command,
+                    Duration::from_secs(self.timeout_secs),
+                    || self.prepare_command("exec sh 2>&1", &working_dir),
+                )
+                .await
+                .map(|output| truncate_output(sanitize_for_json(&output)))
+            };
+            return Ok(result.unwrap_or_else(|err| format!("Error executing command: {}", err)));
+        }
+
         // Execute command
         let result = self.execute_command(command, &working_dir).await;
 
@@ -213,31 +282,32 @@ impl Tool for ExecTool {
 }
 
 impl ExecTool {
-    /// Execute the command and return output
-    async fn execute_command(&self, command: &str, cwd: &Path) -> Result<String, String> {
-        info!("Executing command: '{}' in {:?}", command, cwd);
-
-        // Determine shell based on OS
-        let (shell, args) = if cfg!(target_os = "windows") {
-            (
-                "powershell",
-                vec!["-NoProfile", "-NonInteractive", "-Command"],
-            )
-        } else {
-            ("sh", vec!["-c"])
-        };
+    /// Build the shell (or sandbox) command for `command`, rooted at `cwd`.
+    fn prepare_command(&self, command: &str, cwd: &Path) -> Result<Command, String> {
+        info!(
+            "Executing command: '{}' in {:?} (sandbox: {})",
+            command,
+            cwd,
+            self.sandbox.mode.as_str()
+        );
 
         #[cfg(windows)]
         let command = format!("{}{}", POWERSHELL_UTF8_OUTPUT_PREFIX, command);
+        #[cfg(windows)]
+        let command = command.as_str();
 
-        let mut cmd = Command::new(shell);
-        for arg in args {
-            cmd.arg(arg);
-        }
+        let workspace = self.working_dir.as_deref().unwrap_or(cwd);
+        let mut cmd: Command = sandbox::build_command(&self.sandbox, workspace, cwd, command)
+            .map_err(|e| e.to_string())?;
+        cmd.current_dir(cwd);
+        Ok(cmd)
+    }
+
+    /// Execute the command and return output
+    async fn execute_command(&self, command: &str, cwd: &Path) -> Result<String, String> {
+        let mut cmd = self.prepare_command(command, cwd)?;
 
         let child = cmd
-            .arg(command)
-            .current_dir(cwd)
             .stdin(std::process::Stdio::null())
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped())
@@ -261,53 +331,65 @@ impl ExecTool {
             }
         };
 
-        let mut result_parts = Vec::new();
+        Ok(render_output(&output))
+    }
+}
+
+/// Combine stdout, stderr and a non-zero exit code into the text returned
+/// to the model.
+pub(crate) fn render_output(output: &std::process::Output) -> String {
+    let mut result_parts = Vec::new();
 
-        let stdout_s = decode_shell_pipe_bytes(&output.stdout);
-        let stderr_s = decode_shell_pipe_bytes(&output.stderr);
+    let stdout_s = decode_shell_pipe_bytes(&output.stdout);
+    let stderr_s = decode_shell_pipe_bytes(&output.stderr);
 
-        // Stdout - sanitize to remove control characters and ANSI sequences
-        if !output.stdout.is_empty() {
-            let stdout = sanitize_for_json(&stdout_s);
-            if !stdout.is_empty() {
-                result_parts.push(stdout);
-            }
+    // Stdout - sanitize to remove control characters and ANSI sequences
+    if !output.stdout.is_empty() {
+        let stdout = sanitize_for_json(&stdout_s);
+        if !stdout.is_empty() {
+            result_parts.push(stdout);
         }
+    }
 
-        // Stderr - sanitize to remove control characters and ANSI sequences
-        if !output.stderr.is_empty() {
-            let stderr = sanitize_for_json(&stderr_s);
-            if !stderr.trim().is_empty() {
-                result_parts.push(format!("STDERR:\n{}", stderr));
-            }
+    // Stderr - sanitize to remove control characters and ANSI sequences
+    if !output.stderr.is_empty() {
+        let stderr = sanitize_for_json(&stderr_s);
+        if !stderr.trim().is_empty() {
+            result_parts.push(format!("STDERR:\n{}", stderr));
         }
+    }
 
-        // Exit code
-        if !output.status.success() {
-            result_parts.push(format!(
-                "\nExit code: {}",
-                output.status.code().unwrap_or(-1)
-            ));
-        }
+    // Exit code
+    if !output.status.success() {
+        result_parts.push(format!(
+            "\nExit code: {}",
+            output.status.code().unwrap_or(-1)
+        ));
+    }
 
-        let mut result = if result_parts.is_empty() {
-            "(no output)".to_string()
-        } else {
-            result_parts.join("\n")
-        };
+    let result = if result_parts.is_empty() {
+        "(no output)".to_string()
+    } else {
+        result_parts.join("\n")
+    };
 
-        // Truncate very long output
-        const MAX_LEN: usize = 10000;
-        if result.len() > MAX_LEN {
-            let truncated = result.chars().take(MAX_LEN).collect::<String>();
-            result = format!(
-                "{}\n... (truncated, {} more chars)",
-                truncated,
-                result.len() - MAX_LEN
-            );
-        }
+    truncate_output(result)
+}
 
-        Ok(result)
+/// Maximum characters of command output returned to the model.
+pub(crate) const MAX_OUTPUT_LEN: usize = 10000;
+
+/// Truncate very long output
+pub(crate) fn truncate_output(result: String) -> String {
+    if result.len() > MAX_OUTPUT_LEN {
+        let truncated = result.chars().take(MAX_OUTPUT_LEN).collect::<String>();
+        format!(
+            "{}\n... (truncated, {} more chars)",
+            truncated,
+            result.len() - MAX_OUTPUT_LEN
+        )
+    } else {
+        result
     }
 }
 
@@ -373,6 +455,60 @@ mod tests {
         assert!(tool.guard_command("cat file.txt", &cwd).is_ok());
     }
 
+    /// Run known guard bypasses through a sandbox backend. Each must fail to write outside
+    /// the workspace; when the backend is not available on this host, the error must say so.
+    async fn assert_sandbox_blocks_bypasses(mode: agent_diva_core::config::ExecSandboxMode) {
+        let workspace = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        let tool = ExecTool::with_config(10, Some(workspace.path().to_path_buf()), false)
+            .with_sandbox(ExecSandboxConfig {
+                mode,
+                ..ExecSandboxConfig::default()
+            });
+
+        let probe = tool.execute(json!({ "command": "echo ok" })).await.unwrap();
+        if probe.contains("is unavailable") || probe.contains("only supported on Linux") {
+            assert!(probe.contains(mode.as_str()), "{}", probe);
+            return;
+        }
+        assert!(probe.contains("ok"), "{}", probe);
+
+        let target = outside.path().join("escaped");
+        let target = target.to_string_lossy();
+        let bypasses = [
+            format!("cd / && echo pwned > {}", target),
+            format!("P={}; echo pwned > \"$P\"", target),
+            format!(
+                "ln -s {} link && echo pwned > link/escaped",
+                outside.path().display()
+            ),
+        ];
+        for command in bypasses {
+            tool.execute(json!({ "command": command })).await.unwrap();
+            assert!(
+                !outside.path().join("escaped").exists(),
+                "sandbox allowed write outside workspace: {}",
+                command
+            );
+        }
+
+        let inside = tool
+            .execute(json!({ "command": "echo kept > inside.txt && cat inside.txt" }))
+            .await
+            .unwrap();
+        assert!(inside.contains("kept"), "{}", inside);
+    }
+
+    #[tokio::test]
+    async fn test_bwrap_sandbox_blocks_guard_bypasses() {
+        assert_sandbox_blocks_bypasses(agent_diva_core::config::ExecSandboxMode::Bwrap).await;
+    }
+
+    #[tokio::test]
+    async fn test_landlock_sandbox_blocks_guard_bypasses() {
+        assert_sandbox_blocks_bypasses(agent_diva_core::config::ExecSandboxMode::Landlock).await;
+    }
+
     #[test]
     fn test_sanitize_output_removes_ansi_colors() {
         // Red text: ESC[31mhello ESC[0m
@@ -0,0 +1,961 @@
+//! Background jobs and persistent shell sessions for the exec tool
+//!
+//! `exec` normally waits for a command with a fixed timeout. With `background=true` the
+//! command is started detached and tracked here under the caller's session key, so the
+//! agent can read incremental output, write to stdin, wait on it or kill it through the
+//! `exec_job` tool. `persistent=true` runs commands in one long-lived shell per session
+//! so `cd` and exported variables survive between calls.
+//!
+//! Everything owned by a session is killed by [`ShellJobManager::kill_session`] (session
+//! reset/delete) and by [`ShellJobManager::shutdown`] (gateway shutdown).
+
+use crate::sanitize::sanitize_for_json;
+use crate::shell::{decode_shell_pipe_bytes, truncate_output, MAX_OUTPUT_LEN};
+use agent_diva_tooling::{Tool, ToolError};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::sync::{oneshot, Mutex, Notify};
+use tracing::{debug, info, warn};
+
+/// Session key used when the caller does not provide one (e.g. direct CLI use).
+pub const DEFAULT_JOB_SESSION: &str = "default";
+
+/// Running background jobs allowed per session.
+pub const MAX_RUNNING_JOBS_PER_SESSION: usize = 8;
+
+/// Finished jobs kept per session for `exec_job list/output`.
+const MAX_RETAINED_JOBS_PER_SESSION: usize = 32;
+
+/// Output retained per job; the oldest output is discarded first.
+const MAX_JOB_BUFFER_CHARS: usize = 256 * 1024;
+
+/// Grace period for pipe readers after a job exits or is killed.
+const READER_DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Finished jobs are dropped this long after they exit, whoever owns them.
+const FINISHED_JOB_TTL: Duration = Duration::from_secs(30 * 60);
+
+/// Cap for `exec_job` waits given the tool call timeout, leaving a margin so the
+/// wait returns before the call itself times out.
+pub fn job_wait_cap_secs(exec_timeout_secs: u64) -> u64 {
+    let margin = (exec_timeout_secs / 4).clamp(1, 5);
+    exec_timeout_secs.saturating_sub(margin).max(1)
+}
+
+/// Lifecycle state of a background job.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JobState {
+    Running,
+    Exited(i32),
+    Killed,
+    Failed(String),
+}
+
+impl fmt::Display for JobState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Running => write!(f, "running"),
+            Self::Exited(code) => write!(f, "exited with code {}", code),
+            Self::Killed => write!(f, "killed"),
+            Self::Failed(err) => write!(f, "failed: {}", err),
+        }
+    }
+}
+
+/// Snapshot of a background job.
+#[derive(Debug, Clone)]
+pub struct JobSummary {
+    pub id: String,
+    pub command: String,
+    pub state: JobState,
+    pub elapsed: Duration,
+}
+
+#[derive(Default)]
+struct JobOutput {
+    text: String,
+    read_pos: usize,
+    discarded: usize,
+}
+
+struct ShellJob {
+    id: String,
+    session_key: String,
+    command: String,
+    started_at: Instant,
+    finished_at: StdMutex<Option<Instant>>,
+    output: StdMutex<JobOutput>,
+    state: StdMutex<JobState>,
+    stdin: Mutex<Option<ChildStdin>>,
+    kill_tx: StdMutex<Option<oneshot::Sender<()>>>,
+    finished: Notify,
+}
+
+impl ShellJob {
+    fn state(&self) -> JobState {
+        self.state.lock().expect("job state lock").clone()
+    }
+
+    fn append_output(&self, chunk: &str) {
+        let mut output = self.output.lock().expect("job output lock");
+        output.text.push_str(chunk);
+        if output.text.len() > MAX_JOB_BUFFER_CHARS {
+            let mut cut = output.text.len() - MAX_JOB_BUFFER_CHARS;
+            while !output.text.is_char_boundary(cut) {
+                cut += 1;
+            }
+            output.text.drain(..cut);
+            output.read_pos = output.read_pos.saturating_sub(cut);
+            output.discarded += cut;
+        }
+    }
+
+    /// Return output produced since the previous read.
+    fn take_new_output(&self) -> String {
+        let mut output = self.output.lock().expect("job output lock");
+        let new = output.text[output.read_pos..].to_string();
+        output.read_pos = output.text.len();
+        new
+    }
+
+    fn summary(&self) -> JobSummary {
+        JobSummary {
+            id: self.id.clone(),
+            command: self.command.clone(),
+            state: self.state(),
+            elapsed: self.started_at.elapsed(),
+        }
+    }
+
+    fn is_expired(&self) -> bool {
+        self.finished_at
+            .lock()
+            .expect("job finished lock")
+            .is_some_and(|at| at.elapsed() >= FINISHED_JOB_TTL)
+    }
+
+    fn request_kill(&self) {
+        if let Some(tx) = self.kill_tx.lock().expect("job kill lock").take() {
+            let _ = tx.send(());
+        }
+    }
+
+    /// Wait until the job leaves the running state, up to `limit`.
+    async fn wait_finished(&self, limit: Duration) -> JobState {
+        let notified = self.finished.notified();
+        tokio::pin!(notified);
+        notified.as_mut().enable();
+        if self.state() != JobState::Running {
+            return self.state();
+        }
+        let _ = tokio::time::timeout(limit, notified).await;
+        self.state()
+    }
+}
+
+struct PersistentShell {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+/// Registry of background jobs and persistent shells, keyed by session.
+pub struct ShellJobManager {
+    jobs: Mutex<Vec<Arc<ShellJob>>>,
+    shells: Mutex<HashMap<String, Arc<Mutex<PersistentShell>>>>,
+    next_id: AtomicU64,
+}
+
+impl Default for ShellJobManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ShellJobManager {
+    pub fn new() -> Self {
+        Self {
+            jobs: Mutex::new(Vec::new()),
+            shells: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Start `cmd` detached and return a message containing its job ID.
+    pub async fn start(
+        &self,
+        session_key: &str,
+        command: &str,
+        mut cmd: Command,
+    ) -> Result<String, String> {
+        let mut jobs = self.jobs.lock().await;
+        reap_expired_jobs(&mut jobs);
+        let running = jobs
+            .iter()
+            .filter(|job| job.session_key == session_key && job.state() == JobState::Running)
+            .count();
+        if running >= MAX_RUNNING_JOBS_PER_SESSION {
+            return Err(format!(
+                "too many running background jobs ({}); kill one with exec_job first",
+                running
+            ));
+        }
+
+        cmd.stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .kill_on_drop(true);
+        #[cfg(unix)]
+        cmd.process_group(0);
+        let mut child = cmd
+            .spawn()
+            .map_err(|e| format!("Failed to spawn process: {}", e))?;
+
+        let id = format!("job-{}", self.next_id.fetch_add(1, Ordering::Relaxed));
+        let pid = child.id();
+        let (kill_tx, kill_rx) = oneshot::channel();
+        let job = Arc::new(ShellJob {
+            id: id.clone(),
+            session_key: session_key.to_string(),
+            command: command.to_string(),
+            started_at: Instant::now(),
+            finished_at: StdMutex::new(None),
+            output: StdMutex::new(JobOutput::default()),
+            state: StdMutex::new(JobState::Running),
+            stdin: Mutex::new(child.stdin.take()),
+            kill_tx: StdMutex::new(Some(kill_tx)),
+            finished: Notify::new(),
+        });
+
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+        tokio::spawn(supervise_job(job.clone(), child, stdout, stderr, kill_rx));
+
+        jobs.push(job);
+        evict_finished_jobs(&mut jobs, session_key);
+        info!(
+            "Started background job {} (pid {:?}) for session {}: {}",
+            id, pid, session_key, command
+        );
+
+        Ok(format!(
+            "Started background job {} (pid {}). Use exec_job with action \"output\" to read \
+             its output, \"wait\" to wait for it, or \"kill\" to stop it.",
+            id,
+            pid.map(|p| p.to_string())
+                .unwrap_or_else(|| "unknown".to_string())
+        ))
+    }
+
+    async fn find(&self, session_key: &str, job_id: &str) -> Result<Arc<ShellJob>, String> {
+        let mut jobs = self.jobs.lock().await;
+        reap_expired_jobs(&mut jobs);
+        jobs.iter()
+            .find(|job| job.session_key == session_key && job.id == job_id)
+            .cloned()
+            .ok_or_else(|| format!("background job {} not found", job_id))
+    }
+
+    /// List jobs owned by a session.
+    pub async fn list(&self, session_key: &str) -> Vec<JobSummary> {
+        let mut jobs = self.jobs.lock().await;
+        reap_expired_jobs(&mut jobs);
+        jobs.iter()
+            .filter(|job| job.session_key == session_key)
+            .map(|job| job.summary())
+            .collect()
+    }
+
+    /// Read output produced since the previous read, prefixed with the job state.
+    pub async fn read_output(&self, session_key: &str, job_id: &str) -> Result<String, String> {
+        let job = self.find(session_key, job_id).await?;
+        Ok(format_job_output(&job))
+    }
+
+    /// Wait for a job to finish (bounded by `limit`) and return its new output.
+    pub async fn wait(
+        &self,
+        session_key: &str,
+        job_id: &str,
+        limit: Duration,
+    ) -> Result<String, String> {
+        let job = self.find(session_key, job_id).await?;
+        job.wait_finished(limit).await;
+        Ok(format_job_output(&job))
+    }
+
+    /// Write to a job's stdin, optionally closing it afterwards.
+    pub async fn write_stdin(
+        &self,
+        session_key: &str,
+        job_id: &str,
+        input: &str,
+        close: bool,
+    ) -> Result<String, String> {
+        let job = self.find(session_key, job_id).await?;
+        let mut stdin = job.stdin.lock().await;
+        let Some(pipe) = stdin.as_mut() else {
+            return Err(format!("stdin of {} is closed", job_id));
+        };
+        if !input.is_empty() {
+            pipe.write_all(input.as_bytes())
+                .await
+                .map_err(|e| format!("failed to write to {}: {}", job_id, e))?;
+            pipe.flush()
+                .await
+                .map_err(|e| format!("failed to write to {}: {}", job_id, e))?;
+        }
+        if close {
+            stdin.take();
+        }
+        Ok(format!(
+            "Wrote {} bytes to {}{}",
+            input.len(),
+            job_id,
+            if close { " and closed stdin" } else { "" }
+        ))
+    }
+
+    /// Kill a job and wait briefly for it to exit.
+    pub async fn kill(&self, session_key: &str, job_id: &str) -> Result<String, String> {
+        let job = self.find(session_key, job_id).await?;
+        job.request_kill();
+        let state = job.wait_finished(Duration::from_secs(5)).await;
+        Ok(format!("Job {} {}", job_id, state))
+    }
+
+    /// Run `command` in the session's persistent shell. `spawn_shell` builds the shell
+    /// process when the session has none yet (or the previous one exited).
+    pub async fn run_persistent<F>(
+        &self,
+        session_key: &str,
+        command: &str,
+        limit: Duration,
+        spawn_shell: F,
+    ) -> Result<String, String>
+    where
+        F: FnOnce() -> Result<Command, String>,
+    {
+        if cfg!(windows) {
+            return Err("persistent shell sessions are not supported on Windows".to_string());
+        }
+
+        let shell = {
+            let mut shells = self.shells.lock().await;
+            match shells.get(session_key) {
+                Some(shell) => shell.clone(),
+                None => {
+                    let shell = Arc::new(Mutex::new(spawn_persistent_shell(spawn_shell()?)?));
+                    shells.insert(session_key.to_string(), shell.clone());
+                    info!("Started persistent shell for session {}", session_key);
+                    shell
+                }
+            }
+        };
+
+        let marker = format!(
+            "__AGENT_DIVA_DONE_{}__",
+            self.next_id.fetch_add(1, Ordering::Relaxed)
+        );
+        let mut shell = shell.lock().await;
+        let outcome = tokio::time::timeout(limit, run_in_shell(&mut shell, command, &marker)).await;
+        match outcome {
+            Ok(Ok((output, Some(code)))) => Ok(format_shell_result(output, code)),
+            Ok(Ok((output, None))) => {
+                drop(shell);
+                self.drop_shell(session_key).await;
+                Ok(format!(
+                    "{}\n(persistent shell exited; a new one will be started on the next call)",
+                    format_shell_result(output, 0)
+                ))
+            }
+            Ok(Err(err)) => {
+                drop(shell);
+                self.drop_shell(session_key).await;
+                Err(err)
+            }
+            Err(_) => {
+                drop(shell);
+                self.drop_shell(session_key).await;
+                Err(format!(
+                    "Command timed out after {} seconds; the persistent shell was restarted",
+                    limit.as_secs()
+                ))
+            }
+        }
+    }
+
+    async fn drop_shell(&self, session_key: &str) {
+        let shell = self.shells.lock().await.remove(session_key);
+        if let Some(shell) = shell {
+            let mut shell = shell.lock().await;
+            kill_process_tree(&mut shell.child).await;
+        }
+    }
+
+    /// Kill every job and the persistent shell owned by a session.
+    pub async fn kill_session(&self, session_key: &str) {
+        let owned: Vec<Arc<ShellJob>> = {
+            let mut jobs = self.jobs.lock().await;
+            let (owned, rest) = jobs
+                .drain(..)
+                .partition(|job| job.session_key == session_key);
+            *jobs = rest;
+            owned
+        };
+        for job in &owned {
+            job.request_kill();
+        }
+        for job in &owned {
+            job.wait_finished(Duration::from_secs(5)).await;
+        }
+        self.drop_shell(session_key).await;
+        if !owned.is_empty() {
+            info!(
+                "Cleaned up {} background job(s) for session {}",
+                owned.len(),
+                session_key
+            );
+        }
+    }
+
+    /// Kill all jobs and persistent shells (gateway shutdown).
+    pub async fn shutdown(&self) {
+        let mut sessions: Vec<String> = self
+            .jobs
+            .lock()
+            .await
+            .iter()
+            .map(|job| job.session_key.clone())
+            .collect();
+        sessions.extend(self.shells.lock().await.keys().cloned());
+        sessions.sort();
+        sessions.dedup();
+        for session_key in sessions {
+            self.kill_session(&session_key).await;
+        }
+    }
+}
+
+/// Drop jobs of every session that finished more than [`FINISHED_JOB_TTL`] ago.
+/// Jobs started outside a turn share [`DEFAULT_JOB_SESSION`] and would
+/// otherwise never be cleaned up.
+fn reap_expired_jobs(jobs: &mut Vec<Arc<ShellJob>>) {
+    jobs.retain(|job| !job.is_expired());
+}
+
+fn evict_finished_jobs(jobs: &mut Vec<Arc<ShellJob>>, session_key: &str) {
+    let owned = jobs
+        .iter()
+        .filter(|job| job.session_key == session_key)
+        .count();
+    let mut excess = owned.saturating_sub(MAX_RETAINED_JOBS_PER_SESSION);
+    jobs.retain(|job| {
+        if excess > 0 && job.session_key == session_key && job.state() != JobState::Running {
+            excess -= 1;
+            false
+        } else {
+            true
+        }
+    });
+}
+
+async fn supervise_job(
+    job: Arc<ShellJob>,
+    mut child: Child,
+    stdout: Option<impl AsyncRead + Unpin + Send + 'static>,
+    stderr: Option<impl AsyncRead + Unpin + Send + 'static>,
+    kill_rx: oneshot::Receiver<()>,
+) {
+    let readers: Vec<_> = [
+        stdout.map(|r| spawn_reader(job.clone(), r)),
+        stderr.map(|r| spawn_reader(job.clone(), r)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    let state = tokio::select! {
+        status = child.wait() => match status {
+            Ok(status) => JobState::Exited(status.code().unwrap_or(-1)),
+            Err(e) => JobState::Failed(e.to_string()),
+        },
+        _ = kill_rx => {
+            kill_process_tree(&mut child).await;
+            JobState::Killed
+        }
+    };
+
+    for reader in readers {
+        let abort = reader.abort_handle();
+        if tokio::time::timeout(READER_DRAIN_TIMEOUT, reader)
+            .await
+            .is_err()
+        {
+            abort.abort();
+        }
+    }
+
+    debug!("Background job {} {}", job.id, state);
+    *job.state.lock().expect("job state lock") = state;
+    *job.finished_at.lock().expect("job finished lock") = Some(Instant::now());
+    job.stdin.lock().await.take();
+    job.finished.notify_waiters();
+}
+
+fn spawn_reader<R>(job: Arc<ShellJob>, mut reader: R) -> tokio::task::JoinHandle<()>
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut buf = vec![0u8; 8192];
+        let mut pending: Vec<u8> = Vec::new();
+        loop {
+            match reader.read(&mut buf).await {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    pending.extend_from_slice(&buf[..n]);
+                    // Keep an incomplete trailing UTF-8 sequence for the next chunk.
+                    let complete = match std::str::from_utf8(&pending) {
+                        Ok(_) => pending.len(),
+                        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+                        Err(_) => pending.len(),
+                    };
+                    let chunk: Vec<u8> = pending.drain(..complete).collect();
+                    job.append_output(&sanitize_for_json(&decode_shell_pipe_bytes(&chunk)));
+                }
+            }
+        }
+        if !pending.is_empty() {
+            job.append_output(&sanitize_for_json(&decode_shell_pipe_bytes(&pending)));
+        }
+    })
+}
+
+/// Kill the child and, on Unix, its whole process group.
+async fn kill_process_tree(child: &mut Child) {
+    #[cfg(unix)]
+    if let Some(pid) = child.id() {
+        // SAFETY: signalling a process group we created has no memory-safety concerns.
+        unsafe {
+            libc::kill(-(pid as i32), libc::SIGKILL);
+        }
+    }
+    if let Err(e) = child.kill().await {
+        debug!("kill after group signal: {}", e);
+    }
+}
+
+fn format_job_output(job: &ShellJob) -> String {
+    let state = job.state();
+    let discarded = job.output.lock().expect("job output lock").discarded;
+    let output = job.take_new_output();
+    let mut result = format!("[{} {}]", job.id, state);
+    if discarded > 0 {
+        result.push_str(&format!(" ({} chars of older output discarded)", discarded));
+    }
+    result.push('\n');
+    if output.is_empty() {
+        result.push_str("(no new output)");
+    } else {
+        result.push_str(&output);
+    }
+    truncate_output(result)
+}
+
+fn spawn_persistent_shell(mut cmd: Command) -> Result<PersistentShell, String> {
+    cmd.stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::null())
+        .kill_on_drop(true);
+    #[cfg(unix)]
+    cmd.process_group(0);
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to spawn persistent shell: {}", e))?;
+    let stdin = child.stdin.take().ok_or("persistent shell has no stdin")?;
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or("persistent shell has no stdout")?;
+    Ok(PersistentShell {
+        child,
+        stdin,
+        stdout: BufReader::new(stdout),
+    })
+}
+
+/// Send one command and read until the completion marker. Returns the output and the
+/// exit code, or `None` when the shell itself exited (e.g. `exit` or a syntax error).
+async fn run_in_shell(
+    shell: &mut PersistentShell,
+    command: &str,
+    marker: &str,
+) -> Result<(String, Option<i32>), String> {
+    // Commands read stdin from /dev/null so they cannot swallow the marker line.
+    let script = format!(
+        "{{\n{}\n}} </dev/null\nprintf '\\n{} %s\\n' \"$?\"\n",
+        command, marker
+    );
+    shell
+        .stdin
+        .write_all(script.as_bytes())
+        .await
+        .map_err(|e| format!("failed to write to persistent shell: {}", e))?;
+    shell
+        .stdin
+        .flush()
+        .await
+        .map_err(|e| format!("failed to write to persistent shell: {}", e))?;
+
+    let mut output = String::new();
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        let read = shell
+            .stdout
+            .read_until(b'\n', &mut line)
+            .await
+            .map_err(|e| format!("failed to read from persistent shell: {}", e))?;
+        if read == 0 {
+            return Ok((output, None));
+        }
+        let text = decode_shell_pipe_bytes(&line);
+        if let Some(code) = text.trim_end().strip_prefix(marker) {
+            // Drop the newline printf emitted before the marker.
+            if output.ends_with('\n') {
+                output.pop();
+            }
+            let code = code.trim().parse().unwrap_or(-1);
+            return Ok((output, Some(code)));
+        }
+        if output.len() < MAX_OUTPUT_LEN * 4 {
+            output.push_str(&text);
+        } else {
+            warn!("Persistent shell output exceeded buffer; discarding");
+        }
+    }
+}
+
+fn format_shell_result(output: String, code: i32) -> String {
+    let output = sanitize_for_json(&output);
+    let mut result = if output.trim().is_empty() {
+        "(no output)".to_string()
+    } else {
+        output
+    };
+    if code != 0 {
+        result.push_str(&format!("\n\nExit code: {}", code));
+    }
+    result
+}
+
+/// Tool for managing background jobs started with `exec(background=true)`.
+pub struct ExecJobTool {
+    jobs: Arc<ShellJobManager>,
+    max_wait: Duration,
+}
+
+impl ExecJobTool {
+    /// `max_wait_secs` bounds the `wait` action; keep it below the tool call timeout.
+    pub fn new(jobs: Arc<ShellJobManager>, max_wait_secs: u64) -> Self {
+        Self {
+            jobs,
+            max_wait: Duration::from_secs(max_wait_secs.max(1)),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for ExecJobTool {
+    fn name(&self) -> &str {
+        "exec_job"
+    }
+
+    fn description(&self) -> &str {
+        "Manage background jobs started with exec(background=true): list jobs, read new \
+         output, wait for completion, write to stdin, or kill a job."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["list", "output", "wait", "write", "kill"],
+                    "description": "Action to perform"
+                },
+                "job_id": {
+                    "type": "string",
+                    "description": "Job ID returned by exec (required except for list)"
+                },
+                "input": {
+                    "type": "string",
+                    "description": "Text to write to the job's stdin (for write); include a trailing newline to submit a line"
+                },
+                "close_stdin": {
+                    "type": "boolean",
+                    "description": "Close the job's stdin after writing (for write)"
+                },
+                "timeout_secs": {
+                    "type": "integer",
+                    "description": "Maximum seconds to wait (for wait)"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<String, ToolError> {
+        let action = params
+            .get("action")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidParams("Missing 'action' parameter".to_string()))?;
+        let session_key = params
+            .get("context_session_key")
+            .and_then(|v| v.as_str())
+            .unwrap_or(DEFAULT_JOB_SESSION);
+
+        if action == "list" {
+            let jobs = self.jobs.list(session_key).await;
+            if jobs.is_empty() {
+                return Ok("No background jobs.".to_string());
+            }
+            let lines: Vec<String> = jobs
+                .iter()
+                .map(|job| {
+                    format!(
+                        "- {} [{}, {}s] {}",
+                        job.id,
+                        job.state,
+                        job.elapsed.as_secs(),
+                        job.command
+                    )
+                })
+                .collect();
+            return Ok(format!("Background jobs:\n{}", lines.join("\n")));
+        }
+
+        let job_id = params
+            .get("job_id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| {
+                ToolError::InvalidParams(format!("'job_id' is required for action '{}'", action))
+            })?;
+
+        let result = match action {
+            "output" => self.jobs.read_output(session_key, job_id).await,
+            "wait" => {
+                let limit = params
+                    .get("timeout_secs")
+                    .and_then(|v| v.as_u64())
+                    .map(Duration::from_secs)
+                    .unwrap_or(self.max_wait)
+                    .min(self.max_wait);
+                self.jobs.wait(session_key, job_id, limit).await
+            }
+            "write" => {
+                let input = params.get("input").and_then(|v| v.as_str()).unwrap_or("");
+                let close = params
+                    .get("close_stdin")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+                self.jobs
+                    .write_stdin(session_key, job_id, input, close)
+                    .await
+            }
+            "kill" => self.jobs.kill(session_key, job_id).await,
+            other => {
+                return Err(ToolError::InvalidParams(format!(
+                    "Unknown action '{}'",
+                    other
+                )))
+            }
+        };
+
+        Ok(result.unwrap_or_else(|err| format!("Error: {}", err)))
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::shell::ExecTool;
+
+    fn tools() -> (
+        ExecTool,
+        ExecJobTool,
+        Arc<ShellJobManager>,
+        tempfile::TempDir,
+    ) {
+        let workspace = tempfile::tempdir().unwrap();
+        let jobs = Arc::new(ShellJobManager::new());
+        let exec = ExecTool::with_config(10, Some(workspace.path().to_path_buf()), false)
+            .with_jobs(jobs.clone());
+        let exec_job = ExecJobTool::new(jobs.clone(), 5);
+        (exec, exec_job, jobs, workspace)
+    }
+
+    fn job_id(started: &str) -> String {
+        started
+            .split_whitespace()
+            .find(|word| word.starts_with("job-"))
+            .unwrap()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_background_job_output_is_incremental() {
+        let (exec, exec_job, _jobs, _ws) = tools();
+        let started = exec
+            .execute(json!({ "command": "echo first; sleep 0.3; echo second", "background": true }))
+            .await
+            .unwrap();
+        assert!(started.contains("Started background job"), "{}", started);
+        let id = job_id(&started);
+
+        let waited = exec_job
+            .execute(json!({ "action": "wait", "job_id": id }))
+            .await
+            .unwrap();
+        assert!(waited.contains("exited with code 0"), "{}", waited);
+        assert!(waited.contains("first") && waited.contains("second"));
+
+        let again = exec_job
+            .execute(json!({ "action": "output", "job_id": id }))
+            .await
+            .unwrap();
+        assert!(again.contains("(no new output)"), "{}", again);
+    }
+
+    #[tokio::test]
+    async fn test_background_job_stdin_and_kill() {
+        let (exec, exec_job, _jobs, _ws) = tools();
+        let started = exec
+            .execute(json!({ "command": "while read line; do echo got:$line; done", "background": true }))
+            .await
+            .unwrap();
+        let id = job_id(&started);
+
+        exec_job
+            .execute(json!({ "action": "write", "job_id": id, "input": "ping\n" }))
+            .await
+            .unwrap();
+        let mut output = String::new();
+        for _ in 0..50 {
+            output.push_str(
+                &exec_job
+                    .execute(json!({ "action": "output", "job_id": id }))
+                    .await
+                    .unwrap(),
+            );
+            if output.contains("got:ping") {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(output.contains("got:ping"), "{}", output);
+
+        let killed = exec_job
+            .execute(json!({ "action": "kill", "job_id": id }))
+            .await
+            .unwrap();
+        assert!(killed.contains("killed"), "{}", killed);
+    }
+
+    #[tokio::test]
+    async fn test_jobs_are_scoped_and_cleaned_up_per_session() {
+        let (exec, exec_job, jobs, _ws) = tools();
+        let started = exec
+            .execute(json!({
+                "command": "sleep 30",
+                "background": true,
+                "context_session_key": "cli:a"
+            }))
+            .await
+            .unwrap();
+        let id = job_id(&started);
+
+        let other = exec_job
+            .execute(json!({ "action": "output", "job_id": id, "context_session_key": "cli:b" }))
+            .await
+            .unwrap();
+        assert!(other.contains("not found"), "{}", other);
+
+        jobs.kill_session("cli:a").await;
+        assert!(jobs.list("cli:a").await.is_empty());
+    }
+
+    #[test]
+    fn test_job_wait_cap_leaves_margin_for_short_timeouts() {
+        assert_eq!(job_wait_cap_secs(60), 55);
+        assert_eq!(job_wait_cap_secs(5), 4);
+        assert_eq!(job_wait_cap_secs(2), 1);
+        assert_eq!(job_wait_cap_secs(0), 1);
+    }
+
+    #[tokio::test]
+    async fn test_finished_jobs_are_reaped_after_ttl() {
+        let (exec, _exec_job, jobs, _ws) = tools();
+        let started = exec
+            .execute(json!({ "command": "true", "background": true }))
+            .await
+            .unwrap();
+        let id = job_id(&started);
+        jobs.wait(DEFAULT_JOB_SESSION, &id, Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(jobs.list(DEFAULT_JOB_SESSION).await.len(), 1);
+
+        for job in jobs.jobs.lock().await.iter() {
+            *job.finished_at.lock().unwrap() = Instant::now().checked_sub(FINISHED_JOB_TTL);
+        }
+        assert!(jobs.list(DEFAULT_JOB_SESSION).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_persistent_shell_keeps_cwd_and_env() {
+        let (exec, _exec_job, jobs, ws) = tools();
+        std::fs::create_dir(ws.path().join("sub")).unwrap();
+        exec.execute(json!({ "command": "cd sub && export GREETING=hi", "persistent": true }))
+            .await
+            .unwrap();
+        let result = exec
+            .execute(json!({ "command": "echo $GREETING; pwd", "persistent": true }))
+            .await
+            .unwrap();
+        assert!(result.contains("hi"), "{}", result);
+        assert!(result.trim_end().ends_with("sub"), "{}", result);
+
+        let failed = exec
+            .execute(json!({ "command": "false", "persistent": true }))
+            .await
+            .unwrap();
+        assert!(failed.contains("Exit code: 1"), "{}", failed);
+
+        jobs.shutdown().await;
+        let fresh = exec
+            .execute(json!({ "command": "echo ${GREETING:-unset}", "persistent": true }))
+            .await
+            .unwrap();
+        assert!(fresh.contains("unset"), "{}", fresh);
+    }
+
+    #[tokio::test]
+    async fn test_background_requires_job_manager() {
+        let exec = ExecTool::new();
+        let result = exec
+            .execute(json!({ "command": "echo hi", "background": true }))
+            .await
+            .unwrap();
+        assert!(result.contains("not enabled"), "{}", result);
+    }
+}
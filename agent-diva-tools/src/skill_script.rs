@@ -0,0 +1,368 @@
+//! Tools declared by skills
+//!
+//! A skill that ships helper scripts can expose them as tools through the
+//! runtime metadata in its SKILL.md frontmatter:
+//!
+//! ```text
+//! metadata: '{"nanobot":{"tools":[{"name":"pdf_extract","description":"Extract text from a PDF",
+//!   "parameters":{"type":"object","properties":{"path":{"type":"string"}},"required":["path"]},
+//!   "entry":"scripts/extract.py","interpreter":"python3"}]}}'
+//! ```
+//!
+//! The script runs in the skill directory under the `exec` sandbox and timeout. Arguments
+//! arrive as JSON on stdin and in the `SKILL_TOOL_ARGS` environment variable.
+
+use crate::sandbox::{self, ExecSandboxConfig};
+use crate::shell::render_output;
+use agent_diva_tooling::{Tool, ToolError};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::time::timeout;
+use tracing::{debug, info};
+
+/// A tool declared in skill frontmatter
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SkillToolSpec {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// JSON Schema of the arguments
+    #[serde(default = "default_parameters")]
+    pub parameters: Value,
+    /// Script path relative to the skill directory
+    pub entry: String,
+    /// Program that runs the script, e.g. `python3`; the script is executed
+    /// directly when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interpreter: Option<String>,
+}
+
+fn default_parameters() -> Value {
+    json!({ "type": "object", "properties": {} })
+}
+
+impl SkillToolSpec {
+    pub fn validate(&self) -> Result<(), String> {
+        let name_ok = !self.name.is_empty()
+            && self.name.len() <= 64
+            && self
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        if !name_ok {
+            return Err(format!(
+                "invalid tool name '{}': use 1-64 letters, digits, '_' or '-'",
+                self.name
+            ));
+        }
+        let entry = Path::new(&self.entry);
+        let relative = !self.entry.trim().is_empty()
+            && entry
+                .components()
+                .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+        if !relative {
+            return Err(format!(
+                "tool '{}' entry '{}' must be a path inside the skill directory",
+                self.name, self.entry
+            ));
+        }
+        if !self.parameters.is_object() {
+            return Err(format!(
+                "tool '{}' parameters must be a JSON Schema object",
+                self.name
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Runs a script bundled with a skill
+pub struct SkillScriptTool {
+    skill: String,
+    skill_dir: PathBuf,
+    spec: SkillToolSpec,
+    description: String,
+    workspace: PathBuf,
+    timeout_secs: u64,
+    sandbox: ExecSandboxConfig,
+}
+
+impl SkillScriptTool {
+    pub fn new(
+        skill: impl Into<String>,
+        skill_dir: PathBuf,
+        spec: SkillToolSpec,
+        workspace: PathBuf,
+    ) -> Self {
+        let skill = skill.into();
+        let description = if spec.description.trim().is_empty() {
+            format!("Run {} from the '{}' skill", spec.entry, skill)
+        } else {
+            format!("{} (skill: {})", spec.description.trim(), skill)
+        };
+        Self {
+            skill,
+            skill_dir,
+            spec,
+            description,
+            workspace,
+            timeout_secs: 60,
+            sandbox: ExecSandboxConfig::default(),
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout_secs: u64) -> Self {
+        self.timeout_secs = timeout_secs;
+        self
+    }
+
+    /// Run through a sandbox backend. The workspace stays writable for skills
+    /// installed inside it; other skills may only write to their own directory.
+    pub fn with_sandbox(mut self, sandbox: ExecSandboxConfig) -> Self {
+        self.sandbox = sandbox;
+        self
+    }
+
+    /// The interpreter's words followed by the script, executed without a shell
+    /// so frontmatter values cannot inject commands.
+    fn argv(&self, script: &Path) -> Vec<String> {
+        let mut argv: Vec<String> = self
+            .spec
+            .interpreter
+            .as_deref()
+            .unwrap_or_default()
+            .split_whitespace()
+            .map(str::to_string)
+            .collect();
+        argv.push(script.display().to_string());
+        argv
+    }
+
+    fn writable_root(&self) -> &Path {
+        let workspace = self
+            .workspace
+            .canonicalize()
+            .unwrap_or_else(|_| self.workspace.clone());
+        let skill_dir = self
+            .skill_dir
+            .canonicalize()
+            .unwrap_or_else(|_| self.skill_dir.clone());
+        if skill_dir.starts_with(&workspace) {
+            &self.workspace
+        } else {
+            &self.skill_dir
+        }
+    }
+}
+
+/// Quote `value` as a PowerShell string literal
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+#[async_trait]
+impl Tool for SkillScriptTool {
+    fn name(&self) -> &str {
+        &self.spec.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn parameters(&self) -> Value {
+        self.spec.parameters.clone()
+    }
+
+    fn scope(&self) -> Option<&str> {
+        Some(&self.skill)
+    }
+
+    async fn execute(&self, args: Value) -> Result<String, ToolError> {
+        let script = self.skill_dir.join(&self.spec.entry);
+        if !script.is_file() {
+            return Ok(format!(
+                "Error: script {} of skill '{}' is missing",
+                self.spec.entry, self.skill
+            ));
+        }
+
+        let argv = self.argv(&script);
+        info!(
+            "Running skill tool '{}' of '{}': {:?} (sandbox: {})",
+            self.spec.name,
+            self.skill,
+            argv,
+            self.sandbox.mode.as_str()
+        );
+        let built = if argv.len() == 1 && cfg!(target_os = "windows") {
+            // Without an interpreter, PowerShell runs the script with its associated program.
+            let command = format!("& {}", quote(&argv[0]));
+            sandbox::build_command(
+                &self.sandbox,
+                self.writable_root(),
+                &self.skill_dir,
+                &command,
+            )
+        } else {
+            sandbox::build_program_command(
+                &self.sandbox,
+                self.writable_root(),
+                &self.skill_dir,
+                &argv,
+            )
+        };
+        let mut cmd = match built {
+            Ok(cmd) => cmd,
+            Err(err) => return Ok(format!("Error: {}", err)),
+        };
+        let args = args.to_string();
+        cmd.current_dir(&self.skill_dir)
+            .env("SKILL_DIR", &self.skill_dir)
+            .env("SKILL_TOOL_ARGS", &args)
+            .env("AGENT_DIVA_WORKSPACE", &self.workspace)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .kill_on_drop(true);
+
+        let mut child = match cmd.spawn() {
+            Ok(child) => child,
+            Err(err) => return Ok(format!("Error: failed to start skill tool: {}", err)),
+        };
+        debug!("Skill tool spawned with PID: {:?}", child.id());
+        if let Some(mut stdin) = child.stdin.take() {
+            // Written alongside the wait so a script that never reads a full
+            // pipe still times out; scripts may also exit before reading it.
+            tokio::spawn(async move {
+                let _ = stdin.write_all(args.as_bytes()).await;
+            });
+        }
+
+        match timeout(
+            Duration::from_secs(self.timeout_secs),
+            child.wait_with_output(),
+        )
+        .await
+        {
+            Ok(Ok(output)) => Ok(render_output(&output)),
+            Ok(Err(err)) => Ok(format!("Error: failed to wait for skill tool: {}", err)),
+            Err(_) => Ok(format!(
+                "Error: skill tool timed out after {} seconds",
+                self.timeout_secs
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn spec(entry: &str, interpreter: Option<&str>) -> SkillToolSpec {
+        SkillToolSpec {
+            name: "echo_args".to_string(),
+            description: "Echo arguments".to_string(),
+            parameters: json!({"type": "object", "properties": {"text": {"type": "string"}}}),
+            entry: entry.to_string(),
+            interpreter: interpreter.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn validate_rejects_bad_names_and_entries() {
+        assert!(spec("scripts/run.sh", None).validate().is_ok());
+        assert!(spec("../run.sh", None).validate().is_err());
+        assert!(spec("/bin/sh", None).validate().is_err());
+        let mut bad = spec("run.sh", None);
+        bad.name = "has space".to_string();
+        assert!(bad.validate().is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn runs_script_in_skill_dir_with_args_on_stdin() {
+        let workspace = TempDir::new().unwrap();
+        let skill_dir = workspace.path().join("skills").join("echo");
+        std::fs::create_dir_all(skill_dir.join("scripts")).unwrap();
+        std::fs::write(
+            skill_dir.join("scripts/run.sh"),
+            "pwd\ncat\necho\necho \"env:$SKILL_TOOL_ARGS\"\n",
+        )
+        .unwrap();
+
+        let tool = SkillScriptTool::new(
+            "echo",
+            skill_dir.clone(),
+            spec("scripts/run.sh", Some("sh")),
+            workspace.path().to_path_buf(),
+        );
+        assert_eq!(tool.scope(), Some("echo"));
+        assert!(tool.description().ends_with("(skill: echo)"));
+
+        let output = tool.execute(json!({"text": "hi"})).await.unwrap();
+        let skill_dir = skill_dir.canonicalize().unwrap();
+        assert!(output.contains(&skill_dir.display().to_string()));
+        assert!(output.contains(r#"{"text":"hi"}"#));
+        assert!(output.contains(r#"env:{"text":"hi"}"#));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn interpreter_is_not_run_through_a_shell() {
+        let workspace = TempDir::new().unwrap();
+        std::fs::write(workspace.path().join("run.sh"), "echo ran\n").unwrap();
+        let marker = workspace.path().join("injected");
+
+        let tool = SkillScriptTool::new(
+            "echo",
+            workspace.path().to_path_buf(),
+            spec("run.sh", Some(&format!("sh; touch {} #", marker.display()))),
+            workspace.path().to_path_buf(),
+        );
+        let argv = tool.argv(&workspace.path().join("run.sh"));
+        assert_eq!(argv[0], "sh;");
+        let output = tool.execute(json!({})).await.unwrap();
+        assert!(output.starts_with("Error: failed to start"));
+        assert!(!marker.exists());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn reports_timeout_and_missing_script() {
+        let workspace = TempDir::new().unwrap();
+        std::fs::write(workspace.path().join("slow.sh"), "sleep 5\n").unwrap();
+
+        let slow = SkillScriptTool::new(
+            "slow",
+            workspace.path().to_path_buf(),
+            spec("slow.sh", Some("sh")),
+            workspace.path().to_path_buf(),
+        )
+        .with_timeout(1);
+        let output = slow.execute(json!({})).await.unwrap();
+        assert!(output.contains("timed out"));
+        // Larger than a pipe buffer, so writing stdin blocks until the timeout.
+        let started = std::time::Instant::now();
+        let output = slow
+            .execute(json!({"text": "x".repeat(100_000)}))
+            .await
+            .unwrap();
+        assert!(output.contains("timed out"));
+        assert!(started.elapsed() < Duration::from_secs(4));
+
+        let missing = SkillScriptTool::new(
+            "slow",
+            workspace.path().to_path_buf(),
+            spec("missing.sh", None),
+            workspace.path().to_path_buf(),
+        );
+        let output = missing.execute(json!({})).await.unwrap();
+        assert!(output.contains("missing"));
+    }
+}
@@ -5,11 +5,39 @@ use async_trait::async_trait;
 use serde_json::{json, Value};
 use std::sync::Arc;
 
+/// How the parent agent wants to receive a subagent's result
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SpawnMode {
+    /// Announce the result back as a new message when the subagent finishes
+    #[default]
+    Background,
+    /// Return a task handle; the result is collected with `subagent_wait`
+    Handle,
+}
+
+impl SpawnMode {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "background" => Some(Self::Background),
+            "handle" => Some(Self::Handle),
+            _ => None,
+        }
+    }
+}
+
+/// Arguments of a single `spawn` call
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpawnArgs {
+    pub task: String,
+    pub label: Option<String>,
+    pub profile: Option<String>,
+    pub mode: SpawnMode,
+}
+
 /// Callback function type for spawning subagents
 type SpawnCallback = Arc<
     dyn Fn(
-            String,
-            Option<String>,
+            SpawnArgs,
             String,
             String,
         ) -> std::pin::Pin<
@@ -18,32 +46,59 @@ type SpawnCallback = Arc<
         + Sync,
 >;
 
+const SPAWN_DESCRIPTION: &str = "Spawn a subagent to handle a task in the background. \
+     Use this for complex or time-consuming tasks that can run independently. \
+     The subagent will complete the task and report back when done. \
+     Use mode \"handle\" to get a task id instead and collect the result with subagent_wait.";
+
 /// Spawn tool for creating subagents
 ///
 /// This tool allows the main agent to spawn subagents for background task execution.
 /// The subagent runs asynchronously and announces its result back when complete.
 pub struct SpawnTool {
     spawn_callback: SpawnCallback,
+    description: String,
+    /// `(name, summary)` of the selectable subagent profiles
+    profiles: Vec<(String, String)>,
     origin_channel: Arc<tokio::sync::RwLock<String>>,
     origin_chat_id: Arc<tokio::sync::RwLock<String>>,
 }
 
 impl SpawnTool {
     /// Create a new spawn tool with a callback to the SubagentManager
+    ///
+    /// The callback receives the spawn arguments, origin channel and chat ID.
     pub fn new<F, Fut>(spawn_fn: F) -> Self
     where
-        F: Fn(String, Option<String>, String, String) -> Fut + Send + Sync + 'static,
+        F: Fn(SpawnArgs, String, String) -> Fut + Send + Sync + 'static,
         Fut: std::future::Future<Output = std::result::Result<String, ToolError>> + Send + 'static,
     {
         Self {
-            spawn_callback: Arc::new(move |task, label, channel, chat_id| {
-                Box::pin(spawn_fn(task, label, channel, chat_id))
+            spawn_callback: Arc::new(move |args, channel, chat_id| {
+                Box::pin(spawn_fn(args, channel, chat_id))
             }),
+            description: SPAWN_DESCRIPTION.to_string(),
+            profiles: Vec::new(),
             origin_channel: Arc::new(tokio::sync::RwLock::new("cli".to_string())),
             origin_chat_id: Arc::new(tokio::sync::RwLock::new("direct".to_string())),
         }
     }
 
+    /// Offer named subagent profiles as `(name, summary)` pairs
+    pub fn with_profiles(mut self, profiles: Vec<(String, String)>) -> Self {
+        self.description = SPAWN_DESCRIPTION.to_string();
+        if !profiles.is_empty() {
+            self.description
+                .push_str("\n\nAvailable profiles (pass one as `profile`):");
+            for (_, summary) in &profiles {
+                self.description.push_str("\n- ");
+                self.description.push_str(summary);
+            }
+        }
+        self.profiles = profiles;
+        self
+    }
+
     /// Set the origin context for subagent announcements
     pub async fn set_context(&self, channel: String, chat_id: String) {
         *self.origin_channel.write().await = channel;
@@ -58,13 +113,11 @@ impl Tool for SpawnTool {
     }
 
     fn description(&self) -> &str {
-        "Spawn a subagent to handle a task in the background. \
-         Use this for complex or time-consuming tasks that can run independently. \
-         The subagent will complete the task and report back when done."
+        &self.description
     }
 
     fn parameters(&self) -> Value {
-        json!({
+        let mut params = json!({
             "type": "object",
             "properties": {
                 "task": {
@@ -74,10 +127,28 @@ impl Tool for SpawnTool {
                 "label": {
                     "type": "string",
                     "description": "Optional short label for the task (for display)"
+                },
+                "mode": {
+                    "type": "string",
+                    "enum": ["background", "handle"],
+                    "description": "background (default) announces the result when done; handle returns a task id for subagent_wait"
                 }
             },
             "required": ["task"]
-        })
+        });
+        if !self.profiles.is_empty() {
+            let names: Vec<&str> = self
+                .profiles
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect();
+            params["properties"]["profile"] = json!({
+                "type": "string",
+                "enum": names,
+                "description": "Optional subagent profile; omit for the default setup"
+            });
+        }
+        params
     }
 
     async fn execute(&self, args: Value) -> std::result::Result<String, ToolError> {
@@ -91,11 +162,40 @@ impl Tool for SpawnTool {
         };
 
         let label = args.get("label").and_then(|v| v.as_str()).map(String::from);
+        let profile = args
+            .get("profile")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|profile| !profile.is_empty())
+            .map(String::from);
+        let mode = match args.get("mode").and_then(|v| v.as_str()) {
+            None => SpawnMode::Background,
+            Some(value) => SpawnMode::parse(value).ok_or_else(|| {
+                ToolError::InvalidArguments(format!(
+                    "'mode' must be 'background' or 'handle', got '{}'",
+                    value
+                ))
+            })?,
+        };
 
-        let channel = self.origin_channel.read().await.clone();
-        let chat_id = self.origin_chat_id.read().await.clone();
+        // The runtime injects the calling chat; the stored origin is a fallback
+        // for callers that do not.
+        let channel = match args.get("context_channel").and_then(|v| v.as_str()) {
+            Some(channel) => channel.to_string(),
+            None => self.origin_channel.read().await.clone(),
+        };
+        let chat_id = match args.get("context_chat_id").and_then(|v| v.as_str()) {
+            Some(chat_id) => chat_id.to_string(),
+            None => self.origin_chat_id.read().await.clone(),
+        };
 
-        (self.spawn_callback)(task, label, channel, chat_id).await
+        let args = SpawnArgs {
+            task,
+            label,
+            profile,
+            mode,
+        };
+        (self.spawn_callback)(args, channel, chat_id).await
     }
 }
 
@@ -105,15 +205,13 @@ mod tests {
 
     #[tokio::test]
     async fn test_spawn_tool_name() {
-        let tool =
-            SpawnTool::new(|_task, _label, _channel, _chat_id| async { Ok("spawned".to_string()) });
+        let tool = SpawnTool::new(|_args, _channel, _chat_id| async { Ok("spawned".to_string()) });
         assert_eq!(tool.name(), "spawn");
     }
 
     #[tokio::test]
     async fn test_spawn_tool_parameters() {
-        let tool =
-            SpawnTool::new(|_task, _label, _channel, _chat_id| async { Ok("spawned".to_string()) });
+        let tool = SpawnTool::new(|_args, _channel, _chat_id| async { Ok("spawned".to_string()) });
         let params = tool.parameters();
         assert!(params["properties"]["task"].is_object());
         assert_eq!(params["required"][0], "task");
@@ -121,10 +219,10 @@ mod tests {
 
     #[tokio::test]
     async fn test_spawn_tool_execute() {
-        let tool = SpawnTool::new(|task, label, channel, chat_id| async move {
+        let tool = SpawnTool::new(|args, channel, chat_id| async move {
             Ok(format!(
                 "Spawned: {} (label: {:?}, channel: {}, chat_id: {})",
-                task, label, channel, chat_id
+                args.task, args.label, channel, chat_id
             ))
         });
 
@@ -140,10 +238,27 @@ mod tests {
         assert!(result.contains("chat_id: direct"));
     }
 
+    #[tokio::test]
+    async fn test_spawn_tool_uses_injected_origin() {
+        let tool = SpawnTool::new(|_args, channel, chat_id| async move {
+            Ok(format!("{}:{}", channel, chat_id))
+        });
+
+        let result = tool
+            .execute(json!({
+                "task": "Test task",
+                "context_channel": "telegram",
+                "context_chat_id": "42"
+            }))
+            .await
+            .unwrap();
+        assert_eq!(result, "telegram:42");
+    }
+
     #[tokio::test]
     async fn test_spawn_tool_execute_without_label() {
-        let tool = SpawnTool::new(|task, label, _channel, _chat_id| async move {
-            Ok(format!("Task: {}, Label: {:?}", task, label))
+        let tool = SpawnTool::new(|args, _channel, _chat_id| async move {
+            Ok(format!("Task: {}, Label: {:?}", args.task, args.label))
         });
 
         let args = json!({
@@ -155,9 +270,51 @@ mod tests {
         assert!(result.contains("Label: None"));
     }
 
+    #[tokio::test]
+    async fn test_spawn_tool_lists_and_passes_profiles() {
+        let tool = SpawnTool::new(|args, _channel, _chat_id| async move {
+            Ok(format!("Profile: {:?}", args.profile))
+        });
+        assert!(tool.parameters()["properties"].get("profile").is_none());
+
+        let tool = tool.with_profiles(vec![
+            ("coder".to_string(), "coder: Writes code".to_string()),
+            ("researcher".to_string(), "researcher".to_string()),
+        ]);
+        assert!(tool.description().contains("- coder: Writes code"));
+        assert!(tool.description().contains("- researcher"));
+        assert_eq!(
+            tool.parameters()["properties"]["profile"]["enum"],
+            json!(["coder", "researcher"])
+        );
+
+        let result = tool
+            .execute(json!({"task": "Fix it", "profile": "coder"}))
+            .await
+            .unwrap();
+        assert_eq!(result, "Profile: Some(\"coder\")");
+    }
+
+    #[tokio::test]
+    async fn test_spawn_tool_parses_mode() {
+        let tool = SpawnTool::new(|args, _channel, _chat_id| async move {
+            Ok(format!("Mode: {:?}", args.mode))
+        });
+
+        let result = tool.execute(json!({"task": "Test"})).await.unwrap();
+        assert_eq!(result, "Mode: Background");
+        let result = tool
+            .execute(json!({"task": "Test", "mode": "handle"}))
+            .await
+            .unwrap();
+        assert_eq!(result, "Mode: Handle");
+        let result = tool.execute(json!({"task": "Test", "mode": "later"})).await;
+        assert!(result.is_err());
+    }
+
     #[tokio::test]
     async fn test_spawn_tool_set_context() {
-        let tool = SpawnTool::new(|_task, _label, channel, chat_id| async move {
+        let tool = SpawnTool::new(|_args, channel, chat_id| async move {
             Ok(format!("Channel: {}, Chat: {}", channel, chat_id))
         });
 
@@ -175,7 +332,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_spawn_tool_error_handling() {
-        let tool = SpawnTool::new(|_task, _label, _channel, _chat_id| async {
+        let tool = SpawnTool::new(|_args, _channel, _chat_id| async {
             Err(ToolError::ExecutionFailed("Spawn failed".to_string()))
         });
 
@@ -190,7 +347,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_spawn_tool_missing_task() {
-        let tool = SpawnTool::new(|_task, _label, _channel, _chat_id| async {
+        let tool = SpawnTool::new(|_args, _channel, _chat_id| async {
             Ok("should not reach".to_string())
         });
 
@@ -0,0 +1,156 @@
+//! Server-side context for tool calls
+//!
+//! Several tools read who is calling them from `context_*` arguments (the
+//! session that owns shell jobs and subagents, the memory scope, the chat a
+//! cron job or subagent reports to). Those values must come from the runtime,
+//! never from the model or a remote caller, so every entry point strips what
+//! the caller sent and injects its own [`ToolCallContext`] before executing a
+//! tool.
+
+use agent_diva_core::memory::MemoryScope;
+use serde_json::Value;
+
+/// Arguments with this prefix are reserved for the runtime.
+pub const CONTEXT_ARG_PREFIX: &str = "context_";
+
+/// Marks cron-triggered calls so the cron tool refuses to schedule recursively.
+const IN_CRON_ARG: &str = "_in_cron_context";
+
+/// Who a tool call runs for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolCallContext {
+    pub channel: String,
+    pub chat_id: String,
+    /// Session that owns shell jobs and other per-session state.
+    pub session_key: String,
+    pub memory_scope: MemoryScope,
+    /// Whether the call comes from a cron-triggered run.
+    pub in_cron: bool,
+}
+
+impl ToolCallContext {
+    /// Context for `channel:chat_id` with the global memory scope.
+    pub fn new(channel: impl Into<String>, chat_id: impl Into<String>) -> Self {
+        let channel = channel.into();
+        let chat_id = chat_id.into();
+        Self {
+            session_key: format!("{}:{}", channel, chat_id),
+            channel,
+            chat_id,
+            memory_scope: MemoryScope::Global,
+            in_cron: false,
+        }
+    }
+
+    pub fn with_session_key(mut self, session_key: impl Into<String>) -> Self {
+        self.session_key = session_key.into();
+        self
+    }
+
+    pub fn with_memory_scope(mut self, scope: MemoryScope) -> Self {
+        self.memory_scope = scope;
+        self
+    }
+
+    pub fn with_in_cron(mut self, in_cron: bool) -> Self {
+        self.in_cron = in_cron;
+        self
+    }
+
+    /// Replace any caller-supplied context in `args` with this context, for
+    /// the tools that read it.
+    pub fn apply(&self, tool: &str, args: &mut Value) {
+        strip_context_args(args);
+        let Some(params) = args.as_object_mut() else {
+            return;
+        };
+        if tool == "cron" || tool == "spawn" {
+            params.insert("context_channel".into(), self.channel.clone().into());
+            params.insert("context_chat_id".into(), self.chat_id.clone().into());
+            if tool == "cron" && self.in_cron {
+                params.insert(IN_CRON_ARG.into(), true.into());
+            }
+        }
+        if tool.starts_with("memory_") || tool == "session_search" {
+            params.insert(
+                "context_memory_scope".into(),
+                serde_json::to_value(&self.memory_scope).unwrap_or_default(),
+            );
+        }
+        if tool == "exec"
+            || tool == "exec_job"
+            || tool == "session_search"
+            || tool.starts_with("memory_")
+            || tool.starts_with("subagent_")
+        {
+            params.insert(
+                "context_session_key".into(),
+                self.session_key.clone().into(),
+            );
+        }
+    }
+}
+
+/// Remove every runtime-reserved argument from `args`.
+pub fn strip_context_args(args: &mut Value) {
+    if let Some(params) = args.as_object_mut() {
+        params.retain(|key, _| !key.starts_with(CONTEXT_ARG_PREFIX) && key != IN_CRON_ARG);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_apply_replaces_forged_context() {
+        let context = ToolCallContext::new("telegram", "42").with_memory_scope(MemoryScope::Chat {
+            channel: "telegram".to_string(),
+            chat_id: "42".to_string(),
+        });
+        let mut args = json!({
+            "query": "tea",
+            "context_memory_scope": {"kind": "global"},
+            "context_session_key": "other:chat",
+            "_in_cron_context": true
+        });
+        context.apply("memory_search", &mut args);
+
+        assert_eq!(args["query"], "tea");
+        assert_eq!(
+            args["context_memory_scope"],
+            serde_json::to_value(&context.memory_scope).unwrap()
+        );
+        assert_eq!(args["context_session_key"], "telegram:42");
+        assert!(args.get("_in_cron_context").is_none());
+
+        let mut search = json!({"query": "tea", "context_memory_scope": {"kind": "global"}});
+        context.apply("session_search", &mut search);
+        assert_eq!(search["context_memory_scope"], args["context_memory_scope"]);
+    }
+
+    #[test]
+    fn test_apply_sets_session_and_cron_context() {
+        let context = ToolCallContext::new("cron", "job-1").with_in_cron(true);
+        let mut exec = json!({"command": "ls", "context_session_key": "cli:victim"});
+        context.apply("exec", &mut exec);
+        assert_eq!(exec["context_session_key"], "cron:job-1");
+
+        let mut cron = json!({"action": "list"});
+        context.apply("cron", &mut cron);
+        assert_eq!(cron["context_channel"], "cron");
+        assert_eq!(cron["context_chat_id"], "job-1");
+        assert_eq!(cron["_in_cron_context"], true);
+
+        let mut spawn = json!({"task": "t", "context_channel": "cli"});
+        context.apply("spawn", &mut spawn);
+        assert_eq!(spawn["context_channel"], "cron");
+        assert_eq!(spawn["context_chat_id"], "job-1");
+        assert!(spawn.get("_in_cron_context").is_none());
+
+        let mut wait = json!({"context_session_key": "cli:victim"});
+        context.apply("subagent_wait", &mut wait);
+        assert_eq!(wait["context_session_key"], "cron:job-1");
+    }
+}